@@ -12,6 +12,14 @@ contract VerifyTest is Test {
     }
 
     function testVerify() public {
+        // `noir-bench evm-verify --proof` passes the encoded verify call
+        bytes memory call = vm.envOr("NOIR_BENCH_CALLDATA", bytes(""));
+        if (call.length > 0) {
+            (bool success, bytes memory ret) = address(verifier).call(call);
+            assertTrue(success && abi.decode(ret, (bool)), "verify() failed");
+            return;
+        }
+
         // Inputs via env (hex without 0x), or defaults to empty
         string memory proofHex = vm.envOr("PROOF_HEX", string(""));
         string memory pubHex = vm.envOr("PUB_INPUTS_HEX", string(""));
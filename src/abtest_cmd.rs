@@ -0,0 +1,332 @@
+//! CLI command handler for `abtest`.
+//!
+//! Compares two bb binaries, typically before and after a backend change, on
+//! circuits from a bench config. Each circuit gets a paired run (see
+//! `compare --paired`): both binaries prove the same witnesses interleaved,
+//! and a paired t-test on the per-pair differences decides which one is
+//! faster. The report gives the difference and its 95% confidence interval
+//! per circuit, and sums up where B won and lost.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::backend::{BarretenbergConfig, load_backend_args};
+use crate::bench::config::{CircuitSpec, load_bench_config};
+use crate::bench::hooks::warn_ignored_hooks;
+use crate::ci_cmd::find_prover_toml;
+use crate::compare_cmd::{CompareStatus, PairedComparison, check_alpha, measure_paired};
+use crate::engine::{NargoToolchain, ProveInputs};
+use crate::report::format_value;
+use crate::{BenchError, BenchResult};
+
+const DEFAULT_CONFIG: &str = "bench-config.toml";
+
+/// A circuit whose paired run failed.
+#[derive(Debug, Clone, Serialize)]
+pub struct AbTestFailure {
+    pub circuit: String,
+    pub error: String,
+}
+
+/// Results of an A/B test over several circuits.
+#[derive(Debug, Clone, Serialize)]
+pub struct AbTestReport {
+    pub backend_a: String,
+    pub backend_b: String,
+    pub alpha: f64,
+    pub threshold: f64,
+    pub circuits: Vec<PairedComparison>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<AbTestFailure>,
+}
+
+impl AbTestReport {
+    /// Circuits on which B was significantly slower than A.
+    pub fn b_slower(&self) -> usize {
+        self.count(CompareStatus::Regression)
+    }
+
+    /// Circuits on which B was significantly faster than A.
+    pub fn b_faster(&self) -> usize {
+        self.count(CompareStatus::Improvement)
+    }
+
+    fn count(&self, status: CompareStatus) -> usize {
+        self.circuits.iter().filter(|c| c.status == status).count()
+    }
+
+    /// Geometric mean of B/A mean prove times over all circuits.
+    pub fn geomean_ratio(&self) -> Option<f64> {
+        let ratios: Vec<f64> = self
+            .circuits
+            .iter()
+            .filter(|c| c.test.baseline_mean_ms > 0.0 && c.test.contender_mean_ms > 0.0)
+            .map(|c| (c.test.contender_mean_ms / c.test.baseline_mean_ms).ln())
+            .collect();
+        (!ratios.is_empty()).then(|| (ratios.iter().sum::<f64>() / ratios.len() as f64).exp())
+    }
+}
+
+/// Label of a circuit spec: the name, with `-p<params>` for params variants.
+fn spec_label(spec: &CircuitSpec) -> String {
+    match spec.params {
+        Some(p) => format!("{}-p{p}", spec.name),
+        None => spec.name.clone(),
+    }
+}
+
+/// Specs matching `names` (every params variant of each), or all of them.
+fn select_circuits(specs: Vec<CircuitSpec>, names: &[String]) -> BenchResult<Vec<CircuitSpec>> {
+    if names.is_empty() {
+        return Ok(specs);
+    }
+    let unknown: Vec<&str> = names
+        .iter()
+        .filter(|n| !specs.iter().any(|s| &s.name == *n || spec_label(s) == **n))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(BenchError::Message(format!(
+            "unknown circuit(s) in bench config: {}",
+            unknown.join(", ")
+        )));
+    }
+    Ok(specs
+        .into_iter()
+        .filter(|s| names.iter().any(|n| *n == s.name || *n == spec_label(s)))
+        .collect())
+}
+
+/// Render the report as a markdown table with a summary.
+pub fn render_markdown(report: &AbTestReport) -> String {
+    let mut out = String::from("## A/B test\n\n");
+    out.push_str(&format!(
+        "- **A:** `{}`\n- **B:** `{}`\n\n",
+        report.backend_a, report.backend_b
+    ));
+    out.push_str("| Circuit | Pairs | A prove | B prove | B vs A | 95% CI | p | Verdict |\n");
+    out.push_str("|---------|------:|--------:|--------:|-------:|--------|--:|---------|\n");
+    for c in &report.circuits {
+        let t = &c.test;
+        let pct = |ms: f64| {
+            if t.baseline_mean_ms > 0.0 {
+                ms * 100.0 / t.baseline_mean_ms
+            } else {
+                0.0
+            }
+        };
+        let verdict = match c.status {
+            CompareStatus::Regression => "🔴 A faster",
+            CompareStatus::Improvement => "🟢 B faster",
+            CompareStatus::Unchanged => "⚪ no significant difference",
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {:+.2}% | [{:+.2}%, {:+.2}%] | {:.4} | {verdict} |\n",
+            c.circuit_name,
+            t.pairs,
+            format_value(t.baseline_mean_ms, "prove_ms"),
+            format_value(t.contender_mean_ms, "prove_ms"),
+            t.percent,
+            pct(t.ci95_ms.0),
+            pct(t.ci95_ms.1),
+            t.p_value,
+        ));
+    }
+
+    let n = report.circuits.len();
+    out.push_str(&format!(
+        "\nB is faster on {} and slower on {} of {n} circuit(s) (alpha {}, threshold {}%)",
+        report.b_faster(),
+        report.b_slower(),
+        report.alpha,
+        report.threshold
+    ));
+    if let Some(ratio) = report.geomean_ratio() {
+        out.push_str(&format!(
+            "; overall B/A prove time {ratio:.3} ({:+.2}%, geometric mean)",
+            (ratio - 1.0) * 100.0
+        ));
+    }
+    out.push_str(".\n");
+
+    if !report.failures.is_empty() {
+        out.push_str("\n### Failed\n\n");
+        for f in &report.failures {
+            out.push_str(&format!("- {}: {}\n", f.circuit, f.error));
+        }
+    }
+    out
+}
+
+pub fn run(
+    backend_a: PathBuf,
+    backend_b: PathBuf,
+    circuits: Vec<String>,
+    config: Option<PathBuf>,
+    backend_args: Vec<String>,
+    warmup: usize,
+    pairs: usize,
+    alpha: f64,
+    threshold: f64,
+    format: String,
+    json_out: Option<PathBuf>,
+) -> BenchResult<AbTestReport> {
+    check_alpha(alpha)?;
+    let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let specs = select_circuits(load_bench_config(&cfg_path)?, &circuits)?;
+    warn_ignored_hooks(&cfg_path, "abtest");
+    let args = [load_backend_args(&cfg_path)?, backend_args].concat();
+    let timeout = Duration::from_secs(24 * 60 * 60);
+    let work = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+
+    let mut report = AbTestReport {
+        backend_a: backend_a.display().to_string(),
+        backend_b: backend_b.display().to_string(),
+        alpha,
+        threshold,
+        circuits: Vec::new(),
+        failures: Vec::new(),
+    };
+    for (i, spec) in specs.iter().enumerate() {
+        let label = spec_label(spec);
+        eprintln!(
+            "abtest: [{}/{}] {label}, {warmup} warmup + {pairs} measured pair(s)",
+            i + 1,
+            specs.len()
+        );
+        let toolchain = NargoToolchain::new()
+            .with_work_dir(work.path())
+            .with_env(spec.env.clone());
+        let bb = |path: &PathBuf| {
+            BarretenbergConfig::new(path)
+                .with_args(args.clone())
+                .with_timeout(timeout)
+                .with_work_dir(work.path())
+                .with_env(spec.env.clone())
+        };
+        let mut inputs = ProveInputs::new(&spec.path, &label).with_timeout(timeout);
+        if let Some(pt) = find_prover_toml(&spec.path, spec.params) {
+            inputs = inputs.with_prover_toml(pt);
+        }
+
+        match measure_paired(
+            &toolchain,
+            bb(&backend_a),
+            bb(&backend_b),
+            &inputs,
+            warmup,
+            pairs,
+            alpha,
+            threshold,
+        ) {
+            Ok(result) => report.circuits.push(result),
+            Err(e) => {
+                eprintln!("abtest: {label} failed: {e}");
+                report.failures.push(AbTestFailure {
+                    circuit: label,
+                    error: e.to_string().lines().next().unwrap_or_default().to_string(),
+                });
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| BenchError::Message(format!("failed to serialize A/B test report: {e}")))?;
+    if let Some(path) = &json_out {
+        std::fs::write(path, &json)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))?;
+        eprintln!("Wrote A/B test report to {}", path.display());
+    }
+    match format.as_str() {
+        "json" => println!("{json}"),
+        _ => print!("{}", render_markdown(&report)),
+    }
+
+    if !report.failures.is_empty() {
+        return Err(BenchError::Message(format!(
+            "{} of {} circuit(s) failed",
+            report.failures.len(),
+            specs.len()
+        )));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::paired::{PairedSamples, paired_t_test};
+
+    fn comparison(circuit: &str, a: &[f64], b: &[f64]) -> PairedComparison {
+        let samples = PairedSamples {
+            baseline_ms: a.to_vec(),
+            contender_ms: b.to_vec(),
+        };
+        let test = paired_t_test(&samples).unwrap();
+        PairedComparison {
+            circuit_name: circuit.to_string(),
+            baseline: "bb-old".to_string(),
+            contender: "bb-new".to_string(),
+            alpha: 0.05,
+            threshold: 0.0,
+            status: test.status(0.05, 0.0),
+            test,
+            samples,
+        }
+    }
+
+    #[test]
+    fn test_report_summarizes_winners() {
+        let report = AbTestReport {
+            backend_a: "bb-old".to_string(),
+            backend_b: "bb-new".to_string(),
+            alpha: 0.05,
+            threshold: 0.0,
+            circuits: vec![
+                comparison("merkle", &[1000.0, 1100.0, 1050.0], &[900.0, 985.0, 950.0]),
+                comparison("ecdsa", &[400.0, 420.0, 410.0], &[401.0, 419.0, 411.0]),
+            ],
+            failures: vec![AbTestFailure {
+                circuit: "rollup-p16".to_string(),
+                error: "bb prove failed".to_string(),
+            }],
+        };
+        assert_eq!(report.b_faster(), 1);
+        assert_eq!(report.b_slower(), 0);
+
+        let md = render_markdown(&report);
+        assert!(md.contains("| merkle | 3 | 1.05s | 945ms | -10.00% |"));
+        assert!(md.contains("🟢 B faster"));
+        assert!(md.contains("| ecdsa | 3 |"));
+        assert!(md.contains("⚪ no significant difference"));
+        assert!(md.contains("B is faster on 1 and slower on 0 of 2 circuit(s)"));
+        assert!(md.contains("- rollup-p16: bb prove failed"));
+    }
+
+    #[test]
+    fn test_select_circuits_by_name_or_label() {
+        let spec = |name: &str, params| CircuitSpec {
+            name: name.to_string(),
+            path: PathBuf::from(format!("{name}.json")),
+            params,
+            env: Default::default(),
+        };
+        let specs = vec![
+            spec("merkle", Some(16)),
+            spec("merkle", Some(32)),
+            spec("ecdsa", None),
+        ];
+
+        let all = select_circuits(specs.clone(), &[]).unwrap();
+        assert_eq!(all.len(), 3);
+        let merkle = select_circuits(specs.clone(), &["merkle".to_string()]).unwrap();
+        assert_eq!(merkle.len(), 2);
+        let one = select_circuits(specs.clone(), &["merkle-p32".to_string()]).unwrap();
+        assert_eq!(one[0].params, Some(32));
+        let err = select_circuits(specs, &["rollup".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("rollup"));
+    }
+}
@@ -0,0 +1,188 @@
+//! CLI command handler for `attest`.
+//!
+//! Re-verifies the proof a record was produced with, using the proof and vk
+//! kept in the artifact store under the hashes from the record's
+//! `fingerprints`. A passing attestation shows the stored files are the ones
+//! the record describes and that they still verify.
+
+use std::path::PathBuf;
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::schema::BenchRecord;
+use crate::storage::{ArtifactStore, JsonlWriter};
+use crate::{BenchError, BenchResult};
+
+/// Result of re-verifying a record's stored proof.
+#[derive(Debug, Clone)]
+pub struct Attestation {
+    pub record_id: String,
+    pub proof_path: PathBuf,
+    pub vk_path: PathBuf,
+    pub verify_time_ms: u128,
+    pub success: bool,
+}
+
+/// Find a record by id in a JSONL archive.
+pub fn find_record(jsonl: &PathBuf, record_id: &str) -> BenchResult<BenchRecord> {
+    JsonlWriter::new(jsonl)
+        .read_all()?
+        .into_iter()
+        .find(|r| r.record_id == record_id)
+        .ok_or_else(|| {
+            BenchError::Message(format!(
+                "record '{record_id}' not found in {}",
+                jsonl.display()
+            ))
+        })
+}
+
+/// Re-verify `record`'s stored proof against its stored vk.
+///
+/// Fails if the record has no proof/vk fingerprints or the stored files are
+/// missing or don't match them; a proof that fails verification is reported
+/// through `Attestation::success`.
+pub fn attest_record(
+    record: &BenchRecord,
+    store: &ArtifactStore,
+    backend: &dyn Backend,
+) -> BenchResult<Attestation> {
+    let fingerprints = record.fingerprints.as_ref();
+    let Some(proof_hash) = fingerprints.and_then(|f| f.proof_hash.as_deref()) else {
+        return Err(BenchError::Message(format!(
+            "record '{}' has no proof fingerprint",
+            record.record_id
+        )));
+    };
+    let Some(vk_hash) = fingerprints.and_then(|f| f.vk_hash.as_deref()) else {
+        return Err(BenchError::Message(format!(
+            "record '{}' has no vk fingerprint",
+            record.record_id
+        )));
+    };
+
+    let proof_path = store.get_proof(proof_hash)?;
+    let vk_path = store.get_vk(vk_hash)?;
+    let output = backend.verify(&proof_path, &vk_path)?;
+
+    Ok(Attestation {
+        record_id: record.record_id.clone(),
+        proof_path,
+        vk_path,
+        verify_time_ms: output.verify_time_ms,
+        success: output.success,
+    })
+}
+
+/// Run the `attest` command.
+pub fn run(
+    record_id: String,
+    jsonl: PathBuf,
+    store: PathBuf,
+    backend_path: PathBuf,
+    backend_args: Vec<String>,
+) -> BenchResult<()> {
+    let record = find_record(&jsonl, &record_id)?;
+    let store = ArtifactStore::new(&store);
+    let backend =
+        BarretenbergBackend::new(BarretenbergConfig::new(backend_path).with_args(backend_args));
+    backend.warn_on_args();
+
+    let attestation = attest_record(&record, &store, &backend)?;
+    println!(
+        "attest: record={} proof={} vk={} verify_ms={} ok={}",
+        attestation.record_id,
+        attestation.proof_path.display(),
+        attestation.vk_path.display(),
+        attestation.verify_time_ms,
+        attestation.success
+    );
+    if !attestation.success {
+        return Err(BenchError::Message(format!(
+            "attestation failed: stored proof for '{record_id}' did not verify"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fingerprints;
+    use crate::backend::{MockBackend, MockConfig};
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig};
+
+    fn stored_record(dir: &std::path::Path) -> (BenchRecord, ArtifactStore) {
+        let proof = dir.join("proof");
+        let vk = dir.join("vk");
+        std::fs::write(&proof, b"proof").unwrap();
+        std::fs::write(&vk, b"vk").unwrap();
+        let store = ArtifactStore::new(dir.join("store"));
+
+        let mut record = BenchRecord::new(
+            "c".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "mock".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        record.fingerprints = Some(Fingerprints {
+            proof_hash: Some(store.put_proof(&proof).unwrap()),
+            vk_hash: Some(store.put_vk(&vk).unwrap()),
+            ..Default::default()
+        });
+        (record, store)
+    }
+
+    #[test]
+    fn test_attest_record_verifies_stored_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (record, store) = stored_record(dir.path());
+        let backend = MockBackend::new(MockConfig::new("mock"));
+
+        let attestation = attest_record(&record, &store, &backend).unwrap();
+        assert!(attestation.success);
+        assert!(attestation.proof_path.starts_with(store.root()));
+    }
+
+    #[test]
+    fn test_attest_record_rejects_tampered_proof() {
+        let dir = tempfile::tempdir().unwrap();
+        let (record, store) = stored_record(dir.path());
+        let hash = record
+            .fingerprints
+            .as_ref()
+            .and_then(|f| f.proof_hash.clone())
+            .unwrap();
+        std::fs::write(store.proof_path(&hash), b"forged").unwrap();
+        let backend = MockBackend::new(MockConfig::new("mock"));
+
+        assert!(attest_record(&record, &store, &backend).is_err());
+    }
+
+    #[test]
+    fn test_attest_record_requires_fingerprints() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut record, store) = stored_record(dir.path());
+        record.fingerprints = None;
+        let backend = MockBackend::new(MockConfig::new("mock"));
+
+        let err = attest_record(&record, &store, &backend).unwrap_err();
+        assert!(err.to_string().contains("no proof fingerprint"));
+    }
+
+    #[test]
+    fn test_find_record_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let (record, _) = stored_record(dir.path());
+        let jsonl = dir.path().join("history.jsonl");
+        JsonlWriter::new(&jsonl).append(&record).unwrap();
+
+        let found = find_record(&jsonl, &record.record_id).unwrap();
+        assert_eq!(found.fingerprints, record.fingerprints);
+        assert!(find_record(&jsonl, "missing").is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Validation of extra arguments passed through to bb.
+//!
+//! Extra args come from `--backend-arg` (repeatable), trailing args after
+//! `--`, or a `[backend.args]` config table. They are appended to every bb
+//! command noir-bench runs, so a typo or a flag bb doesn't know in the
+//! installed version only shows up as a failed prove. Checking them up front
+//! against the flags bb accepts lets us warn before the run starts.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::{BenchError, BenchResult};
+
+/// A bb flag we know about.
+struct BbFlag {
+    long: &'static str,
+    short: Option<&'static str>,
+    takes_value: bool,
+    /// First bb version (major, minor) accepting the flag
+    since: Option<(u64, u64)>,
+}
+
+const fn flag(long: &'static str, short: Option<&'static str>, takes_value: bool) -> BbFlag {
+    BbFlag {
+        long,
+        short,
+        takes_value,
+        since: None,
+    }
+}
+
+const fn flag_since(long: &'static str, takes_value: bool, since: (u64, u64)) -> BbFlag {
+    BbFlag {
+        long,
+        short: None,
+        takes_value,
+        since: Some(since),
+    }
+}
+
+/// Flags that may be passed through to bb prove/verify/gates/write_vk.
+const KNOWN_FLAGS: &[BbFlag] = &[
+    flag("--scheme", Some("-s"), true),
+    flag("--crs_path", Some("-c"), true),
+    flag("--debug_logging", Some("-d"), false),
+    flag("--recursive", None, false),
+    flag("--honk_recursion", None, true),
+    flag("--init_kzg_accumulator", None, false),
+    flag_since("--oracle_hash", true, (0, 82)),
+    flag_since("--output_format", true, (0, 82)),
+    flag_since("--ipa_accumulation", false, (0, 82)),
+    flag_since("--write_vk", false, (0, 82)),
+    flag_since("--disable_zk", false, (0, 84)),
+    flag_since("--slow_low_memory", false, (0, 84)),
+    flag_since("--verifier_target", true, (1, 0)),
+];
+
+/// Flags noir-bench sets itself on bb commands; passing them again
+/// conflicts with the paths and modes it chose.
+const MANAGED_FLAGS: &[(&str, &str)] = &[
+    ("-b", "bytecode path"),
+    ("--bytecode_path", "bytecode path"),
+    ("-w", "witness path"),
+    ("--witness_path", "witness path"),
+    ("-o", "output path"),
+    ("--output_path", "output path"),
+    ("-k", "vk path"),
+    ("--vk_path", "vk path"),
+    ("-p", "proof path"),
+    ("--proof_path", "proof path"),
+    ("-i", "public inputs path"),
+    ("--public_inputs_path", "public inputs path"),
+    ("-v", "verbose mode (use --backend-verbose)"),
+    ("--verbose", "verbose mode (use --backend-verbose)"),
+];
+
+/// Leading (major, minor) of a bb version string such as `0.84.0` or
+/// `v1.2.3-nightly`.
+pub fn parse_bb_version(version: &str) -> Option<(u64, u64)> {
+    let v = version.trim().trim_start_matches('v');
+    let mut parts = v.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Warnings for `args` as bb flags: unknown flags, flags the detected
+/// `version` doesn't support yet, flags noir-bench already sets, flags
+/// missing their value, and values not attached to any flag.
+pub fn validate_bb_args(args: &[String], version: Option<&str>) -> Vec<String> {
+    let version = version.and_then(parse_bb_version);
+    let mut warnings = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if !arg.starts_with('-') {
+            warnings.push(format!(
+                "backend arg '{arg}' is not attached to a flag (bb subcommands and paths are set by noir-bench)"
+            ));
+            continue;
+        }
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, _)) => (name, true),
+            None => (arg.as_str(), false),
+        };
+        if let Some((_, what)) = MANAGED_FLAGS.iter().find(|(f, _)| *f == name) {
+            warnings.push(format!(
+                "backend arg '{name}' sets the {what}, which noir-bench already passes to bb"
+            ));
+            i += skip_value(args.get(i), inline_value);
+            continue;
+        }
+        let Some(known) = KNOWN_FLAGS
+            .iter()
+            .find(|f| f.long == name || f.short == Some(name))
+        else {
+            warnings.push(format!("unknown backend arg '{name}'"));
+            i += skip_value(args.get(i), inline_value);
+            continue;
+        };
+        if let (Some(since), Some(found)) = (known.since, version) {
+            if found < since {
+                warnings.push(format!(
+                    "backend arg '{name}' needs bb >= {}.{} (found {}.{})",
+                    since.0, since.1, found.0, found.1
+                ));
+            }
+        }
+        if known.takes_value && !inline_value {
+            match args.get(i) {
+                Some(value) if !value.starts_with('-') => i += 1,
+                _ => warnings.push(format!("backend arg '{name}' is missing its value")),
+            }
+        }
+    }
+    warnings
+}
+
+/// 1 if `next` looks like the value of the flag before it.
+fn skip_value(next: Option<&String>, inline_value: bool) -> usize {
+    match next {
+        Some(value) if !inline_value && !value.starts_with('-') => 1,
+        _ => 0,
+    }
+}
+
+/// The `[backend]` section of a config file.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct BackendSection {
+    /// Flags passed to every bb command, e.g. `scheme = "ultra_honk"` or
+    /// `disable_zk = true`
+    #[serde(default)]
+    pub args: BTreeMap<String, toml::Value>,
+    /// Environment variables for the bb and nargo processes, e.g.
+    /// `RAYON_NUM_THREADS = 8` (see `bench::config`)
+    #[serde(default)]
+    pub env: BTreeMap<String, toml::Value>,
+}
+
+impl BackendSection {
+    /// Render the table as bb arguments: `key = "v"` becomes `--key v`,
+    /// `key = true` becomes `--key` and `key = false` is left out.
+    pub fn to_args(&self) -> BenchResult<Vec<String>> {
+        let mut out = Vec::new();
+        for (key, value) in &self.args {
+            let flag = if key.starts_with('-') {
+                key.clone()
+            } else {
+                format!("--{key}")
+            };
+            match value {
+                toml::Value::Boolean(true) => out.push(flag),
+                toml::Value::Boolean(false) => {}
+                toml::Value::String(s) => out.extend([flag, s.clone()]),
+                toml::Value::Integer(n) => out.extend([flag, n.to_string()]),
+                toml::Value::Float(f) => out.extend([flag, f.to_string()]),
+                other => {
+                    return Err(BenchError::Message(format!(
+                        "[backend.args] {key}: expected a string, number or boolean, got {}",
+                        other.type_str()
+                    )));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Read the `[backend.args]` table from a config file; empty when the file
+/// or section is missing.
+pub fn load_backend_args(path: &std::path::Path) -> BenchResult<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Config {
+        #[serde(default)]
+        backend: BackendSection,
+    }
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let s = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read config: {e}")))?;
+    let cfg: Config = toml::from_str(&s)
+        .map_err(|e| BenchError::Message(format!("failed to parse config: {e}")))?;
+    cfg.backend.to_args()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &[&str]) -> Vec<String> {
+        s.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_bb_version() {
+        assert_eq!(parse_bb_version("0.84.0"), Some((0, 84)));
+        assert_eq!(parse_bb_version("v1.2.3-nightly\n"), Some((1, 2)));
+        assert_eq!(parse_bb_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_valid_args_have_no_warnings() {
+        let a = args(&["--scheme", "ultra_honk", "--oracle_hash=keccak", "-d"]);
+        assert!(validate_bb_args(&a, Some("0.84.0")).is_empty());
+    }
+
+    #[test]
+    fn test_warns_on_unknown_misplaced_and_versioned_args() {
+        let a = args(&[
+            "prove",
+            "--sheme",
+            "ultra_honk",
+            "-o",
+            "out",
+            "--disable_zk",
+            "--scheme",
+        ]);
+        let w = validate_bb_args(&a, Some("0.82.0"));
+        assert_eq!(w.len(), 5);
+        assert!(w[0].contains("'prove' is not attached to a flag"));
+        assert!(w[1].contains("unknown backend arg '--sheme'"));
+        assert!(w[2].contains("'-o' sets the output path"));
+        assert!(w[3].contains("needs bb >= 0.84 (found 0.82)"));
+        assert!(w[4].contains("'--scheme' is missing its value"));
+    }
+
+    #[test]
+    fn test_unknown_version_skips_version_check() {
+        let a = args(&["--disable_zk"]);
+        assert!(validate_bb_args(&a, None).is_empty());
+    }
+
+    #[test]
+    fn test_backend_section_to_args() {
+        let section: BackendSection = toml::from_str(
+            r#"
+[args]
+scheme = "ultra_honk"
+disable_zk = true
+recursive = false
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            section.to_args().unwrap(),
+            args(&["--disable_zk", "--scheme", "ultra_honk"])
+        );
+
+        let bad: BackendSection = toml::from_str("[args]\nscheme = [1]\n").unwrap();
+        assert!(bad.to_args().is_err());
+    }
+}
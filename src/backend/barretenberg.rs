@@ -1,14 +1,45 @@
 //! Barretenberg backend implementation.
-
-use std::collections::HashMap;
+//!
+//! vk generation (`bb write_vk`) runs before each prove and is reported apart
+//! from the prove time. bb builds the proving key in memory for it and again
+//! inside `bb prove`, so there's no proving key generation step to time on its
+//! own and no proving key file.
+//!
+//! With a key cache directory, vks are stored per artifact hash, bb version
+//! and flags (see `storage::key_cache`) and later runs, in this session or
+//! another, prove with the cached vk instead of generating it again.
+//!
+//! With amortized setup the backend keeps the vk across `prove` calls, per
+//! artifact hash, bb version and flags, and subtracts an estimate of bb's
+//! process startup (the fastest of a few `bb --version` runs) from each prove.
+//! This is an estimate, not a resident prover: every prove still spawns bb,
+//! which loads the SRS and deserializes the circuit again, and that time stays
+//! in the prove time. Reports made this way are stamped with their prove mode
+//! so `compare` can tell them from raw timings.
+//!
+//! In verbose mode bb runs with `-v` and its log is parsed into a per-phase
+//! breakdown (see `phases`).
+//!
+//! bb's stdout/stderr go to files next to its outputs; when a command fails
+//! they are kept under the configured log directory (see
+//! `storage::run_logs`).
+
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 
 use serde::Deserialize;
 
+use crate::core::KeyCacheStatus;
+use crate::core::process::{Finished, Supervised};
+use crate::storage::KeyCache;
+use crate::storage::run_logs::{FailureLogs, command_failure};
 use crate::{BenchError, BenchResult};
 
+use super::args::validate_bb_args;
+use super::phases::parse_bb_phases;
 use super::traits::{Backend, Capabilities, GateInfo, ProveOutput, VerifyOutput};
 
 /// Configuration for the Barretenberg backend.
@@ -20,6 +51,22 @@ pub struct BarretenbergConfig {
     pub extra_args: Vec<String>,
     /// Default timeout for operations
     pub default_timeout: Duration,
+    /// Reuse vks and subtract process startup across prove calls
+    pub amortize_setup: bool,
+    /// Run bb prove with `-v` and parse its phase timings
+    pub verbose: bool,
+    /// Directory failed commands' stdout/stderr are kept in
+    pub log_dir: Option<PathBuf>,
+    /// Directory generated keys are cached in across runs
+    pub key_cache: Option<PathBuf>,
+    /// Regenerate keys instead of loading them from the key cache
+    pub cold_keys: bool,
+    /// Directory bb output directories are created in (default: the system
+    /// temp dir, where they are left behind)
+    pub work_dir: Option<PathBuf>,
+    /// Environment variables set for every bb process, e.g.
+    /// `RAYON_NUM_THREADS`
+    pub env: BTreeMap<String, String>,
 }
 
 impl Default for BarretenbergConfig {
@@ -28,6 +75,13 @@ impl Default for BarretenbergConfig {
             bb_path: PathBuf::from("bb"),
             extra_args: Vec::new(),
             default_timeout: Duration::from_secs(24 * 60 * 60), // 24 hours
+            amortize_setup: false,
+            verbose: false,
+            log_dir: None,
+            key_cache: None,
+            cold_keys: false,
+            work_dir: None,
+            env: BTreeMap::new(),
         }
     }
 }
@@ -52,12 +106,64 @@ impl BarretenbergConfig {
         self.default_timeout = timeout;
         self
     }
+
+    /// Reuse the vk and subtract estimated process startup across prove calls.
+    pub fn with_amortize_setup(mut self, amortize_setup: bool) -> Self {
+        self.amortize_setup = amortize_setup;
+        self
+    }
+
+    /// Capture bb's verbose log and report per-phase proving times.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Keep the output of failed bb commands under `dir`.
+    pub fn with_log_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.log_dir = Some(dir.into());
+        self
+    }
+
+    /// Cache generated keys under `dir` and reuse them in later runs.
+    pub fn with_key_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.key_cache = Some(dir.into());
+        self
+    }
+
+    /// Always regenerate keys, replacing the cached ones.
+    pub fn with_cold_keys(mut self, cold_keys: bool) -> Self {
+        self.cold_keys = cold_keys;
+        self
+    }
+
+    /// Create bb's output directories under `dir` (see `storage::work_dir`).
+    pub fn with_work_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.work_dir = Some(dir.into());
+        self
+    }
+
+    /// Set these environment variables for every bb process.
+    pub fn with_env(mut self, env: BTreeMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+}
+
+/// Setup shared across prove calls with amortized setup.
+#[derive(Default)]
+struct AmortizedSetup {
+    /// Estimated bb process startup in milliseconds
+    startup_ms: Option<u128>,
+    /// Written vk per artifact hash, bb version and flags
+    vks: HashMap<String, PathBuf>,
 }
 
 /// Barretenberg proving backend.
 pub struct BarretenbergBackend {
     config: BarretenbergConfig,
     version_cache: Option<String>,
+    amortized: Mutex<AmortizedSetup>,
 }
 
 impl BarretenbergBackend {
@@ -66,6 +172,7 @@ impl BarretenbergBackend {
         BarretenbergBackend {
             config,
             version_cache: None,
+            amortized: Mutex::new(AmortizedSetup::default()),
         }
     }
 
@@ -74,81 +181,201 @@ impl BarretenbergBackend {
         Self::new(BarretenbergConfig::new(bb_path))
     }
 
+    /// Print a warning for each extra arg bb (at its detected version) is
+    /// not expected to accept.
+    pub fn warn_on_args(&self) {
+        if self.config.extra_args.is_empty() {
+            return;
+        }
+        for warning in validate_bb_args(&self.config.extra_args, self.version().as_deref()) {
+            eprintln!("warning: {warning}");
+        }
+    }
+
+    /// Directory bb loads the SRS from: `-c`/`--crs_path` if passed, else
+    /// bb's default `~/.bb-crs`.
+    fn crs_dir(&self) -> Option<PathBuf> {
+        let args = &self.config.extra_args;
+        for (i, arg) in args.iter().enumerate() {
+            if let Some(path) = arg.strip_prefix("--crs_path=") {
+                return Some(PathBuf::from(path));
+            }
+            if arg == "-c" || arg == "--crs_path" {
+                return args.get(i + 1).map(PathBuf::from);
+            }
+        }
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".bb-crs"))
+    }
+
+    /// A bb command with the configured environment.
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.config.bb_path);
+        cmd.envs(&self.config.env);
+        cmd
+    }
+
     /// Run a bb command with timeout and optional memory tracking.
-    fn run_with_timeout(
+    fn run_with_timeout(&self, mut cmd: Command, timeout: Duration) -> BenchResult<Finished> {
+        let child = Supervised::spawn(&mut cmd)
+            .map_err(|e| BenchError::Message(format!("failed to spawn bb: {e}")))?;
+        match child
+            .wait_timeout(timeout)
+            .map_err(|e| BenchError::Message(e.to_string()))?
+        {
+            Some(done) => Ok(done),
+            None => Err(BenchError::Message("operation timed out".into())),
+        }
+    }
+
+    /// State shared across prove calls with amortized setup.
+    fn amortized(&self) -> BenchResult<MutexGuard<'_, AmortizedSetup>> {
+        self.amortized
+            .lock()
+            .map_err(|_| BenchError::Message("bb setup state poisoned".into()))
+    }
+
+    /// Estimate bb process startup as the fastest of a few `bb --version`
+    /// runs. It leaves out SRS loading and circuit deserialization, which
+    /// happen in every `bb prove`.
+    fn probe_startup_ms(&self) -> u128 {
+        (0..3)
+            .filter_map(|_| {
+                let start = Instant::now();
+                self.command()
+                    .arg("--version")
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .ok()
+                    .map(|_| start.elapsed().as_millis())
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Run a bb command with its stdout/stderr written to files in `out_dir`.
+    ///
+    /// Returns how the process finished and the combined output. On failure
+    /// the output is kept in the log directory and its stderr tail goes into
+    /// the error.
+    fn run_captured(
         &self,
         mut cmd: Command,
+        what: &str,
+        out_dir: &Path,
         timeout: Duration,
-    ) -> BenchResult<(std::process::ExitStatus, Option<u64>, u128)> {
-        #[cfg(feature = "mem")]
-        use sysinfo::{ProcessRefreshKind, RefreshKind, System};
+    ) -> BenchResult<(Finished, String)> {
+        let slug = what.replace(' ', "_");
+        let stdout_path = out_dir.join(format!("{slug}.stdout"));
+        let stderr_path = out_dir.join(format!("{slug}.stderr"));
+        let create = |path: &Path| {
+            std::fs::File::create(path).map_err(|e| {
+                BenchError::Message(format!("failed to create {}: {e}", path.display()))
+            })
+        };
+        cmd.stdin(Stdio::null())
+            .stdout(create(&stdout_path)?)
+            .stderr(create(&stderr_path)?);
+
+        let finished = self.run_with_timeout(cmd, timeout)?;
+        let stdout = std::fs::read(&stdout_path).unwrap_or_default();
+        let stderr = std::fs::read(&stderr_path).unwrap_or_default();
+        if !finished.status.success() {
+            let logs = self.config.log_dir.as_ref().map(FailureLogs::new);
+            return Err(command_failure(
+                what,
+                finished.status,
+                &stdout,
+                &stderr,
+                logs.as_ref(),
+            ));
+        }
+        let mut output = String::from_utf8_lossy(&stdout).into_owned();
+        output.push_str(&String::from_utf8_lossy(&stderr));
+        Ok((finished, output))
+    }
 
-        let start = Instant::now();
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| BenchError::Message(format!("failed to spawn bb: {e}")))?;
+    /// Write the vk for `artifact` into `out_dir`, returning its path and how
+    /// the `bb write_vk` process finished.
+    fn write_vk(
+        &self,
+        artifact: &Path,
+        out_dir: &Path,
+        timeout: Duration,
+    ) -> BenchResult<(PathBuf, Finished)> {
+        let mut vk_cmd = self.command();
+        vk_cmd
+            .arg("write_vk")
+            .arg("-b")
+            .arg(artifact)
+            .arg("-o")
+            .arg(out_dir);
+        for arg in &self.config.extra_args {
+            vk_cmd.arg(arg);
+        }
 
-        #[cfg(feature = "mem")]
-        let mut sys = System::new_with_specifics(
-            RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
-        );
-        #[cfg(feature = "mem")]
-        let mut peak_rss: u64 = 0;
-
-        loop {
-            if let Some(status) = child
-                .try_wait()
-                .map_err(|e| BenchError::Message(e.to_string()))?
-            {
-                let elapsed_ms = start.elapsed().as_millis();
-                #[cfg(feature = "mem")]
-                {
-                    if let Some(pid) = child.id().try_into().ok().map(sysinfo::Pid::from_u32) {
-                        sys.refresh_process(pid);
-                        if let Some(p) = sys.process(pid) {
-                            peak_rss = peak_rss.max(p.memory() * 1024);
-                        }
-                    }
-                }
-                return Ok((
-                    status,
-                    {
-                        #[cfg(feature = "mem")]
-                        {
-                            Some(peak_rss)
-                        }
-                        #[cfg(not(feature = "mem"))]
-                        {
-                            None
-                        }
-                    },
-                    elapsed_ms,
-                ));
-            }
+        let (finished, _) = self.run_captured(vk_cmd, "bb write_vk", out_dir, timeout)?;
+        Ok((out_dir.join("vk"), finished))
+    }
 
-            if timeout.as_secs() > 0 && start.elapsed() >= timeout {
-                let _ = child.kill();
-                let _ = child.wait();
-                return Err(BenchError::Message("operation timed out".into()));
+    /// vk for `artifact`: the cached one when the key cache has it, otherwise
+    /// generated into `out_dir` and stored in the cache. Returns the vk path,
+    /// the generating process if the vk was generated and the cache lookup if
+    /// a key cache is used.
+    ///
+    /// The cache is skipped when bb's version can't be detected, since vks of
+    /// different releases would share an entry.
+    fn keys(
+        &self,
+        artifact: &Path,
+        out_dir: &Path,
+        timeout: Duration,
+    ) -> BenchResult<(PathBuf, Option<Finished>, Option<KeyCacheStatus>)> {
+        let cache = self.config.key_cache.as_ref().and_then(|root| {
+            let Some(version) = self.version().filter(|v| !v.trim().is_empty()) else {
+                eprintln!("warning: not caching keys: cannot detect the bb version");
+                return None;
+            };
+            // Keys depend on the bb release and on flags like --disable_zk
+            let backend_id = format!(
+                "{}@{version} {}",
+                self.name(),
+                self.config.extra_args.join(" ")
+            );
+            match KeyCache::key(artifact, &backend_id) {
+                Ok(key) => Some((KeyCache::new(root), key)),
+                Err(e) => {
+                    eprintln!("warning: not caching keys: {e}");
+                    None
+                }
             }
-
-            #[cfg(feature = "mem")]
-            {
-                if let Some(pid) = child.id().try_into().ok().map(sysinfo::Pid::from_u32) {
-                    sys.refresh_process(pid);
-                    if let Some(p) = sys.process(pid) {
-                        peak_rss = peak_rss.max(p.memory() * 1024);
-                    }
+        });
+        if let Some((cache, key)) = &cache {
+            if !self.config.cold_keys {
+                if let Some(vk) = cache.vk(key) {
+                    return Ok((vk, None, Some(KeyCacheStatus::Hit)));
                 }
             }
+        }
 
-            std::thread::sleep(Duration::from_millis(50));
+        let (vk, generated) = self.write_vk(artifact, out_dir, timeout)?;
+        let Some((cache, key)) = cache else {
+            return Ok((vk, Some(generated), None));
+        };
+        if let Err(e) = cache.put(&key, &vk) {
+            eprintln!("warning: failed to cache keys: {e}");
         }
+        let status = if self.config.cold_keys {
+            KeyCacheStatus::Cold
+        } else {
+            KeyCacheStatus::Miss
+        };
+        Ok((vk, Some(generated), Some(status)))
     }
 
     /// Detect bb version.
     fn detect_version(&self) -> Option<String> {
-        Command::new(&self.config.bb_path)
+        self.command()
             .arg("--version")
             .output()
             .ok()
@@ -176,6 +403,33 @@ impl Backend for BarretenbergBackend {
         Capabilities::barretenberg()
     }
 
+    /// The bb release, the binary (two builds can share a version string)
+    /// and flags like `--disable_zk`.
+    fn cache_id(&self) -> String {
+        format!(
+            "{}@{} {} {}",
+            self.name(),
+            self.version().unwrap_or_default(),
+            self.config.bb_path.display(),
+            self.config.extra_args.join(" ")
+        )
+    }
+
+    fn cached_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.crs_dir().into_iter().collect();
+        files.extend(self.config.key_cache.clone());
+        files
+    }
+
+    /// Write a throwaway vk for `artifact`: like a prove, it loads the SRS
+    /// (downloading it on first use) and the artifact.
+    fn probe(&self, artifact: &Path) -> BenchResult<()> {
+        let out_dir = tempfile::tempdir()
+            .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+        self.write_vk(artifact, out_dir.path(), self.config.default_timeout)?;
+        Ok(())
+    }
+
     fn prove(
         &self,
         artifact: &Path,
@@ -188,38 +442,66 @@ impl Backend for BarretenbergBackend {
         })?;
 
         // Caller may read proof/vk paths after this fn returns (e.g. for verify), so leak
-        // the TempDir into a PathBuf instead of letting Drop delete it mid-use.
-        let out_dir = tempfile::tempdir()
-            .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?
-            .into_path();
+        // the TempDir into a PathBuf instead of letting Drop delete it mid-use. Under a
+        // work dir it is removed with the circuit's directory.
+        let out_dir = match &self.config.work_dir {
+            Some(dir) => tempfile::Builder::new().prefix("bb-").tempdir_in(dir),
+            None => tempfile::tempdir(),
+        }
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?
+        .into_path();
 
         // bb 5.x split the old one-shot `bb prove` into two steps. We need to write the VK
         // before proving, otherwise `bb prove` fails looking for a VK at ./target/vk.
-        let mut vk_cmd = Command::new(&self.config.bb_path);
-        vk_cmd
-            .arg("write_vk")
-            .arg("-b")
-            .arg(artifact)
-            .arg("-o")
-            .arg(&out_dir);
-        for arg in &self.config.extra_args {
-            vk_cmd.arg(arg);
-        }
-        vk_cmd
-            .stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        let (vk_status, _, _) = self.run_with_timeout(vk_cmd, timeout)?;
-        if !vk_status.success() {
-            return Err(BenchError::Message(format!(
-                "bb write_vk failed: status={vk_status}"
-            )));
-        }
-
-        let vk_path = out_dir.join("vk");
+        let mut setup_time_ms = None;
+        let mut vk_gen_time_ms = None;
+        let mut vk_gen_cpu = None;
+        let mut key_cache = None;
+        let mut startup_ms = 0;
+        let vk_path = if self.config.amortize_setup {
+            // Keyed by content, so a circuit recompiled in place gets a new vk
+            let vk_key = KeyCache::key(artifact, &self.cache_id())?;
+            let (probed, cached) = {
+                let state = self.amortized()?;
+                let cached = state.vks.get(&vk_key).filter(|vk| vk.exists()).cloned();
+                (state.startup_ms, cached)
+            };
+            // The lock isn't held while bb runs, so other circuits don't wait
+            let mut setup_ms = None;
+            startup_ms = match probed {
+                Some(ms) => ms,
+                None => {
+                    let ms = self.probe_startup_ms();
+                    *setup_ms.get_or_insert(0) += ms;
+                    *self.amortized()?.startup_ms.get_or_insert(ms)
+                }
+            };
+            let vk_path = match cached {
+                Some(vk) => vk,
+                None => {
+                    let (vk, generated, status) = self.keys(artifact, &out_dir, timeout)?;
+                    self.amortized()?.vks.insert(vk_key, vk.clone());
+                    let vk_ms = generated.map(|f| f.elapsed_ms);
+                    *setup_ms.get_or_insert(0) += vk_ms.unwrap_or(0);
+                    vk_gen_time_ms = vk_ms.map(|ms| ms.saturating_sub(startup_ms));
+                    vk_gen_cpu = generated.and_then(|f| f.cpu_time);
+                    key_cache = status;
+                    vk
+                }
+            };
+            setup_time_ms = setup_ms;
+            vk_path
+        } else {
+            let (vk, generated, status) = self.keys(artifact, &out_dir, timeout)?;
+            vk_gen_time_ms = generated.map(|f| f.elapsed_ms);
+            vk_gen_cpu = generated.and_then(|f| f.cpu_time);
+            key_cache = status;
+            vk
+        };
+        // The vk is either written by this call or reused
+        let vk_cached = vk_gen_time_ms.is_none();
 
-        let mut cmd = Command::new(&self.config.bb_path);
+        let mut cmd = self.command();
         cmd.arg("prove")
             .arg("-b")
             .arg(artifact)
@@ -234,18 +516,18 @@ impl Backend for BarretenbergBackend {
             cmd.arg(arg);
         }
 
-        cmd.stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        let (status, peak_memory_bytes, prove_time_ms) = self.run_with_timeout(cmd, timeout)?;
-
-        if !status.success() {
-            return Err(BenchError::Message(format!(
-                "bb prove failed: status={status}"
-            )));
+        if self.config.verbose {
+            cmd.arg("-v");
         }
 
+        let (finished, log) = self.run_captured(cmd, "bb prove", &out_dir, timeout)?;
+        let prove_time_ms = finished.elapsed_ms.saturating_sub(startup_ms);
+        let phases = if self.config.verbose {
+            Some(parse_bb_phases(&log)).filter(|p| !p.is_empty())
+        } else {
+            None
+        };
+
         // bb 5.x emits proof + public_inputs + the pre-computed vk; no pk file.
         let proof_path = out_dir.join("proof");
 
@@ -257,7 +539,7 @@ impl Backend for BarretenbergBackend {
             prove_time_ms,
             witness_gen_time_ms: None, // Witness was pre-generated
             backend_prove_time_ms: Some(prove_time_ms),
-            peak_memory_bytes,
+            peak_memory_bytes: finished.peak_memory_bytes,
             proof_size_bytes,
             proving_key_size_bytes,
             verification_key_size_bytes,
@@ -271,11 +553,18 @@ impl Backend for BarretenbergBackend {
             } else {
                 None
             },
+            setup_time_ms,
+            phases,
+            vk_gen_time_ms,
+            vk_cached,
+            key_cache,
+            prove_cpu: finished.cpu_time,
+            vk_gen_cpu,
         })
     }
 
     fn verify(&self, proof: &Path, vk: &Path) -> BenchResult<VerifyOutput> {
-        let mut cmd = Command::new(&self.config.bb_path);
+        let mut cmd = self.command();
         cmd.arg("verify").arg("-p").arg(proof).arg("-k").arg(vk);
 
         // bb 5.x splits public inputs into a sibling file. If our prove step
@@ -309,7 +598,7 @@ impl Backend for BarretenbergBackend {
     }
 
     fn gate_info(&self, artifact: &Path) -> BenchResult<GateInfo> {
-        let mut cmd = Command::new(&self.config.bb_path);
+        let mut cmd = self.command();
         cmd.arg("gates").arg("-b").arg(artifact);
 
         for arg in &self.config.extra_args {
@@ -321,11 +610,14 @@ impl Backend for BarretenbergBackend {
             .map_err(|e| BenchError::Message(format!("failed to run bb gates: {e}")))?;
 
         if !output.status.success() {
-            return Err(BenchError::Message(format!(
-                "bb gates failed: status={} stderr={}",
+            let logs = self.config.log_dir.as_ref().map(FailureLogs::new);
+            return Err(command_failure(
+                "bb gates",
                 output.status,
-                String::from_utf8_lossy(&output.stderr)
-            )));
+                &output.stdout,
+                &output.stderr,
+                logs.as_ref(),
+            ));
         }
 
         // Parse the JSON output
@@ -397,6 +689,23 @@ mod tests {
         assert_eq!(config.bb_path, PathBuf::from("/usr/local/bin/bb"));
         assert_eq!(config.extra_args, vec!["--scheme", "ultra_honk"]);
         assert_eq!(config.default_timeout, Duration::from_secs(60));
+        assert!(!config.amortize_setup);
+        assert!(!config.verbose);
+        assert!(config.log_dir.is_none());
+        assert!(config.key_cache.is_none());
+        assert!(!config.cold_keys);
+        assert!(config.with_amortize_setup(true).amortize_setup);
+    }
+
+    #[test]
+    fn test_cache_id_includes_binary_and_args() {
+        let a = BarretenbergBackend::new(BarretenbergConfig::new("/nonexistent/a/bb"));
+        let b = BarretenbergBackend::new(BarretenbergConfig::new("/nonexistent/b/bb"));
+        let zk = BarretenbergBackend::new(
+            BarretenbergConfig::new("/nonexistent/a/bb").with_args(vec!["--disable_zk".into()]),
+        );
+        assert_ne!(a.cache_id(), b.cache_id());
+        assert_ne!(a.cache_id(), zk.cache_id());
     }
 
     #[test]
@@ -405,6 +714,185 @@ mod tests {
         assert_eq!(backend.name(), "barretenberg");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_prove_reuses_cached_keys_across_backends() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let vk_log = dir.path().join("write_vk.log");
+        let bb = dir.path().join("bb");
+        let script = format!(
+            r#"#!/bin/sh
+cmd=$1; shift
+out=""
+while [ $# -gt 0 ]; do
+  case "$1" in -o) out=$2; shift ;; esac
+  shift
+done
+case "$cmd" in
+  --version) echo 1.0.0-fake ;;
+  write_vk) echo vk >> "{}"; printf vk > "$out/vk" ;;
+  prove) printf proof > "$out/proof" ;;
+esac
+"#,
+            vk_log.display()
+        );
+        std::fs::write(&bb, script).unwrap();
+        std::fs::set_permissions(&bb, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let artifact = dir.path().join("circuit.json");
+        let witness = dir.path().join("witness.gz");
+        std::fs::write(&artifact, b"{}").unwrap();
+        std::fs::write(&witness, b"witness").unwrap();
+
+        let cache = dir.path().join("keys");
+        let prove = |cold: bool| {
+            let config = BarretenbergConfig::new(&bb)
+                .with_key_cache(&cache)
+                .with_cold_keys(cold);
+            BarretenbergBackend::new(config)
+                .prove(&artifact, Some(&witness), Duration::from_secs(30))
+                .unwrap()
+        };
+        let vk_writes = || std::fs::read_to_string(&vk_log).unwrap().lines().count();
+
+        let first = prove(false);
+        assert_eq!(first.key_cache, Some(KeyCacheStatus::Miss));
+        assert!(first.vk_gen_time_ms.is_some());
+        assert!(!first.vk_cached);
+
+        // A later session loads the keys instead of generating them
+        let second = prove(false);
+        assert_eq!(second.key_cache, Some(KeyCacheStatus::Hit));
+        assert!(second.vk_gen_time_ms.is_none());
+        assert!(second.vk_cached);
+        assert!(second.vk_path.unwrap().starts_with(&cache));
+        assert_eq!(vk_writes(), 1);
+
+        let cold = prove(true);
+        assert_eq!(cold.key_cache, Some(KeyCacheStatus::Cold));
+        assert!(cold.vk_gen_time_ms.is_some());
+        assert_eq!(vk_writes(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prove_amortized_setup_reuses_vk_per_artifact_content() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let vk_log = dir.path().join("write_vk.log");
+        let bb = dir.path().join("bb");
+        let script = format!(
+            r#"#!/bin/sh
+cmd=$1; shift
+out=""
+while [ $# -gt 0 ]; do
+  case "$1" in -o) out=$2; shift ;; esac
+  shift
+done
+case "$cmd" in
+  --version) echo 1.0.0-fake ;;
+  write_vk) echo vk >> "{}"; printf vk > "$out/vk" ;;
+  prove) printf proof > "$out/proof" ;;
+esac
+"#,
+            vk_log.display()
+        );
+        std::fs::write(&bb, script).unwrap();
+        std::fs::set_permissions(&bb, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let artifact = dir.path().join("circuit.json");
+        let witness = dir.path().join("witness.gz");
+        std::fs::write(&artifact, b"{}").unwrap();
+        std::fs::write(&witness, b"witness").unwrap();
+
+        let backend =
+            BarretenbergBackend::new(BarretenbergConfig::new(&bb).with_amortize_setup(true));
+        let prove = || {
+            backend
+                .prove(&artifact, Some(&witness), Duration::from_secs(30))
+                .unwrap()
+        };
+        let vk_writes = || std::fs::read_to_string(&vk_log).unwrap().lines().count();
+
+        let first = prove();
+        assert!(first.setup_time_ms.is_some());
+        assert!(first.vk_gen_time_ms.is_some());
+        assert!(!first.vk_cached);
+
+        // The second prove of the same artifact reuses the vk and reports no setup
+        let second = prove();
+        assert!(second.setup_time_ms.is_none());
+        assert!(second.vk_gen_time_ms.is_none());
+        assert!(second.vk_cached);
+        assert_eq!(second.vk_path, first.vk_path);
+        assert_eq!(vk_writes(), 1);
+
+        // Recompiled in place: same path, new content, new vk
+        std::fs::write(&artifact, b"{\"bytecode\":\"changed\"}").unwrap();
+        let recompiled = prove();
+        assert!(recompiled.setup_time_ms.is_some());
+        assert!(recompiled.vk_gen_time_ms.is_some());
+        assert!(!recompiled.vk_cached);
+        assert_ne!(recompiled.vk_path, first.vk_path);
+        assert_eq!(vk_writes(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prove_skips_key_cache_without_bb_version() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let bb = dir.path().join("bb");
+        let script = r#"#!/bin/sh
+cmd=$1; shift
+out=""
+while [ $# -gt 0 ]; do
+  case "$1" in -o) out=$2; shift ;; esac
+  shift
+done
+case "$cmd" in
+  --version) exit 1 ;;
+  write_vk) printf vk > "$out/vk" ;;
+  prove) printf proof > "$out/proof" ;;
+esac
+"#;
+        std::fs::write(&bb, script).unwrap();
+        std::fs::set_permissions(&bb, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let artifact = dir.path().join("circuit.json");
+        let witness = dir.path().join("witness.gz");
+        std::fs::write(&artifact, b"{}").unwrap();
+        std::fs::write(&witness, b"witness").unwrap();
+
+        let cache = dir.path().join("keys");
+        let config = BarretenbergConfig::new(&bb).with_key_cache(&cache);
+        let output = BarretenbergBackend::new(config)
+            .prove(&artifact, Some(&witness), Duration::from_secs(30))
+            .unwrap();
+        assert!(output.key_cache.is_none());
+        assert!(output.vk_gen_time_ms.is_some());
+        assert!(!cache.exists());
+    }
+
+    #[test]
+    fn test_cached_files_include_crs_and_key_cache() {
+        let backend = BarretenbergBackend::new(
+            BarretenbergConfig::new("bb")
+                .with_args(vec!["-c".into(), "/srs".into()])
+                .with_key_cache("/keys"),
+        );
+        assert_eq!(
+            backend.cached_files(),
+            vec![PathBuf::from("/srs"), PathBuf::from("/keys")]
+        );
+
+        let backend = BarretenbergBackend::new(
+            BarretenbergConfig::new("bb").with_args(vec!["--crs_path=/srs2".into()]),
+        );
+        assert_eq!(backend.cached_files(), vec![PathBuf::from("/srs2")]);
+    }
+
     #[test]
     fn test_backend_capabilities() {
         let backend = BarretenbergBackend::from_path("bb");
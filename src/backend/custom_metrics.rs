@@ -0,0 +1,214 @@
+//! Extra metrics scraped from a template backend's stdout.
+//!
+//! Template backends (`--template`) often report numbers of their own, such
+//! as internal counters of an in-house prover, that are worth keeping with
+//! the record. Each metric is declared as `NAME=SOURCE`:
+//!
+//! - `NAME` alone reads a `NAME=<number>` or `NAME: <number>` line;
+//! - `NAME=regex:PATTERN` takes the first capture group (or the whole match)
+//!   of the last match of `PATTERN`;
+//! - `NAME=json:/POINTER` reads a JSON pointer from the output, parsed as a
+//!   whole or, failing that, from the last JSON line that has the pointer.
+//!
+//! Scraped values end up in `custom_metrics` of the report and record. Compare,
+//! CSV export and the reports show them as `custom.NAME`, handled like any
+//! unregistered metric (lower is better, unit guessed from the name).
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{BenchError, BenchResult};
+
+/// Prefix of custom metrics in comparisons, reports and CSV columns, keeping
+/// them apart from the built-in metrics.
+pub const CUSTOM_METRIC_PREFIX: &str = "custom.";
+
+/// Where a custom metric is read from.
+#[derive(Debug, Clone)]
+pub enum MetricSource {
+    /// A `NAME=<number>` or `NAME: <number>` line
+    KeyValue,
+    /// First capture group (or whole match) of a pattern
+    Regex(Regex),
+    /// JSON pointer into the output
+    JsonPointer(String),
+}
+
+/// A metric declared by the user, and how to scrape it.
+#[derive(Debug, Clone)]
+pub struct MetricScraper {
+    pub name: String,
+    pub source: MetricSource,
+}
+
+/// Parse a `--custom-metric` declaration (see the module docs).
+pub fn parse_metric_spec(spec: &str) -> BenchResult<MetricScraper> {
+    let (name, source) = match spec.split_once('=') {
+        Some((name, source)) => (name.trim(), Some(source)),
+        None => (spec.trim(), None),
+    };
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(BenchError::Message(format!(
+            "invalid custom metric name '{name}' (use letters, digits, '_' and '-')"
+        )));
+    }
+    let source = match source {
+        None => MetricSource::KeyValue,
+        Some(s) => {
+            if let Some(pattern) = s.strip_prefix("regex:") {
+                let re = Regex::new(pattern).map_err(|e| {
+                    BenchError::Message(format!("invalid regex for custom metric '{name}': {e}"))
+                })?;
+                MetricSource::Regex(re)
+            } else if let Some(pointer) = s.strip_prefix("json:") {
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    return Err(BenchError::Message(format!(
+                        "JSON pointer for custom metric '{name}' must start with '/'"
+                    )));
+                }
+                MetricSource::JsonPointer(pointer.to_string())
+            } else {
+                return Err(BenchError::Message(format!(
+                    "unknown source '{s}' for custom metric '{name}' (expected regex:PATTERN or json:/POINTER)"
+                )));
+            }
+        }
+    };
+    Ok(MetricScraper {
+        name: name.to_string(),
+        source,
+    })
+}
+
+/// Leading number of `text`, ignoring surrounding whitespace and a trailing
+/// unit or comma.
+fn leading_number(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+fn json_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+impl MetricScraper {
+    /// Value of the metric in `output`; the last occurrence wins.
+    pub fn scrape(&self, output: &str) -> Option<f64> {
+        match &self.source {
+            MetricSource::KeyValue => output.lines().rev().find_map(|line| {
+                let (key, value) = line.split_once('=').or_else(|| line.split_once(':'))?;
+                (key.trim() == self.name)
+                    .then(|| leading_number(value))
+                    .flatten()
+            }),
+            MetricSource::Regex(re) => re.captures_iter(output).last().and_then(|caps| {
+                let m = caps.get(1).or_else(|| caps.get(0))?;
+                leading_number(m.as_str())
+            }),
+            MetricSource::JsonPointer(pointer) => {
+                if let Ok(doc) = serde_json::from_str::<Value>(output) {
+                    return doc.pointer(pointer).and_then(json_number);
+                }
+                output.lines().rev().find_map(|line| {
+                    let doc: Value = serde_json::from_str(line.trim()).ok()?;
+                    doc.pointer(pointer).and_then(json_number)
+                })
+            }
+        }
+    }
+}
+
+/// Values of every scraper found in `output`; metrics that aren't are left
+/// out.
+pub fn scrape_metrics(scrapers: &[MetricScraper], output: &str) -> BTreeMap<String, f64> {
+    scrapers
+        .iter()
+        .filter_map(|s| Some((s.name.clone(), s.scrape(output)?)))
+        .collect()
+}
+
+/// Mean of each metric over the runs that reported it.
+pub fn mean_metrics(runs: &[BTreeMap<String, f64>]) -> BTreeMap<String, f64> {
+    let mut totals: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+    for (name, value) in runs.iter().flatten() {
+        let total = totals.entry(name.clone()).or_insert((0.0, 0));
+        total.0 += value;
+        total.1 += 1;
+    }
+    totals
+        .into_iter()
+        .map(|(name, (sum, n))| (name, sum / n as f64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+loading circuit
+lookup_tables=12
+poly evals: 4096
+msm took 230ms
+{\"stats\":{\"rounds\":18,\"commitments\":\"7\"}}
+";
+
+    #[test]
+    fn test_scrape_key_value_regex_and_json() {
+        let specs = [
+            "lookup_tables",
+            "poly_evals=regex:poly evals: (\\d+)",
+            "msm_ms=regex:msm took ([0-9.]+)ms",
+            "rounds=json:/stats/rounds",
+            "commitments=json:/stats/commitments",
+            "missing=json:/stats/missing",
+        ];
+        let scrapers: Vec<MetricScraper> = specs
+            .iter()
+            .map(|s| parse_metric_spec(s).unwrap())
+            .collect();
+        let values = scrape_metrics(&scrapers, OUTPUT);
+        assert_eq!(values["lookup_tables"], 12.0);
+        assert_eq!(values["poly_evals"], 4096.0);
+        assert_eq!(values["msm_ms"], 230.0);
+        assert_eq!(values["rounds"], 18.0);
+        assert_eq!(values["commitments"], 7.0);
+        assert!(!values.contains_key("missing"));
+
+        let whole = parse_metric_spec("rounds=json:/rounds").unwrap();
+        assert_eq!(whole.scrape("{\n  \"rounds\": 3\n}\n"), Some(3.0));
+    }
+
+    #[test]
+    fn test_parse_metric_spec_rejects_bad_declarations() {
+        assert!(parse_metric_spec("a.b").is_err());
+        assert!(parse_metric_spec("x=regex:(").is_err());
+        assert!(parse_metric_spec("x=json:stats").is_err());
+        assert!(parse_metric_spec("x=jq:.stats").is_err());
+    }
+
+    #[test]
+    fn test_mean_metrics_skips_missing_runs() {
+        let runs = [
+            BTreeMap::from([("rounds".to_string(), 10.0), ("msm_ms".to_string(), 4.0)]),
+            BTreeMap::from([("rounds".to_string(), 20.0)]),
+        ];
+        let mean = mean_metrics(&runs);
+        assert_eq!(mean["rounds"], 15.0);
+        assert_eq!(mean["msm_ms"], 4.0);
+        assert!(mean_metrics(&[]).is_empty());
+    }
+}
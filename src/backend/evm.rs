@@ -1,35 +1,36 @@
-//! Bench-specific backend helpers.
+//! EVM backend: on-chain verification gas measured with Foundry.
 //!
-//! This module provides specialized backend support for the bench runner.
-//! The main proving workflow is now handled by `crate::engine::workflow` using
-//! the unified `crate::backend::Backend` trait.
-//!
-//! This module only contains:
-//! - `EvmBackend`: For EVM gas verification via Foundry (distinct from proving)
+//! It implements `Backend` as a verify-only backend. The Foundry project
+//! verifies its own proof fixture, so `verify` ignores the proof and vk
+//! paths; `measure` additionally returns the gas and calldata size.
 
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
 use crate::{BenchError, BenchResult};
 
+use super::traits::{Backend, Capabilities, GateInfo, ProveOutput, VerifyOutput};
+
 /// Output from EVM verification.
 #[derive(Debug, Clone)]
 pub struct EvmVerifyOutput {
-    /// Verification time in milliseconds (if available)
+    /// Wall time of `forge test` in milliseconds, including any build
     pub verify_time_ms: Option<u128>,
     /// Whether verification succeeded
     pub success: bool,
     /// Gas used for verification
     pub gas_used: Option<u64>,
+    /// Proof calldata size, when the test logs `CALLDATA_BYTES: <n>`
+    pub calldata_bytes: Option<u64>,
 }
 
 /// EVM backend for gas measurement via Foundry.
 ///
 /// This backend is specialized for measuring EVM verification gas costs.
-/// It runs `forge test --gas-report` and parses the output.
-///
-/// Note: This does not implement `crate::backend::Backend` because it operates
-/// on Foundry projects, not Noir artifacts.
+/// It runs `forge test --gas-report` and parses the output. It operates on a
+/// Foundry project rather than a Noir artifact, so it can't prove or count
+/// gates.
 pub struct EvmBackend {
     /// Path to the Foundry project directory
     pub foundry_dir: PathBuf,
@@ -64,13 +65,13 @@ impl EvmBackend {
         self
     }
 
+    fn forge(&self) -> Command {
+        Command::new(self.forge_bin.as_deref().unwrap_or(Path::new("forge")))
+    }
+
     /// Run EVM verification and measure gas usage.
-    pub fn verify(&self) -> BenchResult<EvmVerifyOutput> {
-        let forge = self
-            .forge_bin
-            .clone()
-            .unwrap_or_else(|| PathBuf::from("forge"));
-        let mut cmd = Command::new(&forge);
+    pub fn measure(&self) -> BenchResult<EvmVerifyOutput> {
+        let mut cmd = self.forge();
         cmd.arg("test").arg("--gas-report");
         if let Some(pat) = &self.test_pattern {
             cmd.arg("-m").arg(pat);
@@ -80,9 +81,11 @@ impl EvmBackend {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        let start = Instant::now();
         let output = cmd
             .output()
             .map_err(|e| BenchError::Message(format!("failed to run forge: {e}")))?;
+        let elapsed_ms = start.elapsed().as_millis();
         let stdout_s = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr_s = String::from_utf8_lossy(&output.stderr).to_string();
 
@@ -102,11 +105,65 @@ impl EvmBackend {
             })?;
 
         Ok(EvmVerifyOutput {
-            verify_time_ms: None,
+            verify_time_ms: Some(elapsed_ms),
             success: true,
             gas_used: Some(gas_used),
+            calldata_bytes: read_calldata_from_stdout(&stdout_s),
+        })
+    }
+}
+
+impl Backend for EvmBackend {
+    fn name(&self) -> &str {
+        "evm"
+    }
+
+    /// The forge release, e.g. `forge 0.2.0 (...)`.
+    fn version(&self) -> Option<String> {
+        self.forge()
+            .arg("--version")
+            .stdin(Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+            .filter(|s| !s.is_empty())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            can_verify: true,
+            ..Capabilities::default()
+        }
+    }
+
+    fn prove(
+        &self,
+        _artifact: &Path,
+        _witness: Option<&Path>,
+        _timeout: Duration,
+    ) -> BenchResult<ProveOutput> {
+        Err(BenchError::Message(
+            "the evm backend only verifies; prove with barretenberg".into(),
+        ))
+    }
+
+    /// Run the Foundry verifier test; the project's own proof fixture is
+    /// verified, not `proof`/`vk`.
+    fn verify(&self, _proof: &Path, _vk: &Path) -> BenchResult<VerifyOutput> {
+        let out = self.measure()?;
+        Ok(VerifyOutput {
+            verify_time_ms: out.verify_time_ms.unwrap_or(0),
+            success: out.success,
         })
     }
+
+    fn gate_info(&self, _artifact: &Path) -> BenchResult<GateInfo> {
+        Err(BenchError::Message(
+            "the evm backend has no gate count".into(),
+        ))
+    }
 }
 
 /// Parse gas usage from .gas-snapshot file.
@@ -153,6 +210,22 @@ fn read_gas_from_stdout(stdout: &str) -> Option<u64> {
     None
 }
 
+/// Parse the calldata size logged by the verifier test. The misspelled
+/// `CALDATA_BYTES:` marker read by `evm-verify` is accepted too.
+fn read_calldata_from_stdout(stdout: &str) -> Option<u64> {
+    ["CALLDATA_BYTES:", "CALDATA_BYTES:"]
+        .iter()
+        .find_map(|marker| {
+            let idx = stdout.find(marker)?;
+            let num: String = stdout[idx + marker.len()..]
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            num.parse().ok()
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +242,29 @@ mod tests {
         assert_eq!(read_gas_from_stdout(stdout), Some(1234567));
     }
 
+    #[test]
+    fn test_read_calldata_from_stdout() {
+        assert_eq!(
+            read_calldata_from_stdout("gas: 1\nCALLDATA_BYTES: 2340\n"),
+            Some(2340)
+        );
+        assert_eq!(read_calldata_from_stdout("CALDATA_BYTES:512"), Some(512));
+        assert_eq!(read_calldata_from_stdout("gas: 1"), None);
+    }
+
+    #[test]
+    fn test_evm_backend_is_verify_only() {
+        let evm = EvmBackend::new("/nonexistent");
+        assert_eq!(evm.name(), "evm");
+        assert!(evm.capabilities().can_verify);
+        assert!(!evm.capabilities().can_prove);
+        assert!(
+            evm.prove(Path::new("a.json"), None, Duration::from_secs(1))
+                .is_err()
+        );
+        assert!(evm.gate_info(Path::new("a.json")).is_err());
+    }
+
     #[test]
     fn test_read_gas_from_stdout_no_match() {
         let stdout = "No gas info here";
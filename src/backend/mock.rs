@@ -1,6 +1,7 @@
 //! Mock backend for testing.
 
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
 
 use crate::BenchResult;
@@ -28,6 +29,9 @@ pub struct MockConfig {
     pub verify_fails: bool,
     /// Whether gate_info should fail
     pub gate_info_fails: bool,
+    /// Report the prove output's vk generation only on the first prove, and
+    /// the vk as reused after that
+    pub cache_vk: bool,
 }
 
 impl MockConfig {
@@ -47,6 +51,13 @@ impl MockConfig {
                 verification_key_size_bytes: Some(1024),
                 proof_path: None,
                 vk_path: None,
+                setup_time_ms: None,
+                phases: None,
+                vk_gen_time_ms: None,
+                vk_cached: false,
+                key_cache: None,
+                prove_cpu: None,
+                vk_gen_cpu: None,
             }),
             verify_output: Some(VerifyOutput {
                 verify_time_ms: 50,
@@ -61,6 +72,7 @@ impl MockConfig {
             prove_fails: false,
             verify_fails: false,
             gate_info_fails: false,
+            cache_vk: false,
         }
     }
 
@@ -100,6 +112,12 @@ impl MockConfig {
         self
     }
 
+    /// Reuse the vk across proves.
+    pub fn with_vk_cache(mut self) -> Self {
+        self.cache_vk = true;
+        self
+    }
+
     /// Set capabilities.
     pub fn with_capabilities(mut self, caps: Capabilities) -> Self {
         self.capabilities = caps;
@@ -113,12 +131,23 @@ impl MockConfig {
 /// any actual proving or verification operations.
 pub struct MockBackend {
     config: MockConfig,
+    vk_generated: AtomicBool,
+    gate_info_calls: AtomicUsize,
 }
 
 impl MockBackend {
     /// Create a new mock backend with the given configuration.
     pub fn new(config: MockConfig) -> Self {
-        MockBackend { config }
+        MockBackend {
+            config,
+            vk_generated: AtomicBool::new(false),
+            gate_info_calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of `gate_info` calls so far.
+    pub fn gate_info_calls(&self) -> usize {
+        self.gate_info_calls.load(Ordering::Relaxed)
     }
 
     /// Create a mock backend with default configuration.
@@ -156,10 +185,17 @@ impl Backend for MockBackend {
         if self.config.prove_fails {
             return Err(crate::BenchError::Message("mock prove failed".into()));
         }
-        self.config
+        let mut output = self
+            .config
             .prove_output
             .clone()
-            .ok_or_else(|| crate::BenchError::Message("no prove output configured".into()))
+            .ok_or_else(|| crate::BenchError::Message("no prove output configured".into()))?;
+        if self.config.cache_vk && self.vk_generated.swap(true, Ordering::Relaxed) {
+            output.vk_gen_time_ms = None;
+            output.vk_gen_cpu = None;
+            output.vk_cached = true;
+        }
+        Ok(output)
     }
 
     fn verify(&self, _proof: &Path, _vk: &Path) -> BenchResult<VerifyOutput> {
@@ -173,6 +209,7 @@ impl Backend for MockBackend {
     }
 
     fn gate_info(&self, _artifact: &Path) -> BenchResult<GateInfo> {
+        self.gate_info_calls.fetch_add(1, Ordering::Relaxed);
         if self.config.gate_info_fails {
             return Err(crate::BenchError::Message("mock gate_info failed".into()));
         }
@@ -3,11 +3,20 @@
 //! This module provides a consolidated `Backend` trait that combines
 //! proving, verification, and gate analysis capabilities.
 
+pub mod args;
 pub mod barretenberg;
+pub mod custom_metrics;
+pub mod evm;
 pub mod mock;
+pub mod phases;
 pub mod traits;
 
 // Re-export key types
+pub use args::{BackendSection, load_backend_args, validate_bb_args};
 pub use barretenberg::{BarretenbergBackend, BarretenbergConfig};
+pub use custom_metrics::{
+    CUSTOM_METRIC_PREFIX, MetricScraper, mean_metrics, parse_metric_spec, scrape_metrics,
+};
+pub use evm::{EvmBackend, EvmVerifyOutput};
 pub use mock::{MockBackend, MockConfig};
 pub use traits::{Backend, Capabilities, GateInfo, ProveOutput, VerifyOutput};
@@ -0,0 +1,116 @@
+//! Proving phase breakdown parsed from bb's verbose (`-v`) log.
+//!
+//! bb logs how long the main proving steps take as lines such as
+//! `computing wire commitments took 120ms` or `sumcheck: 1.2 s`. Labels are
+//! matched by keyword to stable phase keys; lines for phases we don't know
+//! are ignored, and repeated phases (e.g. one FFT per polynomial) are summed.
+
+use std::collections::BTreeMap;
+
+/// Log label keywords, matched case-insensitively in order, and the phase
+/// key they map to.
+const PHASE_KEYWORDS: &[(&str, &str)] = &[
+    ("wire commitment", "wire_commitments"),
+    ("grand product", "permutation"),
+    ("permutation", "permutation"),
+    ("fft", "fft"),
+    ("sumcheck", "sumcheck"),
+    ("opening", "opening_proof"),
+    ("shplemini", "opening_proof"),
+    ("msm", "msm"),
+];
+
+/// Parse per-phase times in milliseconds from a bb verbose log.
+pub fn parse_bb_phases(log: &str) -> BTreeMap<String, f64> {
+    let mut phases = BTreeMap::new();
+    for line in log.lines() {
+        let lower = line.to_lowercase();
+        let Some((label, value)) = lower
+            .rsplit_once(" took ")
+            .or_else(|| lower.rsplit_once(": "))
+        else {
+            continue;
+        };
+        let Some(ms) = parse_duration_ms(value) else {
+            continue;
+        };
+        if let Some((_, key)) = PHASE_KEYWORDS.iter().find(|(kw, _)| label.contains(kw)) {
+            *phases.entry(key.to_string()).or_insert(0.0) += ms;
+        }
+    }
+    phases
+}
+
+/// Mean time per phase across runs; a phase missing from a run counts as
+/// zero for that run. `None` when there are no runs.
+pub fn mean_phases(runs: &[BTreeMap<String, f64>]) -> Option<BTreeMap<String, f64>> {
+    if runs.is_empty() {
+        return None;
+    }
+    let mut total: BTreeMap<String, f64> = BTreeMap::new();
+    for run in runs {
+        for (phase, ms) in run {
+            *total.entry(phase.clone()).or_insert(0.0) += ms;
+        }
+    }
+    let n = runs.len() as f64;
+    Some(total.into_iter().map(|(k, v)| (k, v / n)).collect())
+}
+
+/// Parse `<number><unit>` (unit `ns`, `us`/`µs`, `ms` or `s`) into ms.
+fn parse_duration_ms(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let number: f64 = value[..end].parse().ok()?;
+    let unit = value[end..].trim_start();
+    let scale = if unit.starts_with("ms") {
+        1.0
+    } else if unit.starts_with("ns") {
+        1e-6
+    } else if unit.starts_with("us") || unit.starts_with("µs") {
+        1e-3
+    } else if unit.starts_with('s') {
+        1000.0
+    } else {
+        return None;
+    };
+    Some(number * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bb_phases() {
+        let log = "\
+bb command is: prove
+computing wire commitments took 120ms
+Computing grand product (permutation) took 40 ms
+fft took 500us
+FFT took 1.5ms
+sumcheck: 1.2 s
+Shplemini opening proof took 80ms
+circuit size: 65536
+";
+        let phases = parse_bb_phases(log);
+        assert_eq!(phases["wire_commitments"], 120.0);
+        assert_eq!(phases["permutation"], 40.0);
+        assert!((phases["fft"] - 2.0).abs() < 1e-9);
+        assert_eq!(phases["sumcheck"], 1200.0);
+        assert_eq!(phases["opening_proof"], 80.0);
+        assert_eq!(phases.len(), 5);
+    }
+
+    #[test]
+    fn test_mean_phases() {
+        let a = BTreeMap::from([("fft".to_string(), 10.0), ("msm".to_string(), 4.0)]);
+        let b = BTreeMap::from([("fft".to_string(), 20.0)]);
+        let mean = mean_phases(&[a, b]).unwrap();
+        assert_eq!(mean["fft"], 15.0);
+        assert_eq!(mean["msm"], 2.0);
+        assert!(mean_phases(&[]).is_none());
+    }
+}
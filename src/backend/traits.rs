@@ -1,12 +1,14 @@
 //! Backend trait and output types for the unified backend abstraction.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::BenchResult;
+use crate::core::KeyCacheStatus;
+use crate::core::process::CpuTime;
 
 /// Capabilities that a backend may support.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -72,6 +74,31 @@ pub struct ProveOutput {
     pub proof_path: Option<PathBuf>,
     /// Path to the verification key file
     pub vk_path: Option<PathBuf>,
+    /// One-time backend setup done by this call (process startup probe,
+    /// vk generation) and kept out of `prove_time_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup_time_ms: Option<u128>,
+    /// Per-phase proving times in milliseconds from verbose backend logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phases: Option<BTreeMap<String, f64>>,
+    /// vk generation done by this call before proving, kept out of
+    /// `prove_time_ms`; None when the vk was reused or isn't generated
+    /// separately
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_gen_time_ms: Option<u128>,
+    /// The vk was generated by an earlier call and reused
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub vk_cached: bool,
+    /// On-disk key cache lookup done by this call, if it used one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_cache: Option<KeyCacheStatus>,
+    /// Wall and CPU time of the backend prove process, where reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prove_cpu: Option<CpuTime>,
+    /// Wall and CPU time of the vk generation process, when this call
+    /// generated the vk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_gen_cpu: Option<CpuTime>,
 }
 
 impl Default for ProveOutput {
@@ -86,6 +113,13 @@ impl Default for ProveOutput {
             verification_key_size_bytes: None,
             proof_path: None,
             vk_path: None,
+            setup_time_ms: None,
+            phases: None,
+            vk_gen_time_ms: None,
+            vk_cached: false,
+            key_cache: None,
+            prove_cpu: None,
+            vk_gen_cpu: None,
         }
     }
 }
@@ -178,6 +212,29 @@ pub trait Backend: Send + Sync {
     /// # Returns
     /// GateInfo with gate counts and optional breakdown
     fn gate_info(&self, artifact: &Path) -> BenchResult<GateInfo>;
+
+    /// Warm up what the first prove of `artifact` would otherwise load cold
+    /// (binary, artifact, SRS) before anything is measured, failing if the
+    /// backend doesn't work.
+    ///
+    /// Runs after the gate analysis that health-checks gate-counting
+    /// backends. Defaults to nothing.
+    fn probe(&self, _artifact: &Path) -> BenchResult<()> {
+        Ok(())
+    }
+
+    /// Identifies everything that changes this backend's results (gate
+    /// counts, keys) for cache keys: name and version by default.
+    fn cache_id(&self) -> String {
+        format!("{}@{}", self.name(), self.version().unwrap_or_default())
+    }
+
+    /// Files or directories the backend reads on every prove besides the
+    /// artifact and witness (SRS, cached keys), evicted from the page cache
+    /// in cold-cache runs (see `core::page_cache`).
+    fn cached_files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,271 @@
+//! CLI command handler for `baseline`: named baseline snapshots.
+//!
+//! `baseline freeze --name v1.2.0` collapses a history into one record per
+//! circuit/backend (the median of its last runs, see
+//! `compare_cmd::window_baseline`) and stores it as
+//! `<baselines-dir>/v1.2.0.jsonl`. `compare --against v1.2.0` then compares
+//! against that snapshot, so "how do we compare to the last release" has an
+//! answer that doesn't move with the rolling baseline.
+
+use std::path::{Path, PathBuf};
+
+use crate::compare_cmd::window_baseline;
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Default directory for named baseline snapshots.
+pub const DEFAULT_BASELINES_DIR: &str = ".noir-bench/baselines";
+
+/// Default number of runs per circuit a snapshot takes the median over.
+pub const DEFAULT_FREEZE_WINDOW: usize = 5;
+
+/// Metadata key recording which snapshot a record belongs to.
+const BASELINE_KEY: &str = "baseline";
+
+/// Metadata key set on the records of a snapshot frozen with anomalies.
+const ANOMALIES_KEY: &str = "baseline_anomalies";
+
+/// Path of the snapshot `name` in `dir`. Names are file names, so path
+/// separators and leading dots are refused.
+pub fn snapshot_path(dir: &Path, name: &str) -> BenchResult<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(BenchError::Message(format!(
+            "invalid baseline name {name:?}"
+        )));
+    }
+    Ok(dir.join(format!("{name}.jsonl")))
+}
+
+/// Names of the snapshots in `dir`, sorted.
+pub fn list(dir: &Path) -> BenchResult<Vec<String>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", dir.display())))?;
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()?
+                .strip_suffix(".jsonl")
+                .map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Path of an existing snapshot, for `compare --against`.
+pub fn resolve(dir: &Path, name: &str) -> BenchResult<PathBuf> {
+    let path = snapshot_path(dir, name)?;
+    if path.exists() {
+        return Ok(path);
+    }
+    let known = list(dir)?;
+    Err(BenchError::Message(if known.is_empty() {
+        format!(
+            "no baseline named {name} in {} (create one with `baseline freeze`)",
+            dir.display()
+        )
+    } else {
+        format!(
+            "no baseline named {name} in {}; known: {}",
+            dir.display(),
+            known.join(", ")
+        )
+    }))
+}
+
+/// Path of the baseline `name` in `dir` for `compare --include-anomalies`,
+/// which only a snapshot frozen with anomalies can honour: the others left
+/// them out of their medians.
+pub fn resolve_with_anomalies(dir: &Path, name: &str) -> BenchResult<PathBuf> {
+    let path = resolve(dir, name)?;
+    let records = JsonlWriter::new(&path).read_all()?;
+    if records
+        .iter()
+        .any(|r| !r.metadata.contains_key(ANOMALIES_KEY))
+    {
+        return Err(BenchError::Message(format!(
+            "baseline {name} was frozen without anomalies; freeze it again with \
+             `baseline freeze --include-anomalies --force` to compare against them"
+        )));
+    }
+    Ok(path)
+}
+
+/// Snapshot `history` as the baseline `name` in `dir`: per circuit/backend,
+/// the median of the last `window` successful runs, skipping runs flagged as
+/// anomalies unless `include_anomalies` is set. An existing snapshot is only replaced with `force`, so a
+/// release baseline stays what it was when frozen. Returns the snapshot path
+/// and the number of records in it.
+pub fn freeze(
+    history: &Path,
+    name: &str,
+    dir: &Path,
+    window: usize,
+    force: bool,
+    include_anomalies: bool,
+) -> BenchResult<(PathBuf, usize)> {
+    let path = snapshot_path(dir, name)?;
+    if path.exists() && !force {
+        return Err(BenchError::Message(format!(
+            "baseline {name} already exists at {} (use --force to replace it)",
+            path.display()
+        )));
+    }
+    if !history.exists() {
+        return Err(BenchError::Message(format!(
+            "history not found: {}",
+            history.display()
+        )));
+    }
+    let mut records = JsonlWriter::new(history).read_all()?;
+    records.retain(|r| {
+        r.error.is_none() && !r.is_expected_failure() && (include_anomalies || !r.anomaly)
+    });
+    if records.is_empty() {
+        return Err(BenchError::Message(format!(
+            "no usable records in {}",
+            history.display()
+        )));
+    }
+
+    let mut snapshot = window_baseline(records, window)?;
+    for record in &mut snapshot {
+        // Medians replace the measured values, so the run's signature no
+        // longer applies
+        record.signature = None;
+        record
+            .metadata
+            .insert(BASELINE_KEY.to_string(), name.to_string());
+        if include_anomalies {
+            record
+                .metadata
+                .insert(ANOMALIES_KEY.to_string(), "included".to_string());
+        }
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", dir.display())))?;
+    let tmp = dir.join(format!(".{name}.jsonl.tmp"));
+    let _ = std::fs::remove_file(&tmp);
+    let writer = JsonlWriter::new(&tmp);
+    for record in &snapshot {
+        writer.append(record)?;
+    }
+    std::fs::rename(&tmp, &path)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))?;
+    Ok((path, snapshot.len()))
+}
+
+pub fn run_freeze(
+    jsonl: PathBuf,
+    name: String,
+    baselines_dir: PathBuf,
+    window: usize,
+    force: bool,
+    include_anomalies: bool,
+) -> BenchResult<()> {
+    let (path, count) = freeze(
+        &jsonl,
+        &name,
+        &baselines_dir,
+        window,
+        force,
+        include_anomalies,
+    )?;
+    println!(
+        "baseline: froze {name} ({count} circuit(s), median of last {window} runs) to {}",
+        path.display()
+    );
+    Ok(())
+}
+
+pub fn run_list(baselines_dir: PathBuf) -> BenchResult<()> {
+    let names = list(&baselines_dir)?;
+    if names.is_empty() {
+        println!("no baselines in {}", baselines_dir.display());
+    }
+    for name in names {
+        println!("{name}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, BenchRecord, EnvironmentInfo, RunConfig, TimingStat};
+
+    fn record(circuit: &str, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            circuit.to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r
+    }
+
+    #[test]
+    fn test_freeze_snapshots_medians_and_resolves() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = dir.path().join("history.jsonl");
+        let baselines = dir.path().join("baselines");
+        let mut outlier = record("a", 900.0);
+        outlier.anomaly = true;
+        for r in [
+            record("a", 100.0),
+            record("a", 120.0),
+            outlier,
+            record("a", 110.0),
+            record("b", 50.0),
+        ] {
+            JsonlWriter::new(&history).append(&r).unwrap();
+        }
+
+        let (path, count) = freeze(&history, "v1.2.0", &baselines, 5, false, false).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(resolve(&baselines, "v1.2.0").unwrap(), path);
+        assert_eq!(list(&baselines).unwrap(), ["v1.2.0"]);
+
+        let snapshot = JsonlWriter::new(&path).read_all().unwrap();
+        let a = snapshot.iter().find(|r| r.circuit_name == "a").unwrap();
+        assert_eq!(a.prove_stats.as_ref().unwrap().mean_ms, 110.0);
+        assert_eq!(a.metadata[BASELINE_KEY], "v1.2.0");
+
+        // Frozen baselines are only replaced on request
+        let err = freeze(&history, "v1.2.0", &baselines, 5, false, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert!(freeze(&history, "v1.2.0", &baselines, 5, true, false).is_ok());
+
+        // The outlier counts when anomalies are included
+        let (path, _) = freeze(&history, "with-anomalies", &baselines, 5, false, true).unwrap();
+        let snapshot = JsonlWriter::new(&path).read_all().unwrap();
+        let a = snapshot.iter().find(|r| r.circuit_name == "a").unwrap();
+        assert_eq!(a.prove_stats.as_ref().unwrap().mean_ms, 115.0);
+        assert_eq!(
+            resolve_with_anomalies(&baselines, "with-anomalies").unwrap(),
+            path
+        );
+        let err = resolve_with_anomalies(&baselines, "v1.2.0").unwrap_err();
+        assert!(err.to_string().contains("--include-anomalies"), "{err}");
+    }
+
+    #[test]
+    fn test_resolve_and_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = resolve(dir.path(), "v9").unwrap_err();
+        assert!(err.to_string().contains("baseline freeze"));
+        assert!(snapshot_path(dir.path(), "../v1").is_err());
+        assert!(snapshot_path(dir.path(), ".hidden").is_err());
+        assert!(snapshot_path(dir.path(), "").is_err());
+    }
+}
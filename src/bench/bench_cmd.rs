@@ -4,18 +4,42 @@
 //! It uses `crate::engine::workflow` for the actual proving pipeline.
 
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, Write};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::backend::{BarretenbergBackend, BarretenbergConfig};
-use crate::engine::{NargoToolchain, ProveInputs, full_benchmark};
-use crate::{BenchError, BenchResult};
+use serde_json::{Value, json};
 
-use super::backend::EvmBackend;
-use super::config::{CircuitSpec, list_circuits_in_config, load_bench_config};
+use crate::backend::{
+    Backend, BarretenbergBackend, BarretenbergConfig, EvmBackend, EvmVerifyOutput, GateInfo,
+    load_backend_args,
+};
+use crate::compare_cmd::{DEFAULT_THRESHOLD, compare_records, to_regression_report};
+use crate::core::{
+    BackendInfo, BenchRecord, CacheMode, EnvironmentInfo, RunConfig, RunError, TimingStat,
+    page_cache, parse_record,
+};
+use crate::engine::matrix::{
+    TOOLCHAIN_METADATA_KEY, bb_backend_factory, nargo_flags_factory, nargo_toolchain_factory,
+};
+use crate::engine::sweep::DEFAULT_EXPRESSION_WIDTHS;
+use crate::engine::workflow::VerifyStatus;
+use crate::engine::{
+    BackendOption, BbSource, CompileConfig, EtaEstimator, FullBenchmarkResult, NargoSource,
+    NargoToolchain, Progress, ProveInputs, Toolchain, full_benchmark, parse_versions,
+    run_backend_matrix, run_compile_matrix, run_sweep, run_toolchain_matrix, sweep_points,
+};
+use crate::report::{render_compile_configs, render_markdown, render_sweep, sweep_rows};
+use crate::storage::jsonl::open_lines;
+use crate::storage::{JsonlWriter, Retention, RunWorkDir, WORK_DIR_NAME};
+use crate::{BenchError, BenchResult, file_sha256};
+
+use super::config::{
+    CircuitSpec, list_circuits_in_config, load_bench_config, load_compile_configs,
+};
+use super::hooks::{HookContext, HookEvent, Hooks, load_hooks, warn_ignored_hooks};
 
 const DEFAULT_CONFIG: &str = "bench-config.toml";
 const DEFAULT_JSONL: &str = "out/bench.jsonl";
@@ -67,6 +91,265 @@ fn open_jsonl(path: &PathBuf) -> BenchResult<File> {
     Ok(f)
 }
 
+/// Shape of the JSONL lines `bench run` and `bench run-all` append.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// One BenchRecord per run, as every other command writes
+    #[default]
+    BenchRecord,
+    /// The flat pre-BenchRecord line (`constraints`, `prove_ms`, `evm_gas`,
+    /// `status`, ...), for scripts that still parse it
+    Legacy,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> BenchResult<Self> {
+        match s {
+            "bench-record" | "record" => Ok(OutputFormat::BenchRecord),
+            "legacy" => Ok(OutputFormat::Legacy),
+            other => Err(BenchError::Message(format!(
+                "unknown output format '{other}' (expected bench-record or legacy)"
+            ))),
+        }
+    }
+}
+
+/// JSONL and CSV sinks of `bench run` / `bench run-all`.
+struct BenchOutput {
+    format: OutputFormat,
+    jsonl_path: PathBuf,
+    csv: crate::logging::csv_logger::CsvLogger,
+}
+
+impl BenchOutput {
+    fn new(format: OutputFormat, jsonl_out: Option<PathBuf>, csv_out: Option<PathBuf>) -> Self {
+        BenchOutput {
+            format,
+            jsonl_path: jsonl_out.unwrap_or_else(|| PathBuf::from(DEFAULT_JSONL)),
+            csv: crate::logging::csv_logger::CsvLogger::new(
+                csv_out.unwrap_or_else(|| PathBuf::from(DEFAULT_CSV)),
+            ),
+        }
+    }
+
+    /// Where run work directories go unless `--work-dir` is given: next to
+    /// the JSONL, like failure logs.
+    fn work_root(&self) -> PathBuf {
+        self.jsonl_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .join(WORK_DIR_NAME)
+    }
+
+    fn append_legacy(&self, line: &serde_json::Value) -> BenchResult<()> {
+        let mut jsonl = open_jsonl(&self.jsonl_path)?;
+        writeln!(jsonl, "{line}").map_err(|e| {
+            BenchError::Message(format!(
+                "failed to write {}: {e}",
+                self.jsonl_path.display()
+            ))
+        })
+    }
+
+    /// Write a barretenberg run of `spec`, returning the JSONL line written.
+    fn barretenberg(
+        &mut self,
+        spec: &CircuitSpec,
+        timestamp: &str,
+        result: FullBenchmarkResult,
+        warmup: usize,
+    ) -> BenchResult<Value> {
+        // Compile is implicit in artifact loading
+        let compile_ms = 0u128;
+        let mut record = result.record;
+        let prove_stats = record.prove_stats.as_ref();
+        let prove_ms_avg = prove_stats.map(|s| s.mean_ms).unwrap_or(0.0);
+        let memory_bytes = record.peak_rss_mb.map(|mb| (mb * 1024.0 * 1024.0) as u64);
+
+        let line = match self.format {
+            OutputFormat::Legacy => {
+                let line = json!({
+                "timestamp": timestamp,
+                "circuit": spec.name,
+                "params": spec.params,
+                "backend": "barretenberg",
+                "compile_ms": compile_ms,
+                "constraints": result.constraints,
+                "acir_opcodes": result.acir_opcodes,
+                "acir_bytes": record.artifact_size_bytes,
+                "prove_ms": prove_ms_avg,
+                "memory_bytes": memory_bytes,
+                "proof_size": record.proof_size_bytes,
+                "evm_gas": serde_json::Value::Null,
+                "status": result.verify_success,
+                "iterations": {
+                    "iterations": prove_stats.map(|s| s.iterations).unwrap_or(0),
+                    "warmup": warmup,
+                    "avg_ms": prove_stats.map(|s| s.mean_ms),
+                    "min_ms": prove_stats.map(|s| s.min_ms),
+                    "max_ms": prove_stats.map(|s| s.max_ms),
+                    "stddev_ms": prove_stats.and_then(|s| s.stddev_ms)
+                }
+                });
+                self.append_legacy(&line)?;
+                line
+            }
+            OutputFormat::BenchRecord => {
+                record.params = spec.params;
+                record.config.env = spec.env.clone();
+                if let VerifyStatus::Failed(message) = &result.verify_status {
+                    record.error = Some(RunError {
+                        message: message.clone(),
+                        log_path: None,
+                    });
+                }
+                JsonlWriter::new(&self.jsonl_path).append(&record)?;
+                serde_json::to_value(&record).unwrap_or_default()
+            }
+        };
+
+        self.csv.append_row(
+            timestamp,
+            &spec.name,
+            spec.params,
+            "barretenberg",
+            Some(compile_ms),
+            Some(prove_ms_avg as u128),
+            memory_bytes.map(|b| b / (1024 * 1024)),
+            result.constraints,
+            result.acir_opcodes,
+            record.artifact_size_bytes,
+            record.proof_size_bytes,
+            None,
+            if result.verify_success { "ok" } else { "fail" },
+        )?;
+        Ok(line)
+    }
+
+    /// Write an EVM verification of `spec` by `evm`, returning the JSONL line
+    /// written. Its BenchRecord keeps the gas and outcome in metadata, like
+    /// records upgraded from legacy lines, and the forge run in `verify_stats`.
+    fn evm(
+        &mut self,
+        spec: &CircuitSpec,
+        timestamp: &str,
+        evm: &EvmBackend,
+        verify: &EvmVerifyOutput,
+    ) -> BenchResult<Value> {
+        let line = match self.format {
+            OutputFormat::Legacy => {
+                let line = json!({
+                "timestamp": timestamp,
+                "circuit": spec.name,
+                "params": spec.params,
+                "backend": "evm",
+                "compile_ms": serde_json::Value::Null,
+                "constraints": serde_json::Value::Null,
+                "acir_opcodes": serde_json::Value::Null,
+                "prove_ms": serde_json::Value::Null,
+                "memory_bytes": serde_json::Value::Null,
+                "proof_size": serde_json::Value::Null,
+                "evm_gas": verify.gas_used,
+                "status": verify.success,
+                });
+                self.append_legacy(&line)?;
+                line
+            }
+            OutputFormat::BenchRecord => {
+                let mut record = BenchRecord::new(
+                    spec.name.clone(),
+                    EnvironmentInfo::detect(),
+                    BackendInfo {
+                        name: evm.name().to_string(),
+                        version: evm.version(),
+                        variant: None,
+                    },
+                    RunConfig::default(),
+                );
+                record.params = spec.params;
+                record.verify_stats = verify
+                    .verify_time_ms
+                    .map(|ms| TimingStat::from_samples(&[ms as f64]));
+                record.circuit_path = Some(spec.path.to_string_lossy().to_string());
+                record
+                    .metadata
+                    .insert("status".to_string(), verify.success.to_string());
+                if let Some(gas) = verify.gas_used {
+                    record
+                        .metadata
+                        .insert("evm_gas".to_string(), gas.to_string());
+                }
+                if !verify.success {
+                    record.error = Some(RunError {
+                        message: "EVM verification failed".to_string(),
+                        log_path: None,
+                    });
+                }
+                JsonlWriter::new(&self.jsonl_path).append(&record)?;
+                serde_json::to_value(&record).unwrap_or_default()
+            }
+        };
+
+        self.csv.append_row(
+            timestamp,
+            &spec.name,
+            spec.params,
+            "evm",
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            verify.gas_used,
+            if verify.success { "ok" } else { "fail" },
+        )?;
+        Ok(line)
+    }
+
+    /// Hook context of a run of `spec` on `backend`.
+    fn hook_context(&self, spec: &CircuitSpec, backend: &str) -> HookContext {
+        HookContext {
+            circuit: spec.name.clone(),
+            params: spec.params,
+            backend: backend.to_string(),
+            jsonl_path: Some(self.jsonl_path.clone()),
+            ..HookContext::default()
+        }
+    }
+}
+
+/// Failure recorded in a written JSONL line: the record's error, or a
+/// legacy line's failed status.
+fn line_error(line: &Value) -> Option<String> {
+    if let Some(error) = line.get("error") {
+        let message = error.get("message").and_then(Value::as_str);
+        return Some(message.unwrap_or("run failed").to_string());
+    }
+    (line.get("status") == Some(&Value::Bool(false))).then(|| "verification failed".to_string())
+}
+
+/// Run one circuit between its `[hooks]`: `pre_run` first (its failure
+/// fails the run), then `on_failure` for a failed run and `post_run`, with
+/// the line `run` wrote.
+fn with_hooks(
+    hooks: &Hooks,
+    mut ctx: HookContext,
+    run: impl FnOnce() -> BenchResult<Value>,
+) -> BenchResult<()> {
+    let outcome = hooks.run(HookEvent::PreRun, &ctx).and_then(|_| run());
+    match &outcome {
+        Ok(line) => {
+            ctx.error = line_error(line);
+            ctx.record = Some(line.clone());
+        }
+        Err(e) => ctx.error = Some(e.to_string()),
+    }
+    hooks.finish(&ctx);
+    outcome.map(|_| ())
+}
+
 fn candidate_prover_toml_paths(path: &PathBuf, params: Option<u64>) -> Vec<PathBuf> {
     let mut candidates = Vec::new();
 
@@ -102,156 +385,608 @@ pub fn run(
     jsonl_out: Option<PathBuf>,
     iterations: Option<usize>,
     warmup: Option<usize>,
+    key_cache: Option<PathBuf>,
+    cold_keys: bool,
+    output: OutputFormat,
+    work_dir: Option<PathBuf>,
+    retention: Retention,
+    probe: bool,
+    cold_cache: bool,
 ) -> BenchResult<()> {
     let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    if cold_cache {
+        page_cache::check_supported()?;
+    }
     let specs = load_bench_config(&cfg_path)?;
     let Some(spec) = find_circuit(&specs, &circuit_name, params) else {
         return Err(BenchError::Message("circuit not found".into()));
     };
     let backend_s = backend_name.unwrap_or_else(|| "bb".to_string());
-    let mut csv_logger = crate::logging::csv_logger::CsvLogger::new(
-        csv_out.unwrap_or_else(|| PathBuf::from(DEFAULT_CSV)),
-    );
-    let jsonl_path = jsonl_out.unwrap_or_else(|| PathBuf::from(DEFAULT_JSONL));
-    let mut jsonl = open_jsonl(&jsonl_path)?;
-    let timestamp = now_string();
+    let hooks = load_hooks(&cfg_path)?;
+    let mut out = BenchOutput::new(output, jsonl_out, csv_out);
     let iter_n = iterations.unwrap_or(1);
     let warmup_n = warmup.unwrap_or(0);
 
     match backend_s.as_str() {
         "bb" | "barretenberg" => {
-            // Create toolchain and backend
-            let toolchain = NargoToolchain::new();
-            let bb_config =
-                BarretenbergConfig::new("bb").with_timeout(Duration::from_secs(24 * 60 * 60));
-            let backend = BarretenbergBackend::new(bb_config);
-
-            // Prepare inputs
-            let prover_toml = find_prover_toml(&spec);
-            let mut inputs = ProveInputs::new(&spec.path, &spec.name)
-                .with_timeout(Duration::from_secs(24 * 60 * 60));
-            if let Some(pt) = prover_toml {
-                inputs = inputs.with_prover_toml(pt);
-            }
+            with_hooks(&hooks, out.hook_context(&spec, "barretenberg"), || {
+                let work =
+                    RunWorkDir::create(work_dir.unwrap_or_else(|| out.work_root()), retention)?;
+                let mut circuit_dir = work.circuit(&spec.name, spec.params)?;
 
-            // Run full benchmark workflow
-            let result = full_benchmark(&toolchain, &backend, &inputs, warmup_n, iter_n)?;
-
-            // Extract metrics for legacy JSONL format
-            let compile_ms = 0u128; // Compile is implicit in artifact loading
-            let constraints = result.constraints;
-            let acir_opcodes = result.acir_opcodes;
-            let acir_bytes = result.record.artifact_size_bytes;
-            let prove_ms_avg = result
-                .record
-                .prove_stats
-                .as_ref()
-                .map(|s| s.mean_ms)
-                .unwrap_or(0.0);
-            let memory_bytes = result
-                .record
-                .peak_rss_mb
-                .map(|mb| (mb * 1024.0 * 1024.0) as u64);
-            let proof_size = result.record.proof_size_bytes;
-            let verify_success = result.verify_success;
-
-            // Get iteration stats
-            let prove_stats = result.record.prove_stats.as_ref();
-            let iterations_obj = json!({
-                "iterations": prove_stats.map(|s| s.iterations).unwrap_or(0),
-                "warmup": warmup_n,
-                "avg_ms": prove_stats.map(|s| s.mean_ms),
-                "min_ms": prove_stats.map(|s| s.min_ms),
-                "max_ms": prove_stats.map(|s| s.max_ms),
-                "stddev_ms": prove_stats.and_then(|s| s.stddev_ms)
-            });
-
-            // Write legacy JSONL format
-            let rec = json!({
-                "timestamp": timestamp,
-                "circuit": spec.name,
-                "params": spec.params,
-                "backend": "barretenberg",
-                "compile_ms": compile_ms,
-                "constraints": constraints,
-                "acir_opcodes": acir_opcodes,
-                "acir_bytes": acir_bytes,
-                "prove_ms": prove_ms_avg,
-                "memory_bytes": memory_bytes,
-                "proof_size": proof_size,
-                "evm_gas": serde_json::Value::Null,
-                "status": verify_success,
-                "iterations": iterations_obj
-            });
-            let _ = writeln!(jsonl, "{}", serde_json::to_string(&rec).unwrap());
-
-            // Write CSV
-            csv_logger.append_row(
-                &timestamp,
-                &spec.name,
-                spec.params,
-                "barretenberg",
-                Some(compile_ms),
-                Some(prove_ms_avg as u128),
-                memory_bytes.map(|b| b / (1024 * 1024)),
-                constraints,
-                acir_opcodes,
-                acir_bytes,
-                proof_size,
-                None,
-                if verify_success { "ok" } else { "fail" },
-            )?;
+                // Create toolchain and backend
+                let toolchain = NargoToolchain::new()
+                    .with_work_dir(circuit_dir.path())
+                    .with_env(spec.env.clone());
+                let mut bb_config = BarretenbergConfig::new("bb")
+                    .with_timeout(Duration::from_secs(24 * 60 * 60))
+                    .with_cold_keys(cold_keys)
+                    .with_work_dir(circuit_dir.path())
+                    .with_env(spec.env.clone());
+                if let Some(dir) = &key_cache {
+                    bb_config = bb_config.with_key_cache(dir);
+                }
+                let backend = BarretenbergBackend::new(bb_config);
 
-            println!(
-                "bench run: {} backend=barretenberg prove_ms_avg={:.2} verify_ok={}",
-                spec.name, prove_ms_avg, verify_success
-            );
+                // Prepare inputs
+                let prover_toml = find_prover_toml(&spec);
+                let mut inputs = ProveInputs::new(&spec.path, &spec.name)
+                    .with_timeout(Duration::from_secs(24 * 60 * 60))
+                    .with_probe(probe)
+                    .with_cache_mode(CacheMode::from_cold(cold_cache));
+                if let Some(pt) = prover_toml {
+                    inputs = inputs.with_prover_toml(pt);
+                }
+
+                // Run full benchmark workflow
+                let result = full_benchmark(&toolchain, &backend, &inputs, warmup_n, iter_n)?;
+                let prove_ms_avg = result
+                    .record
+                    .prove_stats
+                    .as_ref()
+                    .map(|s| s.mean_ms)
+                    .unwrap_or(0.0);
+                let verify_success = result.verify_success;
+                if let Some(ms) = result.record.probe_ms {
+                    println!("bench run: {} backend probe ok ({ms:.0} ms)", spec.name);
+                }
+                if !matches!(result.verify_status, VerifyStatus::Failed(_)) {
+                    circuit_dir.succeed();
+                }
+                let line = out.barretenberg(&spec, &now_string(), result, warmup_n)?;
+
+                println!(
+                    "bench run: {} backend=barretenberg prove_ms_avg={:.2} verify_ok={}",
+                    spec.name, prove_ms_avg, verify_success
+                );
+                Ok(line)
+            })
         }
-        "evm" => {
+        "evm" => with_hooks(&hooks, out.hook_context(&spec, "evm"), || {
             let evm = EvmBackend::new(&spec.path);
-            let verify = evm.verify()?;
-
-            // JSONL
-            let rec = json!({
-                "timestamp": timestamp,
-                "circuit": spec.name,
-                "params": spec.params,
-                "backend": "evm",
-                "compile_ms": serde_json::Value::Null,
-                "constraints": serde_json::Value::Null,
-                "acir_opcodes": serde_json::Value::Null,
-                "prove_ms": serde_json::Value::Null,
-                "memory_bytes": serde_json::Value::Null,
-                "proof_size": serde_json::Value::Null,
-                "evm_gas": verify.gas_used,
-                "status": verify.success,
-            });
-            let _ = writeln!(jsonl, "{}", serde_json::to_string(&rec).unwrap());
-
-            // CSV
-            csv_logger.append_row(
-                &timestamp,
-                &spec.name,
-                spec.params,
-                "evm",
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                None,
-                verify.gas_used,
-                if verify.success { "ok" } else { "fail" },
-            )?;
+            let verify = evm.measure()?;
+            let line = out.evm(&spec, &now_string(), &evm, &verify)?;
             println!(
                 "bench run: {} backend=evm gas={:?}",
                 spec.name, verify.gas_used
             );
+            Ok(line)
+        }),
+        other => Err(BenchError::Message(format!("unknown backend '{}'", other))),
+    }
+}
+
+/// Run benchmark for a single circuit under several nargo versions.
+///
+/// Each version recompiles the circuit's project (the directory above the
+/// artifact's `target/`) and appends one BenchRecord tagged with the
+/// toolchain to the JSONL output and a row to the CSV. Nargo binaries come
+/// from `nargo_dir` (per-version installs) or, without it, from `noirup`
+/// installing into a temporary directory for this run. Only the bb backend
+/// is supported.
+pub fn run_matrix(
+    circuit_name: String,
+    backend_name: Option<String>,
+    params: Option<u64>,
+    config: Option<PathBuf>,
+    csv_out: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    nargo_versions: String,
+    nargo_dir: Option<PathBuf>,
+) -> BenchResult<()> {
+    let versions = parse_versions(&nargo_versions)?;
+    if let Some(other) = backend_name
+        .as_deref()
+        .filter(|b| !matches!(*b, "bb" | "barretenberg"))
+    {
+        return Err(BenchError::Message(format!(
+            "--nargo-versions only supports the bb backend, not '{other}'"
+        )));
+    }
+    let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let specs = load_bench_config(&cfg_path)?;
+    let Some(spec) = find_circuit(&specs, &circuit_name, params) else {
+        return Err(BenchError::Message("circuit not found".into()));
+    };
+    let Some(project_dir) = spec.path.parent().and_then(|dir| dir.parent()) else {
+        return Err(BenchError::Message(format!(
+            "cannot find project directory for artifact {}",
+            spec.path.display()
+        )));
+    };
+
+    let install_dir = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+    let source = match nargo_dir {
+        Some(dir) => NargoSource::Dir(dir),
+        None => NargoSource::Noirup(install_dir.path().to_path_buf()),
+    };
+    let make_toolchain = nargo_toolchain_factory(source);
+    let bb_config = BarretenbergConfig::new("bb").with_timeout(Duration::from_secs(24 * 60 * 60));
+    let backend = BarretenbergBackend::new(bb_config);
+
+    let mut inputs =
+        ProveInputs::new(&spec.path, &spec.name).with_timeout(Duration::from_secs(24 * 60 * 60));
+    if let Some(pt) = find_prover_toml(&spec) {
+        inputs = inputs.with_prover_toml(pt);
+    }
+    let work_dir = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+
+    let entries = run_toolchain_matrix(
+        &versions,
+        &make_toolchain,
+        &backend,
+        project_dir,
+        &inputs,
+        work_dir.path(),
+        warmup.unwrap_or(0),
+        iterations.unwrap_or(1),
+    );
+
+    let mut out = BenchOutput::new(OutputFormat::BenchRecord, jsonl_out, csv_out);
+    let mut failed = 0;
+    for entry in entries {
+        match entry.result {
+            Ok(result) => {
+                println!(
+                    "bench matrix: {} {} (nargo {}) gates={:?} prove_ms_avg={:.2} verify_ok={}",
+                    spec.name,
+                    result
+                        .record
+                        .metadata
+                        .get(TOOLCHAIN_METADATA_KEY)
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                    result.record.env.nargo_version.as_deref().unwrap_or("?"),
+                    result.constraints,
+                    result
+                        .record
+                        .prove_stats
+                        .as_ref()
+                        .map(|s| s.mean_ms)
+                        .unwrap_or(0.0),
+                    result.verify_success
+                );
+                out.barretenberg(&spec, &now_string(), result, warmup.unwrap_or(0))?;
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!(
+                    "bench matrix: {} nargo@{} failed: {e}",
+                    spec.name, entry.version
+                );
+            }
         }
-        other => {
-            return Err(BenchError::Message(format!("unknown backend '{}'", other)));
+    }
+
+    if failed == versions.len() {
+        return Err(BenchError::Message(format!(
+            "all {} nargo versions failed for '{}'",
+            failed, spec.name
+        )));
+    }
+    Ok(())
+}
+
+/// Resolve `--compile-config` values: `NAME=FLAGS`, or a bare name of a
+/// `[[compile_config]]` entry in the bench config.
+fn resolve_compile_configs(
+    values: &[String],
+    defined: &[CompileConfig],
+) -> BenchResult<Vec<CompileConfig>> {
+    let mut configs: Vec<CompileConfig> = Vec::new();
+    for value in values {
+        let config = if value.contains('=') {
+            CompileConfig::parse(value)?
+        } else {
+            defined
+                .iter()
+                .find(|c| c.name == *value)
+                .cloned()
+                .ok_or_else(|| {
+                    BenchError::Message(format!(
+                        "no [[compile_config]] named '{value}' in the bench config"
+                    ))
+                })?
+        };
+        if configs.iter().any(|c| c.name == config.name) {
+            return Err(BenchError::Message(format!(
+                "compile config '{}' given twice",
+                config.name
+            )));
         }
+        configs.push(config);
+    }
+    Ok(configs)
+}
+
+/// Run a circuit under several sets of `nargo compile` flags and print a
+/// config-vs-config comparison.
+///
+/// Each config recompiles the circuit's project with the same nargo and
+/// appends one BenchRecord, named `<circuit>@<config>`, tagged
+/// `compile_config=<name>` and carrying the flags in `config.compile_flags`,
+/// to the JSONL output. bb runs with the config's `[backend.args]`. Without
+/// `--compile-config` values every `[[compile_config]]` in the bench config
+/// runs. The first config is the reference in the printed section.
+pub fn run_compile_config_matrix(
+    circuit_name: String,
+    backend_name: Option<String>,
+    params: Option<u64>,
+    config: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    compile_configs: Vec<String>,
+) -> BenchResult<()> {
+    if let Some(other) = backend_name
+        .as_deref()
+        .filter(|b| !matches!(*b, "bb" | "barretenberg"))
+    {
+        return Err(BenchError::Message(format!(
+            "--compile-config only supports the bb backend, not '{other}'"
+        )));
+    }
+    let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let specs = load_bench_config(&cfg_path)?;
+    warn_ignored_hooks(&cfg_path, "bench run --compile-config");
+    let defined = load_compile_configs(&cfg_path)?;
+    let configs = if compile_configs.is_empty() {
+        defined
+    } else {
+        resolve_compile_configs(&compile_configs, &defined)?
+    };
+    if configs.len() < 2 {
+        return Err(BenchError::Message(
+            "need at least two compile configs to compare".into(),
+        ));
+    }
+    let Some(spec) = find_circuit(&specs, &circuit_name, params) else {
+        return Err(BenchError::Message("circuit not found".into()));
+    };
+    let Some(project_dir) = spec.path.parent().and_then(|dir| dir.parent()) else {
+        return Err(BenchError::Message(format!(
+            "cannot find project directory for artifact {}",
+            spec.path.display()
+        )));
+    };
+
+    let make_toolchain = nargo_flags_factory(PathBuf::from("nargo"));
+    let bb_config = BarretenbergConfig::new("bb")
+        .with_timeout(Duration::from_secs(24 * 60 * 60))
+        .with_args(load_backend_args(&cfg_path)?);
+    let backend = BarretenbergBackend::new(bb_config);
+    backend.warn_on_args();
+
+    let mut inputs =
+        ProveInputs::new(&spec.path, &spec.name).with_timeout(Duration::from_secs(24 * 60 * 60));
+    if let Some(pt) = find_prover_toml(&spec) {
+        inputs = inputs.with_prover_toml(pt);
+    }
+    let work_dir = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+
+    let entries = run_compile_matrix(
+        &configs,
+        &make_toolchain,
+        &backend,
+        project_dir,
+        &inputs,
+        work_dir.path(),
+        warmup.unwrap_or(0),
+        iterations.unwrap_or(1),
+    );
+
+    let writer = JsonlWriter::new(jsonl_out.unwrap_or_else(|| PathBuf::from(DEFAULT_JSONL)));
+    let mut records = Vec::new();
+    for entry in entries {
+        match entry.result {
+            Ok(mut result) => {
+                result.record.params = spec.params;
+                writer.append(&result.record)?;
+                println!(
+                    "bench matrix: {} {} gates={:?} prove_ms_avg={:.2} verify_ok={}",
+                    spec.name,
+                    entry.version,
+                    result.constraints,
+                    result
+                        .record
+                        .prove_stats
+                        .as_ref()
+                        .map(|s| s.mean_ms)
+                        .unwrap_or(0.0),
+                    result.verify_success
+                );
+                records.push(result.record);
+            }
+            Err(e) => {
+                eprintln!("bench matrix: {} {} failed: {e}", spec.name, entry.version);
+            }
+        }
+    }
+
+    if records.is_empty() {
+        return Err(BenchError::Message(format!(
+            "all {} compile configs failed for '{}'",
+            configs.len(),
+            spec.name
+        )));
+    }
+    println!();
+    print!("{}", render_compile_configs(&records));
+    Ok(())
+}
+
+/// Sweep a circuit across expression widths and bb option sets and print a
+/// tradeoff table.
+///
+/// Every combination recompiles the project with `--expression-width <n>`
+/// (0 is unbounded) and proves with bb plus the option set's flags (after the
+/// config's `[backend.args]`). Without widths or options the default widths
+/// are swept. Each point appends one BenchRecord tagged `sweep_point=<name>`
+/// to the JSONL output; `json_out` gets the table rows as JSON.
+pub fn run_sweep_cmd(
+    circuit_name: String,
+    params: Option<u64>,
+    config: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    expression_widths: Vec<u32>,
+    bb_options: Vec<String>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let specs = load_bench_config(&cfg_path)?;
+    let options = bb_options
+        .iter()
+        .map(|s| BackendOption::parse(s))
+        .collect::<BenchResult<Vec<_>>>()?;
+    let widths = if expression_widths.is_empty() && options.is_empty() {
+        DEFAULT_EXPRESSION_WIDTHS.to_vec()
+    } else {
+        expression_widths
+    };
+    let points = sweep_points(&widths, &options)?;
+    let Some(spec) = find_circuit(&specs, &circuit_name, params) else {
+        return Err(BenchError::Message("circuit not found".into()));
+    };
+    let Some(project_dir) = spec.path.parent().and_then(|dir| dir.parent()) else {
+        return Err(BenchError::Message(format!(
+            "cannot find project directory for artifact {}",
+            spec.path.display()
+        )));
+    };
+
+    let base_args = load_backend_args(&cfg_path)?;
+    let make_toolchain = |flags: &[String]| -> BenchResult<Box<dyn Toolchain>> {
+        Ok(Box::new(
+            NargoToolchain::new().with_compile_args(flags.to_vec()),
+        ))
+    };
+    let make_backend = |args: &[String]| -> BenchResult<Box<dyn Backend>> {
+        let mut all_args = base_args.clone();
+        all_args.extend_from_slice(args);
+        let bb_config = BarretenbergConfig::new("bb")
+            .with_timeout(Duration::from_secs(24 * 60 * 60))
+            .with_args(all_args);
+        let backend = BarretenbergBackend::new(bb_config);
+        backend.warn_on_args();
+        Ok(Box::new(backend))
+    };
+
+    let mut inputs =
+        ProveInputs::new(&spec.path, &spec.name).with_timeout(Duration::from_secs(24 * 60 * 60));
+    if let Some(pt) = find_prover_toml(&spec) {
+        inputs = inputs.with_prover_toml(pt);
+    }
+    let work_dir = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+
+    let entries = run_sweep(
+        &points,
+        &make_toolchain,
+        &make_backend,
+        project_dir,
+        &inputs,
+        work_dir.path(),
+        warmup.unwrap_or(0),
+        iterations.unwrap_or(1),
+    );
+
+    let writer = JsonlWriter::new(jsonl_out.unwrap_or_else(|| PathBuf::from(DEFAULT_JSONL)));
+    let mut records = Vec::new();
+    for entry in entries {
+        match entry.result {
+            Ok(mut result) => {
+                result.record.params = spec.params;
+                writer.append(&result.record)?;
+                println!(
+                    "bench sweep: {} {} gates={:?} proof_size={:?} prove_ms_avg={:.2}",
+                    spec.name,
+                    entry.version,
+                    result.constraints,
+                    result.record.proof_size_bytes,
+                    result
+                        .record
+                        .prove_stats
+                        .as_ref()
+                        .map(|s| s.mean_ms)
+                        .unwrap_or(0.0),
+                );
+                records.push(result.record);
+            }
+            Err(e) => {
+                eprintln!("bench sweep: {} {} failed: {e}", spec.name, entry.version);
+            }
+        }
+    }
+    if records.is_empty() {
+        return Err(BenchError::Message(format!(
+            "all {} sweep points failed for '{}'",
+            points.len(),
+            spec.name
+        )));
+    }
+
+    let rows = sweep_rows(&records);
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        std::fs::write(&json, serde_json::to_vec_pretty(&rows).unwrap())
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", json.display())))?;
+    }
+    println!();
+    print!("{}", render_sweep(&spec.name, &rows));
+    Ok(())
+}
+
+/// Run circuits under several bb versions and report version-vs-version deltas.
+///
+/// With `circuit_name` set only that circuit runs, otherwise every circuit in
+/// the config. Each (circuit, version) run appends one BenchRecord tagged
+/// `backend_release=bb@<version>` to the JSONL output. Afterwards each version
+/// is compared against the one listed before it and a markdown regression
+/// report is printed; the command fails if any comparison regresses past
+/// `threshold` percent. Bb binaries come from `bb_dir` (per-version installs)
+/// or, without it, from `bbup` installing into a temporary directory for this
+/// run.
+pub fn run_bb_matrix(
+    circuit_name: Option<String>,
+    params: Option<u64>,
+    config: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    bb_versions: String,
+    bb_dir: Option<PathBuf>,
+    threshold: Option<f64>,
+) -> BenchResult<()> {
+    let versions = parse_versions(&bb_versions)?;
+    let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let all_specs = load_bench_config(&cfg_path)?;
+    let specs = match circuit_name {
+        Some(name) => match find_circuit(&all_specs, &name, params) {
+            Some(spec) => vec![spec],
+            None => return Err(BenchError::Message("circuit not found".into())),
+        },
+        None => all_specs,
+    };
+
+    let install_dir = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+    let source = match bb_dir {
+        Some(dir) => BbSource::Dir(dir),
+        None => BbSource::Bbup(install_dir.path().to_path_buf()),
+    };
+    let make_backend = bb_backend_factory(source, Duration::from_secs(24 * 60 * 60));
+    let toolchain = NargoToolchain::new();
+    let writer = JsonlWriter::new(jsonl_out.unwrap_or_else(|| PathBuf::from(DEFAULT_JSONL)));
+
+    // Records per version, in the order versions were given
+    let mut by_version: Vec<Vec<BenchRecord>> = vec![Vec::new(); versions.len()];
+    let mut failed = 0;
+    for spec in &specs {
+        let mut inputs = ProveInputs::new(&spec.path, &spec.name)
+            .with_timeout(Duration::from_secs(24 * 60 * 60));
+        if let Some(pt) = find_prover_toml(spec) {
+            inputs = inputs.with_prover_toml(pt);
+        }
+        let entries = run_backend_matrix(
+            &versions,
+            &make_backend,
+            &toolchain,
+            &inputs,
+            warmup.unwrap_or(0),
+            iterations.unwrap_or(1),
+        );
+        for (idx, entry) in entries.into_iter().enumerate() {
+            match entry.result {
+                Ok(mut result) => {
+                    result.record.params = spec.params;
+                    writer.append(&result.record)?;
+                    println!(
+                        "bench matrix: {} bb@{} prove_ms_avg={:.2} proof_size={:?} verify_ok={}",
+                        spec.name,
+                        entry.version,
+                        result
+                            .record
+                            .prove_stats
+                            .as_ref()
+                            .map(|s| s.mean_ms)
+                            .unwrap_or(0.0),
+                        result.record.proof_size_bytes,
+                        result.verify_success
+                    );
+                    by_version[idx].push(result.record);
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!(
+                        "bench matrix: {} bb@{} failed: {e}",
+                        spec.name, entry.version
+                    );
+                }
+            }
+        }
+    }
+
+    if failed == versions.len() * specs.len() {
+        return Err(BenchError::Message(format!(
+            "all {} bb version runs failed",
+            failed
+        )));
+    }
+
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    let mut total_regressions = 0;
+    for (pair, records) in versions.windows(2).zip(by_version.windows(2)) {
+        if records[0].is_empty() || records[1].is_empty() {
+            continue;
+        }
+        let result = compare_records(
+            records[0].clone(),
+            records[1].clone(),
+            &format!("bb@{}", pair[0]),
+            &format!("bb@{}", pair[1]),
+            threshold,
+            &BTreeMap::new(),
+            &BTreeMap::new(),
+            None,
+        )?;
+        total_regressions += result.total_regressions;
+        println!();
+        println!("{}", render_markdown(&to_regression_report(&result)));
+    }
+
+    if total_regressions > 0 {
+        return Err(BenchError::Message(format!(
+            "{total_regressions} regression(s) between bb versions (threshold {threshold}%)"
+        )));
     }
     Ok(())
 }
@@ -264,129 +999,138 @@ pub fn run_all(
     jsonl_out: Option<PathBuf>,
     iterations: Option<usize>,
     warmup: Option<usize>,
+    key_cache: Option<PathBuf>,
+    cold_keys: bool,
+    output: OutputFormat,
+    work_dir: Option<PathBuf>,
+    retention: Retention,
+    probe: bool,
+    cold_cache: bool,
+    progress_out: Option<PathBuf>,
 ) -> BenchResult<()> {
     let cfg_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    if cold_cache {
+        page_cache::check_supported()?;
+    }
     let specs = load_bench_config(&cfg_path)?;
     let backend_s = backend_name.unwrap_or_else(|| "bb".to_string());
-    let mut csv_logger = crate::logging::csv_logger::CsvLogger::new(
-        csv_out.unwrap_or_else(|| PathBuf::from(DEFAULT_CSV)),
-    );
-    let jsonl_path = jsonl_out.unwrap_or_else(|| PathBuf::from(DEFAULT_JSONL));
-    let mut jsonl = open_jsonl(&jsonl_path)?;
+    let hooks = load_hooks(&cfg_path)?;
+    let mut out = BenchOutput::new(output, jsonl_out, csv_out);
     let iter_n = iterations.unwrap_or(1);
     let warmup_n = warmup.unwrap_or(0);
 
-    // Create shared toolchain and backend for barretenberg
-    let toolchain = NargoToolchain::new();
-    let bb_config = BarretenbergConfig::new("bb").with_timeout(Duration::from_secs(24 * 60 * 60));
-    let backend = BarretenbergBackend::new(bb_config);
+    // Create shared toolchain and backend for barretenberg; output of failed
+    // commands is kept next to the JSONL
+    let log_dir = out
+        .jsonl_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join("logs");
+    let mut bb_config = BarretenbergConfig::new("bb")
+        .with_timeout(Duration::from_secs(24 * 60 * 60))
+        .with_args(load_backend_args(&cfg_path)?)
+        .with_log_dir(&log_dir)
+        .with_cold_keys(cold_keys);
+    if let Some(dir) = &key_cache {
+        bb_config = bb_config.with_key_cache(dir);
+    }
+    BarretenbergBackend::new(bb_config.clone()).warn_on_args();
+    let work = RunWorkDir::create(work_dir.unwrap_or_else(|| out.work_root()), retention)?;
+
+    // Params variants of a circuit usually share one artifact; analyse its
+    // gates once per run (keyed by artifact hash) and reuse the result
+    let mut gate_memo: HashMap<String, (GateInfo, f64)> = HashMap::new();
+    let mut gates_reused = 0usize;
+    let mut gates_saved_ms = 0.0;
+
+    // Earlier runs in the output JSONL give the ETA
+    let history = if out.jsonl_path.exists() {
+        JsonlWriter::new(&out.jsonl_path)
+            .read_all()
+            .unwrap_or_else(|e| {
+                eprintln!("warning: no ETA, failed to read history: {e}");
+                Vec::new()
+            })
+    } else {
+        Vec::new()
+    };
+    let mut progress = Progress::new(
+        "bench run-all",
+        specs.iter().map(|s| (s.name.clone(), s.params)).collect(),
+        EtaEstimator::from_history(&history),
+    );
+    if let Some(path) = progress_out {
+        progress = progress.with_events(path);
+    }
+    progress.start();
 
     for spec in specs {
         let timestamp = now_string();
         match backend_s.as_str() {
             "bb" | "barretenberg" => {
-                // Prepare inputs
-                let prover_toml = find_prover_toml(&spec);
-                let mut inputs = ProveInputs::new(&spec.path, &spec.name)
-                    .with_timeout(Duration::from_secs(24 * 60 * 60));
-                if let Some(pt) = prover_toml {
-                    inputs = inputs.with_prover_toml(pt);
-                }
-
-                // Run full benchmark workflow
-                let result = full_benchmark(&toolchain, &backend, &inputs, warmup_n, iter_n)?;
+                with_hooks(&hooks, out.hook_context(&spec, "barretenberg"), || {
+                    // Each circuit gets its own toolchain and backend writing to
+                    // its work directory
+                    let mut circuit_dir = work.circuit(&spec.name, spec.params)?;
+                    let toolchain = NargoToolchain::new()
+                        .with_log_dir(&log_dir)
+                        .with_work_dir(circuit_dir.path())
+                        .with_env(spec.env.clone());
+                    let backend = BarretenbergBackend::new(
+                        bb_config
+                            .clone()
+                            .with_work_dir(circuit_dir.path())
+                            .with_env(spec.env.clone()),
+                    );
 
-                // Extract metrics for legacy JSONL format
-                let compile_ms = 0u128;
-                let constraints = result.constraints;
-                let acir_opcodes = result.acir_opcodes;
-                let acir_bytes = result.record.artifact_size_bytes;
-                let prove_ms_avg = result
-                    .record
-                    .prove_stats
-                    .as_ref()
-                    .map(|s| s.mean_ms)
-                    .unwrap_or(0.0);
-                let memory_bytes = result
-                    .record
-                    .peak_rss_mb
-                    .map(|mb| (mb * 1024.0 * 1024.0) as u64);
-                let proof_size = result.record.proof_size_bytes;
-                let verify_success = result.verify_success;
+                    // Prepare inputs
+                    let prover_toml = find_prover_toml(&spec);
+                    let mut inputs = ProveInputs::new(&spec.path, &spec.name)
+                        .with_timeout(Duration::from_secs(24 * 60 * 60))
+                        .with_probe(probe)
+                        .with_cache_mode(CacheMode::from_cold(cold_cache));
+                    if let Some(pt) = prover_toml {
+                        inputs = inputs.with_prover_toml(pt);
+                    }
+                    let artifact_hash = file_sha256(&spec.path);
+                    if let Some((info, ms)) = artifact_hash.as_ref().and_then(|h| gate_memo.get(h))
+                    {
+                        inputs = inputs.with_gate_info(info.clone());
+                        gates_reused += 1;
+                        gates_saved_ms += ms;
+                    }
 
-                let rec = json!({
-                    "timestamp": timestamp,
-                    "circuit": spec.name,
-                    "params": spec.params,
-                    "backend": "barretenberg",
-                    "compile_ms": compile_ms,
-                    "constraints": constraints,
-                    "acir_opcodes": acir_opcodes,
-                    "acir_bytes": acir_bytes,
-                    "prove_ms": prove_ms_avg,
-                    "memory_bytes": memory_bytes,
-                    "proof_size": proof_size,
-                    "evm_gas": serde_json::Value::Null,
-                    "status": verify_success,
-                });
-                let _ = writeln!(jsonl, "{}", serde_json::to_string(&rec).unwrap());
-
-                csv_logger.append_row(
-                    &timestamp,
-                    &spec.name,
-                    spec.params,
-                    "barretenberg",
-                    Some(compile_ms),
-                    Some(prove_ms_avg as u128),
-                    memory_bytes.map(|b| b / (1024 * 1024)),
-                    constraints,
-                    acir_opcodes,
-                    acir_bytes,
-                    proof_size,
-                    None,
-                    if verify_success { "ok" } else { "fail" },
-                )?;
+                    // Run full benchmark workflow
+                    let result = full_benchmark(&toolchain, &backend, &inputs, warmup_n, iter_n)?;
+                    if let (Some(hash), Some(info), Some(stats)) =
+                        (artifact_hash, &result.gate_info, &result.record.gates_stats)
+                    {
+                        gate_memo
+                            .entry(hash)
+                            .or_insert_with(|| (info.clone(), stats.mean_ms));
+                    }
+                    if !matches!(result.verify_status, VerifyStatus::Failed(_)) {
+                        circuit_dir.succeed();
+                    }
+                    out.barretenberg(&spec, &timestamp, result, warmup_n)
+                })?
             }
-            "evm" => {
+            "evm" => with_hooks(&hooks, out.hook_context(&spec, "evm"), || {
                 let evm = EvmBackend::new(&spec.path);
-                let verify = evm.verify()?;
-
-                let rec = json!({
-                    "timestamp": timestamp,
-                    "circuit": spec.name,
-                    "params": spec.params,
-                    "backend": "evm",
-                    "compile_ms": serde_json::Value::Null,
-                    "constraints": serde_json::Value::Null,
-                    "acir_opcodes": serde_json::Value::Null,
-                    "prove_ms": serde_json::Value::Null,
-                    "memory_bytes": serde_json::Value::Null,
-                    "proof_size": serde_json::Value::Null,
-                    "evm_gas": verify.gas_used,
-                    "status": verify.success,
-                });
-                let _ = writeln!(jsonl, "{}", serde_json::to_string(&rec).unwrap());
-
-                csv_logger.append_row(
-                    &timestamp,
-                    &spec.name,
-                    spec.params,
-                    "evm",
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    verify.gas_used,
-                    if verify.success { "ok" } else { "fail" },
-                )?;
-            }
+                let verify = evm.measure()?;
+                out.evm(&spec, &timestamp, &evm, &verify)
+            })?,
             other => {
                 return Err(BenchError::Message(format!("unknown backend '{}'", other)));
             }
         }
+        progress.circuit_done(&spec.name, spec.params);
+    }
+    progress.finish();
+    if gates_reused > 0 {
+        println!(
+            "bench run-all: reused gate analysis for {gates_reused} run(s) of an already analysed artifact, saved {gates_saved_ms:.0} ms"
+        );
     }
     Ok(())
 }
@@ -398,76 +1142,40 @@ pub fn export_csv(jsonl_path: Option<PathBuf>, csv_out: Option<PathBuf>) -> Benc
     if let Some(dir) = csvp.parent() {
         std::fs::create_dir_all(dir).ok();
     }
-    let reader =
-        BufReader::new(File::open(&jsonl).map_err(|e| BenchError::Message(e.to_string()))?);
+    let reader = open_lines(&jsonl)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", jsonl.display())))?;
     let mut csv_w = crate::logging::csv_logger::CsvLogger::new(&csvp);
 
+    // Legacy lines are upgraded on parse, so both output formats export alike
     for line in reader.lines() {
         let Ok(l) = line else { continue };
-        let Ok(v): Result<serde_json::Value, _> = serde_json::from_str(&l) else {
+        let Ok(record) = parse_record(&l) else {
             continue;
         };
-        let timestamp = v
-            .get("timestamp")
-            .and_then(|x| x.as_str())
-            .unwrap_or_default()
-            .to_string();
-        let circuit = v
-            .get("circuit")
-            .and_then(|x| x.as_str())
-            .unwrap_or_default()
-            .to_string();
-        let params = v.get("params").and_then(|x| x.as_u64());
-        let backend = v
-            .get("backend")
-            .and_then(|x| x.as_str())
-            .unwrap_or_default()
-            .to_string();
-        let compile_ms = v
-            .get("compile_ms")
-            .and_then(|x| x.as_u64())
-            .map(|x| x as u128);
-        // prove_ms may be float (avg) in JSONL now; support both number types
-        let prove_ms = if let Some(u) = v.get("prove_ms").and_then(|x| x.as_u64()) {
-            Some(u as u128)
-        } else if let Some(f) = v.get("prove_ms").and_then(|x| x.as_f64()) {
-            Some(f.round() as u128)
-        } else {
-            None
+        let mean = |stat: &Option<TimingStat>| stat.as_ref().map(|s| s.mean_ms.round() as u128);
+        let status = match record.metadata.get("status").map(String::as_str) {
+            Some("true") => "ok",
+            Some(_) => "fail",
+            None if record.error.is_some() => "fail",
+            None => "ok",
         };
-        let memory_mb = v
-            .get("memory_bytes")
-            .and_then(|x| x.as_u64())
-            .map(|b| b / (1024 * 1024));
-        let constraints = v.get("constraints").and_then(|x| x.as_u64());
-        let acir_opcodes = v.get("acir_opcodes").and_then(|x| x.as_u64());
-        let proof_size = v.get("proof_size").and_then(|x| x.as_u64());
-        let acir_bytes = v.get("acir_bytes").and_then(|x| x.as_u64());
-        let evm_gas = v.get("evm_gas").and_then(|x| x.as_u64());
-        let status = v
-            .get("status")
-            .map(|x| {
-                if x.as_bool() == Some(true) {
-                    "ok"
-                } else {
-                    "fail"
-                }
-            })
-            .unwrap_or("unknown");
 
         csv_w.append_row(
-            &timestamp,
-            &circuit,
-            params,
-            &backend,
-            compile_ms,
-            prove_ms,
-            memory_mb,
-            constraints,
-            acir_opcodes,
-            acir_bytes,
-            proof_size,
-            evm_gas,
+            &record.timestamp,
+            &record.circuit_name,
+            record.params,
+            &record.backend.name,
+            mean(&record.compile_stats),
+            mean(&record.prove_stats),
+            record.peak_rss_mb.map(|mb| mb as u64),
+            record.total_gates,
+            record.acir_opcodes,
+            record.artifact_size_bytes,
+            record.proof_size_bytes,
+            record
+                .metadata
+                .get("evm_gas")
+                .and_then(|gas| gas.parse().ok()),
             status,
         )?;
     }
@@ -489,7 +1197,7 @@ pub fn evm_verify(
         csv_out.unwrap_or_else(|| PathBuf::from(DEFAULT_CSV)),
     );
     let evm = EvmBackend::new(&spec.path);
-    let verify = evm.verify()?;
+    let verify = evm.measure()?;
     let timestamp = now_string();
 
     csv_logger.append_row(
@@ -519,6 +1227,23 @@ mod tests {
     use crate::engine::workflow::full_benchmark;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_resolve_compile_configs_inline_and_named() {
+        let defined = vec![CompileConfig {
+            name: "brillig".to_string(),
+            flags: vec!["--force-brillig".to_string()],
+        }];
+        let configs =
+            resolve_compile_configs(&["default=".to_string(), "brillig".to_string()], &defined)
+                .unwrap();
+        assert_eq!(configs[0].name, "default");
+        assert_eq!(configs[1], defined[0]);
+
+        assert!(resolve_compile_configs(&["missing".to_string()], &defined).is_err());
+        let twice = ["brillig".to_string(), "brillig=--force-brillig".to_string()];
+        assert!(resolve_compile_configs(&twice, &defined).is_err());
+    }
+
     /// Test that bench uses engine workflow with mock backend/toolchain.
     #[test]
     fn test_bench_uses_engine_workflow() {
@@ -536,6 +1261,13 @@ mod tests {
                     verification_key_size_bytes: Some(512),
                     proof_path: Some(PathBuf::from("/mock/proof")),
                     vk_path: Some(PathBuf::from("/mock/vk")),
+                    setup_time_ms: None,
+                    phases: None,
+                    vk_gen_time_ms: None,
+                    vk_cached: false,
+                    key_cache: None,
+                    prove_cpu: None,
+                    vk_gen_cpu: None,
                 })
                 .with_verify_output(VerifyOutput {
                     verify_time_ms: 50,
@@ -607,4 +1339,85 @@ mod tests {
         assert_eq!(bench_result.record.config.warmup_iterations, 1);
         assert_eq!(bench_result.record.config.measured_iterations, 3);
     }
+
+    #[test]
+    fn test_bench_output_formats_export_alike() {
+        let backend = MockBackend::new(
+            MockConfig::new("mock-bb")
+                .with_prove_output(ProveOutput {
+                    prove_time_ms: 120,
+                    proof_size_bytes: Some(2048),
+                    ..Default::default()
+                })
+                .with_verify_output(VerifyOutput {
+                    verify_time_ms: 10,
+                    success: true,
+                })
+                .with_gate_info(GateInfo::from_gates(7000)),
+        );
+        let inputs = ProveInputs::new("/mock/artifact.json", "merkle");
+        let spec = CircuitSpec {
+            name: "merkle".to_string(),
+            path: PathBuf::from("/mock/artifact.json"),
+            params: Some(16),
+            env: BTreeMap::from([("RAYON_NUM_THREADS".to_string(), "4".to_string())]),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let jsonl = dir.path().join("bench.jsonl");
+
+        for format in [OutputFormat::BenchRecord, OutputFormat::Legacy] {
+            let result = full_benchmark(&MockToolchain::new(), &backend, &inputs, 0, 1).unwrap();
+            let mut out = BenchOutput::new(
+                format,
+                Some(jsonl.clone()),
+                Some(dir.path().join("bench.csv")),
+            );
+            out.barretenberg(&spec, &now_string(), result, 0).unwrap();
+        }
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&jsonl)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert!(lines[0].get("schema_version").is_some());
+        assert_eq!(lines[1]["constraints"], 7000);
+        assert_eq!(lines[1]["iterations"]["avg_ms"], 120.0);
+        assert_eq!(lines[0]["config"]["env"]["RAYON_NUM_THREADS"], "4");
+
+        // Both shapes read back as records with the same measurements
+        let records = JsonlWriter::new(&jsonl).read_all().unwrap();
+        for record in &records {
+            assert_eq!(record.params, Some(16));
+            assert_eq!(record.total_gates, Some(7000));
+            assert_eq!(record.proof_size_bytes, Some(2048));
+        }
+
+        let csv = dir.path().join("export.csv");
+        export_csv(Some(jsonl.clone()), Some(csv.clone())).unwrap();
+        let rows: Vec<String> = std::fs::read_to_string(&csv)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|row| row.split_once(',').unwrap().1.to_string())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("merkle,16,"));
+        assert!(
+            rows.iter()
+                .all(|row| row.contains(",120,") && row.ends_with(",ok"))
+        );
+
+        // Compressed archives export the same rows
+        let lines: Vec<String> = std::fs::read_to_string(&jsonl)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        let gz = dir.path().join("bench.jsonl.gz");
+        crate::storage::jsonl::write_lines(&gz, &lines, true).unwrap();
+        let gz_csv = dir.path().join("export-gz.csv");
+        export_csv(Some(gz), Some(gz_csv.clone())).unwrap();
+        assert_eq!(std::fs::read_to_string(&gz_csv).unwrap().lines().count(), 3);
+    }
 }
@@ -1,7 +1,12 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
+use crate::backend::args::BackendSection;
+use crate::engine::CompileConfig;
+use crate::report::MetricSelection;
+use crate::report::regression::CircuitThresholds;
 use crate::{BenchError, BenchResult};
 
 #[derive(Debug, Clone)]
@@ -9,6 +14,8 @@ pub struct CircuitSpec {
     pub name: String,
     pub path: PathBuf,
     pub params: Option<u64>,
+    /// Environment variables for the circuit's nargo and bb processes
+    pub env: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -17,19 +24,62 @@ struct RawCircuit {
     pub path: PathBuf,
     #[serde(default)]
     pub params: Option<Vec<u64>>,
+    #[serde(default)]
+    pub env: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCompileConfig {
+    pub name: String,
+    #[serde(default)]
+    pub flags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct BenchConfig {
-    #[serde(rename = "circuit")]
+    #[serde(rename = "circuit", default)]
     pub circuits: Vec<RawCircuit>,
+    #[serde(rename = "compile_config", default)]
+    pub compile_configs: Vec<RawCompileConfig>,
+    #[serde(default)]
+    pub backend: BackendSection,
+}
+
+/// Render an `env` table as environment variables: `RAYON_NUM_THREADS = 8`
+/// and `RAYON_NUM_THREADS = "8"` both set `8`.
+fn env_vars(
+    table: &BTreeMap<String, toml::Value>,
+    section: &str,
+) -> BenchResult<BTreeMap<String, String>> {
+    table
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Integer(n) => n.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                toml::Value::Boolean(b) => b.to_string(),
+                other => {
+                    return Err(BenchError::Message(format!(
+                        "{section} {key}: expected a string, number or boolean, got {}",
+                        other.type_str()
+                    )));
+                }
+            };
+            Ok((key.clone(), value))
+        })
+        .collect()
 }
 
 pub fn load_bench_config(path: &Path) -> BenchResult<Vec<CircuitSpec>> {
     let s = std::fs::read_to_string(path).map_err(|e| BenchError::Message(e.to_string()))?;
     let cfg: BenchConfig = toml::from_str(&s).map_err(|e| BenchError::Message(e.to_string()))?;
+    let backend_env = env_vars(&cfg.backend.env, "[backend.env]")?;
     let mut specs: Vec<CircuitSpec> = Vec::new();
     for c in cfg.circuits {
+        // A circuit's own variables override the backend-wide ones
+        let mut env = backend_env.clone();
+        env.extend(env_vars(&c.env, &format!("[[circuit]] {} env", c.name))?);
         match c.params {
             Some(list) if !list.is_empty() => {
                 for p in list {
@@ -37,6 +87,7 @@ pub fn load_bench_config(path: &Path) -> BenchResult<Vec<CircuitSpec>> {
                         name: c.name.clone(),
                         path: c.path.clone(),
                         params: Some(p),
+                        env: env.clone(),
                     });
                 }
             }
@@ -45,6 +96,7 @@ pub fn load_bench_config(path: &Path) -> BenchResult<Vec<CircuitSpec>> {
                     name: c.name,
                     path: c.path,
                     params: None,
+                    env,
                 });
             }
         }
@@ -63,3 +115,139 @@ pub fn list_circuits_in_config(
         .map(|c| (c.name, c.path, c.params))
         .collect())
 }
+
+/// Named `nargo compile` flag sets from `[[compile_config]]` tables, in
+/// file order.
+pub fn load_compile_configs(path: &Path) -> BenchResult<Vec<CompileConfig>> {
+    let s = std::fs::read_to_string(path).map_err(|e| BenchError::Message(e.to_string()))?;
+    let cfg: BenchConfig = toml::from_str(&s).map_err(|e| BenchError::Message(e.to_string()))?;
+    cfg.compile_configs
+        .into_iter()
+        .map(|c| CompileConfig::new(&c.name, c.flags))
+        .collect()
+}
+
+/// CI-specific configuration from bench-config.toml
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct CiConfig {
+    /// Circuits to run in CI (subset of full benchmark suite)
+    #[serde(default)]
+    pub circuits: Vec<String>,
+    /// Path to baseline JSONL file
+    #[serde(default)]
+    pub baseline_file: Option<String>,
+    /// Regression threshold percentage
+    #[serde(default)]
+    pub threshold_percent: Option<f64>,
+    /// Number of iterations for CI runs
+    #[serde(default)]
+    pub iterations: Option<usize>,
+    /// Number of warmup iterations
+    #[serde(default)]
+    pub warmup: Option<usize>,
+    /// Per-metric regression thresholds
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, f64>,
+    /// Per-circuit overrides (`[ci.circuit_thresholds.<circuit>]`), e.g. a
+    /// looser `prove_ms` for a circuit with noisy timings
+    #[serde(default)]
+    pub circuit_thresholds: CircuitThresholds,
+    /// Use `k` standard deviations of the baseline samples as the threshold
+    /// for timing metrics, instead of a flat percentage
+    #[serde(default)]
+    pub noise_sigma: Option<f64>,
+    /// Treat the baseline file as a history and compare against the median
+    /// of the last N runs per circuit/backend
+    #[serde(default)]
+    pub baseline_window: Option<usize>,
+    /// Compare against a baseline measured on a different CPU architecture
+    /// instead of skipping the comparison
+    #[serde(default)]
+    pub allow_cross_arch: bool,
+    /// Metrics to compare (names or aliases such as `gates`, `peak_rss`);
+    /// empty compares every metric
+    #[serde(default)]
+    pub metrics: Vec<String>,
+    /// Metrics that are reported but never fail the run
+    #[serde(default)]
+    pub informational_metrics: Vec<String>,
+    /// Cache gate counts by artifact hash in this directory, so unchanged
+    /// circuits skip gate analysis on later runs
+    #[serde(default)]
+    pub gate_cache: Option<PathBuf>,
+    /// Cache generated vks by artifact hash in this directory, so unchanged
+    /// circuits skip vk generation on later runs
+    #[serde(default)]
+    pub key_cache: Option<PathBuf>,
+    /// Distance from the median, in MADs, beyond which the daemon flags a
+    /// run appended to its history as an anomaly (default 5)
+    #[serde(default)]
+    pub anomaly_mad_k: Option<f64>,
+}
+
+impl CiConfig {
+    /// The `metrics`/`informational_metrics` selection.
+    pub fn metric_selection(&self) -> MetricSelection {
+        MetricSelection::new(&self.metrics, &self.informational_metrics)
+    }
+}
+
+/// `[ci]` section of a config file; the rest is ignored.
+#[derive(Debug, Deserialize)]
+struct CiSection {
+    #[serde(default)]
+    ci: Option<CiConfig>,
+}
+
+/// Load only the `[ci]` section of a config file.
+pub fn load_ci_settings(path: &Path) -> BenchResult<CiConfig> {
+    let s = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read config: {e}")))?;
+    let section: CiSection = toml::from_str(&s)
+        .map_err(|e| BenchError::Message(format!("failed to parse config: {e}")))?;
+    Ok(section.ci.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_env_overrides_backend_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench-config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[backend.env]
+RAYON_NUM_THREADS = 8
+BB_VERBOSE = "0"
+
+[[circuit]]
+name = "merkle"
+path = "merkle/target/merkle.json"
+params = [16, 32]
+env = { RAYON_NUM_THREADS = 4 }
+
+[[circuit]]
+name = "ecdsa"
+path = "ecdsa/target/ecdsa.json"
+"#,
+        )
+        .unwrap();
+
+        let specs = load_bench_config(&path).unwrap();
+        assert_eq!(specs.len(), 3);
+        assert_eq!(specs[1].env["RAYON_NUM_THREADS"], "4");
+        assert_eq!(specs[1].env["BB_VERBOSE"], "0");
+        assert_eq!(specs[2].env["RAYON_NUM_THREADS"], "8");
+
+        std::fs::write(
+            &path,
+            "[[circuit]]\nname = \"c\"\npath = \"c.json\"\nenv = { X = [1] }\n",
+        )
+        .unwrap();
+        let err = load_bench_config(&path).unwrap_err();
+        assert!(err.to_string().contains("got array"));
+    }
+}
@@ -0,0 +1,303 @@
+//! User commands run around each benchmark run.
+//!
+//! A `[hooks]` table in the bench config names shell commands to run before
+//! each circuit run (`pre_run`), after it whatever its outcome (`post_run`)
+//! and, before `post_run`, when it failed (`on_failure`). Teams use them to
+//! warm caches, send notifications or clean up without patching noir-bench.
+//! Each hook gets the run's context in `NOIR_BENCH_*` environment variables.
+//! A failing `pre_run` hook fails the run; the others only warn.
+//!
+//! Only `bench run` and `bench run-all` run hooks. Other commands reading the
+//! same config (`ci`, `abtest`, `bench run --compile-config`) warn that they
+//! ignore them; `suite` has its own config format without hooks.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::core::process::Supervised;
+use crate::{BenchError, BenchResult};
+
+/// Seconds a hook may run before its process tree is killed.
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 600;
+
+/// The `[hooks]` section of a bench config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    pub pre_run: Option<String>,
+    pub post_run: Option<String>,
+    pub on_failure: Option<String>,
+    /// Seconds a hook may run (default 600, 0 for no limit)
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HooksConfig {
+    #[serde(default)]
+    hooks: Hooks,
+}
+
+/// Read the `[hooks]` section of a bench config; empty when absent.
+pub fn load_hooks(path: &Path) -> BenchResult<Hooks> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+    let config: HooksConfig = toml::from_str(&text)
+        .map_err(|e| BenchError::Message(format!("failed to parse {}: {e}", path.display())))?;
+    Ok(config.hooks)
+}
+
+/// Warn when the config at `path` has hooks that `command` won't run.
+pub fn warn_ignored_hooks(path: &Path, command: &str) {
+    if load_hooks(path).is_ok_and(|hooks| !hooks.is_empty()) {
+        eprintln!(
+            "warning: {command} does not run the [hooks] in {}; only bench run and bench run-all do",
+            path.display()
+        );
+    }
+}
+
+/// When a hook runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreRun,
+    PostRun,
+    OnFailure,
+}
+
+impl HookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::PreRun => "pre_run",
+            HookEvent::PostRun => "post_run",
+            HookEvent::OnFailure => "on_failure",
+        }
+    }
+}
+
+/// What a hook is told about the run.
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub circuit: String,
+    pub params: Option<u64>,
+    pub backend: String,
+    /// JSONL file the run's record is appended to
+    pub jsonl_path: Option<PathBuf>,
+    /// The run's record (or legacy line), once written
+    pub record: Option<serde_json::Value>,
+    /// Error of a failed run
+    pub error: Option<String>,
+}
+
+impl HookContext {
+    /// `pending` before the run, then `ok` or `failed`.
+    fn status(&self, event: HookEvent) -> &'static str {
+        match event {
+            HookEvent::PreRun => "pending",
+            _ if self.error.is_some() => "failed",
+            _ => "ok",
+        }
+    }
+}
+
+/// Environment variables a hook runs with. `record_path` is a file holding
+/// the run's record as JSON.
+fn hook_env(
+    event: HookEvent,
+    ctx: &HookContext,
+    record_path: Option<&Path>,
+) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("NOIR_BENCH_HOOK", event.as_str().to_string()),
+        ("NOIR_BENCH_CIRCUIT", ctx.circuit.clone()),
+        ("NOIR_BENCH_BACKEND", ctx.backend.clone()),
+        ("NOIR_BENCH_STATUS", ctx.status(event).to_string()),
+    ];
+    if let Some(params) = ctx.params {
+        env.push(("NOIR_BENCH_PARAMS", params.to_string()));
+    }
+    if let Some(path) = &ctx.jsonl_path {
+        env.push(("NOIR_BENCH_JSONL", path.display().to_string()));
+    }
+    if let Some(path) = record_path {
+        env.push(("NOIR_BENCH_RECORD", path.display().to_string()));
+    }
+    if let Some(error) = &ctx.error {
+        env.push(("NOIR_BENCH_ERROR", error.clone()));
+    }
+    env
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+impl Hooks {
+    fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::PreRun => self.pre_run.as_deref(),
+            HookEvent::PostRun => self.post_run.as_deref(),
+            HookEvent::OnFailure => self.on_failure.as_deref(),
+        }
+        .filter(|c| !c.trim().is_empty())
+    }
+
+    /// Whether no hook is configured.
+    pub fn is_empty(&self) -> bool {
+        [HookEvent::PreRun, HookEvent::PostRun, HookEvent::OnFailure]
+            .into_iter()
+            .all(|event| self.command(event).is_none())
+    }
+
+    /// Run the hook for `event`, if one is configured.
+    pub fn run(&self, event: HookEvent, ctx: &HookContext) -> BenchResult<()> {
+        let Some(command) = self.command(event) else {
+            return Ok(());
+        };
+        let record_file = ctx
+            .record
+            .as_ref()
+            .map(|record| {
+                let file = tempfile::Builder::new()
+                    .prefix("noir-bench-record-")
+                    .suffix(".json")
+                    .tempfile()?;
+                serde_json::to_writer(file.as_file(), record)?;
+                Ok::<_, Box<dyn std::error::Error>>(file)
+            })
+            .transpose()
+            .map_err(|e| BenchError::Message(format!("failed to write record for hook: {e}")))?;
+
+        let mut cmd = shell(command);
+        cmd.envs(hook_env(event, ctx, record_file.as_ref().map(|f| f.path())))
+            .stdin(Stdio::null());
+        let timeout = Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+        let finished = Supervised::spawn(&mut cmd)
+            .and_then(|child| child.wait_timeout(timeout))
+            .map_err(|e| {
+                BenchError::Message(format!("failed to run {} hook: {e}", event.as_str()))
+            })?;
+        match finished {
+            None => Err(BenchError::Message(format!(
+                "{} hook timed out after {}s",
+                event.as_str(),
+                timeout.as_secs()
+            ))),
+            Some(f) if !f.status.success() => Err(BenchError::Message(format!(
+                "{} hook failed: {}",
+                event.as_str(),
+                f.status
+            ))),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Run `on_failure` (for a failed run) and `post_run`, warning about
+    /// hooks that fail.
+    pub fn finish(&self, ctx: &HookContext) {
+        let events: &[HookEvent] = if ctx.error.is_some() {
+            &[HookEvent::OnFailure, HookEvent::PostRun]
+        } else {
+            &[HookEvent::PostRun]
+        };
+        for &event in events {
+            if let Err(e) = self.run(event, ctx) {
+                eprintln!("warning: {}: {e}", ctx.circuit);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_env_describes_run() {
+        let mut ctx = HookContext {
+            circuit: "merkle".to_string(),
+            params: Some(16),
+            backend: "barretenberg".to_string(),
+            jsonl_path: Some(PathBuf::from("out/bench.jsonl")),
+            ..HookContext::default()
+        };
+        let env = hook_env(HookEvent::PreRun, &ctx, None);
+        assert!(env.contains(&("NOIR_BENCH_STATUS", "pending".to_string())));
+        assert!(env.contains(&("NOIR_BENCH_PARAMS", "16".to_string())));
+        assert!(env.contains(&("NOIR_BENCH_JSONL", "out/bench.jsonl".to_string())));
+        assert!(!env.iter().any(|(k, _)| *k == "NOIR_BENCH_RECORD"));
+
+        ctx.error = Some("bb prove failed".to_string());
+        let env = hook_env(
+            HookEvent::OnFailure,
+            &ctx,
+            Some(Path::new("/tmp/record.json")),
+        );
+        assert!(env.contains(&("NOIR_BENCH_HOOK", "on_failure".to_string())));
+        assert!(env.contains(&("NOIR_BENCH_STATUS", "failed".to_string())));
+        assert!(env.contains(&("NOIR_BENCH_RECORD", "/tmp/record.json".to_string())));
+        assert!(env.contains(&("NOIR_BENCH_ERROR", "bb prove failed".to_string())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks_run_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("hooks.log");
+        let config = dir.path().join("bench-config.toml");
+        std::fs::write(
+            &config,
+            format!(
+                r#"
+[hooks]
+post_run = "echo \"$NOIR_BENCH_HOOK $NOIR_BENCH_CIRCUIT $NOIR_BENCH_STATUS $(cat $NOIR_BENCH_RECORD)\" >> {log}"
+on_failure = "echo \"$NOIR_BENCH_HOOK $NOIR_BENCH_ERROR\" >> {log}"
+pre_run = "exit 3"
+"#,
+                log = log.display()
+            ),
+        )
+        .unwrap();
+        let hooks = load_hooks(&config).unwrap();
+        assert!(!hooks.is_empty());
+        assert!(
+            Hooks {
+                pre_run: Some("  ".to_string()),
+                ..Hooks::default()
+            }
+            .is_empty()
+        );
+
+        let mut ctx = HookContext {
+            circuit: "merkle".to_string(),
+            backend: "barretenberg".to_string(),
+            record: Some(serde_json::json!({"circuit_name": "merkle"})),
+            ..HookContext::default()
+        };
+        let err = hooks.run(HookEvent::PreRun, &ctx).unwrap_err();
+        assert!(err.to_string().contains("pre_run hook failed"));
+
+        hooks.finish(&ctx);
+        ctx.error = Some("verify failed".to_string());
+        hooks.finish(&ctx);
+        let log = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(
+            log.lines().collect::<Vec<_>>(),
+            vec![
+                "post_run merkle ok {\"circuit_name\":\"merkle\"}",
+                "on_failure verify failed",
+                "post_run merkle failed {\"circuit_name\":\"merkle\"}",
+            ]
+        );
+    }
+}
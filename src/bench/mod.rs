@@ -1,3 +1,3 @@
-pub mod backend;
 pub mod bench_cmd;
 pub mod config;
+pub mod hooks;
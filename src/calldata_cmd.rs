@@ -0,0 +1,221 @@
+//! CLI command handler for `calldata`: what a proof costs to put on chain.
+//!
+//! Splits the calldata into zero and nonzero bytes and prices it under
+//! EIP-2028 (4 gas per zero byte, 16 per nonzero byte) and the EIP-7623
+//! floor that data-heavy transactions pay since Pectra, next to the blob gas
+//! of posting the same bytes as EIP-4844 blobs. Blob data is not readable by
+//! the EVM, so a verifier consuming it also needs point-evaluation openings.
+//! The gzip ratio shows how much a compressed encoding could save.
+//!
+//! A proof is priced as the `verify(bytes,bytes32[])` call a verifier
+//! contract receives, encoded by `proof_cmd`, so the selector, offsets and
+//! padding count as they do on chain.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{BenchError, BenchResult};
+
+/// Calldata gas per zero byte (EIP-2028).
+pub const ZERO_BYTE_GAS: u64 = 4;
+/// Calldata gas per nonzero byte (EIP-2028).
+pub const NONZERO_BYTE_GAS: u64 = 16;
+/// Floor gas per calldata token, where a nonzero byte is 4 tokens (EIP-7623).
+pub const FLOOR_GAS_PER_TOKEN: u64 = 10;
+/// Blob gas per blob (EIP-4844).
+pub const GAS_PER_BLOB: u64 = 1 << 17;
+/// Payload bytes per blob: 4096 field elements of 31 usable bytes each.
+pub const BLOB_PAYLOAD_BYTES: u64 = 4096 * 31;
+
+/// Calldata cost breakdown of one payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct CalldataAnalysis {
+    pub total_bytes: u64,
+    pub zero_bytes: u64,
+    pub nonzero_bytes: u64,
+    /// EIP-2028 calldata gas
+    pub calldata_gas: u64,
+    /// EIP-7623 floor, charged instead when execution uses less gas
+    pub calldata_floor_gas: u64,
+    pub blobs: u64,
+    pub blob_gas: u64,
+    pub gzip_bytes: u64,
+    /// Compressed size over original size
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
+    pub gzip_ratio: f64,
+    /// EIP-2028 gas of the compressed bytes
+    pub gzip_calldata_gas: u64,
+}
+
+fn count_zeros(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|&&b| b == 0).count() as u64
+}
+
+fn calldata_gas(bytes: &[u8]) -> u64 {
+    let zeros = count_zeros(bytes);
+    zeros * ZERO_BYTE_GAS + (bytes.len() as u64 - zeros) * NONZERO_BYTE_GAS
+}
+
+fn gzip(bytes: &[u8]) -> BenchResult<Vec<u8>> {
+    let err = |e: std::io::Error| BenchError::Message(format!("gzip failed: {e}"));
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(bytes).map_err(err)?;
+    encoder.finish().map_err(err)
+}
+
+/// Analyze `bytes` as calldata.
+pub fn analyze(bytes: &[u8]) -> BenchResult<CalldataAnalysis> {
+    let total_bytes = bytes.len() as u64;
+    let zero_bytes = count_zeros(bytes);
+    let nonzero_bytes = total_bytes - zero_bytes;
+    let tokens = zero_bytes + 4 * nonzero_bytes;
+    let blobs = total_bytes.div_ceil(BLOB_PAYLOAD_BYTES);
+    let compressed = gzip(bytes)?;
+    Ok(CalldataAnalysis {
+        total_bytes,
+        zero_bytes,
+        nonzero_bytes,
+        calldata_gas: calldata_gas(bytes),
+        calldata_floor_gas: tokens * FLOOR_GAS_PER_TOKEN,
+        blobs,
+        blob_gas: blobs * GAS_PER_BLOB,
+        gzip_bytes: compressed.len() as u64,
+        gzip_ratio: if total_bytes == 0 {
+            1.0
+        } else {
+            compressed.len() as f64 / total_bytes as f64
+        },
+        gzip_calldata_gas: calldata_gas(&compressed),
+    })
+}
+
+/// Render the analysis for the terminal.
+pub fn format_text(a: &CalldataAnalysis) -> String {
+    let pct = |n: u64| {
+        if a.total_bytes == 0 {
+            0.0
+        } else {
+            n as f64 * 100.0 / a.total_bytes as f64
+        }
+    };
+    let mut out = String::new();
+    out.push_str(&format!("bytes:            {}\n", a.total_bytes));
+    out.push_str(&format!(
+        "  zero:           {} ({:.1}%)\n",
+        a.zero_bytes,
+        pct(a.zero_bytes)
+    ));
+    out.push_str(&format!(
+        "  nonzero:        {} ({:.1}%)\n",
+        a.nonzero_bytes,
+        pct(a.nonzero_bytes)
+    ));
+    out.push_str(&format!("calldata gas:     {}\n", a.calldata_gas));
+    out.push_str(&format!("  EIP-7623 floor: {}\n", a.calldata_floor_gas));
+    out.push_str(&format!(
+        "blob gas:         {} ({} blob{})\n",
+        a.blob_gas,
+        a.blobs,
+        if a.blobs == 1 { "" } else { "s" }
+    ));
+    out.push_str(&format!(
+        "gzip:             {} bytes (ratio {:.3}, {} calldata gas)\n",
+        a.gzip_bytes, a.gzip_ratio, a.gzip_calldata_gas
+    ));
+    out
+}
+
+/// Run `calldata` on the verify call of a proof (plus public inputs), or on
+/// hex calldata.
+pub fn run(
+    proof: Option<PathBuf>,
+    public_inputs: Option<PathBuf>,
+    calldata: Option<PathBuf>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let bytes = crate::proof_cmd::load_verify_call(
+        calldata.as_deref(),
+        proof.as_deref(),
+        public_inputs.as_deref(),
+    )?
+    .ok_or_else(|| BenchError::Message("calldata needs --proof or --calldata".into()))?;
+    let analysis = analyze(&bytes)?;
+    if let Some(json) = json_out {
+        write_json(&json, &analysis)?;
+    }
+    print!("{}", format_text(&analysis));
+    Ok(())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> BenchResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| BenchError::Message(e.to_string()))?;
+    }
+    let json = serde_json::to_vec_pretty(value).map_err(|e| BenchError::Message(e.to_string()))?;
+    std::fs::write(path, json)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyze_prices_zero_and_nonzero_bytes() {
+        let mut bytes = vec![0u8; 100];
+        bytes.extend([0xffu8; 50]);
+        let a = analyze(&bytes).unwrap();
+        assert_eq!((a.zero_bytes, a.nonzero_bytes), (100, 50));
+        assert_eq!(a.calldata_gas, 100 * 4 + 50 * 16);
+        assert_eq!(a.calldata_floor_gas, (100 + 4 * 50) * 10);
+        assert_eq!((a.blobs, a.blob_gas), (1, 131_072));
+        // Runs of identical bytes compress well
+        assert!(a.gzip_ratio < 0.5);
+        assert!(a.gzip_calldata_gas < a.calldata_gas);
+    }
+
+    #[test]
+    fn test_run_prices_the_verify_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let proof = dir.path().join("proof");
+        let inputs = dir.path().join("public_inputs");
+        std::fs::write(&proof, [7u8; 40]).unwrap();
+        std::fs::write(&inputs, [1u8; 64]).unwrap();
+        let json = dir.path().join("calldata.json");
+
+        run(
+            Some(proof.clone()),
+            Some(inputs.clone()),
+            None,
+            Some(json.clone()),
+        )
+        .unwrap();
+        let call = crate::proof_cmd::load_verify_call(None, Some(&proof), Some(&inputs))
+            .unwrap()
+            .unwrap();
+        let report: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&json).unwrap()).unwrap();
+        assert_eq!(report["total_bytes"], call.len() as u64);
+
+        // The same call as hex calldata
+        let hex_path = dir.path().join("call.hex");
+        std::fs::write(&hex_path, format!("0x{}\n", hex::encode(&call))).unwrap();
+        run(None, None, Some(hex_path), Some(json.clone())).unwrap();
+        let again: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&json).unwrap()).unwrap();
+        assert_eq!(again, report);
+    }
+
+    #[test]
+    fn test_blob_count_uses_usable_bytes() {
+        let a = analyze(&vec![1u8; BLOB_PAYLOAD_BYTES as usize + 1]).unwrap();
+        assert_eq!(a.blobs, 2);
+        let empty = analyze(&[]).unwrap();
+        assert_eq!(
+            (empty.blobs, empty.calldata_gas, empty.gzip_ratio),
+            (0, 0, 1.0)
+        );
+    }
+}
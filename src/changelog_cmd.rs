@@ -0,0 +1,302 @@
+//! CLI command handler for `changelog`: a performance changelog between two
+//! releases, in markdown for pasting into release notes.
+//!
+//! Each side is a named baseline (see `baseline_cmd`) or, with `--jsonl`, a
+//! git ref: the history runs measured at the commit it points to, collapsed
+//! to their per-circuit medians. Only changes beyond the threshold are
+//! listed, so the notes stay readable.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::baseline_cmd;
+use crate::compare_cmd::{
+    CircuitThresholds, CompareResult, CompareStatus, DEFAULT_THRESHOLD, compare_records,
+    window_baseline,
+};
+use crate::core::BenchRecord;
+use crate::report::format_value;
+use crate::report::regression::escape_markdown_inline;
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Commit `reference` (a tag, branch or sha) points to.
+fn git_commit(reference: &str) -> Option<String> {
+    Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{reference}^{{commit}}"))
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Per-circuit medians of the successful, non-anomalous runs in `history`
+/// measured at `commit`.
+pub fn records_at_commit(history: &[BenchRecord], commit: &str) -> BenchResult<Vec<BenchRecord>> {
+    let runs: Vec<BenchRecord> = history
+        .iter()
+        .filter(|r| r.error.is_none() && !r.anomaly && !r.is_expected_failure())
+        .filter(|r| r.env.git_sha.as_deref() == Some(commit))
+        .cloned()
+        .collect();
+    window_baseline(runs, usize::MAX)
+}
+
+/// Records of one side of the changelog: the named baseline `reference`, or
+/// the history runs at the git ref `reference`.
+fn release_records(
+    reference: &str,
+    baselines_dir: &Path,
+    history: Option<&[BenchRecord]>,
+) -> BenchResult<Vec<BenchRecord>> {
+    if let Ok(path) = baseline_cmd::resolve(baselines_dir, reference) {
+        return JsonlWriter::new(&path).read_all();
+    }
+    let Some(history) = history else {
+        return Err(BenchError::Message(format!(
+            "no baseline named {reference} in {} (pass --jsonl to look up git refs in a history)",
+            baselines_dir.display()
+        )));
+    };
+    let commit = git_commit(reference).ok_or_else(|| {
+        BenchError::Message(format!(
+            "{reference} is neither a named baseline nor a git ref"
+        ))
+    })?;
+    let records = records_at_commit(history, &commit)?;
+    if records.is_empty() {
+        return Err(BenchError::Message(format!(
+            "no history runs at {reference} ({commit})"
+        )));
+    }
+    Ok(records)
+}
+
+/// Circuit names in `a` but not in `b`.
+fn missing_from(a: &[BenchRecord], b: &[BenchRecord]) -> BTreeSet<String> {
+    let in_b: BTreeSet<&str> = b.iter().map(|r| r.circuit_name.as_str()).collect();
+    a.iter()
+        .map(|r| r.circuit_name.clone())
+        .filter(|name| !in_b.contains(name.as_str()))
+        .collect()
+}
+
+/// Render the changelog: circuits with significant improvements, then those
+/// with regressions, each listing only the metrics that moved, followed by
+/// added and removed circuits.
+pub fn render_changelog(
+    result: &CompareResult,
+    added: &BTreeSet<String>,
+    removed: &BTreeSet<String>,
+) -> String {
+    let changes = |status: CompareStatus| -> Vec<String> {
+        result
+            .circuits
+            .iter()
+            .filter_map(|c| {
+                let moved: Vec<String> = c
+                    .metrics
+                    .iter()
+                    .filter(|m| m.status == status)
+                    .filter(|m| !result.informational_metrics.contains(&m.metric))
+                    .map(|m| {
+                        format!(
+                            "{} {} → {} ({:+.1}%)",
+                            m.metric,
+                            format_value(m.baseline, &m.metric),
+                            format_value(m.target, &m.metric),
+                            m.percent
+                        )
+                    })
+                    .collect();
+                (!moved.is_empty()).then(|| {
+                    format!(
+                        "- **{}**: {}\n",
+                        escape_markdown_inline(&c.circuit_name),
+                        moved.join(", ")
+                    )
+                })
+            })
+            .collect()
+    };
+    let improvements = changes(CompareStatus::Improvement);
+    let regressions = changes(CompareStatus::Regression);
+
+    let mut sections = vec![format!(
+        "## Performance changes from {} to {}\n",
+        escape_markdown_inline(&result.baseline_ref),
+        escape_markdown_inline(&result.target_ref)
+    )];
+    if improvements.is_empty() && regressions.is_empty() {
+        sections.push(format!(
+            "No changes beyond {:.0}% in {} circuit(s).\n",
+            result.threshold,
+            result.circuits.len()
+        ));
+    }
+    for (title, lines) in [("Improvements", improvements), ("Regressions", regressions)] {
+        if !lines.is_empty() {
+            sections.push(format!("### {title}\n\n{}", lines.concat()));
+        }
+    }
+    for (title, names) in [("New circuits", added), ("Removed circuits", removed)] {
+        if !names.is_empty() {
+            let lines: String = names
+                .iter()
+                .map(|name| format!("- {}\n", escape_markdown_inline(name)))
+                .collect();
+            sections.push(format!("### {title}\n\n{lines}"));
+        }
+    }
+    sections.join("\n")
+}
+
+/// Build the changelog between two releases.
+pub fn changelog(
+    from: &str,
+    to: &str,
+    baselines_dir: &Path,
+    history: Option<&[BenchRecord]>,
+    threshold: f64,
+) -> BenchResult<String> {
+    let old = release_records(from, baselines_dir, history)?;
+    let new = release_records(to, baselines_dir, history)?;
+    let added = missing_from(&new, &old);
+    let removed = missing_from(&old, &new);
+    let result = compare_records(
+        old,
+        new,
+        from,
+        to,
+        threshold,
+        &BTreeMap::new(),
+        &CircuitThresholds::new(),
+        None,
+    )?;
+    Ok(render_changelog(&result, &added, &removed))
+}
+
+pub fn run(
+    from: String,
+    to: String,
+    jsonl: Option<PathBuf>,
+    baselines_dir: PathBuf,
+    threshold: Option<f64>,
+    out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let history = jsonl
+        .map(|path| JsonlWriter::new(&path).read_all())
+        .transpose()?;
+    let markdown = changelog(
+        &from,
+        &to,
+        &baselines_dir,
+        history.as_deref(),
+        threshold.unwrap_or(DEFAULT_THRESHOLD),
+    )?;
+    match out {
+        Some(path) => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).ok();
+            }
+            std::fs::write(&path, markdown).map_err(|e| {
+                BenchError::Message(format!("failed to write {}: {e}", path.display()))
+            })?;
+        }
+        None => print!("{markdown}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+    fn record(circuit: &str, sha: &str, prove_ms: f64, gates: u64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            circuit.to_string(),
+            EnvironmentInfo {
+                git_sha: Some(sha.to_string()),
+                ..EnvironmentInfo::default()
+            },
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r.total_gates = Some(gates);
+        r
+    }
+
+    #[test]
+    fn test_changelog_lists_significant_changes_per_circuit() {
+        let history = vec![
+            record("merkle", "aaa", 1200.0, 5000),
+            record("ecdsa", "aaa", 800.0, 9000),
+            record("keccak", "aaa", 300.0, 100),
+            record("old", "aaa", 10.0, 10),
+            record("merkle", "bbb", 900.0, 5000),
+            record("merkle", "bbb", 950.0, 5000),
+            record("ecdsa", "bbb", 1000.0, 9000),
+            record("keccak", "bbb", 305.0, 100),
+            record("rollup", "bbb", 2000.0, 20000),
+            record("rollup_*v2*", "bbb", 2000.0, 20000),
+        ];
+        let old = records_at_commit(&history, "aaa").unwrap();
+        let new = records_at_commit(&history, "bbb").unwrap();
+        let added = missing_from(&new, &old);
+        let removed = missing_from(&old, &new);
+        let result = compare_records(
+            old,
+            new,
+            "v1.1.0",
+            "v1.2.0",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        )
+        .unwrap();
+        let md = render_changelog(&result, &added, &removed);
+
+        assert!(md.starts_with("## Performance changes from v1.1.0 to v1.2.0\n"));
+        let improvements = md.find("### Improvements").unwrap();
+        let regressions = md.find("### Regressions").unwrap();
+        let merkle = md.find("- **merkle**: prove_ms").unwrap();
+        let ecdsa = md.find("- **ecdsa**: prove_ms").unwrap();
+        assert!(improvements < merkle && merkle < regressions && regressions < ecdsa);
+        assert!(md.contains("(-25.0%)"));
+        assert!(md.contains("(+25.0%)"));
+        // Unchanged circuits and metrics are left out
+        assert!(!md.contains("keccak"));
+        assert!(!md.contains("total_gates"));
+        assert!(md.contains("### New circuits\n\n- rollup\n- rollup\\_\\*v2\\*\n"));
+        assert!(md.contains("### Removed circuits\n\n- old\n"));
+        assert!(md.ends_with("- old\n"));
+    }
+
+    #[test]
+    fn test_changelog_resolves_named_baselines() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, prove_ms) in [("v1", 100.0), ("v2", 100.0)] {
+            let history = dir.path().join(format!("{name}-history.jsonl"));
+            JsonlWriter::new(&history)
+                .append(&record("a", "x", prove_ms, 10))
+                .unwrap();
+            baseline_cmd::freeze(&history, name, dir.path(), 5, false, false).unwrap();
+        }
+        let md = changelog("v1", "v2", dir.path(), None, DEFAULT_THRESHOLD).unwrap();
+        assert!(md.contains("No changes beyond 10% in 1 circuit(s)."));
+
+        let err = changelog("v1", "v9", dir.path(), None, DEFAULT_THRESHOLD).unwrap_err();
+        assert!(err.to_string().contains("--jsonl"));
+    }
+}
@@ -0,0 +1,562 @@
+//! CLI command handler for `check`.
+//!
+//! Checks recorded results against absolute budgets from the `[budgets]`
+//! section of bench-config.toml, without a baseline:
+//!
+//! ```toml
+//! # Applies to every circuit unless overridden
+//! [budgets.default]
+//! prove_ms = 60000
+//!
+//! [budgets.verifier]
+//! total_gates = 300000
+//! proof_size_bytes = 16384
+//! prove_ms_p95 = 5000
+//! ```
+//!
+//! Keys are metric names as in alert rules (`prove_ms`, `prove_ms_p95`,
+//! `gates`, `proof_size_bytes`, ...) and each value is the largest allowed.
+//! The latest record of every circuit (and params variant) is checked.
+//!
+//! `evm_gas` and `calldata_bytes` budget the on-chain verifier instead. They
+//! are measured by EVM verification of the circuit's Foundry project (its
+//! `[[circuit]]` path), or read from a saved `evm-verify --json` report.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::backend::{EvmBackend, EvmVerifyOutput};
+use crate::bench::config::load_bench_config;
+use crate::core::schema::BenchRecord;
+use crate::report::alerts::{known_metrics, metric_value};
+use crate::report::format_value;
+use crate::report::regression::escape_markdown;
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult, EvmVerifyReport};
+
+/// Budget key applying to circuits without their own entry.
+pub const DEFAULT_BUDGET_KEY: &str = "default";
+
+/// `[budgets.<circuit>]` tables: metric name to the largest allowed value.
+pub type Budgets = BTreeMap<String, BTreeMap<String, f64>>;
+
+/// Budget metrics measured by EVM verification rather than read from records.
+pub const EVM_METRICS: [&str; 2] = ["evm_gas", "calldata_bytes"];
+
+fn is_evm_metric(metric: &str) -> bool {
+    EVM_METRICS.contains(&metric)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BudgetConfig {
+    #[serde(default)]
+    budgets: Budgets,
+}
+
+/// Read the `[budgets]` section of a bench config, rejecting unknown metrics.
+pub fn load_budgets(path: &Path) -> BenchResult<Budgets> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+    let config: BudgetConfig = toml::from_str(&text)
+        .map_err(|e| BenchError::Message(format!("failed to parse {}: {e}", path.display())))?;
+    let mut known = known_metrics();
+    known.extend(EVM_METRICS.iter().map(|m| m.to_string()));
+    for (circuit, metrics) in &config.budgets {
+        if let Some(metric) = metrics.keys().find(|m| !known.contains(m)) {
+            return Err(BenchError::Message(format!(
+                "unknown metric '{metric}' in [budgets.{circuit}]; known metrics: {}",
+                known.join(", ")
+            )));
+        }
+        if circuit == DEFAULT_BUDGET_KEY {
+            if let Some(metric) = metrics.keys().find(|m| is_evm_metric(m)) {
+                return Err(BenchError::Message(format!(
+                    "'{metric}' needs a circuit's Foundry project; move it from \
+                     [budgets.default] to [budgets.<circuit>]"
+                )));
+            }
+        }
+    }
+    Ok(config.budgets)
+}
+
+/// Outcome of one budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetStatus {
+    Within,
+    Exceeded,
+    /// The record has no value for the metric
+    NotMeasured,
+}
+
+/// One budget checked against one record.
+#[derive(Debug, Clone)]
+pub struct BudgetCheck {
+    /// Circuit name, with `[params]` for parameterized variants
+    pub circuit: String,
+    pub metric: String,
+    pub value: Option<f64>,
+    pub budget: f64,
+    pub status: BudgetStatus,
+}
+
+impl BudgetCheck {
+    fn new(circuit: String, metric: &str, value: Option<f64>, budget: f64) -> Self {
+        let status = match value {
+            None => BudgetStatus::NotMeasured,
+            Some(v) if v > budget => BudgetStatus::Exceeded,
+            Some(_) => BudgetStatus::Within,
+        };
+        BudgetCheck {
+            circuit,
+            metric: metric.to_string(),
+            value,
+            budget,
+            status,
+        }
+    }
+}
+
+/// Gas and calldata of one circuit's EVM verifier.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvmMeasurement {
+    pub gas_used: Option<f64>,
+    pub calldata_bytes: Option<f64>,
+}
+
+impl EvmMeasurement {
+    fn value(&self, metric: &str) -> Option<f64> {
+        match metric {
+            "evm_gas" => self.gas_used,
+            "calldata_bytes" => self.calldata_bytes,
+            _ => None,
+        }
+    }
+}
+
+impl From<EvmVerifyOutput> for EvmMeasurement {
+    fn from(out: EvmVerifyOutput) -> Self {
+        EvmMeasurement {
+            gas_used: out.gas_used.map(|v| v as f64),
+            calldata_bytes: out.calldata_bytes.map(|v| v as f64),
+        }
+    }
+}
+
+impl From<EvmVerifyReport> for EvmMeasurement {
+    fn from(report: EvmVerifyReport) -> Self {
+        EvmMeasurement {
+            gas_used: Some(report.gas_used as f64),
+            calldata_bytes: report.calldata_bytes.map(|v| v as f64),
+        }
+    }
+}
+
+/// Circuits with EVM budgets, which need an EVM verification.
+pub fn evm_circuits(budgets: &Budgets) -> Vec<&str> {
+    budgets
+        .iter()
+        .filter(|(_, metrics)| metrics.keys().any(|m| is_evm_metric(m)))
+        .map(|(circuit, _)| circuit.as_str())
+        .collect()
+}
+
+/// Latest successful record of each circuit/params pair, by timestamp. A
+/// failed run measured nothing, so it neither passes nor fails a budget, and
+/// an expected failure only measured the time to fail.
+fn latest_records(records: &[BenchRecord]) -> Vec<&BenchRecord> {
+    let mut latest: BTreeMap<(&str, Option<u64>), &BenchRecord> = BTreeMap::new();
+    for record in records
+        .iter()
+        .filter(|r| r.error.is_none() && !r.is_expected_failure())
+    {
+        let key = (record.circuit_name.as_str(), record.params);
+        match latest.get(&key) {
+            Some(prev) if prev.timestamp >= record.timestamp => {}
+            _ => {
+                latest.insert(key, record);
+            }
+        }
+    }
+    latest.into_values().collect()
+}
+
+/// Budgets of one circuit: its own entry over the `default` one, metric by
+/// metric.
+pub fn circuit_budgets(budgets: &Budgets, circuit: &str) -> BTreeMap<String, f64> {
+    let mut merged = budgets.get(DEFAULT_BUDGET_KEY).cloned().unwrap_or_default();
+    if let Some(own) = budgets.get(circuit) {
+        merged.extend(own.iter().map(|(k, v)| (k.clone(), *v)));
+    }
+    merged
+}
+
+/// Check the latest record of every circuit against its budgets (see
+/// `circuit_budgets`).
+pub fn check_budgets(records: &[BenchRecord], budgets: &Budgets) -> Vec<BudgetCheck> {
+    let mut checks = Vec::new();
+    for record in latest_records(records) {
+        let merged = circuit_budgets(budgets, &record.circuit_name);
+        let circuit = match record.params {
+            Some(p) => format!("{}[{p}]", record.circuit_name),
+            None => record.circuit_name.clone(),
+        };
+        for (metric, &budget) in merged.iter().filter(|(m, _)| !is_evm_metric(m)) {
+            let value = metric_value(record, metric);
+            checks.push(BudgetCheck::new(circuit.clone(), metric, value, budget));
+        }
+    }
+    checks
+}
+
+/// Check the EVM budgets of every circuit in `measurements`.
+pub fn check_evm_budgets(
+    budgets: &Budgets,
+    measurements: &BTreeMap<String, EvmMeasurement>,
+) -> Vec<BudgetCheck> {
+    let mut checks = Vec::new();
+    for (circuit, measurement) in measurements {
+        let Some(metrics) = budgets.get(circuit) else {
+            continue;
+        };
+        for (metric, &budget) in metrics.iter().filter(|(m, _)| is_evm_metric(m)) {
+            let value = measurement.value(metric);
+            checks.push(BudgetCheck::new(circuit.clone(), metric, value, budget));
+        }
+    }
+    checks
+}
+
+/// Measure `circuit` from its saved report, or by running EVM verification
+/// in its `[[circuit]]` path.
+fn measure_evm(
+    circuit: &str,
+    reports: &BTreeMap<String, PathBuf>,
+    config: &Path,
+    forge_bin: Option<&Path>,
+) -> BenchResult<EvmMeasurement> {
+    if let Some(path) = reports.get(circuit) {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+        let report: EvmVerifyReport = serde_json::from_str(&text).map_err(|e| {
+            BenchError::Message(format!("invalid evm-verify report {}: {e}", path.display()))
+        })?;
+        return Ok(report.into());
+    }
+    let specs = load_bench_config(config)?;
+    let spec = specs.iter().find(|s| s.name == circuit).ok_or_else(|| {
+        BenchError::Message(format!(
+            "EVM budgets for '{circuit}' need an --evm-report or a [[circuit]] entry with its Foundry project"
+        ))
+    })?;
+    eprintln!("Running EVM verification for {circuit}...");
+    let mut evm = EvmBackend::new(&spec.path);
+    if let Some(forge) = forge_bin {
+        evm = evm.with_forge_bin(forge);
+    }
+    Ok(evm.measure()?.into())
+}
+
+/// Render the checks as a table: violations only unless `all`.
+pub fn format_table(checks: &[BudgetCheck], markdown: bool, all: bool) -> String {
+    let rows: Vec<[String; 5]> = checks
+        .iter()
+        .filter(|c| all || c.status != BudgetStatus::Within)
+        .map(|c| {
+            let status = match c.status {
+                BudgetStatus::Within => "ok",
+                BudgetStatus::Exceeded => "EXCEEDED",
+                BudgetStatus::NotMeasured => "not measured",
+            };
+            [
+                c.circuit.clone(),
+                c.metric.clone(),
+                c.value
+                    .map(|v| format_value(v, &c.metric))
+                    .unwrap_or_else(|| "-".to_string()),
+                format_value(c.budget, &c.metric),
+                status.to_string(),
+            ]
+        })
+        .collect();
+    let header = ["Circuit", "Metric", "Value", "Budget", "Status"].map(String::from);
+    let mut out = String::new();
+    if markdown {
+        out.push_str(&format!("| {} |\n", header.join(" | ")));
+        out.push_str("|---|---|---:|---:|---|\n");
+        for row in &rows {
+            let cells: Vec<String> = row.iter().map(|c| escape_markdown(c)).collect();
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+        }
+        return out;
+    }
+    let mut widths = header.clone().map(|h| h.len());
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, w)| format!("{cell:<w$}"))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Run `check`: print the violations (or every check with `all`) and fail
+/// when any budget is exceeded.
+///
+/// `evm_reports` are `CIRCUIT=FILE` pairs of saved `evm-verify` reports;
+/// other circuits with EVM budgets are verified with forge.
+pub fn run(
+    config: PathBuf,
+    jsonl: Option<PathBuf>,
+    format: String,
+    all: bool,
+    evm_reports: Vec<String>,
+    forge_bin: Option<PathBuf>,
+) -> BenchResult<()> {
+    let markdown = match format.as_str() {
+        "text" => false,
+        "markdown" => true,
+        other => {
+            return Err(BenchError::Message(format!(
+                "unknown format '{other}' (expected text or markdown)"
+            )));
+        }
+    };
+    let budgets = load_budgets(&config)?;
+    if budgets.is_empty() {
+        return Err(BenchError::Message(format!(
+            "no [budgets] in {}",
+            config.display()
+        )));
+    }
+    let reports = evm_reports
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .map(|(circuit, file)| (circuit.to_string(), PathBuf::from(file)))
+                .ok_or_else(|| {
+                    BenchError::Message(format!(
+                        "invalid --evm-report '{pair}' (expected CIRCUIT=FILE)"
+                    ))
+                })
+        })
+        .collect::<BenchResult<BTreeMap<_, _>>>()?;
+
+    let mut checks = match &jsonl {
+        Some(jsonl) => check_budgets(&JsonlWriter::new(jsonl).read_all()?, &budgets),
+        None if budgets
+            .values()
+            .flat_map(|m| m.keys())
+            .all(|m| is_evm_metric(m)) =>
+        {
+            Vec::new()
+        }
+        None => {
+            return Err(BenchError::Message(
+                "--jsonl is required to check budgets other than evm_gas and calldata_bytes"
+                    .to_string(),
+            ));
+        }
+    };
+    let mut measurements = BTreeMap::new();
+    for circuit in evm_circuits(&budgets) {
+        let measurement = measure_evm(circuit, &reports, &config, forge_bin.as_deref())?;
+        measurements.insert(circuit.to_string(), measurement);
+    }
+    checks.extend(check_evm_budgets(&budgets, &measurements));
+    let exceeded = checks
+        .iter()
+        .filter(|c| c.status == BudgetStatus::Exceeded)
+        .count();
+    let not_measured = checks
+        .iter()
+        .filter(|c| c.status == BudgetStatus::NotMeasured)
+        .count();
+
+    if all || exceeded + not_measured > 0 {
+        print!("{}", format_table(&checks, markdown, all));
+    }
+    if not_measured > 0 {
+        eprintln!("warning: {not_measured} budgeted metric(s) not measured");
+    }
+    if exceeded > 0 {
+        return Err(BenchError::Message(format!(
+            "{exceeded} of {} budget(s) exceeded",
+            checks.len()
+        )));
+    }
+    eprintln!("All {} budget(s) met", checks.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig, TimingStat};
+
+    fn record(name: &str, timestamp: &str, gates: u64, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            name.to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "mock".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.timestamp = timestamp.to_string();
+        r.total_gates = Some(gates);
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r
+    }
+
+    fn budgets(toml: &str) -> Budgets {
+        toml::from_str::<BudgetConfig>(toml).unwrap().budgets
+    }
+
+    #[test]
+    fn test_latest_record_is_checked_with_default_budgets() {
+        let records = vec![
+            record("verifier", "2026-01-02T00:00:00Z", 310_000, 900.0),
+            record("verifier", "2026-01-01T00:00:00Z", 250_000, 900.0),
+            record("hash", "2026-01-01T00:00:00Z", 1_000, 2_000.0),
+        ];
+        let budgets = budgets(
+            "[budgets.default]\nprove_ms = 1000\n\n[budgets.verifier]\ngates = 300000\nproof_size_bytes = 2048\n",
+        );
+        let checks = check_budgets(&records, &budgets);
+        let status = |circuit: &str, metric: &str| {
+            checks
+                .iter()
+                .find(|c| c.circuit == circuit && c.metric == metric)
+                .map(|c| c.status)
+        };
+        assert_eq!(status("verifier", "gates"), Some(BudgetStatus::Exceeded));
+        assert_eq!(status("verifier", "prove_ms"), Some(BudgetStatus::Within));
+        assert_eq!(
+            status("verifier", "proof_size_bytes"),
+            Some(BudgetStatus::NotMeasured)
+        );
+        assert_eq!(status("hash", "prove_ms"), Some(BudgetStatus::Exceeded));
+        assert_eq!(status("hash", "gates"), None);
+
+        // A newer failed run doesn't hide the latest measurement
+        let mut failed = record("hash", "2026-01-03T00:00:00Z", 1_000, 0.0);
+        failed.prove_stats = None;
+        failed.error = Some(crate::core::RunError {
+            message: "bb crashed".to_string(),
+            log_path: None,
+        });
+        // Nor does a run that was meant to fail
+        let mut expected = record("hash", "2026-01-04T00:00:00Z", 1_000, 5.0);
+        expected.status = Some(crate::core::RunStatus::ExpectedFailure);
+        let mut with_failure = records.clone();
+        with_failure.push(failed);
+        with_failure.push(expected);
+        let checks = check_budgets(&with_failure, &budgets);
+        let hash = checks
+            .iter()
+            .find(|c| c.circuit == "hash" && c.metric == "prove_ms")
+            .unwrap();
+        assert_eq!(hash.status, BudgetStatus::Exceeded);
+    }
+
+    #[test]
+    fn test_format_table_lists_violations() {
+        let records = vec![record("verifier", "2026-01-01T00:00:00Z", 310_000, 900.0)];
+        let checks = check_budgets(
+            &records,
+            &budgets("[budgets.verifier]\ngates = 300000\nprove_ms = 1000\n"),
+        );
+        assert_eq!(
+            format_table(&checks, false, false),
+            "Circuit   Metric  Value   Budget  Status\nverifier  gates   310.0K  300.0K  EXCEEDED\n"
+        );
+        let md = format_table(&checks, true, true);
+        assert!(md.contains("| verifier | gates | 310.0K | 300.0K | EXCEEDED |"));
+        assert!(md.contains("| verifier | prove_ms | 900ms | 1.00s | ok |"));
+
+        let records = vec![record("a|b", "2026-01-01T00:00:00Z", 310_000, 900.0)];
+        let checks = check_budgets(&records, &budgets("[budgets.default]\ngates = 300000\n"));
+        assert!(format_table(&checks, true, false).contains("| a\\|b | gates |"));
+        assert!(format_table(&checks, false, false).contains("a|b "));
+    }
+
+    #[test]
+    fn test_evm_budgets_use_measurements() {
+        let budgets =
+            budgets("[budgets.verifier]\nevm_gas = 300000\ncalldata_bytes = 2048\ngates = 1000\n");
+        assert_eq!(evm_circuits(&budgets), vec!["verifier"]);
+        // Record checks leave EVM metrics alone
+        let records = vec![record("verifier", "2026-01-01T00:00:00Z", 900, 1.0)];
+        let checks = check_budgets(&records, &budgets);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].metric, "gates");
+
+        let measurements = BTreeMap::from([(
+            "verifier".to_string(),
+            EvmMeasurement {
+                gas_used: Some(312_000.0),
+                calldata_bytes: None,
+            },
+        )]);
+        let checks = check_evm_budgets(&budgets, &measurements);
+        let status: Vec<_> = checks
+            .iter()
+            .map(|c| (c.metric.as_str(), c.status))
+            .collect();
+        assert_eq!(
+            status,
+            vec![
+                ("calldata_bytes", BudgetStatus::NotMeasured),
+                ("evm_gas", BudgetStatus::Exceeded),
+            ]
+        );
+        assert!(format_table(&checks, false, false).contains("312.0K  300.0K  EXCEEDED"));
+    }
+
+    #[test]
+    fn test_evm_budgets_from_saved_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("evm.json");
+        std::fs::write(
+            &report,
+            r#"{"name":"evm-verify","timestamp":"2026-01-01T00:00:00Z","noir_version":"n/a","artifact_path":"x","cli_args":[],"gas_used":250000,"calldata_bytes":1000,"est_latency_ms":200,"backend":{"name":"foundry","version":null},"system":null}"#,
+        )
+        .unwrap();
+        let reports = BTreeMap::from([("verifier".to_string(), report)]);
+        let m = measure_evm("verifier", &reports, Path::new("missing.toml"), None).unwrap();
+        assert_eq!(m.gas_used, Some(250_000.0));
+        assert_eq!(m.calldata_bytes, Some(1000.0));
+    }
+
+    #[test]
+    fn test_load_budgets_rejects_evm_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench-config.toml");
+        std::fs::write(&path, "[budgets.default]\nevm_gas = 300000\n").unwrap();
+        let err = load_budgets(&path).unwrap_err().to_string();
+        assert!(err.contains("move it from [budgets.default]"));
+    }
+
+    #[test]
+    fn test_load_budgets_rejects_unknown_metrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench-config.toml");
+        std::fs::write(&path, "[budgets.verifier]\ngas = 300000\n").unwrap();
+        let err = load_budgets(&path).unwrap_err().to_string();
+        assert!(err.contains("unknown metric 'gas' in [budgets.verifier]"));
+    }
+}
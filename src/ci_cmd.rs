@@ -4,19 +4,35 @@
 //! and outputs results suitable for CI environments.
 
 use std::collections::BTreeMap;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use ed25519_dalek::SigningKey;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::backend::{BarretenbergBackend, BarretenbergConfig};
-use crate::compare_cmd::{self, CompareResult, DEFAULT_THRESHOLD, to_regression_report};
+use crate::backend::{BarretenbergBackend, BarretenbergConfig, load_backend_args};
+use crate::bench::config::{CiConfig, load_bench_config};
+use crate::bench::hooks::warn_ignored_hooks;
+use crate::check_cmd::{Budgets, load_budgets};
+use crate::compare_cmd::{
+    self, CircuitThresholds, CompareResult, DEFAULT_THRESHOLD, attach_budgets, to_regression_report,
+};
+use crate::core::signing::{SignaturePolicy, load_signing_key, sign_record, signed_line};
+use crate::core::{BenchRecord, RunError, SCHEMA_VERSION};
+use crate::core::{annotations, rounding};
 use crate::engine::provenance;
 use crate::engine::{NargoToolchain, ProveInputs, full_benchmark};
-use crate::report::{render_markdown as report_render_markdown, write_html as report_write_html};
+use crate::report::alerts::{self, AlertAction, AlertRule, AlertsConfig, FiredAlert};
+use crate::report::github_check::{CHECK_NAME, CheckAnnotation, CheckRun, report_annotations};
+use crate::report::reporter::{self, ReportData};
+use crate::report::{
+    JunitCase, JunitOutcome, JunitSuite, MetricSelection, render_junit,
+    render_markdown as report_render_markdown, render_tap, suites_from_report,
+};
+use crate::storage::{
+    JsonlWriter, ParseLimits, TRIAGE_DIR_NAME, TriageBundle, jsonl, read_untrusted,
+};
 use crate::{BenchError, BenchResult};
 
 const DEFAULT_CONFIG: &str = "bench-config.toml";
@@ -24,34 +40,13 @@ const DEFAULT_BASELINE: &str = ".noir-bench-baseline.jsonl";
 const DEFAULT_CI_ITERATIONS: usize = 3;
 const DEFAULT_CI_WARMUP: usize = 1;
 
-/// CI-specific configuration from bench-config.toml
-#[derive(Debug, Clone, Deserialize, Default)]
-pub struct CiConfig {
-    /// Circuits to run in CI (subset of full benchmark suite)
-    #[serde(default)]
-    pub circuits: Vec<String>,
-    /// Path to baseline JSONL file
-    #[serde(default)]
-    pub baseline_file: Option<String>,
-    /// Regression threshold percentage
-    #[serde(default)]
-    pub threshold_percent: Option<f64>,
-    /// Number of iterations for CI runs
-    #[serde(default)]
-    pub iterations: Option<usize>,
-    /// Number of warmup iterations
-    #[serde(default)]
-    pub warmup: Option<usize>,
-    /// Per-metric regression thresholds
-    #[serde(default)]
-    pub thresholds: BTreeMap<String, f64>,
-}
-
 /// Full config including CI section
 #[derive(Debug, Deserialize)]
 struct FullConfig {
     #[serde(default)]
     pub ci: Option<CiConfig>,
+    #[serde(default)]
+    pub alerts: Option<AlertsConfig>,
     #[serde(rename = "circuit", default)]
     pub circuits: Vec<RawCircuit>,
 }
@@ -73,6 +68,9 @@ pub struct CiCircuitResult {
     pub gates: Option<u64>,
     pub proof_size_bytes: Option<u64>,
     pub status: String,
+    /// Triage bundle collected for a failed run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triage_bundle: Option<String>,
 }
 
 /// Full CI run result
@@ -83,10 +81,59 @@ pub struct CiRunResult {
     pub default_threshold: f64,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub metric_thresholds: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub circuit_thresholds: CircuitThresholds,
     pub comparison: Option<CompareResult>,
+    /// Alert rules that fired for this run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<FiredAlert>,
     pub exit_code: i32,
 }
 
+/// Shard selector parsed from `--shard i/n` (1-based index).
+///
+/// Targets are assigned round-robin over the deterministically sorted target
+/// list, so every shard gets a stable subset and the union of all shards
+/// covers each target exactly once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ShardSpec {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl ShardSpec {
+    /// Parse a shard spec of the form `i/n` where `1 <= i <= n`.
+    pub fn parse(s: &str) -> BenchResult<Self> {
+        let (index, count) = s
+            .split_once('/')
+            .ok_or_else(|| BenchError::Message(format!("invalid shard '{s}': expected i/n")))?;
+        let index: usize = index
+            .trim()
+            .parse()
+            .map_err(|_| BenchError::Message(format!("invalid shard index in '{s}'")))?;
+        let count: usize = count
+            .trim()
+            .parse()
+            .map_err(|_| BenchError::Message(format!("invalid shard count in '{s}'")))?;
+        if count == 0 || index == 0 || index > count {
+            return Err(BenchError::Message(format!(
+                "invalid shard '{s}': index must be in 1..={count}"
+            )));
+        }
+        Ok(ShardSpec { index, count })
+    }
+
+    fn includes(&self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+}
+
+impl std::fmt::Display for ShardSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
 fn now_string() -> String {
     time::OffsetDateTime::now_utc()
         .format(&time::format_description::well_known::Rfc3339)
@@ -128,6 +175,19 @@ fn expand_ci_targets(
     targets
 }
 
+/// Keep only the targets belonging to `shard` (all targets when `None`).
+fn select_shard<T>(targets: Vec<T>, shard: Option<ShardSpec>) -> Vec<T> {
+    match shard {
+        Some(spec) => targets
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| spec.includes(*i))
+            .map(|(_, t)| t)
+            .collect(),
+        None => targets,
+    }
+}
+
 /// Load CI config from bench-config.toml
 fn load_ci_config(
     path: &PathBuf,
@@ -147,13 +207,79 @@ fn load_ci_config(
     Ok((ci_config, circuits))
 }
 
+/// Environment variables of each configured circuit, from `[backend.env]`
+/// and its `env` table (see `bench::config`); none if the file is missing.
+fn load_circuit_env(path: &PathBuf) -> BenchResult<BTreeMap<String, BTreeMap<String, String>>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    // Read when ci runs the config's circuits
+    warn_ignored_hooks(path, "ci");
+    Ok(load_bench_config(path)?
+        .into_iter()
+        .map(|spec| (spec.name, spec.env))
+        .collect())
+}
+
+/// Parse the `[alerts]` rules from the config file (none if it is missing).
+fn load_alert_rules(path: &PathBuf) -> BenchResult<Vec<AlertRule>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let s = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read config: {e}")))?;
+    let cfg: FullConfig = toml::from_str(&s)
+        .map_err(|e| BenchError::Message(format!("failed to parse config: {e}")))?;
+    cfg.alerts.unwrap_or_default().rules()
+}
+
+/// Collect a triage bundle for a failed run of `inputs` under `dir`.
+/// Returns its path, or `None` (with a warning) if it couldn't be written.
+fn write_triage_bundle(
+    dir: &Path,
+    inputs: &ProveInputs,
+    params: Option<u64>,
+    error: &str,
+    log: Option<&Path>,
+    backend_args: &[String],
+    record: &serde_json::Value,
+) -> Option<String> {
+    let mut bundle = TriageBundle::new(&inputs.circuit_name, params, error)
+        .with_artifact(&inputs.artifact_path)
+        .with_backend_args(backend_args)
+        .with_record(record);
+    if let Some(prover_toml) = &inputs.prover_toml {
+        bundle = bundle.with_prover_toml(prover_toml);
+    }
+    if let Some(log) = log {
+        bundle = bundle.with_log(log);
+    }
+    match bundle.write(dir) {
+        Ok(path) => {
+            eprintln!("  Triage bundle: {}", path.display());
+            Some(path.display().to_string())
+        }
+        Err(e) => {
+            eprintln!("Warning: failed to write triage bundle: {e}");
+            None
+        }
+    }
+}
+
 /// Run benchmarks for CI circuits using engine workflow.
 fn run_ci_benchmarks(
     circuits: &[(String, PathBuf, Option<Vec<u64>>)],
     ci_circuits: &[String],
     iterations: usize,
     warmup: usize,
+    shard: Option<ShardSpec>,
     output_path: &PathBuf,
+    signing_key: Option<&SigningKey>,
+    annotations: &BTreeMap<String, String>,
+    gate_cache: Option<&PathBuf>,
+    key_cache: Option<&PathBuf>,
+    backend_args: Vec<String>,
+    circuit_env: &BTreeMap<String, BTreeMap<String, String>>,
 ) -> BenchResult<Vec<CiCircuitResult>> {
     // Ensure output directory exists
     if let Some(parent) = output_path.parent() {
@@ -162,23 +288,61 @@ fn run_ci_benchmarks(
         }
     }
 
-    let mut jsonl = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(output_path)
+    jsonl::write_lines(output_path, &[], true)
         .map_err(|e| BenchError::Message(format!("failed to create output file: {e}")))?;
 
-    // Create toolchain and backend using engine workflow
-    let toolchain = NargoToolchain::new();
-    let bb_config = BarretenbergConfig::new("bb").with_timeout(Duration::from_secs(24 * 60 * 60));
-    let backend = BarretenbergBackend::new(bb_config);
+    // Create toolchain and backend using engine workflow; output of failed
+    // commands and triage bundles of failed runs are kept next to the records
+    let output_dir = output_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let log_dir = output_dir.join("logs");
+    let triage_dir = output_dir.join(TRIAGE_DIR_NAME);
+    let mut bb_config = BarretenbergConfig::new("bb")
+        .with_timeout(Duration::from_secs(24 * 60 * 60))
+        .with_args(backend_args.clone())
+        .with_log_dir(&log_dir);
+    if let Some(dir) = key_cache {
+        bb_config = bb_config.with_key_cache(dir);
+    }
+    BarretenbergBackend::new(bb_config.clone()).warn_on_args();
 
     let mut results = Vec::new();
     let timestamp = now_string();
+    let write_record = |mut record: serde_json::Value| -> BenchResult<()> {
+        if !annotations.is_empty() {
+            let mut metadata: BTreeMap<String, String> =
+                serde_json::from_value(record["metadata"].take()).unwrap_or_default();
+            annotations::apply(&mut metadata, annotations);
+            record["metadata"] = json!(metadata);
+        }
+        rounding::round_json(&mut record);
+        let line = match signing_key {
+            Some(key) => {
+                let mut signed: BenchRecord = serde_json::from_value(record)
+                    .map_err(|e| BenchError::Message(format!("invalid CI record: {e}")))?;
+                sign_record(&mut signed, key)?;
+                signed_line(&signed)?
+            }
+            None => serde_json::to_string(&record).unwrap(),
+        };
+        jsonl::write_lines(output_path, &[line], false)
+            .map_err(|e| BenchError::Message(format!("failed to write record: {e}")))
+    };
 
     // Expand and sort targets deterministically (circuit, path, params)
-    let targets = expand_ci_targets(circuits, ci_circuits);
+    let all_targets = expand_ci_targets(circuits, ci_circuits);
+    let total_targets = all_targets.len();
+    let targets = select_shard(all_targets, shard);
+
+    if let Some(spec) = shard {
+        eprintln!(
+            "Shard {}: running {} of {} target(s)",
+            spec,
+            targets.len(),
+            total_targets
+        );
+    }
 
     if targets.is_empty() {
         eprintln!("Warning: No matching circuits found for CI run");
@@ -197,12 +361,53 @@ fn run_ci_benchmarks(
         if let Some(pt) = prover_toml {
             inputs = inputs.with_prover_toml(pt);
         }
+        if let Some(cache) = gate_cache {
+            inputs = inputs.with_gate_cache(cache);
+        }
+
+        // The circuit's environment variables apply to its nargo and bb runs
+        let env = circuit_env.get(&name).cloned().unwrap_or_default();
+        let toolchain = NargoToolchain::new()
+            .with_log_dir(&log_dir)
+            .with_env(env.clone());
+        let backend = BarretenbergBackend::new(bb_config.clone().with_env(env.clone()));
 
         // Run full benchmark using engine workflow
         let bench_result = match full_benchmark(&toolchain, &backend, &inputs, warmup, iterations) {
             Ok(r) => r,
             Err(e) => {
                 eprintln!("  Benchmark failed: {e}");
+                // Keep a record of the failure so history shows it
+                let mut record = json!({
+                    "schema_version": SCHEMA_VERSION,
+                    "record_id": format!("ci-{}-{}", name, timestamp.replace([':', '-', 'T', 'Z'], "")),
+                    "timestamp": timestamp,
+                    "circuit_name": name,
+                    "params": params,
+                    "env": { "os": std::env::consts::OS },
+                    "backend": { "name": "barretenberg" },
+                    "config": {
+                        "warmup_iterations": warmup,
+                        "measured_iterations": iterations
+                    },
+                    "error": RunError::from_error(&e)
+                });
+                if !env.is_empty() {
+                    record["config"]["env"] = json!(env);
+                }
+                let triage_bundle = write_triage_bundle(
+                    &triage_dir,
+                    &inputs,
+                    params,
+                    &e.to_string(),
+                    e.log_path(),
+                    &backend_args,
+                    &record,
+                );
+                if let Some(bundle) = &triage_bundle {
+                    record["metadata"] = json!({ "triage_bundle": bundle });
+                }
+                write_record(record)?;
                 results.push(CiCircuitResult {
                     circuit_name: name.clone(),
                     params,
@@ -210,6 +415,7 @@ fn run_ci_benchmarks(
                     gates: None,
                     proof_size_bytes: None,
                     status: "failed".to_string(),
+                    triage_bundle,
                 });
                 continue;
             }
@@ -227,11 +433,12 @@ fn run_ci_benchmarks(
         };
 
         // Write JSONL record (compatible with BenchRecord schema)
-        let record = json!({
-            "schema_version": 1,
+        let mut record = json!({
+            "schema_version": SCHEMA_VERSION,
             "record_id": format!("ci-{}-{}", name, timestamp.replace([':', '-', 'T', 'Z'], "")),
             "timestamp": timestamp,
             "circuit_name": name,
+            "params": params,
             "env": { "os": std::env::consts::OS },
             "backend": { "name": "barretenberg" },
             "config": {
@@ -244,13 +451,33 @@ fn run_ci_benchmarks(
                 "min_ms": prove_stats.map(|s| s.min_ms).unwrap_or(0.0),
                 "max_ms": prove_stats.map(|s| s.max_ms).unwrap_or(0.0)
             },
+            "gates_stats": bench_result.record.gates_stats,
             "total_gates": gates,
             "acir_opcodes": bench_result.acir_opcodes,
             "proof_size_bytes": proof_size,
-            "peak_rss_mb": bench_result.record.peak_rss_mb
+            "peak_rss_mb": bench_result.record.peak_rss_mb,
+            "fingerprints": bench_result.record.fingerprints
         });
-        writeln!(jsonl, "{}", serde_json::to_string(&record).unwrap())
-            .map_err(|e| BenchError::Message(format!("failed to write record: {e}")))?;
+        if !env.is_empty() {
+            record["config"]["env"] = json!(env);
+        }
+        let triage_bundle = if bench_result.verify_success {
+            None
+        } else {
+            write_triage_bundle(
+                &triage_dir,
+                &inputs,
+                params,
+                "proof verification failed",
+                None,
+                &backend_args,
+                &record,
+            )
+        };
+        if let Some(bundle) = &triage_bundle {
+            record["metadata"] = json!({ "triage_bundle": bundle });
+        }
+        write_record(record)?;
 
         results.push(CiCircuitResult {
             circuit_name: name.clone(),
@@ -259,6 +486,7 @@ fn run_ci_benchmarks(
             gates,
             proof_size_bytes: proof_size,
             status: status.to_string(),
+            triage_bundle,
         });
 
         eprintln!(
@@ -289,7 +517,7 @@ fn candidate_prover_toml_paths(path: &PathBuf, params: Option<u64>) -> Vec<PathB
 }
 
 /// Find Prover.toml for a circuit path.
-fn find_prover_toml(path: &PathBuf, params: Option<u64>) -> Option<PathBuf> {
+pub(crate) fn find_prover_toml(path: &PathBuf, params: Option<u64>) -> Option<PathBuf> {
     candidate_prover_toml_paths(path, params)
         .into_iter()
         .find(|cand| cand.exists())
@@ -315,6 +543,14 @@ fn format_markdown(result: &CiRunResult) -> String {
     for (metric, threshold) in &result.metric_thresholds {
         out.push_str(&format!("| {} | {:.1}% |\n", metric, threshold));
     }
+    for (circuit, thresholds) in &result.circuit_thresholds {
+        for (metric, threshold) in thresholds {
+            out.push_str(&format!(
+                "| {} ({}) | {:.1}% |\n",
+                metric, circuit, threshold
+            ));
+        }
+    }
     out.push_str("\n");
 
     // Benchmark results table
@@ -353,6 +589,25 @@ fn format_markdown(result: &CiRunResult) -> String {
         ));
     }
 
+    // Where to find what is needed to reproduce each failure locally
+    let triaged: Vec<&CiCircuitResult> = sorted_circuits
+        .iter()
+        .filter(|c| c.triage_bundle.is_some())
+        .collect();
+    if !triaged.is_empty() {
+        out.push_str("\n### Failure Triage\n\n");
+        for c in triaged {
+            let target = match c.params {
+                Some(p) => format!("{} (params={p})", c.circuit_name),
+                None => c.circuit_name.clone(),
+            };
+            out.push_str(&format!(
+                "- {target}: `{}`\n",
+                c.triage_bundle.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
     // Comparison results if available
     if let Some(comparison) = &result.comparison {
         let mut comparison_circuits = comparison.circuits.clone();
@@ -418,31 +673,385 @@ fn format_markdown(result: &CiRunResult) -> String {
     out
 }
 
+fn load_ci_config_or_default(
+    config_path: &PathBuf,
+) -> BenchResult<(CiConfig, Vec<(String, PathBuf, Option<Vec<u64>>)>)> {
+    if config_path.exists() {
+        load_ci_config(config_path)
+    } else {
+        eprintln!(
+            "Warning: Config file not found at {}",
+            config_path.display()
+        );
+        Ok((CiConfig::default(), Vec::new()))
+    }
+}
+
+/// Metric selection from `--metrics`/`--informational-metrics`, falling
+/// back to the config's when neither flag is given.
+fn resolve_metric_selection(
+    ci_config: &CiConfig,
+    metrics: Vec<String>,
+    informational_metrics: Vec<String>,
+) -> BenchResult<MetricSelection> {
+    let selection = if metrics.is_empty() && informational_metrics.is_empty() {
+        ci_config.metric_selection()
+    } else {
+        MetricSelection::new(&metrics, &informational_metrics)
+    };
+    compare_cmd::check_metric_selection(&selection)?;
+    Ok(selection)
+}
+
+/// Compare a results JSONL against the baseline, if the baseline exists.
+fn compare_against_baseline(
+    baseline_path: &PathBuf,
+    target_path: &PathBuf,
+    threshold_pct: f64,
+    metric_thresholds: &BTreeMap<String, f64>,
+    circuit_thresholds: &CircuitThresholds,
+    noise_sigma: Option<f64>,
+    baseline_window: Option<usize>,
+    allow_cross_arch: bool,
+    metrics: &MetricSelection,
+) -> Option<CompareResult> {
+    if !baseline_path.exists() {
+        eprintln!("No baseline file found at {}", baseline_path.display());
+        return None;
+    }
+
+    eprintln!("Comparing against baseline: {}", baseline_path.display());
+    let compare_config = compare_cmd::CompareConfig {
+        baseline_file: Some(baseline_path.clone()),
+        target_file: Some(target_path.clone()),
+        baseline_json: None,
+        target_json: None,
+        threshold: threshold_pct,
+        metric_thresholds: metric_thresholds.clone(),
+        circuit_thresholds: circuit_thresholds.clone(),
+        noise_sigma,
+        baseline_window,
+        arch: None,
+        allow_cross_arch,
+        include_anomalies: false,
+        metrics: metrics.clone(),
+        format: "text".to_string(),
+        json_out: None,
+    };
+    match compare_cmd::compare(&compare_config) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            eprintln!("Warning: Comparison failed: {e}");
+            None
+        }
+    }
+}
+
+/// Evaluate alert rules over the results in `target_path`, using the baseline
+/// (if it exists) for `delta_pct` rules, and carry out their actions.
+fn check_alerts(
+    rules: &[AlertRule],
+    baseline_path: &PathBuf,
+    target_path: &PathBuf,
+) -> BenchResult<Vec<FiredAlert>> {
+    if rules.is_empty() || !target_path.exists() {
+        return Ok(Vec::new());
+    }
+    let records = JsonlWriter::new(target_path).read_all()?;
+    let baseline = if baseline_path.exists() {
+        JsonlWriter::new(baseline_path).read_all()?
+    } else {
+        Vec::new()
+    };
+    Ok(alerts::evaluate_and_dispatch(rules, &records, &baseline))
+}
+
+/// Exit code for a run: the comparison's, raised to 1 if a `fail` alert fired.
+fn run_exit_code(comparison: Option<&CompareResult>, fired: &[FiredAlert]) -> i32 {
+    let exit_code = comparison.map(|c| c.ci_exit_code).unwrap_or(0);
+    if alerts::any_failed(fired) {
+        exit_code.max(1)
+    } else {
+        exit_code
+    }
+}
+
+/// JUnit suites for a CI run: the baseline comparison (one suite per
+/// circuit), then the benchmark runs themselves and any fired alerts, so
+/// failed circuits show up even without a baseline.
+fn junit_suites(result: &CiRunResult) -> Vec<JunitSuite> {
+    let mut suites = result
+        .comparison
+        .as_ref()
+        .map(|comp| suites_from_report(&to_regression_report(comp)))
+        .unwrap_or_default();
+
+    let mut circuits = result.circuits.clone();
+    circuits.sort_by(|a, b| {
+        a.circuit_name
+            .cmp(&b.circuit_name)
+            .then_with(|| a.params.cmp(&b.params))
+    });
+    suites.push(JunitSuite {
+        name: "ci runs".to_string(),
+        cases: circuits
+            .iter()
+            .map(|c| JunitCase {
+                name: match c.params {
+                    Some(p) => format!("{}[{p}]", c.circuit_name),
+                    None => c.circuit_name.clone(),
+                },
+                outcome: if c.status == "failed" {
+                    JunitOutcome::Error {
+                        message: failure_message(c),
+                    }
+                } else {
+                    JunitOutcome::Passed
+                },
+                output: Some(format!("prove_ms={:.1} gates={:?}", c.prove_ms, c.gates)),
+            })
+            .collect(),
+    });
+
+    if !result.alerts.is_empty() {
+        suites.push(JunitSuite {
+            name: "alerts".to_string(),
+            cases: result
+                .alerts
+                .iter()
+                .map(|alert| JunitCase {
+                    name: alert.describe(),
+                    outcome: if alert.action == AlertAction::Fail {
+                        JunitOutcome::Failed {
+                            message: format!("alert '{}' fired", alert.rule),
+                            details: alert.describe(),
+                        }
+                    } else {
+                        JunitOutcome::Passed
+                    },
+                    output: None,
+                })
+                .collect(),
+        });
+    }
+    suites
+}
+
+/// "benchmark failed", with the triage bundle to reproduce it from.
+fn failure_message(c: &CiCircuitResult) -> String {
+    match &c.triage_bundle {
+        Some(bundle) => format!("benchmark failed (triage bundle: {bundle})"),
+        None => "benchmark failed".to_string(),
+    }
+}
+
+/// Create a GitHub check run for `result` (`--github-check`), annotating the
+/// config entry of each regressed, improved or failed circuit. Problems are
+/// only warnings: the run's exit code already carries the outcome.
+fn publish_github_check(result: &CiRunResult, config_path: &Path) {
+    let env = |key: &str| std::env::var(key).ok().filter(|v| !v.is_empty());
+    let (Some(token), Some(repo), Some(sha)) = (
+        env("GITHUB_TOKEN"),
+        env("GITHUB_REPOSITORY"),
+        env("GITHUB_SHA"),
+    ) else {
+        eprintln!(
+            "Warning: --github-check needs GITHUB_TOKEN, GITHUB_REPOSITORY and GITHUB_SHA; skipping"
+        );
+        return;
+    };
+
+    // Annotation paths are relative to the repository root
+    let config_ref = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| config_path.strip_prefix(cwd).ok())
+        .unwrap_or(config_path)
+        .display()
+        .to_string();
+    let config_text = std::fs::read_to_string(config_path).unwrap_or_default();
+    let report = result.comparison.as_ref().map(to_regression_report);
+
+    let mut annotations = report
+        .as_ref()
+        .map(|r| report_annotations(r, &config_ref, &config_text))
+        .unwrap_or_default();
+    for c in result.circuits.iter().filter(|c| c.status == "failed") {
+        annotations.push(CheckAnnotation::for_circuit(
+            &config_ref,
+            &config_text,
+            &c.circuit_name,
+            "failure",
+            failure_message(c),
+        ));
+    }
+    for alert in &result.alerts {
+        let level = if alert.action == AlertAction::Fail {
+            "failure"
+        } else {
+            "warning"
+        };
+        annotations.push(CheckAnnotation::for_circuit(
+            &config_ref,
+            &config_text,
+            &alert.circuit_name,
+            level,
+            format!("alert: {}", alert.describe()),
+        ));
+    }
+
+    let title = match &result.comparison {
+        Some(c) => format!(
+            "{} regression(s), {} improvement(s)",
+            c.total_regressions, c.total_improvements
+        ),
+        None => format!("{} circuit(s), no baseline", result.circuits.len()),
+    };
+    let summary = match &report {
+        Some(r) => report_render_markdown(r),
+        None => format_markdown(result),
+    };
+    let run = CheckRun::completed(&sha, result.exit_code != 0, title, summary, annotations);
+    match run.post(&repo, &token) {
+        Ok(()) => eprintln!("Created GitHub check run '{CHECK_NAME}' for {sha}"),
+        Err(e) => eprintln!("Warning: failed to create GitHub check run: {e}"),
+    }
+}
+
+/// Write the optional JSON/HTML reports and print the result in `format`.
+fn emit_outputs(
+    result: &CiRunResult,
+    format: &str,
+    json_out: Option<PathBuf>,
+    html_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    // Collect provenance once for reuse
+    let target_provenance = provenance::collect(None);
+
+    // Write RegressionReport JSON if requested
+    if let Some(ref json_path) = json_out {
+        if let Some(ref comp) = result.comparison {
+            let mut regression_report = to_regression_report(comp);
+            regression_report.set_provenance(None, Some(target_provenance.clone()));
+
+            let json_str = serde_json::to_string_pretty(&regression_report).map_err(|e| {
+                BenchError::Message(format!("failed to serialize regression report: {e}"))
+            })?;
+            std::fs::write(json_path, json_str).map_err(|e| {
+                BenchError::Message(format!("failed to write {}: {e}", json_path.display()))
+            })?;
+            eprintln!("Wrote regression report to {}", json_path.display());
+        } else {
+            eprintln!("Warning: No comparison data available for --json-out (no baseline)");
+        }
+    }
+
+    // Write HTML report if requested
+    if let Some(ref html_path) = html_out {
+        if let Some(ref comp) = result.comparison {
+            let mut regression_report = to_regression_report(comp);
+            regression_report.set_provenance(None, Some(target_provenance.clone()));
+
+            reporter::write_with(
+                "html",
+                html_path,
+                &ReportData::Regression(&regression_report),
+            )?;
+            eprintln!("Wrote HTML report to {}", html_path.display());
+        } else {
+            eprintln!("Warning: No comparison data available for --html-out (no baseline)");
+        }
+    }
+
+    if let Some(comp) = result
+        .comparison
+        .as_ref()
+        .filter(|_| reporter::has_reporters())
+    {
+        let mut regression_report = to_regression_report(comp);
+        regression_report.set_provenance(None, Some(target_provenance.clone()));
+        reporter::publish(&ReportData::Regression(&regression_report));
+    }
+
+    // Output results
+    let output_str = match format {
+        "json" => serde_json::to_string_pretty(result).unwrap_or_else(|_| "{}".to_string()),
+        "markdown" | "md" => {
+            // Use the new RegressionReport markdown renderer if we have comparison data
+            if let Some(ref comp) = result.comparison {
+                let mut regression_report = to_regression_report(comp);
+                regression_report.set_provenance(None, Some(target_provenance));
+                report_render_markdown(&regression_report)
+            } else {
+                format_markdown(result)
+            }
+        }
+        "junit" => render_junit(&junit_suites(result)),
+        "tap" => render_tap(&junit_suites(result)),
+        _ => {
+            // Text format
+            let mut s = String::new();
+            let mut sorted_circuits = result.circuits.clone();
+            sorted_circuits.sort_by(|a, b| {
+                a.circuit_name
+                    .cmp(&b.circuit_name)
+                    .then_with(|| a.params.cmp(&b.params))
+            });
+            s.push_str(&format!("CI Run: {}\n", result.timestamp));
+            for c in &sorted_circuits {
+                s.push_str(&format!(
+                    "  {}: prove_ms={:.1} gates={:?} status={}\n",
+                    c.circuit_name, c.prove_ms, c.gates, c.status
+                ));
+            }
+            if let Some(comp) = &result.comparison {
+                s.push_str(&format!(
+                    "\nRegressions: {} | Improvements: {}\n",
+                    comp.total_regressions, comp.total_improvements
+                ));
+            }
+            if !result.alerts.is_empty() {
+                s.push_str("\nAlerts:\n");
+                for alert in &result.alerts {
+                    s.push_str(&format!("  [{}] {}\n", alert.action, alert.describe()));
+                }
+            }
+            s
+        }
+    };
+
+    println!("{}", output_str);
+
+    Ok(())
+}
+
 /// Main entry point for CI command
 pub fn run(
     config: Option<PathBuf>,
     circuits: Option<Vec<String>>,
     baseline_file: Option<PathBuf>,
+    baseline_window: Option<usize>,
     threshold: Option<f64>,
     iterations: Option<usize>,
     warmup: Option<usize>,
+    shard: Option<String>,
     output: Option<PathBuf>,
     format: String,
     json_out: Option<PathBuf>,
     html_out: Option<PathBuf>,
+    sign_key: Option<PathBuf>,
+    annotate: Vec<String>,
+    metrics: Vec<String>,
+    informational_metrics: Vec<String>,
+    github_check: bool,
 ) -> BenchResult<i32> {
     let config_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let annotations = annotations::parse(&annotate)?;
+    let shard = shard.as_deref().map(ShardSpec::parse).transpose()?;
+    let signing_key = sign_key.as_deref().map(load_signing_key).transpose()?;
 
     // Load config
-    let (ci_config, all_circuits) = if config_path.exists() {
-        load_ci_config(&config_path)?
-    } else {
-        eprintln!(
-            "Warning: Config file not found at {}",
-            config_path.display()
-        );
-        (CiConfig::default(), Vec::new())
-    };
+    let (ci_config, all_circuits) = load_ci_config_or_default(&config_path)?;
+    let alert_rules = load_alert_rules(&config_path)?;
 
     // Determine which circuits to run
     let ci_circuits: Vec<String> = sort_ci_circuit_names(
@@ -461,12 +1070,15 @@ pub fn run(
     let baseline_path = baseline_file
         .or_else(|| ci_config.baseline_file.map(PathBuf::from))
         .unwrap_or_else(|| PathBuf::from(DEFAULT_BASELINE));
+    let baseline_window = baseline_window.or(ci_config.baseline_window);
 
     // Determine threshold
     let threshold_pct = threshold
         .or(ci_config.threshold_percent)
         .unwrap_or(DEFAULT_THRESHOLD);
     let metric_thresholds = ci_config.thresholds.clone();
+    let circuit_thresholds = ci_config.circuit_thresholds.clone();
+    let metrics = resolve_metric_selection(&ci_config, metrics, informational_metrics)?;
 
     // Determine iterations
     let iter_n = iterations
@@ -476,8 +1088,14 @@ pub fn run(
 
     // Output file for benchmark results
     let output_path = output.unwrap_or_else(|| {
-        let tmp = std::env::temp_dir().join("noir-bench-ci-results.jsonl");
-        tmp
+        let name = match shard {
+            Some(spec) => format!(
+                "noir-bench-ci-results.shard-{}-of-{}.jsonl",
+                spec.index, spec.count
+            ),
+            None => "noir-bench-ci-results.jsonl".to_string(),
+        };
+        std::env::temp_dir().join(name)
     });
 
     eprintln!("noir-bench ci");
@@ -490,7 +1108,17 @@ pub fn run(
             ci_circuits.clone()
         }
     );
-    eprintln!("  Baseline: {}", baseline_path.display());
+    if let Some(spec) = shard {
+        eprintln!("  Shard: {}", spec);
+    }
+    match baseline_window {
+        Some(window) => eprintln!(
+            "  Baseline: {} (median of last {} runs)",
+            baseline_path.display(),
+            window
+        ),
+        None => eprintln!("  Baseline: {}", baseline_path.display()),
+    }
     eprintln!("  Default threshold: {:.1}%", threshold_pct);
     if !metric_thresholds.is_empty() {
         eprintln!("  Metric thresholds:");
@@ -498,12 +1126,46 @@ pub fn run(
             eprintln!("    {}: {:.1}%", metric, threshold);
         }
     }
+    if let Some(k) = ci_config.noise_sigma {
+        eprintln!("  Noise threshold: {}σ of baseline timings", k);
+    }
+    if !metrics.metrics.is_empty() {
+        let names: Vec<&str> = metrics.metrics.iter().map(String::as_str).collect();
+        eprintln!("  Metrics: {}", names.join(", "));
+    }
+    if !metrics.informational.is_empty() {
+        let names: Vec<&str> = metrics.informational.iter().map(String::as_str).collect();
+        eprintln!("  Informational: {}", names.join(", "));
+    }
+    if !circuit_thresholds.is_empty() {
+        eprintln!("  Circuit thresholds:");
+        for (circuit, thresholds) in &circuit_thresholds {
+            for (metric, threshold) in thresholds {
+                eprintln!("    {}/{}: {:.1}%", circuit, metric, threshold);
+            }
+        }
+    }
     eprintln!("  Iterations: {} (warmup: {})", iter_n, warmup_n);
+    for (key, value) in &annotations {
+        eprintln!("  Annotation: {}={}", key, value);
+    }
     eprintln!("");
 
     // Run benchmarks
-    let mut circuit_results =
-        run_ci_benchmarks(&all_circuits, &ci_circuits, iter_n, warmup_n, &output_path)?;
+    let mut circuit_results = run_ci_benchmarks(
+        &all_circuits,
+        &ci_circuits,
+        iter_n,
+        warmup_n,
+        shard,
+        &output_path,
+        signing_key.as_ref(),
+        &annotations,
+        ci_config.gate_cache.as_ref(),
+        ci_config.key_cache.as_ref(),
+        load_backend_args(&config_path)?,
+        &load_circuit_env(&config_path)?,
+    )?;
     circuit_results.sort_by(|a, b| {
         a.circuit_name
             .cmp(&b.circuit_name)
@@ -511,116 +1173,260 @@ pub fn run(
     });
 
     // Compare against baseline if it exists
-    let comparison = if baseline_path.exists() {
-        eprintln!("Comparing against baseline: {}", baseline_path.display());
-        let compare_config = compare_cmd::CompareConfig {
-            baseline_file: Some(baseline_path.clone()),
-            target_file: Some(output_path.clone()),
-            baseline_json: None,
-            target_json: None,
-            threshold: threshold_pct,
-            metric_thresholds: metric_thresholds.clone(),
-            format: "text".to_string(),
-            json_out: None,
-        };
-        match compare_cmd::compare(&compare_config) {
-            Ok(result) => Some(result),
-            Err(e) => {
-                eprintln!("Warning: Comparison failed: {e}");
-                None
-            }
-        }
-    } else {
-        eprintln!("No baseline file found at {}", baseline_path.display());
-        None
-    };
-
-    let exit_code = comparison.as_ref().map(|c| c.ci_exit_code).unwrap_or(0);
+    let comparison = compare_against_baseline(
+        &baseline_path,
+        &output_path,
+        threshold_pct,
+        &metric_thresholds,
+        &circuit_thresholds,
+        ci_config.noise_sigma,
+        baseline_window,
+        ci_config.allow_cross_arch,
+        &metrics,
+    )
+    .map(|mut comparison| {
+        comparison.annotations = annotations.clone();
+        comparison
+    });
+    let fired = check_alerts(&alert_rules, &baseline_path, &output_path)?;
+    let exit_code = run_exit_code(comparison.as_ref(), &fired);
 
     let result = CiRunResult {
         timestamp: now_string(),
         circuits: circuit_results,
         default_threshold: threshold_pct,
         metric_thresholds,
+        circuit_thresholds,
         comparison,
+        alerts: fired,
         exit_code,
     };
 
-    // Collect provenance once for reuse
-    let target_provenance = provenance::collect(None);
+    emit_outputs(&result, &format, json_out, html_out)?;
+    if github_check {
+        publish_github_check(&result, &config_path);
+    }
 
-    // Write RegressionReport JSON if requested
-    if let Some(ref json_path) = json_out {
-        if let Some(ref comp) = result.comparison {
-            let mut regression_report = to_regression_report(comp);
-            regression_report.set_provenance(None, Some(target_provenance.clone()));
+    Ok(exit_code)
+}
 
-            let json_str = serde_json::to_string_pretty(&regression_report).map_err(|e| {
-                BenchError::Message(format!("failed to serialize regression report: {e}"))
-            })?;
-            std::fs::write(json_path, json_str).map_err(|e| {
-                BenchError::Message(format!("failed to write {}: {e}", json_path.display()))
-            })?;
-            eprintln!("Wrote regression report to {}", json_path.display());
-        } else {
-            eprintln!("Warning: No comparison data available for --json-out (no baseline)");
-        }
-    }
+/// Run every circuit from the config's `[ci]` section (or all circuits) into
+/// `output_path` and compare against `baseline_path`, if it exists.
+///
+/// Iterations, warmup and thresholds come from the config. This is what the
+/// `daemon` scheduler runs on each tick; nothing is printed to stdout.
+pub fn run_configured(
+    config_path: &PathBuf,
+    baseline_path: &PathBuf,
+    output_path: &PathBuf,
+    signing_key: Option<&SigningKey>,
+) -> BenchResult<CiRunResult> {
+    let (ci_config, all_circuits) = load_ci_config_or_default(config_path)?;
+    let alert_rules = load_alert_rules(config_path)?;
+    let ci_circuits = sort_ci_circuit_names(ci_config.circuits.clone());
+    let threshold_pct = ci_config.threshold_percent.unwrap_or(DEFAULT_THRESHOLD);
+    let metric_thresholds = ci_config.thresholds.clone();
+    let circuit_thresholds = ci_config.circuit_thresholds.clone();
+
+    let mut circuit_results = run_ci_benchmarks(
+        &all_circuits,
+        &ci_circuits,
+        ci_config.iterations.unwrap_or(DEFAULT_CI_ITERATIONS),
+        ci_config.warmup.unwrap_or(DEFAULT_CI_WARMUP),
+        None,
+        output_path,
+        signing_key,
+        &BTreeMap::new(),
+        ci_config.gate_cache.as_ref(),
+        ci_config.key_cache.as_ref(),
+        load_backend_args(config_path)?,
+        &load_circuit_env(config_path)?,
+    )?;
+    circuit_results.sort_by(|a, b| {
+        a.circuit_name
+            .cmp(&b.circuit_name)
+            .then_with(|| a.params.cmp(&b.params))
+    });
 
-    // Write HTML report if requested
-    if let Some(ref html_path) = html_out {
-        if let Some(ref comp) = result.comparison {
-            let mut regression_report = to_regression_report(comp);
-            regression_report.set_provenance(None, Some(target_provenance.clone()));
+    let budgets = if config_path.exists() {
+        load_budgets(config_path)?
+    } else {
+        Budgets::new()
+    };
+    let comparison = compare_against_baseline(
+        baseline_path,
+        output_path,
+        threshold_pct,
+        &metric_thresholds,
+        &circuit_thresholds,
+        ci_config.noise_sigma,
+        ci_config.baseline_window,
+        ci_config.allow_cross_arch,
+        &ci_config.metric_selection(),
+    )
+    .map(|mut comparison| {
+        attach_budgets(&mut comparison, &budgets);
+        comparison
+    });
+    let fired = check_alerts(&alert_rules, baseline_path, output_path)?;
+    let exit_code = run_exit_code(comparison.as_ref(), &fired);
 
-            report_write_html(html_path, &regression_report)
-                .map_err(|e| BenchError::Message(format!("failed to write HTML report: {e}")))?;
-            eprintln!("Wrote HTML report to {}", html_path.display());
-        } else {
-            eprintln!("Warning: No comparison data available for --html-out (no baseline)");
+    Ok(CiRunResult {
+        timestamp: now_string(),
+        circuits: circuit_results,
+        default_threshold: threshold_pct,
+        metric_thresholds,
+        circuit_thresholds,
+        comparison,
+        alerts: fired,
+        exit_code,
+    })
+}
+
+/// Merge shard result JSONLs into a single file, ordered by circuit name.
+///
+/// Returns one `CiCircuitResult` per merged record, ordered by circuit name
+/// and params. Shard JSONLs only contain successful runs, so every collected
+/// result has status `ok`. Every record must pass `policy` before anything is
+/// written.
+fn merge_shard_results(
+    shard_files: &[PathBuf],
+    output_path: &PathBuf,
+    policy: &SignaturePolicy,
+) -> BenchResult<Vec<CiCircuitResult>> {
+    let mut records = Vec::new();
+    for path in shard_files {
+        if !path.exists() {
+            return Err(BenchError::Message(format!(
+                "shard results not found: {}",
+                path.display()
+            )));
         }
+        let read = read_untrusted(path, &ParseLimits::default())?;
+        read.report_warnings(path);
+        let shard_records = read.records;
+        policy
+            .check_all(&shard_records)
+            .map_err(|e| BenchError::Message(format!("{}: {e}", path.display())))?;
+        eprintln!("  {}: {} record(s)", path.display(), shard_records.len());
+        records.extend(shard_records);
     }
+    records.sort_by(|a, b| {
+        a.circuit_name
+            .cmp(&b.circuit_name)
+            .then_with(|| a.params.cmp(&b.params))
+    });
 
-    // Output results
-    let output_str = match format.as_str() {
-        "json" => serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string()),
-        "markdown" | "md" => {
-            // Use the new RegressionReport markdown renderer if we have comparison data
-            if let Some(ref comp) = result.comparison {
-                let mut regression_report = to_regression_report(comp);
-                regression_report.set_provenance(None, Some(target_provenance));
-                report_render_markdown(&regression_report)
-            } else {
-                format_markdown(&result)
-            }
-        }
-        _ => {
-            // Text format
-            let mut s = String::new();
-            let mut sorted_circuits = result.circuits.clone();
-            sorted_circuits.sort_by(|a, b| {
-                a.circuit_name
-                    .cmp(&b.circuit_name)
-                    .then_with(|| a.params.cmp(&b.params))
-            });
-            s.push_str(&format!("CI Run: {}\n", result.timestamp));
-            for c in &sorted_circuits {
-                s.push_str(&format!(
-                    "  {}: prove_ms={:.1} gates={:?} status={}\n",
-                    c.circuit_name, c.prove_ms, c.gates, c.status
-                ));
-            }
-            if let Some(comp) = &result.comparison {
-                s.push_str(&format!(
-                    "\nRegressions: {} | Improvements: {}\n",
-                    comp.total_regressions, comp.total_improvements
-                ));
-            }
-            s
+    if let Some(parent) = output_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).ok();
         }
+    }
+    if output_path.exists() {
+        std::fs::remove_file(output_path).map_err(|e| {
+            BenchError::Message(format!("failed to replace {}: {e}", output_path.display()))
+        })?;
+    }
+
+    let writer = JsonlWriter::new(output_path);
+    let mut results = Vec::with_capacity(records.len());
+    for record in &records {
+        writer.append(record)?;
+        results.push(CiCircuitResult {
+            circuit_name: record.circuit_name.clone(),
+            params: record.params,
+            prove_ms: record
+                .prove_stats
+                .as_ref()
+                .map(|s| s.mean_ms)
+                .unwrap_or(0.0),
+            gates: record.total_gates,
+            proof_size_bytes: record.proof_size_bytes,
+            status: "ok".to_string(),
+            triage_bundle: None,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Entry point for `ci collect`: merge shard JSONLs and produce one report.
+pub fn collect(
+    shard_files: Vec<PathBuf>,
+    config: Option<PathBuf>,
+    baseline_file: Option<PathBuf>,
+    threshold: Option<f64>,
+    output: Option<PathBuf>,
+    format: String,
+    json_out: Option<PathBuf>,
+    html_out: Option<PathBuf>,
+    policy: SignaturePolicy,
+    metrics: Vec<String>,
+    informational_metrics: Vec<String>,
+    github_check: bool,
+) -> BenchResult<i32> {
+    if shard_files.is_empty() {
+        return Err(BenchError::Message(
+            "ci collect requires at least one shard results file".into(),
+        ));
+    }
+
+    let config_path = config.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG));
+    let (ci_config, _) = load_ci_config_or_default(&config_path)?;
+    let alert_rules = load_alert_rules(&config_path)?;
+
+    let baseline_path = baseline_file
+        .or_else(|| ci_config.baseline_file.map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_BASELINE));
+    let baseline_window = ci_config.baseline_window;
+    let threshold_pct = threshold
+        .or(ci_config.threshold_percent)
+        .unwrap_or(DEFAULT_THRESHOLD);
+    let metric_thresholds = ci_config.thresholds.clone();
+    let circuit_thresholds = ci_config.circuit_thresholds.clone();
+    let metrics = resolve_metric_selection(&ci_config, metrics, informational_metrics)?;
+    let output_path =
+        output.unwrap_or_else(|| std::env::temp_dir().join("noir-bench-ci-results.jsonl"));
+
+    eprintln!("noir-bench ci collect");
+    eprintln!("  Shards: {}", shard_files.len());
+    let circuit_results = merge_shard_results(&shard_files, &output_path, &policy)?;
+    eprintln!(
+        "  Merged {} record(s) into {}",
+        circuit_results.len(),
+        output_path.display()
+    );
+    eprintln!("");
+
+    let comparison = compare_against_baseline(
+        &baseline_path,
+        &output_path,
+        threshold_pct,
+        &metric_thresholds,
+        &circuit_thresholds,
+        ci_config.noise_sigma,
+        baseline_window,
+        ci_config.allow_cross_arch,
+        &metrics,
+    );
+    let fired = check_alerts(&alert_rules, &baseline_path, &output_path)?;
+    let exit_code = run_exit_code(comparison.as_ref(), &fired);
+
+    let result = CiRunResult {
+        timestamp: now_string(),
+        circuits: circuit_results,
+        default_threshold: threshold_pct,
+        metric_thresholds,
+        circuit_thresholds,
+        comparison,
+        alerts: fired,
+        exit_code,
     };
 
-    println!("{}", output_str);
+    emit_outputs(&result, &format, json_out, html_out)?;
+    if github_check {
+        publish_github_check(&result, &config_path);
+    }
 
     Ok(exit_code)
 }
@@ -629,6 +1435,7 @@ pub fn run(
 mod tests {
     use super::*;
     use crate::compare_cmd::{CircuitComparison, CompareStatus, MetricComparison};
+    use crate::report::ThresholdRule;
 
     #[test]
     fn test_expand_ci_targets_is_deterministic() {
@@ -679,6 +1486,7 @@ mod tests {
                     gates: Some(5000),
                     proof_size_bytes: Some(2048),
                     status: "ok".to_string(),
+                    triage_bundle: None,
                 },
                 CiCircuitResult {
                     circuit_name: "alpha".to_string(),
@@ -687,6 +1495,7 @@ mod tests {
                     gates: Some(3000),
                     proof_size_bytes: Some(1024),
                     status: "ok".to_string(),
+                    triage_bundle: None,
                 },
             ],
             comparison: Some(CompareResult {
@@ -697,6 +1506,14 @@ mod tests {
                     ("prove_ms".to_string(), 25.0),
                     ("total_gates".to_string(), 0.0),
                 ]),
+                circuit_thresholds: CircuitThresholds::new(),
+                noise_sigma: None,
+                annotations: BTreeMap::new(),
+                environment_mismatches: Vec::new(),
+                thermal_warnings: Vec::new(),
+                methodology: None,
+                informational_metrics: Default::default(),
+                budgets: Budgets::new(),
                 circuits: vec![
                     CircuitComparison {
                         circuit_name: "zeta".to_string(),
@@ -708,6 +1525,7 @@ mod tests {
                                 delta: 200.0,
                                 percent: 4.0,
                                 threshold: 0.0,
+                                threshold_rule: ThresholdRule::Percent,
                                 status: CompareStatus::Unchanged,
                             },
                             MetricComparison {
@@ -717,6 +1535,7 @@ mod tests {
                                 delta: 20.0,
                                 percent: 11.1,
                                 threshold: 25.0,
+                                threshold_rule: ThresholdRule::Percent,
                                 status: CompareStatus::Regression,
                             },
                         ],
@@ -731,6 +1550,7 @@ mod tests {
                             delta: -10.0,
                             percent: -9.09,
                             threshold: 25.0,
+                            threshold_rule: ThresholdRule::Percent,
                             status: CompareStatus::Unchanged,
                         }],
                         has_regression: false,
@@ -745,6 +1565,11 @@ mod tests {
                 ("prove_ms".to_string(), 25.0),
                 ("total_gates".to_string(), 0.0),
             ]),
+            circuit_thresholds: CircuitThresholds::from([(
+                "zeta".to_string(),
+                BTreeMap::from([("prove_ms".to_string(), 50.0)]),
+            )]),
+            alerts: Vec::new(),
             exit_code: 1,
         };
 
@@ -760,6 +1585,49 @@ mod tests {
         );
         assert!(a.contains("| default | 10.0% |"));
         assert!(a.contains("| prove_ms | 25.0% |"));
+        assert!(a.contains("| prove_ms (zeta) | 50.0% |"));
+    }
+
+    #[test]
+    fn test_junit_reports_failed_runs_and_alerts_without_baseline() {
+        let result = CiRunResult {
+            timestamp: "2026-02-01T00:00:00Z".to_string(),
+            circuits: vec![CiCircuitResult {
+                circuit_name: "broken".to_string(),
+                params: None,
+                prove_ms: 0.0,
+                gates: None,
+                proof_size_bytes: None,
+                status: "failed".to_string(),
+                triage_bundle: Some("out/triage/broken-20260201T000000.zip".to_string()),
+            }],
+            default_threshold: 10.0,
+            metric_thresholds: BTreeMap::new(),
+            circuit_thresholds: CircuitThresholds::new(),
+            comparison: None,
+            alerts: vec![FiredAlert {
+                rule: "prove_ms > 100".to_string(),
+                action: AlertAction::Fail,
+                circuit_name: "slow".to_string(),
+                params: None,
+                value: 150.0,
+            }],
+            exit_code: 1,
+        };
+
+        let xml = render_junit(&junit_suites(&result));
+        assert!(xml.contains("tests=\"2\" failures=\"1\" errors=\"1\""));
+        assert!(xml.contains(
+            "<error message=\"benchmark failed (triage bundle: out/triage/broken-20260201T000000.zip)\"/>"
+        ));
+
+        let md = format_markdown(&result);
+        assert!(
+            md.contains(
+                "### Failure Triage\n\n- broken: `out/triage/broken-20260201T000000.zip`\n"
+            )
+        );
+        assert!(xml.contains("<failure message=\"alert &apos;prove_ms &gt; 100&apos; fired\""));
     }
 
     #[test]
@@ -776,4 +1644,129 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_shard_spec_parse() {
+        assert_eq!(
+            ShardSpec::parse("2/5").unwrap(),
+            ShardSpec { index: 2, count: 5 }
+        );
+        assert_eq!(ShardSpec::parse("1/1").unwrap().to_string(), "1/1");
+        assert!(ShardSpec::parse("0/5").is_err());
+        assert!(ShardSpec::parse("6/5").is_err());
+        assert!(ShardSpec::parse("1/0").is_err());
+        assert!(ShardSpec::parse("2").is_err());
+        assert!(ShardSpec::parse("a/b").is_err());
+    }
+
+    #[test]
+    fn test_select_shard_partitions_targets() {
+        let targets: Vec<usize> = (0..11).collect();
+        let count = 4;
+
+        let mut seen = Vec::new();
+        for index in 1..=count {
+            let shard = select_shard(targets.clone(), Some(ShardSpec { index, count }));
+            // Same shard selected twice yields the same subset
+            assert_eq!(
+                shard,
+                select_shard(targets.clone(), Some(ShardSpec { index, count }))
+            );
+            seen.extend(shard);
+        }
+        seen.sort();
+        assert_eq!(seen, targets, "shards must cover every target exactly once");
+
+        assert_eq!(
+            select_shard(targets.clone(), Some(ShardSpec { index: 2, count: 4 })),
+            vec![1, 5, 9]
+        );
+        assert_eq!(select_shard(targets.clone(), None), targets);
+    }
+
+    #[test]
+    fn test_merge_shard_results_orders_by_circuit() {
+        use crate::core::env::EnvironmentInfo;
+        use crate::core::schema::{BackendInfo, BenchRecord, RunConfig};
+
+        let dir = tempfile::tempdir().unwrap();
+        let make = |name: &str, gates: u64| {
+            let mut r = BenchRecord::new(
+                name.to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "barretenberg".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.total_gates = Some(gates);
+            r
+        };
+
+        let shard1 = dir.path().join("shard1.jsonl");
+        let shard2 = dir.path().join("shard2.jsonl");
+        JsonlWriter::new(&shard1).append(&make("zeta", 30)).unwrap();
+        JsonlWriter::new(&shard2)
+            .append(&make("alpha", 10))
+            .unwrap();
+        JsonlWriter::new(&shard2).append(&make("beta", 20)).unwrap();
+
+        let merged = dir.path().join("merged.jsonl");
+        let results =
+            merge_shard_results(&[shard1, shard2], &merged, &SignaturePolicy::default()).unwrap();
+
+        let names: Vec<_> = results.iter().map(|r| r.circuit_name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "beta", "zeta"]);
+        assert_eq!(JsonlWriter::new(&merged).count().unwrap(), 3);
+
+        // Missing shard files are an error rather than a silently partial report
+        let missing = dir.path().join("missing.jsonl");
+        assert!(merge_shard_results(&[missing], &merged, &SignaturePolicy::default()).is_err());
+    }
+
+    #[test]
+    fn test_merge_shard_results_enforces_signature_policy() {
+        use crate::core::env::EnvironmentInfo;
+        use crate::core::schema::{BackendInfo, RunConfig};
+        use crate::core::signing::public_key_hex;
+
+        let dir = tempfile::tempdir().unwrap();
+        let record = BenchRecord::new(
+            "alpha".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "barretenberg".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        let runner_key = SigningKey::from_bytes(&[1; 32]);
+        let shard = dir.path().join("shard.jsonl");
+        JsonlWriter::new(&shard)
+            .with_signing_key(runner_key.clone())
+            .append(&record)
+            .unwrap();
+        let merged = dir.path().join("merged.jsonl");
+
+        let trusted = SignaturePolicy {
+            trusted_keys: [public_key_hex(&runner_key)].into(),
+            require_signed: true,
+        };
+        assert!(merge_shard_results(&[shard.clone()], &merged, &trusted).is_ok());
+        let merged_records = JsonlWriter::new(&merged).read_all().unwrap();
+        assert!(
+            trusted.check_all(&merged_records).is_ok(),
+            "signatures survive merge"
+        );
+
+        let other = SignaturePolicy {
+            trusted_keys: [public_key_hex(&SigningKey::from_bytes(&[2; 32]))].into(),
+            require_signed: true,
+        };
+        let err = merge_shard_results(&[shard], &merged, &other).unwrap_err();
+        assert!(err.to_string().contains("untrusted key"), "{err}");
+    }
 }
@@ -0,0 +1,584 @@
+//! CLI command handler for `circuit report`.
+//!
+//! Builds a "circuit card" for a compiled artifact: gate counts from the
+//! backend, the ACIR opcode breakdown, an ABI summary, Brillig function sizes,
+//! artifact size and noir version. The card is written as JSON for tooling
+//! and as markdown/HTML for circuit reviews.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use acvm::FieldElement;
+use acvm::acir::circuit::Opcode as AcirOpcode;
+use noirc_abi::{AbiType, AbiVisibility, Sign};
+use noirc_artifacts::program::ProgramArtifact;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig, GateInfo};
+use crate::history::run_html::html_escape;
+use crate::{BenchError, BenchResult};
+
+/// Opcodes of one kind (e.g. "assert_zero", "blackbox::sha256_compression").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpcodeKindStats {
+    pub kind: String,
+    pub count: usize,
+    /// Backend gates attributed to these opcodes (main function only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gates: Option<u64>,
+}
+
+/// One ABI parameter or the return value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbiEntry {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub visibility: String,
+    /// Number of field elements the value is encoded as
+    pub fields: u32,
+}
+
+/// Size of one unconstrained (Brillig) function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrilligFunctionSize {
+    pub name: String,
+    pub opcodes: usize,
+}
+
+/// Everything a reviewer needs to know about a circuit, in one document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitCard {
+    pub circuit_name: String,
+    pub noir_version: String,
+    pub artifact_path: PathBuf,
+    pub artifact_bytes: u64,
+    pub artifact_sha256: String,
+    /// Number of ACIR functions (main plus non-inlined calls)
+    pub acir_functions: usize,
+    /// ACIR opcodes across all functions
+    pub acir_opcodes: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_gates: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgroup_size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<String>,
+    /// Opcode counts by kind, most frequent first
+    pub opcodes: Vec<OpcodeKindStats>,
+    pub inputs: Vec<AbiEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_value: Option<AbiEntry>,
+    /// Field elements across all inputs
+    pub input_fields: u32,
+    pub brillig_functions: Vec<BrilligFunctionSize>,
+}
+
+/// Label for an ACIR opcode kind.
+fn opcode_kind(op: &AcirOpcode<FieldElement>) -> String {
+    match op {
+        AcirOpcode::AssertZero(_) => "assert_zero".to_string(),
+        AcirOpcode::BlackBoxFuncCall(call) => format!("blackbox::{}", call.name()),
+        AcirOpcode::MemoryOp { .. } => "memory_op".to_string(),
+        AcirOpcode::MemoryInit { .. } => "memory_init".to_string(),
+        AcirOpcode::BrilligCall { .. } => "brillig_call".to_string(),
+        AcirOpcode::Call { .. } => "call".to_string(),
+    }
+}
+
+/// Count opcodes by kind across functions.
+///
+/// Backend per-opcode gates (keyed "opcode_{index}") only cover the main
+/// function, so gates are attributed from `functions[0]` alone.
+fn opcode_breakdown(
+    functions: &[Vec<String>],
+    per_opcode_gates: Option<&HashMap<String, u64>>,
+) -> Vec<OpcodeKindStats> {
+    let mut by_kind: BTreeMap<&str, OpcodeKindStats> = BTreeMap::new();
+    for (func_index, kinds) in functions.iter().enumerate() {
+        for (i, kind) in kinds.iter().enumerate() {
+            let entry = by_kind
+                .entry(kind.as_str())
+                .or_insert_with(|| OpcodeKindStats {
+                    kind: kind.clone(),
+                    count: 0,
+                    gates: per_opcode_gates.map(|_| 0),
+                });
+            entry.count += 1;
+            let gates = match per_opcode_gates {
+                Some(m) if func_index == 0 => m.get(&format!("opcode_{i}")),
+                _ => None,
+            };
+            if let (Some(total), Some(gates)) = (entry.gates.as_mut(), gates) {
+                *total += gates;
+            }
+        }
+    }
+    let mut stats: Vec<OpcodeKindStats> = by_kind.into_values().collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.kind.cmp(&b.kind)));
+    stats
+}
+
+/// Noir-style label for an ABI type, e.g. "[u8; 32]".
+fn type_label(typ: &AbiType) -> String {
+    match typ {
+        AbiType::Field => "Field".to_string(),
+        AbiType::Boolean => "bool".to_string(),
+        AbiType::Integer { sign, width } => match sign {
+            Sign::Unsigned => format!("u{width}"),
+            Sign::Signed => format!("i{width}"),
+        },
+        AbiType::Array { length, typ } => format!("[{}; {length}]", type_label(typ)),
+        AbiType::String { length } => format!("str<{length}>"),
+        AbiType::Struct { path, .. } => path.clone(),
+        AbiType::Tuple { fields } => format!(
+            "({})",
+            fields.iter().map(type_label).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn visibility_label(visibility: &AbiVisibility) -> String {
+    format!("{visibility:?}").to_lowercase()
+}
+
+/// Build the card for a loaded artifact of `artifact_bytes` bytes. `gates` is
+/// the backend's gate info and its name/version, when gates were measured.
+pub fn build_card(
+    circuit_name: &str,
+    artifact_path: &Path,
+    program: &ProgramArtifact,
+    artifact_bytes: u64,
+    artifact_sha256: String,
+    gates: Option<(&GateInfo, &str, Option<String>)>,
+) -> CircuitCard {
+    let functions: Vec<Vec<String>> = program
+        .bytecode
+        .functions
+        .iter()
+        .map(|f| f.opcodes.iter().map(opcode_kind).collect())
+        .collect();
+    let gate_info = gates.as_ref().map(|(info, _, _)| *info);
+
+    let inputs: Vec<AbiEntry> = program
+        .abi
+        .parameters
+        .iter()
+        .map(|p| AbiEntry {
+            name: p.name.clone(),
+            typ: type_label(&p.typ),
+            visibility: visibility_label(&p.visibility),
+            fields: p.typ.field_count(),
+        })
+        .collect();
+    let return_value = program.abi.return_type.as_ref().map(|r| AbiEntry {
+        name: "return".to_string(),
+        typ: type_label(&r.abi_type),
+        visibility: visibility_label(&r.visibility),
+        fields: r.abi_type.field_count(),
+    });
+
+    let brillig_functions = program
+        .bytecode
+        .unconstrained_functions
+        .iter()
+        .enumerate()
+        .map(|(i, f)| BrilligFunctionSize {
+            name: program
+                .brillig_names
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| format!("brillig_{i}")),
+            opcodes: f.bytecode.len(),
+        })
+        .collect();
+
+    CircuitCard {
+        circuit_name: circuit_name.to_string(),
+        noir_version: program.noir_version.clone(),
+        artifact_path: artifact_path.to_path_buf(),
+        artifact_bytes,
+        artifact_sha256,
+        acir_functions: functions.len(),
+        acir_opcodes: functions.iter().map(Vec::len).sum(),
+        total_gates: gate_info.map(|g| g.backend_gates),
+        subgroup_size: gate_info.and_then(|g| g.subgroup_size),
+        backend: gates.as_ref().map(|(_, name, _)| name.to_string()),
+        backend_version: gates.as_ref().and_then(|(_, _, version)| version.clone()),
+        opcodes: opcode_breakdown(&functions, gate_info.and_then(|g| g.per_opcode.as_ref())),
+        input_fields: inputs.iter().map(|p| p.fields).sum(),
+        inputs,
+        return_value,
+        brillig_functions,
+    }
+}
+
+fn fmt_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+fn fmt_gates(gates: Option<u64>) -> String {
+    gates
+        .map(|g| g.to_string())
+        .unwrap_or_else(|| "—".to_string())
+}
+
+/// Summary rows shared by the markdown and HTML renderings.
+fn summary_rows(card: &CircuitCard) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("Noir version", card.noir_version.clone()),
+        (
+            "Artifact",
+            format!(
+                "{} ({})",
+                card.artifact_path.display(),
+                fmt_bytes(card.artifact_bytes)
+            ),
+        ),
+        ("Artifact sha256", card.artifact_sha256.clone()),
+        ("ACIR functions", card.acir_functions.to_string()),
+        ("ACIR opcodes", card.acir_opcodes.to_string()),
+        ("Backend gates", fmt_gates(card.total_gates)),
+    ];
+    if let Some(subgroup) = card.subgroup_size {
+        rows.push((
+            "Subgroup size",
+            format!("2^{} ({subgroup})", subgroup.trailing_zeros()),
+        ));
+    }
+    if let Some(backend) = &card.backend {
+        let version = card.backend_version.as_deref().unwrap_or("unknown");
+        rows.push(("Backend", format!("{backend} {version}")));
+    }
+    rows.push(("Input fields", card.input_fields.to_string()));
+    rows.push((
+        "Brillig functions",
+        card.brillig_functions.len().to_string(),
+    ));
+    rows
+}
+
+fn abi_rows(card: &CircuitCard) -> impl Iterator<Item = &AbiEntry> {
+    card.inputs.iter().chain(card.return_value.as_ref())
+}
+
+/// Render the card as markdown.
+pub fn render_markdown(card: &CircuitCard) -> String {
+    let mut out = format!("# Circuit: {}\n\n", card.circuit_name);
+    out.push_str("| Property | Value |\n|---|---|\n");
+    for (k, v) in summary_rows(card) {
+        out.push_str(&format!("| {k} | {v} |\n"));
+    }
+
+    out.push_str("\n## Opcodes\n\n| Kind | Count | Gates |\n|---|---:|---:|\n");
+    for op in &card.opcodes {
+        out.push_str(&format!(
+            "| {} | {} | {} |\n",
+            op.kind,
+            op.count,
+            fmt_gates(op.gates)
+        ));
+    }
+
+    out.push_str("\n## ABI\n\n| Name | Type | Visibility | Fields |\n|---|---|---|---:|\n");
+    for p in abi_rows(card) {
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} |\n",
+            p.name, p.typ, p.visibility, p.fields
+        ));
+    }
+
+    if !card.brillig_functions.is_empty() {
+        out.push_str("\n## Brillig functions\n\n| Function | Opcodes |\n|---|---:|\n");
+        for f in &card.brillig_functions {
+            out.push_str(&format!("| {} | {} |\n", f.name, f.opcodes));
+        }
+    }
+    out
+}
+
+/// Render the card as a static HTML page. NO JavaScript; all strings from
+/// the artifact are HTML-escaped.
+pub fn render_html(card: &CircuitCard) -> String {
+    let table = |head: &[&str], rows: Vec<Vec<String>>| {
+        let mut html = String::from("<table>\n<tr>");
+        for h in head {
+            html.push_str(&format!("<th>{h}</th>"));
+        }
+        html.push_str("</tr>\n");
+        for row in rows {
+            html.push_str("<tr>");
+            for cell in row {
+                html.push_str(&format!("<td>{}</td>", html_escape(&cell)));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        html
+    };
+
+    let summary = table(
+        &["Property", "Value"],
+        summary_rows(card)
+            .into_iter()
+            .map(|(k, v)| vec![k.to_string(), v])
+            .collect(),
+    );
+    let opcodes = table(
+        &["Kind", "Count", "Gates"],
+        card.opcodes
+            .iter()
+            .map(|op| vec![op.kind.clone(), op.count.to_string(), fmt_gates(op.gates)])
+            .collect(),
+    );
+    let abi = table(
+        &["Name", "Type", "Visibility", "Fields"],
+        abi_rows(card)
+            .map(|p| {
+                vec![
+                    p.name.clone(),
+                    p.typ.clone(),
+                    p.visibility.clone(),
+                    p.fields.to_string(),
+                ]
+            })
+            .collect(),
+    );
+    let brillig = if card.brillig_functions.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<h2>Brillig functions</h2>\n{}",
+            table(
+                &["Function", "Opcodes"],
+                card.brillig_functions
+                    .iter()
+                    .map(|f| vec![f.name.clone(), f.opcodes.to_string()])
+                    .collect(),
+            )
+        )
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>{name} - circuit report</title>
+<style>
+* {{ box-sizing: border-box; margin: 0; padding: 0; }}
+body {{
+  font-family: system-ui, -apple-system, sans-serif;
+  background: #1a1a2e;
+  color: #e8e8e8;
+  padding: 24px;
+  max-width: 960px;
+  margin: 0 auto;
+}}
+h1 {{ font-size: 1.5rem; margin-bottom: 16px; }}
+h2 {{ font-size: 1.125rem; margin: 24px 0 12px 0; color: #9a9a9a; }}
+table {{ width: 100%; border-collapse: collapse; font-size: 0.875rem; background: #16213e; margin-bottom: 16px; }}
+th, td {{ padding: 8px 12px; text-align: left; border-bottom: 1px solid #2d3a5c; }}
+th {{ background: #1a1a2e; color: #9a9a9a; font-weight: 600; font-size: 0.75rem; text-transform: uppercase; }}
+</style>
+</head>
+<body>
+<h1>Circuit: {name}</h1>
+{summary}
+<h2>Opcodes</h2>
+{opcodes}
+<h2>ABI</h2>
+{abi}
+{brillig}</body>
+</html>"#,
+        name = html_escape(&card.circuit_name),
+    )
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> BenchResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).ok();
+    }
+    std::fs::write(path, contents)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+}
+
+/// Main entry point for `circuit report`.
+///
+/// Prints the markdown card to stdout unless an output file is given.
+pub fn report(
+    artifact: PathBuf,
+    name: Option<String>,
+    backend_path: PathBuf,
+    no_gates: bool,
+    json_out: Option<PathBuf>,
+    md_out: Option<PathBuf>,
+    html_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let program = crate::engine::load_program(&artifact)?;
+    let artifact_bytes = std::fs::metadata(&artifact)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", artifact.display())))?
+        .len();
+    let artifact_sha256 = crate::engine::artifact_sha256(&artifact).unwrap_or_default();
+    let name = name.unwrap_or_else(|| {
+        artifact
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("circuit")
+            .to_string()
+    });
+
+    let gates = if no_gates {
+        None
+    } else {
+        let backend = BarretenbergBackend::new(BarretenbergConfig::new(backend_path));
+        Some((
+            backend.gate_info(&artifact)?,
+            backend.name().to_string(),
+            backend.version(),
+        ))
+    };
+    let card = build_card(
+        &name,
+        &artifact,
+        &program,
+        artifact_bytes,
+        artifact_sha256,
+        gates
+            .as_ref()
+            .map(|(info, backend, version)| (info, backend.as_str(), version.clone())),
+    );
+
+    if let Some(path) = &json_out {
+        let json = serde_json::to_vec_pretty(&card)
+            .map_err(|e| BenchError::Message(format!("failed to serialize circuit card: {e}")))?;
+        write_file(path, &json)?;
+    }
+    if let Some(path) = &md_out {
+        write_file(path, render_markdown(&card).as_bytes())?;
+    }
+    if let Some(path) = &html_out {
+        write_file(path, render_html(&card).as_bytes())?;
+    }
+    if json_out.is_none() && md_out.is_none() && html_out.is_none() {
+        print!("{}", render_markdown(&card));
+    } else {
+        println!(
+            "circuit: {} opcodes={} gates={} brillig_functions={}",
+            card.circuit_name,
+            card.acir_opcodes,
+            fmt_gates(card.total_gates),
+            card.brillig_functions.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card() -> CircuitCard {
+        CircuitCard {
+            circuit_name: "merkle<script>".to_string(),
+            noir_version: "1.0.0-beta.20".to_string(),
+            artifact_path: PathBuf::from("target/merkle.json"),
+            artifact_bytes: 2048,
+            artifact_sha256: "abc".to_string(),
+            acir_functions: 1,
+            acir_opcodes: 3,
+            total_gates: Some(5000),
+            subgroup_size: Some(8192),
+            backend: Some("barretenberg".to_string()),
+            backend_version: Some("0.62.0".to_string()),
+            opcodes: vec![OpcodeKindStats {
+                kind: "assert_zero".to_string(),
+                count: 3,
+                gates: Some(12),
+            }],
+            inputs: vec![AbiEntry {
+                name: "root".to_string(),
+                typ: "[u8; 32]".to_string(),
+                visibility: "public".to_string(),
+                fields: 32,
+            }],
+            return_value: None,
+            input_fields: 32,
+            brillig_functions: vec![BrilligFunctionSize {
+                name: "directive_invert".to_string(),
+                opcodes: 14,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_opcode_breakdown_attributes_main_function_gates() {
+        let main = vec!["assert_zero", "blackbox::range", "assert_zero"];
+        let callee = vec!["assert_zero", "call"];
+        let functions: Vec<Vec<String>> = [main, callee]
+            .iter()
+            .map(|f| f.iter().map(|k| k.to_string()).collect())
+            .collect();
+        let gates: HashMap<String, u64> = [("opcode_0", 4), ("opcode_1", 10), ("opcode_2", 3)]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+
+        let stats = opcode_breakdown(&functions, Some(&gates));
+        assert_eq!(stats[0].kind, "assert_zero");
+        assert_eq!(stats[0].count, 3);
+        assert_eq!(stats[0].gates, Some(7));
+        assert_eq!(stats[1].kind, "blackbox::range");
+        assert_eq!(stats[1].gates, Some(10));
+        assert_eq!(stats[2].kind, "call");
+        assert_eq!(stats[2].gates, Some(0));
+
+        let stats = opcode_breakdown(&functions, None);
+        assert!(stats.iter().all(|s| s.gates.is_none()));
+    }
+
+    #[test]
+    fn test_type_label() {
+        let bytes = AbiType::Array {
+            length: 32,
+            typ: Box::new(AbiType::Integer {
+                sign: Sign::Unsigned,
+                width: 8,
+            }),
+        };
+        assert_eq!(type_label(&bytes), "[u8; 32]");
+        let tuple = AbiType::Tuple {
+            fields: vec![AbiType::Field, AbiType::Boolean],
+        };
+        assert_eq!(type_label(&tuple), "(Field, bool)");
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render_markdown(&card());
+        assert!(md.contains("| Backend gates | 5000 |"));
+        assert!(md.contains("| Subgroup size | 2^13 (8192) |"));
+        assert!(md.contains("target/merkle.json (2.0 KiB)"));
+        assert!(md.contains("| assert_zero | 3 | 12 |"));
+        assert!(md.contains("| root | `[u8; 32]` | public | 32 |"));
+        assert!(md.contains("| directive_invert | 14 |"));
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let html = render_html(&card());
+        assert!(!html.contains("<script"));
+        assert!(html.contains("Circuit: merkle&lt;script&gt;"));
+        assert!(html.contains("<td>[u8; 32]</td>"));
+        assert!(html.contains("<h2>Brillig functions</h2>"));
+    }
+}
@@ -2,53 +2,60 @@
 //!
 //! Supports comparing single JSON reports or JSONL files containing multiple records.
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::engine::provenance;
+use crate::backend::{BarretenbergBackend, BarretenbergConfig, CUSTOM_METRIC_PREFIX};
+use crate::bench::config::load_ci_settings;
+use crate::check_cmd::{Budgets, circuit_budgets, load_budgets};
+use crate::core::annotations;
+use crate::core::calibration::{self, CALIBRATION_METADATA_KEY};
+use crate::core::env::{arch_matches, normalize_arch};
+use crate::core::{
+    BenchRecord, CacheMode, EnvironmentInfo, PROVE_MODE_METADATA_KEY, STANDARD_PROVE_MODE,
+};
+use crate::corpus_cmd::CORPUS_METADATA_KEY;
+use crate::engine::paired::{self, PairedSamples, PairedTest};
+use crate::engine::provenance::{self, EnvironmentMismatch, ThermalWarning};
+use crate::engine::{NargoToolchain, ProveInputs, Toolchain};
+use crate::report::metrics::{self, Verdict};
+use crate::report::reference::{self, ReferenceDataset};
+use crate::report::reporter::{self, ReportData};
 use crate::report::{
-    CircuitRegression, MetricDelta, RegressionReport, RegressionStatus,
-    render_markdown as report_render_markdown, write_html as report_write_html,
+    CircuitRegression, Methodology, MetricDelta, MetricSelection, RegressionReport,
+    RegressionStatus, ThresholdRule, format_value, percent_change, render_junit,
+    render_markdown as report_render_markdown, render_tap, suites_from_report,
 };
 use crate::{BenchError, BenchResult, JsonlWriter};
 
 /// Default regression threshold percentage
 pub const DEFAULT_THRESHOLD: f64 = 10.0;
 
+pub use crate::report::regression::{CircuitThresholds, CompareStatus};
+
 /// Comparison of a single metric
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricComparison {
     pub metric: String,
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub baseline: f64,
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub target: f64,
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub delta: f64,
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub percent: f64,
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub threshold: f64,
+    #[serde(default, skip_serializing_if = "ThresholdRule::is_percent")]
+    pub threshold_rule: ThresholdRule,
     pub status: CompareStatus,
 }
 
-/// Status of a metric comparison
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum CompareStatus {
-    Regression,
-    Improvement,
-    Unchanged,
-}
-
-impl CompareStatus {
-    pub fn emoji(&self) -> &'static str {
-        match self {
-            CompareStatus::Regression => "🔴",
-            CompareStatus::Improvement => "🟢",
-            CompareStatus::Unchanged => "⚪",
-        }
-    }
-}
-
 /// Comparison results for a single circuit
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CircuitComparison {
@@ -65,31 +72,97 @@ pub struct CompareResult {
     pub threshold: f64,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub metric_thresholds: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub circuit_thresholds: CircuitThresholds,
+    /// `k` for noise-relative thresholds, when enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_sigma: Option<f64>,
+    /// Run annotations (commit, PR, ...) carried into reports
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    /// Machine differences between matched baseline and target records
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environment_mismatches: Vec<EnvironmentMismatch>,
+    /// Records measured under elevated thermal pressure
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thermal_warnings: Vec<ThermalWarning>,
+    /// How the compared records were measured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub methodology: Option<Methodology>,
+    /// Metrics reported without counting as regressions or improvements
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub informational_metrics: BTreeSet<String>,
+    /// Budgets of the compared circuits from `[budgets]` (circuit -> metric -> limit)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub budgets: Budgets,
     pub circuits: Vec<CircuitComparison>,
     pub total_regressions: usize,
     pub total_improvements: usize,
     pub ci_exit_code: i32,
 }
 
-/// Metrics to compare with their display names and whether higher is worse
-const METRIC_DEFS: &[(&str, &str, bool)] = &[
-    ("prove_time_ms", "prove_ms", true),
-    ("prove_stats.mean_ms", "prove_ms", true),
-    ("witness_gen_time_ms", "witness_ms", true),
-    ("witness_stats.mean_ms", "witness_ms", true),
-    ("verify_time_ms", "verify_ms", true),
-    ("verify_stats.mean_ms", "verify_ms", true),
-    ("backend_prove_time_ms", "backend_ms", true),
-    ("execution_time_ms", "exec_ms", true),
-    ("total_gates", "total_gates", true),
-    ("proof_size_bytes", "proof_size_bytes", true),
-    ("acir_opcodes", "acir_opcodes", true),
-    ("peak_memory_bytes", "peak_memory_bytes", true),
-    ("peak_rss_mb", "peak_rss_mb", true),
-    ("proving_key_size_bytes", "pk_size", false),
-    ("verification_key_size_bytes", "vk_size", false),
+/// Metrics to compare: JSON path and display name. Direction and unit come
+/// from the registry in `report::metrics`.
+const METRIC_DEFS: &[(&str, &str)] = &[
+    ("prove_time_ms", "prove_ms"),
+    ("prove_stats.mean_ms", "prove_ms"),
+    ("witness_gen_time_ms", "witness_ms"),
+    ("witness_stats.mean_ms", "witness_ms"),
+    ("verify_time_ms", "verify_ms"),
+    ("verify_stats.mean_ms", "verify_ms"),
+    ("verify_cold_ms", "verify_cold_ms"),
+    ("gates_stats.mean_ms", "gates_ms"),
+    ("backend_prove_time_ms", "backend_ms"),
+    ("backend_setup_ms", "backend_setup_ms"),
+    ("vk_gen_stats.mean_ms", "vk_gen_ms"),
+    ("cpu_times.prove.cpu_ms", "prove_cpu_ms"),
+    (
+        "cpu_times.prove.parallelism_efficiency",
+        "prove_parallelism",
+    ),
+    ("execution_time_ms", "exec_ms"),
+    ("exec_split.brillig_ms", "brillig_ms"),
+    ("exec_split.foreign_call_ms", "foreign_call_ms"),
+    ("exec_split.resolver_ms", "oracle_ms"),
+    ("oracle_resolver_ms", "oracle_ms"),
+    ("oracle_stats.mean_ms", "oracle_ms"),
+    ("compile_stats.mean_ms", "compile_ms"),
+    ("compile_warm_stats.mean_ms", "compile_warm_ms"),
+    (
+        "compile_incremental_stats.mean_ms",
+        "compile_incremental_ms",
+    ),
+    ("total_gates", "total_gates"),
+    ("subgroup_size", "subgroup_size"),
+    ("proof_size_bytes", "proof_size_bytes"),
+    ("acir_opcodes", "acir_opcodes"),
+    ("peak_memory_bytes", "peak_memory_bytes"),
+    ("peak_rss_mb", "peak_rss_mb"),
+    ("proving_key_size_bytes", "pk_size"),
+    ("verification_key_size_bytes", "vk_size"),
 ];
 
+/// Compared metrics of `records` as `(json_path, display_name)`: the
+/// `METRIC_DEFS`, then the custom metrics any of them has, as `custom.NAME`.
+fn metric_paths<'a>(records: impl IntoIterator<Item = &'a Value>) -> Vec<(String, String)> {
+    let mut paths: Vec<(String, String)> = METRIC_DEFS
+        .iter()
+        .map(|(path, name)| (path.to_string(), name.to_string()))
+        .collect();
+    let custom: BTreeSet<&String> = records
+        .into_iter()
+        .filter_map(|r| r.get("custom_metrics")?.as_object())
+        .flat_map(|m| m.keys())
+        .collect();
+    paths.extend(custom.into_iter().map(|name| {
+        (
+            format!("custom_metrics.{name}"),
+            format!("{CUSTOM_METRIC_PREFIX}{name}"),
+        )
+    }));
+    paths
+}
+
 fn get_nested_num(v: &Value, path: &str) -> Option<f64> {
     let parts: Vec<&str> = path.split('.').collect();
     let mut current = v;
@@ -102,6 +175,21 @@ fn get_nested_num(v: &Value, path: &str) -> Option<f64> {
         .and_then(|x| x.as_f64().or_else(|| x.as_u64().map(|u| u as f64)))
 }
 
+fn set_nested_num(v: &mut Value, path: &str, num: f64) {
+    let mut current = v;
+    for part in path.split('.') {
+        match current.get_mut(part) {
+            Some(next) => current = next,
+            None => return,
+        }
+    }
+    *current = if current.is_u64() {
+        Value::from(num as u64)
+    } else {
+        Value::from(num)
+    };
+}
+
 fn get_circuit_name(v: &Value) -> Option<String> {
     v.get("circuit_name")
         .and_then(|x| x.as_str())
@@ -131,18 +219,85 @@ fn threshold_for_metric(
     metric_thresholds.get(metric).copied().unwrap_or(threshold)
 }
 
+/// Resolve the default and per-metric thresholds for one circuit.
+///
+/// Precedence: circuit metric, circuit `default`, global metric, global default.
+fn thresholds_for_circuit(
+    circuit: &str,
+    threshold: f64,
+    metric_thresholds: &BTreeMap<String, f64>,
+    circuit_thresholds: &CircuitThresholds,
+) -> (f64, BTreeMap<String, f64>) {
+    let Some(overrides) = circuit_thresholds.get(circuit) else {
+        return (threshold, metric_thresholds.clone());
+    };
+    let (threshold, mut resolved) = match overrides.get("default") {
+        Some(default) => (*default, BTreeMap::new()),
+        None => (threshold, metric_thresholds.clone()),
+    };
+    resolved.extend(
+        overrides
+            .iter()
+            .filter(|(metric, _)| metric.as_str() != "default")
+            .map(|(metric, pct)| (metric.clone(), *pct)),
+    );
+    (threshold, resolved)
+}
+
+/// Standard deviation of the baseline samples behind a `*.mean_ms` metric.
+///
+/// Uses `stddev_ms` when recorded, else estimates it from the spread between
+/// median and p95 (1.645σ for a normal distribution).
+fn baseline_stddev(baseline: &Value, json_path: &str) -> Option<f64> {
+    let stat = json_path.strip_suffix(".mean_ms")?;
+    let stddev = get_nested_num(baseline, &format!("{stat}.stddev_ms")).or_else(|| {
+        let p95 = get_nested_num(baseline, &format!("{stat}.p95_ms"))?;
+        let median = get_nested_num(baseline, &format!("{stat}.median_ms"))?;
+        Some((p95 - median) / 1.645)
+    })?;
+    (stddev > 0.0).then_some(stddev)
+}
+
+/// Threshold for one metric: `k`·σ of the baseline (as a percent of the
+/// baseline value) when `noise_sigma` is set and the baseline has a spread,
+/// otherwise the configured percentage.
+fn metric_threshold(
+    baseline: &Value,
+    json_path: &str,
+    display_name: &str,
+    baseline_value: f64,
+    threshold: f64,
+    metric_thresholds: &BTreeMap<String, f64>,
+    noise_sigma: Option<f64>,
+) -> (f64, ThresholdRule) {
+    let noise = noise_sigma
+        .filter(|_| baseline_value > 0.0)
+        .and_then(|k| Some((k, baseline_stddev(baseline, json_path)?)))
+        .map(|(k, stddev_ms)| (k, stddev_ms, k * stddev_ms * 100.0 / baseline_value))
+        .filter(|(_, _, pct)| pct.is_finite());
+    match noise {
+        Some((k, stddev_ms, pct)) => (pct, ThresholdRule::Noise { k, stddev_ms }),
+        None => (
+            threshold_for_metric(display_name, threshold, metric_thresholds),
+            ThresholdRule::Percent,
+        ),
+    }
+}
+
 fn compare_values(
     baseline: &Value,
     target: &Value,
     threshold: f64,
     metric_thresholds: &BTreeMap<String, f64>,
+    noise_sigma: Option<f64>,
 ) -> Vec<MetricComparison> {
     let mut results = Vec::new();
     let mut seen_metrics = std::collections::HashSet::new();
+    let paths = metric_paths([baseline]);
 
-    for (json_path, display_name, higher_is_worse) in METRIC_DEFS {
+    for (json_path, display_name) in &paths {
         // Skip if we've already seen this display name
-        if seen_metrics.contains(*display_name) {
+        if seen_metrics.contains(display_name) {
             continue;
         }
 
@@ -150,23 +305,24 @@ fn compare_values(
             get_nested_num(baseline, json_path),
             get_nested_num(target, json_path),
         ) {
-            seen_metrics.insert(*display_name);
+            seen_metrics.insert(display_name);
 
             let delta = tv - bv;
-            let percent = if bv != 0.0 { delta * 100.0 / bv } else { 0.0 };
-            let metric_threshold = threshold_for_metric(display_name, threshold, metric_thresholds);
-
-            let status = if *higher_is_worse {
-                if percent > metric_threshold {
-                    CompareStatus::Regression
-                } else if percent < -metric_threshold {
-                    CompareStatus::Improvement
-                } else {
-                    CompareStatus::Unchanged
-                }
-            } else {
-                // For metrics where lower is worse (like key sizes - informational only)
-                CompareStatus::Unchanged
+            let percent = percent_change(bv, tv);
+            let (metric_threshold, threshold_rule) = metric_threshold(
+                baseline,
+                json_path,
+                display_name,
+                bv,
+                threshold,
+                metric_thresholds,
+                noise_sigma,
+            );
+
+            let status = match metrics::direction(display_name).judge(percent, metric_threshold) {
+                Verdict::Worse => CompareStatus::Regression,
+                Verdict::Better => CompareStatus::Improvement,
+                Verdict::Within => CompareStatus::Unchanged,
             };
 
             results.push(MetricComparison {
@@ -176,6 +332,7 @@ fn compare_values(
                 delta,
                 percent,
                 threshold: metric_threshold,
+                threshold_rule,
                 status,
             });
         }
@@ -189,12 +346,13 @@ fn compare_single_records(
     target: &Value,
     threshold: f64,
     metric_thresholds: &BTreeMap<String, f64>,
+    noise_sigma: Option<f64>,
 ) -> CircuitComparison {
     let circuit_name = get_circuit_name(baseline)
         .or_else(|| get_circuit_name(target))
         .unwrap_or_else(|| "unknown".to_string());
 
-    let metrics = compare_values(baseline, target, threshold, metric_thresholds);
+    let metrics = compare_values(baseline, target, threshold, metric_thresholds, noise_sigma);
     let has_regression = metrics
         .iter()
         .any(|m| m.status == CompareStatus::Regression);
@@ -206,49 +364,263 @@ fn compare_single_records(
     }
 }
 
+/// Whether `record` was measured with a cold page cache. Cold and warm runs
+/// of a circuit are compared separately.
+fn is_cold(record: &BenchRecord) -> bool {
+    record.cache_mode == Some(CacheMode::Cold)
+}
+
+/// Name a comparison of `record` is reported under: cold-cache runs are
+/// marked so they don't read as a second run of the warm circuit.
+fn comparison_label(record: &BenchRecord) -> String {
+    if is_cold(record) {
+        format!("{} (cold cache)", record.circuit_name)
+    } else {
+        record.circuit_name.clone()
+    }
+}
+
+/// Collapse a history into one baseline record per circuit, backend and
+/// cache mode.
+///
+/// Each baseline is the latest record of its circuit/backend with every
+/// compared metric replaced by the median over the last `window` records, so
+/// a single bad run does not skew the comparison. For an even window the
+/// lower median is used, which keeps integer metrics integral. Failed runs
+/// (with an `error`) and expected failures are left out.
+pub fn window_baseline(history: Vec<BenchRecord>, window: usize) -> BenchResult<Vec<BenchRecord>> {
+    let mut groups: BTreeMap<(String, String, bool), Vec<Value>> = BTreeMap::new();
+    for record in history
+        .into_iter()
+        .filter(|r| r.error.is_none() && !r.is_expected_failure())
+    {
+        let json = serde_json::to_value(&record)
+            .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+        let cold = is_cold(&record);
+        groups
+            .entry((record.circuit_name, record.backend.name, cold))
+            .or_default()
+            .push(json);
+    }
+
+    let mut baselines = Vec::with_capacity(groups.len());
+    for runs in groups.into_values() {
+        let runs = &runs[runs.len().saturating_sub(window.max(1))..];
+        let mut baseline = runs[runs.len() - 1].clone();
+        for (json_path, _) in metric_paths(runs) {
+            let mut values: Vec<f64> = runs
+                .iter()
+                .filter_map(|r| get_nested_num(r, &json_path))
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+            values.sort_by(f64::total_cmp);
+            set_nested_num(&mut baseline, &json_path, values[(values.len() - 1) / 2]);
+        }
+        let record = serde_json::from_value(baseline)
+            .map_err(|e| BenchError::Message(format!("failed to build baseline record: {e}")))?;
+        baselines.push(record);
+    }
+    Ok(baselines)
+}
+
 /// Compare JSONL files by matching records with the same circuit_name
+///
+/// With `baseline_window`, the baseline file is a history and each circuit is
+/// compared against the median of its last runs (see `window_baseline`).
+/// Baseline records flagged as anomalies are dropped unless
+/// `include_anomalies` is set.
 fn compare_jsonl_files(
     baseline_path: &PathBuf,
     target_path: &PathBuf,
     threshold: f64,
     metric_thresholds: &BTreeMap<String, f64>,
-) -> BenchResult<Vec<CircuitComparison>> {
+    circuit_thresholds: &CircuitThresholds,
+    noise_sigma: Option<f64>,
+    baseline_window: Option<usize>,
+    arch: Option<&str>,
+    allow_cross_arch: bool,
+    include_anomalies: bool,
+) -> BenchResult<RecordSetComparison> {
     let baseline_reader = JsonlWriter::new(baseline_path);
     let target_reader = JsonlWriter::new(target_path);
 
-    let baseline_records = baseline_reader.read_all()?;
-    let target_records = target_reader.read_all()?;
+    let mut baseline_records = baseline_reader.read_all()?;
+    let mut target_records = target_reader.read_all()?;
+    if let Some(arch) = arch {
+        baseline_records.retain(|r| arch_matches(&r.env, arch));
+        target_records.retain(|r| arch_matches(&r.env, arch));
+    }
+    if !include_anomalies {
+        baseline_records.retain(|r| !r.anomaly);
+    }
+    if let Some(window) = baseline_window {
+        baseline_records = window_baseline(baseline_records, window)?;
+    }
+    compare_record_sets(
+        baseline_records,
+        target_records,
+        threshold,
+        metric_thresholds,
+        circuit_thresholds,
+        noise_sigma,
+        allow_cross_arch,
+    )
+}
+
+/// Comparisons of matched records plus what their environment blocks say
+/// about how comparable they are.
+#[derive(Default)]
+struct RecordSetComparison {
+    circuits: Vec<CircuitComparison>,
+    environment_mismatches: Vec<EnvironmentMismatch>,
+    thermal_warnings: Vec<ThermalWarning>,
+    methodology: Option<Methodology>,
+}
 
-    // Index baseline records by circuit_name
-    let mut baseline_map: HashMap<String, Value> = HashMap::new();
+/// Compare two sets of records, matching them by circuit_name and cache mode
+/// (cold-cache runs are only compared with cold-cache runs). Also returns
+/// the distinct environment differences across matched records and the
+/// records measured under thermal pressure. Failed runs (with an `error`)
+/// carry partial metrics and are left out on both sides, as are expected
+/// failures, whose timings are time to failure.
+fn compare_record_sets(
+    baseline_records: Vec<BenchRecord>,
+    target_records: Vec<BenchRecord>,
+    threshold: f64,
+    metric_thresholds: &BTreeMap<String, f64>,
+    circuit_thresholds: &CircuitThresholds,
+    noise_sigma: Option<f64>,
+    allow_cross_arch: bool,
+) -> BenchResult<RecordSetComparison> {
+    let ok = |r: &BenchRecord| r.error.is_none() && !r.is_expected_failure();
+    let baseline_records: Vec<BenchRecord> = baseline_records.into_iter().filter(ok).collect();
+    let target_records: Vec<BenchRecord> = target_records.into_iter().filter(ok).collect();
+    let methodology = Methodology::from_records(baseline_records.iter().chain(&target_records));
+    // Index baseline records by circuit_name and cache mode
+    let mut baseline_map: HashMap<(String, bool), Value> = HashMap::new();
+    let mut baseline_envs = HashMap::new();
     for record in baseline_records {
         let json = serde_json::to_value(&record)
             .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
-        baseline_map.insert(record.circuit_name.clone(), json);
+        let key = (record.circuit_name.clone(), is_cold(&record));
+        baseline_map.insert(key.clone(), json);
+        baseline_envs.insert(key, record.env);
     }
+    let mut environment_mismatches: Vec<EnvironmentMismatch> = Vec::new();
+    let mut thermal_warnings: Vec<ThermalWarning> = Vec::new();
 
     // Compare each target record against its baseline
     let mut comparisons = Vec::new();
     for record in target_records {
+        thermal_warnings.extend(provenance::check_thermal_pressure(
+            &record.circuit_name,
+            "target",
+            &record.env,
+        ));
         let target_json = serde_json::to_value(&record)
             .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
-
-        if let Some(baseline_json) = baseline_map.get(&record.circuit_name) {
-            let comparison =
-                compare_single_records(baseline_json, &target_json, threshold, metric_thresholds);
+        let (threshold, metric_thresholds) = thresholds_for_circuit(
+            &record.circuit_name,
+            threshold,
+            metric_thresholds,
+            circuit_thresholds,
+        );
+
+        let key = (record.circuit_name.clone(), is_cold(&record));
+        if let Some(baseline_json) = baseline_map.get(&key) {
+            let baseline_env = &baseline_envs[&key];
+            if !allow_cross_arch {
+                check_same_arch(&record.circuit_name, baseline_env, &record.env)?;
+            }
+            thermal_warnings.extend(provenance::check_thermal_pressure(
+                &record.circuit_name,
+                "baseline",
+                baseline_env,
+            ));
+            for mismatch in
+                provenance::check_record_environment_mismatches(baseline_env, &record.env)
+                    .into_iter()
+                    .chain(prove_mode_mismatch(baseline_json, &target_json))
+            {
+                if !environment_mismatches.contains(&mismatch) {
+                    environment_mismatches.push(mismatch);
+                }
+            }
+            let mut comparison = compare_single_records(
+                baseline_json,
+                &target_json,
+                threshold,
+                &metric_thresholds,
+                noise_sigma,
+            );
+            comparison.circuit_name = comparison_label(&record);
             comparisons.push(comparison);
         } else {
             // New circuit in target, no baseline to compare
-            let metrics = compare_values(&Value::Null, &target_json, threshold, metric_thresholds);
+            let metrics = compare_values(
+                &Value::Null,
+                &target_json,
+                threshold,
+                &metric_thresholds,
+                noise_sigma,
+            );
             comparisons.push(CircuitComparison {
-                circuit_name: record.circuit_name,
+                circuit_name: comparison_label(&record),
                 metrics,
                 has_regression: false,
             });
         }
     }
 
-    Ok(comparisons)
+    Ok(RecordSetComparison {
+        circuits: comparisons,
+        environment_mismatches,
+        thermal_warnings,
+        methodology,
+    })
+}
+
+/// Refuse to compare records measured on different CPU architectures;
+/// records with no arch recorded are let through.
+fn check_same_arch(
+    circuit_name: &str,
+    baseline: &EnvironmentInfo,
+    target: &EnvironmentInfo,
+) -> BenchResult<()> {
+    let (Some(b), Some(t)) = (baseline.arch.as_deref(), target.arch.as_deref()) else {
+        return Ok(());
+    };
+    let (b, t) = (normalize_arch(b), normalize_arch(t));
+    if b != t {
+        return Err(BenchError::Message(format!(
+            "{circuit_name}: baseline was measured on {b} and target on {t}; \
+             pass --allow-cross-arch to compare across architectures"
+        )));
+    }
+    Ok(())
+}
+
+/// How `prove_ms` was measured: `metadata.prove_mode` on records, the
+/// top-level `prove_mode` on prove reports, standard when neither says.
+fn prove_mode(json: &Value) -> &str {
+    json.get("metadata")
+        .and_then(|m| m.get(PROVE_MODE_METADATA_KEY))
+        .or_else(|| json.get(PROVE_MODE_METADATA_KEY))
+        .and_then(Value::as_str)
+        .unwrap_or(STANDARD_PROVE_MODE)
+}
+
+/// Baseline and target prove times measured in different modes (e.g. one
+/// with `--amortize-setup`), so `prove_ms` deltas aren't like for like.
+fn prove_mode_mismatch(baseline: &Value, target: &Value) -> Option<EnvironmentMismatch> {
+    let (baseline, target) = (prove_mode(baseline), prove_mode(target));
+    (baseline != target).then(|| EnvironmentMismatch {
+        field: PROVE_MODE_METADATA_KEY.to_string(),
+        baseline: baseline.to_string(),
+        target: target.to_string(),
+    })
 }
 
 /// Compare single JSON files
@@ -257,7 +629,9 @@ fn compare_json_files(
     target_path: &PathBuf,
     threshold: f64,
     metric_thresholds: &BTreeMap<String, f64>,
-) -> BenchResult<Vec<CircuitComparison>> {
+    circuit_thresholds: &CircuitThresholds,
+    noise_sigma: Option<f64>,
+) -> BenchResult<RecordSetComparison> {
     let b = std::fs::read(baseline_path).map_err(|e| BenchError::Message(e.to_string()))?;
     let t = std::fs::read(target_path).map_err(|e| BenchError::Message(e.to_string()))?;
     let baseline: Value =
@@ -265,40 +639,25 @@ fn compare_json_files(
     let target: Value =
         serde_json::from_slice(&t).map_err(|e| BenchError::Message(e.to_string()))?;
 
-    let comparison = compare_single_records(&baseline, &target, threshold, metric_thresholds);
-    Ok(vec![comparison])
-}
-
-fn format_value(value: f64, metric: &str) -> String {
-    if metric.contains("size") || metric.contains("mem") || metric.contains("rss") {
-        if metric.contains("rss_mb") {
-            format!("{:.1} MB", value)
-        } else if value >= 1_000_000_000.0 {
-            format!("{:.1} GB", value / 1_000_000_000.0)
-        } else if value >= 1_000_000.0 {
-            format!("{:.1} MB", value / 1_000_000.0)
-        } else if value >= 1_000.0 {
-            format!("{:.1} KB", value / 1_000.0)
-        } else {
-            format!("{:.0} B", value)
-        }
-    } else if metric.contains("ms") {
-        if value >= 1000.0 {
-            format!("{:.2}s", value / 1000.0)
-        } else {
-            format!("{:.0}ms", value)
-        }
-    } else if metric.contains("gates") {
-        if value >= 1_000_000.0 {
-            format!("{:.2}M", value / 1_000_000.0)
-        } else if value >= 1_000.0 {
-            format!("{:.1}K", value / 1_000.0)
-        } else {
-            format!("{:.0}", value)
-        }
-    } else {
-        format!("{:.0}", value)
-    }
+    let circuit = get_circuit_name(&baseline)
+        .or_else(|| get_circuit_name(&target))
+        .unwrap_or_default();
+    let (threshold, metric_thresholds) =
+        thresholds_for_circuit(&circuit, threshold, metric_thresholds, circuit_thresholds);
+    let comparison = compare_single_records(
+        &baseline,
+        &target,
+        threshold,
+        &metric_thresholds,
+        noise_sigma,
+    );
+    Ok(RecordSetComparison {
+        circuits: vec![comparison],
+        environment_mismatches: prove_mode_mismatch(&baseline, &target)
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    })
 }
 
 fn format_text(result: &CompareResult) -> String {
@@ -316,27 +675,66 @@ fn format_text(result: &CompareResult) -> String {
         out.push('\n');
     }
 
+    if let Some(k) = result.noise_sigma {
+        out.push_str(&format!(
+            "Timing thresholds: {k}σ of the baseline where it has a spread\n\n"
+        ));
+    }
+
+    if !result.circuit_thresholds.is_empty() {
+        out.push_str("Circuit thresholds:\n");
+        for (circuit, thresholds) in &result.circuit_thresholds {
+            for (metric, metric_threshold) in thresholds {
+                out.push_str(&format!(
+                    "  {}/{}: {:.1}%\n",
+                    circuit, metric, metric_threshold
+                ));
+            }
+        }
+        out.push('\n');
+    }
+
     for circuit in &result.circuits {
         out.push_str(&format!("Circuit: {}\n", circuit.circuit_name));
         for m in &circuit.metrics {
             let status_str = match m.status {
+                _ if result.informational_metrics.contains(&m.metric) => "[INFO]",
                 CompareStatus::Regression => "[REGRESS]",
                 CompareStatus::Improvement => "[IMPROVE]",
                 CompareStatus::Unchanged => "[OK]",
             };
             out.push_str(&format!(
-                "  {}: {} -> {} ({:+.2}%, threshold {:.1}%) {}\n",
+                "  {}: {} -> {} ({:+.2}%, threshold {}) {}\n",
                 m.metric,
                 format_value(m.baseline, &m.metric),
                 format_value(m.target, &m.metric),
                 m.percent,
-                m.threshold,
+                m.threshold_rule.format_threshold(m.threshold),
                 status_str
             ));
         }
         out.push('\n');
     }
 
+    for m in &result.environment_mismatches {
+        out.push_str(&format!(
+            "Warning: environment changed: {}: {} -> {}\n",
+            m.field, m.baseline, m.target
+        ));
+    }
+
+    for w in &result.thermal_warnings {
+        out.push_str(&format!("Warning: {}: {}\n", w.circuit_name, w.describe()));
+    }
+
+    for crossing in to_regression_report(result).subgroup_crossings {
+        out.push_str(&format!(
+            "Warning: {}: {}\n",
+            crossing.circuit_name,
+            crossing.describe()
+        ));
+    }
+
     if result.total_regressions > 0 {
         out.push_str(&format!(
             "Result: {} regression(s) detected\n",
@@ -361,15 +759,53 @@ pub struct CompareConfig {
     pub target_json: Option<PathBuf>,
     pub threshold: f64,
     pub metric_thresholds: BTreeMap<String, f64>,
+    pub circuit_thresholds: CircuitThresholds,
+    /// Use `k`·σ of the baseline as the threshold for timing metrics
+    pub noise_sigma: Option<f64>,
+    /// Treat `baseline_file` as a history and compare against the median of
+    /// the last N runs per circuit/backend
+    pub baseline_window: Option<usize>,
+    /// Only compare records measured on this CPU architecture
+    pub arch: Option<String>,
+    /// Compare records measured on different architectures instead of
+    /// refusing
+    pub allow_cross_arch: bool,
+    /// Keep baseline records flagged as anomalies (see `core::anomaly`)
+    pub include_anomalies: bool,
+    /// Metrics to compare, and which of them are informational only
+    pub metrics: MetricSelection,
     pub format: String,
     pub json_out: Option<PathBuf>,
 }
 
+/// Attach each compared circuit's budgets (see `check_cmd::circuit_budgets`)
+/// so reports can show the headroom left under them.
+pub fn attach_budgets(result: &mut CompareResult, budgets: &Budgets) {
+    result.budgets = result
+        .circuits
+        .iter()
+        .map(|c| {
+            (
+                c.circuit_name.clone(),
+                circuit_budgets(budgets, &c.circuit_name),
+            )
+        })
+        .filter(|(_, b)| !b.is_empty())
+        .collect();
+}
+
 /// Convert CompareResult to RegressionReport for JSON output.
 pub fn to_regression_report(result: &CompareResult) -> RegressionReport {
     let mut report =
         RegressionReport::new(&result.baseline_ref, &result.target_ref, result.threshold);
     report.set_metric_thresholds(result.metric_thresholds.clone());
+    report.set_circuit_thresholds(result.circuit_thresholds.clone());
+    report.set_annotations(result.annotations.clone());
+    report.set_informational_metrics(result.informational_metrics.clone());
+    report.set_budgets(result.budgets.clone());
+    report.environment_mismatches = result.environment_mismatches.clone();
+    report.thermal_warnings = result.thermal_warnings.clone();
+    report.methodology = result.methodology.clone();
 
     for circuit in &result.circuits {
         let metrics: Vec<MetricDelta> = circuit
@@ -389,6 +825,7 @@ pub fn to_regression_report(result: &CompareResult) -> RegressionReport {
                     delta_abs: m.delta,
                     delta_pct: m.percent,
                     threshold: m.threshold,
+                    threshold_rule: m.threshold_rule,
                     status,
                 }
             })
@@ -419,34 +856,46 @@ pub fn to_regression_report(result: &CompareResult) -> RegressionReport {
 
 /// Run comparison and return result
 pub fn compare(config: &CompareConfig) -> BenchResult<CompareResult> {
-    let (circuits, baseline_ref, target_ref) = if let (Some(baseline), Some(target)) =
+    check_metric_selection(&config.metrics)?;
+    let (mut compared, baseline_ref, target_ref) = if let (Some(baseline), Some(target)) =
         (&config.baseline_file, &config.target_file)
     {
         // JSONL comparison
-        let circuits = compare_jsonl_files(
+        let compared = compare_jsonl_files(
             baseline,
             target,
             config.threshold,
             &config.metric_thresholds,
+            &config.circuit_thresholds,
+            config.noise_sigma,
+            config.baseline_window,
+            config.arch.as_deref(),
+            config.allow_cross_arch,
+            config.include_anomalies,
         )?;
-        let baseline_ref = baseline
+        let mut baseline_ref = baseline
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("baseline")
             .to_string();
+        if let Some(window) = config.baseline_window {
+            baseline_ref = format!("{baseline_ref} (median of last {window})");
+        }
         let target_ref = target
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("target")
             .to_string();
-        (circuits, baseline_ref, target_ref)
+        (compared, baseline_ref, target_ref)
     } else if let (Some(baseline), Some(target)) = (&config.baseline_json, &config.target_json) {
         // Single JSON comparison (legacy)
-        let circuits = compare_json_files(
+        let compared = compare_json_files(
             baseline,
             target,
             config.threshold,
             &config.metric_thresholds,
+            &config.circuit_thresholds,
+            config.noise_sigma,
         )?;
         let baseline_ref = baseline
             .file_name()
@@ -458,13 +907,111 @@ pub fn compare(config: &CompareConfig) -> BenchResult<CompareResult> {
             .and_then(|s| s.to_str())
             .unwrap_or("target")
             .to_string();
-        (circuits, baseline_ref, target_ref)
+        (compared, baseline_ref, target_ref)
     } else {
         return Err(BenchError::Message(
             "must provide either --baseline-file/--target-file or --baseline/--contender".into(),
         ));
     };
+    apply_metric_selection(&mut compared.circuits, &config.metrics);
+
+    let mut result = summarize(
+        compared.circuits,
+        baseline_ref,
+        target_ref,
+        config.threshold,
+        &config.metric_thresholds,
+        &config.circuit_thresholds,
+        config.noise_sigma,
+    );
+    result.environment_mismatches = compared.environment_mismatches;
+    result.thermal_warnings = compared.thermal_warnings;
+    result.methodology = compared.methodology;
+    result.informational_metrics = config.metrics.informational.clone();
+    Ok(result)
+}
+
+/// Reject metric names no comparison produces, so a typo in `--metrics`
+/// doesn't silently compare nothing. Custom metrics (`custom.NAME`) depend
+/// on the records and are always accepted.
+pub fn check_metric_selection(selection: &MetricSelection) -> BenchResult<()> {
+    let mut known: Vec<&str> = METRIC_DEFS.iter().map(|(_, name)| *name).collect();
+    known.sort_unstable();
+    known.dedup();
+    let mut unknown = selection.unknown(&known);
+    unknown.retain(|m| !m.starts_with(CUSTOM_METRIC_PREFIX));
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(BenchError::Message(format!(
+        "unknown metric(s): {} (expected one of: {})",
+        unknown.join(", "),
+        known.join(", ")
+    )))
+}
+
+/// Drop unselected metrics and neutralize informational ones, which stay in
+/// the report but never count as a regression or improvement.
+fn apply_metric_selection(circuits: &mut [CircuitComparison], selection: &MetricSelection) {
+    for circuit in circuits {
+        circuit.metrics.retain(|m| selection.includes(&m.metric));
+        for m in &mut circuit.metrics {
+            if selection.is_informational(&m.metric) {
+                m.status = CompareStatus::Unchanged;
+            }
+        }
+        circuit.has_regression = circuit
+            .metrics
+            .iter()
+            .any(|m| m.status == CompareStatus::Regression);
+    }
+}
+
+/// Compare in-memory record sets (e.g. the same circuits run under two
+/// backend versions). Records are matched by circuit_name and cache mode.
+pub fn compare_records(
+    baseline: Vec<BenchRecord>,
+    target: Vec<BenchRecord>,
+    baseline_ref: &str,
+    target_ref: &str,
+    threshold: f64,
+    metric_thresholds: &BTreeMap<String, f64>,
+    circuit_thresholds: &CircuitThresholds,
+    noise_sigma: Option<f64>,
+) -> BenchResult<CompareResult> {
+    let compared = compare_record_sets(
+        baseline,
+        target,
+        threshold,
+        metric_thresholds,
+        circuit_thresholds,
+        noise_sigma,
+        true,
+    )?;
+    let mut result = summarize(
+        compared.circuits,
+        baseline_ref.to_string(),
+        target_ref.to_string(),
+        threshold,
+        metric_thresholds,
+        circuit_thresholds,
+        noise_sigma,
+    );
+    result.environment_mismatches = compared.environment_mismatches;
+    result.thermal_warnings = compared.thermal_warnings;
+    result.methodology = compared.methodology;
+    Ok(result)
+}
 
+fn summarize(
+    circuits: Vec<CircuitComparison>,
+    baseline_ref: String,
+    target_ref: String,
+    threshold: f64,
+    metric_thresholds: &BTreeMap<String, f64>,
+    circuit_thresholds: &CircuitThresholds,
+    noise_sigma: Option<f64>,
+) -> CompareResult {
     let total_regressions = circuits
         .iter()
         .flat_map(|c| &c.metrics)
@@ -479,42 +1026,186 @@ pub fn compare(config: &CompareConfig) -> BenchResult<CompareResult> {
 
     let ci_exit_code = if total_regressions > 0 { 1 } else { 0 };
 
-    Ok(CompareResult {
+    CompareResult {
         baseline_ref,
         target_ref,
-        threshold: config.threshold,
-        metric_thresholds: config.metric_thresholds.clone(),
+        threshold,
+        metric_thresholds: metric_thresholds.clone(),
+        circuit_thresholds: circuit_thresholds.clone(),
+        noise_sigma,
+        annotations: BTreeMap::new(),
+        environment_mismatches: Vec::new(),
+        thermal_warnings: Vec::new(),
+        methodology: None,
+        informational_metrics: BTreeSet::new(),
+        budgets: Budgets::new(),
         circuits,
         total_regressions,
         total_improvements,
         ci_exit_code,
-    })
+    }
 }
 
 /// Main entry point for the compare command
+///
+/// With `config`, per-metric and per-circuit thresholds (and the default
+/// threshold, noise sigma, baseline window and metric selection, unless
+/// given) come from its `[ci]` section, and the budgets shown in the reports
+/// from its `[budgets]` section. `annotate` holds `key=value` annotations (commit, PR, ...)
+/// embedded in the JSON and HTML reports.
 pub fn run(
     baseline: Option<PathBuf>,
     contender: Option<PathBuf>,
     baseline_file: Option<PathBuf>,
     target_file: Option<PathBuf>,
-    threshold: f64,
+    threshold: Option<f64>,
+    noise_sigma: Option<f64>,
+    baseline_window: Option<usize>,
+    arch: Option<String>,
+    allow_cross_arch: bool,
+    include_anomalies: bool,
+    metrics: Vec<String>,
+    informational_metrics: Vec<String>,
+    config: Option<PathBuf>,
+    annotate: Vec<String>,
     format: String,
     json_out: Option<PathBuf>,
     html_out: Option<PathBuf>,
 ) -> BenchResult<CompareResult> {
+    let annotations = annotations::parse(&annotate)?;
+    let ci_config = config
+        .as_ref()
+        .map(load_ci_settings)
+        .transpose()?
+        .unwrap_or_default();
+    let budgets = config
+        .as_deref()
+        .map(load_budgets)
+        .transpose()?
+        .unwrap_or_default();
+    let metrics = if metrics.is_empty() && informational_metrics.is_empty() {
+        ci_config.metric_selection()
+    } else {
+        MetricSelection::new(&metrics, &informational_metrics)
+    };
     let config = CompareConfig {
         baseline_file,
         target_file,
         baseline_json: baseline,
         target_json: contender,
-        threshold,
-        metric_thresholds: BTreeMap::new(),
+        threshold: threshold
+            .or(ci_config.threshold_percent)
+            .unwrap_or(DEFAULT_THRESHOLD),
+        metric_thresholds: ci_config.thresholds,
+        circuit_thresholds: ci_config.circuit_thresholds,
+        noise_sigma: noise_sigma.or(ci_config.noise_sigma),
+        baseline_window: baseline_window.or(ci_config.baseline_window),
+        arch,
+        allow_cross_arch: allow_cross_arch || ci_config.allow_cross_arch,
+        include_anomalies,
+        metrics,
         format: format.clone(),
         json_out: json_out.clone(),
     };
 
-    let result = compare(&config)?;
+    let mut result = compare(&config)?;
+    result.annotations = annotations;
+    attach_budgets(&mut result, &budgets);
+    emit(result, &format, json_out, html_out)
+}
+
+/// Compare corpus results against a reference dataset (see
+/// `report::reference`), with reference timings normalized to this
+/// machine's calibration score.
+///
+/// The local score is taken from the records' `calibration_score` metadata
+/// (written by `corpus run`), or measured now if absent. Reference
+/// comparisons are informational: `ci_exit_code` is always 0.
+pub fn compare_reference(
+    dataset: &ReferenceDataset,
+    target_records: Vec<BenchRecord>,
+    target_ref: &str,
+    machine: Option<&str>,
+    threshold: f64,
+) -> BenchResult<CompareResult> {
+    let target_records: Vec<BenchRecord> = target_records
+        .into_iter()
+        .filter(|r| {
+            r.metadata.get(CORPUS_METADATA_KEY).map(String::as_str)
+                == Some(dataset.corpus_version.as_str())
+        })
+        .collect();
+    if target_records.is_empty() {
+        return Err(BenchError::Message(format!(
+            "no corpus {} records to compare against '{}' (run `noir-bench corpus run` first)",
+            dataset.corpus_version, dataset.name
+        )));
+    }
+
+    let mut scores: Vec<f64> = target_records
+        .iter()
+        .filter_map(|r| r.metadata.get(CALIBRATION_METADATA_KEY)?.parse().ok())
+        .collect();
+    let local_score = if scores.is_empty() {
+        calibration::measure()
+    } else {
+        scores.sort_by(f64::total_cmp);
+        scores[scores.len() / 2]
+    };
+
+    let reference = dataset.select_machine(machine, local_score)?;
+    let baseline_ref = format!(
+        "{}/{} (score {:.1}, normalized to {:.1})",
+        dataset.name, reference.id, reference.calibration_score, local_score
+    );
+    let mut result = compare_records(
+        reference.normalized_records(local_score),
+        target_records,
+        &baseline_ref,
+        target_ref,
+        threshold,
+        &BTreeMap::new(),
+        &CircuitThresholds::new(),
+        None,
+    )?;
+    result.ci_exit_code = 0;
+    Ok(result)
+}
+
+/// Entry point for `compare --reference`.
+pub fn run_reference(
+    reference: String,
+    machine: Option<String>,
+    target_file: PathBuf,
+    threshold: f64,
+    format: String,
+    json_out: Option<PathBuf>,
+    html_out: Option<PathBuf>,
+) -> BenchResult<CompareResult> {
+    let dataset = reference::load(&reference)?;
+    let records = JsonlWriter::new(&target_file).read_all()?;
+    let target_ref = target_file
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("target")
+        .to_string();
+    let result = compare_reference(
+        &dataset,
+        records,
+        &target_ref,
+        machine.as_deref(),
+        threshold,
+    )?;
+    emit(result, &format, json_out, html_out)
+}
 
+/// Write the requested report files and print the comparison.
+fn emit(
+    result: CompareResult,
+    format: &str,
+    json_out: Option<PathBuf>,
+    html_out: Option<PathBuf>,
+) -> BenchResult<CompareResult> {
     // Collect provenance once for reuse
     let target_provenance = provenance::collect(None);
 
@@ -537,12 +1228,21 @@ pub fn run(
         let mut regression_report = to_regression_report(&result);
         regression_report.set_provenance(None, Some(target_provenance.clone()));
 
-        report_write_html(html_path, &regression_report)
-            .map_err(|e| BenchError::Message(format!("failed to write HTML report: {e}")))?;
+        reporter::write_with(
+            "html",
+            html_path,
+            &ReportData::Regression(&regression_report),
+        )?;
         eprintln!("Wrote HTML report to {}", html_path.display());
     }
 
-    let output = match format.as_str() {
+    if reporter::has_reporters() {
+        let mut regression_report = to_regression_report(&result);
+        regression_report.set_provenance(None, Some(target_provenance.clone()));
+        reporter::publish(&ReportData::Regression(&regression_report));
+    }
+
+    let output = match format {
         "json" => format_json(&result),
         "markdown" | "md" => {
             // Use the new report markdown renderer for better output
@@ -550,6 +1250,8 @@ pub fn run(
             regression_report.set_provenance(None, Some(target_provenance));
             report_render_markdown(&regression_report)
         }
+        "junit" => render_junit(&suites_from_report(&to_regression_report(&result))),
+        "tap" => render_tap(&suites_from_report(&to_regression_report(&result))),
         _ => format_text(&result),
     };
 
@@ -558,10 +1260,213 @@ pub fn run(
     Ok(result)
 }
 
+/// Default significance level of `compare --paired`.
+pub const DEFAULT_PAIRED_ALPHA: f64 = 0.05;
+
+/// Result of a paired A/B run (`compare --paired`).
+#[derive(Debug, Clone, Serialize)]
+pub struct PairedComparison {
+    pub circuit_name: String,
+    pub baseline: String,
+    pub contender: String,
+    pub alpha: f64,
+    pub threshold: f64,
+    pub test: PairedTest,
+    pub status: CompareStatus,
+    pub samples: PairedSamples,
+}
+
+fn format_paired_text(result: &PairedComparison) -> String {
+    let test = &result.test;
+    let signed = |ms: f64| {
+        let sign = if ms < 0.0 { "-" } else { "+" };
+        format!("{sign}{}", format_value(ms.abs(), "prove_ms"))
+    };
+    let verdict = match result.status {
+        CompareStatus::Regression => "regression",
+        CompareStatus::Improvement => "improvement",
+        CompareStatus::Unchanged => "no significant change",
+    };
+    let mut out = format!(
+        "Paired comparison: {} ({} interleaved pairs)\n",
+        result.circuit_name, test.pairs
+    );
+    out.push_str(&format!("  baseline:   {}\n", result.baseline));
+    out.push_str(&format!("  contender:  {}\n", result.contender));
+    out.push_str(&format!(
+        "  prove_ms:   {} -> {}\n",
+        format_value(test.baseline_mean_ms, "prove_ms"),
+        format_value(test.contender_mean_ms, "prove_ms")
+    ));
+    out.push_str(&format!(
+        "  difference: {} ({:+.2}%), 95% CI [{}, {}]\n",
+        signed(test.mean_diff_ms),
+        test.percent,
+        signed(test.ci95_ms.0),
+        signed(test.ci95_ms.1)
+    ));
+    out.push_str(&format!(
+        "  t = {:.2}, p = {:.4}; contender faster in {}/{} pairs\n",
+        test.t_stat, test.p_value, test.contender_faster, test.pairs
+    ));
+    out.push_str(&format!(
+        "{} {verdict} (alpha {}, threshold {}%)\n",
+        result.status.emoji(),
+        result.alpha,
+        result.threshold
+    ));
+    out
+}
+
+/// Check a significance level given on the command line.
+pub(crate) fn check_alpha(alpha: f64) -> BenchResult<()> {
+    if alpha > 0.0 && alpha < 1.0 {
+        Ok(())
+    } else {
+        Err(BenchError::Message(format!(
+            "--alpha must be between 0 and 1, got {alpha}"
+        )))
+    }
+}
+
+/// Prove `inputs` with the `baseline` and `contender` bb configurations
+/// interleaved and test the difference of their prove times (see
+/// `engine::paired`).
+pub(crate) fn measure_paired(
+    toolchain: &dyn Toolchain,
+    baseline: BarretenbergConfig,
+    contender: BarretenbergConfig,
+    inputs: &ProveInputs,
+    warmup: usize,
+    pairs: usize,
+    alpha: f64,
+    threshold: f64,
+) -> BenchResult<PairedComparison> {
+    let label = |config: &BarretenbergConfig| {
+        std::iter::once(config.bb_path.display().to_string())
+            .chain(config.extra_args.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let (baseline_label, contender_label) = (label(&baseline), label(&contender));
+    let (baseline, contender) = (
+        BarretenbergBackend::new(baseline),
+        BarretenbergBackend::new(contender),
+    );
+    baseline.warn_on_args();
+    contender.warn_on_args();
+
+    let samples = paired::run_paired(toolchain, &baseline, &contender, inputs, warmup, pairs)?;
+    let test = paired::paired_t_test(&samples)
+        .ok_or_else(|| BenchError::Message("a paired comparison needs at least 2 pairs".into()))?;
+    Ok(PairedComparison {
+        circuit_name: inputs.circuit_name.clone(),
+        baseline: baseline_label,
+        contender: contender_label,
+        alpha,
+        threshold,
+        status: test.status(alpha, threshold),
+        test,
+        samples,
+    })
+}
+
+/// Benchmark two bb binaries (or one bb with different args) interleaved on
+/// `artifact` in this session and compare their prove times with a paired
+/// t-test.
+///
+/// A difference counts when its p-value is below `alpha` and it exceeds
+/// `threshold` percent.
+pub fn run_paired(
+    artifact: PathBuf,
+    prover_toml: Option<PathBuf>,
+    baseline_bb: PathBuf,
+    contender_bb: PathBuf,
+    baseline_args: Vec<String>,
+    contender_args: Vec<String>,
+    warmup: usize,
+    pairs: usize,
+    alpha: f64,
+    threshold: f64,
+    format: String,
+    json_out: Option<PathBuf>,
+) -> BenchResult<PairedComparison> {
+    check_alpha(alpha)?;
+    let timeout = Duration::from_secs(24 * 60 * 60);
+    let work = tempfile::tempdir()
+        .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+    let toolchain = NargoToolchain::new().with_work_dir(work.path());
+    let backend = |path: PathBuf, args: Vec<String>| {
+        BarretenbergConfig::new(path)
+            .with_args(args)
+            .with_timeout(timeout)
+            .with_work_dir(work.path())
+    };
+
+    let circuit_name = artifact
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "circuit".to_string());
+    let mut inputs = ProveInputs::new(&artifact, &circuit_name).with_timeout(timeout);
+    if let Some(pt) = prover_toml {
+        inputs = inputs.with_prover_toml(pt);
+    }
+
+    eprintln!("compare --paired: {circuit_name}, {warmup} warmup + {pairs} measured pair(s)");
+    let result = measure_paired(
+        &toolchain,
+        backend(baseline_bb, baseline_args),
+        backend(contender_bb, contender_args),
+        &inputs,
+        warmup,
+        pairs,
+        alpha,
+        threshold,
+    )?;
+
+    let json = serde_json::to_string_pretty(&result)
+        .map_err(|e| BenchError::Message(format!("failed to serialize paired comparison: {e}")))?;
+    if let Some(path) = &json_out {
+        std::fs::write(path, &json)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))?;
+        eprintln!("Wrote paired comparison to {}", path.display());
+    }
+    match format.as_str() {
+        "json" => println!("{json}"),
+        _ => print!("{}", format_paired_text(&result)),
+    }
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_paired_text() {
+        let samples = PairedSamples {
+            baseline_ms: vec![1000.0, 1100.0, 1050.0],
+            contender_ms: vec![950.0, 1040.0, 1005.0],
+        };
+        let test = paired::paired_t_test(&samples).unwrap();
+        let result = PairedComparison {
+            circuit_name: "merkle".to_string(),
+            baseline: "bb".to_string(),
+            contender: "bb --scheme ultra_honk".to_string(),
+            alpha: DEFAULT_PAIRED_ALPHA,
+            threshold: 0.0,
+            status: test.status(DEFAULT_PAIRED_ALPHA, 0.0),
+            test,
+            samples,
+        };
+        assert_eq!(result.status, CompareStatus::Improvement);
+        let text = format_paired_text(&result);
+        assert!(text.contains("merkle (3 interleaved pairs)"));
+        assert!(text.contains("difference: -52ms (-4.92%)"));
+        assert!(text.contains("contender faster in 3/3 pairs"));
+        assert!(text.contains("🟢 improvement"));
+    }
+
     #[test]
     fn test_compare_status_emoji() {
         assert_eq!(CompareStatus::Regression.emoji(), "🔴");
@@ -600,7 +1505,7 @@ mod tests {
             "total_gates": 1000
         });
 
-        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new());
+        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new(), None);
 
         let prove_metric = results.iter().find(|m| m.metric == "prove_ms").unwrap();
         assert_eq!(prove_metric.status, CompareStatus::Regression);
@@ -616,7 +1521,7 @@ mod tests {
             "prove_time_ms": 80.0  // 20% decrease
         });
 
-        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new());
+        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new(), None);
 
         let prove_metric = results.iter().find(|m| m.metric == "prove_ms").unwrap();
         assert_eq!(prove_metric.status, CompareStatus::Improvement);
@@ -631,7 +1536,7 @@ mod tests {
             "prove_time_ms": 105.0  // 5% increase, below threshold
         });
 
-        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new());
+        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new(), None);
 
         let prove_metric = results.iter().find(|m| m.metric == "prove_ms").unwrap();
         assert_eq!(prove_metric.status, CompareStatus::Unchanged);
@@ -657,7 +1562,7 @@ mod tests {
             ("total_gates".to_string(), 0.0),
         ]);
 
-        let results = compare_values(&baseline, &target, 10.0, &thresholds);
+        let results = compare_values(&baseline, &target, 10.0, &thresholds, None);
 
         let prove_metric = results.iter().find(|m| m.metric == "prove_ms").unwrap();
         assert_eq!(prove_metric.threshold, 25.0);
@@ -667,4 +1572,694 @@ mod tests {
         assert_eq!(gates_metric.threshold, 0.0);
         assert_eq!(gates_metric.status, CompareStatus::Regression);
     }
+
+    #[test]
+    fn test_compare_values_custom_metrics() {
+        let baseline = serde_json::json!({
+            "prove_time_ms": 100.0,
+            "custom_metrics": {"folded_constraints": 1000.0, "rounds": 18.0}
+        });
+        let target = serde_json::json!({
+            "prove_time_ms": 100.0,
+            "custom_metrics": {"folded_constraints": 1300.0}
+        });
+        let thresholds = BTreeMap::from([("custom.folded_constraints".to_string(), 50.0)]);
+
+        let results = compare_values(&baseline, &target, 10.0, &thresholds, None);
+
+        assert_eq!(results.len(), 2);
+        let folded = results
+            .iter()
+            .find(|m| m.metric == "custom.folded_constraints")
+            .unwrap();
+        assert_eq!(folded.target, 1300.0);
+        assert_eq!(folded.threshold, 50.0);
+        assert_eq!(folded.status, CompareStatus::Unchanged);
+        // Only in the baseline, so not compared
+        assert!(!results.iter().any(|m| m.metric == "custom.rounds"));
+
+        let selection = MetricSelection::new(&["custom.folded_constraints".to_string()], &[]);
+        assert!(check_metric_selection(&selection).is_ok());
+    }
+
+    #[test]
+    fn test_compare_values_noise_threshold() {
+        // A fast, jittery circuit: 20% slower is within 3σ of the baseline
+        let baseline = serde_json::json!({
+            "prove_stats": { "mean_ms": 10.0, "stddev_ms": 1.0 },
+            "verify_stats": { "mean_ms": 10.0, "median_ms": 10.0, "p95_ms": 11.645 },
+            "total_gates": 1000
+        });
+        let target = serde_json::json!({
+            "prove_stats": { "mean_ms": 12.0 },
+            "verify_stats": { "mean_ms": 14.0 },
+            "total_gates": 1000
+        });
+
+        let results = compare_values(&baseline, &target, 10.0, &BTreeMap::new(), Some(3.0));
+
+        let prove = results.iter().find(|m| m.metric == "prove_ms").unwrap();
+        assert_eq!(prove.threshold, 30.0);
+        assert_eq!(
+            prove.threshold_rule,
+            ThresholdRule::Noise {
+                k: 3.0,
+                stddev_ms: 1.0
+            }
+        );
+        assert_eq!(prove.status, CompareStatus::Unchanged);
+
+        // σ estimated from median and p95
+        let verify = results.iter().find(|m| m.metric == "verify_ms").unwrap();
+        assert!((verify.threshold - 30.0).abs() < 1e-6);
+        assert_eq!(verify.status, CompareStatus::Regression);
+
+        // Gate counts have no spread and keep the percent threshold
+        let gates = results.iter().find(|m| m.metric == "total_gates").unwrap();
+        assert_eq!(gates.threshold, 10.0);
+        assert_eq!(gates.threshold_rule, ThresholdRule::Percent);
+
+        let without = compare_values(&baseline, &target, 10.0, &BTreeMap::new(), None);
+        let prove = without.iter().find(|m| m.metric == "prove_ms").unwrap();
+        assert_eq!(prove.status, CompareStatus::Regression);
+    }
+
+    #[test]
+    fn test_metric_selection_drops_and_neutralizes_metrics() {
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = dir.path().join("baseline.json");
+        let target = dir.path().join("target.json");
+        std::fs::write(
+            &baseline,
+            r#"{"circuit_name":"c","prove_stats":{"mean_ms":100.0},"total_gates":1000,"peak_rss_mb":100.0}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &target,
+            r#"{"circuit_name":"c","prove_stats":{"mean_ms":150.0},"total_gates":1000,"peak_rss_mb":200.0}"#,
+        )
+        .unwrap();
+        let config = |metrics: MetricSelection| CompareConfig {
+            baseline_file: None,
+            target_file: None,
+            baseline_json: Some(baseline.clone()),
+            target_json: Some(target.clone()),
+            threshold: DEFAULT_THRESHOLD,
+            metric_thresholds: BTreeMap::new(),
+            circuit_thresholds: CircuitThresholds::new(),
+            noise_sigma: None,
+            baseline_window: None,
+            arch: None,
+            allow_cross_arch: false,
+            include_anomalies: false,
+            metrics,
+            format: "text".to_string(),
+            json_out: None,
+        };
+
+        let all = compare(&config(MetricSelection::default())).unwrap();
+        assert_eq!(all.total_regressions, 2);
+
+        let selected = compare(&config(MetricSelection::new(
+            &["gates".to_string()],
+            &["peak_rss".to_string()],
+        )))
+        .unwrap();
+        assert_eq!(selected.total_regressions, 0);
+        assert_eq!(selected.ci_exit_code, 0);
+        let circuit = &selected.circuits[0];
+        assert!(!circuit.has_regression);
+        let names: Vec<_> = circuit.metrics.iter().map(|m| m.metric.as_str()).collect();
+        assert_eq!(names, ["total_gates", "peak_rss_mb"]);
+        assert!(format_text(&selected).contains("peak_rss_mb: 100.0 MB -> 200.0 MB"));
+        assert!(format_text(&selected).contains("[INFO]"));
+        assert_eq!(
+            to_regression_report(&selected)
+                .metadata
+                .informational_metrics
+                .len(),
+            1
+        );
+
+        let err = compare(&config(MetricSelection::new(&["gatez".to_string()], &[])))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown metric(s): gatez"));
+    }
+
+    #[test]
+    fn test_window_baseline_uses_median_of_last_runs() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |name: &str, prove_ms: f64, gates: u64| {
+            let mut r = BenchRecord::new(
+                name.to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+            r.total_gates = Some(gates);
+            r
+        };
+        let history = vec![
+            // Outside the window
+            record("a", 10.0, 900),
+            record("a", 100.0, 1000),
+            // One bad run
+            record("a", 400.0, 1000),
+            record("a", 104.0, 1010),
+            // Failed runs don't count towards the window
+            {
+                let mut r = record("a", 5.0, 0);
+                r.error = Some(crate::core::RunError {
+                    message: "timeout".to_string(),
+                    log_path: None,
+                });
+                r
+            },
+            // Neither do expected failures, which time the failure
+            {
+                let mut r = record("a", 2.0, 1000);
+                r.status = Some(crate::core::RunStatus::ExpectedFailure);
+                r
+            },
+            record("b", 50.0, 10),
+        ];
+
+        let baselines = window_baseline(history, 3).unwrap();
+        assert_eq!(baselines.len(), 2);
+        let a = &baselines[0];
+        assert_eq!(a.circuit_name, "a");
+        assert!(a.error.is_none());
+        assert_eq!(a.prove_stats.as_ref().unwrap().mean_ms, 104.0);
+        assert_eq!(a.total_gates, Some(1000));
+
+        let result = compare_records(
+            baselines,
+            vec![record("a", 110.0, 1000)],
+            "history",
+            "target",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.total_regressions, 0);
+
+        // Cold-cache runs only compare with cold-cache baselines
+        let cold = |prove_ms: f64| {
+            let mut r = record("a", prove_ms, 1000);
+            r.cache_mode = Some(CacheMode::Cold);
+            r
+        };
+        let baselines = window_baseline(vec![record("a", 100.0, 1000), cold(900.0)], 3).unwrap();
+        assert_eq!(baselines.len(), 2);
+        let result = compare_records(
+            baselines,
+            vec![record("a", 100.0, 1000), cold(1800.0)],
+            "history",
+            "target",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        )
+        .unwrap();
+        let names: Vec<&str> = result
+            .circuits
+            .iter()
+            .map(|c| c.circuit_name.as_str())
+            .collect();
+        assert_eq!(names, ["a", "a (cold cache)"]);
+        assert_eq!(result.total_regressions, 1);
+    }
+
+    #[test]
+    fn test_compare_jsonl_skips_anomalous_baselines() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |prove_ms: f64, anomaly: bool| {
+            let mut r = BenchRecord::new(
+                "a".to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+            r.anomaly = anomaly;
+            r
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let baseline = dir.path().join("history.jsonl");
+        let target = dir.path().join("target.jsonl");
+        for r in [
+            record(100.0, false),
+            record(100.0, false),
+            record(40.0, true),
+        ] {
+            JsonlWriter::new(&baseline).append(&r).unwrap();
+        }
+        JsonlWriter::new(&target)
+            .append(&record(102.0, false))
+            .unwrap();
+
+        let compare = |include_anomalies: bool| {
+            compare_jsonl_files(
+                &baseline,
+                &target,
+                DEFAULT_THRESHOLD,
+                &BTreeMap::new(),
+                &CircuitThresholds::new(),
+                None,
+                Some(1),
+                None,
+                false,
+                include_anomalies,
+            )
+            .unwrap()
+        };
+        // The flagged 40ms run would make 102ms a regression
+        assert_eq!(compare(false).total_regressions, 0);
+        assert_eq!(compare(true).total_regressions, 1);
+    }
+
+    #[test]
+    fn test_compare_records_reports_prove_mode_change() {
+        use crate::core::{
+            AMORTIZED_SETUP_MODE, BackendInfo, EnvironmentInfo, RunConfig, TimingStat,
+        };
+
+        let record = |mode: Option<&str>| {
+            let mut r = BenchRecord::new(
+                "a".to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[100.0]));
+            if let Some(mode) = mode {
+                r.metadata
+                    .insert(PROVE_MODE_METADATA_KEY.to_string(), mode.to_string());
+            }
+            r
+        };
+        let compare = |baseline: Option<&str>, target: Option<&str>| {
+            compare_records(
+                vec![record(baseline)],
+                vec![record(target)],
+                "base",
+                "head",
+                DEFAULT_THRESHOLD,
+                &BTreeMap::new(),
+                &CircuitThresholds::new(),
+                None,
+            )
+            .unwrap()
+        };
+
+        let result = compare(None, Some(AMORTIZED_SETUP_MODE));
+        assert_eq!(result.environment_mismatches.len(), 1);
+        assert!(
+            format_text(&result)
+                .contains("Warning: environment changed: prove_mode: standard -> amortized_setup")
+        );
+        let same = compare(Some(AMORTIZED_SETUP_MODE), Some(AMORTIZED_SETUP_MODE));
+        assert!(same.environment_mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_compare_records_reports_environment_changes() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |name: &str, cpu: &str, governor: &str| {
+            let mut r = BenchRecord::new(
+                name.to_string(),
+                EnvironmentInfo {
+                    cpu_model: Some(cpu.to_string()),
+                    cpu_governor: Some(governor.to_string()),
+                    ..EnvironmentInfo::default()
+                },
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[100.0]));
+            r
+        };
+
+        let result = compare_records(
+            vec![
+                record("a", "EPYC", "performance"),
+                record("b", "EPYC", "performance"),
+            ],
+            vec![
+                record("a", "EPYC", "powersave"),
+                record("b", "EPYC", "powersave"),
+            ],
+            "lab",
+            "laptop",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        )
+        .unwrap();
+
+        // Reported once, not per circuit
+        assert_eq!(result.environment_mismatches.len(), 1);
+        assert_eq!(result.environment_mismatches[0].field, "cpu_governor");
+        assert!(
+            format_text(&result)
+                .contains("Warning: environment changed: cpu_governor: performance -> powersave")
+        );
+        let report = to_regression_report(&result);
+        assert_eq!(report.environment_mismatches, result.environment_mismatches);
+    }
+
+    #[test]
+    fn test_compare_records_warns_on_thermal_pressure() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |thermal: &str| {
+            let mut r = BenchRecord::new(
+                "a".to_string(),
+                EnvironmentInfo {
+                    thermal_pressure: Some(thermal.to_string()),
+                    ..EnvironmentInfo::default()
+                },
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[100.0]));
+            r
+        };
+
+        let result = compare_records(
+            vec![record("nominal")],
+            vec![record("heavy")],
+            "base",
+            "laptop",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(result.thermal_warnings.len(), 1);
+        assert_eq!(result.thermal_warnings[0].run, "target");
+        assert!(format_text(&result).contains(
+            "Warning: a: target measured under heavy thermal pressure; timings may be throttled"
+        ));
+        let md = crate::report::render_markdown(&to_regression_report(&result));
+        assert!(md.contains("### ⚠️ Thermal Throttling"));
+    }
+
+    #[test]
+    fn test_compare_refuses_cross_arch_unless_allowed() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |arch: &str| {
+            let mut r = BenchRecord::new(
+                "a".to_string(),
+                EnvironmentInfo {
+                    arch: Some(arch.to_string()),
+                    ..EnvironmentInfo::default()
+                },
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[100.0]));
+            r
+        };
+        let compare = |baseline: &str, target: &str, allow: bool| {
+            compare_record_sets(
+                vec![record(baseline)],
+                vec![record(target)],
+                DEFAULT_THRESHOLD,
+                &BTreeMap::new(),
+                &CircuitThresholds::new(),
+                None,
+                allow,
+            )
+        };
+
+        // Aliases of the same architecture are not a mismatch
+        assert!(compare("arm64", "aarch64", false).is_ok());
+
+        let err = compare("x86_64", "aarch64", false).unwrap_err();
+        assert!(err.to_string().contains("--allow-cross-arch"));
+
+        let allowed = compare("x86_64", "aarch64", true).unwrap();
+        assert_eq!(allowed.environment_mismatches.len(), 1);
+        assert_eq!(allowed.environment_mismatches[0].field, "arch");
+    }
+
+    #[test]
+    fn test_compare_records_matches_by_circuit() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |name: &str, prove_ms: f64| {
+            let mut r = BenchRecord::new(
+                name.to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+            r
+        };
+
+        let result = compare_records(
+            vec![record("a", 100.0), record("b", 100.0)],
+            vec![record("b", 150.0), record("a", 100.0)],
+            "bb@1.0.0",
+            "bb@1.1.0",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(result.baseline_ref, "bb@1.0.0");
+        assert_eq!(result.target_ref, "bb@1.1.0");
+        assert_eq!(result.total_regressions, 1);
+        assert_eq!(result.ci_exit_code, 1);
+        let b = result
+            .circuits
+            .iter()
+            .find(|c| c.circuit_name == "b")
+            .unwrap();
+        assert!(b.has_regression);
+    }
+
+    #[test]
+    fn test_thresholds_for_circuit_precedence() {
+        let metric_thresholds = BTreeMap::from([
+            ("prove_ms".to_string(), 10.0),
+            ("total_gates".to_string(), 0.5),
+        ]);
+        let circuit_thresholds = CircuitThresholds::from([
+            (
+                "noisy".to_string(),
+                BTreeMap::from([("prove_ms".to_string(), 40.0)]),
+            ),
+            (
+                "tiny".to_string(),
+                BTreeMap::from([
+                    ("default".to_string(), 50.0),
+                    ("total_gates".to_string(), 0.0),
+                ]),
+            ),
+        ]);
+
+        let (default, resolved) =
+            thresholds_for_circuit("other", 5.0, &metric_thresholds, &circuit_thresholds);
+        assert_eq!(default, 5.0);
+        assert_eq!(resolved, metric_thresholds);
+
+        let (default, resolved) =
+            thresholds_for_circuit("noisy", 5.0, &metric_thresholds, &circuit_thresholds);
+        assert_eq!(default, 5.0);
+        assert_eq!(resolved["prove_ms"], 40.0);
+        assert_eq!(resolved["total_gates"], 0.5);
+
+        // A circuit default shadows the global per-metric thresholds
+        let (default, resolved) =
+            thresholds_for_circuit("tiny", 5.0, &metric_thresholds, &circuit_thresholds);
+        assert_eq!(default, 50.0);
+        assert!(!resolved.contains_key("prove_ms"));
+        assert_eq!(resolved["total_gates"], 0.0);
+    }
+
+    #[test]
+    fn test_compare_records_applies_circuit_thresholds() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |name: &str, prove_ms: f64| {
+            let mut r = BenchRecord::new(
+                name.to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+            r
+        };
+        let circuit_thresholds = CircuitThresholds::from([(
+            "noisy".to_string(),
+            BTreeMap::from([("prove_ms".to_string(), 40.0)]),
+        )]);
+
+        let result = compare_records(
+            vec![record("noisy", 100.0), record("stable", 100.0)],
+            vec![record("noisy", 130.0), record("stable", 130.0)],
+            "base",
+            "target",
+            DEFAULT_THRESHOLD,
+            &BTreeMap::new(),
+            &circuit_thresholds,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(result.total_regressions, 1);
+        let noisy = &result.circuits[0];
+        assert!(!noisy.has_regression);
+        assert_eq!(noisy.metrics[0].threshold, 40.0);
+        assert!(result.circuits[1].has_regression);
+        assert_eq!(
+            to_regression_report(&result).metadata.circuit_thresholds,
+            circuit_thresholds
+        );
+    }
+
+    #[test]
+    fn test_compare_reference_normalizes_by_calibration() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let dataset = reference::parse(
+            r#"{
+                "name": "test-ref",
+                "corpus_version": "v1",
+                "calibration_version": 1,
+                "machines": [{
+                    "id": "fast",
+                    "os": "linux",
+                    "calibration_score": 200.0,
+                    "results": { "merkle_proof": { "prove_ms": 1000.0 } }
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let record = |corpus: Option<&str>| {
+            let mut r = BenchRecord::new(
+                "merkle_proof".to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "barretenberg".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            // Half the reference machine's score at twice its time: on par
+            r.prove_stats = Some(TimingStat::from_samples(&[2000.0]));
+            r.metadata
+                .insert(CALIBRATION_METADATA_KEY.to_string(), "100.0".to_string());
+            if let Some(corpus) = corpus {
+                r.metadata
+                    .insert(CORPUS_METADATA_KEY.to_string(), corpus.to_string());
+            }
+            r
+        };
+
+        let result =
+            compare_reference(&dataset, vec![record(Some("v1"))], "local", None, 10.0).unwrap();
+        assert!(result.baseline_ref.starts_with("test-ref/fast"));
+        let prove = &result.circuits[0].metrics[0];
+        assert_eq!(prove.metric, "prove_ms");
+        assert_eq!(prove.baseline, 2000.0);
+        assert_eq!(prove.status, CompareStatus::Unchanged);
+        assert_eq!(result.ci_exit_code, 0);
+
+        // Records from other corpus versions (or outside the corpus) are ignored
+        assert!(
+            compare_reference(&dataset, vec![record(Some("v0"))], "local", None, 10.0).is_err()
+        );
+        assert!(compare_reference(&dataset, vec![record(None)], "local", None, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_subgroup_change_is_informational_and_reported() {
+        let baseline = serde_json::json!({
+            "circuit_name": "rollup",
+            "total_gates": 64000,
+            "subgroup_size": 65536
+        });
+        let target = serde_json::json!({
+            "circuit_name": "rollup",
+            "total_gates": 65920,
+            "subgroup_size": 131072
+        });
+        let circuit = compare_single_records(&baseline, &target, 10.0, &BTreeMap::new(), None);
+        let subgroup = circuit
+            .metrics
+            .iter()
+            .find(|m| m.metric == "subgroup_size")
+            .unwrap();
+        assert_eq!(subgroup.status, CompareStatus::Unchanged);
+        assert!(!circuit.has_regression);
+
+        let result = summarize(
+            vec![circuit],
+            "base".to_string(),
+            "target".to_string(),
+            10.0,
+            &BTreeMap::new(),
+            &CircuitThresholds::new(),
+            None,
+        );
+        let text = format_text(&result);
+        assert!(text.contains("subgroup_size: 2^16 -> 2^17"), "{text}");
+        assert!(
+            text.contains("Warning: rollup: gates +3.0% but subgroup doubled"),
+            "{text}"
+        );
+    }
 }
@@ -0,0 +1,154 @@
+//! CLI command handler for `compile`.
+//!
+//! Benchmarks `nargo compile` on a project, cold (fresh `target/`), warm and
+//! optionally incremental (after editing one source file), and writes the
+//! result as a BenchRecord.
+
+use std::path::{Path, PathBuf};
+
+use crate::core::signing::load_signing_key;
+use crate::engine::{NargoToolchain, compile_benchmark};
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Circuit name for a project: the package name from Nargo.toml, else the directory name.
+fn project_name(project: &Path) -> String {
+    std::fs::read_to_string(project.join("Nargo.toml"))
+        .ok()
+        .and_then(|s| s.parse::<toml::Table>().ok())
+        .and_then(|t| t.get("package")?.get("name")?.as_str().map(str::to_string))
+        .or_else(|| {
+            project
+                .canonicalize()
+                .ok()?
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "project".to_string())
+}
+
+pub fn run(
+    project: PathBuf,
+    name: Option<String>,
+    nargo_path: Option<PathBuf>,
+    incremental: Option<PathBuf>,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    json_out: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    sign_key: Option<PathBuf>,
+) -> BenchResult<()> {
+    if !project.join("Nargo.toml").exists() {
+        return Err(BenchError::Message(format!(
+            "no Nargo.toml in {}",
+            project.display()
+        )));
+    }
+    let toolchain = match nargo_path {
+        Some(path) => NargoToolchain::with_path(path),
+        None => NargoToolchain::new(),
+    };
+    let name = name.unwrap_or_else(|| project_name(&project));
+    let record = compile_benchmark(
+        &toolchain,
+        &project,
+        &name,
+        incremental.as_deref(),
+        warmup.unwrap_or(0),
+        iterations.unwrap_or(1),
+    )?;
+
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        let bytes = serde_json::to_vec_pretty(&record)
+            .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+        std::fs::write(&json, bytes)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", json.display())))?;
+    }
+    if let Some(jsonl) = jsonl_out {
+        let mut writer = JsonlWriter::new(&jsonl);
+        if let Some(key) = &sign_key {
+            writer = writer.with_signing_key(load_signing_key(key)?);
+        }
+        writer.append(&record)?;
+    }
+
+    let cold = record.compile_stats.as_ref().expect("compile_stats is set");
+    let warm = record
+        .compile_warm_stats
+        .as_ref()
+        .expect("compile_warm_stats is set");
+    println!(
+        "compile: {} cold mean={:.1}ms min={:.1}ms max={:.1}ms | warm mean={:.1}ms min={:.1}ms max={:.1}ms iterations={}",
+        record.circuit_name,
+        cold.mean_ms,
+        cold.min_ms,
+        cold.max_ms,
+        warm.mean_ms,
+        warm.min_ms,
+        warm.max_ms,
+        cold.iterations
+    );
+    if let Some(inc) = &record.compile_incremental_stats {
+        println!(
+            "compile: {} incremental mean={:.1}ms min={:.1}ms max={:.1}ms (touching {})",
+            record.circuit_name,
+            inc.mean_ms,
+            inc.min_ms,
+            inc.max_ms,
+            record
+                .metadata
+                .get("touched_file")
+                .map(String::as_str)
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_name_prefers_nargo_package_name() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("Nargo.toml"),
+            "[package]\nname = \"merkle\"\ntype = \"bin\"\n",
+        )
+        .unwrap();
+        assert_eq!(project_name(dir.path()), "merkle");
+
+        std::fs::write(dir.path().join("Nargo.toml"), "[workspace]\n").unwrap();
+        let dir_name = dir
+            .path()
+            .canonicalize()
+            .unwrap()
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        assert_eq!(project_name(dir.path()), dir_name);
+    }
+
+    #[test]
+    fn test_run_requires_nargo_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = run(
+            dir.path().to_path_buf(),
+            None,
+            None,
+            None,
+            Some(1),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Nargo.toml"), "{err}");
+    }
+}
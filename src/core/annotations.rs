@@ -0,0 +1,125 @@
+//! Run annotations: free-form `key=value` pairs such as the commit and PR a
+//! run was made for.
+//!
+//! Annotations are passed on the command line (`--annotate commit=<sha>
+//! pr=<url>`), embedded in regression reports, and stored in BenchRecord
+//! metadata under `annotation.<key>` so history pages can show which commit
+//! each run corresponds to.
+
+use std::collections::BTreeMap;
+
+use crate::{BenchError, BenchResult};
+
+/// Prefix of annotation keys in `BenchRecord.metadata`.
+pub const METADATA_PREFIX: &str = "annotation.";
+
+/// Parse `key=value` arguments; an argument may hold several
+/// whitespace-separated pairs. Later values for the same key win.
+pub fn parse(args: &[String]) -> BenchResult<BTreeMap<String, String>> {
+    let mut annotations = BTreeMap::new();
+    for pair in args.iter().flat_map(|a| a.split_whitespace()) {
+        let (key, value) = pair
+            .split_once('=')
+            .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+            .ok_or_else(|| {
+                BenchError::Message(format!(
+                    "invalid annotation '{pair}': expected key=value (e.g. commit=<sha>)"
+                ))
+            })?;
+        annotations.insert(key.to_string(), value.to_string());
+    }
+    Ok(annotations)
+}
+
+/// Store annotations in record metadata.
+pub fn apply(metadata: &mut BTreeMap<String, String>, annotations: &BTreeMap<String, String>) {
+    for (key, value) in annotations {
+        metadata.insert(format!("{METADATA_PREFIX}{key}"), value.clone());
+    }
+}
+
+/// Annotations stored in record metadata.
+pub fn from_metadata(metadata: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    metadata
+        .iter()
+        .filter_map(|(k, v)| Some((k.strip_prefix(METADATA_PREFIX)?.to_string(), v.clone())))
+        .collect()
+}
+
+fn is_url(value: &str) -> bool {
+    value.starts_with("https://") || value.starts_with("http://")
+}
+
+/// Link target for an annotation, if it has one.
+///
+/// URLs link to themselves. With a `repo=<url>` annotation, `commit` links
+/// to `<repo>/commit/<sha>` and a numeric `pr` to `<repo>/pull/<n>`.
+pub fn href(key: &str, value: &str, annotations: &BTreeMap<String, String>) -> Option<String> {
+    if is_url(value) {
+        return Some(value.to_string());
+    }
+    let repo = annotations
+        .get("repo")
+        .filter(|r| is_url(r))?
+        .trim_end_matches('/');
+    match key {
+        "commit" if value.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Some(format!("{repo}/commit/{value}"))
+        }
+        "pr" => {
+            let n = value.trim_start_matches('#');
+            n.chars()
+                .all(|c| c.is_ascii_digit())
+                .then(|| format!("{repo}/pull/{n}"))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_annotations() {
+        let args = vec![
+            "commit=abc123 pr=https://github.com/o/r/pull/7".to_string(),
+            "branch=main".to_string(),
+        ];
+        let annotations = parse(&args).unwrap();
+        assert_eq!(annotations["commit"], "abc123");
+        assert_eq!(annotations["pr"], "https://github.com/o/r/pull/7");
+        assert_eq!(annotations["branch"], "main");
+
+        assert!(parse(&["commit".to_string()]).is_err());
+        assert!(parse(&["=abc".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let annotations = parse(&["commit=abc123".to_string()]).unwrap();
+        let mut metadata = BTreeMap::from([("corpus".to_string(), "v1".to_string())]);
+        apply(&mut metadata, &annotations);
+        assert_eq!(metadata["annotation.commit"], "abc123");
+        assert_eq!(from_metadata(&metadata), annotations);
+    }
+
+    #[test]
+    fn test_href() {
+        let annotations = parse(&["repo=https://github.com/o/r/".to_string()]).unwrap();
+        assert_eq!(
+            href("commit", "abc123", &annotations).as_deref(),
+            Some("https://github.com/o/r/commit/abc123")
+        );
+        assert_eq!(
+            href("pr", "#42", &annotations).as_deref(),
+            Some("https://github.com/o/r/pull/42")
+        );
+        assert_eq!(
+            href("pr", "https://example.com/pr/1", &BTreeMap::new()).as_deref(),
+            Some("https://example.com/pr/1")
+        );
+        assert_eq!(href("commit", "abc123", &BTreeMap::new()), None);
+        assert_eq!(href("branch", "main", &annotations), None);
+    }
+}
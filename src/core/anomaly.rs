@@ -0,0 +1,212 @@
+//! Outlier detection for runs appended to a history.
+//!
+//! A new record is compared against the rolling distribution of its
+//! circuit/backend: the median and the median absolute deviation (MAD) of
+//! the last runs. A timing more than `k` MADs from the median marks the
+//! record `anomaly: true`, and baselines and cost model fits skip it, so one
+//! run on a noisy machine doesn't poison later comparisons.
+//!
+//! Flagged runs stay in the window, so a lasting level shift (a real
+//! regression or speedup) stops being flagged once it makes up half of it.
+
+use super::schema::BenchRecord;
+
+/// Default distance from the median, in MADs, beyond which a run is flagged.
+pub const DEFAULT_MAD_K: f64 = 5.0;
+
+/// Previous runs of a circuit/backend the distribution is taken over.
+pub const DEFAULT_WINDOW: usize = 20;
+
+/// Previous runs needed before anything is flagged.
+pub const MIN_HISTORY: usize = 5;
+
+/// The MAD is floored at this fraction of the median, so a history of
+/// identical timings doesn't flag the smallest jitter.
+const MIN_MAD_FRACTION: f64 = 0.01;
+
+/// A metric of a record outside its rolling distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub metric: &'static str,
+    pub value: f64,
+    pub median: f64,
+    pub mad: f64,
+}
+
+impl Anomaly {
+    /// Distance from the median in MADs.
+    pub fn score(&self) -> f64 {
+        (self.value - self.median).abs() / self.mad
+    }
+}
+
+/// Metrics checked for outliers: timings, which pick up machine noise.
+/// Gate counts and sizes are deterministic, so a change there is real.
+fn timings(record: &BenchRecord) -> [(&'static str, Option<f64>); 3] {
+    [
+        ("prove_ms", record.prove_stats.as_ref().map(|s| s.mean_ms)),
+        (
+            "witness_ms",
+            record.witness_stats.as_ref().map(|s| s.mean_ms),
+        ),
+        ("verify_ms", record.verify_stats.as_ref().map(|s| s.mean_ms)),
+    ]
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    }
+}
+
+/// Whether two records are runs of the same circuit, params and backend.
+pub fn same_series(a: &BenchRecord, b: &BenchRecord) -> bool {
+    a.circuit_name == b.circuit_name && a.params == b.params && a.backend.name == b.backend.name
+}
+
+/// Metrics of `record` more than `k` MADs from the median of the last
+/// `window` runs of its circuit/backend in `history` (oldest first).
+/// Nothing is flagged with fewer than `MIN_HISTORY` previous runs.
+pub fn detect(
+    history: &[BenchRecord],
+    record: &BenchRecord,
+    k: f64,
+    window: usize,
+) -> Vec<Anomaly> {
+    let previous: Vec<&BenchRecord> = history
+        .iter()
+        .filter(|r| same_series(r, record) && r.error.is_none() && !r.is_expected_failure())
+        .collect();
+    let previous = &previous[previous.len().saturating_sub(window)..];
+
+    let mut anomalies = Vec::new();
+    for (i, (metric, value)) in timings(record).into_iter().enumerate() {
+        let Some(value) = value else { continue };
+        let mut values: Vec<f64> = previous.iter().filter_map(|r| timings(r)[i].1).collect();
+        if values.len() < MIN_HISTORY {
+            continue;
+        }
+        values.sort_by(f64::total_cmp);
+        let center = median(&values);
+        let mut deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+        deviations.sort_by(f64::total_cmp);
+        let mad = median(&deviations).max(center.abs() * MIN_MAD_FRACTION);
+        if mad > 0.0 && (value - center).abs() > k * mad {
+            anomalies.push(Anomaly {
+                metric,
+                value,
+                median: center,
+                mad,
+            });
+        }
+    }
+    anomalies
+}
+
+/// Flag each of `records`, about to be appended to `history`, whose timings
+/// are outliers (see `detect`). Records are checked in order, each against
+/// the history plus the new records before it. Returns the anomalies found
+/// per flagged record id.
+pub fn flag(
+    history: &[BenchRecord],
+    records: &mut [BenchRecord],
+    k: f64,
+    window: usize,
+) -> Vec<(String, Vec<Anomaly>)> {
+    let mut seen = history.to_vec();
+    let mut flagged = Vec::new();
+    for record in records.iter_mut() {
+        let anomalies = detect(&seen, record, k, window);
+        if !anomalies.is_empty() {
+            record.anomaly = true;
+            flagged.push((record.record_id.clone(), anomalies));
+        }
+        seen.push(record.clone());
+    }
+    flagged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig, TimingStat};
+
+    fn run(circuit: &str, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            circuit.to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r
+    }
+
+    fn history() -> Vec<BenchRecord> {
+        [100.0, 102.0, 98.0, 101.0, 99.0, 103.0]
+            .iter()
+            .map(|&ms| run("c", ms))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_flags_outlier_beyond_k_mads() {
+        let history = history();
+        // Median 100.5, MAD 1.5: 140 is 26 MADs out
+        let anomalies = detect(&history, &run("c", 140.0), DEFAULT_MAD_K, DEFAULT_WINDOW);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].metric, "prove_ms");
+        assert_eq!(anomalies[0].median, 100.5);
+        assert_eq!(anomalies[0].mad, 1.5);
+        assert!(anomalies[0].score() > 25.0);
+
+        assert!(detect(&history, &run("c", 104.0), DEFAULT_MAD_K, DEFAULT_WINDOW).is_empty());
+        // Other circuits have no history
+        assert!(detect(&history, &run("d", 140.0), DEFAULT_MAD_K, DEFAULT_WINDOW).is_empty());
+        // Too little history
+        assert!(
+            detect(
+                &history[..4],
+                &run("c", 140.0),
+                DEFAULT_MAD_K,
+                DEFAULT_WINDOW
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_detect_floors_mad_for_constant_history() {
+        let history: Vec<BenchRecord> = (0..6).map(|_| run("c", 100.0)).collect();
+        // MAD 0 is floored at 1ms: 103 is within 5, 110 is not
+        assert!(detect(&history, &run("c", 103.0), DEFAULT_MAD_K, DEFAULT_WINDOW).is_empty());
+        assert_eq!(
+            detect(&history, &run("c", 110.0), DEFAULT_MAD_K, DEFAULT_WINDOW).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_flag_sets_anomaly_and_adapts_to_level_shift() {
+        let history = history();
+        let mut records = vec![run("c", 140.0), run("c", 101.0)];
+        let flagged = flag(&history, &mut records, DEFAULT_MAD_K, DEFAULT_WINDOW);
+        assert_eq!(flagged.len(), 1);
+        assert!(records[0].anomaly);
+        assert!(!records[1].anomaly);
+
+        // A lasting shift is flagged only until it dominates the window
+        let mut shifted: Vec<BenchRecord> = (0..8).map(|_| run("c", 200.0)).collect();
+        flag(&history, &mut shifted, DEFAULT_MAD_K, 10);
+        assert!(shifted[0].anomaly);
+        assert!(!shifted[7].anomaly);
+    }
+}
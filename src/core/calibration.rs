@@ -0,0 +1,75 @@
+//! Machine calibration score.
+//!
+//! A short, fixed CPU workload (modular multiplication chains, the inner loop
+//! of most provers) used to normalize timings taken on different hardware.
+//! Higher is faster. Scores are only comparable within one
+//! `CALIBRATION_VERSION`.
+
+use std::time::Instant;
+
+/// Version of the calibration workload. Bump when the workload changes.
+pub const CALIBRATION_VERSION: u32 = 1;
+
+/// Metadata key holding the calibration score on records.
+pub const CALIBRATION_METADATA_KEY: &str = "calibration_score";
+
+/// Number of modular multiplications per measured round.
+const ROUND_MULMODS: u64 = 2_000_000;
+
+/// Number of measured rounds; the fastest is kept.
+const ROUNDS: usize = 5;
+
+/// 2^61 - 1, so products fit in u128 without overflow.
+const MODULUS: u128 = (1 << 61) - 1;
+
+fn round() -> u64 {
+    let mut acc: u128 = 0x1234_5678_9abc_def0 % MODULUS;
+    let mut x: u128 = 0x0fed_cba9_8765_4321 % MODULUS;
+    for _ in 0..ROUND_MULMODS {
+        acc = (acc * x + 1) % MODULUS;
+        x = (x * x + acc) % MODULUS;
+    }
+    std::hint::black_box(acc as u64)
+}
+
+/// Measure this machine's calibration score, in millions of modular
+/// multiplications per second (best of several rounds).
+pub fn measure() -> f64 {
+    round();
+    let best = (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            round();
+            start.elapsed().as_secs_f64()
+        })
+        .fold(f64::INFINITY, f64::min);
+    // Two mulmods per loop iteration
+    (2 * ROUND_MULMODS) as f64 / best.max(f64::EPSILON) / 1_000_000.0
+}
+
+/// Scale a timing taken on a machine with `from_score` to what it would be on
+/// a machine with `to_score`.
+pub fn normalize_ms(ms: f64, from_score: f64, to_score: f64) -> f64 {
+    if from_score <= 0.0 || to_score <= 0.0 {
+        return ms;
+    }
+    ms * from_score / to_score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_is_positive() {
+        assert!(measure() > 0.0);
+    }
+
+    #[test]
+    fn test_normalize_ms() {
+        // A machine twice as fast should take half the time
+        assert_eq!(normalize_ms(100.0, 10.0, 20.0), 50.0);
+        assert_eq!(normalize_ms(100.0, 20.0, 10.0), 200.0);
+        assert_eq!(normalize_ms(100.0, 0.0, 10.0), 100.0);
+    }
+}
@@ -0,0 +1,421 @@
+//! Backend cost models: predict prove time and memory from circuit size.
+//!
+//! Each model is a pair of power laws `y = coefficient * size^exponent` over
+//! the subgroup size (next power of two of the gate count), fitted in log-log
+//! space. The residual spread in log space gives the error bars. Timings are
+//! relative to the calibration score of the machine(s) the model was fitted
+//! on, so predictions can be scaled to the current machine.
+//!
+//! Models are stored as JSON per backend version under a models directory
+//! (`<backend>@<version>.json`, or `<backend>.json` for any version); a
+//! built-in default is used for barretenberg when nothing has been fitted.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::BenchRecord;
+use crate::core::calibration::{CALIBRATION_METADATA_KEY, normalize_ms};
+use crate::{BenchError, BenchResult};
+
+/// Version of the cost model JSON format.
+pub const COST_MODEL_VERSION: u32 = 1;
+
+/// Default directory for fitted models.
+pub const DEFAULT_MODELS_DIR: &str = ".noir-bench/models";
+
+/// z-score for the two-sided 95% interval.
+const Z_95: f64 = 1.96;
+
+/// Fewest data points a power law is fitted on.
+pub const MIN_FIT_SAMPLES: usize = 3;
+
+/// `y = coefficient * x^exponent`, with the standard deviation of the
+/// log-space residuals.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerLaw {
+    pub coefficient: f64,
+    pub exponent: f64,
+    pub log_sigma: f64,
+}
+
+impl PowerLaw {
+    /// Least-squares fit of `ln y = ln c + e ln x` over `(x, y)` points.
+    ///
+    /// Returns `None` with fewer than `MIN_FIT_SAMPLES` positive points or
+    /// fewer than two distinct `x` values.
+    pub fn fit(points: &[(f64, f64)]) -> Option<PowerLaw> {
+        let logs: Vec<(f64, f64)> = points
+            .iter()
+            .filter(|(x, y)| *x > 0.0 && *y > 0.0)
+            .map(|(x, y)| (x.ln(), y.ln()))
+            .collect();
+        let n = logs.len();
+        if n < MIN_FIT_SAMPLES {
+            return None;
+        }
+        let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n as f64;
+        let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n as f64;
+        let sxx: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        if sxx <= f64::EPSILON {
+            return None;
+        }
+        let sxy: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+        let exponent = sxy / sxx;
+        let intercept = mean_y - exponent * mean_x;
+        let ssr: f64 = logs
+            .iter()
+            .map(|(x, y)| (y - intercept - exponent * x).powi(2))
+            .sum();
+        Some(PowerLaw {
+            coefficient: intercept.exp(),
+            exponent,
+            log_sigma: (ssr / (n - 2) as f64).sqrt(),
+        })
+    }
+
+    /// Point prediction at `x`.
+    pub fn predict(&self, x: f64) -> f64 {
+        self.coefficient * x.powf(self.exponent)
+    }
+
+    /// 95% prediction interval at `x`.
+    pub fn interval(&self, x: f64) -> (f64, f64) {
+        let y = self.predict(x);
+        let spread = (Z_95 * self.log_sigma).exp();
+        (y / spread, y * spread)
+    }
+}
+
+/// A fitted (or built-in) cost model for one backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostModel {
+    pub version: u32,
+    pub backend: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<String>,
+    /// Calibration score the prove_ms coefficients are relative to
+    pub calibration_score: f64,
+    pub prove_ms: PowerLaw,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss_mb: Option<PowerLaw>,
+    /// Number of records the model was fitted on (0 for built-in defaults)
+    pub samples: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fitted_at: Option<String>,
+}
+
+/// A prediction with its 95% interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Prediction {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl CostModel {
+    /// Built-in default model for a backend, if there is one.
+    ///
+    /// Rough UltraHonk figures (~0.5s at 2^16, ~9s at 2^20 on a machine with
+    /// calibration score 150) with wide error bars; fit a model from your own
+    /// history for anything better.
+    pub fn builtin(backend: &str) -> Option<CostModel> {
+        if backend != "barretenberg" && backend != "bb" {
+            return None;
+        }
+        Some(CostModel {
+            version: COST_MODEL_VERSION,
+            backend: "barretenberg".to_string(),
+            backend_version: None,
+            calibration_score: 150.0,
+            prove_ms: PowerLaw {
+                coefficient: 0.00439,
+                exponent: 1.05,
+                log_sigma: 0.35,
+            },
+            peak_rss_mb: Some(PowerLaw {
+                coefficient: 0.0301,
+                exponent: 0.83,
+                log_sigma: 0.4,
+            }),
+            samples: 0,
+            fitted_at: None,
+        })
+    }
+
+    /// Predict prove time (scaled to a machine with `calibration_score`)
+    /// and peak memory for a circuit with the given subgroup size.
+    pub fn predict(
+        &self,
+        subgroup_size: u64,
+        calibration_score: f64,
+    ) -> (Prediction, Option<Prediction>) {
+        let x = subgroup_size.max(1) as f64;
+        let scale = if calibration_score > 0.0 && self.calibration_score > 0.0 {
+            self.calibration_score / calibration_score
+        } else {
+            1.0
+        };
+        let (low, high) = self.prove_ms.interval(x);
+        let prove = Prediction {
+            estimate: self.prove_ms.predict(x) * scale,
+            low: low * scale,
+            high: high * scale,
+        };
+        let rss = self.peak_rss_mb.map(|law| {
+            let (low, high) = law.interval(x);
+            Prediction {
+                estimate: law.predict(x),
+                low,
+                high,
+            }
+        });
+        (prove, rss)
+    }
+
+    /// Fit a model from benchmark records of one backend.
+    ///
+    /// Records are matched on `backend.name` (and `backend.version` when
+    /// given); runs flagged as anomalies are skipped. Each record's size is its subgroup size (or gate count rounded
+    /// up to a power of two). Prove times are scaled to a common calibration
+    /// score, the median of the records' `calibration_score` metadata;
+    /// records without one are assumed to score `default_score`.
+    pub fn fit(
+        records: &[BenchRecord],
+        backend: &str,
+        backend_version: Option<&str>,
+        default_score: f64,
+    ) -> BenchResult<CostModel> {
+        let matching: Vec<&BenchRecord> = records
+            .iter()
+            .filter(|r| r.backend.name == backend && !r.anomaly)
+            .filter(|r| {
+                backend_version.is_none() || r.backend.version.as_deref() == backend_version
+            })
+            .collect();
+
+        let score_of = |r: &BenchRecord| {
+            r.metadata
+                .get(CALIBRATION_METADATA_KEY)
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(default_score)
+        };
+        let mut scores: Vec<f64> = matching.iter().map(|r| score_of(r)).collect();
+        scores.sort_by(f64::total_cmp);
+        let calibration_score = scores
+            .get(scores.len() / 2)
+            .copied()
+            .unwrap_or(default_score);
+
+        let size_of = |r: &BenchRecord| {
+            r.subgroup_size
+                .or_else(|| r.total_gates.map(|g| g.max(1).next_power_of_two()))
+                .map(|s| s as f64)
+        };
+        let prove_points: Vec<(f64, f64)> = matching
+            .iter()
+            .filter_map(|r| {
+                let ms = r.prove_stats.as_ref()?.mean_ms;
+                Some((
+                    size_of(r)?,
+                    normalize_ms(ms, score_of(r), calibration_score),
+                ))
+            })
+            .collect();
+        let rss_points: Vec<(f64, f64)> = matching
+            .iter()
+            .filter_map(|r| Some((size_of(r)?, r.peak_rss_mb?)))
+            .collect();
+
+        let prove_ms = PowerLaw::fit(&prove_points).ok_or_else(|| {
+            BenchError::Message(format!(
+                "not enough data to fit a model for {backend}{}: {} record(s) with prove time and \
+                 circuit size (need {MIN_FIT_SAMPLES}, at two or more sizes)",
+                backend_version.map(|v| format!("@{v}")).unwrap_or_default(),
+                prove_points.len()
+            ))
+        })?;
+
+        Ok(CostModel {
+            version: COST_MODEL_VERSION,
+            backend: backend.to_string(),
+            backend_version: backend_version.map(str::to_string),
+            calibration_score,
+            prove_ms,
+            peak_rss_mb: PowerLaw::fit(&rss_points),
+            samples: prove_points.len(),
+            fitted_at: time::OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .ok(),
+        })
+    }
+
+    /// Read a model from a JSON file.
+    pub fn load(path: &Path) -> BenchResult<CostModel> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+        let model: CostModel = serde_json::from_str(&json).map_err(|e| {
+            BenchError::Message(format!("invalid cost model {}: {e}", path.display()))
+        })?;
+        if model.version != COST_MODEL_VERSION {
+            return Err(BenchError::Message(format!(
+                "unsupported cost model version {} in {}",
+                model.version,
+                path.display()
+            )));
+        }
+        Ok(model)
+    }
+
+    /// Write the model as JSON, creating parent directories.
+    pub fn save(&self, path: &Path) -> BenchResult<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                BenchError::Message(format!("failed to create {}: {e}", dir.display()))
+            })?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| BenchError::Message(format!("failed to serialize cost model: {e}")))?;
+        std::fs::write(path, json)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+    }
+}
+
+/// Path of the model file for a backend (and optionally a version).
+pub fn model_path(models_dir: &Path, backend: &str, version: Option<&str>) -> PathBuf {
+    match version {
+        Some(v) => models_dir.join(format!("{backend}@{v}.json")),
+        None => models_dir.join(format!("{backend}.json")),
+    }
+}
+
+/// Find the model for a backend version: the version-specific file, then the
+/// backend-wide file, then the built-in default. Returns the model and where
+/// it came from.
+pub fn resolve(
+    models_dir: &Path,
+    backend: &str,
+    version: Option<&str>,
+) -> BenchResult<(CostModel, String)> {
+    let candidates = version
+        .map(|v| model_path(models_dir, backend, Some(v)))
+        .into_iter()
+        .chain(std::iter::once(model_path(models_dir, backend, None)));
+    for path in candidates {
+        if path.exists() {
+            let model = CostModel::load(&path)?;
+            return Ok((model, path.display().to_string()));
+        }
+    }
+    CostModel::builtin(backend)
+        .map(|model| (model, "built-in default".to_string()))
+        .ok_or_else(|| {
+            BenchError::Message(format!(
+                "no cost model for backend '{backend}' in {} (fit one with `noir-bench model fit`)",
+                models_dir.display()
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_law_interval_brackets_prediction() {
+        let law = PowerLaw {
+            coefficient: 2.0,
+            exponent: 1.0,
+            log_sigma: 0.1,
+        };
+        assert_eq!(law.predict(10.0), 20.0);
+        let (low, high) = law.interval(10.0);
+        assert!(low < 20.0 && 20.0 < high);
+        assert!((low * high - 400.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_predict_scales_to_calibration_score() {
+        let model = CostModel::builtin("bb").unwrap();
+        let (same, rss) = model.predict(1 << 16, model.calibration_score);
+        let (slower, _) = model.predict(1 << 16, model.calibration_score / 2.0);
+        assert!((slower.estimate - 2.0 * same.estimate).abs() < 1e-6);
+        assert!(same.estimate > 100.0 && same.estimate < 2000.0);
+        assert!(rss.is_some());
+    }
+
+    #[test]
+    fn test_resolve_prefers_version_specific_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut model = CostModel::builtin("barretenberg").unwrap();
+        model.samples = 7;
+        model
+            .save(&model_path(dir.path(), "barretenberg", Some("1.2.0")))
+            .unwrap();
+
+        let (found, source) = resolve(dir.path(), "barretenberg", Some("1.2.0")).unwrap();
+        assert_eq!(found.samples, 7);
+        assert!(source.ends_with("barretenberg@1.2.0.json"));
+
+        let (_, source) = resolve(dir.path(), "barretenberg", Some("0.1.0")).unwrap();
+        assert_eq!(source, "built-in default");
+
+        assert!(resolve(dir.path(), "plonky2", None).is_err());
+    }
+
+    #[test]
+    fn test_power_law_fit_recovers_exact_law() {
+        let points: Vec<(f64, f64)> = [1024.0, 4096.0, 16384.0, 65536.0]
+            .iter()
+            .map(|&x: &f64| (x, 0.5 * x.powf(1.1)))
+            .collect();
+        let law = PowerLaw::fit(&points).unwrap();
+        assert!((law.exponent - 1.1).abs() < 1e-9);
+        assert!((law.coefficient - 0.5).abs() < 1e-9);
+        assert!(law.log_sigma < 1e-9);
+
+        // A single size can't determine the exponent
+        assert!(PowerLaw::fit(&[(16.0, 1.0), (16.0, 2.0), (16.0, 3.0)]).is_none());
+        assert!(PowerLaw::fit(&points[..2]).is_none());
+    }
+
+    #[test]
+    fn test_fit_from_records_normalizes_calibration() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+        let record = |subgroup: u64, prove_ms: f64, score: &str, version: &str| {
+            let mut r = BenchRecord::new(
+                "c".to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "barretenberg".to_string(),
+                    version: Some(version.to_string()),
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.subgroup_size = Some(subgroup);
+            r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+            r.metadata
+                .insert(CALIBRATION_METADATA_KEY.to_string(), score.to_string());
+            r
+        };
+        let records = vec![
+            record(1 << 10, 100.0, "100", "1.0.0"),
+            record(1 << 12, 400.0, "100", "1.0.0"),
+            record(1 << 14, 1600.0, "100", "1.0.0"),
+            // Same circuit on a machine twice as fast
+            record(1 << 14, 800.0, "200", "1.0.0"),
+            record(1 << 14, 99_999.0, "100", "2.0.0"),
+        ];
+
+        let model = CostModel::fit(&records, "barretenberg", Some("1.0.0"), 100.0).unwrap();
+        assert_eq!(model.samples, 4);
+        assert_eq!(model.calibration_score, 100.0);
+        assert!((model.prove_ms.exponent - 1.0).abs() < 1e-9);
+        assert!(model.peak_rss_mb.is_none());
+
+        assert!(CostModel::fit(&records, "barretenberg", Some("2.0.0"), 100.0).is_err());
+        assert!(CostModel::fit(&records, "plonky2", None, 100.0).is_err());
+    }
+}
@@ -18,9 +18,25 @@ pub struct EnvironmentInfo {
 
     pub os: String,
 
+    /// CPU architecture (e.g. "x86_64", "aarch64")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+
+    /// CPU features that change which code paths bb takes (e.g. "avx2", "adx", "neon")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_features: Option<Vec<String>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hostname: Option<String>,
 
+    /// CPU frequency governor (e.g. "performance"), Linux only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_governor: Option<String>,
+
+    /// Highest thermal pressure level seen while measuring, macOS only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thermal_pressure: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_sha: Option<String>,
 
@@ -41,7 +57,11 @@ impl Default for EnvironmentInfo {
             cpu_cores: None,
             total_ram_bytes: None,
             os: std::env::consts::OS.to_string(),
+            arch: None,
+            cpu_features: None,
             hostname: None,
+            cpu_governor: None,
+            thermal_pressure: None,
             git_sha: None,
             git_dirty: None,
             nargo_version: None,
@@ -62,7 +82,10 @@ impl EnvironmentInfo {
         let cpu_cores = sys.physical_core_count().map(|c| c as u32);
         let total_ram_bytes = Some(sys.total_memory());
         let os = System::name().unwrap_or_else(|| std::env::consts::OS.to_string());
+        let arch = Some(std::env::consts::ARCH.to_string());
+        let cpu_features = detect_cpu_features();
         let hostname = System::host_name();
+        let cpu_governor = detect_cpu_governor();
 
         let git_sha = detect_git_sha();
         let git_dirty = detect_git_dirty();
@@ -74,7 +97,11 @@ impl EnvironmentInfo {
             cpu_cores,
             total_ram_bytes,
             os,
+            arch,
+            cpu_features,
             hostname,
+            cpu_governor,
+            thermal_pressure: None,
             git_sha,
             git_dirty,
             nargo_version,
@@ -92,6 +119,67 @@ impl EnvironmentInfo {
     }
 }
 
+/// Canonical name for a CPU architecture, so `arm64`/`aarch64` and
+/// `amd64`/`x64`/`x86_64` match.
+pub fn normalize_arch(arch: &str) -> String {
+    match arch.trim().to_ascii_lowercase().as_str() {
+        "arm64" | "aarch64" => "aarch64".to_string(),
+        "amd64" | "x64" | "x86_64" | "x86-64" => "x86_64".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether a record's `arch` matches the requested one (records without an
+/// arch never match).
+pub fn arch_matches(env: &EnvironmentInfo, arch: &str) -> bool {
+    env.arch
+        .as_deref()
+        .is_some_and(|a| normalize_arch(a) == normalize_arch(arch))
+}
+
+/// Detect the CPU features relevant to proving performance: wide vector
+/// and big-integer arithmetic extensions.
+pub fn detect_cpu_features() -> Option<Vec<String>> {
+    #[allow(unused_mut)]
+    let mut features: Vec<&str> = Vec::new();
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::arch::is_x86_feature_detected!("avx2") {
+            features.push("avx2");
+        }
+        if std::arch::is_x86_feature_detected!("avx512f") {
+            features.push("avx512f");
+        }
+        if std::arch::is_x86_feature_detected!("adx") {
+            features.push("adx");
+        }
+        if std::arch::is_x86_feature_detected!("bmi2") {
+            features.push("bmi2");
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            features.push("neon");
+        }
+        if std::arch::is_aarch64_feature_detected!("sve") {
+            features.push("sve");
+        }
+        if std::arch::is_aarch64_feature_detected!("sha3") {
+            features.push("sha3");
+        }
+    }
+    Some(features.into_iter().map(String::from).collect::<Vec<_>>()).filter(|f| !f.is_empty())
+}
+
+/// Detect the CPU frequency governor of cpu0 from sysfs
+pub fn detect_cpu_governor() -> Option<String> {
+    std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
 /// Detect git SHA from `git rev-parse HEAD`
 fn detect_git_sha() -> Option<String> {
     Command::new("git")
@@ -153,6 +241,24 @@ mod tests {
         assert!(!env.os.is_empty());
     }
 
+    #[test]
+    fn test_environment_detect_has_arch() {
+        let env = EnvironmentInfo::detect();
+        assert_eq!(env.arch.as_deref(), Some(std::env::consts::ARCH));
+    }
+
+    #[test]
+    fn test_arch_matches_aliases() {
+        let env = EnvironmentInfo {
+            arch: Some("aarch64".to_string()),
+            ..EnvironmentInfo::default()
+        };
+        assert!(arch_matches(&env, "arm64"));
+        assert!(arch_matches(&env, "AArch64"));
+        assert!(!arch_matches(&env, "x86_64"));
+        assert!(!arch_matches(&EnvironmentInfo::default(), "x86_64"));
+    }
+
     #[test]
     fn test_environment_default() {
         let env = EnvironmentInfo::default();
@@ -0,0 +1,122 @@
+//! Streaming file hashes and large-artifact thresholds.
+//!
+//! Aggregated circuits can produce artifacts of several GB, too big to read
+//! into memory just to fingerprint them. Files are hashed in fixed-size
+//! chunks instead, with progress on stderr for files of at least
+//! [`HASH_PROGRESS_BYTES`], and artifacts of at least
+//! [`LARGE_ARTIFACT_BYTES`] get a warning when they're loaded.
+
+use std::io::Read;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// Bytes read per chunk while hashing.
+const CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Files at least this large report hashing progress.
+pub const HASH_PROGRESS_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Artifacts at least this large are warned about when loaded: parsing one
+/// takes several times its size in memory.
+pub const LARGE_ARTIFACT_BYTES: u64 = 512 * 1024 * 1024;
+
+/// `bytes` as MiB or GiB, for messages about large files.
+pub fn format_size(bytes: u64) -> String {
+    const MIB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MIB {
+        format!("{:.1} GiB", bytes as f64 / (1024.0 * MIB))
+    } else {
+        format!("{:.1} MiB", bytes as f64 / MIB)
+    }
+}
+
+/// sha256 (hex) of `reader`, read in chunks. `progress` is called after
+/// every chunk with the number of bytes hashed so far.
+pub fn sha256_reader(
+    mut reader: impl Read,
+    mut progress: impl FnMut(u64),
+) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; CHUNK_BYTES];
+    let mut total = 0u64;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buf[..n]);
+        total += n as u64;
+        progress(total);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// sha256 (hex) of the file at `path`, streamed; files of at least
+/// [`HASH_PROGRESS_BYTES`] print their progress every 10%.
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < HASH_PROGRESS_BYTES {
+        return sha256_reader(file, |_| {});
+    }
+    eprintln!("hashing {} ({})", path.display(), format_size(len));
+    let mut reported = 0;
+    sha256_reader(file, |done| {
+        let pct = done * 100 / len.max(1);
+        if pct >= reported + 10 && done < len {
+            reported = pct - pct % 10;
+            eprintln!("hashing {}: {reported}%", path.display());
+        }
+    })
+}
+
+/// Warn when the artifact at `path`, `len` bytes long, is at least
+/// [`LARGE_ARTIFACT_BYTES`].
+pub fn warn_if_large_artifact(path: &Path, len: u64) {
+    if len >= LARGE_ARTIFACT_BYTES {
+        eprintln!(
+            "warning: {} is {}; loading it takes several times that in memory",
+            path.display(),
+            format_size(len)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streaming_hash_matches_whole_buffer_hash() {
+        let data: Vec<u8> = (0..(CHUNK_BYTES * 2 + 17))
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut seen = Vec::new();
+        let hash = sha256_reader(data.as_slice(), |done| seen.push(done)).unwrap();
+        assert_eq!(hash, crate::sha256_hex(&data));
+        assert_eq!(seen.last(), Some(&(data.len() as u64)));
+        assert!(seen.len() >= 3);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("program.json");
+        std::fs::write(&path, b"{}").unwrap();
+        assert_eq!(sha256_file(&path).unwrap(), crate::sha256_hex(b"{}"));
+        assert_eq!(
+            sha256_reader(&b""[..], |_| {}).unwrap(),
+            crate::sha256_hex(b"")
+        );
+        assert_eq!(
+            crate::sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+        assert_eq!(format_size(LARGE_ARTIFACT_BYTES * 5), "2.5 GiB");
+    }
+}
@@ -0,0 +1,506 @@
+//! Conversion of pre-BenchRecord per-command reports into BenchRecords.
+//!
+//! `exec`, `prove`, `gates` and `verify` used to write standalone JSON reports
+//! (`ExecReport`, `ProveReport`, `GatesReport`, `VerifyReport`). These
+//! converters map them onto the canonical schema so older data can flow into
+//! history, compare and trend analysis.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::env::EnvironmentInfo;
+use super::schema::{
+    BackendInfo, BenchRecord, PROVE_MODE_METADATA_KEY, RunConfig, RunStatus, SCHEMA_VERSION,
+    TimingStat,
+};
+use crate::{
+    BenchError, CommonMeta, ExecReport, GatesReport, IterationStats, ProveReport, SystemInfo,
+    VerifyReport,
+};
+
+/// Which legacy per-command report a JSON object is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyReportKind {
+    Exec,
+    Prove,
+    Gates,
+    Verify,
+}
+
+impl LegacyReportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LegacyReportKind::Exec => "exec",
+            LegacyReportKind::Prove => "prove",
+            LegacyReportKind::Gates => "gates",
+            LegacyReportKind::Verify => "verify",
+        }
+    }
+}
+
+/// Detect a legacy per-command report by its distinguishing timing/count field.
+pub fn detect_legacy_report(value: &Value) -> Option<LegacyReportKind> {
+    let has = |key: &str| value.get(key).is_some();
+    if value.get("schema_version").is_some() || !has("name") {
+        return None;
+    }
+    if has("prove_time_ms") {
+        Some(LegacyReportKind::Prove)
+    } else if has("verify_time_ms") {
+        Some(LegacyReportKind::Verify)
+    } else if has("execution_time_ms") {
+        Some(LegacyReportKind::Exec)
+    } else if has("total_gates") && has("per_opcode") {
+        Some(LegacyReportKind::Gates)
+    } else {
+        None
+    }
+}
+
+fn env_from_legacy(meta: &CommonMeta, system: Option<&SystemInfo>) -> EnvironmentInfo {
+    EnvironmentInfo {
+        cpu_model: system.and_then(|s| s.cpu_model.clone()),
+        cpu_cores: system.and_then(|s| s.cpu_cores_logical).map(|c| c as u32),
+        total_ram_bytes: system.and_then(|s| s.total_ram_bytes),
+        os: system
+            .and_then(|s| s.os.clone())
+            .unwrap_or_else(|| "unknown".to_string()),
+        arch: system.and_then(|s| s.arch.clone()),
+        nargo_version: Some(meta.noir_version.clone()).filter(|v| !v.is_empty()),
+        ..EnvironmentInfo::default()
+    }
+}
+
+/// Timing stats from legacy iteration data, falling back to the single
+/// reported time when no per-iteration samples were kept.
+fn stats_from_legacy(iterations: Option<&IterationStats>, single_ms: u128) -> TimingStat {
+    let samples: Vec<f64> = iterations
+        .map(|it| it.times_ms.iter().map(|t| *t as f64).collect())
+        .unwrap_or_default();
+    if samples.is_empty() {
+        TimingStat::from_samples(&[single_ms as f64])
+    } else {
+        TimingStat::from_samples(&samples)
+    }
+}
+
+fn config_from_legacy(iterations: Option<&IterationStats>) -> RunConfig {
+    match iterations {
+        Some(it) => RunConfig {
+            warmup_iterations: it.warmup as u32,
+            measured_iterations: it.iterations.max(1) as u32,
+            timeout_secs: None,
+            compile_flags: Vec::new(),
+            env: BTreeMap::new(),
+        },
+        None => RunConfig {
+            warmup_iterations: 0,
+            measured_iterations: 1,
+            timeout_secs: None,
+            compile_flags: Vec::new(),
+            env: BTreeMap::new(),
+        },
+    }
+}
+
+fn bytes_to_mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// Build the common part of a converted record.
+///
+/// The record id is derived from the report kind and content so converting
+/// the same report twice yields the same id.
+fn base_record(
+    kind: LegacyReportKind,
+    meta: &CommonMeta,
+    env: EnvironmentInfo,
+    backend: BackendInfo,
+    config: RunConfig,
+    content: &Value,
+) -> BenchRecord {
+    let digest = crate::sha256_hex(content.to_string().as_bytes());
+    let mut metadata = BTreeMap::new();
+    metadata.insert("source".to_string(), format!("legacy-{}", kind.as_str()));
+    if let Some(h) = &meta.artifact_sha256 {
+        metadata.insert("artifact_sha256".to_string(), h.clone());
+    }
+    if let Some(h) = &meta.inputs_sha256 {
+        metadata.insert("inputs_sha256".to_string(), h.clone());
+    }
+
+    BenchRecord {
+        schema_version: SCHEMA_VERSION,
+        record_id: format!("legacy-{}-{}", kind.as_str(), &digest[..16]),
+        timestamp: meta.timestamp.clone(),
+        circuit_name: meta.name.clone(),
+        circuit_path: Some(meta.artifact_path.display().to_string()),
+        params: None,
+        env,
+        backend,
+        config,
+        compile_stats: None,
+        compile_warm_stats: None,
+        compile_incremental_stats: None,
+        witness_stats: None,
+        prove_stats: None,
+        backend_setup_ms: None,
+        probe_ms: None,
+        vk_gen_stats: None,
+        vk_cached: None,
+        key_cache: None,
+        cache_mode: None,
+        backend_phases: None,
+        cpu_times: None,
+        verify_stats: None,
+        verify_cold_ms: None,
+        gates_stats: None,
+        exec_split: None,
+        oracle_stats: None,
+        custom_metrics: BTreeMap::new(),
+        proof_size_bytes: None,
+        proving_key_size_bytes: None,
+        verification_key_size_bytes: None,
+        artifact_size_bytes: None,
+        total_gates: None,
+        acir_opcodes: None,
+        subgroup_size: None,
+        peak_rss_mb: None,
+        cli_args: meta.cli_args.clone(),
+        fingerprints: None,
+        error: None,
+        status: None,
+        metadata,
+        anomaly: false,
+        signature: None,
+        signed_payload: None,
+    }
+}
+
+fn convert_backend(b: &crate::BackendInfo) -> BackendInfo {
+    BackendInfo {
+        name: b.name.clone(),
+        version: b.version.clone(),
+        variant: None,
+    }
+}
+
+/// Carry a report's `--expect-failure` outcome over to its record.
+fn mark_expected_failure(
+    record: &mut BenchRecord,
+    status: Option<RunStatus>,
+    message: Option<&str>,
+) {
+    record.status = status;
+    if let Some(message) = message {
+        record
+            .metadata
+            .insert("failure_message".to_string(), message.to_string());
+    }
+}
+
+/// Convert an `ExecReport`: execution time maps to witness generation.
+pub fn exec_report_to_record(report: &ExecReport, content: &Value) -> BenchRecord {
+    let iterations = report.iterations.as_ref();
+    let mut record = base_record(
+        LegacyReportKind::Exec,
+        &report.meta,
+        env_from_legacy(&report.meta, report.system.as_ref()),
+        BackendInfo {
+            name: "nargo-exec".to_string(),
+            version: None,
+            variant: None,
+        },
+        config_from_legacy(iterations),
+        content,
+    );
+    record.witness_stats = Some(stats_from_legacy(iterations, report.execution_time_ms));
+    record.exec_split = report.exec_split.clone();
+    record.oracle_stats = report
+        .exec_split
+        .as_ref()
+        .and_then(|s| s.resolver_ms)
+        .map(|ms| TimingStat::from_samples(&[ms]));
+    record.peak_rss_mb = report.peak_memory_bytes.map(bytes_to_mb);
+    if let Some(svg) = &report.flamegraph_svg {
+        record
+            .metadata
+            .insert("flamegraph_svg".to_string(), svg.display().to_string());
+    }
+    if let Some(ok) = report.return_ok {
+        record
+            .metadata
+            .insert("return_ok".to_string(), ok.to_string());
+    }
+    mark_expected_failure(
+        &mut record,
+        report.status,
+        report.failure_message.as_deref(),
+    );
+    record
+}
+
+/// Convert a `ProveReport`.
+pub fn prove_report_to_record(report: &ProveReport, content: &Value) -> BenchRecord {
+    let iterations = report.iterations.as_ref();
+    let mut record = base_record(
+        LegacyReportKind::Prove,
+        &report.meta,
+        env_from_legacy(&report.meta, report.system.as_ref()),
+        convert_backend(&report.backend),
+        config_from_legacy(iterations),
+        content,
+    );
+    record.prove_stats = Some(stats_from_legacy(iterations, report.prove_time_ms));
+    record.witness_stats = report
+        .witness_gen_time_ms
+        .map(|ms| TimingStat::from_samples(&[ms as f64]));
+    record.oracle_stats = report
+        .oracle_resolver_ms
+        .map(|ms| TimingStat::from_samples(&[ms]));
+    record.backend_setup_ms = report.backend_setup_ms.map(|ms| ms as f64);
+    if let Some(mode) = &report.prove_mode {
+        record
+            .metadata
+            .insert(PROVE_MODE_METADATA_KEY.to_string(), mode.clone());
+    }
+    record.vk_gen_stats = report
+        .vk_gen_time_ms
+        .map(|ms| TimingStat::from_samples(&[ms as f64]));
+    record.vk_cached = report.vk_cached;
+    record.key_cache = report.key_cache;
+    record.backend_phases = report.backend_phases.clone();
+    record.custom_metrics = report.custom_metrics.clone();
+    record.proof_size_bytes = report.proof_size_bytes;
+    record.proving_key_size_bytes = report.proving_key_size_bytes;
+    record.verification_key_size_bytes = report.verification_key_size_bytes;
+    record.total_gates = report.gate_count;
+    record.peak_rss_mb = report.peak_memory_bytes.map(bytes_to_mb);
+    mark_expected_failure(
+        &mut record,
+        report.status,
+        report.failure_message.as_deref(),
+    );
+    record
+}
+
+/// Convert a `GatesReport`.
+pub fn gates_report_to_record(report: &GatesReport, content: &Value) -> BenchRecord {
+    let mut record = base_record(
+        LegacyReportKind::Gates,
+        &report.meta,
+        env_from_legacy(&report.meta, report.system.as_ref()),
+        convert_backend(&report.backend),
+        config_from_legacy(report.iterations.as_ref()),
+        content,
+    );
+    record.gates_stats = report
+        .gates_time_ms
+        .map(|ms| stats_from_legacy(report.iterations.as_ref(), ms));
+    record.total_gates = Some(report.total_gates as u64);
+    record.acir_opcodes = Some(report.acir_opcodes as u64);
+    record.subgroup_size = report.subgroup_size;
+    record
+}
+
+/// Convert a `VerifyReport`; the verification outcome is kept in metadata.
+pub fn verify_report_to_record(report: &VerifyReport, content: &Value) -> BenchRecord {
+    let iterations = report.iterations.as_ref();
+    let mut record = base_record(
+        LegacyReportKind::Verify,
+        &report.meta,
+        env_from_legacy(&report.meta, report.system.as_ref()),
+        convert_backend(&report.backend),
+        config_from_legacy(iterations),
+        content,
+    );
+    record.verify_stats = Some(stats_from_legacy(iterations, report.verify_time_ms));
+    record
+        .metadata
+        .insert("verify_ok".to_string(), report.ok.to_string());
+    record
+}
+
+/// Convert any legacy per-command report JSON into a BenchRecord.
+pub fn legacy_report_to_record(value: &Value) -> Result<BenchRecord, BenchError> {
+    let kind = detect_legacy_report(value)
+        .ok_or_else(|| BenchError::Message("not a recognized legacy report".into()))?;
+    let parse_err = |e: serde_json::Error| {
+        BenchError::Message(format!("invalid {} report: {e}", kind.as_str()))
+    };
+
+    let record = match kind {
+        LegacyReportKind::Exec => exec_report_to_record(
+            &serde_json::from_value(value.clone()).map_err(parse_err)?,
+            value,
+        ),
+        LegacyReportKind::Prove => prove_report_to_record(
+            &serde_json::from_value(value.clone()).map_err(parse_err)?,
+            value,
+        ),
+        LegacyReportKind::Gates => gates_report_to_record(
+            &serde_json::from_value(value.clone()).map_err(parse_err)?,
+            value,
+        ),
+        LegacyReportKind::Verify => verify_report_to_record(
+            &serde_json::from_value(value.clone()).map_err(parse_err)?,
+            value,
+        ),
+    };
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn meta_json() -> Value {
+        json!({
+            "name": "merkle",
+            "timestamp": "2025-06-01T00:00:00Z",
+            "noir_version": "1.0.0-beta.3",
+            "artifact_path": "target/merkle.json",
+            "cli_args": ["noir-bench", "prove"],
+            "artifact_sha256": "abc",
+            "inputs_sha256": null
+        })
+    }
+
+    fn with_meta(extra: Value) -> Value {
+        let mut v = meta_json();
+        for (k, val) in extra.as_object().unwrap() {
+            v[k] = val.clone();
+        }
+        v
+    }
+
+    #[test]
+    fn test_detect_legacy_report_kinds() {
+        let prove = with_meta(json!({"prove_time_ms": 1}));
+        let verify = with_meta(json!({"verify_time_ms": 1}));
+        let exec = with_meta(json!({"execution_time_ms": 1}));
+        let gates = with_meta(json!({"total_gates": 1, "per_opcode": []}));
+        assert_eq!(detect_legacy_report(&prove), Some(LegacyReportKind::Prove));
+        assert_eq!(
+            detect_legacy_report(&verify),
+            Some(LegacyReportKind::Verify)
+        );
+        assert_eq!(detect_legacy_report(&exec), Some(LegacyReportKind::Exec));
+        assert_eq!(detect_legacy_report(&gates), Some(LegacyReportKind::Gates));
+        assert_eq!(detect_legacy_report(&meta_json()), None);
+        assert_eq!(
+            detect_legacy_report(&json!({"schema_version": 2, "name": "x"})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prove_report_conversion() {
+        let report = with_meta(json!({
+            "prove_time_ms": 120,
+            "witness_gen_time_ms": 15,
+            "backend_prove_time_ms": 100,
+            "peak_memory_bytes": 2097152,
+            "proof_size_bytes": 4096,
+            "proving_key_size_bytes": null,
+            "verification_key_size_bytes": 1024,
+            "gate_count": 5000,
+            "backend": {"name": "bb", "version": "0.82.0"},
+            "system": {"cpu_model": "CPU", "cpu_cores_logical": 8, "cpu_cores_physical": 4, "total_ram_bytes": 1000, "os": "Linux"},
+            "iterations": {"iterations": 3, "warmup": 1, "times_ms": [100, 120, 140], "avg_ms": 120.0, "min_ms": 100, "max_ms": 140, "stddev_ms": 16.3},
+            "prove_mode": "amortized_setup"
+        }));
+        let record = legacy_report_to_record(&report).unwrap();
+
+        assert_eq!(record.schema_version, SCHEMA_VERSION);
+        assert_eq!(record.circuit_name, "merkle");
+        assert_eq!(record.circuit_path.as_deref(), Some("target/merkle.json"));
+        assert_eq!(record.backend.name, "bb");
+        assert_eq!(record.backend.version.as_deref(), Some("0.82.0"));
+        assert_eq!(record.env.os, "Linux");
+        assert_eq!(record.env.cpu_cores, Some(8));
+        assert_eq!(record.env.nargo_version.as_deref(), Some("1.0.0-beta.3"));
+        assert_eq!(record.config.measured_iterations, 3);
+        assert_eq!(record.config.warmup_iterations, 1);
+
+        let prove = record.prove_stats.as_ref().unwrap();
+        assert_eq!(prove.iterations, 3);
+        assert_eq!(prove.mean_ms, 120.0);
+        assert_eq!(prove.min_ms, 100.0);
+        assert_eq!(record.witness_stats.as_ref().unwrap().mean_ms, 15.0);
+        assert_eq!(record.proof_size_bytes, Some(4096));
+        assert_eq!(record.total_gates, Some(5000));
+        assert_eq!(record.peak_rss_mb, Some(2.0));
+        assert_eq!(
+            record.metadata.get("source").map(String::as_str),
+            Some("legacy-prove")
+        );
+        assert_eq!(
+            record.metadata.get("prove_mode").map(String::as_str),
+            Some("amortized_setup")
+        );
+        assert_eq!(
+            record.metadata.get("artifact_sha256").map(String::as_str),
+            Some("abc")
+        );
+
+        // Deterministic id
+        let again = legacy_report_to_record(&report).unwrap();
+        assert_eq!(record.record_id, again.record_id);
+        assert!(record.record_id.starts_with("legacy-prove-"));
+    }
+
+    #[test]
+    fn test_gates_exec_verify_conversion() {
+        let gates = with_meta(json!({
+            "total_gates": 900,
+            "acir_opcodes": 30,
+            "per_opcode": [],
+            "per_opcode_gates": null,
+            "subgroup_size": 1024,
+            "per_opcode_percent": null,
+            "backend": {"name": "bb", "version": null},
+            "system": null
+        }));
+        let record = legacy_report_to_record(&gates).unwrap();
+        assert_eq!(record.total_gates, Some(900));
+        assert_eq!(record.acir_opcodes, Some(30));
+        assert_eq!(record.subgroup_size, Some(1024));
+        assert_eq!(record.env.os, "unknown");
+
+        let exec = with_meta(json!({
+            "execution_time_ms": 42,
+            "samples_count": 0,
+            "peak_memory_bytes": null,
+            "flamegraph_svg": null,
+            "system": null,
+            "iterations": null,
+            "exec_split": {
+                "acir_ms": 10.0, "brillig_ms": 30.0, "foreign_call_ms": 2.0,
+                "acir_opcodes": 5, "brillig_opcodes": 15, "foreign_calls": 1,
+                "resolver_calls": 1, "resolver_ms": 1.5
+            }
+        }));
+        let record = legacy_report_to_record(&exec).unwrap();
+        assert_eq!(record.witness_stats.as_ref().unwrap().mean_ms, 42.0);
+        assert_eq!(record.exec_split.as_ref().unwrap().brillig_opcodes, 15);
+        assert_eq!(record.oracle_stats.as_ref().unwrap().mean_ms, 1.5);
+        assert_eq!(record.config.measured_iterations, 1);
+
+        let verify = with_meta(json!({
+            "verify_time_ms": 7,
+            "ok": false,
+            "backend": {"name": "bb", "version": null},
+            "system": null,
+            "iterations": null
+        }));
+        let record = legacy_report_to_record(&verify).unwrap();
+        assert_eq!(record.verify_stats.as_ref().unwrap().mean_ms, 7.0);
+        assert_eq!(
+            record.metadata.get("verify_ok").map(String::as_str),
+            Some("false")
+        );
+    }
+}
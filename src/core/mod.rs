@@ -1,10 +1,30 @@
 //! Core types and schemas for noir-bench.
 //!
-//! This module contains the canonical `BenchRecord` schema (v1) used for all benchmark outputs.
+//! This module contains the canonical `BenchRecord` schema (v2) used for all benchmark outputs,
+//! plus migrations from older record shapes (see `schema::upgrade_value` and `legacy`).
 
+pub mod annotations;
+pub mod anomaly;
+pub mod calibration;
+pub mod cost_model;
 pub mod env;
+pub mod hashing;
+pub mod legacy;
+pub mod page_cache;
+pub mod process;
+pub mod rounding;
 pub mod schema;
+pub mod signing;
+pub mod thermal;
 
 // Re-export key types for convenience
 pub use env::EnvironmentInfo;
-pub use schema::{BackendInfo, BenchRecord, RunConfig, SCHEMA_VERSION, TimingStat};
+pub use hashing::{LARGE_ARTIFACT_BYTES, sha256_file};
+pub use page_cache::CacheMode;
+pub use schema::{
+    AMORTIZED_SETUP_MODE, BackendInfo, BenchRecord, CpuTimeStat, DataQuality, ExecSplit,
+    KeyCacheStatus, MIN_P95_SAMPLES, MIN_SUPPORTED_SCHEMA_VERSION, PROVE_MODE_METADATA_KEY,
+    PercentileMethod, RecordSignature, RunConfig, RunError, RunStatus, SCHEMA_VERSION,
+    STANDARD_PROVE_MODE, TimingStat, parse_record, upgrade_value,
+};
+pub use signing::SignaturePolicy;
@@ -0,0 +1,138 @@
+//! Page cache eviction for cold-cache measurements (Linux).
+//!
+//! Repeated runs find the artifact, the witness and bb's SRS (CRS points) in
+//! the OS page cache, so after the first run they measure warm-start proving.
+//! In cold mode (`--cold-cache`) the files a prove reads are evicted right
+//! before it with `posix_fadvise(POSIX_FADV_DONTNEED)`, after flushing them
+//! since dirty pages can't be dropped, and bb reads them from disk again.
+//!
+//! Unlike `echo 3 > /proc/sys/vm/drop_caches` this needs no root and leaves
+//! the rest of the machine's cache alone. The SRS files are evicted rather
+//! than deleted, so a cold run doesn't turn into a download.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BenchError, BenchResult};
+
+/// Page cache state the prove runs of a record were measured in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    /// Inputs and SRS as left in the page cache by earlier runs
+    #[default]
+    Warm,
+    /// Inputs and SRS evicted from the page cache before every prove
+    Cold,
+}
+
+impl CacheMode {
+    pub fn from_cold(cold: bool) -> Self {
+        if cold {
+            CacheMode::Cold
+        } else {
+            CacheMode::Warm
+        }
+    }
+}
+
+/// Whether this platform can evict files from the page cache.
+pub fn supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Fail early when cold-cache measurements aren't possible here, rather
+/// than silently measuring warm runs.
+pub fn check_supported() -> BenchResult<()> {
+    if supported() {
+        Ok(())
+    } else {
+        Err(BenchError::Message(
+            "--cold-cache needs page cache eviction, which is only supported on Linux".into(),
+        ))
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            collect_files(&entry.path(), files);
+        }
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+}
+
+/// Evict `paths` from the page cache; directories are walked and missing
+/// paths skipped. Returns the number of files evicted.
+pub fn evict(paths: &[PathBuf]) -> BenchResult<usize> {
+    check_supported()?;
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(path, &mut files);
+    }
+    for file in &files {
+        evict_file(file).map_err(|e| {
+            BenchError::Message(format!(
+                "failed to evict {} from page cache: {e}",
+                file.display()
+            ))
+        })?;
+    }
+    Ok(files.len())
+}
+
+#[cfg(target_os = "linux")]
+fn evict_file(path: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let file = std::fs::File::open(path)?;
+    // Dirty pages (a freshly written witness) stay cached until written back
+    file.sync_data()?;
+    // SAFETY: the fd is open for the duration of the call
+    let rc = unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+    if rc != 0 {
+        return Err(std::io::Error::from_raw_os_error(rc));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn evict_file(_path: &Path) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_mode_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&CacheMode::from_cold(true)).unwrap(),
+            "\"cold\""
+        );
+        assert_eq!(CacheMode::from_cold(false), CacheMode::Warm);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_evict_walks_directories_and_skips_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let srs = dir.path().join("crs");
+        std::fs::create_dir_all(&srs).unwrap();
+        std::fs::write(srs.join("bn254_g1.dat"), vec![1u8; 8192]).unwrap();
+        std::fs::write(srs.join("bn254_g2.dat"), vec![2u8; 128]).unwrap();
+        let artifact = dir.path().join("program.json");
+        std::fs::write(&artifact, b"{}").unwrap();
+
+        let evicted = evict(&[srs, artifact.clone(), dir.path().join("missing.gz")]).unwrap();
+        assert_eq!(evicted, 3);
+        // Eviction leaves the contents alone
+        assert_eq!(std::fs::read(&artifact).unwrap(), b"{}");
+    }
+}
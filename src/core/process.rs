@@ -0,0 +1,614 @@
+//! Running backend commands under a timeout with peak memory tracking.
+//!
+//! Each child is waited on by a thread of its own, so its exit is seen as it
+//! happens rather than at the next poll, and the supervising thread only
+//! wakes up to sample memory or enforce the timeout.
+//!
+//! A command that times out is killed along with everything it spawned, so a
+//! stuck bb doesn't leave workers behind. On Windows the child is put in a Job
+//! Object that is terminated as a whole (and that dies with noir-bench); on
+//! other platforms the child's descendants are found through the process
+//! table and killed.
+//!
+//! The child's CPU time (user and system) comes with its exit status from
+//! `wait4` on Linux and macOS and from `GetProcessTimes` on Windows, so a
+//! slower run can be told apart as doing more work or using fewer cores.
+//!
+//! Peak memory (`mem` feature) is tracked by the OS where it can be, so
+//! spikes between samples are not missed: the RSS high-water mark (`VmHWM`)
+//! on Linux, the peak working set (`PROCESS_MEMORY_COUNTERS`) on Windows and
+//! the lifetime peak physical footprint (`proc_pid_rusage`) on macOS, where
+//! RSS undercounts compressed and purgeable memory. Elsewhere the child's
+//! RSS is sampled through sysinfo. Sampling starts every
+//! [`MIN_SAMPLE_INTERVAL`] and backs off to [`MAX_SAMPLE_INTERVAL`] while
+//! memory stays flat, going back to the fast rate whenever it grows.
+
+use std::io;
+use std::process::{Child, Command, ExitStatus};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Shortest time between memory samples, used while memory grows.
+pub const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Longest time between memory samples, reached while memory stays flat.
+pub const MAX_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How peak memory is sampled on a machine running `os` (as recorded in
+/// `EnvironmentInfo::os`), for report methodology sections.
+pub fn peak_memory_method(os: &str) -> String {
+    let os = os.to_ascii_lowercase();
+    if os.contains("windows") {
+        "peak working set of the backend process (PROCESS_MEMORY_COUNTERS)".to_string()
+    } else if os.contains("mac") || os.contains("darwin") {
+        "lifetime peak physical footprint of the backend process (proc_pid_rusage)".to_string()
+    } else if os.contains("linux") {
+        format!(
+            "peak resident set size of the backend process (VmHWM) sampled every {}-{} ms; growth after the last sample is missed",
+            MIN_SAMPLE_INTERVAL.as_millis(),
+            MAX_SAMPLE_INTERVAL.as_millis()
+        )
+    } else {
+        format!(
+            "resident set size of the backend process sampled every {}-{} ms; spikes between samples are missed",
+            MIN_SAMPLE_INTERVAL.as_millis(),
+            MAX_SAMPLE_INTERVAL.as_millis()
+        )
+    }
+}
+
+/// Time until the next memory sample: short while memory grows, backing
+/// off while it stays flat.
+#[derive(Debug, Clone, Copy)]
+struct SampleInterval(Duration);
+
+impl SampleInterval {
+    fn new() -> Self {
+        SampleInterval(MIN_SAMPLE_INTERVAL)
+    }
+
+    fn get(self) -> Duration {
+        self.0
+    }
+
+    /// Move on after a sample that did (or didn't) raise the peak.
+    fn next(&mut self, grew: bool) {
+        self.0 = if grew {
+            MIN_SAMPLE_INTERVAL
+        } else {
+            (self.0 * 3 / 2).min(MAX_SAMPLE_INTERVAL)
+        };
+    }
+}
+
+/// CPU time a process used, next to its wall time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTime {
+    pub wall_ms: f64,
+    pub user_ms: f64,
+    pub sys_ms: f64,
+}
+
+impl CpuTime {
+    /// User plus system time.
+    pub fn cpu_ms(&self) -> f64 {
+        self.user_ms + self.sys_ms
+    }
+}
+
+/// A command that ran to completion.
+#[derive(Debug, Clone, Copy)]
+pub struct Finished {
+    pub status: ExitStatus,
+    pub peak_memory_bytes: Option<u64>,
+    pub elapsed_ms: u128,
+    /// `None` where the platform doesn't report it
+    pub cpu_time: Option<CpuTime>,
+}
+
+/// What the waiting thread sends once the child exits. The child comes
+/// back with it so its handle stays open until the final memory sample.
+struct Exited {
+    status: io::Result<ExitStatus>,
+    at: Instant,
+    /// User and system ms, where they come with the exit status
+    cpu_ms: Option<(f64, f64)>,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    child: Child,
+}
+
+/// Block until `child` exits, returning its status and user and system ms.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn wait_child(child: &mut Child) -> (io::Result<ExitStatus>, Option<(f64, f64)>) {
+    use std::os::unix::process::ExitStatusExt;
+
+    let ms = |t: libc::timeval| t.tv_sec as f64 * 1000.0 + t.tv_usec as f64 / 1000.0;
+    let mut status = 0;
+    // SAFETY: rusage is plain data
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: the pid is our own child, which only this thread reaps,
+        // and both out pointers are valid for the call
+        let rc = unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, 0, &mut usage) };
+        if rc >= 0 {
+            let cpu = (ms(usage.ru_utime), ms(usage.ru_stime));
+            return (Ok(ExitStatus::from_raw(status)), Some(cpu));
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return (Err(err), None);
+        }
+    }
+}
+
+/// Block until `child` exits; CPU time is read from its handle afterwards
+/// on Windows, and not available elsewhere.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn wait_child(child: &mut Child) -> (io::Result<ExitStatus>, Option<(f64, f64)>) {
+    (child.wait(), None)
+}
+
+/// A spawned child whose process tree can be killed on timeout.
+pub struct Supervised {
+    pid: u32,
+    exit: Receiver<Exited>,
+    start: Instant,
+    peak_rss: u64,
+    #[cfg(windows)]
+    job: Option<windows::Job>,
+    /// The child's process handle, open until `Exited` is dropped
+    #[cfg(windows)]
+    handle: usize,
+    #[cfg(all(feature = "mem", not(any(windows, target_os = "macos"))))]
+    sys: sysinfo::System,
+}
+
+impl Supervised {
+    /// Spawn `cmd`, starting the clock just before, and a thread waiting for
+    /// it to exit.
+    pub fn spawn(cmd: &mut Command) -> io::Result<Self> {
+        let start = Instant::now();
+        let mut child = cmd.spawn()?;
+        let pid = child.id();
+        #[cfg(windows)]
+        let job = windows::Job::for_child(&child);
+        #[cfg(windows)]
+        let handle = {
+            use std::os::windows::io::AsRawHandle;
+            child.as_raw_handle() as usize
+        };
+
+        let (tx, exit) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name(format!("wait-{pid}"))
+            .spawn(move || {
+                let (status, cpu_ms) = wait_child(&mut child);
+                let _ = tx.send(Exited {
+                    status,
+                    at: Instant::now(),
+                    cpu_ms,
+                    child,
+                });
+            });
+        if let Err(e) = spawned {
+            // The closure, and the child with it, is gone; don't leave the
+            // process running unsupervised
+            kill_pid_tree(pid);
+            return Err(e);
+        }
+        Ok(Supervised {
+            pid,
+            exit,
+            start,
+            peak_rss: 0,
+            #[cfg(windows)]
+            job,
+            #[cfg(windows)]
+            handle,
+            #[cfg(all(feature = "mem", not(any(windows, target_os = "macos"))))]
+            sys: sysinfo::System::new(),
+        })
+    }
+
+    /// Wait for the child to exit, sampling its memory. With a non-zero
+    /// `timeout`, the process tree is killed once it expires and `None` is
+    /// returned.
+    pub fn wait_timeout(mut self, timeout: Duration) -> io::Result<Option<Finished>> {
+        let deadline = (timeout.as_secs() > 0).then(|| self.start + timeout);
+        let mut interval = SampleInterval::new();
+        self.sample_memory();
+        loop {
+            let until_deadline = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+            let wait = match (cfg!(feature = "mem"), until_deadline) {
+                (true, Some(left)) => Some(interval.get().min(left)),
+                (true, None) => Some(interval.get()),
+                (false, left) => left,
+            };
+            let received = match wait {
+                Some(wait) => self.exit.recv_timeout(wait),
+                None => self.exit.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(exited) => return self.finish(exited).map(Some),
+                Err(RecvTimeoutError::Timeout) => {
+                    if deadline.is_some_and(|d| Instant::now() >= d) {
+                        // A child that exited since the last wait has been
+                        // reaped and its pid may belong to another process
+                        // by now, so it is reported rather than killed
+                        if let Ok(exited) = self.exit.try_recv() {
+                            return self.finish(exited).map(Some);
+                        }
+                        self.kill_tree();
+                        return Ok(None);
+                    }
+                    let before = self.peak_rss;
+                    self.sample_memory();
+                    interval.next(self.peak_rss > before);
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::other("waiting for the child process failed"));
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, exited: Exited) -> io::Result<Finished> {
+        let status = exited.status?;
+        self.sample_memory();
+        let wall = exited.at.duration_since(self.start);
+        #[cfg(windows)]
+        let cpu_ms = exited
+            .cpu_ms
+            .or_else(|| windows::process_times(self.handle));
+        #[cfg(not(windows))]
+        let cpu_ms = exited.cpu_ms;
+        Ok(Finished {
+            status,
+            peak_memory_bytes: self.peak_memory_bytes(),
+            elapsed_ms: wall.as_millis(),
+            cpu_time: cpu_ms.map(|(user_ms, sys_ms)| CpuTime {
+                wall_ms: wall.as_secs_f64() * 1000.0,
+                user_ms,
+                sys_ms,
+            }),
+        })
+    }
+
+    fn peak_memory_bytes(&self) -> Option<u64> {
+        if cfg!(feature = "mem") {
+            Some(self.peak_rss)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(all(feature = "mem", windows))]
+    fn sample_memory(&mut self) {
+        // The handle stays valid after exit, so the last sample sees the
+        // lifetime peak
+        if let Some(peak) = windows::peak_working_set(self.handle) {
+            self.peak_rss = self.peak_rss.max(peak);
+        }
+    }
+
+    #[cfg(all(feature = "mem", target_os = "macos"))]
+    fn sample_memory(&mut self) {
+        // Only answers while the child is alive; samples come faster while
+        // memory grows, so the last one before exit is close to the peak
+        let mut info: libc::rusage_info_v4 = unsafe { std::mem::zeroed() };
+        let rc = unsafe {
+            libc::proc_pid_rusage(
+                self.pid as libc::c_int,
+                libc::RUSAGE_INFO_V4,
+                &mut info as *mut _ as *mut libc::rusage_info_t,
+            )
+        };
+        if rc == 0 {
+            self.peak_rss = self.peak_rss.max(info.ri_lifetime_max_phys_footprint);
+        }
+    }
+
+    #[cfg(all(feature = "mem", not(any(windows, target_os = "macos"))))]
+    fn sample_memory(&mut self) {
+        // The kernel's high-water mark covers spikes between samples; gone
+        // once the child has exited
+        #[cfg(target_os = "linux")]
+        if let Some(peak) = linux_peak_rss(self.pid) {
+            self.peak_rss = self.peak_rss.max(peak);
+            return;
+        }
+        let pid = sysinfo::Pid::from_u32(self.pid);
+        self.sys.refresh_process(pid);
+        if let Some(p) = self.sys.process(pid) {
+            self.peak_rss = self.peak_rss.max(p.memory() * 1024);
+        }
+    }
+
+    #[cfg(not(feature = "mem"))]
+    fn sample_memory(&mut self) {}
+
+    /// Kill the child and its descendants. Only called while the child is
+    /// still running as far as the waiting thread has reported; it can still
+    /// exit in the moment before the kill, though its pid being reused that
+    /// quickly is not a practical concern.
+    fn kill_tree(&self) {
+        #[cfg(windows)]
+        if let Some(job) = &self.job {
+            job.terminate();
+            let _ = self.exit.recv();
+            return;
+        }
+        kill_pid_tree(self.pid);
+        // Let the waiting thread reap the child
+        let _ = self.exit.recv();
+    }
+}
+
+/// `VmHWM` of `pid` in bytes, from `/proc/<pid>/status`.
+#[cfg(all(feature = "mem", target_os = "linux"))]
+fn linux_peak_rss(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    parse_vm_hwm(&status)
+}
+
+/// The `VmHWM:` line of a `/proc/<pid>/status` file, in bytes.
+#[cfg_attr(not(all(feature = "mem", target_os = "linux")), allow(dead_code))]
+fn parse_vm_hwm(status: &str) -> Option<u64> {
+    let line = status.lines().find_map(|l| l.strip_prefix("VmHWM:"))?;
+    let kib: u64 = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Kill `pid` and its descendants, found through the process table.
+fn kill_pid_tree(pid: u32) {
+    use sysinfo::{Pid, System};
+
+    // Collect the descendants before killing the child reparents them
+    let mut sys = System::new();
+    sys.refresh_processes();
+    let mut tree = vec![Pid::from_u32(pid)];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        tree.extend(
+            sys.processes()
+                .iter()
+                .filter(|(_, p)| p.parent() == Some(parent))
+                .map(|(pid, _)| *pid),
+        );
+        i += 1;
+    }
+    for pid in &tree {
+        if let Some(p) = sys.process(*pid) {
+            p.kill();
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, TerminateJobObject,
+    };
+    #[cfg(feature = "mem")]
+    use windows_sys::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::GetProcessTimes;
+
+    /// Job Object holding a child and everything it spawns.
+    ///
+    /// Processes the child starts before it is assigned to the job escape
+    /// it; bb and nargo don't spawn workers that early.
+    pub(super) struct Job(HANDLE);
+
+    impl Job {
+        /// Put `child` in a new job that is killed when its handle closes.
+        /// `None` when the job can't be set up (e.g. the child already
+        /// belongs to a job that forbids nesting).
+        pub(super) fn for_child(child: &Child) -> Option<Job> {
+            unsafe {
+                let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+                if handle.is_null() {
+                    return None;
+                }
+                let job = Job(handle);
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let ok = SetInformationJobObject(
+                    job.0,
+                    JobObjectExtendedLimitInformation,
+                    &info as *const _ as *const std::ffi::c_void,
+                    std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+                ) != 0
+                    && AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) != 0;
+                ok.then_some(job)
+            }
+        }
+
+        /// Kill every process in the job.
+        pub(super) fn terminate(&self) {
+            unsafe {
+                TerminateJobObject(self.0, 1);
+            }
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+
+    /// User and system ms of the exited process behind `handle`.
+    pub(super) fn process_times(handle: usize) -> Option<(f64, f64)> {
+        // FILETIME durations count 100 ns ticks
+        let ms = |t: FILETIME| {
+            ((t.dwHighDateTime as u64) << 32 | t.dwLowDateTime as u64) as f64 / 10_000.0
+        };
+        unsafe {
+            let mut created: FILETIME = std::mem::zeroed();
+            let mut exited: FILETIME = std::mem::zeroed();
+            let mut kernel: FILETIME = std::mem::zeroed();
+            let mut user: FILETIME = std::mem::zeroed();
+            let ok = GetProcessTimes(
+                handle as HANDLE,
+                &mut created,
+                &mut exited,
+                &mut kernel,
+                &mut user,
+            ) != 0;
+            ok.then(|| (ms(user), ms(kernel)))
+        }
+    }
+
+    /// Peak working set in bytes of the process behind `handle`, as tracked
+    /// by the OS.
+    #[cfg(feature = "mem")]
+    pub(super) fn peak_working_set(handle: usize) -> Option<u64> {
+        unsafe {
+            let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+            let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+            counters.cb = size;
+            (K32GetProcessMemoryInfo(handle as HANDLE, &mut counters, size) != 0)
+                .then_some(counters.PeakWorkingSetSize as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exits_ok() -> Command {
+        if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", "exit 0"]);
+            cmd
+        } else {
+            Command::new("true")
+        }
+    }
+
+    #[test]
+    fn test_finished_command_reports_status() {
+        let finished = Supervised::spawn(&mut exits_ok())
+            .unwrap()
+            .wait_timeout(Duration::ZERO)
+            .unwrap()
+            .unwrap();
+        assert!(finished.status.success());
+        assert_eq!(finished.peak_memory_bytes.is_some(), cfg!(feature = "mem"));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[test]
+    fn test_cpu_time_and_exit_code_reported() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg("i=0; while [ $i -lt 200000 ]; do i=$((i+1)); done; exit 3");
+        let finished = Supervised::spawn(&mut cmd)
+            .unwrap()
+            .wait_timeout(Duration::from_secs(60))
+            .unwrap()
+            .unwrap();
+        assert_eq!(finished.status.code(), Some(3));
+        let cpu = finished.cpu_time.unwrap();
+        assert!(cpu.cpu_ms() > 0.0);
+        assert!(cpu.wall_ms >= finished.elapsed_ms as f64);
+    }
+
+    #[test]
+    fn test_timeout_kills_command() {
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", "ping -n 30 127.0.0.1 > NUL"]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sleep");
+            cmd.arg("30");
+            cmd
+        };
+        let start = Instant::now();
+        let outcome = Supervised::spawn(&mut cmd)
+            .unwrap()
+            .wait_timeout(Duration::from_secs(1))
+            .unwrap();
+        assert!(outcome.is_none());
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_sample_interval_backs_off_until_memory_grows() {
+        let mut interval = SampleInterval::new();
+        assert_eq!(interval.get(), MIN_SAMPLE_INTERVAL);
+        for _ in 0..20 {
+            interval.next(false);
+        }
+        assert_eq!(interval.get(), MAX_SAMPLE_INTERVAL);
+        interval.next(true);
+        assert_eq!(interval.get(), MIN_SAMPLE_INTERVAL);
+    }
+
+    #[test]
+    fn test_parse_vm_hwm() {
+        let status = "Name:\tbb\nVmPeak:\t  812345 kB\nVmHWM:\t   20480 kB\nVmRSS:\t   10240 kB\n";
+        assert_eq!(parse_vm_hwm(status), Some(20480 * 1024));
+        assert_eq!(parse_vm_hwm("Name:\tbb\nState:\tZ (zombie)\n"), None);
+    }
+
+    #[cfg(all(feature = "mem", target_os = "linux"))]
+    #[test]
+    fn test_peak_memory_sampled_while_running() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("0.3");
+        let finished = Supervised::spawn(&mut cmd)
+            .unwrap()
+            .wait_timeout(Duration::from_secs(10))
+            .unwrap()
+            .unwrap();
+        assert!(finished.status.success());
+        assert!(finished.peak_memory_bytes.unwrap() > 0);
+        assert!(finished.elapsed_ms >= 300);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_grandchildren() {
+        use sysinfo::{Pid, ProcessStatus, System};
+
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!("sleep 30 & echo $! > {}; wait", pid_file.display()));
+        let outcome = Supervised::spawn(&mut cmd)
+            .unwrap()
+            .wait_timeout(Duration::from_secs(1))
+            .unwrap();
+        assert!(outcome.is_none());
+
+        let pid: u32 = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        let mut sys = System::new();
+        sys.refresh_processes();
+        let alive = sys
+            .process(Pid::from_u32(pid))
+            .is_some_and(|p| p.status() != ProcessStatus::Zombie);
+        assert!(!alive, "grandchild {pid} survived the timeout");
+    }
+}
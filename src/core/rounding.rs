@@ -0,0 +1,115 @@
+//! Rounding policy for floating point metrics.
+//!
+//! Timings and derived values (means, percentiles, deltas) differ in their
+//! last bits between platforms and summation orders. Everything written to
+//! records, the history index and reports is rounded to [`METRIC_DECIMALS`]
+//! places first, so the same input produces byte-identical output anywhere.
+//! Number formatting itself goes through serde_json and `format!`, which do
+//! not depend on the locale.
+
+use serde::Serializer;
+use serde_json::Value;
+
+/// Decimal places kept for floating point metrics.
+pub const METRIC_DECIMALS: i32 = 3;
+
+/// Round `value` to [`METRIC_DECIMALS`] places, half away from zero.
+///
+/// Negative zero becomes zero and non-finite values are returned unchanged.
+pub fn round_metric(value: f64) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    let scale = 10f64.powi(METRIC_DECIMALS);
+    let rounded = (value * scale).round() / scale;
+    if rounded == 0.0 { 0.0 } else { rounded }
+}
+
+/// Round an optional metric in place.
+pub fn round_opt(value: &mut Option<f64>) {
+    if let Some(v) = value {
+        *v = round_metric(*v);
+    }
+}
+
+/// Round every non-integer number in a JSON tree, for records built as
+/// `serde_json::Value`.
+pub fn round_json(value: &mut Value) {
+    match value {
+        Value::Number(n) if n.is_f64() => {
+            if let Some(rounded) = n
+                .as_f64()
+                .map(round_metric)
+                .and_then(serde_json::Number::from_f64)
+            {
+                *n = rounded;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(round_json),
+        Value::Object(map) => map.values_mut().for_each(round_json),
+        _ => {}
+    }
+}
+
+/// `serialize_with` helper for `f64` fields.
+pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(round_metric(*value))
+}
+
+/// `serialize_with` helper for `Option<f64>` fields.
+pub fn serialize_opt<S: Serializer>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => serializer.serialize_some(&round_metric(*v)),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_metric_policy() {
+        assert_eq!(round_metric(100.1234), 100.123);
+        assert_eq!(round_metric(0.0005), 0.001);
+        assert_eq!(round_metric(0.0004), 0.0);
+        assert_eq!(round_metric(-0.0004).to_string(), "0");
+        assert_eq!(round_metric(-1.2345678), -1.235);
+        assert!(round_metric(f64::NAN).is_nan());
+        // Rounding twice changes nothing
+        assert_eq!(
+            round_metric(round_metric(2.0 / 3.0)),
+            round_metric(2.0 / 3.0)
+        );
+    }
+
+    #[test]
+    fn test_round_json_keeps_integers() {
+        let mut v = serde_json::json!({
+            "total_gates": 1048576,
+            "prove_stats": { "mean_ms": 110.123456789, "samples_ms": [1.00049, 2.5] },
+            "name": "x"
+        });
+        round_json(&mut v);
+        assert_eq!(
+            v.to_string(),
+            r#"{"name":"x","prove_stats":{"mean_ms":110.123,"samples_ms":[1.0,2.5]},"total_gates":1048576}"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_helpers() {
+        #[derive(serde::Serialize)]
+        struct S {
+            #[serde(serialize_with = "serialize")]
+            a: f64,
+            #[serde(serialize_with = "serialize_opt")]
+            b: Option<f64>,
+        }
+        let s = S {
+            a: 0.1 + 0.2,
+            b: Some(1.0 / 3.0),
+        };
+        assert_eq!(serde_json::to_string(&s).unwrap(), r#"{"a":0.3,"b":0.333}"#);
+    }
+}
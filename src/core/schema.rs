@@ -1,11 +1,34 @@
-//! BenchRecord schema v1 - canonical schema for all benchmark outputs.
+//! BenchRecord schema - canonical schema for all benchmark outputs.
+//!
+//! Writers always emit [`SCHEMA_VERSION`]. Readers accept every version from
+//! [`MIN_SUPPORTED_SCHEMA_VERSION`] upwards, plus the legacy `bench` JSONL
+//! shape and legacy per-command reports, and upgrade them through explicit per-version migrations
+//! (see [`upgrade_value`]).
+//!
+//! Version history:
+//! - v1: initial canonical record
+//! - v2: adds `params` (circuit parameter variant) and free-form `metadata`;
+//!   optional `fingerprints`, `signature`, `compile_warm_stats` and
+//!   `compile_incremental_stats` were added later without a version bump
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 
 use super::env::EnvironmentInfo;
+use super::legacy::{LegacyReportKind, detect_legacy_report, legacy_report_to_record};
+use super::page_cache::CacheMode;
+use super::process::CpuTime;
+use super::rounding::{round_metric, round_opt};
+use crate::{BenchError, Fingerprints};
 
 /// Schema version for forward compatibility
-pub const SCHEMA_VERSION: u32 = 1;
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Oldest schema version readers can still upgrade
+pub const MIN_SUPPORTED_SCHEMA_VERSION: u32 = 1;
 
 /// Timing statistics for a benchmark phase
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,11 +43,139 @@ pub struct TimingStat {
     pub max_ms: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub p95_ms: Option<f64>,
+    /// Raw per-iteration times, in measurement order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub samples_ms: Vec<f64>,
+    /// Set when invalid samples were left out of the statistics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_quality: Option<DataQuality>,
+    /// How `p95_ms` was computed; absent in records written before the
+    /// method was recorded, which used nearest-rank
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p95_method: Option<PercentileMethod>,
+}
+
+/// Fewest samples for a p95 that means more than "the slowest run".
+pub const MIN_P95_SAMPLES: u32 = 20;
+
+/// How percentiles are computed from samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PercentileMethod {
+    /// Linear interpolation between the closest ranks (Hyndman & Fan
+    /// type 7, the default of R, NumPy and spreadsheets)
+    #[default]
+    Linear,
+    /// The smallest sample with at least p% of samples at or below it
+    NearestRank,
+}
+
+static PERCENTILE_METHOD: OnceLock<PercentileMethod> = OnceLock::new();
+
+impl PercentileMethod {
+    /// Parse a `--percentile-method` value.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "linear" => Some(PercentileMethod::Linear),
+            "nearest-rank" => Some(PercentileMethod::NearestRank),
+            _ => None,
+        }
+    }
+
+    /// Use `method` for every stat computed by this process. Only the first
+    /// call has an effect.
+    pub fn set_default(method: PercentileMethod) {
+        let _ = PERCENTILE_METHOD.set(method);
+    }
+
+    /// The method set with [`PercentileMethod::set_default`], else linear.
+    pub fn current() -> Self {
+        PERCENTILE_METHOD.get().copied().unwrap_or_default()
+    }
+
+    /// The `p`th percentile (0-100) of ascending, non-empty `sorted`.
+    pub fn percentile(self, sorted: &[f64], p: f64) -> f64 {
+        let n = sorted.len();
+        match self {
+            PercentileMethod::Linear => {
+                let h = (n - 1) as f64 * p / 100.0;
+                let lo = h.floor() as usize;
+                let hi = (lo + 1).min(n - 1);
+                sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo])
+            }
+            PercentileMethod::NearestRank => {
+                let rank = (p / 100.0 * n as f64).ceil() as usize;
+                sorted[rank.saturating_sub(1).min(n - 1)]
+            }
+        }
+    }
+}
+
+/// `metadata` key saying how `prove_stats` were measured.
+pub const PROVE_MODE_METADATA_KEY: &str = "prove_mode";
+
+/// Prove mode of `--amortize-setup` runs: the vk is reused across iterations
+/// and estimated bb startup is subtracted from each prove.
+pub const AMORTIZED_SETUP_MODE: &str = "amortized_setup";
+
+/// Prove mode of records that don't state one: raw process timings.
+pub const STANDARD_PROVE_MODE: &str = "standard";
+
+/// Longest plausible sample (about 30 years); anything above is corrupt.
+pub const MAX_SAMPLE_MS: f64 = 1e12;
+
+/// Samples left out of a [`TimingStat`] because they can't be real times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataQuality {
+    /// NaN or infinite samples
+    pub non_finite_samples: u32,
+    /// Samples below zero
+    pub negative_samples: u32,
+    /// Samples above [`MAX_SAMPLE_MS`]
+    pub out_of_range_samples: u32,
+}
+
+impl DataQuality {
+    /// Sort `samples` into valid times and a tally of the rejected ones
+    /// (`None` when every sample is valid).
+    pub fn partition(samples: &[f64]) -> (Vec<f64>, Option<DataQuality>) {
+        let mut quality = DataQuality::default();
+        let mut valid = Vec::with_capacity(samples.len());
+        for &sample in samples {
+            if !sample.is_finite() {
+                quality.non_finite_samples += 1;
+            } else if sample < 0.0 {
+                quality.negative_samples += 1;
+            } else if sample > MAX_SAMPLE_MS {
+                quality.out_of_range_samples += 1;
+            } else {
+                valid.push(sample);
+            }
+        }
+        let clean = valid.len() == samples.len();
+        (valid, (!clean).then_some(quality))
+    }
+
+    /// Number of samples left out.
+    pub fn rejected(&self) -> u32 {
+        self.non_finite_samples + self.negative_samples + self.out_of_range_samples
+    }
 }
 
 impl TimingStat {
-    /// Create TimingStat from a slice of sample times in milliseconds
+    /// Create TimingStat from a slice of sample times in milliseconds, with
+    /// the process's percentile method (see [`PercentileMethod::current`]).
+    ///
+    /// NaN, infinite, negative and implausibly large samples are left out
+    /// and counted in `data_quality`, so the statistics are always finite.
     pub fn from_samples(samples: &[f64]) -> Self {
+        Self::from_samples_with(samples, PercentileMethod::current())
+    }
+
+    /// Same as [`TimingStat::from_samples`] with an explicit percentile method.
+    pub fn from_samples_with(samples: &[f64], method: PercentileMethod) -> Self {
+        let (samples, data_quality) = DataQuality::partition(samples);
+        let samples = samples.as_slice();
         let n = samples.len();
         if n == 0 {
             return TimingStat {
@@ -35,6 +186,9 @@ impl TimingStat {
                 min_ms: 0.0,
                 max_ms: 0.0,
                 p95_ms: None,
+                samples_ms: Vec::new(),
+                data_quality,
+                p95_method: None,
             };
         }
 
@@ -59,11 +213,7 @@ impl TimingStat {
             Some(sorted[n / 2])
         };
 
-        // p95: index = ceil(0.95 * n) - 1, clamped
-        let p95_idx = ((0.95 * n as f64).ceil() as usize)
-            .saturating_sub(1)
-            .min(n - 1);
-        let p95_ms = Some(sorted[p95_idx]);
+        let p95_ms = Some(method.percentile(&sorted, 95.0));
 
         TimingStat {
             iterations,
@@ -73,10 +223,167 @@ impl TimingStat {
             min_ms,
             max_ms,
             p95_ms,
+            samples_ms: samples.to_vec(),
+            data_quality,
+            p95_method: Some(method),
+        }
+    }
+
+    /// Whether `p95_ms` rests on fewer than [`MIN_P95_SAMPLES`] samples and
+    /// says little about the tail.
+    pub fn p95_low_confidence(&self) -> bool {
+        self.p95_ms.is_some() && self.iterations < MIN_P95_SAMPLES
+    }
+
+    /// Round every field to the metric rounding policy (`core::rounding`).
+    pub fn round_metrics(&mut self) {
+        self.mean_ms = round_metric(self.mean_ms);
+        self.min_ms = round_metric(self.min_ms);
+        self.max_ms = round_metric(self.max_ms);
+        round_opt(&mut self.median_ms);
+        round_opt(&mut self.stddev_ms);
+        round_opt(&mut self.p95_ms);
+        for sample in &mut self.samples_ms {
+            *sample = round_metric(*sample);
         }
     }
 }
 
+/// Split of execution time between constrained ACIR simulation,
+/// unconstrained Brillig code and foreign calls.
+///
+/// Foreign call time is measured. The solver doesn't time individual opcodes,
+/// so the remaining time is divided between ACIR and Brillig by their share
+/// of executed opcodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecSplit {
+    pub acir_ms: f64,
+    pub brillig_ms: f64,
+    pub foreign_call_ms: f64,
+    /// ACIR opcodes in the executed program
+    pub acir_opcodes: u64,
+    /// Brillig opcodes executed
+    pub brillig_opcodes: u64,
+    /// Foreign calls (oracles, prints) made by Brillig code
+    pub foreign_calls: u64,
+    /// Foreign calls answered by an external oracle resolver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolver_calls: Option<u64>,
+    /// Time spent in resolver round-trips (part of `foreign_call_ms`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolver_ms: Option<f64>,
+}
+
+impl ExecSplit {
+    /// Split `total_ms` of execution given the measured foreign call time and
+    /// the opcode counts.
+    pub fn estimate(
+        total_ms: f64,
+        foreign_call_ms: f64,
+        acir_opcodes: u64,
+        brillig_opcodes: u64,
+        foreign_calls: u64,
+    ) -> Self {
+        let solver_ms = (total_ms - foreign_call_ms).max(0.0);
+        let opcodes = acir_opcodes + brillig_opcodes;
+        let brillig_ms = if opcodes > 0 {
+            solver_ms * brillig_opcodes as f64 / opcodes as f64
+        } else {
+            0.0
+        };
+        ExecSplit {
+            acir_ms: solver_ms - brillig_ms,
+            brillig_ms,
+            foreign_call_ms,
+            acir_opcodes,
+            brillig_opcodes,
+            foreign_calls,
+            resolver_calls: None,
+            resolver_ms: None,
+        }
+    }
+
+    /// Record the oracle resolver round-trips among the foreign calls.
+    pub fn with_resolver(mut self, calls: u64, ms: f64) -> Self {
+        self.resolver_calls = Some(calls);
+        self.resolver_ms = Some(ms);
+        self
+    }
+
+    /// Round the timings to the metric rounding policy (`core::rounding`).
+    pub fn round_metrics(&mut self) {
+        self.acir_ms = round_metric(self.acir_ms);
+        self.brillig_ms = round_metric(self.brillig_ms);
+        self.foreign_call_ms = round_metric(self.foreign_call_ms);
+        round_opt(&mut self.resolver_ms);
+    }
+
+    /// Fraction of execution time spent in unconstrained code (Brillig and
+    /// foreign calls).
+    pub fn unconstrained_fraction(&self) -> f64 {
+        let total = self.acir_ms + self.brillig_ms + self.foreign_call_ms;
+        if total > 0.0 {
+            (self.brillig_ms + self.foreign_call_ms) / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Mean wall and CPU time of a backend process over the measured runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CpuTimeStat {
+    /// Whole process lifetime, the span the CPU time covers. This includes
+    /// the process startup that `prove_time_ms` leaves out, so it is a few
+    /// ms longer.
+    pub wall_ms: f64,
+    pub user_ms: f64,
+    pub sys_ms: f64,
+    /// User plus system time
+    pub cpu_ms: f64,
+    /// `cpu_ms / (wall_ms * cores)` over the logical cores (hardware threads)
+    /// available to the run: at most 1.0, reached when every one was busy
+    /// for the whole run, and 1/cores for a single-threaded process. `None`
+    /// when the core count is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallelism_efficiency: Option<f64>,
+}
+
+impl CpuTimeStat {
+    /// Mean of `times`, with the parallelism efficiency over `cores`; `None`
+    /// without any samples.
+    pub fn from_times(times: &[CpuTime], cores: Option<u32>) -> Option<Self> {
+        if times.is_empty() {
+            return None;
+        }
+        let n = times.len() as f64;
+        let mean = |f: fn(&CpuTime) -> f64| times.iter().map(f).sum::<f64>() / n;
+        let wall_ms = mean(|t| t.wall_ms);
+        let user_ms = mean(|t| t.user_ms);
+        let sys_ms = mean(|t| t.sys_ms);
+        let cpu_ms = user_ms + sys_ms;
+        let parallelism_efficiency = cores
+            .filter(|&c| c > 0 && wall_ms > 0.0)
+            .map(|c| cpu_ms / (wall_ms * c as f64));
+        Some(CpuTimeStat {
+            wall_ms,
+            user_ms,
+            sys_ms,
+            cpu_ms,
+            parallelism_efficiency,
+        })
+    }
+
+    /// Round the timings to the metric rounding policy (`core::rounding`).
+    pub fn round_metrics(&mut self) {
+        self.wall_ms = round_metric(self.wall_ms);
+        self.user_ms = round_metric(self.user_ms);
+        self.sys_ms = round_metric(self.sys_ms);
+        self.cpu_ms = round_metric(self.cpu_ms);
+        round_opt(&mut self.parallelism_efficiency);
+    }
+}
+
 /// Backend information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackendInfo {
@@ -94,6 +401,14 @@ pub struct RunConfig {
     pub measured_iterations: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_secs: Option<u64>,
+    /// Extra `nargo compile` flags the artifact was built with, e.g.
+    /// `--force-brillig` (empty for a default compile)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compile_flags: Vec<String>,
+    /// Environment variables set for the nargo and bb child processes, from
+    /// `[backend.env]` and the circuit's `env` table in the config
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
 }
 
 impl Default for RunConfig {
@@ -102,6 +417,57 @@ impl Default for RunConfig {
             warmup_iterations: 1,
             measured_iterations: 3,
             timeout_secs: None,
+            compile_flags: Vec::new(),
+            env: BTreeMap::new(),
+        }
+    }
+}
+
+/// Outcome of looking up a run's keys in the on-disk key cache
+/// (`storage::key_cache`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyCacheStatus {
+    /// Keys were loaded from the cache and not generated
+    Hit,
+    /// Keys weren't cached; they were generated and stored
+    Miss,
+    /// The cache was bypassed (`--cold-keys`); keys were regenerated and
+    /// replaced the cached ones
+    Cold,
+}
+
+/// Outcome of a run that isn't a plain success, besides failing (`error`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// The run was meant to fail (`--expect-failure`, or `verify
+    /// --expect-invalid` on a tampered proof) and did; its timings are the
+    /// time it took to detect the failure, and the failure message, if any,
+    /// is in `metadata.failure_message`
+    ExpectedFailure,
+}
+
+/// Why a run failed, recorded on the BenchRecord of a failed run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunError {
+    /// First line of the error message
+    pub message: String,
+    /// Log file with the failing command's full stdout/stderr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_path: Option<String>,
+}
+
+impl RunError {
+    pub fn from_error(err: &crate::BenchError) -> Self {
+        RunError {
+            message: err
+                .to_string()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            log_path: err.log_path().map(|p| p.to_string_lossy().to_string()),
         }
     }
 }
@@ -125,6 +491,10 @@ pub struct BenchRecord {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub circuit_path: Option<String>,
 
+    /// Circuit parameter value for parameterized benchmarks (v2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<u64>,
+
     /// Environment information (CPU, OS, versions, etc.)
     pub env: EnvironmentInfo,
 
@@ -135,10 +505,18 @@ pub struct BenchRecord {
     pub config: RunConfig,
 
     // --- Timing statistics ---
-    /// Compilation/artifact loading timing
+    /// Compilation/artifact loading timing (cold builds when measured by `compile`)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub compile_stats: Option<TimingStat>,
 
+    /// Warm compilation timing (`target/` left in place between runs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_warm_stats: Option<TimingStat>,
+
+    /// Incremental compilation timing (recompile after editing one source file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_incremental_stats: Option<TimingStat>,
+
     /// Witness generation timing
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub witness_stats: Option<TimingStat>,
@@ -147,10 +525,71 @@ pub struct BenchRecord {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub prove_stats: Option<TimingStat>,
 
-    /// Verification timing
+    /// One-time backend setup kept out of `prove_stats` (`--amortize-setup`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_setup_ms: Option<f64>,
+
+    /// Backend warm-up run by the pre-measurement probe (see
+    /// `Backend::probe`), kept out of every stat
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probe_ms: Option<f64>,
+
+    /// vk generation time over the runs (warmup included) that generated a
+    /// vk, kept out of `prove_stats`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_gen_stats: Option<TimingStat>,
+
+    /// Whether runs reused a vk generated by an earlier run instead of
+    /// generating their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_cached: Option<bool>,
+
+    /// Key cache lookup of the first run that needed keys, when a key cache
+    /// was used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_cache: Option<KeyCacheStatus>,
+
+    /// Page cache state the prove runs were measured in; `cold` runs had
+    /// their inputs and SRS evicted first (`core::page_cache`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_mode: Option<CacheMode>,
+
+    /// Mean per-phase proving time in ms parsed from verbose backend logs
+    /// (e.g. `wire_commitments`, `permutation`, `fft`, `opening_proof`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_phases: Option<BTreeMap<String, f64>>,
+
+    /// Wall and CPU (user + sys) time of the backend processes, keyed by
+    /// phase (`prove`, `vk_gen`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_times: Option<BTreeMap<String, CpuTimeStat>>,
+
+    /// Verification timing (steady state when `verify_cold_ms` is set)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub verify_stats: Option<TimingStat>,
 
+    /// First verify iteration, which may read the binary, vk and SRS from
+    /// disk (not guaranteed: an earlier run can leave them in the OS cache)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_cold_ms: Option<f64>,
+
+    /// Gate analysis timing (absent when the gate count came from a cache)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gates_stats: Option<TimingStat>,
+
+    /// Witness generation split between ACIR, Brillig and foreign calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_split: Option<ExecSplit>,
+
+    /// Oracle resolver round-trip time during witness generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oracle_stats: Option<TimingStat>,
+
+    /// Metrics scraped from a template backend's output, keyed by the name
+    /// they were declared with (see `backend::custom_metrics`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_metrics: BTreeMap<String, f64>,
+
     // --- Size metrics ---
     /// Proof size in bytes
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -190,6 +629,47 @@ pub struct BenchRecord {
     /// Command line arguments used
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub cli_args: Vec<String>,
+
+    /// Content hashes of the artifact, inputs, witness, proof and vk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprints: Option<Fingerprints>,
+
+    /// Failure detail when the run failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RunError>,
+
+    /// Outcome other than plain success or `error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<RunStatus>,
+
+    /// Free-form key/value metadata (v2)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+
+    /// Set when history ingestion found the run to be an outlier of its
+    /// circuit/backend (see `core::anomaly`); covered by the signature
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub anomaly: bool,
+
+    /// Ed25519 signature over the rest of the record (see `core::signing`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<RecordSignature>,
+
+    /// Bytes the signature covers, as stored in the JSONL line the record
+    /// was read from. Signed records are verified against and written back
+    /// as these bytes, so changing a field of one after reading it needs a
+    /// new `signing::sign_record`.
+    #[serde(skip)]
+    pub signed_payload: Option<Vec<u8>>,
+}
+
+/// Detached Ed25519 signature carried on a record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordSignature {
+    /// Hex-encoded 32-byte Ed25519 public key of the signer
+    pub public_key: String,
+    /// Hex-encoded 64-byte signature over the record's canonical JSON
+    pub signature: String,
 }
 
 impl BenchRecord {
@@ -220,13 +700,29 @@ impl BenchRecord {
             timestamp,
             circuit_name,
             circuit_path: None,
+            params: None,
             env,
             backend,
             config,
             compile_stats: None,
+            compile_warm_stats: None,
+            compile_incremental_stats: None,
             witness_stats: None,
             prove_stats: None,
+            backend_setup_ms: None,
+            probe_ms: None,
+            vk_gen_stats: None,
+            vk_cached: None,
+            key_cache: None,
+            cache_mode: None,
+            backend_phases: None,
+            cpu_times: None,
             verify_stats: None,
+            verify_cold_ms: None,
+            gates_stats: None,
+            exec_split: None,
+            oracle_stats: None,
+            custom_metrics: BTreeMap::new(),
             proof_size_bytes: None,
             proving_key_size_bytes: None,
             verification_key_size_bytes: None,
@@ -236,14 +732,299 @@ impl BenchRecord {
             subgroup_size: None,
             peak_rss_mb: None,
             cli_args: Vec::new(),
+            fingerprints: None,
+            error: None,
+            status: None,
+            metadata: BTreeMap::new(),
+            anomaly: false,
+            signature: None,
+            signed_payload: None,
         }
     }
+
+    /// Whether the run was meant to fail and did. Its timings measure time
+    /// to failure, so they are never compared with those of passing runs.
+    pub fn is_expected_failure(&self) -> bool {
+        self.status == Some(RunStatus::ExpectedFailure)
+    }
+
+    /// Round every floating point metric to the metric rounding policy
+    /// (`core::rounding`), so the same measurements serialize identically
+    /// on every platform. Writers call this before signing.
+    pub fn round_metrics(&mut self) {
+        for stats in [
+            &mut self.compile_stats,
+            &mut self.compile_warm_stats,
+            &mut self.compile_incremental_stats,
+            &mut self.witness_stats,
+            &mut self.prove_stats,
+            &mut self.verify_stats,
+            &mut self.gates_stats,
+            &mut self.oracle_stats,
+            &mut self.vk_gen_stats,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            stats.round_metrics();
+        }
+        round_opt(&mut self.backend_setup_ms);
+        round_opt(&mut self.probe_ms);
+        round_opt(&mut self.verify_cold_ms);
+        round_opt(&mut self.peak_rss_mb);
+        for ms in self.backend_phases.iter_mut().flat_map(|p| p.values_mut()) {
+            *ms = round_metric(*ms);
+        }
+        for value in self.custom_metrics.values_mut() {
+            *value = round_metric(*value);
+        }
+        if let Some(split) = &mut self.exec_split {
+            split.round_metrics();
+        }
+        for stat in self.cpu_times.iter_mut().flat_map(|t| t.values_mut()) {
+            stat.round_metrics();
+        }
+    }
+}
+
+/// Shape of a stored record before migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordShape {
+    /// Legacy `bench run`/`bench run-all` JSONL line (no schema_version)
+    LegacyBench,
+    /// Legacy per-command report (`ExecReport`, `ProveReport`, ...)
+    LegacyReport(LegacyReportKind),
+    /// Versioned BenchRecord
+    Versioned(u32),
+}
+
+/// Detect the shape of a raw JSON record.
+pub fn detect_shape(value: &Value) -> Option<RecordShape> {
+    if let Some(v) = value.get("schema_version").and_then(Value::as_u64) {
+        return Some(RecordShape::Versioned(v as u32));
+    }
+    if value.get("circuit").is_some() && value.get("circuit_name").is_none() {
+        return Some(RecordShape::LegacyBench);
+    }
+    detect_legacy_report(value).map(RecordShape::LegacyReport)
+}
+
+/// A migration from version `N` to `N + 1`, operating on raw JSON.
+type Migration = fn(Value) -> Result<Value, BenchError>;
+
+/// Migrations indexed by source version: `MIGRATIONS[i]` upgrades
+/// `MIN_SUPPORTED_SCHEMA_VERSION + i` to the next version.
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// v1 -> v2: `params` and `metadata` are new optional fields.
+fn migrate_v1_to_v2(mut value: Value) -> Result<Value, BenchError> {
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| BenchError::Message("record is not a JSON object".into()))?;
+    obj.insert("schema_version".to_string(), json!(2));
+    Ok(value)
+}
+
+/// Convert a legacy `bench` JSONL line into a current-version record.
+///
+/// The legacy shape carries a single averaged prove time plus an
+/// `iterations` summary; these map onto `prove_stats`. `status` and the
+/// `evm_gas` value (when present) are kept in `metadata`.
+fn migrate_legacy_bench(value: Value) -> Result<Value, BenchError> {
+    let circuit = value
+        .get("circuit")
+        .and_then(Value::as_str)
+        .ok_or_else(|| BenchError::Message("legacy record missing 'circuit'".into()))?;
+    let timestamp = value
+        .get("timestamp")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let backend = value
+        .get("backend")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let num = |key: &str| value.get(key).and_then(Value::as_f64);
+    let int = |key: &str| value.get(key).and_then(Value::as_u64);
+
+    let iters = value.get("iterations");
+    let iter_num = |key: &str| iters.and_then(|i| i.get(key)).and_then(Value::as_f64);
+    let measured = iter_num("iterations").map(|v| v as u32).unwrap_or(1);
+    let warmup = iter_num("warmup").map(|v| v as u32).unwrap_or(0);
+
+    // Deterministic id so repeated upgrades of the same archive are stable
+    let record_id = format!(
+        "legacy-{}",
+        &crate::sha256_hex(value.to_string().as_bytes())[..16]
+    );
+
+    let mut record = json!({
+        "schema_version": SCHEMA_VERSION,
+        "record_id": record_id,
+        "timestamp": timestamp,
+        "circuit_name": circuit,
+        "env": { "os": "unknown" },
+        "backend": { "name": backend },
+        "config": {
+            "warmup_iterations": warmup,
+            "measured_iterations": measured
+        }
+    });
+    let obj = record.as_object_mut().expect("record literal is an object");
+
+    if let Some(params) = int("params") {
+        obj.insert("params".to_string(), json!(params));
+    }
+    if let Some(compile_ms) = num("compile_ms") {
+        obj.insert(
+            "compile_stats".to_string(),
+            serde_json::to_value(TimingStat::from_samples(&[compile_ms]))
+                .map_err(|e| BenchError::Message(format!("failed to convert record: {e}")))?,
+        );
+    }
+    if let Some(prove_ms) = iter_num("avg_ms").or_else(|| num("prove_ms")) {
+        let mut stats = json!({
+            "iterations": measured,
+            "mean_ms": prove_ms,
+            "min_ms": iter_num("min_ms").unwrap_or(prove_ms),
+            "max_ms": iter_num("max_ms").unwrap_or(prove_ms)
+        });
+        if let Some(stddev) = iter_num("stddev_ms") {
+            stats["stddev_ms"] = json!(stddev);
+        }
+        obj.insert("prove_stats".to_string(), stats);
+    }
+    for (legacy, current) in [
+        ("constraints", "total_gates"),
+        ("acir_opcodes", "acir_opcodes"),
+        ("acir_bytes", "artifact_size_bytes"),
+        ("proof_size", "proof_size_bytes"),
+    ] {
+        if let Some(v) = int(legacy) {
+            obj.insert(current.to_string(), json!(v));
+        }
+    }
+    if let Some(bytes) = int("memory_bytes") {
+        obj.insert(
+            "peak_rss_mb".to_string(),
+            json!(bytes as f64 / (1024.0 * 1024.0)),
+        );
+    }
+
+    let mut metadata = serde_json::Map::new();
+    metadata.insert("source".to_string(), json!("legacy-bench"));
+    if let Some(status) = value.get("status").and_then(Value::as_bool) {
+        metadata.insert("status".to_string(), json!(status.to_string()));
+    }
+    if let Some(gas) = int("evm_gas") {
+        metadata.insert("evm_gas".to_string(), json!(gas.to_string()));
+    }
+    obj.insert("metadata".to_string(), Value::Object(metadata));
+
+    Ok(record)
+}
+
+/// Upgrade a raw JSON record of any supported shape to [`SCHEMA_VERSION`].
+///
+/// # Errors
+/// Returns an error for unrecognized shapes, versions older than
+/// [`MIN_SUPPORTED_SCHEMA_VERSION`], or versions newer than this build.
+pub fn upgrade_value(value: Value) -> Result<Value, BenchError> {
+    let mut version = match detect_shape(&value) {
+        Some(RecordShape::LegacyBench) => return migrate_legacy_bench(value),
+        Some(RecordShape::LegacyReport(_)) => {
+            let record = legacy_report_to_record(&value)?;
+            return serde_json::to_value(record)
+                .map_err(|e| BenchError::Message(format!("failed to convert record: {e}")));
+        }
+        Some(RecordShape::Versioned(v)) => v,
+        None => {
+            return Err(BenchError::Message(
+                "unrecognized record shape (no schema_version)".into(),
+            ));
+        }
+    };
+
+    if version > SCHEMA_VERSION {
+        return Err(BenchError::Message(format!(
+            "record has schema v{version}, newer than supported v{SCHEMA_VERSION}"
+        )));
+    }
+    if version < MIN_SUPPORTED_SCHEMA_VERSION {
+        return Err(BenchError::Message(format!(
+            "record has schema v{version}, older than supported v{MIN_SUPPORTED_SCHEMA_VERSION}"
+        )));
+    }
+
+    let mut value = value;
+    while version < SCHEMA_VERSION {
+        let migration = MIGRATIONS[(version - MIN_SUPPORTED_SCHEMA_VERSION) as usize];
+        value = migration(value)?;
+        version += 1;
+    }
+    Ok(value)
+}
+
+/// Parse a single stored JSON record, upgrading it to the current schema.
+pub fn parse_record(line: &str) -> Result<BenchRecord, BenchError> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| BenchError::Message(format!("invalid JSON: {e}")))?;
+    // The signature covers the line as stored, before any upgrade
+    let signed_payload = match value.get("signature") {
+        Some(_) => Some(super::signing::payload_bytes(value.clone())?),
+        None => None,
+    };
+    let upgraded = upgrade_value(value)?;
+    let mut record: BenchRecord = serde_json::from_value(upgraded)
+        .map_err(|e| BenchError::Message(format!("invalid record: {e}")))?;
+    record.signed_payload = signed_payload;
+    Ok(record)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_exec_split_estimate() {
+        let split = ExecSplit::estimate(100.0, 20.0, 30, 90, 4);
+        assert_eq!(split.foreign_call_ms, 20.0);
+        assert_eq!(split.brillig_ms, 60.0);
+        assert_eq!(split.acir_ms, 20.0);
+        assert!((split.unconstrained_fraction() - 0.8).abs() < 1e-9);
+
+        // Foreign calls never push the solver time negative
+        let split = ExecSplit::estimate(5.0, 6.0, 0, 0, 1);
+        assert_eq!(split.acir_ms, 0.0);
+        assert_eq!(split.brillig_ms, 0.0);
+    }
+
+    #[test]
+    fn test_cpu_time_stat_from_times() {
+        let times = [
+            CpuTime {
+                wall_ms: 100.0,
+                user_ms: 300.0,
+                sys_ms: 20.0,
+            },
+            CpuTime {
+                wall_ms: 300.0,
+                user_ms: 500.0,
+                sys_ms: 40.0,
+            },
+        ];
+        let stat = CpuTimeStat::from_times(&times, Some(4)).unwrap();
+        assert_eq!(stat.wall_ms, 200.0);
+        assert_eq!(stat.cpu_ms, 430.0);
+        assert_eq!(stat.parallelism_efficiency, Some(430.0 / 800.0));
+        assert_eq!(
+            CpuTimeStat::from_times(&times, None)
+                .unwrap()
+                .parallelism_efficiency,
+            None
+        );
+        assert!(CpuTimeStat::from_times(&[], Some(4)).is_none());
+    }
+
     #[test]
     fn test_timing_stat_from_samples() {
         let samples = vec![100.0, 110.0, 105.0, 115.0, 120.0];
@@ -261,8 +1042,11 @@ mod tests {
         // = sqrt((100 + 0 + 25 + 25 + 100) / 5) = sqrt(50) = 7.071...
         assert!((stat.stddev_ms.unwrap() - 7.071).abs() < 0.01);
 
-        // p95 with 5 samples: index = ceil(0.95 * 5) - 1 = ceil(4.75) - 1 = 5 - 1 = 4 -> 120
-        assert_eq!(stat.p95_ms, Some(120.0));
+        // Linear p95 with 5 samples: rank (5 - 1) * 0.95 = 3.8, between 115 and 120
+        assert!((stat.p95_ms.unwrap() - 119.0).abs() < 1e-9);
+        assert_eq!(stat.p95_method, Some(PercentileMethod::Linear));
+        assert!(stat.p95_low_confidence());
+        assert_eq!(stat.samples_ms, samples);
     }
 
     #[test]
@@ -277,6 +1061,53 @@ mod tests {
         assert!(stat.median_ms.is_none());
     }
 
+    #[test]
+    fn test_percentile_methods() {
+        let sorted: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert!((PercentileMethod::Linear.percentile(&sorted, 95.0) - 19.05).abs() < 1e-9);
+        assert_eq!(
+            PercentileMethod::NearestRank.percentile(&sorted, 95.0),
+            19.0
+        );
+        assert_eq!(PercentileMethod::Linear.percentile(&sorted, 50.0), 10.5);
+        assert_eq!(PercentileMethod::Linear.percentile(&[7.0], 95.0), 7.0);
+        assert_eq!(PercentileMethod::NearestRank.percentile(&[7.0], 95.0), 7.0);
+
+        let stat = TimingStat::from_samples_with(&sorted, PercentileMethod::NearestRank);
+        assert_eq!(stat.p95_ms, Some(19.0));
+        assert!(!stat.p95_low_confidence());
+        assert_eq!(
+            PercentileMethod::parse("nearest-rank"),
+            Some(PercentileMethod::NearestRank)
+        );
+        assert_eq!(PercentileMethod::parse("R-7"), None);
+    }
+
+    #[test]
+    fn test_timing_stat_drops_invalid_samples() {
+        let samples = vec![10.0, f64::NAN, 20.0, -5.0, f64::INFINITY, 1e300];
+        let stat = TimingStat::from_samples(&samples);
+
+        assert_eq!(stat.iterations, 2);
+        assert_eq!(stat.mean_ms, 15.0);
+        assert_eq!(stat.max_ms, 20.0);
+        assert_eq!(stat.samples_ms, vec![10.0, 20.0]);
+        let quality = stat.data_quality.unwrap();
+        assert_eq!(quality.non_finite_samples, 2);
+        assert_eq!(quality.negative_samples, 1);
+        assert_eq!(quality.out_of_range_samples, 1);
+        assert_eq!(quality.rejected(), 4);
+
+        // Nothing valid: an empty stat that still records why
+        let stat = TimingStat::from_samples(&[f64::NAN]);
+        assert_eq!(stat.iterations, 0);
+        assert_eq!(stat.mean_ms, 0.0);
+        assert_eq!(stat.data_quality.unwrap().non_finite_samples, 1);
+
+        // Clean samples carry no flag
+        assert!(TimingStat::from_samples(&[1.0]).data_quality.is_none());
+    }
+
     #[test]
     fn test_timing_stat_single_sample() {
         let samples = vec![42.0];
@@ -289,4 +1120,134 @@ mod tests {
         assert_eq!(stat.median_ms, Some(42.0));
         assert_eq!(stat.stddev_ms, Some(0.0));
     }
+
+    #[test]
+    fn test_round_metrics_serializes_deterministically() {
+        let mut record = BenchRecord::new(
+            "c".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        record.prove_stats = Some(TimingStat::from_samples(&[
+            100.00049,
+            110.1234567,
+            0.1 + 0.2,
+        ]));
+        record.peak_rss_mb = Some(512.00000001);
+        record.backend_phases = Some(BTreeMap::from([("fft".to_string(), 1.0 / 3.0)]));
+        record
+            .custom_metrics
+            .insert("custom_sumcheck_ms".to_string(), 2.0 / 3.0);
+        record.round_metrics();
+
+        let stats = record.prove_stats.as_ref().unwrap();
+        assert_eq!(stats.samples_ms, [100.0, 110.123, 0.3]);
+        assert_eq!(stats.mean_ms, 70.141);
+        assert_eq!(record.peak_rss_mb, Some(512.0));
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(r#""fft":0.333"#));
+        assert_eq!(record.custom_metrics["custom_sumcheck_ms"], 0.667);
+
+        // Idempotent, so re-rounding a stored record doesn't change it
+        let mut again = record.clone();
+        again.round_metrics();
+        assert_eq!(serde_json::to_string(&again).unwrap(), json);
+    }
+
+    #[test]
+    fn test_migrations_cover_every_supported_version() {
+        assert_eq!(
+            MIGRATIONS.len() as u32,
+            SCHEMA_VERSION - MIN_SUPPORTED_SCHEMA_VERSION
+        );
+    }
+
+    #[test]
+    fn test_parse_record_upgrades_v1() {
+        let line = r#"{"schema_version":1,"record_id":"r1","timestamp":"2026-01-01T00:00:00Z","circuit_name":"c","env":{"os":"linux"},"backend":{"name":"bb"},"config":{"warmup_iterations":1,"measured_iterations":3},"total_gates":100}"#;
+        let record = parse_record(line).unwrap();
+        assert_eq!(record.schema_version, SCHEMA_VERSION);
+        assert_eq!(record.record_id, "r1");
+        assert_eq!(record.total_gates, Some(100));
+        assert!(record.params.is_none());
+        assert!(record.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_parse_record_current_version_roundtrip() {
+        let mut record = BenchRecord::new(
+            "c".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        record.params = Some(16);
+        record
+            .metadata
+            .insert("commit".to_string(), "abc".to_string());
+        record.fingerprints = Some(Fingerprints {
+            proof_hash: Some("p".to_string()),
+            vk_hash: Some("k".to_string()),
+            ..Default::default()
+        });
+        let line = serde_json::to_string(&record).unwrap();
+        let parsed = parse_record(&line).unwrap();
+        assert_eq!(parsed.params, Some(16));
+        assert_eq!(
+            parsed.metadata.get("commit").map(String::as_str),
+            Some("abc")
+        );
+        assert_eq!(parsed.fingerprints, record.fingerprints);
+    }
+
+    #[test]
+    fn test_parse_record_rejects_unknown_versions() {
+        let future = format!(
+            r#"{{"schema_version":{},"record_id":"r","timestamp":"t","circuit_name":"c","env":{{"os":"x"}},"backend":{{"name":"bb"}},"config":{{"warmup_iterations":0,"measured_iterations":1}}}}"#,
+            SCHEMA_VERSION + 1
+        );
+        assert!(parse_record(&future).is_err());
+        assert!(parse_record(r#"{"schema_version":0}"#).is_err());
+        assert!(parse_record(r#"{"foo":1}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_record_converts_legacy_bench_line() {
+        let line = r#"{"timestamp":"2026-01-01T00:00:00Z","circuit":"merkle","params":16,"backend":"barretenberg","compile_ms":12.0,"constraints":5000,"acir_opcodes":40,"acir_bytes":2048,"prove_ms":150.0,"memory_bytes":1048576,"proof_size":4096,"evm_gas":null,"status":true,"iterations":{"iterations":3,"warmup":1,"avg_ms":150.0,"min_ms":140.0,"max_ms":160.0,"stddev_ms":8.0}}"#;
+        let record = parse_record(line).unwrap();
+        assert_eq!(record.schema_version, SCHEMA_VERSION);
+        assert_eq!(record.circuit_name, "merkle");
+        assert_eq!(record.params, Some(16));
+        assert_eq!(record.backend.name, "barretenberg");
+        assert_eq!(record.total_gates, Some(5000));
+        assert_eq!(record.artifact_size_bytes, Some(2048));
+        assert_eq!(record.proof_size_bytes, Some(4096));
+        assert_eq!(record.peak_rss_mb, Some(1.0));
+        assert_eq!(record.config.measured_iterations, 3);
+        assert_eq!(record.config.warmup_iterations, 1);
+        let prove = record.prove_stats.unwrap();
+        assert_eq!(prove.mean_ms, 150.0);
+        assert_eq!(prove.min_ms, 140.0);
+        assert_eq!(prove.stddev_ms, Some(8.0));
+        assert_eq!(
+            record.metadata.get("source").map(String::as_str),
+            Some("legacy-bench")
+        );
+        assert_eq!(
+            record.metadata.get("status").map(String::as_str),
+            Some("true")
+        );
+
+        // Stable id across repeated conversions
+        assert_eq!(parse_record(line).unwrap().record_id, record.record_id);
+    }
 }
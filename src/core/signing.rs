@@ -0,0 +1,346 @@
+//! Ed25519 signing of BenchRecords.
+//!
+//! A record is signed over its canonical JSON: the record serialized with
+//! `signature` unset, object keys sorted, no whitespace. That includes the
+//! `anomaly` flag, so whoever flags a signed record at history ingestion
+//! signs it again. Signed records are stored as
+//! exactly those bytes plus the `signature` key, and readers verify the
+//! stored bytes (see `BenchRecord::signed_payload`) rather than the record
+//! serialized again, so schema changes and float formatting can't break a
+//! signature. The signer's public key travels with the signature so readers
+//! can check it against a list of trusted keys without any other
+//! configuration.
+//!
+//! Key files hold the 32-byte Ed25519 secret seed as hex (e.g.
+//! `openssl rand -hex 32 > bench.key`). Trusted-key files list one hex public
+//! key per line; blank lines and `#` comments are ignored.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use super::schema::{BenchRecord, RecordSignature};
+use crate::{BenchError, BenchResult};
+
+/// Load an Ed25519 signing key from a hex seed file.
+pub fn load_signing_key(path: &Path) -> BenchResult<SigningKey> {
+    let s = std::fs::read_to_string(path).map_err(|e| {
+        BenchError::Message(format!(
+            "failed to read signing key {}: {e}",
+            path.display()
+        ))
+    })?;
+    let seed: [u8; 32] = decode_fixed(s.trim())
+        .map_err(|e| BenchError::Message(format!("invalid signing key {}: {e}", path.display())))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Hex-encoded public key for a signing key.
+pub fn public_key_hex(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().to_bytes())
+}
+
+fn serialize_error(e: serde_json::Error) -> BenchError {
+    BenchError::Message(format!("failed to serialize record: {e}"))
+}
+
+/// Canonical bytes of a stored record's JSON with its signature removed.
+///
+/// serde_json::Value keeps object keys sorted, and parsing canonical bytes
+/// and serializing them again gives the same bytes, so this recovers exactly
+/// what was signed from a stored line.
+pub fn payload_bytes(mut value: serde_json::Value) -> BenchResult<Vec<u8>> {
+    if let Some(object) = value.as_object_mut() {
+        object.remove("signature");
+    }
+    serde_json::to_vec(&value).map_err(serialize_error)
+}
+
+/// Canonical bytes a record's signature covers: the stored bytes for a
+/// record read from JSONL, else the record serialized canonically.
+pub fn canonical_bytes(record: &BenchRecord) -> BenchResult<Vec<u8>> {
+    if let Some(payload) = &record.signed_payload {
+        return Ok(payload.clone());
+    }
+    payload_bytes(serde_json::to_value(record).map_err(serialize_error)?)
+}
+
+/// Sign `record` in place, replacing any existing signature.
+pub fn sign_record(record: &mut BenchRecord, key: &SigningKey) -> BenchResult<()> {
+    record.signed_payload = None;
+    let bytes = canonical_bytes(record)?;
+    let signature = key.sign(&bytes);
+    record.signature = Some(RecordSignature {
+        public_key: public_key_hex(key),
+        signature: hex::encode(signature.to_bytes()),
+    });
+    Ok(())
+}
+
+/// JSONL line of a signed record: its canonical bytes with the signature
+/// added, so the line holds exactly what was signed.
+pub fn signed_line(record: &BenchRecord) -> BenchResult<String> {
+    let mut value: serde_json::Value =
+        serde_json::from_slice(&canonical_bytes(record)?).map_err(serialize_error)?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "signature".to_string(),
+            serde_json::to_value(&record.signature).map_err(serialize_error)?,
+        );
+    }
+    serde_json::to_string(&value).map_err(serialize_error)
+}
+
+/// Check a record's signature.
+///
+/// Returns `Ok(None)` for unsigned records, `Ok(Some(public_key))` when the
+/// signature is valid, and an error when it is malformed or doesn't match.
+pub fn verify_signature(record: &BenchRecord) -> BenchResult<Option<String>> {
+    let Some(sig) = &record.signature else {
+        return Ok(None);
+    };
+    let invalid = |reason: String| {
+        BenchError::Message(format!(
+            "record '{}' has an invalid signature: {reason}",
+            record.record_id
+        ))
+    };
+
+    let key_bytes: [u8; 32] = decode_fixed(&sig.public_key).map_err(&invalid)?;
+    let sig_bytes: [u8; 64] = decode_fixed(&sig.signature).map_err(&invalid)?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| invalid(e.to_string()))?;
+    let bytes = canonical_bytes(record)?;
+    key.verify(&bytes, &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| invalid("signature does not match record contents".to_string()))?;
+    Ok(Some(sig.public_key.clone()))
+}
+
+/// Which records to accept when ingesting JSONL from other machines.
+///
+/// Records with a signature that doesn't verify are always rejected. With
+/// trusted keys configured, signed records must come from one of them; with
+/// `require_signed`, unsigned records are rejected too.
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicy {
+    pub trusted_keys: BTreeSet<String>,
+    pub require_signed: bool,
+}
+
+impl SignaturePolicy {
+    /// Build a policy from CLI options.
+    pub fn from_args(trusted_keys: Option<&Path>, require_signed: bool) -> BenchResult<Self> {
+        let trusted_keys = match trusted_keys {
+            Some(path) => load_trusted_keys(path)?,
+            None => BTreeSet::new(),
+        };
+        if require_signed && trusted_keys.is_empty() {
+            return Err(BenchError::Message(
+                "--require-signed needs --trusted-keys".into(),
+            ));
+        }
+        Ok(SignaturePolicy {
+            trusted_keys,
+            require_signed,
+        })
+    }
+
+    /// Check a single record against the policy.
+    pub fn check(&self, record: &BenchRecord) -> BenchResult<()> {
+        match verify_signature(record)?.map(|k| k.to_ascii_lowercase()) {
+            Some(key) if !self.trusted_keys.is_empty() && !self.trusted_keys.contains(&key) => {
+                Err(BenchError::Message(format!(
+                    "record '{}' is signed by untrusted key {key}",
+                    record.record_id
+                )))
+            }
+            None if self.require_signed => Err(BenchError::Message(format!(
+                "record '{}' is not signed",
+                record.record_id
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check every record, failing on the first rejected one.
+    pub fn check_all(&self, records: &[BenchRecord]) -> BenchResult<()> {
+        records.iter().try_for_each(|r| self.check(r))
+    }
+}
+
+/// Load trusted public keys (hex, one per line).
+pub fn load_trusted_keys(path: &Path) -> BenchResult<BTreeSet<String>> {
+    let s = std::fs::read_to_string(path).map_err(|e| {
+        BenchError::Message(format!(
+            "failed to read trusted keys {}: {e}",
+            path.display()
+        ))
+    })?;
+    let mut keys = BTreeSet::new();
+    for (line_num, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let _: [u8; 32] = decode_fixed(line).map_err(|e| {
+            BenchError::Message(format!(
+                "invalid public key on line {} of {}: {e}",
+                line_num + 1,
+                path.display()
+            ))
+        })?;
+        keys.insert(line.to_ascii_lowercase());
+    }
+    Ok(keys)
+}
+
+fn decode_fixed<const N: usize>(s: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| format!("expected {N} bytes, got {}", b.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig};
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn record() -> BenchRecord {
+        let mut r = BenchRecord::new(
+            "c".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.total_gates = Some(100);
+        r
+    }
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let mut r = record();
+        sign_record(&mut r, &key(1)).unwrap();
+
+        // Survives a JSONL roundtrip
+        let line = signed_line(&r).unwrap();
+        let parsed = crate::core::parse_record(&line).unwrap();
+        assert_eq!(
+            verify_signature(&parsed).unwrap(),
+            Some(public_key_hex(&key(1)))
+        );
+        assert_eq!(signed_line(&parsed).unwrap(), line);
+    }
+
+    #[test]
+    fn test_signature_covers_stored_bytes() {
+        let mut r = record();
+        r.peak_rss_mb = Some(0.1 + 0.2);
+        sign_record(&mut r, &key(1)).unwrap();
+        let line = signed_line(&r).unwrap();
+
+        // Keys in another order still verify: the stored bytes are what was
+        // signed, not the record serialized again
+        let mut value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        let object = value.as_object_mut().unwrap();
+        let entries: Vec<String> = object
+            .iter()
+            .rev()
+            .map(|(k, v)| format!("{}: {v}", serde_json::Value::from(k.as_str())))
+            .collect();
+        let reordered = format!("{{ {} }}", entries.join(", "));
+        let parsed = crate::core::parse_record(&reordered).unwrap();
+        assert!(verify_signature(&parsed).is_ok());
+
+        object.insert("total_gates".to_string(), serde_json::json!(99));
+        let tampered = crate::core::parse_record(&value.to_string()).unwrap();
+        assert!(verify_signature(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_anomaly_flag_is_signed() {
+        let mut r = record();
+        sign_record(&mut r, &key(1)).unwrap();
+        r.anomaly = true;
+        assert!(verify_signature(&r).is_err());
+
+        // Flagged then signed again: the flag survives a round trip
+        sign_record(&mut r, &key(1)).unwrap();
+        let stored = crate::core::parse_record(&signed_line(&r).unwrap()).unwrap();
+        assert!(stored.anomaly);
+        assert_eq!(
+            verify_signature(&stored).unwrap(),
+            Some(public_key_hex(&key(1)))
+        );
+        let mut value: serde_json::Value = serde_json::from_str(&signed_line(&r).unwrap()).unwrap();
+        value.as_object_mut().unwrap().remove("anomaly");
+        let unflagged = crate::core::parse_record(&value.to_string()).unwrap();
+        assert!(verify_signature(&unflagged).is_err());
+    }
+
+    #[test]
+    fn test_tampered_record_fails_verification() {
+        let mut r = record();
+        sign_record(&mut r, &key(1)).unwrap();
+        r.total_gates = Some(99);
+
+        let err = verify_signature(&r).unwrap_err().to_string();
+        assert!(err.contains("does not match"), "{err}");
+        assert!(SignaturePolicy::default().check(&r).is_err());
+    }
+
+    #[test]
+    fn test_policy_trusted_keys_and_require_signed() {
+        let mut trusted = record();
+        sign_record(&mut trusted, &key(1)).unwrap();
+        let mut untrusted = record();
+        sign_record(&mut untrusted, &key(2)).unwrap();
+        let unsigned = record();
+
+        let open = SignaturePolicy {
+            trusted_keys: BTreeSet::from([public_key_hex(&key(1))]),
+            require_signed: false,
+        };
+        assert!(open.check(&trusted).is_ok());
+        assert!(open.check(&unsigned).is_ok());
+        assert!(open.check(&untrusted).is_err());
+
+        let strict = SignaturePolicy {
+            require_signed: true,
+            ..open
+        };
+        assert!(strict.check(&unsigned).is_err());
+        assert!(strict.check_all(&[trusted]).is_ok());
+    }
+
+    #[test]
+    fn test_load_keys_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("bench.key");
+        std::fs::write(&key_path, format!("{}\n", hex::encode([7u8; 32]))).unwrap();
+        let loaded = load_signing_key(&key_path).unwrap();
+        assert_eq!(public_key_hex(&loaded), public_key_hex(&key(7)));
+
+        let trusted_path = dir.path().join("trusted.txt");
+        std::fs::write(
+            &trusted_path,
+            format!("# fleet runners\n{}\n\n", public_key_hex(&key(7))),
+        )
+        .unwrap();
+        let policy = SignaturePolicy::from_args(Some(&trusted_path), true).unwrap();
+        assert!(policy.trusted_keys.contains(&public_key_hex(&key(7))));
+
+        std::fs::write(&key_path, "not-hex").unwrap();
+        assert!(load_signing_key(&key_path).is_err());
+        assert!(SignaturePolicy::from_args(None, true).is_err());
+    }
+}
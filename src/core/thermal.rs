@@ -0,0 +1,131 @@
+//! Thermal pressure sampling during measurement (macOS).
+//!
+//! Laptops, Apple silicon ones in particular, throttle under sustained load,
+//! which shows up as slow runs that look like regressions. A sampler polls
+//! the system thermal pressure level while a benchmark runs and keeps the
+//! highest level seen, which goes into the record's environment block.
+//! Elsewhere the sampler records nothing.
+
+/// Thermal pressure levels, from least to most throttled.
+pub const THERMAL_LEVELS: &[&str] = &["nominal", "moderate", "heavy", "trapping", "sleeping"];
+
+/// Whether a recorded level means the machine was throttling.
+pub fn is_elevated(level: &str) -> bool {
+    level != "nominal"
+}
+
+/// Highest thermal pressure level seen between `start` and `finish`.
+pub struct ThermalSampler {
+    #[cfg(target_os = "macos")]
+    inner: Option<macos::Sampler>,
+}
+
+impl ThermalSampler {
+    /// Start sampling in the background.
+    pub fn start() -> Self {
+        ThermalSampler {
+            #[cfg(target_os = "macos")]
+            inner: macos::Sampler::start(),
+        }
+    }
+
+    /// Stop sampling and return the highest level seen, if the platform
+    /// reports one.
+    pub fn finish(self) -> Option<String> {
+        #[cfg(target_os = "macos")]
+        {
+            self.inner
+                .and_then(|s| s.finish())
+                .and_then(|level| THERMAL_LEVELS.get(level as usize))
+                .map(|level| level.to_string())
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::ffi::{CStr, c_char, c_int};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    const PRESSURE_LEVEL_NAME: &CStr = c"com.apple.system.thermalpressurelevel";
+    const INTERVAL: Duration = Duration::from_millis(250);
+    const NOT_SAMPLED: u64 = u64::MAX;
+
+    unsafe extern "C" {
+        fn notify_register_check(name: *const c_char, out_token: *mut c_int) -> u32;
+        fn notify_get_state(token: c_int, state: *mut u64) -> u32;
+        fn notify_cancel(token: c_int) -> u32;
+    }
+
+    pub(super) struct Sampler {
+        stop: Arc<AtomicBool>,
+        max: Arc<AtomicU64>,
+        thread: JoinHandle<()>,
+    }
+
+    impl Sampler {
+        pub(super) fn start() -> Option<Sampler> {
+            let mut token: c_int = 0;
+            if unsafe { notify_register_check(PRESSURE_LEVEL_NAME.as_ptr(), &mut token) } != 0 {
+                return None;
+            }
+            let stop = Arc::new(AtomicBool::new(false));
+            let max = Arc::new(AtomicU64::new(NOT_SAMPLED));
+            let thread = {
+                let (stop, max) = (stop.clone(), max.clone());
+                std::thread::spawn(move || {
+                    loop {
+                        let mut state = 0u64;
+                        if unsafe { notify_get_state(token, &mut state) } == 0 {
+                            let seen = max.load(Ordering::Relaxed);
+                            if seen == NOT_SAMPLED || state > seen {
+                                max.store(state, Ordering::Relaxed);
+                            }
+                        }
+                        if stop.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        std::thread::sleep(INTERVAL);
+                    }
+                    unsafe { notify_cancel(token) };
+                })
+            };
+            Some(Sampler { stop, max, thread })
+        }
+
+        pub(super) fn finish(self) -> Option<u64> {
+            self.stop.store(true, Ordering::Relaxed);
+            let _ = self.thread.join();
+            Some(self.max.load(Ordering::Relaxed)).filter(|&m| m != NOT_SAMPLED)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_elevated() {
+        assert!(!is_elevated("nominal"));
+        assert!(is_elevated("moderate"));
+        assert!(is_elevated("heavy"));
+    }
+
+    #[test]
+    fn test_sampler_reports_known_level() {
+        let level = ThermalSampler::start().finish();
+        if cfg!(target_os = "macos") {
+            assert!(THERMAL_LEVELS.contains(&level.unwrap().as_str()));
+        } else {
+            assert!(level.is_none());
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! CLI command handler for `corpus`.
+//!
+//! The corpus is a fixed, versioned set of representative Noir circuits
+//! embedded in the binary (sources live under `corpus/<version>/`). Running
+//! it on different machines or backends gives directly comparable records:
+//! each one carries the corpus version and circuit name in its metadata.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::calibration;
+use crate::core::signing::load_signing_key;
+use crate::core::{BenchRecord, TimingStat};
+use crate::engine::{NargoToolchain, ProveInputs, Toolchain, full_benchmark};
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Version of the embedded corpus. Bump when any circuit or input changes.
+pub const CORPUS_VERSION: &str = "v1";
+
+/// Metadata key holding the corpus version on corpus records.
+pub const CORPUS_METADATA_KEY: &str = "corpus";
+
+/// Default directory the corpus is extracted to.
+pub const DEFAULT_OUT_DIR: &str = "out/corpus";
+
+/// Default JSONL output for corpus records.
+pub const DEFAULT_JSONL: &str = "out/corpus.jsonl";
+
+/// One embedded corpus circuit.
+#[derive(Debug, Clone, Copy)]
+pub struct CorpusCircuit {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub nargo_toml: &'static str,
+    pub main_nr: &'static str,
+    pub prover_toml: &'static str,
+}
+
+macro_rules! corpus_circuit {
+    ($name:literal, $description:literal) => {
+        CorpusCircuit {
+            name: $name,
+            description: $description,
+            nargo_toml: include_str!(concat!("../corpus/v1/", $name, "/Nargo.toml")),
+            main_nr: include_str!(concat!("../corpus/v1/", $name, "/src/main.nr")),
+            prover_toml: include_str!(concat!("../corpus/v1/", $name, "/Prover.toml")),
+        }
+    };
+}
+
+/// The embedded corpus, in run order.
+pub const CORPUS: &[CorpusCircuit] = &[
+    corpus_circuit!("merkle_proof", "depth-20 pedersen merkle membership proof"),
+    corpus_circuit!("ecdsa_verify", "secp256k1 ECDSA signature verification"),
+    corpus_circuit!("keccak_sponge", "keccak sponge over 8 x 136-byte blocks"),
+    corpus_circuit!(
+        "rollup_transition",
+        "8 transfers over 16 accounts with pedersen state commitments"
+    ),
+];
+
+/// Select corpus circuits by name; `None` selects all of them.
+pub fn select_circuits(only: Option<&[String]>) -> BenchResult<Vec<CorpusCircuit>> {
+    let Some(names) = only else {
+        return Ok(CORPUS.to_vec());
+    };
+    names
+        .iter()
+        .map(|name| {
+            let name = name.trim();
+            CORPUS
+                .iter()
+                .find(|c| c.name == name)
+                .copied()
+                .ok_or_else(|| {
+                    let known: Vec<_> = CORPUS.iter().map(|c| c.name).collect();
+                    BenchError::Message(format!(
+                        "unknown corpus circuit '{name}' (known: {})",
+                        known.join(", ")
+                    ))
+                })
+        })
+        .collect()
+}
+
+fn write_file(path: &Path, contents: &str) -> BenchResult<()> {
+    std::fs::write(path, contents)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+}
+
+/// Write a corpus circuit's project to `<out_dir>/<version>/<name>`.
+pub fn extract(circuit: &CorpusCircuit, out_dir: &Path) -> BenchResult<PathBuf> {
+    let dir = out_dir.join(CORPUS_VERSION).join(circuit.name);
+    std::fs::create_dir_all(dir.join("src"))
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", dir.display())))?;
+    write_file(&dir.join("Nargo.toml"), circuit.nargo_toml)?;
+    write_file(&dir.join("src/main.nr"), circuit.main_nr)?;
+    write_file(&dir.join("Prover.toml"), circuit.prover_toml)?;
+    Ok(dir)
+}
+
+/// Extract, compile and benchmark one corpus circuit.
+pub fn run_circuit(
+    circuit: &CorpusCircuit,
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+    out_dir: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> BenchResult<BenchRecord> {
+    let project = extract(circuit, out_dir)?;
+    let compiled = toolchain.compile(&project)?;
+    let inputs = ProveInputs::new(&compiled.artifact_path, circuit.name)
+        .with_prover_toml(project.join("Prover.toml"))
+        .with_timeout(Duration::from_secs(24 * 60 * 60));
+    let result = full_benchmark(toolchain, backend, &inputs, warmup, iterations)?;
+    let mut record = result.record;
+    record.compile_stats = Some(TimingStat::from_samples(&[compiled.compile_time_ms as f64]));
+    record
+        .metadata
+        .insert(CORPUS_METADATA_KEY.to_string(), CORPUS_VERSION.to_string());
+    record
+        .metadata
+        .insert("verify_ok".to_string(), result.verify_success.to_string());
+    Ok(record)
+}
+
+/// Print the embedded corpus.
+pub fn list() -> BenchResult<()> {
+    println!("corpus {CORPUS_VERSION}:");
+    for c in CORPUS {
+        println!("  {:<20} {}", c.name, c.description);
+    }
+    Ok(())
+}
+
+/// Main entry point for `corpus run`.
+pub fn run(
+    only: Option<Vec<String>>,
+    out_dir: PathBuf,
+    jsonl_out: PathBuf,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    nargo_path: Option<PathBuf>,
+    backend_path: PathBuf,
+    sign_key: Option<PathBuf>,
+) -> BenchResult<()> {
+    let circuits = select_circuits(only.as_deref())?;
+    let toolchain = match nargo_path {
+        Some(path) => NargoToolchain::with_path(path),
+        None => NargoToolchain::new(),
+    };
+    let backend = BarretenbergBackend::new(
+        BarretenbergConfig::new(backend_path).with_timeout(Duration::from_secs(24 * 60 * 60)),
+    );
+    let mut writer = JsonlWriter::new(&jsonl_out);
+    if let Some(key) = &sign_key {
+        writer = writer.with_signing_key(load_signing_key(key)?);
+    }
+
+    // Recorded so results can later be normalized against reference hardware
+    let calibration_score = calibration::measure();
+    eprintln!("calibration score: {calibration_score:.1}");
+
+    let mut failed = 0;
+    for circuit in &circuits {
+        match run_circuit(
+            circuit,
+            &toolchain,
+            &backend,
+            &out_dir,
+            warmup.unwrap_or(0),
+            iterations.unwrap_or(1),
+        ) {
+            Ok(mut record) => {
+                record.metadata.insert(
+                    calibration::CALIBRATION_METADATA_KEY.to_string(),
+                    format!("{calibration_score:.2}"),
+                );
+                writer.append(&record)?;
+                println!(
+                    "corpus {}: {} gates={:?} prove_ms_avg={:.2} verify_ok={}",
+                    CORPUS_VERSION,
+                    circuit.name,
+                    record.total_gates,
+                    record
+                        .prove_stats
+                        .as_ref()
+                        .map(|s| s.mean_ms)
+                        .unwrap_or(0.0),
+                    record
+                        .metadata
+                        .get("verify_ok")
+                        .map(String::as_str)
+                        .unwrap_or("false")
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("corpus {}: {} failed: {e}", CORPUS_VERSION, circuit.name);
+            }
+        }
+    }
+
+    eprintln!("Appended corpus records to {}", jsonl_out.display());
+    if failed > 0 {
+        return Err(BenchError::Message(format!(
+            "{failed} of {} corpus circuit(s) failed",
+            circuits.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig};
+    use crate::engine::MockToolchain;
+
+    #[test]
+    fn test_corpus_fixtures_are_well_formed() {
+        for c in CORPUS {
+            let nargo: toml::Table = c.nargo_toml.parse().unwrap();
+            assert_eq!(nargo["package"]["name"].as_str(), Some(c.name));
+            assert!(c.main_nr.contains("fn main("), "{}", c.name);
+            assert!(c.prover_toml.parse::<toml::Table>().is_ok(), "{}", c.name);
+        }
+    }
+
+    #[test]
+    fn test_select_circuits() {
+        assert_eq!(select_circuits(None).unwrap().len(), CORPUS.len());
+        let only = vec!["keccak_sponge".to_string()];
+        assert_eq!(
+            select_circuits(Some(&only)).unwrap()[0].name,
+            "keccak_sponge"
+        );
+        assert!(select_circuits(Some(&["sha512".to_string()])).is_err());
+    }
+
+    #[test]
+    fn test_run_circuit_extracts_and_tags_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let circuit = select_circuits(Some(&["merkle_proof".to_string()])).unwrap()[0];
+
+        let record = run_circuit(
+            &circuit,
+            &MockToolchain::new(),
+            &MockBackend::new(MockConfig::new("mock-bb")),
+            dir.path(),
+            0,
+            1,
+        )
+        .unwrap();
+
+        let project = dir.path().join("v1/merkle_proof");
+        assert!(project.join("src/main.nr").exists());
+        assert!(project.join("Prover.toml").exists());
+        assert_eq!(record.circuit_name, "merkle_proof");
+        assert_eq!(record.metadata[CORPUS_METADATA_KEY], CORPUS_VERSION);
+        assert!(record.compile_stats.is_some());
+    }
+}
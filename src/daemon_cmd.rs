@@ -0,0 +1,570 @@
+//! Scheduler daemon for running the benchmark suite on a lab machine.
+//!
+//! `noir-bench daemon --cron "0 3 * * *"` sleeps until each scheduled time,
+//! runs the configured CI circuits, appends the records to a history JSONL,
+//! rebuilds the history site, re-fits stale cost models, and runs a
+//! notification command when the run regressed against the previous one.
+//! Schedules are evaluated in UTC.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use ed25519_dalek::SigningKey;
+use shlex::Shlex;
+use time::OffsetDateTime;
+
+use crate::bench::config::load_ci_settings;
+use crate::check_cmd;
+use crate::ci_cmd;
+use crate::compare_cmd::to_regression_report;
+use crate::core::SignaturePolicy;
+use crate::core::anomaly;
+use crate::core::calibration;
+use crate::core::schema::BenchRecord;
+use crate::core::signing::{load_signing_key, sign_record};
+use crate::history_cmd;
+use crate::model_cmd;
+use crate::report::render_markdown;
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Default directory for daemon state (the last run, used as the next baseline).
+pub const DEFAULT_STATE_DIR: &str = ".noir-bench/daemon";
+
+/// A five-field cron schedule: minute, hour, day of month, month, day of week.
+///
+/// Fields accept `*`, numbers, ranges (`1-5`), lists (`1,15`) and steps
+/// (`*/15`, `0-30/10`). Day of week is 0-7 with both 0 and 7 meaning Sunday.
+/// As in cron, when both day fields are restricted a day matching either runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression such as `0 3 * * *`.
+    pub fn parse(expr: &str) -> BenchResult<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            return Err(BenchError::Message(format!(
+                "invalid cron expression '{expr}': expected 5 fields (minute hour day month weekday)"
+            )));
+        };
+        let mut days_of_week = parse_field(dow, 0, 7, "weekday")?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+        Ok(CronSchedule {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days_of_month: parse_field(dom, 1, 31, "day")?,
+            months: parse_field(month, 1, 12, "month")?,
+            days_of_week,
+            dom_restricted: !dom.starts_with('*'),
+            dow_restricted: !dow.starts_with('*'),
+        })
+    }
+
+    fn matches_day(&self, t: OffsetDateTime) -> bool {
+        let dom = bit(self.days_of_month, t.day());
+        let dow = bit(self.days_of_week, t.weekday().number_days_from_sunday());
+        let day = match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        };
+        day && bit(self.months, t.month() as u8)
+    }
+
+    /// The first scheduled minute strictly after `t`, searching up to five years ahead.
+    pub fn next_after(&self, t: OffsetDateTime) -> Option<OffsetDateTime> {
+        let minute = time::Duration::minutes(1);
+        let mut next = t
+            .replace_second(0)
+            .and_then(|t| t.replace_nanosecond(0))
+            .ok()?
+            + minute;
+        let limit = t + time::Duration::days(5 * 366);
+        while next <= limit {
+            if !self.matches_day(next) {
+                // Jump to the start of the next day
+                next = next.replace_time(time::Time::MIDNIGHT) + time::Duration::days(1);
+            } else if !bit(self.hours, next.hour()) {
+                next = next.replace_minute(0).ok()? + time::Duration::hours(1);
+            } else if !bit(self.minutes, next.minute()) {
+                next += minute;
+            } else {
+                return Some(next);
+            }
+        }
+        None
+    }
+}
+
+fn bit(mask: u64, n: u8) -> bool {
+    mask & (1 << n) != 0
+}
+
+fn parse_field(field: &str, min: u8, max: u8, name: &str) -> BenchResult<u64> {
+    let invalid = || BenchError::Message(format!("invalid cron {name} field '{field}'"));
+    let num = |s: &str| -> BenchResult<u8> {
+        s.parse::<u8>()
+            .ok()
+            .filter(|n| (min..=max).contains(n))
+            .ok_or_else(|| {
+                BenchError::Message(format!("invalid cron {name} '{s}': expected {min}-{max}"))
+            })
+    };
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (part, None),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (num(lo)?, num(hi)?)
+        } else {
+            let n = num(range)?;
+            // `5/10` means "from 5 to the end, every 10"
+            (n, if step.is_some() { max } else { n })
+        };
+        let step = match step {
+            Some(s) => s
+                .parse::<u8>()
+                .ok()
+                .filter(|s| *s > 0)
+                .ok_or_else(invalid)?,
+            None => 1,
+        };
+        if lo > hi {
+            return Err(invalid());
+        }
+        for n in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << n;
+        }
+    }
+    Ok(mask)
+}
+
+/// Outcome of a single scheduled run.
+#[derive(Debug, Clone, Default)]
+pub struct TickOutcome {
+    /// Records appended to the history JSONL
+    pub records: usize,
+    /// Regressions against the previous run (0 without a previous run)
+    pub regressions: usize,
+    /// Alert rules from the config's `[alerts]` section that fired
+    pub alerts: usize,
+}
+
+/// Run the suite once: benchmark, append to history, rebuild the site, notify.
+///
+/// The previous run's JSONL in `state_dir` is the baseline; a run that
+/// produced records replaces it for the next tick. New records are checked
+/// against the history before they are appended and outliers are flagged
+/// (see `core::anomaly`).
+pub fn tick(
+    config: &Path,
+    history: &Path,
+    site: &Path,
+    state_dir: &Path,
+    models_dir: &Path,
+    notify: Option<&str>,
+    sign_key: Option<&Path>,
+) -> BenchResult<TickOutcome> {
+    std::fs::create_dir_all(state_dir).map_err(|e| {
+        BenchError::Message(format!(
+            "failed to create state directory {}: {e}",
+            state_dir.display()
+        ))
+    })?;
+    let signing_key = sign_key.map(load_signing_key).transpose()?;
+    let previous = state_dir.join("previous.jsonl");
+    let latest = state_dir.join("latest.jsonl");
+
+    let result = ci_cmd::run_configured(
+        &config.to_path_buf(),
+        &previous,
+        &latest,
+        signing_key.as_ref(),
+    )?;
+
+    let mut records = JsonlWriter::new(&latest).read_all()?;
+    let history_writer = JsonlWriter::new(history);
+    let past = if history_writer.exists() {
+        history_writer.read_all()?
+    } else {
+        Vec::new()
+    };
+    let mad_k = if config.exists() {
+        load_ci_settings(config)?.anomaly_mad_k
+    } else {
+        None
+    };
+    let flagged = anomaly::flag(
+        &past,
+        &mut records,
+        mad_k.unwrap_or(anomaly::DEFAULT_MAD_K),
+        anomaly::DEFAULT_WINDOW,
+    );
+    for (record_id, anomalies) in &flagged {
+        for a in anomalies {
+            eprintln!(
+                "daemon: flagged {record_id} as an anomaly: {} {:.1} is {:.1} MADs from the median {:.1}",
+                a.metric,
+                a.value,
+                a.score(),
+                a.median
+            );
+        }
+    }
+    sign_flagged(&mut records, signing_key.as_ref())?;
+    for record in &records {
+        history_writer.append(record)?;
+    }
+    if records.is_empty() {
+        eprintln!("daemon: run produced no records; keeping previous baseline");
+    } else {
+        let baseline_records = if previous.exists() {
+            JsonlWriter::new(&previous).read_all()?
+        } else {
+            Vec::new()
+        };
+        let baseline = next_baseline(baseline_records, &records);
+        let tmp = state_dir.join("previous.jsonl.tmp");
+        let _ = std::fs::remove_file(&tmp);
+        let writer = JsonlWriter::new(&tmp);
+        for record in &baseline {
+            writer.append(record)?;
+        }
+        std::fs::rename(&tmp, &previous)
+            .map_err(|e| BenchError::Message(format!("failed to rotate baseline: {e}")))?;
+        std::fs::remove_file(&latest).ok();
+    }
+
+    if !records.is_empty() {
+        let all = JsonlWriter::new(history).read_all()?;
+        let refitted = model_cmd::refit_stale(
+            &all,
+            models_dir,
+            model_cmd::DEFAULT_REFIT_MIN_NEW,
+            calibration::measure(),
+        );
+        for path in refitted {
+            eprintln!("daemon: re-fitted cost model {}", path.display());
+        }
+    }
+
+    if history.exists() {
+        let budgets = if config.exists() {
+            check_cmd::load_budgets(config)?
+        } else {
+            check_cmd::Budgets::new()
+        };
+        history_cmd::build(
+            history.to_path_buf(),
+            site.to_path_buf(),
+            &SignaturePolicy::default(),
+            None,
+            &budgets,
+        )?;
+    }
+
+    let regressions = result
+        .comparison
+        .as_ref()
+        .map(|c| c.total_regressions)
+        .unwrap_or(0);
+    let regressed = result.comparison.as_ref().filter(|_| regressions > 0);
+    if let (Some(comparison), Some(cmd)) = (regressed, notify) {
+        let report = render_markdown(&to_regression_report(comparison));
+        if let Err(e) = run_notify(cmd, &report, regressions) {
+            eprintln!("daemon: notification failed: {e}");
+        }
+    }
+
+    Ok(TickOutcome {
+        records: records.len(),
+        regressions,
+        alerts: result.alerts.len(),
+    })
+}
+
+/// Sign flagged records again, as the anomaly flag is covered by the
+/// signature. Without a key, a signed record keeps its signature and is left
+/// unflagged (with a warning) rather than stored with one that no longer
+/// verifies.
+fn sign_flagged(records: &mut [BenchRecord], key: Option<&SigningKey>) -> BenchResult<()> {
+    for record in records
+        .iter_mut()
+        .filter(|r| r.anomaly && r.signature.is_some())
+    {
+        match key {
+            Some(key) => sign_record(record, key)?,
+            None => {
+                eprintln!(
+                    "daemon: not flagging signed record {} without --sign-key",
+                    record.record_id
+                );
+                record.anomaly = false;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The baseline for the next tick: the latest run, except that a circuit
+/// whose new run was flagged as an anomaly keeps its previous record, so one
+/// noisy run isn't what the next run is compared against.
+fn next_baseline(previous: Vec<BenchRecord>, latest: &[BenchRecord]) -> Vec<BenchRecord> {
+    latest
+        .iter()
+        .map(|record| {
+            record
+                .anomaly
+                .then(|| {
+                    previous
+                        .iter()
+                        .find(|p| !p.anomaly && anomaly::same_series(p, record))
+                })
+                .flatten()
+                .unwrap_or(record)
+                .clone()
+        })
+        .collect()
+}
+
+/// Run the notification command with the markdown report on stdin.
+///
+/// The regression count is exported as `NOIR_BENCH_REGRESSIONS`.
+fn run_notify(cmd: &str, report: &str, regressions: usize) -> BenchResult<()> {
+    let mut parts = Shlex::new(cmd);
+    let Some(program) = parts.next() else {
+        return Err(BenchError::Message("empty --notify command".into()));
+    };
+    let mut child = Command::new(&program)
+        .args(parts)
+        .env("NOIR_BENCH_REGRESSIONS", regressions.to_string())
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| BenchError::Message(format!("failed to run {program}: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores stdin may close it early; that's fine
+        let _ = stdin.write_all(report.as_bytes());
+    }
+    let status = child
+        .wait()
+        .map_err(|e| BenchError::Message(format!("failed to wait for {program}: {e}")))?;
+    if !status.success() {
+        return Err(BenchError::Message(format!(
+            "{program} exited with status: {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Main entry point for the daemon command.
+///
+/// Runs forever, one tick per scheduled time; a failed tick is logged and the
+/// daemon waits for the next one. With `once`, runs a single tick immediately.
+pub fn run(
+    cron: String,
+    config: PathBuf,
+    history: PathBuf,
+    site: PathBuf,
+    state_dir: PathBuf,
+    models_dir: PathBuf,
+    notify: Option<String>,
+    once: bool,
+    sign_key: Option<PathBuf>,
+) -> BenchResult<()> {
+    let schedule = CronSchedule::parse(&cron)?;
+    let run_tick = || {
+        tick(
+            &config,
+            &history,
+            &site,
+            &state_dir,
+            &models_dir,
+            notify.as_deref(),
+            sign_key.as_deref(),
+        )
+    };
+
+    if once {
+        let outcome = run_tick()?;
+        eprintln!(
+            "daemon: appended {} record(s), {} regression(s), {} alert(s)",
+            outcome.records, outcome.regressions, outcome.alerts
+        );
+        return Ok(());
+    }
+
+    loop {
+        let now = OffsetDateTime::now_utc();
+        let Some(next) = schedule.next_after(now) else {
+            return Err(BenchError::Message(format!(
+                "cron expression '{cron}' never fires"
+            )));
+        };
+        eprintln!(
+            "daemon: next run at {}",
+            next.format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default()
+        );
+        std::thread::sleep((next - now).unsigned_abs());
+
+        match run_tick() {
+            Ok(outcome) => eprintln!(
+                "daemon: appended {} record(s), {} regression(s), {} alert(s)",
+                outcome.records, outcome.regressions, outcome.alerts
+            ),
+            Err(e) => eprintln!("daemon: run failed: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-01 00:00:00 UTC, a Monday
+    fn jan_1_2024() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1_704_067_200).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_expressions() {
+        assert!(CronSchedule::parse("0 3 * *").is_err());
+        assert!(CronSchedule::parse("60 3 * * *").is_err());
+        assert!(CronSchedule::parse("0 3 * * 8").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_next_after_daily() {
+        let s = CronSchedule::parse("0 3 * * *").unwrap();
+        let t = jan_1_2024();
+
+        let next = s.next_after(t).unwrap();
+        assert_eq!((next.day(), next.hour(), next.minute()), (1, 3, 0));
+
+        // Exactly on the schedule moves to the next day
+        let next = s.next_after(next).unwrap();
+        assert_eq!((next.day(), next.hour(), next.minute()), (2, 3, 0));
+    }
+
+    #[test]
+    fn test_next_after_steps_lists_and_weekdays() {
+        let t = jan_1_2024() + time::Duration::minutes(7);
+
+        let s = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(s.next_after(t).unwrap().minute(), 15);
+
+        let s = CronSchedule::parse("30 1,13 * * *").unwrap();
+        let next = s.next_after(t + time::Duration::hours(2)).unwrap();
+        assert_eq!((next.hour(), next.minute()), (13, 30));
+
+        // Saturdays only (Jan 1 2024 is a Monday); 7 means Sunday
+        let s = CronSchedule::parse("0 0 * * 6").unwrap();
+        assert_eq!(s.next_after(t).unwrap().day(), 6);
+        let s = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(s.next_after(t).unwrap().day(), 7);
+
+        // Either day field matches when both are restricted
+        let s = CronSchedule::parse("0 0 15 * 3").unwrap();
+        assert_eq!(s.next_after(t).unwrap().day(), 3);
+    }
+
+    #[test]
+    fn test_next_baseline_keeps_previous_record_for_anomalies() {
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig};
+
+        let record = |circuit: &str, id: &str, anomaly: bool| {
+            let mut r = BenchRecord::new(
+                circuit.to_string(),
+                EnvironmentInfo::default(),
+                BackendInfo {
+                    name: "bb".to_string(),
+                    version: None,
+                    variant: None,
+                },
+                RunConfig::default(),
+            );
+            r.record_id = id.to_string();
+            r.anomaly = anomaly;
+            r
+        };
+        let previous = vec![record("a", "a1", false), record("b", "b1", false)];
+        let latest = vec![
+            record("a", "a2", true),
+            record("b", "b2", false),
+            // No earlier run to fall back to
+            record("c", "c2", true),
+        ];
+        let ids: Vec<String> = next_baseline(previous, &latest)
+            .into_iter()
+            .map(|r| r.record_id)
+            .collect();
+        assert_eq!(ids, ["a1", "b2", "c2"]);
+    }
+
+    #[test]
+    fn test_sign_flagged_keeps_signatures_valid() {
+        use crate::core::signing::verify_signature;
+        use crate::core::{BackendInfo, EnvironmentInfo, RunConfig};
+
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut r = BenchRecord::new(
+            "a".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        sign_record(&mut r, &key).unwrap();
+        r.anomaly = true;
+
+        let mut signed = vec![r.clone()];
+        sign_flagged(&mut signed, Some(&key)).unwrap();
+        assert!(signed[0].anomaly);
+        assert!(verify_signature(&signed[0]).is_ok());
+
+        let mut no_key = vec![r];
+        sign_flagged(&mut no_key, None).unwrap();
+        assert!(!no_key[0].anomaly);
+        assert!(verify_signature(&no_key[0]).is_ok());
+    }
+
+    #[test]
+    fn test_next_after_impossible_date() {
+        let s = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert_eq!(s.next_after(jan_1_2024()), None);
+    }
+
+    #[test]
+    fn test_run_notify_pipes_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("report.md");
+        let cmd = format!("sh -c 'cat > {}'", out.display());
+
+        run_notify(&cmd, "# Regressions\n", 2).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "# Regressions\n");
+
+        assert!(run_notify("false", "", 1).is_err());
+    }
+}
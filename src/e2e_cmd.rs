@@ -0,0 +1,362 @@
+//! CLI command handler for `e2e`: the latency of a dapp's proving pipeline.
+//!
+//! A dapp generates the witness with noir_js and proves natively, so its
+//! latency includes a JS stage the other commands never see. Each iteration
+//! runs a small node script that loads `@noir-lang/noir_js` from the given
+//! node project and executes the circuit, then proves the JS witness with bb.
+//! The report splits the wall clock into node startup and module loading, JS
+//! witness generation and native proving.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::schema::TimingStat;
+use crate::{BackendInfo, BenchError, BenchResult, CommonMeta, SystemInfo, collect_system_info};
+
+/// Witness generation with noir_js; prints the stage times as JSON.
+const WITNESS_SCRIPT: &str = r#"
+const { createRequire } = require('node:module');
+const { readFileSync, writeFileSync } = require('node:fs');
+const path = require('node:path');
+
+async function main() {
+  const [project, artifactPath, inputsPath, witnessPath] = process.argv.slice(2);
+  const t0 = performance.now();
+  const { Noir } = createRequire(path.join(path.resolve(project), 'package.json'))('@noir-lang/noir_js');
+  const circuit = JSON.parse(readFileSync(artifactPath, 'utf8'));
+  const inputs = JSON.parse(readFileSync(inputsPath, 'utf8'));
+  const noir = new Noir(circuit);
+  const t1 = performance.now();
+  const { witness } = await noir.execute(inputs);
+  const t2 = performance.now();
+  writeFileSync(witnessPath, witness);
+  console.log(JSON.stringify({ load_ms: t1 - t0, witness_ms: t2 - t1 }));
+}
+
+main().catch((e) => {
+  console.error(e);
+  process.exit(1);
+});
+"#;
+
+/// Stage times printed by the witness script.
+#[derive(Debug, Deserialize)]
+struct ScriptTimings {
+    load_ms: f64,
+    witness_ms: f64,
+}
+
+/// One pipeline run.
+#[derive(Debug, Clone, Copy)]
+pub struct E2eSample {
+    /// Node process wall clock, startup and module loading included
+    pub node_ms: f64,
+    /// noir_js import and circuit setup inside node
+    pub js_load_ms: f64,
+    /// `noir.execute` inside node
+    pub js_witness_ms: f64,
+    /// Native bb proving of the JS witness
+    pub prove_ms: f64,
+    /// Wall clock of the whole pipeline
+    pub total_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct E2eReport {
+    #[serde(flatten)]
+    pub meta: CommonMeta,
+    pub node_version: Option<String>,
+    pub node_ms: TimingStat,
+    pub js_load_ms: TimingStat,
+    pub js_witness_ms: TimingStat,
+    pub prove_ms: TimingStat,
+    pub total_ms: TimingStat,
+    pub proof_size_bytes: Option<u64>,
+    pub backend: BackendInfo,
+    pub system: Option<SystemInfo>,
+}
+
+impl E2eReport {
+    /// Share of the mean total spent in each stage, node overhead being the
+    /// node wall clock outside loading and witness generation.
+    pub fn stage_split(&self) -> [(&'static str, f64); 4] {
+        let overhead =
+            (self.node_ms.mean_ms - self.js_load_ms.mean_ms - self.js_witness_ms.mean_ms).max(0.0);
+        [
+            ("node startup", overhead),
+            ("noir_js load", self.js_load_ms.mean_ms),
+            ("js witness", self.js_witness_ms.mean_ms),
+            ("native prove", self.prove_ms.mean_ms),
+        ]
+    }
+}
+
+/// Prover.toml inputs as the JSON input map noir_js expects.
+pub fn inputs_json(prover_toml: &Path) -> BenchResult<String> {
+    let text = std::fs::read_to_string(prover_toml).map_err(|e| {
+        BenchError::Message(format!("failed to read {}: {e}", prover_toml.display()))
+    })?;
+    let value: toml::Value = toml::from_str(&text).map_err(|e| {
+        BenchError::Message(format!("failed to parse {}: {e}", prover_toml.display()))
+    })?;
+    serde_json::to_string(&value).map_err(|e| BenchError::Message(e.to_string()))
+}
+
+fn node_version(node: &Path) -> Option<String> {
+    Command::new(node)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Generate the witness with noir_js, returning the node wall clock and
+/// the script's own stage times.
+fn js_witness(
+    node: &Path,
+    node_project: &Path,
+    script: &Path,
+    artifact: &Path,
+    inputs: &Path,
+    witness: &Path,
+) -> BenchResult<(f64, ScriptTimings)> {
+    let start = Instant::now();
+    let output = Command::new(node)
+        .arg(script)
+        .arg(node_project)
+        .arg(artifact)
+        .arg(inputs)
+        .arg(witness)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| BenchError::Message(format!("failed to run node: {e}")))?;
+    let node_ms = start.elapsed().as_secs_f64() * 1000.0;
+    if !output.status.success() {
+        return Err(BenchError::Message(format!(
+            "noir_js witness generation failed. stderr=\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let timings = stdout
+        .lines()
+        .rev()
+        .find_map(|l| serde_json::from_str(l).ok())
+        .ok_or_else(|| BenchError::Message("witness script printed no timings".into()))?;
+    Ok((node_ms, timings))
+}
+
+/// Run one pipeline: JS witness, then native proof.
+fn run_once(
+    backend: &dyn Backend,
+    node: &Path,
+    node_project: &Path,
+    dir: &Path,
+    artifact: &Path,
+    timeout: Duration,
+) -> BenchResult<(E2eSample, Option<u64>)> {
+    let witness = dir.join("witness.gz");
+    let start = Instant::now();
+    let (node_ms, timings) = js_witness(
+        node,
+        node_project,
+        &dir.join("witness.cjs"),
+        artifact,
+        &dir.join("inputs.json"),
+        &witness,
+    )?;
+    let prove = backend.prove(artifact, Some(&witness), timeout)?;
+    let total_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let sample = E2eSample {
+        node_ms,
+        js_load_ms: timings.load_ms,
+        js_witness_ms: timings.witness_ms,
+        prove_ms: prove.prove_time_ms as f64,
+        total_ms,
+    };
+    Ok((sample, prove.proof_size_bytes))
+}
+
+/// Summarize the samples of every stage.
+pub fn summarize(samples: &[E2eSample]) -> [TimingStat; 5] {
+    let stat = |f: fn(&E2eSample) -> f64| {
+        let mut s = TimingStat::from_samples(&samples.iter().map(f).collect::<Vec<_>>());
+        s.round_metrics();
+        s
+    };
+    [
+        stat(|s| s.node_ms),
+        stat(|s| s.js_load_ms),
+        stat(|s| s.js_witness_ms),
+        stat(|s| s.prove_ms),
+        stat(|s| s.total_ms),
+    ]
+}
+
+pub fn run(
+    artifact: PathBuf,
+    prover_toml: PathBuf,
+    node_project: PathBuf,
+    node_bin: Option<PathBuf>,
+    backend_path: PathBuf,
+    iterations: usize,
+    warmup: usize,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let node = node_bin.unwrap_or_else(|| PathBuf::from("node"));
+    let dir = tempfile::tempdir().map_err(|e| BenchError::Message(e.to_string()))?;
+    let write = |name: &str, contents: &str| {
+        std::fs::write(dir.path().join(name), contents)
+            .map_err(|e| BenchError::Message(format!("failed to write {name}: {e}")))
+    };
+    write("witness.cjs", WITNESS_SCRIPT)?;
+    write("inputs.json", &inputs_json(&prover_toml)?)?;
+
+    let timeout = Duration::from_secs(24 * 60 * 60);
+    let backend =
+        BarretenbergBackend::new(BarretenbergConfig::new(backend_path).with_timeout(timeout));
+
+    for _ in 0..warmup {
+        run_once(
+            &backend,
+            &node,
+            &node_project,
+            dir.path(),
+            &artifact,
+            timeout,
+        )?;
+    }
+    let mut samples = Vec::with_capacity(iterations.max(1));
+    let mut proof_size_bytes = None;
+    for _ in 0..iterations.max(1) {
+        let (sample, size) = run_once(
+            &backend,
+            &node,
+            &node_project,
+            dir.path(),
+            &artifact,
+            timeout,
+        )?;
+        samples.push(sample);
+        proof_size_bytes = size;
+    }
+
+    let [node_ms, js_load_ms, js_witness_ms, prove_ms, total_ms] = summarize(&samples);
+    let report = E2eReport {
+        meta: CommonMeta {
+            name: "e2e".into(),
+            timestamp: time::OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default(),
+            noir_version: crate::engine::load_program(&artifact)
+                .map(|p| p.noir_version.clone())
+                .unwrap_or_else(|_| "n/a".into()),
+            artifact_path: artifact.clone(),
+            cli_args: std::env::args().collect(),
+            artifact_sha256: crate::file_sha256(&artifact),
+            inputs_sha256: crate::file_sha256(&prover_toml),
+        },
+        node_version: node_version(&node),
+        node_ms,
+        js_load_ms,
+        js_witness_ms,
+        prove_ms,
+        total_ms,
+        proof_size_bytes,
+        backend: BackendInfo {
+            name: backend.name().to_string(),
+            version: backend.version(),
+        },
+        system: Some(collect_system_info()),
+    };
+
+    if let Some(json) = json_out {
+        if let Some(parent) = json.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(&json, serde_json::to_vec_pretty(&report).unwrap()).ok();
+    }
+    println!(
+        "e2e: total {:.1}ms over {} iteration(s)",
+        report.total_ms.mean_ms, report.total_ms.iterations
+    );
+    for (stage, ms) in report.stage_split() {
+        let share = if report.total_ms.mean_ms > 0.0 {
+            ms * 100.0 / report.total_ms.mean_ms
+        } else {
+            0.0
+        };
+        println!("  {stage:<13} {ms:>10.1}ms  {share:>5.1}%");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inputs_json_from_prover_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Prover.toml");
+        std::fs::write(&path, "x = \"0x01\"\ny = [1, 2]\n\n[s]\na = \"3\"\n").unwrap();
+        assert_eq!(
+            inputs_json(&path).unwrap(),
+            r#"{"s":{"a":"3"},"x":"0x01","y":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn test_summarize_and_stage_split() {
+        let sample = |node_ms, prove_ms| E2eSample {
+            node_ms,
+            js_load_ms: 40.0,
+            js_witness_ms: 20.0,
+            prove_ms,
+            total_ms: node_ms + prove_ms,
+        };
+        let [node_ms, js_load_ms, js_witness_ms, prove_ms, total_ms] =
+            summarize(&[sample(100.0, 300.0), sample(110.0, 310.0)]);
+        assert_eq!(total_ms.mean_ms, 410.0);
+        let report = E2eReport {
+            meta: CommonMeta {
+                name: "e2e".into(),
+                timestamp: String::new(),
+                noir_version: "n/a".into(),
+                artifact_path: PathBuf::new(),
+                cli_args: vec![],
+                artifact_sha256: None,
+                inputs_sha256: None,
+            },
+            node_version: None,
+            node_ms,
+            js_load_ms,
+            js_witness_ms,
+            prove_ms,
+            total_ms,
+            proof_size_bytes: None,
+            backend: BackendInfo {
+                name: "mock".into(),
+                version: None,
+            },
+            system: None,
+        };
+        assert_eq!(
+            report.stage_split(),
+            [
+                ("node startup", 45.0),
+                ("noir_js load", 40.0),
+                ("js witness", 20.0),
+                ("native prove", 305.0),
+            ]
+        );
+    }
+}
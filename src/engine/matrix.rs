@@ -0,0 +1,660 @@
+//! Version matrices: benchmark circuits under several nargo or bb versions.
+//!
+//! For a nargo matrix each version recompiles the circuit's project, then runs
+//! the same backend workflow on the result. For a bb matrix the artifact stays
+//! fixed and only the backend binary changes. Records are tagged with the
+//! version they ran under so compiler regressions (gate/opcode counts, artifact
+//! size) can be told apart from backend regressions (prove/verify time, proof
+//! size).
+//!
+//! A compile-config matrix works like a nargo matrix with one nargo and
+//! several sets of `nargo compile` flags (`--force-brillig`, expression
+//! width, inliner aggressiveness), recorded in each record's
+//! `config.compile_flags`.
+//!
+//! Note: witness generation runs in-process with the ACVM noir-bench is built
+//! against, so very old or very new artifacts may fail at the witness step
+//! even when their nargo compiled them fine.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::TimingStat;
+use crate::{BenchError, BenchResult};
+
+use super::toolchain::{NargoToolchain, Toolchain};
+use super::workflow::{FullBenchmarkResult, ProveInputs, full_benchmark};
+
+/// Metadata key nargo matrix records are tagged with (`nargo@<version>`).
+pub const TOOLCHAIN_METADATA_KEY: &str = "toolchain";
+
+/// Metadata key bb matrix records are tagged with (`bb@<version>`).
+pub const BACKEND_METADATA_KEY: &str = "backend_release";
+
+/// Metadata key compile-config matrix records are tagged with (the config name).
+pub const COMPILE_CONFIG_METADATA_KEY: &str = "compile_config";
+
+/// Whether `s` can name a matrix cell: it becomes a directory under the work
+/// directory, so only `[A-Za-z0-9._+-]`, not starting with a dot (no `..`).
+fn is_valid_label(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with('.')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '+' | '-'))
+}
+
+/// Parse a comma-separated `--nargo-versions`/`--bb-versions` list, dropping duplicates.
+///
+/// Versions end up in install and work directory paths, so only
+/// `[A-Za-z0-9._+-]` is accepted and a version can't start with `.`.
+pub fn parse_versions(s: &str) -> BenchResult<Vec<String>> {
+    let mut versions: Vec<String> = Vec::new();
+    for v in s.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+        if !is_valid_label(v) {
+            return Err(BenchError::Message(format!(
+                "invalid version '{v}' (expected e.g. 0.38.0 or nightly)"
+            )));
+        }
+        if !versions.iter().any(|seen| seen == v) {
+            versions.push(v.to_string());
+        }
+    }
+    if versions.is_empty() {
+        return Err(BenchError::Message(format!(
+            "no versions in '{s}' (expected e.g. 0.38.0,0.39.0,nightly)"
+        )));
+    }
+    Ok(versions)
+}
+
+/// Where nargo binaries for each version come from.
+#[derive(Debug, Clone)]
+pub enum NargoSource {
+    /// Pre-installed per-version binaries under a root directory:
+    /// `<root>/<version>/bin/nargo`, `<root>/<version>/nargo` or `<root>/nargo-<version>`.
+    Dir(PathBuf),
+    /// Install each version with `noirup --version <version>` into
+    /// `<root>/<version>` (via `NARGO_HOME`), leaving the user's own nargo alone.
+    Noirup(PathBuf),
+}
+
+impl NargoSource {
+    /// Resolve the nargo binary for `version`, installing it first for noirup.
+    pub fn resolve(&self, version: &str) -> BenchResult<PathBuf> {
+        match self {
+            NargoSource::Dir(root) => find_versioned_binary(root, "nargo", version),
+            NargoSource::Noirup(root) => {
+                let home = root.join(version);
+                run_installer("noirup", "NARGO_HOME", &home, version)?;
+                Ok(home.join("bin").join("nargo"))
+            }
+        }
+    }
+}
+
+/// Where bb binaries for each version come from.
+#[derive(Debug, Clone)]
+pub enum BbSource {
+    /// Pre-installed per-version binaries under a root directory:
+    /// `<root>/<version>/bin/bb`, `<root>/<version>/bb` or `<root>/bb-<version>`.
+    Dir(PathBuf),
+    /// Install each version with `bbup --version <version>` into
+    /// `<root>/<version>` (via `BB_HOME`), leaving the user's own bb alone.
+    Bbup(PathBuf),
+}
+
+impl BbSource {
+    /// Resolve the bb binary for `version`, installing it first for bbup.
+    pub fn resolve(&self, version: &str) -> BenchResult<PathBuf> {
+        match self {
+            BbSource::Dir(root) => find_versioned_binary(root, "bb", version),
+            BbSource::Bbup(root) => {
+                let home = root.join(version);
+                run_installer("bbup", "BB_HOME", &home, version)?;
+                Ok(home.join("bb"))
+            }
+        }
+    }
+}
+
+fn find_versioned_binary(root: &Path, tool: &str, version: &str) -> BenchResult<PathBuf> {
+    let candidates = [
+        root.join(version).join("bin").join(tool),
+        root.join(version).join(tool),
+        root.join(format!("{tool}-{version}")),
+    ];
+    candidates
+        .iter()
+        .find(|p| p.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            BenchError::Message(format!(
+                "no {tool} {version} under {} (tried {version}/bin/{tool}, {version}/{tool}, {tool}-{version})",
+                root.display()
+            ))
+        })
+}
+
+/// Run `<installer> --version <version>` (noirup/bbup) with `home_var`
+/// pointing at `home`, so the install lands there instead of the user's home.
+fn run_installer(installer: &str, home_var: &str, home: &Path, version: &str) -> BenchResult<()> {
+    std::fs::create_dir_all(home)
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", home.display())))?;
+    let status = Command::new(installer)
+        .arg("--version")
+        .arg(version)
+        .env(home_var, home)
+        .status()
+        .map_err(|e| BenchError::Message(format!("failed to run {installer}: {e}")))?;
+    if !status.success() {
+        return Err(BenchError::Message(format!(
+            "{installer} --version {version} failed with status: {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// A named set of `nargo compile` flags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileConfig {
+    pub name: String,
+    pub flags: Vec<String>,
+}
+
+impl CompileConfig {
+    /// Parse `NAME=FLAGS`, e.g. `brillig=--force-brillig` (see
+    /// `parse_named_flags`).
+    pub fn parse(s: &str) -> BenchResult<Self> {
+        let (name, flags) = parse_named_flags(s)?;
+        Self::new(&name, flags)
+    }
+
+    /// A config named `name`; the name becomes a work directory and a
+    /// circuit-name suffix, so it must be a valid label.
+    pub fn new(name: &str, flags: Vec<String>) -> BenchResult<Self> {
+        if !is_valid_label(name) {
+            return Err(BenchError::Message(format!(
+                "invalid compile config name '{name}' (expected e.g. brillig or inline-0)"
+            )));
+        }
+        Ok(CompileConfig {
+            name: name.to_string(),
+            flags,
+        })
+    }
+
+    /// Circuit name of `circuit` built under this config, `<circuit>@<name>`,
+    /// so records of different configs never match each other in compare or
+    /// history.
+    pub fn circuit_name(&self, circuit: &str) -> String {
+        format!("{circuit}@{}", self.name)
+    }
+}
+
+/// Parse a named flag set `NAME=FLAGS`; flags are split like a shell command
+/// line and may be empty (`default=`).
+pub fn parse_named_flags(s: &str) -> BenchResult<(String, Vec<String>)> {
+    let Some((name, flags)) = s.split_once('=') else {
+        return Err(BenchError::Message(format!(
+            "invalid flag set '{s}' (expected NAME=FLAGS, e.g. brillig=--force-brillig)"
+        )));
+    };
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return Err(BenchError::Message(format!(
+            "invalid flag set name '{name}'"
+        )));
+    }
+    let flags = shlex::split(flags)
+        .ok_or_else(|| BenchError::Message(format!("unbalanced quotes in flag set '{s}'")))?;
+    Ok((name.to_string(), flags))
+}
+
+/// Outcome of one matrix cell.
+#[derive(Debug)]
+pub struct MatrixEntry {
+    /// Requested version label (as given on the command line), or the
+    /// compile config name
+    pub version: String,
+    pub result: BenchResult<FullBenchmarkResult>,
+}
+
+/// Benchmark `inputs` once per toolchain version.
+///
+/// `project_dir` is recompiled with each toolchain; the compiled artifact is
+/// copied under `work_dir/<version>/` so versions don't overwrite each other,
+/// and the project's own `target/` artifact is restored afterwards. A failing
+/// version is reported in its entry and doesn't stop the others.
+pub fn run_toolchain_matrix(
+    versions: &[String],
+    make_toolchain: &dyn Fn(&str) -> BenchResult<Box<dyn Toolchain>>,
+    backend: &dyn Backend,
+    project_dir: &Path,
+    inputs: &ProveInputs,
+    work_dir: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> Vec<MatrixEntry> {
+    let original = std::fs::read(&inputs.artifact_path).ok();
+
+    let entries = versions
+        .iter()
+        .map(|version| MatrixEntry {
+            version: version.clone(),
+            result: run_one(
+                version,
+                make_toolchain,
+                backend,
+                project_dir,
+                inputs,
+                work_dir,
+                warmup,
+                iterations,
+            ),
+        })
+        .collect();
+
+    if let Some(bytes) = original {
+        let _ = std::fs::write(&inputs.artifact_path, bytes);
+    }
+    entries
+}
+
+fn run_one(
+    version: &str,
+    make_toolchain: &dyn Fn(&str) -> BenchResult<Box<dyn Toolchain>>,
+    backend: &dyn Backend,
+    project_dir: &Path,
+    inputs: &ProveInputs,
+    work_dir: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> BenchResult<FullBenchmarkResult> {
+    let toolchain = make_toolchain(version)?;
+    let mut result = compile_and_benchmark(
+        version,
+        toolchain.as_ref(),
+        backend,
+        project_dir,
+        inputs,
+        work_dir,
+        warmup,
+        iterations,
+    )?;
+    result.record.metadata.insert(
+        TOOLCHAIN_METADATA_KEY.to_string(),
+        format!("{}@{version}", toolchain.name()),
+    );
+    Ok(result)
+}
+
+/// Compile `project_dir` with `toolchain`, copy the artifact under
+/// `work_dir/<label>/` and benchmark it.
+pub(crate) fn compile_and_benchmark(
+    label: &str,
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+    project_dir: &Path,
+    inputs: &ProveInputs,
+    work_dir: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> BenchResult<FullBenchmarkResult> {
+    let compiled = toolchain.compile(project_dir)?;
+
+    let label_dir = work_dir.join(label);
+    std::fs::create_dir_all(&label_dir).map_err(|e| {
+        BenchError::Message(format!("failed to create {}: {e}", label_dir.display()))
+    })?;
+    let file_name = compiled
+        .artifact_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "program.json".into());
+    let artifact = label_dir.join(file_name);
+    std::fs::copy(&compiled.artifact_path, &artifact).map_err(|e| {
+        BenchError::Message(format!(
+            "failed to copy {} compiled for {label}: {e}",
+            compiled.artifact_path.display()
+        ))
+    })?;
+
+    let mut label_inputs = inputs.clone();
+    label_inputs.artifact_path = artifact;
+    let mut result = full_benchmark(toolchain, backend, &label_inputs, warmup, iterations)?;
+    result.record.compile_stats =
+        Some(TimingStat::from_samples(&[compiled.compile_time_ms as f64]));
+    Ok(result)
+}
+
+/// Benchmark `inputs` once per compile config.
+///
+/// Like `run_toolchain_matrix`, with the toolchain for each config built by
+/// `make_toolchain` (one nargo with the config's flags). Records are tagged
+/// with the config name, carry its flags in `config.compile_flags` and are
+/// named `<circuit>@<config>` (see `CompileConfig::circuit_name`).
+pub fn run_compile_matrix(
+    configs: &[CompileConfig],
+    make_toolchain: &dyn Fn(&CompileConfig) -> BenchResult<Box<dyn Toolchain>>,
+    backend: &dyn Backend,
+    project_dir: &Path,
+    inputs: &ProveInputs,
+    work_dir: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> Vec<MatrixEntry> {
+    let original = std::fs::read(&inputs.artifact_path).ok();
+
+    let entries = configs
+        .iter()
+        .map(|config| MatrixEntry {
+            version: config.name.clone(),
+            result: make_toolchain(config).and_then(|toolchain| {
+                let mut result = compile_and_benchmark(
+                    &config.name,
+                    toolchain.as_ref(),
+                    backend,
+                    project_dir,
+                    inputs,
+                    work_dir,
+                    warmup,
+                    iterations,
+                )?;
+                result.record.config.compile_flags = config.flags.clone();
+                result.record.circuit_name = config.circuit_name(&result.record.circuit_name);
+                result
+                    .record
+                    .metadata
+                    .insert(COMPILE_CONFIG_METADATA_KEY.to_string(), config.name.clone());
+                Ok(result)
+            }),
+        })
+        .collect();
+
+    if let Some(bytes) = original {
+        let _ = std::fs::write(&inputs.artifact_path, bytes);
+    }
+    entries
+}
+
+/// Benchmark `inputs` once per backend version, keeping the artifact fixed.
+///
+/// A failing version is reported in its entry and doesn't stop the others.
+pub fn run_backend_matrix(
+    versions: &[String],
+    make_backend: &dyn Fn(&str) -> BenchResult<Box<dyn Backend>>,
+    toolchain: &dyn Toolchain,
+    inputs: &ProveInputs,
+    warmup: usize,
+    iterations: usize,
+) -> Vec<MatrixEntry> {
+    versions
+        .iter()
+        .map(|version| {
+            let result = make_backend(version).and_then(|backend| {
+                let mut result =
+                    full_benchmark(toolchain, backend.as_ref(), inputs, warmup, iterations)?;
+                result.record.metadata.insert(
+                    BACKEND_METADATA_KEY.to_string(),
+                    format!("{}@{version}", backend.name()),
+                );
+                Ok(result)
+            });
+            MatrixEntry {
+                version: version.clone(),
+                result,
+            }
+        })
+        .collect()
+}
+
+/// Toolchain factory for real nargo binaries from `source`.
+pub fn nargo_toolchain_factory(
+    source: NargoSource,
+) -> impl Fn(&str) -> BenchResult<Box<dyn Toolchain>> {
+    move |version| {
+        let path = source.resolve(version)?;
+        Ok(Box::new(NargoToolchain::with_path(path)) as Box<dyn Toolchain>)
+    }
+}
+
+/// Toolchain factory for compile configs: `nargo_path` with each config's flags.
+pub fn nargo_flags_factory(
+    nargo_path: PathBuf,
+) -> impl Fn(&CompileConfig) -> BenchResult<Box<dyn Toolchain>> {
+    move |config| {
+        let toolchain =
+            NargoToolchain::with_path(&nargo_path).with_compile_args(config.flags.clone());
+        Ok(Box::new(toolchain) as Box<dyn Toolchain>)
+    }
+}
+
+/// Backend factory for real bb binaries from `source`.
+pub fn bb_backend_factory(
+    source: BbSource,
+    timeout: Duration,
+) -> impl Fn(&str) -> BenchResult<Box<dyn Backend>> {
+    move |version| {
+        let path = source.resolve(version)?;
+        let config = BarretenbergConfig::new(path).with_timeout(timeout);
+        Ok(Box::new(BarretenbergBackend::new(config)) as Box<dyn Backend>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::MockBackend;
+    use crate::engine::toolchain::{CompileArtifacts, MockToolchain};
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!(
+            parse_versions(" 0.38.0,0.39.0 ,nightly,0.38.0").unwrap(),
+            vec!["0.38.0", "0.39.0", "nightly"]
+        );
+        assert!(parse_versions(" , ").is_err());
+        assert!(parse_versions("0.38.0,../../bin").is_err());
+        assert!(parse_versions("..").is_err());
+        assert!(parse_versions("0.38.0 nightly").is_err());
+    }
+
+    #[test]
+    fn test_parse_compile_config() {
+        assert_eq!(
+            CompileConfig::parse("inline=--inliner-aggressiveness 0 --expression-width 4").unwrap(),
+            CompileConfig {
+                name: "inline".to_string(),
+                flags: vec![
+                    "--inliner-aggressiveness".to_string(),
+                    "0".to_string(),
+                    "--expression-width".to_string(),
+                    "4".to_string(),
+                ],
+            }
+        );
+        assert!(CompileConfig::parse("default=").unwrap().flags.is_empty());
+        assert!(CompileConfig::parse("--force-brillig").is_err());
+        assert!(CompileConfig::parse("=--force-brillig").is_err());
+        assert!(CompileConfig::parse("..=--force-brillig").is_err());
+        assert!(CompileConfig::parse("a b=").is_err());
+        assert!(CompileConfig::new("..", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_nargo_source_dir_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("0.38.0/bin")).unwrap();
+        std::fs::write(dir.path().join("0.38.0/bin/nargo"), b"").unwrap();
+        std::fs::write(dir.path().join("nargo-nightly"), b"").unwrap();
+
+        let source = NargoSource::Dir(dir.path().to_path_buf());
+        assert_eq!(
+            source.resolve("0.38.0").unwrap(),
+            dir.path().join("0.38.0/bin/nargo")
+        );
+        assert_eq!(
+            source.resolve("nightly").unwrap(),
+            dir.path().join("nargo-nightly")
+        );
+        assert!(source.resolve("0.1.0").is_err());
+
+        std::fs::create_dir_all(dir.path().join("1.0.0")).unwrap();
+        std::fs::write(dir.path().join("1.0.0/bb"), b"").unwrap();
+        assert_eq!(
+            BbSource::Dir(dir.path().to_path_buf())
+                .resolve("1.0.0")
+                .unwrap(),
+            dir.path().join("1.0.0/bb")
+        );
+    }
+
+    #[test]
+    fn test_run_backend_matrix_tags_each_version() {
+        use crate::backend::{MockConfig, ProveOutput};
+
+        let make = |version: &str| -> BenchResult<Box<dyn Backend>> {
+            let prove_time_ms = match version {
+                "1.0.0" => 100,
+                "1.1.0" => 150,
+                _ => return Err(BenchError::Message("not installed".into())),
+            };
+            Ok(Box::new(MockBackend::new(
+                MockConfig::new("mock-bb").with_prove_output(ProveOutput {
+                    prove_time_ms,
+                    ..ProveOutput::default()
+                }),
+            )))
+        };
+        let versions = vec![
+            "1.0.0".to_string(),
+            "1.1.0".to_string(),
+            "2.0.0".to_string(),
+        ];
+        let inputs = ProveInputs::new("/tmp/test-artifact.json", "circuit");
+
+        let entries = run_backend_matrix(&versions, &make, &MockToolchain::new(), &inputs, 0, 1);
+
+        assert_eq!(entries.len(), 3);
+        let means: Vec<_> = entries[..2]
+            .iter()
+            .map(|e| {
+                let r = e.result.as_ref().unwrap();
+                assert_eq!(
+                    r.record.metadata.get(BACKEND_METADATA_KEY),
+                    Some(&format!("mock-bb@{}", e.version))
+                );
+                r.record.prove_stats.as_ref().unwrap().mean_ms
+            })
+            .collect();
+        assert_eq!(means, vec![100.0, 150.0]);
+        assert!(entries[2].result.is_err());
+    }
+
+    #[test]
+    fn test_run_toolchain_matrix_tags_records_and_restores_artifact() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("circuit");
+        let artifact = project.join("target/circuit.json");
+        std::fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        std::fs::write(&artifact, b"original").unwrap();
+        let compiled = dir.path().join("compiled.json");
+        std::fs::write(&compiled, b"compiled").unwrap();
+
+        let make = |version: &str| -> BenchResult<Box<dyn Toolchain>> {
+            if version == "broken" {
+                return Err(BenchError::Message("not installed".into()));
+            }
+            let mut toolchain = MockToolchain::new().with_version(version);
+            toolchain.compile_output = Some(CompileArtifacts {
+                artifact_path: compiled.clone(),
+                compile_time_ms: 40,
+            });
+            Ok(Box::new(toolchain))
+        };
+        let versions = vec!["0.38.0".to_string(), "broken".to_string()];
+        let inputs = ProveInputs::new(&artifact, "circuit");
+        let work = dir.path().join("work");
+
+        let entries = run_toolchain_matrix(
+            &versions,
+            &make,
+            &MockBackend::default_mock(),
+            &project,
+            &inputs,
+            &work,
+            0,
+            1,
+        );
+
+        assert_eq!(entries.len(), 2);
+        let ok = entries[0].result.as_ref().unwrap();
+        assert_eq!(ok.record.env.nargo_version.as_deref(), Some("0.38.0"));
+        assert_eq!(
+            ok.record
+                .metadata
+                .get(TOOLCHAIN_METADATA_KEY)
+                .map(String::as_str),
+            Some("mock-nargo@0.38.0")
+        );
+        assert_eq!(ok.record.compile_stats.as_ref().unwrap().mean_ms, 40.0);
+        assert!(work.join("0.38.0/compiled.json").exists());
+        assert!(entries[1].result.is_err());
+        assert_eq!(std::fs::read(&artifact).unwrap(), b"original");
+    }
+
+    #[test]
+    fn test_run_compile_matrix_records_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("circuit");
+        let artifact = project.join("target/circuit.json");
+        std::fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        std::fs::write(&artifact, b"original").unwrap();
+        let compiled = dir.path().join("compiled.json");
+        std::fs::write(&compiled, b"compiled").unwrap();
+
+        let make = |config: &CompileConfig| -> BenchResult<Box<dyn Toolchain>> {
+            let mut toolchain = MockToolchain::new();
+            toolchain.compile_output = Some(CompileArtifacts {
+                artifact_path: compiled.clone(),
+                compile_time_ms: if config.flags.is_empty() { 40 } else { 60 },
+            });
+            Ok(Box::new(toolchain))
+        };
+        let configs = vec![
+            CompileConfig::parse("default=").unwrap(),
+            CompileConfig::parse("brillig=--force-brillig").unwrap(),
+        ];
+        let inputs = ProveInputs::new(&artifact, "circuit");
+        let work = dir.path().join("work");
+
+        let entries = run_compile_matrix(
+            &configs,
+            &make,
+            &MockBackend::default_mock(),
+            &project,
+            &inputs,
+            &work,
+            0,
+            1,
+        );
+
+        let records: Vec<_> = entries
+            .iter()
+            .map(|e| &e.result.as_ref().unwrap().record)
+            .collect();
+        assert!(records[0].config.compile_flags.is_empty());
+        assert_eq!(records[1].config.compile_flags, ["--force-brillig"]);
+        assert_eq!(
+            records[1].metadata[COMPILE_CONFIG_METADATA_KEY].as_str(),
+            "brillig"
+        );
+        assert_eq!(records[0].circuit_name, "circuit@default");
+        assert_eq!(records[1].circuit_name, "circuit@brillig");
+        assert_eq!(records[1].compile_stats.as_ref().unwrap().mean_ms, 60.0);
+        assert!(work.join("brillig/compiled.json").exists());
+        assert_eq!(std::fs::read(&artifact).unwrap(), b"original");
+    }
+}
@@ -11,7 +11,12 @@
 //!   Defined in `crate::backend` - examples: `BarretenbergBackend`, `MockBackend`.
 //!
 //! The `workflow` submodule composes these to execute complete benchmark workflows
-//! (e.g., compile -> witness -> prove) while collecting timing statistics.
+//! (e.g., compile -> witness -> prove) while collecting timing statistics. The `matrix`
+//! submodule repeats a workflow across several nargo versions, and `sweep` across
+//! expression widths and bb options. `progress` estimates how long a multi-circuit
+//! run has left from the durations of earlier runs, and `paired` interleaves two
+//! backends on one circuit for an A/B comparison. `program_cache` parses each
+//! program artifact once and shares it across iterations and steps.
 //!
 //! # Boundaries
 //!
@@ -19,12 +24,29 @@
 //! - `Backend` does NOT know about Noir source compilation - that's the Toolchain's job.
 //! - Workflow functions orchestrate both to produce `BenchRecord` outputs.
 
+pub mod matrix;
+pub mod oracle;
+pub mod paired;
+pub mod program_cache;
+pub mod progress;
 pub mod provenance;
+pub mod sweep;
 pub mod toolchain;
 pub mod workflow;
 
 // Re-export key types for convenience
-pub use toolchain::{CompileArtifacts, MockToolchain, NargoToolchain, Toolchain, WitnessArtifact};
+pub use matrix::{
+    BbSource, CompileConfig, MatrixEntry, NargoSource, parse_versions, run_backend_matrix,
+    run_compile_matrix, run_toolchain_matrix,
+};
+pub use paired::{PairedSamples, PairedTest, paired_t_test, run_paired};
+pub use program_cache::{artifact_sha256, load_compiled, load_program};
+pub use progress::{EtaEstimator, Progress};
+pub use sweep::{BackendOption, SweepPoint, run_sweep, sweep_points};
+pub use toolchain::{
+    CompileArtifacts, MockToolchain, NargoToolchain, Toolchain, WitnessArtifact, witness_error,
+};
 pub use workflow::{
-    FullBenchmarkResult, ProveInputs, full_benchmark, prove_only, prove_with_iterations,
+    FullBenchmarkResult, ProveInputs, compile_benchmark, full_benchmark, prove_only,
+    prove_with_iterations,
 };
@@ -0,0 +1,365 @@
+//! Mock oracle responses, latency injection and resolver timing for execution.
+//!
+//! Circuits that call external oracles can't run offline with nargo's default
+//! foreign call handling. A mock file answers those calls from fixed values,
+//! optionally after an artificial delay, so execution is deterministic:
+//!
+//! ```toml
+//! # Delay applied to every foreign call, mocked or not
+//! latency_ms = 1.0
+//!
+//! [[mock]]
+//! function = "get_price"
+//! inputs = ["0x01"]      # optional: only answer calls with these inputs
+//! outputs = ["42", ["1", "2"]]
+//! latency_ms = 25.0      # optional: overrides the default delay
+//! ```
+//!
+//! Values are field elements as decimal or `0x` hex strings; a list of them
+//! is an array parameter. JSON files use the same structure.
+//!
+//! Circuits can instead resolve oracles over JSON-RPC with an external
+//! resolver (nargo's `--oracle-resolver`); `ResolverTiming` measures those
+//! round-trips separately from the rest of execution.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use acvm::acir::brillig::{ForeignCallParam, ForeignCallResult};
+use acvm::pwg::ForeignCallWaitInfo;
+use acvm::{AcirField, FieldElement};
+use nargo::foreign_calls::{ForeignCallError, ForeignCallExecutor};
+use serde::{Deserialize, Serialize};
+
+use crate::{BenchError, BenchResult};
+
+/// One foreign call parameter: a single field or an array of fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MockValue {
+    Single(String),
+    Array(Vec<String>),
+}
+
+impl MockValue {
+    fn to_param(&self) -> BenchResult<ForeignCallParam<FieldElement>> {
+        let field = |s: &str| {
+            FieldElement::try_from_str(s)
+                .ok_or_else(|| BenchError::Message(format!("invalid field element in mock: {s}")))
+        };
+        Ok(match self {
+            MockValue::Single(s) => ForeignCallParam::Single(field(s)?),
+            MockValue::Array(values) => ForeignCallParam::Array(
+                values
+                    .iter()
+                    .map(|s| field(s))
+                    .collect::<BenchResult<_>>()?,
+            ),
+        })
+    }
+}
+
+/// A mocked response for calls to one oracle function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OracleMock {
+    pub function: String,
+    /// Only answer calls with exactly these inputs (any inputs when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<MockValue>>,
+    #[serde(default)]
+    pub outputs: Vec<MockValue>,
+    /// Delay before answering, overriding the file's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+}
+
+/// Contents of a mock file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OracleMocks {
+    /// Delay applied to every foreign call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+    #[serde(default, rename = "mock")]
+    pub mocks: Vec<OracleMock>,
+}
+
+impl OracleMocks {
+    /// Load mocks from a `.json` file, or TOML otherwise.
+    pub fn load(path: &Path) -> BenchResult<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+        let mocks: OracleMocks = if path.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str(&text).map_err(|e| {
+                BenchError::Message(format!("failed to parse {}: {e}", path.display()))
+            })?
+        } else {
+            toml::from_str(&text).map_err(|e| {
+                BenchError::Message(format!("failed to parse {}: {e}", path.display()))
+            })?
+        };
+        // Reject bad field elements up front rather than mid-execution
+        for mock in &mocks.mocks {
+            for value in mock.inputs.iter().flatten().chain(&mock.outputs) {
+                value.to_param()?;
+            }
+        }
+        Ok(mocks)
+    }
+}
+
+struct ResolvedMock {
+    function: String,
+    inputs: Option<Vec<ForeignCallParam<FieldElement>>>,
+    outputs: Vec<ForeignCallParam<FieldElement>>,
+    latency: Option<Duration>,
+}
+
+fn latency(ms: Option<f64>) -> Option<Duration> {
+    ms.filter(|ms| *ms > 0.0)
+        .map(|ms| Duration::from_secs_f64(ms / 1000.0))
+}
+
+/// Foreign call executor answering mocked oracles and delaying every call.
+///
+/// Calls without a matching mock are forwarded to `inner`. The first mock in
+/// file order whose function (and inputs, when given) match wins.
+pub struct MockForeignCalls<E> {
+    inner: E,
+    mocks: Vec<ResolvedMock>,
+    latency: Option<Duration>,
+}
+
+impl<E> MockForeignCalls<E> {
+    /// Wrap `inner`. `latency_ms` overrides the file's default delay.
+    pub fn new(inner: E, mocks: &OracleMocks, latency_ms: Option<f64>) -> BenchResult<Self> {
+        let params = |values: &[MockValue]| {
+            values
+                .iter()
+                .map(MockValue::to_param)
+                .collect::<BenchResult<Vec<_>>>()
+        };
+        let resolved = mocks
+            .mocks
+            .iter()
+            .map(|m| {
+                Ok(ResolvedMock {
+                    function: m.function.clone(),
+                    inputs: m.inputs.as_deref().map(params).transpose()?,
+                    outputs: params(&m.outputs)?,
+                    latency: latency(m.latency_ms),
+                })
+            })
+            .collect::<BenchResult<_>>()?;
+        Ok(MockForeignCalls {
+            inner,
+            mocks: resolved,
+            latency: latency(latency_ms.or(mocks.latency_ms)),
+        })
+    }
+}
+
+impl<E> MockForeignCalls<E> {
+    /// The executor unmocked calls are forwarded to.
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+}
+
+impl<E: ForeignCallExecutor<FieldElement>> ForeignCallExecutor<FieldElement>
+    for MockForeignCalls<E>
+{
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+        let mock = self.mocks.iter().find(|m| {
+            m.function == foreign_call.function
+                && m.inputs
+                    .as_ref()
+                    .is_none_or(|inputs| *inputs == foreign_call.inputs)
+        });
+        if let Some(delay) = mock.and_then(|m| m.latency).or(self.latency) {
+            std::thread::sleep(delay);
+        }
+        match mock {
+            Some(m) => Ok(ForeignCallResult {
+                values: m.outputs.clone(),
+            }),
+            None => self.inner.execute(foreign_call),
+        }
+    }
+}
+
+/// Foreign calls nargo answers itself, without asking the resolver.
+const BUILTIN_FOREIGN_CALLS: &[&str] = &[
+    "print",
+    "create_mock",
+    "set_mock_params",
+    "get_mock_last_params",
+    "set_mock_returns",
+    "set_mock_times",
+    "clear_mock",
+];
+
+/// Foreign call executor wrapper timing the calls that reach the external
+/// oracle resolver, i.e. everything but nargo's built-in calls.
+pub struct ResolverTiming<E> {
+    inner: E,
+    calls: u64,
+    elapsed: Duration,
+}
+
+impl<E> ResolverTiming<E> {
+    pub fn new(inner: E) -> Self {
+        ResolverTiming {
+            inner,
+            calls: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Number of resolver round-trips.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Total time spent in resolver round-trips, in milliseconds.
+    pub fn elapsed_ms(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0
+    }
+}
+
+impl<F, E: ForeignCallExecutor<F>> ForeignCallExecutor<F> for ResolverTiming<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<F>,
+    ) -> Result<ForeignCallResult<F>, ForeignCallError> {
+        if BUILTIN_FOREIGN_CALLS.contains(&foreign_call.function.as_str()) {
+            return self.inner.execute(foreign_call);
+        }
+        let start = Instant::now();
+        let result = self.inner.execute(foreign_call);
+        self.elapsed += start.elapsed();
+        self.calls += 1;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Executor that fails every call, standing in for "no oracle resolver".
+    struct Unreachable;
+
+    impl ForeignCallExecutor<FieldElement> for Unreachable {
+        fn execute(
+            &mut self,
+            foreign_call: &ForeignCallWaitInfo<FieldElement>,
+        ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+            Err(ForeignCallError::NoHandler(foreign_call.function.clone()))
+        }
+    }
+
+    fn call(
+        function: &str,
+        inputs: Vec<ForeignCallParam<FieldElement>>,
+    ) -> ForeignCallWaitInfo<FieldElement> {
+        ForeignCallWaitInfo {
+            function: function.to_string(),
+            inputs,
+        }
+    }
+
+    #[test]
+    fn test_mocks_answer_matching_calls() {
+        let mocks: OracleMocks = toml::from_str(
+            r#"
+[[mock]]
+function = "get_price"
+inputs = ["0x01"]
+outputs = ["42", ["1", "2"]]
+
+[[mock]]
+function = "get_price"
+outputs = ["7"]
+"#,
+        )
+        .unwrap();
+        let mut executor = MockForeignCalls::new(Unreachable, &mocks, None).unwrap();
+        let one = ForeignCallParam::Single(FieldElement::from(1u128));
+
+        let result = executor.execute(&call("get_price", vec![one])).unwrap();
+        assert_eq!(
+            result.values,
+            vec![
+                ForeignCallParam::Single(FieldElement::from(42u128)),
+                ForeignCallParam::Array(vec![FieldElement::from(1u128), FieldElement::from(2u128)]),
+            ]
+        );
+
+        // Other inputs fall through to the catch-all mock
+        let two = ForeignCallParam::Single(FieldElement::from(2u128));
+        let result = executor.execute(&call("get_price", vec![two])).unwrap();
+        assert_eq!(
+            result.values,
+            vec![ForeignCallParam::Single(FieldElement::from(7u128))]
+        );
+
+        // Unmocked functions go to the inner executor
+        assert!(executor.execute(&call("get_time", vec![])).is_err());
+    }
+
+    #[test]
+    fn test_latency_is_injected() {
+        let mocks: OracleMocks = serde_json::from_str(
+            r#"{"latency_ms": 1.0, "mock": [{"function": "slow", "outputs": [], "latency_ms": 30.0}]}"#,
+        )
+        .unwrap();
+        let mut executor = MockForeignCalls::new(Unreachable, &mocks, None).unwrap();
+
+        let start = std::time::Instant::now();
+        executor.execute(&call("slow", vec![])).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(30));
+
+        // The CLI override replaces the default but not per-mock delays
+        let executor = MockForeignCalls::new(Unreachable, &mocks, Some(5.0)).unwrap();
+        let delay = executor.latency.unwrap().as_secs_f64();
+        assert!((delay - 0.005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_resolver_timing_skips_builtin_calls() {
+        let mocks: OracleMocks = serde_json::from_str(
+            r#"{"mock": [{"function": "get_price", "outputs": [], "latency_ms": 10.0}, {"function": "print", "outputs": []}]}"#,
+        )
+        .unwrap();
+        let mut resolver =
+            ResolverTiming::new(MockForeignCalls::new(Unreachable, &mocks, None).unwrap());
+
+        resolver.execute(&call("print", vec![])).unwrap();
+        assert_eq!(resolver.calls(), 0);
+        resolver.execute(&call("get_price", vec![])).unwrap();
+        assert_eq!(resolver.calls(), 1);
+        assert!(resolver.elapsed_ms() >= 10.0);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mocks.toml");
+        std::fs::write(
+            &path,
+            "[[mock]]\nfunction = \"f\"\noutputs = [\"not a field\"]\n",
+        )
+        .unwrap();
+        assert!(OracleMocks::load(&path).is_err());
+
+        std::fs::write(&path, "[[mock]]\nfunction = \"f\"\noutputs = [\"0x2a\"]\n").unwrap();
+        let mocks = OracleMocks::load(&path).unwrap();
+        assert_eq!(
+            mocks.mocks[0].outputs,
+            vec![MockValue::Single("0x2a".to_string())]
+        );
+    }
+}
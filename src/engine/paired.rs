@@ -0,0 +1,315 @@
+//! Paired A/B benchmarking of two backends in one session.
+//!
+//! Comparing two separate runs mixes the change under test with whatever the
+//! machine did in between (thermal state, background load, frequency
+//! scaling). A paired run interleaves the two instead, so each pair is
+//! measured under nearly the same conditions, and tests the per-pair
+//! differences: a paired t-test on `contender - baseline` prove times.
+//!
+//! The order within a pair alternates, A,B then B,A (ABBA), so a backend
+//! that always ran second does not consistently get the warmer caches.
+//!
+//! Both backends prove the same witness in each pair, so witness generation
+//! is not part of the comparison.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::backend::Backend;
+use crate::report::{CompareStatus, percent_change};
+use crate::{BenchError, BenchResult};
+
+use super::toolchain::Toolchain;
+use super::workflow::ProveInputs;
+
+/// Prove times of interleaved baseline and contender runs; entry `i` of
+/// both belongs to the same pair.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PairedSamples {
+    pub baseline_ms: Vec<f64>,
+    pub contender_ms: Vec<f64>,
+}
+
+/// Whether pair `i` proves with the contender first (ABBA order).
+fn contender_first(i: usize) -> bool {
+    i % 2 == 1
+}
+
+/// Run `warmup` unmeasured and `pairs` measured baseline/contender pairs.
+pub fn run_paired(
+    toolchain: &dyn Toolchain,
+    baseline: &dyn Backend,
+    contender: &dyn Backend,
+    inputs: &ProveInputs,
+    warmup: usize,
+    pairs: usize,
+) -> BenchResult<PairedSamples> {
+    if pairs < 2 {
+        return Err(BenchError::Message(
+            "a paired comparison needs at least 2 pairs".into(),
+        ));
+    }
+    let prover_toml = inputs
+        .prover_toml
+        .as_deref()
+        .unwrap_or(Path::new("Prover.toml"));
+    let mut samples = PairedSamples::default();
+
+    for i in 0..warmup + pairs {
+        let witness = toolchain.gen_witness(&inputs.artifact_path, prover_toml)?;
+        let prove = |backend: &dyn Backend| {
+            backend
+                .prove(
+                    &inputs.artifact_path,
+                    Some(&witness.witness_path),
+                    inputs.timeout,
+                )
+                .map(|output| output.prove_time_ms as f64)
+        };
+        let prove_baseline = || {
+            prove(baseline).map_err(|e| BenchError::Message(format!("baseline prove failed: {e}")))
+        };
+        let prove_contender = || {
+            prove(contender)
+                .map_err(|e| BenchError::Message(format!("contender prove failed: {e}")))
+        };
+        let pair = if contender_first(i) {
+            prove_contender().and_then(|b| prove_baseline().map(|a| (a, b)))
+        } else {
+            prove_baseline().and_then(|a| prove_contender().map(|b| (a, b)))
+        };
+        let _ = std::fs::remove_file(&witness.witness_path);
+        let (a, b) = pair?;
+
+        if i >= warmup {
+            samples.baseline_ms.push(a);
+            samples.contender_ms.push(b);
+        }
+    }
+    Ok(samples)
+}
+
+/// Paired t-test of contender against baseline times.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PairedTest {
+    pub pairs: usize,
+    pub baseline_mean_ms: f64,
+    pub contender_mean_ms: f64,
+    /// Mean of `contender - baseline` over the pairs
+    pub mean_diff_ms: f64,
+    /// Mean difference relative to the baseline mean
+    pub percent: f64,
+    /// 95% confidence interval of the mean difference
+    pub ci95_ms: (f64, f64),
+    pub t_stat: f64,
+    /// Two-sided p-value
+    pub p_value: f64,
+    /// Pairs in which the contender was faster
+    pub contender_faster: usize,
+}
+
+impl PairedTest {
+    /// Regression or improvement when the difference is significant at
+    /// `alpha` and larger than `threshold` percent.
+    pub fn status(&self, alpha: f64, threshold: f64) -> CompareStatus {
+        if self.p_value >= alpha {
+            CompareStatus::Unchanged
+        } else if self.percent > threshold {
+            CompareStatus::Regression
+        } else if self.percent < -threshold {
+            CompareStatus::Improvement
+        } else {
+            CompareStatus::Unchanged
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Paired t-test on `samples`; `None` with fewer than 2 pairs.
+pub fn paired_t_test(samples: &PairedSamples) -> Option<PairedTest> {
+    let n = samples.baseline_ms.len().min(samples.contender_ms.len());
+    if n < 2 {
+        return None;
+    }
+    let (baseline, contender) = (&samples.baseline_ms[..n], &samples.contender_ms[..n]);
+    let diffs: Vec<f64> = contender.iter().zip(baseline).map(|(b, a)| b - a).collect();
+    let mean_diff = mean(&diffs);
+    let variance = diffs.iter().map(|d| (d - mean_diff).powi(2)).sum::<f64>() / (n - 1) as f64;
+    let std_err = (variance / n as f64).sqrt();
+    let df = (n - 1) as f64;
+
+    let (t_stat, p_value) = if std_err > 0.0 {
+        let t = mean_diff / std_err;
+        (t, student_t_two_sided(t, df))
+    } else if mean_diff == 0.0 {
+        (0.0, 1.0)
+    } else {
+        // Every pair differs by exactly the same amount
+        (mean_diff.signum() * f64::INFINITY, 0.0)
+    };
+    let margin = student_t_critical(0.05, df) * std_err;
+    let (baseline_mean, contender_mean) = (mean(baseline), mean(contender));
+
+    Some(PairedTest {
+        pairs: n,
+        baseline_mean_ms: baseline_mean,
+        contender_mean_ms: contender_mean,
+        mean_diff_ms: mean_diff,
+        percent: percent_change(baseline_mean, contender_mean),
+        ci95_ms: (mean_diff - margin, mean_diff + margin),
+        t_stat,
+        p_value,
+        contender_faster: diffs.iter().filter(|d| **d < 0.0).count(),
+    })
+}
+
+/// Two-sided p-value of `t` under Student's t with `df` degrees of freedom.
+fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// `t` such that the two-sided p-value is `alpha`, by bisection.
+fn student_t_critical(alpha: f64, df: f64) -> f64 {
+    let (mut lo, mut hi) = (0.0, 1000.0);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if student_t_two_sided(mid, df) > alpha {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+/// ln Γ(x) for x > 0: shifted up to 10 and Stirling's series from there.
+fn ln_gamma(x: f64) -> f64 {
+    let mut shift = 0.0;
+    let mut z = x;
+    while z < 10.0 {
+        shift += z.ln();
+        z += 1.0;
+    }
+    let z2 = z * z;
+    let series = 1.0 / (12.0 * z) - 1.0 / (360.0 * z * z2) + 1.0 / (1260.0 * z * z2 * z2);
+    (z - 0.5) * z.ln() - z + 0.5 * (2.0 * std::f64::consts::PI).ln() + series - shift
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges fast on this side of the mean
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function (modified Lentz).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let nonzero = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = 1.0;
+    let mut d = 1.0 / nonzero(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / nonzero(1.0 + even * d);
+        c = nonzero(1.0 + even / c);
+        h *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / nonzero(1.0 + odd * d);
+        c = nonzero(1.0 + odd / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-14 {
+            break;
+        }
+    }
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig, ProveOutput};
+    use crate::engine::MockToolchain;
+
+    #[test]
+    fn test_student_t_matches_tables() {
+        // t(0.975, 10) = 2.228, t(0.975, 4) = 2.776
+        assert!((student_t_two_sided(2.228, 10.0) - 0.05).abs() < 1e-3);
+        assert!((student_t_critical(0.05, 4.0) - 2.776).abs() < 1e-3);
+        assert_eq!(student_t_two_sided(0.0, 5.0), 1.0);
+    }
+
+    #[test]
+    fn test_paired_test_sees_small_shift_under_drift() {
+        // Both sides drift by 50% over the session; the contender is
+        // consistently 2% slower within each pair
+        let baseline: Vec<f64> = (0..10).map(|i| 1000.0 + 50.0 * i as f64).collect();
+        let samples = PairedSamples {
+            contender_ms: baseline
+                .iter()
+                .enumerate()
+                .map(|(i, a)| a * 1.02 + if i % 2 == 0 { 1.0 } else { -1.0 })
+                .collect(),
+            baseline_ms: baseline,
+        };
+        let test = paired_t_test(&samples).unwrap();
+        assert_eq!(test.pairs, 10);
+        assert_eq!(test.contender_faster, 0);
+        assert!(test.p_value < 1e-6);
+        assert!(test.ci95_ms.0 > 0.0);
+        assert!((test.percent - 2.0).abs() < 0.1);
+        assert_eq!(test.status(0.05, 1.0), CompareStatus::Regression);
+        assert_eq!(test.status(0.05, 5.0), CompareStatus::Unchanged);
+
+        let same = PairedSamples {
+            baseline_ms: vec![100.0, 200.0],
+            contender_ms: vec![100.0, 200.0],
+        };
+        let test = paired_t_test(&same).unwrap();
+        assert_eq!(test.p_value, 1.0);
+        assert_eq!(test.status(0.05, 0.0), CompareStatus::Unchanged);
+    }
+
+    #[test]
+    fn test_run_paired_interleaves_backends() {
+        let toolchain = MockToolchain::new();
+        let backend = |ms| {
+            MockBackend::new(MockConfig::new("mock").with_prove_output(ProveOutput {
+                prove_time_ms: ms,
+                ..ProveOutput::default()
+            }))
+        };
+        let inputs = ProveInputs::new("/mock/artifact.json", "test-circuit");
+        let samples = run_paired(&toolchain, &backend(100), &backend(90), &inputs, 1, 3).unwrap();
+        assert_eq!(samples.baseline_ms, vec![100.0; 3]);
+        assert_eq!(samples.contender_ms, vec![90.0; 3]);
+
+        let failing = MockBackend::new(MockConfig::new("mock").prove_fails());
+        let err = run_paired(&toolchain, &backend(100), &failing, &inputs, 0, 2).unwrap_err();
+        assert!(err.to_string().contains("contender prove failed"));
+        assert!(run_paired(&toolchain, &backend(100), &failing, &inputs, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_pair_order_alternates() {
+        let order: Vec<bool> = (0..4).map(contender_first).collect();
+        assert_eq!(order, vec![false, true, false, true]);
+    }
+}
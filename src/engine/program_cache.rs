@@ -0,0 +1,263 @@
+//! Parsed program artifacts shared across iterations.
+//!
+//! Reading and deserializing `program.json` takes milliseconds for a large
+//! circuit, which used to land in every iteration of `prove` and in each of
+//! the exec, gates and prove steps of a run. Artifacts are now parsed once per
+//! process and handed out as `Arc`s; an entry is dropped when the file's size
+//! or modification time changes, so a recompiled circuit is read again.
+//! Modification times are only as precise as the filesystem (a second or two
+//! on some), so an artifact read within [`MTIME_GRANULARITY`] of its last
+//! change could be rewritten with the same size and time: such entries have
+//! their contents hashed again on reuse until that window has passed. The
+//! artifact's sha256, which every report carries, is hashed from the bytes
+//! that were parsed and kept with it.
+//!
+//! Artifacts of at least [`MMAP_THRESHOLD_BYTES`] are memory-mapped instead of
+//! copied into a buffer before parsing. The map is only held while parsing,
+//! but a file truncated by another process during that time (say, a `nargo
+//! compile` running alongside) makes the read of the missing pages raise
+//! SIGBUS, which kills the process. Smaller artifacts, which are most of them,
+//! are always copied.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use noirc_artifacts::program::{CompiledProgram, ProgramArtifact};
+
+use crate::{BenchError, BenchResult};
+
+/// Artifacts at least this large are memory-mapped for parsing.
+pub const MMAP_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// Coarsest modification time resolution expected of a filesystem. A file
+/// rewritten within this long of being read may keep its size and time.
+pub const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// Programs kept parsed at once; the oldest is dropped first.
+const MAX_CACHED_PROGRAMS: usize = 8;
+
+/// A parsed artifact, its file hash and, once asked for, its
+/// `CompiledProgram` form.
+struct CachedProgram {
+    path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+    /// Whether a rewrite could still hide behind an unchanged size and
+    /// modification time, so the contents must be compared on reuse
+    racy: AtomicBool,
+    sha256: String,
+    artifact: Arc<ProgramArtifact>,
+    compiled: OnceLock<Arc<CompiledProgram>>,
+}
+
+fn cache() -> &'static Mutex<VecDeque<Arc<CachedProgram>>> {
+    static CACHE: OnceLock<Mutex<VecDeque<Arc<CachedProgram>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// Whether a file modified at `modified` and read at `read_at` could be
+/// rewritten without its size or modification time changing.
+fn is_racy(modified: Option<SystemTime>, read_at: SystemTime) -> bool {
+    match modified {
+        Some(modified) => read_at
+            .duration_since(modified)
+            .is_ok_and(|age| age < MTIME_GRANULARITY),
+        None => true,
+    }
+}
+
+/// Whether a cached read of `path` with hash `sha256` still holds, its size
+/// and modification time being unchanged. A racy entry is hashed again, and
+/// stops being racy once it's still unchanged past the window, as any later
+/// rewrite moves the modification time.
+fn still_current(
+    path: &Path,
+    sha256: &str,
+    racy: &AtomicBool,
+    modified: Option<SystemTime>,
+    read_at: SystemTime,
+) -> bool {
+    if !racy.load(Ordering::Relaxed) {
+        return true;
+    }
+    if crate::file_sha256(path).as_deref() != Some(sha256) {
+        return false;
+    }
+    if !is_racy(modified, read_at) {
+        racy.store(false, Ordering::Relaxed);
+    }
+    true
+}
+
+/// Read the whole file, memory-mapping it when it's at least `mmap_threshold`
+/// bytes.
+fn with_file_bytes<R>(
+    path: &Path,
+    mmap_threshold: u64,
+    f: impl FnOnce(&[u8]) -> R,
+) -> std::io::Result<R> {
+    let file = std::fs::File::open(path)?;
+    if file.metadata()?.len() >= mmap_threshold {
+        // SAFETY: the map is read-only and dropped once `f` returns. It is not
+        // protected against other processes: contents changed in place while
+        // mapped are caught by the size and time check after parsing, but a
+        // truncation makes reads past the new end raise SIGBUS, which aborts
+        // the process. Only large artifacts take this path (see module docs).
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(f(&map))
+    } else {
+        let bytes = std::fs::read(path)?;
+        Ok(f(&bytes))
+    }
+}
+
+/// Size and modification time of the file at `path`.
+fn stamp(path: &Path) -> BenchResult<(u64, Option<SystemTime>)> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+fn load_entry(path: &Path) -> BenchResult<Arc<CachedProgram>> {
+    let path = std::fs::canonicalize(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+    let read_at = SystemTime::now();
+    let (len, modified) = stamp(&path)?;
+
+    let mut cached = cache().lock().unwrap_or_else(|e| e.into_inner());
+    cached.retain(|e| e.path != path || (e.len == len && e.modified == modified));
+    if let Some(entry) = cached.iter().find(|e| e.path == path).cloned() {
+        if still_current(&path, &entry.sha256, &entry.racy, modified, read_at) {
+            return Ok(entry);
+        }
+        cached.retain(|e| e.path != path);
+    }
+
+    crate::core::hashing::warn_if_large_artifact(&path, len);
+    let (sha256, artifact) = with_file_bytes(&path, MMAP_THRESHOLD_BYTES, |bytes| {
+        (
+            crate::sha256_hex(bytes),
+            serde_json::from_slice::<ProgramArtifact>(bytes),
+        )
+    })
+    .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+    let artifact = artifact
+        .map_err(|e| BenchError::Message(format!("failed to parse {}: {e}", path.display())))?;
+    if stamp(&path)? != (len, modified) {
+        return Err(BenchError::Message(format!(
+            "{} changed while it was being read",
+            path.display()
+        )));
+    }
+    let entry = Arc::new(CachedProgram {
+        path,
+        len,
+        modified,
+        racy: AtomicBool::new(is_racy(modified, read_at)),
+        sha256,
+        artifact: Arc::new(artifact),
+        compiled: OnceLock::new(),
+    });
+    if cached.len() >= MAX_CACHED_PROGRAMS {
+        cached.pop_front();
+    }
+    cached.push_back(entry.clone());
+    Ok(entry)
+}
+
+/// The parsed artifact at `path`, shared with earlier loads of the same file.
+pub fn load_program(path: &Path) -> BenchResult<Arc<ProgramArtifact>> {
+    Ok(load_entry(path)?.artifact.clone())
+}
+
+/// The artifact at `path` as a `CompiledProgram`, ready for execution.
+pub fn load_compiled(path: &Path) -> BenchResult<Arc<CompiledProgram>> {
+    let entry = load_entry(path)?;
+    Ok(entry
+        .compiled
+        .get_or_init(|| Arc::new(entry.artifact.as_ref().clone().into()))
+        .clone())
+}
+
+/// sha256 of the artifact file at `path`, as parsed; `None` if it can't be
+/// loaded.
+pub fn artifact_sha256(path: &Path) -> Option<String> {
+    Some(load_entry(path).ok()?.sha256.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_bytes_read_or_mapped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("program.json");
+        std::fs::write(&path, b"{\"noir_version\":\"1.0.0\"}").unwrap();
+
+        let parse = |bytes: &[u8]| serde_json::from_slice::<serde_json::Value>(bytes).unwrap();
+        let read = with_file_bytes(&path, u64::MAX, parse).unwrap();
+        let mapped = with_file_bytes(&path, 0, parse).unwrap();
+        assert_eq!(read, mapped);
+        assert_eq!(mapped["noir_version"], "1.0.0");
+    }
+
+    #[test]
+    fn test_is_racy() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        assert!(is_racy(Some(modified), modified));
+        assert!(is_racy(
+            Some(modified),
+            modified + Duration::from_millis(1500)
+        ));
+        assert!(!is_racy(Some(modified), modified + MTIME_GRANULARITY));
+        assert!(is_racy(None, modified));
+    }
+
+    #[test]
+    fn test_same_size_rewrite_is_noticed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("program.json");
+        std::fs::write(&path, b"{\"v\":1}").unwrap();
+        let modified = std::fs::metadata(&path).unwrap().modified().ok();
+        let sha256 = crate::sha256_hex(b"{\"v\":1}");
+
+        let racy = AtomicBool::new(true);
+        assert!(still_current(
+            &path,
+            &sha256,
+            &racy,
+            modified,
+            SystemTime::now()
+        ));
+        std::fs::write(&path, b"{\"v\":2}").unwrap();
+        assert!(!still_current(
+            &path,
+            &sha256,
+            &racy,
+            modified,
+            SystemTime::now()
+        ));
+
+        // Hashed again only while inside the window
+        std::fs::write(&path, b"{\"v\":1}").unwrap();
+        let later = SystemTime::now() + MTIME_GRANULARITY;
+        assert!(still_current(&path, &sha256, &racy, modified, later));
+        assert!(!racy.load(Ordering::Relaxed));
+        std::fs::write(&path, b"{\"v\":2}").unwrap();
+        assert!(still_current(&path, &sha256, &racy, modified, later));
+    }
+
+    #[test]
+    fn test_load_program_reports_bad_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("program.json");
+        std::fs::write(&path, b"not json").unwrap();
+        let err = load_program(&path).unwrap_err();
+        assert!(err.to_string().contains("failed to parse"));
+        assert!(load_program(&dir.path().join("missing.json")).is_err());
+    }
+}
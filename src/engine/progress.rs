@@ -0,0 +1,331 @@
+//! Progress and ETA for multi-circuit runs.
+//!
+//! With a history of earlier runs, the time left is the sum of the median
+//! historical duration of each pending circuit (by name and params), so the
+//! estimate shrinks as circuits finish. A run's duration is taken from its
+//! record: the time of every timed step across all of its iterations.
+//! Circuits without history are counted but not estimated.
+//!
+//! Progress is logged to stderr and, when asked, appended as JSON lines to
+//! an event file that dashboards or wrappers can follow:
+//!
+//! ```text
+//! {"event":"start","timestamp":"...","done":0,"total":6,"eta_secs":412.0,"unestimated":1}
+//! {"event":"circuit_done","timestamp":"...","circuit":"merkle","params":16,"done":1,"total":6,"eta_secs":350.5,"unestimated":1}
+//! ```
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::core::{BenchRecord, TimingStat};
+
+/// Estimated wall time of the run behind `record`, in milliseconds, or
+/// `None` if it has no timings.
+pub fn record_duration_ms(record: &BenchRecord) -> Option<f64> {
+    let warmup = record.config.warmup_iterations as f64;
+    let total = |stat: &Option<TimingStat>, extra_runs: f64| {
+        stat.as_ref()
+            .map(|s| s.mean_ms * (s.iterations as f64 + extra_runs))
+    };
+    let parts = [
+        total(&record.compile_stats, 0.0),
+        total(&record.witness_stats, warmup),
+        total(&record.prove_stats, warmup),
+        total(&record.verify_stats, 0.0),
+        total(&record.gates_stats, 0.0),
+        // Key generation stats already include warmup runs
+        total(&record.vk_gen_stats, 0.0),
+        record.backend_setup_ms,
+        record.probe_ms,
+    ];
+    parts
+        .iter()
+        .any(Option::is_some)
+        .then(|| parts.iter().flatten().sum())
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let n = values.len();
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    }
+}
+
+/// Median historical durations by circuit and params.
+#[derive(Debug, Clone, Default)]
+pub struct EtaEstimator {
+    medians: HashMap<(String, Option<u64>), f64>,
+}
+
+impl EtaEstimator {
+    /// Learn durations from the successful, non-anomalous runs in `history`.
+    pub fn from_history(history: &[BenchRecord]) -> Self {
+        let mut durations: HashMap<(String, Option<u64>), Vec<f64>> = HashMap::new();
+        for record in history.iter().filter(|r| r.error.is_none() && !r.anomaly) {
+            if let Some(ms) = record_duration_ms(record) {
+                durations
+                    .entry((record.circuit_name.clone(), record.params))
+                    .or_default()
+                    .push(ms);
+            }
+        }
+        EtaEstimator {
+            medians: durations
+                .into_iter()
+                .map(|(key, values)| (key, median(values)))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.medians.is_empty()
+    }
+
+    /// Median duration of `circuit` with `params`, if it ran before.
+    pub fn estimate_ms(&self, circuit: &str, params: Option<u64>) -> Option<f64> {
+        self.medians.get(&(circuit.to_string(), params)).copied()
+    }
+}
+
+/// Format a duration for logs: `37s`, `4m12s`, `1h02m`.
+pub fn format_eta(ms: f64) -> String {
+    let secs = (ms / 1000.0).round() as u64;
+    match secs {
+        s if s >= 3600 => format!("{}h{:02}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{s}s"),
+    }
+}
+
+/// One line of the progress event file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProgressEvent {
+    /// `start`, `circuit_done` or `finish`
+    pub event: &'static str,
+    pub timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<u64>,
+    pub done: usize,
+    pub total: usize,
+    /// Estimated seconds left for the estimated pending circuits
+    pub eta_secs: Option<f64>,
+    /// Pending circuits without history, not part of `eta_secs`
+    pub unestimated: usize,
+}
+
+/// Progress of a run over a known list of circuits.
+pub struct Progress {
+    label: String,
+    pending: Vec<(String, Option<u64>)>,
+    total: usize,
+    estimator: EtaEstimator,
+    events: Option<PathBuf>,
+}
+
+impl Progress {
+    /// Track `targets` (circuit, params), logging lines prefixed with `label`.
+    pub fn new(
+        label: impl Into<String>,
+        targets: Vec<(String, Option<u64>)>,
+        estimator: EtaEstimator,
+    ) -> Self {
+        Progress {
+            label: label.into(),
+            total: targets.len(),
+            pending: targets,
+            estimator,
+            events: None,
+        }
+    }
+
+    /// Also append every event as a JSON line to `path`.
+    pub fn with_events(mut self, path: impl Into<PathBuf>) -> Self {
+        self.events = Some(path.into());
+        self
+    }
+
+    /// Estimated time left and the number of pending circuits without one.
+    pub fn remaining(&self) -> (Option<f64>, usize) {
+        let estimates: Vec<Option<f64>> = self
+            .pending
+            .iter()
+            .map(|(circuit, params)| self.estimator.estimate_ms(circuit, *params))
+            .collect();
+        let unestimated = estimates.iter().filter(|e| e.is_none()).count();
+        let eta = (!self.estimator.is_empty()).then(|| estimates.iter().flatten().sum());
+        (eta, unestimated)
+    }
+
+    fn event(&self, event: &'static str, circuit: Option<(&str, Option<u64>)>) -> ProgressEvent {
+        let (eta_ms, unestimated) = self.remaining();
+        ProgressEvent {
+            event,
+            timestamp: time::OffsetDateTime::now_utc()
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default(),
+            circuit: circuit.map(|(c, _)| c.to_string()),
+            params: circuit.and_then(|(_, p)| p),
+            done: self.total - self.pending.len(),
+            total: self.total,
+            eta_secs: eta_ms.map(|ms| ms / 1000.0),
+            unestimated,
+        }
+    }
+
+    fn emit(&self, event: &ProgressEvent) {
+        let eta = match (event.eta_secs, event.unestimated) {
+            (Some(secs), 0) => format!("ETA {}", format_eta(secs * 1000.0)),
+            (Some(secs), n) => format!(
+                "ETA {} + {n} circuit(s) without history",
+                format_eta(secs * 1000.0)
+            ),
+            (None, _) => "no history for an ETA".to_string(),
+        };
+        match &event.circuit {
+            Some(circuit) => eprintln!(
+                "{}: [{}/{}] {circuit} done, {eta}",
+                self.label, event.done, event.total
+            ),
+            None if event.event == "start" => {
+                eprintln!("{}: {} circuit(s), {eta}", self.label, event.total)
+            }
+            None => {}
+        }
+
+        let Some(path) = &self.events else { return };
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut f| {
+                writeln!(
+                    f,
+                    "{}",
+                    serde_json::to_string(event).expect("event serializes")
+                )
+            });
+        if let Err(e) = written {
+            eprintln!(
+                "warning: failed to write progress event to {}: {e}",
+                path.display()
+            );
+        }
+    }
+
+    /// Report the start of the run.
+    pub fn start(&self) {
+        self.emit(&self.event("start", None));
+    }
+
+    /// Mark `circuit` with `params` as done and report the new ETA.
+    pub fn circuit_done(&mut self, circuit: &str, params: Option<u64>) {
+        if let Some(i) = self
+            .pending
+            .iter()
+            .position(|(c, p)| c == circuit && *p == params)
+        {
+            self.pending.remove(i);
+        }
+        self.emit(&self.event("circuit_done", Some((circuit, params))));
+    }
+
+    /// Report the end of the run.
+    pub fn finish(&self) {
+        self.emit(&self.event("finish", None));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig};
+
+    fn record(circuit: &str, params: Option<u64>, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            circuit.to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig {
+                warmup_iterations: 1,
+                measured_iterations: 2,
+                ..RunConfig::default()
+            },
+        );
+        r.params = params;
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms, prove_ms]));
+        r
+    }
+
+    #[test]
+    fn test_record_duration_counts_warmup_runs() {
+        let mut r = record("merkle", None, 1000.0);
+        r.verify_stats = Some(TimingStat::from_samples(&[50.0]));
+        // 2 measured + 1 warmup prove, one verify
+        assert_eq!(record_duration_ms(&r), Some(3050.0));
+
+        r.prove_stats = None;
+        r.verify_stats = None;
+        assert_eq!(record_duration_ms(&r), None);
+    }
+
+    #[test]
+    fn test_eta_sums_medians_of_pending_circuits() {
+        let history = vec![
+            record("merkle", Some(16), 1000.0),
+            record("merkle", Some(16), 3000.0),
+            record("merkle", Some(16), 2000.0),
+            record("ecdsa", None, 500.0),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let events = dir.path().join("progress.jsonl");
+        let mut progress = Progress::new(
+            "bench run-all",
+            vec![
+                ("merkle".to_string(), Some(16)),
+                ("ecdsa".to_string(), None),
+                ("rollup".to_string(), None),
+            ],
+            EtaEstimator::from_history(&history),
+        )
+        .with_events(&events);
+
+        // merkle: median of 3000/6000/9000; ecdsa: 1500
+        assert_eq!(progress.remaining(), (Some(7500.0), 1));
+        progress.start();
+        progress.circuit_done("merkle", Some(16));
+        assert_eq!(progress.remaining(), (Some(1500.0), 1));
+        progress.finish();
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&events)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["event"], "start");
+        assert_eq!(lines[0]["eta_secs"], 7.5);
+        assert_eq!(lines[1]["circuit"], "merkle");
+        assert_eq!(lines[1]["done"], 1);
+        assert_eq!(lines[1]["unestimated"], 1);
+        assert_eq!(lines[2]["event"], "finish");
+    }
+
+    #[test]
+    fn test_format_eta() {
+        assert_eq!(format_eta(37_400.0), "37s");
+        assert_eq!(format_eta(252_000.0), "4m12s");
+        assert_eq!(format_eta(3_720_000.0), "1h02m");
+    }
+}
@@ -11,6 +11,9 @@ use std::process::Command;
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::env::{EnvironmentInfo, detect_cpu_governor, normalize_arch};
+use crate::core::thermal;
+
 /// Comprehensive provenance information for a benchmark run.
 ///
 /// This is a sidecar structure that can be attached to reports without
@@ -70,6 +73,9 @@ pub struct SystemInfo {
     /// Hostname
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hostname: Option<String>,
+    /// CPU frequency governor (e.g., "performance"), Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_governor: Option<String>,
 }
 
 impl Default for SystemInfo {
@@ -81,6 +87,7 @@ impl Default for SystemInfo {
             cpu_cores: None,
             ram_bytes: None,
             hostname: None,
+            cpu_governor: None,
         }
     }
 }
@@ -212,6 +219,7 @@ fn collect_system_info() -> SystemInfo {
         cpu_cores: sys.physical_core_count().map(|c| c as u32),
         ram_bytes: Some(sys.total_memory()),
         hostname: System::host_name(),
+        cpu_governor: detect_cpu_governor(),
     }
 }
 
@@ -256,7 +264,8 @@ fn detect_git_dirty() -> Option<bool> {
 
 /// Check if two provenance records have matching tool versions.
 ///
-/// Returns a list of mismatches for reporting.
+/// Returns a list of mismatches for reporting. Machine differences (OS, CPU,
+/// RAM, governor) are reported by `check_environment_mismatches`.
 pub fn check_version_mismatches(
     baseline: &Provenance,
     target: &Provenance,
@@ -285,18 +294,120 @@ pub fn check_version_mismatches(
         }
     }
 
-    // Check OS/arch
-    if baseline.system.os != target.system.os || baseline.system.arch != target.system.arch {
-        mismatches.push(VersionMismatch {
-            tool: "system".to_string(),
-            baseline_version: Some(format!("{}/{}", baseline.system.os, baseline.system.arch)),
-            target_version: Some(format!("{}/{}", target.system.os, target.system.arch)),
-        });
-    }
-
     mismatches
 }
 
+/// Check if two provenance records ran on comparable machines.
+///
+/// Returns one mismatch per differing OS, arch, CPU model, core count, RAM or
+/// CPU governor. Properties unknown on either side are not compared.
+pub fn check_environment_mismatches(
+    baseline: &SystemInfo,
+    target: &SystemInfo,
+) -> Vec<EnvironmentMismatch> {
+    diff_environment(vec![
+        ("os", Some(baseline.os.clone()), Some(target.os.clone())),
+        (
+            "arch",
+            Some(baseline.arch.clone()),
+            Some(target.arch.clone()),
+        ),
+        (
+            "cpu_model",
+            baseline.cpu_brand.clone(),
+            target.cpu_brand.clone(),
+        ),
+        (
+            "cpu_cores",
+            baseline.cpu_cores.map(|c| c.to_string()),
+            target.cpu_cores.map(|c| c.to_string()),
+        ),
+        (
+            "ram",
+            baseline.ram_bytes.map(format_ram),
+            target.ram_bytes.map(format_ram),
+        ),
+        (
+            "cpu_governor",
+            baseline.cpu_governor.clone(),
+            target.cpu_governor.clone(),
+        ),
+    ])
+}
+
+/// Same as `check_environment_mismatches`, for the environment stored in
+/// BenchRecords.
+pub fn check_record_environment_mismatches(
+    baseline: &EnvironmentInfo,
+    target: &EnvironmentInfo,
+) -> Vec<EnvironmentMismatch> {
+    diff_environment(vec![
+        ("os", Some(baseline.os.clone()), Some(target.os.clone())),
+        (
+            "arch",
+            baseline.arch.as_deref().map(normalize_arch),
+            target.arch.as_deref().map(normalize_arch),
+        ),
+        (
+            "cpu_model",
+            baseline.cpu_model.clone(),
+            target.cpu_model.clone(),
+        ),
+        (
+            "cpu_cores",
+            baseline.cpu_cores.map(|c| c.to_string()),
+            target.cpu_cores.map(|c| c.to_string()),
+        ),
+        (
+            "ram",
+            baseline.total_ram_bytes.map(format_ram),
+            target.total_ram_bytes.map(format_ram),
+        ),
+        (
+            "cpu_governor",
+            baseline.cpu_governor.clone(),
+            target.cpu_governor.clone(),
+        ),
+    ])
+}
+
+fn diff_environment(
+    fields: Vec<(&str, Option<String>, Option<String>)>,
+) -> Vec<EnvironmentMismatch> {
+    fields
+        .into_iter()
+        .filter_map(|(field, baseline, target)| match (baseline, target) {
+            (Some(baseline), Some(target)) if baseline != target => Some(EnvironmentMismatch {
+                field: field.to_string(),
+                baseline,
+                target,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// RAM rounded to 0.1 GiB, so small reporting differences don't count.
+fn format_ram(bytes: u64) -> String {
+    format!("{:.1} GiB", bytes as f64 / (1u64 << 30) as f64)
+}
+
+/// Warning for a record measured under elevated thermal pressure, if it was.
+pub fn check_thermal_pressure(
+    circuit_name: &str,
+    run: &str,
+    env: &EnvironmentInfo,
+) -> Option<ThermalWarning> {
+    env.thermal_pressure
+        .as_deref()
+        .filter(|level| thermal::is_elevated(level))
+        .map(|level| ThermalWarning {
+            circuit_name: circuit_name.to_string(),
+            run: run.to_string(),
+            level: level.to_string(),
+        })
+}
+
 /// A version mismatch between baseline and target.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionMismatch {
@@ -305,6 +416,37 @@ pub struct VersionMismatch {
     pub target_version: Option<String>,
 }
 
+/// A machine property that differs between baseline and target, so deltas
+/// may come from the hardware rather than the code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentMismatch {
+    /// Property name (os, arch, cpu_model, cpu_cores, ram, cpu_governor, or
+    /// prove_mode when prove times were measured differently)
+    pub field: String,
+    pub baseline: String,
+    pub target: String,
+}
+
+/// A record measured while the machine was thermally throttling, so its
+/// timings may be inflated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThermalWarning {
+    pub circuit_name: String,
+    /// "baseline" or "target"
+    pub run: String,
+    /// Highest thermal pressure level seen (e.g. "moderate", "heavy")
+    pub level: String,
+}
+
+impl ThermalWarning {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} measured under {} thermal pressure; timings may be throttled",
+            self.run, self.level
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,6 +507,7 @@ mod tests {
                 cpu_cores: None,
                 ram_bytes: None,
                 hostname: None,
+                cpu_governor: None,
             },
             cli_args: vec![],
             collected_at: "2026-01-15T00:00:00Z".to_string(),
@@ -395,5 +538,78 @@ mod tests {
         let prov = collect_minimal();
         let mismatches = check_version_mismatches(&prov, &prov);
         assert!(mismatches.is_empty());
+        assert!(check_environment_mismatches(&prov.system, &prov.system).is_empty());
+    }
+
+    #[test]
+    fn test_environment_mismatch_detection() {
+        let baseline = SystemInfo {
+            cpu_brand: Some("AMD EPYC 7763".to_string()),
+            cpu_cores: Some(64),
+            ram_bytes: Some(256 << 30),
+            cpu_governor: Some("performance".to_string()),
+            ..SystemInfo::default()
+        };
+        let target = SystemInfo {
+            cpu_brand: Some("Apple M2".to_string()),
+            cpu_cores: Some(64),
+            // Within rounding of the baseline
+            ram_bytes: Some((256 << 30) + 4096),
+            cpu_governor: Some("powersave".to_string()),
+            hostname: Some("laptop".to_string()),
+            ..SystemInfo::default()
+        };
+
+        let mismatches = check_environment_mismatches(&baseline, &target);
+        let fields: Vec<&str> = mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, vec!["cpu_model", "cpu_governor"]);
+        assert_eq!(mismatches[1].baseline, "performance");
+        assert_eq!(mismatches[1].target, "powersave");
+    }
+
+    #[test]
+    fn test_record_environment_mismatch_skips_unknown() {
+        let baseline = EnvironmentInfo {
+            cpu_cores: Some(8),
+            total_ram_bytes: Some(16 << 30),
+            ..EnvironmentInfo::default()
+        };
+        let target = EnvironmentInfo {
+            cpu_cores: Some(16),
+            total_ram_bytes: Some(32 << 30),
+            cpu_governor: Some("performance".to_string()),
+            ..EnvironmentInfo::default()
+        };
+
+        let mismatches = check_record_environment_mismatches(&baseline, &target);
+        assert_eq!(
+            mismatches,
+            vec![
+                EnvironmentMismatch {
+                    field: "cpu_cores".to_string(),
+                    baseline: "8".to_string(),
+                    target: "16".to_string(),
+                },
+                EnvironmentMismatch {
+                    field: "ram".to_string(),
+                    baseline: "16.0 GiB".to_string(),
+                    target: "32.0 GiB".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_thermal_pressure() {
+        let mut env = EnvironmentInfo::default();
+        assert!(check_thermal_pressure("c", "target", &env).is_none());
+        env.thermal_pressure = Some("nominal".to_string());
+        assert!(check_thermal_pressure("c", "target", &env).is_none());
+        env.thermal_pressure = Some("heavy".to_string());
+        let warning = check_thermal_pressure("c", "target", &env).unwrap();
+        assert_eq!(
+            warning.describe(),
+            "target measured under heavy thermal pressure; timings may be throttled"
+        );
     }
 }
@@ -0,0 +1,254 @@
+//! Expression-width and backend-option sweeps.
+//!
+//! A sweep benchmarks one circuit at every combination of a nargo
+//! `--expression-width` and a named set of bb options (`--disable_zk`,
+//! `--oracle_hash keccak`, ...), so the gate count, proof size and prove
+//! time each setting trades can be read off one table (see
+//! `report::sweep`). Each point recompiles the project like a compile-config
+//! matrix; records carry the compile flags in `config.compile_flags` and the
+//! bb options in metadata.
+
+use std::path::Path;
+
+use crate::backend::Backend;
+use crate::{BenchError, BenchResult};
+
+use super::matrix::{MatrixEntry, compile_and_benchmark, parse_named_flags};
+use super::toolchain::Toolchain;
+use super::workflow::ProveInputs;
+
+/// Metadata key sweep records are tagged with (the point name).
+pub const SWEEP_METADATA_KEY: &str = "sweep_point";
+
+/// Metadata key holding the bb options of a sweep point, space-separated.
+pub const BACKEND_ARGS_METADATA_KEY: &str = "backend_args";
+
+/// Expression widths swept when none are given; 0 is unbounded.
+pub const DEFAULT_EXPRESSION_WIDTHS: &[u32] = &[3, 4, 5, 0];
+
+/// A named set of bb options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendOption {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl BackendOption {
+    /// Parse `NAME=FLAGS`, e.g. `no-zk=--disable_zk`.
+    pub fn parse(s: &str) -> BenchResult<Self> {
+        let (name, args) = parse_named_flags(s)?;
+        Ok(BackendOption { name, args })
+    }
+}
+
+/// One setting of a sweep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepPoint {
+    pub name: String,
+    /// Expression width the circuit is compiled for (0 = unbounded), if swept
+    pub expression_width: Option<u32>,
+    /// Name of the bb option set, if swept
+    pub backend_option: Option<String>,
+    pub compile_flags: Vec<String>,
+    pub backend_args: Vec<String>,
+}
+
+fn width_label(width: u32) -> String {
+    if width == 0 {
+        "unbounded".to_string()
+    } else {
+        width.to_string()
+    }
+}
+
+/// Every combination of `expression_widths` and `backend_options`; an empty
+/// axis is left at the default (no flags).
+pub fn sweep_points(
+    expression_widths: &[u32],
+    backend_options: &[BackendOption],
+) -> BenchResult<Vec<SweepPoint>> {
+    if expression_widths.is_empty() && backend_options.is_empty() {
+        return Err(BenchError::Message(
+            "nothing to sweep: give expression widths or bb options".into(),
+        ));
+    }
+    let widths: Vec<Option<u32>> = if expression_widths.is_empty() {
+        vec![None]
+    } else {
+        expression_widths.iter().copied().map(Some).collect()
+    };
+    let options: Vec<Option<&BackendOption>> = if backend_options.is_empty() {
+        vec![None]
+    } else {
+        backend_options.iter().map(Some).collect()
+    };
+
+    let mut points = Vec::with_capacity(widths.len() * options.len());
+    for width in &widths {
+        for option in &options {
+            let name = match (width, option) {
+                (Some(w), Some(o)) => format!("w{}+{}", width_label(*w), o.name),
+                (Some(w), None) => format!("w{}", width_label(*w)),
+                (None, Some(o)) => o.name.clone(),
+                (None, None) => unreachable!("at least one axis is set"),
+            };
+            if points.iter().any(|p: &SweepPoint| p.name == name) {
+                return Err(BenchError::Message(format!(
+                    "sweep point '{name}' given twice"
+                )));
+            }
+            points.push(SweepPoint {
+                name,
+                expression_width: *width,
+                backend_option: option.map(|o| o.name.clone()),
+                compile_flags: width
+                    .map(|w| vec!["--expression-width".to_string(), w.to_string()])
+                    .unwrap_or_default(),
+                backend_args: option.map(|o| o.args.clone()).unwrap_or_default(),
+            });
+        }
+    }
+    Ok(points)
+}
+
+/// Benchmark `inputs` at every sweep point.
+///
+/// `make_toolchain` builds a nargo with the point's compile flags and
+/// `make_backend` a bb with its options. The project's own artifact is
+/// restored afterwards; a failing point is reported in its entry (named
+/// after the point) and doesn't stop the others.
+pub fn run_sweep(
+    points: &[SweepPoint],
+    make_toolchain: &dyn Fn(&[String]) -> BenchResult<Box<dyn Toolchain>>,
+    make_backend: &dyn Fn(&[String]) -> BenchResult<Box<dyn Backend>>,
+    project_dir: &Path,
+    inputs: &ProveInputs,
+    work_dir: &Path,
+    warmup: usize,
+    iterations: usize,
+) -> Vec<MatrixEntry> {
+    let original = std::fs::read(&inputs.artifact_path).ok();
+
+    let entries = points
+        .iter()
+        .map(|point| MatrixEntry {
+            version: point.name.clone(),
+            result: make_toolchain(&point.compile_flags).and_then(|toolchain| {
+                let backend = make_backend(&point.backend_args)?;
+                let mut result = compile_and_benchmark(
+                    &point.name,
+                    toolchain.as_ref(),
+                    backend.as_ref(),
+                    project_dir,
+                    inputs,
+                    work_dir,
+                    warmup,
+                    iterations,
+                )?;
+                let record = &mut result.record;
+                record.config.compile_flags = point.compile_flags.clone();
+                record
+                    .metadata
+                    .insert(SWEEP_METADATA_KEY.to_string(), point.name.clone());
+                if !point.backend_args.is_empty() {
+                    record.metadata.insert(
+                        BACKEND_ARGS_METADATA_KEY.to_string(),
+                        point.backend_args.join(" "),
+                    );
+                }
+                Ok(result)
+            }),
+        })
+        .collect();
+
+    if let Some(bytes) = original {
+        let _ = std::fs::write(&inputs.artifact_path, bytes);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig, ProveOutput};
+    use crate::engine::toolchain::{CompileArtifacts, MockToolchain};
+
+    #[test]
+    fn test_sweep_points_cross_both_axes() {
+        let no_zk = BackendOption::parse("no-zk=--disable_zk").unwrap();
+        let points = sweep_points(&[4, 0], &[no_zk]).unwrap();
+        let names: Vec<&str> = points.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["w4+no-zk", "wunbounded+no-zk"]);
+        assert_eq!(points[0].compile_flags, ["--expression-width", "4"]);
+        assert_eq!(points[0].backend_args, ["--disable_zk"]);
+
+        let points = sweep_points(&[3], &[]).unwrap();
+        assert_eq!(points[0].name, "w3");
+        assert!(points[0].backend_args.is_empty());
+        assert!(sweep_points(&[], &[]).is_err());
+        assert!(sweep_points(&[3, 3], &[]).is_err());
+    }
+
+    #[test]
+    fn test_run_sweep_passes_flags_and_tags_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("circuit");
+        let artifact = project.join("target/circuit.json");
+        std::fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        std::fs::write(&artifact, b"original").unwrap();
+        let compiled = dir.path().join("compiled.json");
+        std::fs::write(&compiled, b"compiled").unwrap();
+
+        let make_toolchain = |_flags: &[String]| -> BenchResult<Box<dyn Toolchain>> {
+            let mut toolchain = MockToolchain::new();
+            toolchain.compile_output = Some(CompileArtifacts {
+                artifact_path: compiled.clone(),
+                compile_time_ms: 40,
+            });
+            Ok(Box::new(toolchain))
+        };
+        let make_backend = |args: &[String]| -> BenchResult<Box<dyn Backend>> {
+            let prove_time_ms = if args.is_empty() { 100 } else { 80 };
+            Ok(Box::new(MockBackend::new(
+                MockConfig::new("mock-bb").with_prove_output(ProveOutput {
+                    prove_time_ms,
+                    ..ProveOutput::default()
+                }),
+            )))
+        };
+        let points = sweep_points(
+            &[4],
+            &[
+                BackendOption::parse("zk=").unwrap(),
+                BackendOption::parse("no-zk=--disable_zk").unwrap(),
+            ],
+        )
+        .unwrap();
+        let inputs = ProveInputs::new(&artifact, "circuit");
+
+        let entries = run_sweep(
+            &points,
+            &make_toolchain,
+            &make_backend,
+            &project,
+            &inputs,
+            &dir.path().join("work"),
+            0,
+            1,
+        );
+
+        let records: Vec<_> = entries
+            .iter()
+            .map(|e| &e.result.as_ref().unwrap().record)
+            .collect();
+        assert_eq!(records[0].metadata[SWEEP_METADATA_KEY], "w4+zk");
+        assert!(!records[0].metadata.contains_key(BACKEND_ARGS_METADATA_KEY));
+        assert_eq!(
+            records[1].metadata[BACKEND_ARGS_METADATA_KEY],
+            "--disable_zk"
+        );
+        assert_eq!(records[1].config.compile_flags, ["--expression-width", "4"]);
+        assert_eq!(records[1].prove_stats.as_ref().unwrap().mean_ms, 80.0);
+        assert_eq!(std::fs::read(&artifact).unwrap(), b"original");
+    }
+}
@@ -6,11 +6,14 @@
 //!
 //! This is distinct from `Backend` which handles proving system operations (prove, verify).
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::BenchResult;
+use crate::storage::run_logs::{FailureLogs, command_failure};
 
 /// Output from a compilation operation.
 #[derive(Debug, Clone)]
@@ -28,6 +31,11 @@ pub struct WitnessArtifact {
     pub witness_path: PathBuf,
     /// Witness generation time in milliseconds
     pub witness_gen_time_ms: u128,
+    /// Time spent in oracle resolver round-trips (when a resolver is set)
+    pub oracle_resolver_ms: Option<f64>,
+    /// Directory created for this witness alone, removed once the last
+    /// clone of the artifact is dropped (`None` when the caller owns the file)
+    pub dir: Option<Arc<tempfile::TempDir>>,
 }
 
 /// Trait for Noir toolchain operations.
@@ -79,6 +87,17 @@ pub struct NargoToolchain {
     nargo_path: PathBuf,
     /// Timeout for nargo operations
     timeout: Duration,
+    /// JSON-RPC oracle resolver URL for witness generation
+    oracle_resolver: Option<String>,
+    /// Directory failed commands' stdout/stderr are kept in
+    log_dir: Option<PathBuf>,
+    /// Extra arguments passed to `nargo compile`
+    compile_args: Vec<String>,
+    /// Directory witnesses are written to (default: the system temp dir)
+    work_dir: Option<PathBuf>,
+    /// Environment variables set for nargo processes (witnesses are
+    /// generated in-process and don't see them)
+    env: BTreeMap<String, String>,
 }
 
 impl Default for NargoToolchain {
@@ -93,6 +112,11 @@ impl NargoToolchain {
         NargoToolchain {
             nargo_path: PathBuf::from("nargo"),
             timeout: Duration::from_secs(300), // 5 minute default
+            oracle_resolver: None,
+            log_dir: None,
+            compile_args: Vec::new(),
+            work_dir: None,
+            env: BTreeMap::new(),
         }
     }
 
@@ -101,6 +125,11 @@ impl NargoToolchain {
         NargoToolchain {
             nargo_path: nargo_path.into(),
             timeout: Duration::from_secs(300),
+            oracle_resolver: None,
+            log_dir: None,
+            compile_args: Vec::new(),
+            work_dir: None,
+            env: BTreeMap::new(),
         }
     }
 
@@ -110,12 +139,60 @@ impl NargoToolchain {
         self
     }
 
+    /// Resolve oracle calls during witness generation with this JSON-RPC
+    /// resolver.
+    pub fn with_oracle_resolver(mut self, url: impl Into<String>) -> Self {
+        self.oracle_resolver = Some(url.into());
+        self
+    }
+
+    /// Keep the output of failed nargo commands under `dir`.
+    pub fn with_log_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.log_dir = Some(dir.into());
+        self
+    }
+
+    /// Write witnesses under `dir` (see `storage::work_dir`).
+    pub fn with_work_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.work_dir = Some(dir.into());
+        self
+    }
+
+    /// Pass these flags to `nargo compile`, e.g. `--force-brillig` or
+    /// `--inliner-aggressiveness 0`.
+    pub fn with_compile_args(mut self, args: Vec<String>) -> Self {
+        self.compile_args = args;
+        self
+    }
+
+    /// Set these environment variables for nargo processes.
+    pub fn with_env(mut self, env: BTreeMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
     /// Get the path to the nargo binary.
     pub fn nargo_path(&self) -> &Path {
         &self.nargo_path
     }
 }
 
+/// Error of in-process witness generation, prefixed with `context`. A
+/// failed assertion or constraint is [`BenchError::Unsatisfied`]; unreadable
+/// inputs, oracle errors and the like stay plain messages.
+pub fn witness_error(context: &str, e: noir_artifact_cli::errors::CliError) -> crate::BenchError {
+    use nargo::errors::NargoError;
+    use noir_artifact_cli::errors::CliError;
+
+    let message = format!("{context}: {e}");
+    match e {
+        CliError::CircuitExecutionError(NargoError::ExecutionError(_)) => {
+            crate::BenchError::Unsatisfied(message)
+        }
+        _ => crate::BenchError::Message(message),
+    }
+}
+
 /// Parse nargo version from command output.
 ///
 /// Expected formats:
@@ -159,6 +236,7 @@ impl Toolchain for NargoToolchain {
 
     fn version(&self) -> BenchResult<String> {
         let output = Command::new(&self.nargo_path)
+            .envs(&self.env)
             .arg("--version")
             .output()
             .map_err(|e| {
@@ -181,21 +259,27 @@ impl Toolchain for NargoToolchain {
     fn compile(&self, project_dir: &Path) -> BenchResult<CompileArtifacts> {
         let start = std::time::Instant::now();
 
-        let status = Command::new(&self.nargo_path)
+        let output = Command::new(&self.nargo_path)
+            .envs(&self.env)
             .arg("compile")
+            .args(&self.compile_args)
             .current_dir(project_dir)
-            .status()
+            .output()
             .map_err(|e| {
                 crate::BenchError::Message(format!("failed to run nargo compile: {}", e))
             })?;
 
         let compile_time_ms = start.elapsed().as_millis();
 
-        if !status.success() {
-            return Err(crate::BenchError::Message(format!(
-                "nargo compile failed with status: {}",
-                status
-            )));
+        if !output.status.success() {
+            let logs = self.log_dir.as_ref().map(FailureLogs::new);
+            return Err(command_failure(
+                "nargo compile",
+                output.status,
+                &output.stdout,
+                &output.stderr,
+                logs.as_ref(),
+            ));
         }
 
         // nargo compile outputs to target/<project_name>.json
@@ -215,48 +299,57 @@ impl Toolchain for NargoToolchain {
         //
         // This is a placeholder that delegates to the existing in-process execution.
         // A future iteration could shell out to `nargo execute` instead.
+        use crate::engine::oracle::ResolverTiming;
         use bn254_blackbox_solver::Bn254BlackBoxSolver;
         use nargo::foreign_calls::DefaultForeignCallBuilder;
         use noir_artifact_cli::execution::execute as execute_program_artifact;
-        use noir_artifact_cli::fs::artifact::read_program_from_file;
         use noir_artifact_cli::fs::witness::save_witness_to_dir;
 
-        let start = std::time::Instant::now();
-
-        // Read the compiled program
-        let program = read_program_from_file(artifact)
-            .map_err(|e| crate::BenchError::Message(format!("failed to read artifact: {}", e)))?;
+        // Parsed once per artifact and kept out of the witness timing
+        let compiled = crate::engine::load_compiled(artifact)?;
 
-        let compiled: noirc_artifacts::program::CompiledProgram = program.into();
+        let start = std::time::Instant::now();
 
         // Execute to generate witness
+        let mut foreign_calls = ResolverTiming::new(
+            DefaultForeignCallBuilder {
+                resolver_url: self.oracle_resolver.clone(),
+                ..Default::default()
+            }
+            .build(),
+        );
         let exec_res = execute_program_artifact(
             &compiled,
             &Bn254BlackBoxSolver,
-            &mut DefaultForeignCallBuilder::default().build(),
+            &mut foreign_calls,
             prover_toml,
         )
-        .map_err(|e| crate::BenchError::Message(format!("witness generation failed: {}", e)))?;
-
-        // Save witness to temp directory
-        let tempdir = tempfile::tempdir()
-            .map_err(|e| crate::BenchError::Message(format!("failed to create temp dir: {}", e)))?;
-        let witness_path = save_witness_to_dir(&exec_res.witness_stack, "witness", tempdir.path())
+        .map_err(|e| witness_error("witness generation failed", e))?;
+
+        // Every call gets its own directory (under the run's work dir, if
+        // set), so iterations and parallel jobs never write the same file.
+        // The artifact owns it: the witness lives as long as the workflow
+        // holds on to the artifact.
+        let dir = match &self.work_dir {
+            Some(work_dir) => tempfile::Builder::new()
+                .prefix("witness-")
+                .tempdir_in(work_dir),
+            None => tempfile::Builder::new()
+                .prefix("noir-bench-witness-")
+                .tempdir(),
+        }
+        .map_err(|e| crate::BenchError::Message(format!("failed to create temp dir: {}", e)))?;
+        let witness_path = save_witness_to_dir(&exec_res.witness_stack, "witness", dir.path())
             .map_err(|e| crate::BenchError::Message(format!("failed to save witness: {}", e)))?;
 
-        let witness_gen_time_ms = start.elapsed().as_millis();
-
-        // Note: The tempdir will be dropped after this function returns.
-        // In a real implementation, we'd want to persist this or pass ownership.
-        // For now, we copy to a stable location.
-        let stable_witness_path =
-            std::env::temp_dir().join(format!("noir-bench-witness-{}.gz", std::process::id()));
-        std::fs::copy(&witness_path, &stable_witness_path)
-            .map_err(|e| crate::BenchError::Message(format!("failed to copy witness: {}", e)))?;
-
         Ok(WitnessArtifact {
-            witness_path: stable_witness_path,
-            witness_gen_time_ms,
+            witness_path,
+            witness_gen_time_ms: start.elapsed().as_millis(),
+            oracle_resolver_ms: self
+                .oracle_resolver
+                .is_some()
+                .then(|| foreign_calls.elapsed_ms()),
+            dir: Some(Arc::new(dir)),
         })
     }
 }
@@ -316,6 +409,8 @@ impl Default for MockToolchain {
             witness_output: Some(WitnessArtifact {
                 witness_path: PathBuf::from("/tmp/mock-witness.gz"),
                 witness_gen_time_ms: 25,
+                oracle_resolver_ms: None,
+                dir: None,
             }),
             should_fail: false,
         }
@@ -8,12 +8,18 @@
 //! Workflows produce `BenchRecord` v1 outputs that are compatible with the existing
 //! storage and reporting infrastructure (JSONL, CSV export, compare, etc.).
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::BenchResult;
-use crate::backend::Backend;
-use crate::core::{BackendInfo, BenchRecord, EnvironmentInfo, RunConfig, TimingStat};
+use crate::backend::phases::mean_phases;
+use crate::backend::{Backend, GateInfo, ProveOutput};
+use crate::core::page_cache::{self, CacheMode};
+use crate::core::process::CpuTime;
+use crate::core::thermal::ThermalSampler;
+use crate::core::{BackendInfo, BenchRecord, CpuTimeStat, EnvironmentInfo, RunConfig, TimingStat};
+use crate::storage::{ArtifactStore, GateCache};
+use crate::{BenchResult, Fingerprints, file_sha256};
 
 use super::toolchain::Toolchain;
 
@@ -28,6 +34,17 @@ pub struct ProveInputs {
     pub circuit_name: String,
     /// Timeout for backend operations
     pub timeout: Duration,
+    /// Content-addressed store to keep the final proof/vk in (see `attest`)
+    pub artifact_store: Option<PathBuf>,
+    /// Directory to cache gate analysis results in (see `storage::gate_cache`)
+    pub gate_cache: Option<PathBuf>,
+    /// Gate analysis already done for this artifact earlier in the run
+    pub gate_info: Option<GateInfo>,
+    /// Health-check the backend before the first measured run
+    pub probe: bool,
+    /// Evict the prove's inputs and the backend's SRS from the page cache
+    /// before every prove (see `core::page_cache`)
+    pub cache_mode: CacheMode,
 }
 
 impl ProveInputs {
@@ -38,6 +55,11 @@ impl ProveInputs {
             prover_toml: None,
             circuit_name: circuit_name.into(),
             timeout: Duration::from_secs(300), // 5 minute default
+            artifact_store: None,
+            gate_cache: None,
+            gate_info: None,
+            probe: false,
+            cache_mode: CacheMode::Warm,
         }
     }
 
@@ -52,6 +74,177 @@ impl ProveInputs {
         self.timeout = timeout;
         self
     }
+
+    /// Keep the final proof and vk in a content-addressed artifact store.
+    pub fn with_artifact_store(mut self, store: impl Into<PathBuf>) -> Self {
+        self.artifact_store = Some(store.into());
+        self
+    }
+
+    /// Reuse gate analysis results cached by artifact hash and backend.
+    pub fn with_gate_cache(mut self, cache: impl Into<PathBuf>) -> Self {
+        self.gate_cache = Some(cache.into());
+        self
+    }
+
+    /// Use gate analysis of the same artifact instead of running the backend.
+    pub fn with_gate_info(mut self, info: GateInfo) -> Self {
+        self.gate_info = Some(info);
+        self
+    }
+
+    /// Probe the backend (see `Backend::probe`) before measuring.
+    pub fn with_probe(mut self, probe: bool) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Measure warm or cold-cache proving.
+    pub fn with_cache_mode(mut self, cache_mode: CacheMode) -> Self {
+        self.cache_mode = cache_mode;
+        self
+    }
+}
+
+/// Hash the files behind a run and, if configured, archive the proof/vk.
+///
+/// `witness_hash` must be taken before the temp witness is removed.
+fn fingerprint_run(
+    inputs: &ProveInputs,
+    prover_toml: &Path,
+    witness_hash: Option<String>,
+    output: Option<&ProveOutput>,
+) -> BenchResult<Fingerprints> {
+    let proof_path = output.and_then(|o| o.proof_path.as_deref());
+    let vk_path = output.and_then(|o| o.vk_path.as_deref());
+
+    if let Some(root) = &inputs.artifact_store {
+        let store = ArtifactStore::new(root);
+        if let Some(proof) = proof_path {
+            store.put_proof(proof)?;
+        }
+        if let Some(vk) = vk_path {
+            store.put_vk(vk)?;
+        }
+    }
+
+    Ok(Fingerprints {
+        acir_hash: file_sha256(&inputs.artifact_path),
+        inputs_hash: file_sha256(prover_toml),
+        proof_hash: proof_path.and_then(file_sha256),
+        vk_hash: vk_path.and_then(file_sha256),
+        witness_hash,
+    })
+}
+
+/// Hardware threads this process may run on, which bounds how much CPU time
+/// a backend can use per unit of wall time (physical cores don't, with SMT).
+fn logical_cores() -> Option<u32> {
+    std::thread::available_parallelism()
+        .ok()
+        .map(|n| n.get() as u32)
+}
+
+/// Per-phase CPU time of the backend processes, from the measured prove runs
+/// and every run that generated a vk; `None` if the backend
+/// reported neither.
+fn cpu_times(
+    prove: &[CpuTime],
+    vk_gen: &[CpuTime],
+    cores: Option<u32>,
+) -> Option<BTreeMap<String, CpuTimeStat>> {
+    let times: BTreeMap<String, CpuTimeStat> = [("prove", prove), ("vk_gen", vk_gen)]
+        .into_iter()
+        .filter_map(|(phase, times)| {
+            CpuTimeStat::from_times(times, cores).map(|stat| (phase.to_string(), stat))
+        })
+        .collect();
+    (!times.is_empty()).then_some(times)
+}
+
+/// Run the backend probe if `inputs` asks for one: the gate analysis as a
+/// health check (for backends that count gates), then the backend's
+/// warm-up. Returns the warm-up time and the gate analysis, which the run
+/// reuses instead of analysing again.
+///
+/// A failing probe fails the run before any measurement, with the backend's
+/// error, instead of surfacing mid-way as a failed first iteration.
+
+/// Gate analysis done by the probe: the info and its time, `None` when it
+/// came from a cache.
+type ProbedGates = Option<(GateInfo, Option<f64>)>;
+
+fn probe(backend: &dyn Backend, inputs: &ProveInputs) -> BenchResult<(Option<f64>, ProbedGates)> {
+    if !inputs.probe {
+        return Ok((None, None));
+    }
+    let failed = |e: crate::BenchError| {
+        crate::BenchError::Message(format!("backend health check failed: {e}"))
+    };
+    let gates = if backend.capabilities().has_gate_count {
+        Some(gate_info_cached(backend, inputs).map_err(failed)?)
+    } else {
+        None
+    };
+    let start = Instant::now();
+    backend.probe(&inputs.artifact_path).map_err(failed)?;
+    Ok((Some(start.elapsed().as_secs_f64() * 1000.0), gates))
+}
+
+/// In cold-cache mode, evict what the next prove reads (artifact, witness,
+/// Prover.toml and the backend's cached files) from the page cache.
+fn evict_for_prove(
+    backend: &dyn Backend,
+    inputs: &ProveInputs,
+    prover_toml: &Path,
+    witness: &Path,
+) -> BenchResult<()> {
+    if inputs.cache_mode == CacheMode::Warm {
+        return Ok(());
+    }
+    let mut paths = vec![
+        inputs.artifact_path.clone(),
+        prover_toml.to_path_buf(),
+        witness.to_path_buf(),
+    ];
+    paths.extend(backend.cached_files());
+    page_cache::evict(&paths)?;
+    Ok(())
+}
+
+/// Get gate info for the inputs' artifact, from `inputs.gate_info` or
+/// `inputs.gate_cache` if set.
+///
+/// Returns the analysis time alongside the result when the backend actually
+/// ran, and `None` on a cache hit so cached runs don't report a bogus timing.
+fn gate_info_cached(
+    backend: &dyn Backend,
+    inputs: &ProveInputs,
+) -> BenchResult<(GateInfo, Option<f64>)> {
+    if let Some(info) = &inputs.gate_info {
+        return Ok((info.clone(), None));
+    }
+    let backend_id = backend.cache_id();
+    let cache = inputs.gate_cache.as_ref().and_then(|root| {
+        GateCache::key(&inputs.artifact_path, &backend_id)
+            .ok()
+            .map(|key| (GateCache::new(root), key))
+    });
+    if let Some((cache, key)) = &cache {
+        if let Some(info) = cache.get(key) {
+            return Ok((info, None));
+        }
+    }
+
+    let start = Instant::now();
+    let info = backend.gate_info(&inputs.artifact_path)?;
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    if let Some((cache, key)) = &cache {
+        if let Err(e) = cache.put(key, &info) {
+            eprintln!("Warning: failed to cache gate info: {e}");
+        }
+    }
+    Ok((info, Some(elapsed_ms)))
 }
 
 /// Execute a prove-only workflow.
@@ -91,6 +284,8 @@ pub fn prove_only(
         warmup_iterations: 0,
         measured_iterations: 1,
         timeout_secs: Some(inputs.timeout.as_secs()),
+        compile_flags: Vec::new(),
+        env: BTreeMap::new(),
     };
 
     // Create the record
@@ -99,6 +294,8 @@ pub fn prove_only(
     // Set circuit path
     record.circuit_path = Some(inputs.artifact_path.to_string_lossy().to_string());
 
+    let thermal = ThermalSampler::start();
+
     // Step 1: Generate witness using toolchain
     let prover_toml = inputs
         .prover_toml
@@ -109,17 +306,37 @@ pub fn prove_only(
     // Record witness timing as TimingStat (single sample)
     let witness_ms = witness_result.witness_gen_time_ms as f64;
     record.witness_stats = Some(TimingStat::from_samples(&[witness_ms]));
+    record.oracle_stats = witness_result
+        .oracle_resolver_ms
+        .map(|ms| TimingStat::from_samples(&[ms]));
 
     // Step 2: Call backend prove with the generated witness
+    evict_for_prove(backend, inputs, prover_toml, &witness_result.witness_path)?;
     let prove_output = backend.prove(
         &inputs.artifact_path,
         Some(&witness_result.witness_path),
         inputs.timeout,
     )?;
 
+    record.env.thermal_pressure = thermal.finish();
+
     // Record prove timing (backend prove time, not including witness gen)
     let prove_ms = prove_output.prove_time_ms as f64;
     record.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+    record.backend_setup_ms = prove_output.setup_time_ms.map(|ms| ms as f64);
+    record.vk_gen_stats = prove_output
+        .vk_gen_time_ms
+        .map(|ms| TimingStat::from_samples(&[ms as f64]));
+    record.vk_cached = (prove_output.vk_cached || prove_output.vk_gen_time_ms.is_some())
+        .then_some(prove_output.vk_cached);
+    record.key_cache = prove_output.key_cache;
+    record.cache_mode = Some(inputs.cache_mode);
+    record.backend_phases = prove_output.phases.clone();
+    record.cpu_times = cpu_times(
+        prove_output.prove_cpu.as_slice(),
+        prove_output.vk_gen_cpu.as_slice(),
+        logical_cores(),
+    );
 
     // Record size metrics
     record.proof_size_bytes = prove_output.proof_size_bytes;
@@ -141,6 +358,14 @@ pub fn prove_only(
         record.env.nargo_version = toolchain_version;
     }
 
+    let witness_hash = file_sha256(&witness_result.witness_path);
+    record.fingerprints = Some(fingerprint_run(
+        inputs,
+        prover_toml,
+        witness_hash,
+        Some(&prove_output),
+    )?);
+
     // Cleanup: remove temp witness file
     let _ = std::fs::remove_file(&witness_result.witness_path);
 
@@ -175,7 +400,15 @@ pub fn prove_with_iterations(
 
     let total_runs = warmup + iterations;
     let mut witness_times: Vec<f64> = Vec::with_capacity(iterations);
+    let mut oracle_times: Vec<f64> = Vec::new();
     let mut prove_times: Vec<f64> = Vec::with_capacity(iterations);
+    let mut setup_ms: Option<f64> = None;
+    let mut vk_gen_times: Vec<f64> = Vec::new();
+    let mut vk_cached = false;
+    let mut key_cache = None;
+    let mut phase_runs = Vec::new();
+    let mut prove_cpu: Vec<CpuTime> = Vec::new();
+    let mut vk_gen_cpu: Vec<CpuTime> = Vec::new();
 
     // Get environment info once
     let env = EnvironmentInfo::detect();
@@ -191,6 +424,8 @@ pub fn prove_with_iterations(
         warmup_iterations: warmup as u32,
         measured_iterations: iterations as u32,
         timeout_secs: Some(inputs.timeout.as_secs()),
+        compile_flags: Vec::new(),
+        env: BTreeMap::new(),
     };
 
     let mut record = BenchRecord::new(inputs.circuit_name.clone(), env, backend_info, config);
@@ -200,7 +435,10 @@ pub fn prove_with_iterations(
         .prover_toml
         .as_deref()
         .unwrap_or(Path::new("Prover.toml"));
+    record.probe_ms = probe(backend, inputs)?.0;
     let mut last_prove_output = None;
+    let mut witness_hash = None;
+    let thermal = ThermalSampler::start();
 
     for i in 0..total_runs {
         let is_warmup = i < warmup;
@@ -209,16 +447,33 @@ pub fn prove_with_iterations(
         let witness_result = toolchain.gen_witness(&inputs.artifact_path, prover_toml)?;
 
         // Run backend prove
+        evict_for_prove(backend, inputs, prover_toml, &witness_result.witness_path)?;
         let prove_output = backend.prove(
             &inputs.artifact_path,
             Some(&witness_result.witness_path),
             inputs.timeout,
         )?;
+        if let Some(ms) = prove_output.setup_time_ms {
+            *setup_ms.get_or_insert(0.0) += ms as f64;
+        }
+        // Key generation is one-time work, so warmup runs count too
+        vk_gen_times.extend(prove_output.vk_gen_time_ms.map(|ms| ms as f64));
+        vk_gen_cpu.extend(prove_output.vk_gen_cpu);
+        vk_cached |= prove_output.vk_cached;
+        key_cache = key_cache.or(prove_output.key_cache);
 
         // Only collect times for measured iterations
         if !is_warmup {
             witness_times.push(witness_result.witness_gen_time_ms as f64);
             prove_times.push(prove_output.prove_time_ms as f64);
+            oracle_times.extend(witness_result.oracle_resolver_ms);
+            phase_runs.extend(prove_output.phases.clone());
+            prove_cpu.extend(prove_output.prove_cpu);
+        }
+
+        // Hash the last witness before it's removed
+        if i + 1 == total_runs {
+            witness_hash = file_sha256(&witness_result.witness_path);
         }
 
         // Cleanup witness file
@@ -228,9 +483,28 @@ pub fn prove_with_iterations(
         last_prove_output = Some(prove_output);
     }
 
+    record.env.thermal_pressure = thermal.finish();
+
     // Populate timing stats from collected samples
     record.witness_stats = Some(TimingStat::from_samples(&witness_times));
     record.prove_stats = Some(TimingStat::from_samples(&prove_times));
+    record.oracle_stats =
+        (!oracle_times.is_empty()).then(|| TimingStat::from_samples(&oracle_times));
+    record.backend_setup_ms = setup_ms;
+    record.vk_gen_stats =
+        (!vk_gen_times.is_empty()).then(|| TimingStat::from_samples(&vk_gen_times));
+    record.vk_cached = (vk_cached || !vk_gen_times.is_empty()).then_some(vk_cached);
+    record.key_cache = key_cache;
+    record.cache_mode = Some(inputs.cache_mode);
+    record.backend_phases = mean_phases(&phase_runs);
+    record.cpu_times = cpu_times(&prove_cpu, &vk_gen_cpu, logical_cores());
+
+    record.fingerprints = Some(fingerprint_run(
+        inputs,
+        prover_toml,
+        witness_hash,
+        last_prove_output.as_ref(),
+    )?);
 
     // Populate size metrics from last run
     if let Some(output) = last_prove_output {
@@ -286,6 +560,8 @@ pub struct FullBenchmarkResult {
     pub acir_opcodes: Option<u64>,
     /// Gate info collection status
     pub gate_info_status: GateInfoStatus,
+    /// Gate analysis of the artifact, to reuse for runs of the same artifact
+    pub gate_info: Option<GateInfo>,
     /// Verification succeeded
     pub verify_success: bool,
     /// Verification status
@@ -331,7 +607,15 @@ pub fn full_benchmark(
 
     let total_runs = warmup + iterations;
     let mut witness_times: Vec<f64> = Vec::with_capacity(iterations);
+    let mut oracle_times: Vec<f64> = Vec::new();
     let mut prove_times: Vec<f64> = Vec::with_capacity(iterations);
+    let mut setup_ms: Option<f64> = None;
+    let mut vk_gen_times: Vec<f64> = Vec::new();
+    let mut vk_cached = false;
+    let mut key_cache = None;
+    let mut phase_runs = Vec::new();
+    let mut prove_cpu: Vec<CpuTime> = Vec::new();
+    let mut vk_gen_cpu: Vec<CpuTime> = Vec::new();
 
     // Get environment info once
     let env = EnvironmentInfo::detect();
@@ -347,6 +631,8 @@ pub fn full_benchmark(
         warmup_iterations: warmup as u32,
         measured_iterations: iterations as u32,
         timeout_secs: Some(inputs.timeout.as_secs()),
+        compile_flags: Vec::new(),
+        env: BTreeMap::new(),
     };
 
     let mut record = BenchRecord::new(inputs.circuit_name.clone(), env, backend_info, config);
@@ -356,7 +642,11 @@ pub fn full_benchmark(
         .prover_toml
         .as_deref()
         .unwrap_or(Path::new("Prover.toml"));
+    let (probe_ms, probed_gates) = probe(backend, inputs)?;
+    record.probe_ms = probe_ms;
     let mut last_prove_output = None;
+    let mut witness_hash = None;
+    let thermal = ThermalSampler::start();
 
     // Run prove iterations
     for i in 0..total_runs {
@@ -366,16 +656,33 @@ pub fn full_benchmark(
         let witness_result = toolchain.gen_witness(&inputs.artifact_path, prover_toml)?;
 
         // Run backend prove
+        evict_for_prove(backend, inputs, prover_toml, &witness_result.witness_path)?;
         let prove_output = backend.prove(
             &inputs.artifact_path,
             Some(&witness_result.witness_path),
             inputs.timeout,
         )?;
+        if let Some(ms) = prove_output.setup_time_ms {
+            *setup_ms.get_or_insert(0.0) += ms as f64;
+        }
+        // Key generation is one-time work, so warmup runs count too
+        vk_gen_times.extend(prove_output.vk_gen_time_ms.map(|ms| ms as f64));
+        vk_gen_cpu.extend(prove_output.vk_gen_cpu);
+        vk_cached |= prove_output.vk_cached;
+        key_cache = key_cache.or(prove_output.key_cache);
 
         // Only collect times for measured iterations
         if !is_warmup {
             witness_times.push(witness_result.witness_gen_time_ms as f64);
             prove_times.push(prove_output.prove_time_ms as f64);
+            oracle_times.extend(witness_result.oracle_resolver_ms);
+            phase_runs.extend(prove_output.phases.clone());
+            prove_cpu.extend(prove_output.prove_cpu);
+        }
+
+        // Hash the last witness before it's removed
+        if i + 1 == total_runs {
+            witness_hash = file_sha256(&witness_result.witness_path);
         }
 
         // Cleanup witness file
@@ -385,16 +692,35 @@ pub fn full_benchmark(
         last_prove_output = Some(prove_output);
     }
 
+    record.env.thermal_pressure = thermal.finish();
+
     // Populate timing stats from collected samples
     record.witness_stats = Some(TimingStat::from_samples(&witness_times));
     record.prove_stats = Some(TimingStat::from_samples(&prove_times));
+    record.oracle_stats =
+        (!oracle_times.is_empty()).then(|| TimingStat::from_samples(&oracle_times));
+    record.backend_setup_ms = setup_ms;
+    record.vk_gen_stats =
+        (!vk_gen_times.is_empty()).then(|| TimingStat::from_samples(&vk_gen_times));
+    record.vk_cached = (vk_cached || !vk_gen_times.is_empty()).then_some(vk_cached);
+    record.key_cache = key_cache;
+    record.cache_mode = Some(inputs.cache_mode);
+    record.backend_phases = mean_phases(&phase_runs);
+    record.cpu_times = cpu_times(&prove_cpu, &vk_gen_cpu, logical_cores());
 
     let capabilities = backend.capabilities();
 
     // Get gate info (constraints)
     let (gate_info, gate_info_status) = if capabilities.has_gate_count {
-        match backend.gate_info(&inputs.artifact_path) {
-            Ok(info) => (Some(info), GateInfoStatus::Ok),
+        let gates = match probed_gates {
+            Some(gates) => Ok(gates),
+            None => gate_info_cached(backend, inputs),
+        };
+        match gates {
+            Ok((info, elapsed_ms)) => {
+                record.gates_stats = elapsed_ms.map(|ms| TimingStat::from_samples(&[ms]));
+                (Some(info), GateInfoStatus::Ok)
+            }
             Err(err) => (None, GateInfoStatus::Failed(err.to_string())),
         }
     } else {
@@ -410,6 +736,13 @@ pub fn full_benchmark(
         record.subgroup_size = gi.subgroup_size;
     }
 
+    record.fingerprints = Some(fingerprint_run(
+        inputs,
+        prover_toml,
+        witness_hash,
+        last_prove_output.as_ref(),
+    )?);
+
     // Populate size metrics from last run
     let (proof_path, vk_path) = if let Some(ref output) = last_prove_output {
         record.proof_size_bytes = output.proof_size_bytes;
@@ -460,6 +793,7 @@ pub fn full_benchmark(
         constraints,
         acir_opcodes,
         gate_info_status,
+        gate_info,
         verify_success,
         verify_status,
         verify_time_ms,
@@ -468,6 +802,174 @@ pub fn full_benchmark(
     })
 }
 
+/// Delete a project's `target/` so the next compile starts cold.
+fn clear_target_dir(project_dir: &Path) -> BenchResult<()> {
+    let target = project_dir.join("target");
+    match std::fs::remove_dir_all(&target) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(crate::BenchError::Message(format!(
+            "failed to clear {}: {e}",
+            target.display()
+        ))),
+    }
+}
+
+/// Edits a source file so the compiler sees a change, restoring it on drop.
+///
+/// Each touch rewrites the original contents plus a numbered trailing
+/// comment, so the file differs by content (not just mtime) from every
+/// earlier run.
+struct SourceTouch {
+    path: PathBuf,
+    original: Vec<u8>,
+    count: usize,
+}
+
+impl SourceTouch {
+    fn new(path: &Path) -> BenchResult<Self> {
+        let original = std::fs::read(path).map_err(|e| {
+            crate::BenchError::Message(format!("failed to read {}: {e}", path.display()))
+        })?;
+        Ok(SourceTouch {
+            path: path.to_path_buf(),
+            original,
+            count: 0,
+        })
+    }
+
+    fn touch(&mut self) -> BenchResult<()> {
+        self.count += 1;
+        let mut contents = self.original.clone();
+        contents.extend_from_slice(format!("\n// noir-bench touch {}\n", self.count).as_bytes());
+        std::fs::write(&self.path, contents).map_err(|e| {
+            crate::BenchError::Message(format!("failed to write {}: {e}", self.path.display()))
+        })
+    }
+}
+
+impl Drop for SourceTouch {
+    fn drop(&mut self) {
+        let _ = std::fs::write(&self.path, &self.original);
+    }
+}
+
+/// Benchmark compilation of a Noir project.
+///
+/// Cold runs delete the project's `target/` before each compile and are
+/// recorded in `compile_stats`. Warm runs follow with `target/` left in
+/// place and are recorded in `compile_warm_stats`. With `touch` set (a
+/// source file relative to `project_dir`), incremental runs edit that file
+/// before each compile and are recorded in `compile_incremental_stats`; the
+/// file is restored afterwards. Warmup runs are discarded in every phase.
+///
+/// # Arguments
+/// * `toolchain` - The toolchain to compile with
+/// * `project_dir` - Directory containing Nargo.toml
+/// * `circuit_name` - Circuit name for the record
+/// * `touch` - Source file to edit for incremental runs, if any
+/// * `warmup` - Number of warmup compiles per phase (not measured)
+/// * `iterations` - Number of measured compiles per phase
+pub fn compile_benchmark(
+    toolchain: &dyn Toolchain,
+    project_dir: &Path,
+    circuit_name: &str,
+    touch: Option<&Path>,
+    warmup: usize,
+    iterations: usize,
+) -> BenchResult<BenchRecord> {
+    if iterations == 0 {
+        return Err(crate::BenchError::Message(
+            "iterations must be at least 1".into(),
+        ));
+    }
+
+    let total_runs = warmup + iterations;
+    let mut cold_times: Vec<f64> = Vec::with_capacity(iterations);
+    let mut warm_times: Vec<f64> = Vec::with_capacity(iterations);
+    let mut artifact_path = None;
+
+    let toolchain_version = toolchain.version().ok();
+    let backend_info = BackendInfo {
+        name: toolchain.name().to_string(),
+        version: toolchain_version.clone(),
+        variant: None,
+    };
+    let config = RunConfig {
+        warmup_iterations: warmup as u32,
+        measured_iterations: iterations as u32,
+        timeout_secs: None,
+        compile_flags: Vec::new(),
+        env: BTreeMap::new(),
+    };
+    let mut record = BenchRecord::new(
+        circuit_name.to_string(),
+        EnvironmentInfo::detect(),
+        backend_info,
+        config,
+    );
+
+    let thermal = ThermalSampler::start();
+    for i in 0..total_runs {
+        clear_target_dir(project_dir)?;
+        let output = toolchain.compile(project_dir)?;
+        if i >= warmup {
+            cold_times.push(output.compile_time_ms as f64);
+        }
+        artifact_path = Some(output.artifact_path);
+    }
+
+    // The last cold run left target/ populated, so these start warm
+    for i in 0..total_runs {
+        let output = toolchain.compile(project_dir)?;
+        if i >= warmup {
+            warm_times.push(output.compile_time_ms as f64);
+        }
+        artifact_path = Some(output.artifact_path);
+    }
+
+    record.compile_stats = Some(TimingStat::from_samples(&cold_times));
+    record.compile_warm_stats = Some(TimingStat::from_samples(&warm_times));
+
+    if let Some(touch) = touch {
+        let mut source = SourceTouch::new(&project_dir.join(touch))?;
+        let mut incremental_times: Vec<f64> = Vec::with_capacity(iterations);
+        for i in 0..total_runs {
+            source.touch()?;
+            let output = toolchain.compile(project_dir)?;
+            if i >= warmup {
+                incremental_times.push(output.compile_time_ms as f64);
+            }
+        }
+        // Restore the source and rebuild so target/ matches it again
+        drop(source);
+        artifact_path = Some(toolchain.compile(project_dir)?.artifact_path);
+        record.compile_incremental_stats = Some(TimingStat::from_samples(&incremental_times));
+        record
+            .metadata
+            .insert("touched_file".to_string(), touch.display().to_string());
+    }
+
+    record.env.thermal_pressure = thermal.finish();
+
+    if let Some(path) = artifact_path {
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            record.artifact_size_bytes = Some(metadata.len());
+        }
+        record.fingerprints = Some(Fingerprints {
+            acir_hash: file_sha256(&path),
+            ..Default::default()
+        });
+        record.circuit_path = Some(path.to_string_lossy().to_string());
+    }
+
+    if toolchain_version.is_some() {
+        record.env.nargo_version = toolchain_version;
+    }
+
+    Ok(record)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -490,6 +992,13 @@ mod tests {
                 verification_key_size_bytes: Some(512),
                 proof_path: None,
                 vk_path: None,
+                setup_time_ms: None,
+                phases: None,
+                vk_gen_time_ms: None,
+                vk_cached: false,
+                key_cache: None,
+                prove_cpu: None,
+                vk_gen_cpu: None,
             }),
         )
     }
@@ -506,7 +1015,7 @@ mod tests {
         let record = result.unwrap();
         assert_eq!(record.circuit_name, "test-circuit");
         assert_eq!(record.backend.name, "mock-backend");
-        assert_eq!(record.schema_version, 1);
+        assert_eq!(record.schema_version, crate::core::SCHEMA_VERSION);
     }
 
     #[test]
@@ -616,6 +1125,77 @@ mod tests {
         assert_eq!(prove_stats.iterations, 3);
     }
 
+    #[test]
+    fn test_prove_with_iterations_tracks_vk_generation_and_reuse() {
+        let toolchain = create_mock_toolchain();
+        let inputs = ProveInputs::new("/tmp/test-artifact.json", "test-circuit");
+        let output = ProveOutput {
+            prove_time_ms: 100,
+            vk_gen_time_ms: Some(40),
+            ..ProveOutput::default()
+        };
+
+        // Generated in the warmup run, reused by the measured ones
+        let cached = MockBackend::new(
+            MockConfig::new("mock-backend")
+                .with_prove_output(output.clone())
+                .with_vk_cache(),
+        );
+        let record = prove_with_iterations(&toolchain, &cached, &inputs, 1, 3).unwrap();
+        let vk_gen = record.vk_gen_stats.unwrap();
+        assert_eq!(vk_gen.iterations, 1);
+        assert_eq!(vk_gen.mean_ms, 40.0);
+        assert_eq!(record.vk_cached, Some(true));
+        assert_eq!(record.prove_stats.unwrap().mean_ms, 100.0);
+
+        // Generated by every run
+        let fresh = MockBackend::new(MockConfig::new("mock-backend").with_prove_output(output));
+        let record = prove_with_iterations(&toolchain, &fresh, &inputs, 0, 3).unwrap();
+        assert_eq!(record.vk_gen_stats.unwrap().iterations, 3);
+        assert_eq!(record.vk_cached, Some(false));
+
+        // Not reported by the backend at all
+        let record =
+            prove_with_iterations(&toolchain, &create_mock_backend(), &inputs, 0, 2).unwrap();
+        assert!(record.vk_gen_stats.is_none());
+        assert!(record.vk_cached.is_none());
+    }
+
+    #[test]
+    fn test_prove_with_iterations_records_cpu_times() {
+        let toolchain = create_mock_toolchain();
+        let inputs = ProveInputs::new("/tmp/test-artifact.json", "test-circuit");
+        let cpu = |wall_ms, user_ms| CpuTime {
+            wall_ms,
+            user_ms,
+            sys_ms: 10.0,
+        };
+        let backend = MockBackend::new(
+            MockConfig::new("mock-backend")
+                .with_prove_output(ProveOutput {
+                    prove_time_ms: 100,
+                    vk_gen_time_ms: Some(40),
+                    prove_cpu: Some(cpu(100.0, 390.0)),
+                    vk_gen_cpu: Some(cpu(40.0, 30.0)),
+                    ..ProveOutput::default()
+                })
+                .with_vk_cache(),
+        );
+        let record = prove_with_iterations(&toolchain, &backend, &inputs, 1, 2).unwrap();
+        let times = record.cpu_times.unwrap();
+        assert_eq!(times["prove"].cpu_ms, 400.0);
+        assert_eq!(times["vk_gen"].wall_ms, 40.0);
+
+        let times = cpu_times(&[cpu(100.0, 390.0)], &[], Some(8)).unwrap();
+        assert_eq!(times["prove"].parallelism_efficiency, Some(0.5));
+        assert!(!times.contains_key("vk_gen"));
+        assert!(cpu_times(&[], &[], Some(8)).is_none());
+
+        let record =
+            prove_with_iterations(&toolchain, &create_mock_backend(), &inputs, 0, 2).unwrap();
+        assert!(record.cpu_times.is_none());
+    }
+
     #[test]
     fn test_prove_with_iterations_zero_fails() {
         let toolchain = create_mock_toolchain();
@@ -625,4 +1205,204 @@ mod tests {
         let result = prove_with_iterations(&toolchain, &backend, &inputs, 0, 0);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_prove_with_iterations_records_fingerprints() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("circuit.json");
+        let prover_toml = dir.path().join("Prover.toml");
+        let witness = dir.path().join("witness.gz");
+        let proof = dir.path().join("proof");
+        let vk = dir.path().join("vk");
+        std::fs::write(&artifact, b"{}").unwrap();
+        std::fs::write(&prover_toml, b"x = 1").unwrap();
+        std::fs::write(&witness, b"witness").unwrap();
+        std::fs::write(&proof, b"proof").unwrap();
+        std::fs::write(&vk, b"vk").unwrap();
+
+        let mut toolchain = create_mock_toolchain();
+        toolchain.witness_output = Some(crate::engine::WitnessArtifact {
+            witness_path: witness.clone(),
+            witness_gen_time_ms: 5,
+            oracle_resolver_ms: None,
+            dir: None,
+        });
+        let backend = MockBackend::new(MockConfig::new("mock-backend").with_prove_output(
+            ProveOutput {
+                proof_path: Some(proof.clone()),
+                vk_path: Some(vk.clone()),
+                ..ProveOutput::default()
+            },
+        ));
+        let store = dir.path().join("store");
+        let inputs = ProveInputs::new(&artifact, "test-circuit")
+            .with_prover_toml(&prover_toml)
+            .with_artifact_store(&store);
+
+        let record = prove_with_iterations(&toolchain, &backend, &inputs, 0, 1).unwrap();
+
+        let fp = record.fingerprints.unwrap();
+        assert_eq!(fp.acir_hash, Some(crate::sha256_hex(b"{}")));
+        assert_eq!(fp.inputs_hash, Some(crate::sha256_hex(b"x = 1")));
+        assert_eq!(fp.witness_hash, Some(crate::sha256_hex(b"witness")));
+        assert!(!witness.exists(), "witness is still cleaned up");
+
+        let store = ArtifactStore::new(&store);
+        assert!(store.get_proof(fp.proof_hash.as_deref().unwrap()).is_ok());
+        assert!(store.get_vk(fp.vk_hash.as_deref().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_full_benchmark_reuses_cached_gate_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("circuit.json");
+        std::fs::write(&artifact, b"{}").unwrap();
+        let inputs =
+            ProveInputs::new(&artifact, "test-circuit").with_gate_cache(dir.path().join("gates"));
+        let toolchain = create_mock_toolchain();
+
+        let backend = MockBackend::new(
+            MockConfig::new("mock-backend").with_gate_info(GateInfo::from_gates(10_000)),
+        );
+        let first = full_benchmark(&toolchain, &backend, &inputs, 0, 1).unwrap();
+        assert_eq!(first.constraints, Some(10_000));
+        assert_eq!(first.record.gates_stats.unwrap().iterations, 1);
+
+        // The backend isn't asked again, and no timing is reported for the hit
+        let backend = MockBackend::new(MockConfig::new("mock-backend").gate_info_fails());
+        let second = full_benchmark(&toolchain, &backend, &inputs, 0, 1).unwrap();
+        assert!(matches!(second.gate_info_status, GateInfoStatus::Ok));
+        assert_eq!(second.constraints, Some(10_000));
+        assert!(second.record.gates_stats.is_none());
+    }
+
+    #[test]
+    fn test_probe_runs_before_measuring() {
+        let toolchain = create_mock_toolchain();
+        let inputs = ProveInputs::new("/mock/artifact.json", "test-circuit").with_probe(true);
+
+        let backend = MockBackend::new(
+            MockConfig::new("mock-backend").with_gate_info(GateInfo::from_gates(10_000)),
+        );
+        let result = full_benchmark(&toolchain, &backend, &inputs, 0, 1).unwrap();
+        assert!(result.record.probe_ms.is_some());
+        assert_eq!(result.record.prove_stats.unwrap().iterations, 1);
+        // The probe's gate analysis is the run's
+        assert_eq!(backend.gate_info_calls(), 1);
+        assert_eq!(result.record.total_gates, Some(10_000));
+        assert_eq!(result.record.gates_stats.unwrap().iterations, 1);
+
+        let backend = MockBackend::new(MockConfig::new("mock-backend").gate_info_fails());
+        let err = prove_with_iterations(&toolchain, &backend, &inputs, 0, 1).unwrap_err();
+        assert!(err.to_string().contains("backend health check failed"));
+
+        // Without the probe nothing is recorded
+        let record = prove_with_iterations(
+            &toolchain,
+            &backend,
+            &ProveInputs::new("/mock/artifact.json", "test-circuit"),
+            0,
+            1,
+        )
+        .unwrap();
+        assert!(record.probe_ms.is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cold_cache_mode_is_recorded() {
+        let toolchain = create_mock_toolchain();
+        let backend = MockBackend::new(MockConfig::new("mock-backend"));
+        let inputs = ProveInputs::new("/mock/artifact.json", "test-circuit");
+        let record = prove_with_iterations(&toolchain, &backend, &inputs, 0, 1).unwrap();
+        assert_eq!(record.cache_mode, Some(CacheMode::Warm));
+
+        // Missing mock files are skipped by the eviction
+        let inputs = inputs.with_cache_mode(CacheMode::Cold);
+        let result = full_benchmark(&toolchain, &backend, &inputs, 1, 2).unwrap();
+        assert_eq!(result.record.cache_mode, Some(CacheMode::Cold));
+        assert_eq!(result.record.prove_stats.unwrap().iterations, 2);
+    }
+
+    #[test]
+    fn test_full_benchmark_reuses_gate_info_from_earlier_run() {
+        let toolchain = create_mock_toolchain();
+        let backend = MockBackend::new(
+            MockConfig::new("mock-backend").with_gate_info(GateInfo::from_gates(10_000)),
+        );
+        let inputs = ProveInputs::new("/mock/artifact.json", "test-circuit");
+        let first = full_benchmark(&toolchain, &backend, &inputs, 0, 1).unwrap();
+        assert!(first.record.gates_stats.is_some());
+
+        let backend = MockBackend::new(MockConfig::new("mock-backend").gate_info_fails());
+        let inputs = inputs.with_gate_info(first.gate_info.unwrap());
+        let second = full_benchmark(&toolchain, &backend, &inputs, 0, 1).unwrap();
+        assert_eq!(second.record.total_gates, Some(10_000));
+        assert!(second.record.gates_stats.is_none());
+    }
+
+    #[test]
+    fn test_compile_benchmark_records_cold_and_warm_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/stale.json"), b"{}").unwrap();
+
+        let record =
+            compile_benchmark(&create_mock_toolchain(), dir.path(), "project", None, 1, 3).unwrap();
+
+        // Cold runs clear target/ (the mock toolchain doesn't recreate it)
+        assert!(!dir.path().join("target").exists());
+        let cold = record.compile_stats.unwrap();
+        let warm = record.compile_warm_stats.unwrap();
+        assert_eq!(cold.iterations, 3);
+        assert_eq!(warm.iterations, 3);
+        assert_eq!(cold.mean_ms, 50.0);
+        assert_eq!(record.config.warmup_iterations, 1);
+        assert_eq!(record.backend.name, "mock-nargo");
+        assert_eq!(record.env.nargo_version.as_deref(), Some("0.38.0-test"));
+        assert!(record.prove_stats.is_none());
+        assert!(record.compile_incremental_stats.is_none());
+    }
+
+    #[test]
+    fn test_compile_benchmark_incremental_restores_touched_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        let main = dir.path().join("src/main.nr");
+        std::fs::write(&main, "fn main() {}\n").unwrap();
+
+        let record = compile_benchmark(
+            &create_mock_toolchain(),
+            dir.path(),
+            "project",
+            Some(Path::new("src/main.nr")),
+            0,
+            2,
+        )
+        .unwrap();
+
+        assert_eq!(record.compile_incremental_stats.unwrap().iterations, 2);
+        assert_eq!(
+            record.metadata.get("touched_file").map(String::as_str),
+            Some("src/main.nr")
+        );
+        assert_eq!(std::fs::read_to_string(&main).unwrap(), "fn main() {}\n");
+
+        let err = compile_benchmark(
+            &create_mock_toolchain(),
+            dir.path(),
+            "project",
+            Some(Path::new("src/missing.nr")),
+            0,
+            1,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("missing.nr"), "{err}");
+    }
+
+    #[test]
+    fn test_compile_benchmark_rejects_zero_iterations() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(compile_benchmark(&create_mock_toolchain(), dir.path(), "p", None, 0, 0).is_err());
+    }
 }
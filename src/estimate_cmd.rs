@@ -0,0 +1,183 @@
+//! CLI command handler for `estimate`.
+//!
+//! Predicts prove time and peak memory for a compiled circuit without
+//! proving it: gate counts come from the backend, the prediction from the
+//! backend's cost model (see `core::cost_model`), scaled to this machine's
+//! calibration score.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::calibration;
+use crate::core::cost_model::{self, CostModel, Prediction};
+use crate::{BenchError, BenchResult};
+
+/// Predicted cost of proving one circuit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Estimate {
+    pub circuit_name: String,
+    pub backend: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<String>,
+    pub total_gates: u64,
+    pub subgroup_size: u64,
+    pub calibration_score: f64,
+    pub prove_ms: Prediction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_rss_mb: Option<Prediction>,
+    /// Where the model came from (file path or "built-in default")
+    pub model_source: String,
+    /// Records the model was fitted on
+    pub model_samples: usize,
+}
+
+/// Estimate proving cost for an artifact with the given backend and model.
+pub fn estimate(
+    backend: &dyn Backend,
+    artifact: &Path,
+    circuit_name: &str,
+    model: &CostModel,
+    model_source: &str,
+    calibration_score: f64,
+) -> BenchResult<Estimate> {
+    let info = backend.gate_info(artifact)?;
+    let subgroup_size = info
+        .subgroup_size
+        .unwrap_or_else(|| info.backend_gates.max(1).next_power_of_two());
+    let (prove_ms, peak_rss_mb) = model.predict(subgroup_size, calibration_score);
+    Ok(Estimate {
+        circuit_name: circuit_name.to_string(),
+        backend: backend.name().to_string(),
+        backend_version: backend.version(),
+        total_gates: info.backend_gates,
+        subgroup_size,
+        calibration_score,
+        prove_ms,
+        peak_rss_mb,
+        model_source: model_source.to_string(),
+        model_samples: model.samples,
+    })
+}
+
+fn fmt_ms(ms: f64) -> String {
+    if ms >= 60_000.0 {
+        format!("{:.1}min", ms / 60_000.0)
+    } else if ms >= 1000.0 {
+        format!("{:.2}s", ms / 1000.0)
+    } else {
+        format!("{ms:.0}ms")
+    }
+}
+
+fn format_text(e: &Estimate) -> String {
+    let mut out = format!(
+        "estimate: {} gates={} subgroup=2^{} ({})\n",
+        e.circuit_name,
+        e.total_gates,
+        e.subgroup_size.trailing_zeros(),
+        e.subgroup_size
+    );
+    out.push_str(&format!(
+        "  prove    ~{} (95%: {} - {})\n",
+        fmt_ms(e.prove_ms.estimate),
+        fmt_ms(e.prove_ms.low),
+        fmt_ms(e.prove_ms.high)
+    ));
+    if let Some(rss) = &e.peak_rss_mb {
+        out.push_str(&format!(
+            "  peak_rss ~{:.0} MB (95%: {:.0} - {:.0} MB)\n",
+            rss.estimate, rss.low, rss.high
+        ));
+    }
+    out.push_str(&format!(
+        "  model: {} ({} samples), calibration score {:.1}\n",
+        e.model_source, e.model_samples, e.calibration_score
+    ));
+    out
+}
+
+/// Main entry point for the estimate command.
+pub fn run(
+    artifact: PathBuf,
+    name: Option<String>,
+    backend_path: PathBuf,
+    model: Option<PathBuf>,
+    models_dir: PathBuf,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    if !artifact.exists() {
+        return Err(BenchError::Message(format!(
+            "artifact not found: {}",
+            artifact.display()
+        )));
+    }
+    let backend = BarretenbergBackend::new(BarretenbergConfig::new(backend_path));
+    let (model, source) = match model {
+        Some(path) => (CostModel::load(&path)?, path.display().to_string()),
+        None => cost_model::resolve(&models_dir, backend.name(), backend.version().as_deref())?,
+    };
+    let name = name.unwrap_or_else(|| {
+        artifact
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("circuit")
+            .to_string()
+    });
+
+    let result = estimate(
+        &backend,
+        &artifact,
+        &name,
+        &model,
+        &source,
+        calibration::measure(),
+    )?;
+
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        let bytes = serde_json::to_vec_pretty(&result)
+            .map_err(|e| BenchError::Message(format!("failed to serialize estimate: {e}")))?;
+        std::fs::write(&json, bytes)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", json.display())))?;
+    }
+    print!("{}", format_text(&result));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig};
+
+    #[test]
+    fn test_estimate_uses_backend_gate_counts() {
+        let backend = MockBackend::new(MockConfig::new("mock-bb"));
+        let model = CostModel::builtin("barretenberg").unwrap();
+        let e = estimate(
+            &backend,
+            Path::new("/tmp/program.json"),
+            "merkle",
+            &model,
+            "built-in default",
+            model.calibration_score,
+        )
+        .unwrap();
+
+        assert_eq!(e.total_gates, 1000);
+        assert_eq!(e.subgroup_size, 1024);
+        assert!(e.prove_ms.low < e.prove_ms.estimate);
+        assert!(e.prove_ms.estimate < e.prove_ms.high);
+        assert!(format_text(&e).contains("subgroup=2^10 (1024)"));
+    }
+
+    #[test]
+    fn test_fmt_ms() {
+        assert_eq!(fmt_ms(250.0), "250ms");
+        assert_eq!(fmt_ms(1500.0), "1.50s");
+        assert_eq!(fmt_ms(90_000.0), "1.5min");
+    }
+}
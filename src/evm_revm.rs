@@ -0,0 +1,225 @@
+//! In-process EVM verification with revm.
+//!
+//! Deploys the verifier's creation bytecode into an empty in-memory chain and
+//! calls it with the proof, so gas is measured deterministically without
+//! forge, Hardhat or a node. The gas is that of the whole call transaction,
+//! intrinsic and calldata costs included: what a relayer submitting the proof
+//! pays, and slightly more than forge reports for the test function alone.
+
+use std::path::{Path, PathBuf};
+
+use revm::Evm;
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{AccountInfo, Address, Bytes, ExecutionResult, Output, TxKind, U256};
+
+use crate::proof_cmd;
+use crate::{BenchError, BenchResult};
+
+pub use crate::proof_cmd::{VERIFY_SIGNATURE, encode_verify_call};
+
+/// What to deploy and how to call it.
+#[derive(Debug, Clone)]
+pub struct RevmInputs {
+    /// Creation bytecode: a hex file, or a forge/Hardhat artifact JSON
+    pub bytecode: PathBuf,
+    /// Raw calldata as hex; takes precedence over `proof`
+    pub calldata: Option<PathBuf>,
+    /// Proof (raw, hex or fields), encoded into a `verify(bytes,bytes32[])`
+    /// call
+    pub proof: Option<PathBuf>,
+    /// Public inputs (raw, hex or fields), 32 bytes each
+    pub public_inputs: Option<PathBuf>,
+}
+
+/// Gas of one verification call.
+#[derive(Debug, Clone, Copy)]
+pub struct RevmMeasurement {
+    pub deploy_gas: u64,
+    pub gas_used: u64,
+    pub calldata_bytes: u64,
+}
+
+fn read(path: &Path) -> BenchResult<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))
+}
+
+fn decode_hex(text: &str, path: &Path) -> BenchResult<Vec<u8>> {
+    proof_cmd::decode_hex(text)
+        .ok_or_else(|| BenchError::Message(format!("invalid hex in {}", path.display())))
+}
+
+/// Creation bytecode from a hex file or an artifact JSON (`bytecode` as a
+/// string, or forge's `bytecode.object`).
+pub fn load_bytecode(path: &Path) -> BenchResult<Vec<u8>> {
+    let text = String::from_utf8(read(path)?)
+        .map_err(|_| BenchError::Message(format!("{} is not text", path.display())))?;
+    if !text.trim_start().starts_with('{') {
+        return decode_hex(&text, path);
+    }
+    let json: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| BenchError::Message(format!("invalid JSON in {}: {e}", path.display())))?;
+    let bytecode = json
+        .get("bytecode")
+        .and_then(|b| b.as_str().or_else(|| b.get("object")?.as_str()));
+    match bytecode {
+        Some(hex) => decode_hex(hex, path),
+        None => Err(BenchError::Message(format!(
+            "no bytecode in artifact {}",
+            path.display()
+        ))),
+    }
+}
+
+fn calldata(inputs: &RevmInputs) -> BenchResult<Vec<u8>> {
+    proof_cmd::load_verify_call(
+        inputs.calldata.as_deref(),
+        inputs.proof.as_deref(),
+        inputs.public_inputs.as_deref(),
+    )?
+    .ok_or_else(|| {
+        BenchError::Message("revm needs --calldata or --proof (with --public-inputs)".into())
+    })
+}
+
+/// Deploy the verifier and call it once, failing unless it returns true.
+pub fn measure(inputs: &RevmInputs) -> BenchResult<RevmMeasurement> {
+    let bytecode = load_bytecode(&inputs.bytecode)?;
+    let calldata = calldata(inputs)?;
+    execute(bytecode, calldata)
+}
+
+/// Deploy `bytecode` and call it with `calldata`.
+pub fn execute(bytecode: Vec<u8>, calldata: Vec<u8>) -> BenchResult<RevmMeasurement> {
+    let caller = Address::with_last_byte(1);
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        caller,
+        AccountInfo {
+            balance: U256::MAX,
+            ..Default::default()
+        },
+    );
+    let calldata_bytes = calldata.len() as u64;
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .modify_cfg_env(|cfg| {
+            // Generated verifiers often exceed the 24KB mainnet limit
+            cfg.limit_contract_code_size = Some(1024 * 1024);
+        })
+        .modify_tx_env(|tx| {
+            tx.caller = caller;
+            tx.transact_to = TxKind::Create;
+            tx.data = Bytes::from(bytecode);
+            tx.nonce = None;
+        })
+        .build();
+
+    let deploy = evm
+        .transact_commit()
+        .map_err(|e| BenchError::Message(format!("revm deployment failed: {e:?}")))?;
+    let (address, deploy_gas) = match deploy {
+        ExecutionResult::Success {
+            output: Output::Create(_, Some(address)),
+            gas_used,
+            ..
+        } => (address, gas_used),
+        other => {
+            return Err(BenchError::Message(format!(
+                "verifier deployment failed: {other:?}"
+            )));
+        }
+    };
+
+    let tx = evm.tx_mut();
+    tx.transact_to = TxKind::Call(address);
+    tx.data = Bytes::from(calldata);
+    let call = evm
+        .transact_commit()
+        .map_err(|e| BenchError::Message(format!("revm call failed: {e:?}")))?;
+    match call {
+        ExecutionResult::Success {
+            output: Output::Call(ret),
+            gas_used,
+            ..
+        } => {
+            // A bool return is one word ending in 1
+            if ret.len() == 32 && ret[31] == 1 {
+                Ok(RevmMeasurement {
+                    deploy_gas,
+                    gas_used,
+                    calldata_bytes,
+                })
+            } else {
+                Err(BenchError::Message(format!(
+                    "verifier returned false (0x{})",
+                    hex::encode(&ret)
+                )))
+            }
+        }
+        ExecutionResult::Revert { output, gas_used } => Err(BenchError::Message(format!(
+            "verifier reverted after {gas_used} gas (0x{})",
+            hex::encode(&output)
+        ))),
+        other => Err(BenchError::Message(format!(
+            "verifier call failed: {other:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::keccak256;
+
+    #[test]
+    fn test_encode_verify_call() {
+        let call = encode_verify_call(&[0xaa; 33], &[0x11; 64]).unwrap();
+        assert_eq!(
+            hex::encode(&call[..4]),
+            hex::encode(&keccak256(VERIFY_SIGNATURE).as_slice()[..4])
+        );
+        let word = |i: usize| U256::from_be_slice(&call[4 + 32 * i..4 + 32 * (i + 1)]);
+        assert_eq!(word(0), U256::from(0x40));
+        // Offset of the array: after the proof length word and 64 padded bytes
+        assert_eq!(word(1), U256::from(0x40 + 32 + 64));
+        assert_eq!(word(2), U256::from(33));
+        assert_eq!(word(5), U256::from(2));
+        assert_eq!(call.len(), 4 + 32 * 8);
+        assert!(encode_verify_call(&[], &[0; 31]).is_err());
+    }
+
+    #[test]
+    fn test_execute_measures_a_returning_contract() {
+        // Runtime: return the 32-byte word 1 (PUSH1 1 PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN)
+        let runtime = "600160005260206000f3";
+        // Init code: copy the 10-byte runtime to memory and return it
+        let init = format!("600a600c600039600a6000f3{runtime}");
+        let m = execute(hex::decode(init).unwrap(), vec![1, 2, 3]).unwrap();
+        assert_eq!(m.calldata_bytes, 3);
+        // Intrinsic 21000, three non-zero calldata bytes, then the call itself
+        assert!(m.gas_used > 21_000 + 3 * 16);
+        assert!(m.gas_used < 21_100);
+        assert!(m.deploy_gas > 53_000);
+    }
+
+    #[test]
+    fn test_execute_rejects_false() {
+        // Runtime returns the zero word
+        let runtime = "600060005260206000f3";
+        let init = format!("600a600c600039600a6000f3{runtime}");
+        let err = execute(hex::decode(init).unwrap(), vec![]).unwrap_err();
+        assert!(err.to_string().contains("verifier returned false"));
+    }
+
+    #[test]
+    fn test_load_bytecode_from_forge_artifact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Verifier.json");
+        std::fs::write(&path, r#"{"bytecode":{"object":"0x6001"}}"#).unwrap();
+        assert_eq!(load_bytecode(&path).unwrap(), vec![0x60, 0x01]);
+        let hex_path = dir.path().join("verifier.hex");
+        std::fs::write(&hex_path, "6001\n").unwrap();
+        assert_eq!(load_bytecode(&hex_path).unwrap(), vec![0x60, 0x01]);
+    }
+}
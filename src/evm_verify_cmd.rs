@@ -1,11 +1,11 @@
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
-use noir_artifact_cli::fs::artifact::read_program_from_file;
+use serde::Deserialize;
 
 use crate::{
-    BackendInfo, BenchError, BenchResult, CommonMeta, EvmVerifyReport, SystemInfo,
-    collect_system_info,
+    BackendInfo, BenchError, BenchResult, CommonMeta, EvmBatchPoint, EvmBatchReport,
+    EvmVerifyReport, SystemInfo, collect_system_info,
 };
 
 fn read_gas_from_snapshot(snapshot_path: &Path, match_pattern: &Option<String>) -> Option<u128> {
@@ -56,6 +56,74 @@ fn read_gas_from_stdout(stdout: &str) -> Option<u128> {
     None
 }
 
+/// Where the verifier runs.
+#[derive(Debug, Clone)]
+pub enum EvmToolchain {
+    /// `forge test --gas-report` in a Foundry project
+    Foundry { forge_bin: Option<PathBuf> },
+    /// `npx hardhat test` in a Hardhat project; the test logs `gas: <n>`
+    Hardhat,
+    /// In-process revm, no external binary
+    #[cfg(feature = "revm")]
+    Revm(crate::evm_revm::RevmInputs),
+}
+
+/// Toolchain named by `--toolchain` or a pipeline stage's `toolchain`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvmToolchainKind {
+    #[default]
+    Foundry,
+    Hardhat,
+    Revm,
+}
+
+impl EvmToolchain {
+    /// Build the toolchain of `kind`; the revm inputs are ignored by the
+    /// others.
+    pub fn from_args(
+        kind: EvmToolchainKind,
+        forge_bin: Option<PathBuf>,
+        bytecode: Option<PathBuf>,
+        calldata: Option<PathBuf>,
+        proof: Option<PathBuf>,
+        public_inputs: Option<PathBuf>,
+    ) -> BenchResult<Self> {
+        match kind {
+            EvmToolchainKind::Foundry => Ok(EvmToolchain::Foundry { forge_bin }),
+            EvmToolchainKind::Hardhat => Ok(EvmToolchain::Hardhat),
+            #[cfg(feature = "revm")]
+            EvmToolchainKind::Revm => {
+                let bytecode = bytecode.ok_or_else(|| {
+                    BenchError::Message("--toolchain revm needs --bytecode".into())
+                })?;
+                Ok(EvmToolchain::Revm(crate::evm_revm::RevmInputs {
+                    bytecode,
+                    calldata,
+                    proof,
+                    public_inputs,
+                }))
+            }
+            #[cfg(not(feature = "revm"))]
+            EvmToolchainKind::Revm => {
+                let _ = (bytecode, calldata, proof, public_inputs);
+                Err(BenchError::Message(
+                    "this build has no revm support (enable the `revm` feature)".into(),
+                ))
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            EvmToolchain::Foundry { .. } => "foundry",
+            EvmToolchain::Hardhat => "hardhat",
+            #[cfg(feature = "revm")]
+            EvmToolchain::Revm(_) => "revm",
+        }
+    }
+}
+
 fn foundry_backend_info(forge_bin: &Path) -> BackendInfo {
     let version = Command::new(forge_bin)
         .arg("--version")
@@ -70,6 +138,60 @@ fn foundry_backend_info(forge_bin: &Path) -> BackendInfo {
     }
 }
 
+fn hardhat_backend_info(project_dir: &Path) -> BackendInfo {
+    let version = Command::new("npx")
+        .args(["hardhat", "--version"])
+        .current_dir(project_dir)
+        .output()
+        .ok()
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    BackendInfo {
+        name: "hardhat".into(),
+        version,
+    }
+}
+
+/// Run `npx hardhat test` with `envs` set and return its stdout.
+fn run_hardhat(
+    project_dir: &Path,
+    test_pattern: &Option<String>,
+    envs: &[(&str, String)],
+) -> BenchResult<String> {
+    let mut cmd = Command::new("npx");
+    cmd.args(["hardhat", "test"]);
+    if let Some(pat) = test_pattern {
+        cmd.arg("--grep").arg(pat);
+    }
+    cmd.envs(envs.iter().map(|(k, v)| (k, v)));
+    cmd.current_dir(project_dir);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let output = cmd
+        .output()
+        .map_err(|e| BenchError::Message(format!("failed to run npx hardhat: {e}")))?;
+    if !output.status.success() {
+        return Err(BenchError::Message(format!(
+            "hardhat test failed. stderr=\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Calldata size logged by the test as `CALDATA_BYTES: <n>`.
+fn read_calldata_from_stdout(stdout: &str) -> Option<u64> {
+    let idx = stdout.find("CALDATA_BYTES:")?;
+    let num: String = stdout[idx + "CALDATA_BYTES:".len()..]
+        .chars()
+        .skip_while(|c| c.is_whitespace())
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    num.parse().ok()
+}
+
 fn estimate_latency_ms(gas_used: u128, gas_per_second: u64) -> u64 {
     if gas_per_second == 0 {
         return 0;
@@ -78,24 +200,32 @@ fn estimate_latency_ms(gas_used: u128, gas_per_second: u64) -> u64 {
     (secs * 1000.0).round() as u64
 }
 
-pub fn run(
-    foundry_dir: PathBuf,
-    artifact: Option<PathBuf>,
-    test_pattern: Option<String>,
-    calldata_bytes: Option<u64>,
-    gas_per_second: Option<u64>,
-    forge_bin: Option<PathBuf>,
-    json_out: Option<PathBuf>,
-) -> BenchResult<()> {
-    let forge = forge_bin.unwrap_or_else(|| PathBuf::from("forge"));
+/// Environment variable holding the number of proofs a batch test verifies.
+pub const BATCH_SIZE_ENV: &str = "NOIR_BENCH_BATCH_SIZE";
+
+/// Environment variable holding the `verify(bytes,bytes32[])` calldata as
+/// `0x` hex, for tests to send instead of an embedded fixture (Foundry:
+/// `vm.envBytes`).
+pub const CALLDATA_ENV: &str = "NOIR_BENCH_CALLDATA";
 
-    // Execute forge test with gas report
-    let mut cmd = Command::new(&forge);
+/// Environment variable holding the path of a file with the same hex
+/// calldata, for calls too large to pass in the environment.
+pub const CALLDATA_FILE_ENV: &str = "NOIR_BENCH_CALLDATA_FILE";
+
+/// Run `forge test --gas-report` with `envs` set and return its stdout.
+fn run_forge(
+    forge: &Path,
+    foundry_dir: &Path,
+    test_pattern: &Option<String>,
+    envs: &[(&str, String)],
+) -> BenchResult<String> {
+    let mut cmd = Command::new(forge);
     cmd.arg("test").arg("--gas-report");
-    if let Some(pat) = &test_pattern {
+    if let Some(pat) = test_pattern {
         cmd.arg("-m").arg(pat);
     }
-    cmd.current_dir(&foundry_dir);
+    cmd.envs(envs.iter().map(|(k, v)| (k, v)));
+    cmd.current_dir(foundry_dir);
     cmd.stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -111,36 +241,13 @@ pub fn run(
             stderr_s
         )));
     }
+    Ok(stdout_s)
+}
 
-    // Prefer .gas-snapshot, fallback to stdout heuristic
-    let snapshot_path = foundry_dir.join(".gas-snapshot");
-    let gas_used = read_gas_from_snapshot(&snapshot_path, &test_pattern)
-        .or_else(|| read_gas_from_stdout(&stdout_s))
-        .ok_or_else(|| {
-            BenchError::Message("failed to parse gas used from Foundry outputs".into())
-        })?;
-
-    // Calldata bytes: user-provided or discover from stdout if test logs a line like "CALDATA_BYTES: <n>"
-    let mut calldata_b = calldata_bytes;
-    if calldata_b.is_none() {
-        if let Some(idx) = stdout_s.find("CALDATA_BYTES:") {
-            let s = &stdout_s[idx + "CALDATA_BYTES:".len()..];
-            let num: String = s
-                .chars()
-                .skip_while(|c| c.is_whitespace())
-                .take_while(|c| c.is_ascii_digit())
-                .collect();
-            if let Ok(v) = num.parse::<u64>() {
-                calldata_b = Some(v);
-            }
-        }
-    }
-
-    // Build meta: if artifact provided, use it to extract Noir version; else fill placeholders
-    let meta = if let Some(artifact_path) = &artifact {
-        let program = read_program_from_file(artifact_path)
-            .map_err(|e| BenchError::Message(e.to_string()))?;
-        let artifact_bytes = std::fs::read(artifact_path).ok();
+fn build_meta(artifact: &Option<PathBuf>, foundry_dir: &Path) -> BenchResult<CommonMeta> {
+    // Use the artifact, if provided, to extract the Noir version; else fill placeholders
+    let meta = if let Some(artifact_path) = artifact {
+        let program = crate::engine::load_program(artifact_path)?;
         let meta = CommonMeta {
             name: "evm-verify".into(),
             timestamp: time::OffsetDateTime::now_utc()
@@ -149,7 +256,7 @@ pub fn run(
             noir_version: program.noir_version.clone(),
             artifact_path: artifact_path.clone(),
             cli_args: std::env::args().collect(),
-            artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
+            artifact_sha256: crate::engine::artifact_sha256(artifact_path),
             inputs_sha256: None,
         };
         meta
@@ -160,16 +267,119 @@ pub fn run(
                 .format(&time::format_description::well_known::Rfc3339)
                 .unwrap_or_default(),
             noir_version: "n/a".into(),
-            artifact_path: foundry_dir.clone(),
+            artifact_path: foundry_dir.to_path_buf(),
             cli_args: std::env::args().collect(),
             artifact_sha256: None,
             inputs_sha256: None,
         };
         meta
     };
+    Ok(meta)
+}
+
+/// Measure verifier gas with `toolchain`. `project_dir` is the Foundry or
+/// Hardhat project; revm takes the bytecode and proof instead.
+/// `verify_call`, when given, is passed to Foundry and Hardhat tests in
+/// [`CALLDATA_ENV`] and [`CALLDATA_FILE_ENV`].
+pub fn run(
+    toolchain: EvmToolchain,
+    project_dir: Option<PathBuf>,
+    artifact: Option<PathBuf>,
+    test_pattern: Option<String>,
+    verify_call: Option<Vec<u8>>,
+    calldata_bytes: Option<u64>,
+    gas_per_second: Option<u64>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    // The calldata file is kept until the tests have run
+    let mut envs: Vec<(&str, String)> = Vec::new();
+    let mut calldata_file = None;
+    if let Some(call) = &verify_call {
+        let call_hex = format!("0x{}", hex::encode(call));
+        let file = tempfile::Builder::new()
+            .prefix("noir-bench-calldata-")
+            .suffix(".hex")
+            .tempfile()
+            .and_then(|file| std::fs::write(file.path(), &call_hex).map(|_| file))
+            .map_err(|e| BenchError::Message(format!("failed to write calldata: {e}")))?;
+        envs.push((CALLDATA_ENV, call_hex));
+        envs.push((CALLDATA_FILE_ENV, file.path().display().to_string()));
+        calldata_file = Some(file);
+    }
+    let project = |toolchain: &EvmToolchain| {
+        project_dir.clone().ok_or_else(|| {
+            BenchError::Message(format!(
+                "--project-dir is required for the {} toolchain",
+                toolchain.name()
+            ))
+        })
+    };
+    // Gas, calldata logged by the test (or computed), backend and the path recorded in meta
+    let (gas_used, logged_calldata, backend, meta_path) = match &toolchain {
+        EvmToolchain::Foundry { forge_bin } => {
+            let foundry_dir = project(&toolchain)?;
+            let forge = forge_bin.clone().unwrap_or_else(|| PathBuf::from("forge"));
+
+            // Execute forge test with gas report
+            let stdout_s = run_forge(&forge, &foundry_dir, &test_pattern, &envs)?;
+
+            // Prefer .gas-snapshot, fallback to stdout heuristic
+            let snapshot_path = foundry_dir.join(".gas-snapshot");
+            let gas_used = read_gas_from_snapshot(&snapshot_path, &test_pattern)
+                .or_else(|| read_gas_from_stdout(&stdout_s))
+                .ok_or_else(|| {
+                    BenchError::Message("failed to parse gas used from Foundry outputs".into())
+                })?;
+            let calldata = read_calldata_from_stdout(&stdout_s);
+            (
+                gas_used,
+                calldata,
+                foundry_backend_info(&forge),
+                foundry_dir,
+            )
+        }
+        EvmToolchain::Hardhat => {
+            let project_dir = project(&toolchain)?;
+            let stdout_s = run_hardhat(&project_dir, &test_pattern, &envs)?;
+            let gas_used = read_gas_from_stdout(&stdout_s).ok_or_else(|| {
+                BenchError::Message(
+                    "failed to parse gas used from Hardhat output (log a `gas: <n>` line)".into(),
+                )
+            })?;
+            let calldata = read_calldata_from_stdout(&stdout_s);
+            (
+                gas_used,
+                calldata,
+                hardhat_backend_info(&project_dir),
+                project_dir,
+            )
+        }
+        #[cfg(feature = "revm")]
+        EvmToolchain::Revm(inputs) => {
+            let m = crate::evm_revm::measure(inputs)?;
+            let backend = BackendInfo {
+                name: "revm".into(),
+                version: None,
+            };
+            (
+                m.gas_used as u128,
+                Some(m.calldata_bytes),
+                backend,
+                inputs.bytecode.clone(),
+            )
+        }
+    };
+
+    // Calldata bytes: user-provided, else the call handed to the test, else
+    // logged by the test as "CALDATA_BYTES: <n>"
+    let calldata_b = calldata_bytes
+        .or(verify_call.as_ref().map(|call| call.len() as u64))
+        .or(logged_calldata);
+    drop(calldata_file);
+
+    let meta = build_meta(&artifact, &meta_path)?;
 
     let system: Option<SystemInfo> = Some(collect_system_info());
-    let backend = foundry_backend_info(&forge);
     let est_latency_ms = Some(estimate_latency_ms(
         gas_used,
         gas_per_second.unwrap_or(1_250_000),
@@ -196,3 +406,255 @@ pub fn run(
     );
     Ok(())
 }
+
+/// Least-squares fit of gas against batch size: `(base_gas, marginal_gas)`,
+/// where verifying `n` proofs costs about `base_gas + n * marginal_gas`.
+pub fn fit_batch_gas(points: &[EvmBatchPoint]) -> Option<(f64, f64)> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.proofs as f64).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.gas_used as f64).sum::<f64>() / n;
+    let sxx: f64 = points
+        .iter()
+        .map(|p| (p.proofs as f64 - mean_x).powi(2))
+        .sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let sxy: f64 = points
+        .iter()
+        .map(|p| (p.proofs as f64 - mean_x) * (p.gas_used as f64 - mean_y))
+        .sum();
+    let marginal = sxy / sxx;
+    Some((mean_y - marginal * mean_x, marginal))
+}
+
+/// Build batch points from `(proofs, gas_used)` pairs sorted by size, with
+/// the marginal gas of each step over the previous one.
+pub fn batch_points(mut measured: Vec<(u64, u128)>) -> Vec<EvmBatchPoint> {
+    measured.sort_by_key(|&(proofs, _)| proofs);
+    let mut points: Vec<EvmBatchPoint> = Vec::with_capacity(measured.len());
+    for (proofs, gas_used) in measured {
+        let marginal_gas = points
+            .last()
+            .map(|prev| (gas_used as f64 - prev.gas_used as f64) / (proofs - prev.proofs) as f64);
+        points.push(EvmBatchPoint {
+            proofs,
+            gas_used,
+            gas_per_proof: gas_used as f64 / proofs as f64,
+            marginal_gas,
+        });
+    }
+    points
+}
+
+/// The `--match` pattern for batch size `n`. A pattern with `{n}` names one
+/// test per size, so it is anchored: `testAggregated{n}` must not also match
+/// `testAggregated16` for size 1.
+fn batch_test_pattern(pattern: &str, n: u64) -> String {
+    if pattern.contains("{n}") {
+        format!("^(?:{})$", pattern.replace("{n}", &n.to_string()))
+    } else {
+        pattern.to_string()
+    }
+}
+
+/// Measure gas for verifying each of `batch_sizes` proofs in one transaction.
+///
+/// The batch size is passed to the test in `NOIR_BENCH_BATCH_SIZE` (read it
+/// with `vm.envUint`) and substituted for `{n}` in the test pattern, so
+/// either a looping test or one test per aggregated verifier size works.
+/// Gas is read from forge's stdout, as `.gas-snapshot` is not rewritten by
+/// `forge test`.
+pub fn run_batch(
+    toolchain: EvmToolchainKind,
+    project_dir: Option<PathBuf>,
+    artifact: Option<PathBuf>,
+    test_pattern: Option<String>,
+    batch_sizes: Vec<u64>,
+    forge_bin: Option<PathBuf>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    if toolchain != EvmToolchainKind::Foundry {
+        return Err(BenchError::Message(
+            "--batch is only supported with the foundry toolchain".into(),
+        ));
+    }
+    let Some(foundry_dir) = project_dir else {
+        return Err(BenchError::Message("--batch needs --project-dir".into()));
+    };
+    let mut sizes = batch_sizes;
+    sizes.sort_unstable();
+    sizes.dedup();
+    if sizes.len() < 2 || sizes[0] == 0 {
+        return Err(BenchError::Message(
+            "--batch needs at least two distinct batch sizes of 1 or more".into(),
+        ));
+    }
+    let forge = forge_bin.unwrap_or_else(|| PathBuf::from("forge"));
+
+    let mut measured = Vec::with_capacity(sizes.len());
+    for &n in &sizes {
+        let pattern = test_pattern.as_deref().map(|p| batch_test_pattern(p, n));
+        let stdout_s = run_forge(
+            &forge,
+            &foundry_dir,
+            &pattern,
+            &[(BATCH_SIZE_ENV, n.to_string())],
+        )?;
+        let gas_used = read_gas_from_stdout(&stdout_s).ok_or_else(|| {
+            BenchError::Message(format!(
+                "failed to parse gas used from forge output for batch size {n}"
+            ))
+        })?;
+        measured.push((n, gas_used));
+    }
+    let points = batch_points(measured);
+    let Some((base_gas, marginal_gas_per_proof)) = fit_batch_gas(&points) else {
+        return Err(BenchError::Message(
+            "could not fit gas against batch size".into(),
+        ));
+    };
+
+    let report = EvmBatchReport {
+        meta: build_meta(&artifact, &foundry_dir)?,
+        points,
+        base_gas,
+        marginal_gas_per_proof,
+        backend: foundry_backend_info(&forge),
+        system: Some(collect_system_info()),
+    };
+
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        std::fs::write(&json, serde_json::to_vec_pretty(&report).unwrap()).ok();
+    }
+    println!("evm-verify batch:");
+    println!("  proofs  gas_used  gas/proof  marginal");
+    for p in &report.points {
+        let marginal = p
+            .marginal_gas
+            .map(|m| format!("{m:.0}"))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "  {:>6}  {:>8}  {:>9.0}  {:>8}",
+            p.proofs, p.gas_used, p.gas_per_proof, marginal
+        );
+    }
+    println!(
+        "  fit: {:.0} base + {:.0} gas per proof",
+        report.base_gas, report.marginal_gas_per_proof
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_points_and_fit() {
+        let points = batch_points(vec![(4, 1_000_000), (1, 400_000), (2, 600_000)]);
+        let sizes: Vec<u64> = points.iter().map(|p| p.proofs).collect();
+        assert_eq!(sizes, vec![1, 2, 4]);
+        assert_eq!(points[0].marginal_gas, None);
+        assert_eq!(points[1].marginal_gas, Some(200_000.0));
+        assert_eq!(points[2].marginal_gas, Some(200_000.0));
+        assert_eq!(points[2].gas_per_proof, 250_000.0);
+
+        let (base, marginal) = fit_batch_gas(&points).unwrap();
+        assert!((base - 200_000.0).abs() < 1e-6);
+        assert!((marginal - 200_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_toolchain_from_args() {
+        let tc = |kind: EvmToolchainKind, bytecode: Option<PathBuf>| {
+            EvmToolchain::from_args(kind, None, bytecode, None, None, None)
+        };
+        assert_eq!(
+            tc(EvmToolchainKind::Foundry, None).unwrap().name(),
+            "foundry"
+        );
+        assert_eq!(
+            tc(EvmToolchainKind::Hardhat, None).unwrap().name(),
+            "hardhat"
+        );
+        assert!(tc(EvmToolchainKind::Revm, None).is_err());
+        #[cfg(feature = "revm")]
+        assert_eq!(
+            tc(EvmToolchainKind::Revm, Some(PathBuf::from("v.hex")))
+                .unwrap()
+                .name(),
+            "revm"
+        );
+        assert_eq!(
+            serde_yaml::from_str::<EvmToolchainKind>("hardhat").unwrap(),
+            EvmToolchainKind::Hardhat
+        );
+        assert!(serde_yaml::from_str::<EvmToolchainKind>("anvil").is_err());
+    }
+
+    #[test]
+    fn test_read_calldata_from_stdout() {
+        assert_eq!(
+            read_calldata_from_stdout("gas: 1\nCALDATA_BYTES: 2340\n"),
+            Some(2340)
+        );
+        assert_eq!(read_calldata_from_stdout("gas: 1"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_forge_test_gets_verify_call() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let forge = dir.path().join("forge");
+        std::fs::write(
+            &forge,
+            "#!/bin/sh\n[ \"$NOIR_BENCH_CALLDATA\" = \"$(cat \"$NOIR_BENCH_CALLDATA_FILE\")\" ] || exit 1\necho \"gas: 250000\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&forge, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let call = crate::proof_cmd::encode_verify_call(&[1; 64], &[2; 32]).unwrap();
+        let json = dir.path().join("evm.json");
+
+        run(
+            EvmToolchain::Foundry {
+                forge_bin: Some(forge),
+            },
+            Some(dir.path().to_path_buf()),
+            None,
+            None,
+            Some(call.clone()),
+            None,
+            None,
+            Some(json.clone()),
+        )
+        .unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+        assert_eq!(report["gas_used"], 250_000);
+        assert_eq!(report["calldata_bytes"], call.len() as u64);
+    }
+
+    #[test]
+    fn test_batch_test_pattern_is_anchored_per_size() {
+        assert_eq!(
+            batch_test_pattern("testAggregated{n}", 1),
+            "^(?:testAggregated1)$"
+        );
+        assert_eq!(batch_test_pattern("testBatch", 4), "testBatch");
+    }
+
+    #[test]
+    fn test_fit_needs_distinct_sizes() {
+        assert!(fit_batch_gas(&batch_points(vec![(1, 400_000)])).is_none());
+        assert!(fit_batch_gas(&[]).is_none());
+    }
+}
@@ -1,11 +1,18 @@
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use acvm::acir::brillig::ForeignCallResult;
+use acvm::pwg::ForeignCallWaitInfo;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
-use noir_artifact_cli::fs::{artifact::read_program_from_file, inputs::read_inputs_from_file};
+use nargo::foreign_calls::{ForeignCallError, ForeignCallExecutor};
+use noir_artifact_cli::fs::inputs::read_inputs_from_file;
+use noirc_abi::Abi;
+use noirc_abi::input_parser::{Format, InputValue};
 use noirc_artifacts::debug::DebugArtifact;
 use tracing::info;
 
+use crate::core::{ExecSplit, RunStatus};
+use crate::engine::oracle::{MockForeignCalls, OracleMocks, ResolverTiming};
 use crate::{
     BenchError, BenchResult, CommonMeta, ExecReport, IterationStats, SystemInfo,
     collect_system_info, compute_iteration_stats,
@@ -26,6 +33,36 @@ fn capture_peak_mem() -> Option<u64> {
     None
 }
 
+/// Foreign call executor wrapper counting and timing the calls it forwards.
+struct TimedForeignCalls<E> {
+    inner: E,
+    calls: u64,
+    elapsed: Duration,
+}
+
+impl<E> TimedForeignCalls<E> {
+    fn new(inner: E) -> Self {
+        TimedForeignCalls {
+            inner,
+            calls: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl<F, E: ForeignCallExecutor<F>> ForeignCallExecutor<F> for TimedForeignCalls<E> {
+    fn execute(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<F>,
+    ) -> Result<ForeignCallResult<F>, ForeignCallError> {
+        self.calls += 1;
+        let start = Instant::now();
+        let result = self.inner.execute(foreign_call);
+        self.elapsed += start.elapsed();
+        result
+    }
+}
+
 fn now_string() -> String {
     time::OffsetDateTime::now_utc()
         .format(&time::format_description::well_known::Rfc3339)
@@ -40,6 +77,79 @@ fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> BenchResult<()> {
     std::fs::write(path, json).map_err(|e| BenchError::Message(e.to_string()))
 }
 
+/// Key of the return value in input files, as in nargo's Prover.toml.
+const RETURN_KEY: &str = "return";
+
+/// Where the expected return value of `exec` comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedReturn {
+    /// A value in Prover.toml syntax, e.g. `42`, `[1, 2]` or `{ x = 1 }`
+    Value(String),
+    /// A TOML or JSON file with a `return` entry (a Prover.toml will do)
+    File(PathBuf),
+}
+
+/// ABI with only the return type of `abi`, for parsing an expected return
+/// value without the circuit's inputs.
+fn return_abi(abi: &Abi) -> Abi {
+    Abi {
+        parameters: Vec::new(),
+        return_type: abi.return_type.clone(),
+        error_types: Default::default(),
+    }
+}
+
+/// Parse the expected return value against the circuit's return type.
+fn parse_expected(expected: &ExpectedReturn, abi: &Abi) -> BenchResult<InputValue> {
+    if abi.return_type.is_none() {
+        return Err(BenchError::Message(
+            "--expect-return/--expect-file given, but the circuit returns nothing".to_string(),
+        ));
+    }
+    let (format, text, source) = match expected {
+        ExpectedReturn::Value(value) => (
+            Format::Toml,
+            format!("{RETURN_KEY} = {value}"),
+            "--expect-return".to_string(),
+        ),
+        ExpectedReturn::File(path) => {
+            let text = std::fs::read_to_string(path).map_err(|e| {
+                BenchError::Message(format!("failed to read {}: {e}", path.display()))
+            })?;
+            let format = match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => Format::Json,
+                _ => Format::Toml,
+            };
+            (format, text, path.display().to_string())
+        }
+    };
+    let mut parsed = format
+        .parse(&text, &return_abi(abi))
+        .map_err(|e| BenchError::Message(format!("invalid return value in {source}: {e}")))?;
+    parsed
+        .remove(RETURN_KEY)
+        .ok_or_else(|| BenchError::Message(format!("no `{RETURN_KEY}` value in {source}")))
+}
+
+/// A return value in the syntax `--expect-return` takes.
+pub fn format_input_value(value: &InputValue) -> String {
+    match value {
+        InputValue::Field(f) => f.to_string(),
+        InputValue::String(s) => format!("{s:?}"),
+        InputValue::Vec(items) => {
+            let items: Vec<String> = items.iter().map(format_input_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        InputValue::Struct(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, v)| format!("{name} = {}", format_input_value(v)))
+                .collect();
+            format!("{{ {} }}", fields.join(", "))
+        }
+    }
+}
+
 pub fn run(
     artifact: PathBuf,
     prover_toml: PathBuf,
@@ -48,44 +158,126 @@ pub fn run(
     flamegraph: bool,
     iterations: Option<usize>,
     warmup: Option<usize>,
+    oracle_mocks: Option<PathBuf>,
+    oracle_latency_ms: Option<f64>,
+    oracle_resolver: Option<String>,
+    expect_return: Option<ExpectedReturn>,
+    expect_failure: bool,
 ) -> BenchResult<()> {
+    if expect_failure && (flamegraph || expect_return.is_some()) {
+        return Err(BenchError::Message(
+            "--expect-failure can't be combined with --flamegraph or an expected return value"
+                .to_string(),
+        ));
+    }
+    let mocks = match &oracle_mocks {
+        Some(path) => OracleMocks::load(path)?,
+        None => OracleMocks::default(),
+    };
+
     info!("loading artifact");
-    let program =
-        read_program_from_file(&artifact).map_err(|e| BenchError::Message(e.to_string()))?;
+    let program = crate::engine::load_program(&artifact)?;
 
     // Inputs
     let (inputs_map, _) = read_inputs_from_file(&prover_toml.with_extension("toml"), &program.abi)
         .map_err(|e| BenchError::Message(e.to_string()))?;
+    let expected = expect_return
+        .as_ref()
+        .map(|e| parse_expected(e, &program.abi))
+        .transpose()?;
 
     // Warmup and iterations
     let iter_n = iterations.unwrap_or(1);
     let warmup_n = warmup.unwrap_or(0);
     let mut last_profiling = Vec::new();
     let mut times: Vec<u128> = Vec::new();
+    let mut last_elapsed = Duration::ZERO;
+    let mut last_foreign_calls = (0, Duration::ZERO);
+    let mut last_resolver = (0, 0.0);
+    let mut last_return = None;
+    let mut failure: Option<String> = None;
     for i in 0..(warmup_n + iter_n) {
         let initial_witness = program
             .abi
             .encode(&inputs_map, None)
             .map_err(|e| BenchError::Message(e.to_string()))?;
+        let mut foreign_calls = TimedForeignCalls::new(MockForeignCalls::new(
+            ResolverTiming::new(
+                nargo::foreign_calls::DefaultForeignCallBuilder {
+                    resolver_url: oracle_resolver.clone(),
+                    ..Default::default()
+                }
+                .with_output(std::io::stdout())
+                .build(),
+            ),
+            &mocks,
+            oracle_latency_ms,
+        )?);
         let start = Instant::now();
-        let (_witness_stack, profiling_samples) = nargo::ops::execute_program_with_profiling(
+        let executed = nargo::ops::execute_program_with_profiling(
             &program.bytecode,
             initial_witness,
             &Bn254BlackBoxSolver,
-            &mut nargo::foreign_calls::DefaultForeignCallBuilder::default()
-                .with_output(std::io::stdout())
-                .build(),
-        )
-        .map_err(|e| BenchError::Message(format!("execution failed: {e}")))?;
-        let dur = start.elapsed().as_millis();
+            &mut foreign_calls,
+        );
+        last_elapsed = start.elapsed();
+        if expect_failure {
+            // Time to failure is the measurement; a passing run is the error,
+            // and so is any failure other than a failed assertion or constraint
+            let e = match executed {
+                Ok(_) => {
+                    return Err(BenchError::Message(
+                        "expected execution to fail (--expect-failure), but it succeeded"
+                            .to_string(),
+                    ));
+                }
+                Err(e @ nargo::errors::NargoError::ExecutionError(_)) => e,
+                Err(e) => return Err(BenchError::Message(format!("execution failed: {e}"))),
+            };
+            if i >= warmup_n {
+                times.push(last_elapsed.as_millis());
+            }
+            failure = e.to_string().lines().next().map(str::to_string);
+            continue;
+        }
+        let (witness_stack, profiling_samples) =
+            executed.map_err(|e| BenchError::Message(format!("execution failed: {e}")))?;
         if i >= warmup_n {
-            times.push(dur);
+            times.push(last_elapsed.as_millis());
         }
         last_profiling = profiling_samples;
+        if let Some(main) = witness_stack.peek() {
+            let (_, return_value) = program
+                .abi
+                .decode(&main.witness)
+                .map_err(|e| BenchError::Message(format!("failed to decode return value: {e}")))?;
+            last_return = return_value;
+        }
+        last_foreign_calls = (foreign_calls.calls, foreign_calls.elapsed);
+        let resolver = foreign_calls.inner.inner();
+        last_resolver = (resolver.calls(), resolver.elapsed_ms());
     }
     let duration_ms = *times.last().unwrap_or(&0);
     let samples_count = last_profiling.len();
 
+    // Each profiling sample is one executed Brillig opcode
+    let acir_opcodes: usize = program
+        .bytecode
+        .functions
+        .iter()
+        .map(|f| f.opcodes.len())
+        .sum();
+    let mut exec_split = ExecSplit::estimate(
+        last_elapsed.as_secs_f64() * 1000.0,
+        last_foreign_calls.1.as_secs_f64() * 1000.0,
+        acir_opcodes as u64,
+        samples_count as u64,
+        last_foreign_calls.0,
+    );
+    if oracle_resolver.is_some() {
+        exec_split = exec_split.with_resolver(last_resolver.0, last_resolver.1);
+    }
+
     // Optional flamegraph
     let mut flamegraph_svg = None;
     if flamegraph {
@@ -97,7 +289,7 @@ pub fn run(
         std::fs::create_dir_all(out_dir).map_err(|e| BenchError::Message(e.to_string()))?;
 
         // Build debug artifact view
-        let debug_artifact: DebugArtifact = program.clone().into();
+        let debug_artifact: DebugArtifact = program.as_ref().clone().into();
 
         // Convert ACVM profiling samples into profiler-like samples lines
         let samples: Vec<exec_samples::BrilligExecSample> = {
@@ -147,19 +339,20 @@ pub fn run(
 
     // Build report
     // fingerprints
-    let artifact_bytes = std::fs::read(&artifact).ok();
-    let inputs_bytes = std::fs::read(&prover_toml).ok();
     let meta = CommonMeta {
         name: "exec".to_string(),
         timestamp: now_string(),
         noir_version: program.noir_version.clone(),
         artifact_path: artifact.clone(),
         cli_args: std::env::args().collect(),
-        artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-        inputs_sha256: inputs_bytes.as_ref().map(|b| crate::sha256_hex(b)),
+        artifact_sha256: crate::engine::artifact_sha256(&artifact),
+        inputs_sha256: crate::file_sha256(&prover_toml),
     };
     let system: SystemInfo = collect_system_info();
     let iter_stats: Option<IterationStats> = Some(compute_iteration_stats(times, iter_n, warmup_n));
+    let return_ok = expected
+        .as_ref()
+        .map(|expected| last_return.as_ref() == Some(expected));
     let report = ExecReport {
         meta,
         execution_time_ms: duration_ms,
@@ -168,6 +361,11 @@ pub fn run(
         flamegraph_svg,
         system: Some(system),
         iterations: iter_stats,
+        exec_split: Some(exec_split).filter(|_| !expect_failure),
+        return_value: last_return.as_ref().map(format_input_value),
+        return_ok,
+        status: expect_failure.then_some(RunStatus::ExpectedFailure),
+        failure_message: failure,
     };
 
     // Output JSON
@@ -175,6 +373,14 @@ pub fn run(
         write_json(&json_path, &report)?;
     }
 
+    if let Some(message) = &report.failure_message {
+        println!(
+            "exec: failed as expected after {}ms: {message}",
+            report.execution_time_ms
+        );
+        return Ok(());
+    }
+
     // Human summary
     println!(
         "exec: time={}ms samples={}{}",
@@ -186,10 +392,46 @@ pub fn run(
             ""
         }
     );
+    if let Some(split) = &report.exec_split {
+        println!("{}", format_split(split));
+        if split.unconstrained_fraction() > 0.5 {
+            eprintln!(
+                "note: unconstrained code takes {:.0}% of execution; Brillig hints dominate witness generation",
+                split.unconstrained_fraction() * 100.0
+            );
+        }
+    }
+
+    if let Some(expected) = &expected {
+        let actual = report.return_value.as_deref().unwrap_or("nothing");
+        if report.return_ok != Some(true) {
+            return Err(BenchError::Message(format!(
+                "return value mismatch: expected {}, got {actual}",
+                format_input_value(expected)
+            )));
+        }
+        println!("return: {actual} (as expected)");
+    }
 
     Ok(())
 }
 
+fn format_split(split: &ExecSplit) -> String {
+    let mut line = format!(
+        "exec split: acir~{:.1}ms ({} opcodes) brillig~{:.1}ms ({} opcodes) foreign={:.1}ms ({} calls)",
+        split.acir_ms,
+        split.acir_opcodes,
+        split.brillig_ms,
+        split.brillig_opcodes,
+        split.foreign_call_ms,
+        split.foreign_calls
+    );
+    if let (Some(calls), Some(ms)) = (split.resolver_calls, split.resolver_ms) {
+        line.push_str(&format!(" resolver={ms:.1}ms ({calls} calls)"));
+    }
+    line
+}
+
 // Minimal internal helpers to avoid depending on profiler crate
 mod exec_samples {
     use acvm::FieldElement;
@@ -273,13 +515,13 @@ mod flame {
     }
 }
 
-mod profiler_like {
+pub mod profiler_like {
     use std::collections::BTreeMap;
 
     use acvm::acir::circuit::{AcirOpcodeLocation, OpcodeLocation};
     use fm::codespan_files::Files;
-    use noirc_errors::Location;
     use noirc_artifacts::debug::DebugInfo;
+    use noirc_errors::Location;
     use noirc_errors::reporter::line_and_column_from_span;
 
     use super::exec_samples::BrilligExecSample;
@@ -346,7 +588,9 @@ mod profiler_like {
         out
     }
 
-    fn find_callsite_labels<'files>(
+    /// Source labels ("file:line:col::code") of the call stack at an opcode,
+    /// outermost frame first.
+    pub fn find_callsite_labels<'files>(
         debug_symbols: &DebugInfo,
         opcode_location: &OpcodeLocation,
         brillig_function_id: Option<acvm::acir::circuit::brillig::BrilligFunctionId>,
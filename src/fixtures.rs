@@ -0,0 +1,345 @@
+//! Canonical fixtures for snapshot tests (`test-fixtures` feature).
+//!
+//! The fixed records and reports our own snapshot tests are built from.
+//! Every value, timestamp and id is constant, so crates embedding
+//! noir-bench can snapshot their rendering of our schemas against them:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! noir_bench = { version = "0.1", features = ["test-fixtures"] }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core::env::EnvironmentInfo;
+use crate::core::schema::{BackendInfo, BenchRecord, RunConfig, TimingStat};
+use crate::engine::provenance::{Provenance, SystemInfo, ToolInfo, VersionMismatch};
+use crate::history::{RunIndexRecordV1, build_index_from_records};
+use crate::report::{
+    CircuitRegression, MetricDelta, RegressionReport, RegressionStatus, ReportMetadata,
+    ReportSummary, ThresholdRule,
+};
+
+/// A BenchRecord with every stage measured (the `bench_record_v2.json` fixture).
+pub fn fixed_record() -> BenchRecord {
+    let env = EnvironmentInfo {
+        cpu_model: Some("Test CPU".to_string()),
+        cpu_cores: Some(8),
+        total_ram_bytes: Some(17_179_869_184),
+        os: "test-os".to_string(),
+        arch: None,
+        cpu_features: None,
+        hostname: Some("test-host".to_string()),
+        cpu_governor: None,
+        thermal_pressure: None,
+        git_sha: Some("deadbeef".to_string()),
+        git_dirty: Some(false),
+        nargo_version: Some("0.42.0".to_string()),
+        bb_version: Some("1.0.0".to_string()),
+    };
+
+    let backend = BackendInfo {
+        name: "mock-backend".to_string(),
+        version: Some("1.2.3".to_string()),
+        variant: Some("mock-variant".to_string()),
+    };
+
+    let config = RunConfig {
+        warmup_iterations: 1,
+        measured_iterations: 2,
+        timeout_secs: Some(30),
+        compile_flags: Vec::new(),
+        env: BTreeMap::new(),
+    };
+
+    BenchRecord {
+        schema_version: 2,
+        record_id: "test-record-1".to_string(),
+        timestamp: "2026-01-15T00:00:00Z".to_string(),
+        circuit_name: "test-circuit".to_string(),
+        circuit_path: Some("path/to/circuit.json".to_string()),
+        params: Some(16),
+        env,
+        backend,
+        config,
+        compile_stats: Some(TimingStat {
+            iterations: 2,
+            mean_ms: 1.5,
+            median_ms: Some(1.5),
+            stddev_ms: Some(0.1),
+            min_ms: 1.4,
+            max_ms: 1.6,
+            p95_ms: Some(1.6),
+            samples_ms: Vec::new(),
+            data_quality: None,
+            p95_method: None,
+        }),
+        compile_warm_stats: None,
+        compile_incremental_stats: None,
+        witness_stats: Some(TimingStat {
+            iterations: 2,
+            mean_ms: 2.5,
+            median_ms: Some(2.5),
+            stddev_ms: Some(0.2),
+            min_ms: 2.4,
+            max_ms: 2.6,
+            p95_ms: Some(2.6),
+            samples_ms: Vec::new(),
+            data_quality: None,
+            p95_method: None,
+        }),
+        prove_stats: Some(TimingStat {
+            iterations: 2,
+            mean_ms: 10.5,
+            median_ms: Some(10.0),
+            stddev_ms: Some(0.3),
+            min_ms: 10.0,
+            max_ms: 11.0,
+            p95_ms: Some(11.0),
+            samples_ms: Vec::new(),
+            data_quality: None,
+            p95_method: None,
+        }),
+        backend_setup_ms: None,
+        probe_ms: None,
+        vk_gen_stats: None,
+        vk_cached: None,
+        key_cache: None,
+        cache_mode: None,
+        backend_phases: None,
+        cpu_times: None,
+        verify_stats: Some(TimingStat {
+            iterations: 1,
+            mean_ms: 3.0,
+            median_ms: Some(3.0),
+            stddev_ms: Some(0.0),
+            min_ms: 3.0,
+            max_ms: 3.0,
+            p95_ms: Some(3.0),
+            samples_ms: Vec::new(),
+            data_quality: None,
+            p95_method: None,
+        }),
+        verify_cold_ms: None,
+        gates_stats: None,
+        exec_split: None,
+        oracle_stats: None,
+        custom_metrics: BTreeMap::new(),
+        proof_size_bytes: Some(2048),
+        proving_key_size_bytes: Some(4096),
+        verification_key_size_bytes: Some(1024),
+        artifact_size_bytes: Some(512),
+        total_gates: Some(12_345),
+        acir_opcodes: Some(234),
+        subgroup_size: Some(16_384),
+        peak_rss_mb: Some(12.34),
+        cli_args: vec!["noir-bench".to_string(), "prove".to_string()],
+        fingerprints: None,
+        error: None,
+        status: None,
+        metadata: BTreeMap::from([("commit".to_string(), "deadbeef".to_string())]),
+        anomaly: false,
+        signature: None,
+        signed_payload: None,
+    }
+}
+
+/// A regression report with one regressed, one improved and one unchanged
+/// circuit, provenance on both sides and two version mismatches.
+pub fn fixed_report() -> RegressionReport {
+    // Create report with fixed timestamp for determinism
+    let mut report = RegressionReport {
+        version: 1,
+        metadata: ReportMetadata {
+            baseline_id: "baseline-abc123".to_string(),
+            target_id: "target-def456".to_string(),
+            generated_at: "2026-01-15T12:00:00Z".to_string(),
+            threshold_percent: 10.0,
+            metric_thresholds: BTreeMap::new(),
+            circuit_thresholds: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            informational_metrics: BTreeSet::new(),
+            budgets: BTreeMap::new(),
+            baseline_provenance: Some(Provenance {
+                noir_bench: ToolInfo {
+                    name: "noir-bench".to_string(),
+                    version: Some("0.1.0".to_string()),
+                    git_sha: Some("abc123".to_string()),
+                    git_dirty: Some(false),
+                    path: None,
+                },
+                nargo: Some(ToolInfo {
+                    name: "nargo".to_string(),
+                    version: Some("0.38.0".to_string()),
+                    git_sha: None,
+                    git_dirty: None,
+                    path: None,
+                }),
+                backend: Some(ToolInfo {
+                    name: "barretenberg".to_string(),
+                    version: Some("0.62.0".to_string()),
+                    git_sha: None,
+                    git_dirty: None,
+                    path: None,
+                }),
+                system: SystemInfo {
+                    os: "linux".to_string(),
+                    arch: "x86_64".to_string(),
+                    cpu_brand: Some("Test CPU".to_string()),
+                    cpu_cores: Some(8),
+                    ram_bytes: Some(16_000_000_000),
+                    hostname: Some("test-host".to_string()),
+                    cpu_governor: None,
+                },
+                cli_args: vec!["noir-bench".to_string(), "ci".to_string()],
+                collected_at: "2026-01-15T12:00:00Z".to_string(),
+            }),
+            target_provenance: Some(Provenance {
+                noir_bench: ToolInfo {
+                    name: "noir-bench".to_string(),
+                    version: Some("0.1.0".to_string()),
+                    git_sha: Some("def456".to_string()),
+                    git_dirty: Some(false),
+                    path: None,
+                },
+                nargo: Some(ToolInfo {
+                    name: "nargo".to_string(),
+                    version: Some("0.39.0".to_string()),
+                    git_sha: None,
+                    git_dirty: None,
+                    path: None,
+                }),
+                backend: Some(ToolInfo {
+                    name: "barretenberg".to_string(),
+                    version: Some("0.63.0".to_string()),
+                    git_sha: None,
+                    git_dirty: None,
+                    path: None,
+                }),
+                system: SystemInfo {
+                    os: "linux".to_string(),
+                    arch: "x86_64".to_string(),
+                    cpu_brand: Some("Test CPU".to_string()),
+                    cpu_cores: Some(8),
+                    ram_bytes: Some(16_000_000_000),
+                    hostname: Some("test-host".to_string()),
+                    cpu_governor: None,
+                },
+                cli_args: vec!["noir-bench".to_string(), "ci".to_string()],
+                collected_at: "2026-01-15T12:00:00Z".to_string(),
+            }),
+        },
+        circuits: Vec::new(),
+        summary: ReportSummary {
+            total_circuits: 0,
+            circuits_with_regressions: 0,
+            circuits_with_improvements: 0,
+            total_metrics: 0,
+            regressions: 0,
+            improvements: 0,
+            unchanged: 0,
+            missing_baselines: 0,
+            errors: 0,
+            ci_exit_code: 0,
+        },
+        version_mismatches: vec![
+            VersionMismatch {
+                tool: "nargo".to_string(),
+                baseline_version: Some("0.38.0".to_string()),
+                target_version: Some("0.39.0".to_string()),
+            },
+            VersionMismatch {
+                tool: "barretenberg".to_string(),
+                baseline_version: Some("0.62.0".to_string()),
+                target_version: Some("0.63.0".to_string()),
+            },
+        ],
+        environment_mismatches: Vec::new(),
+        thermal_warnings: Vec::new(),
+        subgroup_crossings: Vec::new(),
+        methodology: None,
+    };
+
+    // Add circuits with various statuses
+    report.add_circuit(CircuitRegression {
+        circuit_name: "circuit-alpha".to_string(),
+        params: Some(100),
+        metrics: vec![
+            MetricDelta {
+                metric: "prove_ms".to_string(),
+                baseline: 100.0,
+                target: 125.0,
+                delta_abs: 25.0,
+                delta_pct: 25.0,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status: RegressionStatus::ExceededThreshold,
+            },
+            MetricDelta {
+                metric: "gates".to_string(),
+                baseline: 10000.0,
+                target: 10000.0,
+                delta_abs: 0.0,
+                delta_pct: 0.0,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status: RegressionStatus::Ok,
+            },
+        ],
+        status: RegressionStatus::ExceededThreshold,
+    });
+
+    report.add_circuit(CircuitRegression {
+        circuit_name: "circuit-beta".to_string(),
+        params: None,
+        metrics: vec![
+            MetricDelta {
+                metric: "prove_ms".to_string(),
+                baseline: 200.0,
+                target: 150.0,
+                delta_abs: -50.0,
+                delta_pct: -25.0,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status: RegressionStatus::Improved,
+            },
+            MetricDelta {
+                metric: "proof_size".to_string(),
+                baseline: 2048.0,
+                target: 2048.0,
+                delta_abs: 0.0,
+                delta_pct: 0.0,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status: RegressionStatus::Ok,
+            },
+        ],
+        status: RegressionStatus::Improved,
+    });
+
+    report.add_circuit(CircuitRegression {
+        circuit_name: "circuit-gamma".to_string(),
+        params: Some(50),
+        metrics: vec![MetricDelta {
+            metric: "prove_ms".to_string(),
+            baseline: 50.0,
+            target: 52.0,
+            delta_abs: 2.0,
+            delta_pct: 4.0,
+            threshold: 10.0,
+            threshold_rule: ThresholdRule::Percent,
+            status: RegressionStatus::Ok,
+        }],
+        status: RegressionStatus::Ok,
+    });
+
+    report.finalize();
+    report
+}
+
+/// The history index row derived from [`fixed_record`].
+pub fn fixed_index_record() -> RunIndexRecordV1 {
+    build_index_from_records(&[fixed_record()])
+        .pop()
+        .expect("one record yields one index row")
+}
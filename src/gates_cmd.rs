@@ -1,17 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
 
+use crate::exec_cmd::profiler_like::find_callsite_labels;
+use crate::storage::GateCache;
 use crate::{
-    BackendInfo, BenchError, BenchResult, CommonMeta, GatesOpcodeBreakdown, GatesReport,
-    SystemInfo, collect_system_info,
+    BackendInfo, BenchError, BenchResult, CommonMeta, GatesCallSite, GatesFunctionTotal,
+    GatesOpcodeBreakdown, GatesReport, SystemInfo, collect_system_info, compute_iteration_stats,
 };
 // New unified backend abstraction
 use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig, GateInfo};
 use acvm::acir::circuit::Opcode as AcirOpcode;
-use noir_artifact_cli::fs::artifact::read_program_from_file;
+use acvm::acir::circuit::OpcodeLocation;
+use noirc_artifacts::debug::DebugArtifact;
 // opcode naming best-effort is deferred; we keep stable labels for now
 use shlex::Shlex;
 
@@ -167,6 +171,152 @@ pub fn gates_with_backend<B: Backend>(backend: &B, artifact: &Path) -> BenchResu
     backend.gate_info(artifact)
 }
 
+#[derive(Default)]
+struct CallNode {
+    gates: u64,
+    self_gates: u64,
+    opcodes: usize,
+    children: BTreeMap<String, CallNode>,
+}
+
+fn into_call_sites(children: BTreeMap<String, CallNode>) -> Vec<GatesCallSite> {
+    let mut sites: Vec<GatesCallSite> = children
+        .into_iter()
+        .map(|(label, node)| GatesCallSite {
+            label,
+            gates: node.gates,
+            self_gates: node.self_gates,
+            opcodes: node.opcodes,
+            children: into_call_sites(node.children),
+        })
+        .collect();
+    sites.sort_by(|a, b| b.gates.cmp(&a.gates).then_with(|| a.label.cmp(&b.label)));
+    sites
+}
+
+/// Name of the function called at a call-site label ("file:line:col::code"),
+/// e.g. "hash" for `std::hash::hash::<T>(x)`.
+fn callee_name(label: &str) -> String {
+    let code = label
+        .split_once("::")
+        .map(|(_, code)| code)
+        .unwrap_or(label);
+    let call = code.split('(').next().unwrap_or(code);
+    let call = call.split("::<").next().unwrap_or(call).trim();
+    call.rsplit([':', '.'])
+        .find(|s| !s.is_empty())
+        .unwrap_or(call)
+        .to_string()
+}
+
+/// Attribute per-opcode gates to their ACIR call stacks.
+///
+/// Each entry is an opcode's call stack (outermost frame first) and its
+/// gates. Returns the call-site tree and per-function totals, both sorted by
+/// gates. An opcode belongs to the function called by the second-innermost
+/// frame, or to `main` when the stack has a single frame.
+pub fn attribute_call_stacks(
+    stacks: &[(Vec<String>, u64)],
+) -> (Vec<GatesCallSite>, Vec<GatesFunctionTotal>) {
+    let mut root: BTreeMap<String, CallNode> = BTreeMap::new();
+    let mut functions: BTreeMap<String, (u64, usize)> = BTreeMap::new();
+    for (frames, gates) in stacks {
+        let frames: Vec<String> = if frames.is_empty() {
+            vec!["<unknown>".to_string()]
+        } else {
+            frames.clone()
+        };
+        let mut level = &mut root;
+        for (depth, frame) in frames.iter().enumerate() {
+            let node = level.entry(frame.clone()).or_default();
+            node.gates += gates;
+            node.opcodes += 1;
+            if depth == frames.len() - 1 {
+                node.self_gates += gates;
+            }
+            level = &mut node.children;
+        }
+
+        let function = match frames.len() {
+            0 | 1 => "main".to_string(),
+            n => callee_name(&frames[n - 2]),
+        };
+        let total = functions.entry(function).or_default();
+        total.0 += gates;
+        total.1 += 1;
+    }
+
+    let mut per_function: Vec<GatesFunctionTotal> = functions
+        .into_iter()
+        .map(|(function, (gates, opcodes))| GatesFunctionTotal {
+            function,
+            gates,
+            opcodes,
+        })
+        .collect();
+    per_function.sort_by(|a, b| {
+        b.gates
+            .cmp(&a.gates)
+            .then_with(|| a.function.cmp(&b.function))
+    });
+    (into_call_sites(root), per_function)
+}
+
+/// Call paths with their self gates, deepest frames last.
+fn flatten_call_sites(sites: &[GatesCallSite], prefix: &str, out: &mut Vec<(String, u64)>) {
+    for site in sites {
+        let path = if prefix.is_empty() {
+            site.label.clone()
+        } else {
+            format!("{prefix} > {}", site.label)
+        };
+        if site.self_gates > 0 {
+            out.push((path.clone(), site.self_gates));
+        }
+        flatten_call_sites(&site.children, &path, out);
+    }
+}
+
+/// Text view of the top `n` functions and call paths by gates.
+fn format_top(
+    per_function: &[GatesFunctionTotal],
+    call_sites: &[GatesCallSite],
+    n: usize,
+) -> String {
+    let total: u64 = per_function.iter().map(|f| f.gates).sum();
+    let pct = |gates: u64| {
+        if total > 0 {
+            gates as f64 * 100.0 / total as f64
+        } else {
+            0.0
+        }
+    };
+    let mut out = format!("top {n} functions by gates:\n");
+    for f in per_function.iter().take(n) {
+        out.push_str(&format!(
+            "  {:>10} {:>5.1}%  {} ({} opcodes)\n",
+            f.gates,
+            pct(f.gates),
+            f.function,
+            f.opcodes
+        ));
+    }
+    let mut paths = Vec::new();
+    flatten_call_sites(call_sites, "", &mut paths);
+    paths.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    out.push_str(&format!("top {n} call sites by gates:\n"));
+    for (path, gates) in paths.iter().take(n) {
+        out.push_str(&format!("  {:>10} {:>5.1}%  {path}\n", gates, pct(*gates)));
+    }
+    out
+}
+
+/// Report gates for an artifact, timing the analysis over `iterations` runs.
+///
+/// With `cache_dir`, results are cached by artifact hash and backend; a cache
+/// hit skips the analysis and records no timing. When the artifact has debug
+/// info, gates are also attributed to call sites and functions; `top` prints
+/// the `top` largest of each.
 pub fn run(
     artifact: PathBuf,
     backend: Option<String>,
@@ -174,7 +324,13 @@ pub fn run(
     backend_args: Vec<String>,
     command_template: Option<String>,
     json_out: Option<PathBuf>,
+    iterations: usize,
+    cache_dir: Option<PathBuf>,
+    top: Option<usize>,
 ) -> BenchResult<()> {
+    if iterations == 0 {
+        return Err(BenchError::Message("iterations must be at least 1".into()));
+    }
     let backend_name = backend.unwrap_or_else(|| "barretenberg".to_string());
     // Default to `bb` from PATH for the barretenberg backend when no path is provided.
     let backend_path = match backend_path {
@@ -195,58 +351,111 @@ pub fn run(
         } else {
             None
         };
+    if let Some(bb) = &unified_backend {
+        bb.warn_on_args();
+    }
+
+    // Two binaries can report the same version, so the path is part of the cache key
+    let backend_path_id = backend_path
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
 
     // Use the new Backend trait for barretenberg, fall back to legacy providers for other backends
-    let (resp, backend_info) = match (&unified_backend, command_template.as_ref()) {
-        // New code path: use unified Backend trait for barretenberg
-        (Some(bb), None) => {
-            let gate_info = gates_with_backend(bb, &artifact)?;
-            // Convert GateInfo to BackendGatesResponse format
-            let report = BackendGatesReport {
-                acir_opcodes: gate_info.acir_opcodes.unwrap_or(0) as usize,
-                total_gates: gate_info.backend_gates as usize,
-                gates_per_opcode: gate_info
-                    .per_opcode
-                    .as_ref()
-                    .map(|m| {
-                        let mut v: Vec<(String, u64)> =
-                            m.iter().map(|(k, v)| (k.clone(), *v)).collect();
-                        v.sort_by_key(|(k, _)| k.clone());
-                        v.into_iter().map(|(_, g)| g as usize).collect()
+    type Analyse<'a> = Box<dyn Fn() -> BenchResult<BackendGatesResponse> + 'a>;
+    let artifact_path = artifact.as_path();
+    let (analyse, backend_info): (Analyse, BackendInfo) =
+        match (&unified_backend, command_template.as_ref()) {
+            // New code path: use unified Backend trait for barretenberg
+            (Some(bb), None) => {
+                let analyse = move || {
+                    let gate_info = gates_with_backend(bb, artifact_path)?;
+                    // Convert GateInfo to BackendGatesResponse format
+                    let report = BackendGatesReport {
+                        acir_opcodes: gate_info.acir_opcodes.unwrap_or(0) as usize,
+                        total_gates: gate_info.backend_gates as usize,
+                        gates_per_opcode: gate_info
+                            .per_opcode
+                            .as_ref()
+                            .map(|m| {
+                                let mut v: Vec<(String, u64)> =
+                                    m.iter().map(|(k, v)| (k.clone(), *v)).collect();
+                                v.sort_by_key(|(k, _)| k.clone());
+                                v.into_iter().map(|(_, g)| g as usize).collect()
+                            })
+                            .unwrap_or_default(),
+                    };
+                    Ok(BackendGatesResponse {
+                        functions: vec![report],
                     })
-                    .unwrap_or_default(),
-            };
-            let resp = BackendGatesResponse {
-                functions: vec![report],
-            };
-            let info = BackendInfo {
-                name: bb.name().to_string(),
-                version: bb.version(),
-            };
-            (resp, info)
+                };
+                let info = BackendInfo {
+                    name: bb.name().to_string(),
+                    version: bb.version(),
+                };
+                (Box::new(analyse), info)
+            }
+            // Legacy code path: use GatesProvider for other backends
+            (_, Some(tpl)) => {
+                let provider = GenericGatesProvider {
+                    command_template: tpl.clone(),
+                    extra_args: backend_args.clone(),
+                };
+                let info = provider.backend_info();
+                (Box::new(move || provider.gates(artifact_path)), info)
+            }
+            (None, None) => {
+                let bp = backend_path
+                    .ok_or_else(|| BenchError::Message("--backend-path is required".into()))?;
+                let provider = BackendGatesProvider {
+                    backend_name: backend_name.clone(),
+                    backend_path: bp,
+                    gates_command: "gates".to_string(),
+                    extra_args: backend_args.clone(),
+                };
+                let info = provider.backend_info();
+                (Box::new(move || provider.gates(artifact_path)), info)
+            }
+        };
+
+    // Reuse a cached result for this artifact and backend, or time the analysis
+    let cache = match cache_dir {
+        Some(dir) => {
+            let backend_id = format!(
+                "{}@{}:{}:{}:{}",
+                backend_info.name,
+                backend_info.version.as_deref().unwrap_or_default(),
+                backend_path_id,
+                command_template.as_deref().unwrap_or_default(),
+                backend_args.join(" ")
+            );
+            Some((GateCache::new(dir), GateCache::key(&artifact, &backend_id)?))
         }
-        // Legacy code path: use GatesProvider for other backends
-        (_, Some(tpl)) => {
-            let provider = GenericGatesProvider {
-                command_template: tpl.clone(),
-                extra_args: backend_args.clone(),
-            };
-            let resp = provider.gates(&artifact)?;
-            let info = provider.backend_info();
-            (resp, info)
+        None => None,
+    };
+    let cached = cache
+        .as_ref()
+        .and_then(|(cache, key)| cache.get::<BackendGatesResponse>(key));
+    let mut times_ms: Vec<u128> = Vec::new();
+    let resp = match cached {
+        Some(resp) => {
+            eprintln!("gates: using cached result (no timing recorded)");
+            resp
         }
-        (None, None) => {
-            let bp = backend_path
-                .ok_or_else(|| BenchError::Message("--backend-path is required".into()))?;
-            let provider = BackendGatesProvider {
-                backend_name: backend_name.clone(),
-                backend_path: bp,
-                gates_command: "gates".to_string(),
-                extra_args: backend_args.clone(),
-            };
-            let resp = provider.gates(&artifact)?;
-            let info = provider.backend_info();
-            (resp, info)
+        None => {
+            let mut resp = None;
+            for _ in 0..iterations {
+                let start = Instant::now();
+                resp = Some(analyse()?);
+                times_ms.push(start.elapsed().as_millis());
+            }
+            let resp = resp.ok_or_else(|| BenchError::Message("no gate analysis ran".into()))?;
+            if let Some((cache, key)) = &cache {
+                if let Err(e) = cache.put(key, &resp) {
+                    eprintln!("Warning: failed to cache gate result: {e}");
+                }
+            }
+            resp
         }
     };
 
@@ -268,31 +477,56 @@ pub fn run(
     }
 
     // Noir version and sha256 from artifact if available
-    let (noir_version, artifact_sha256, opcode_names): (String, Option<String>, Vec<String>) =
-        match read_program_from_file(&artifact) {
-            Ok(p) => {
-                let bytes = serde_json::to_vec(&p).ok();
-                let sha = bytes.as_ref().map(|b| crate::sha256_hex(b));
-                let names: Vec<String> = p
-                    .bytecode
-                    .functions
-                    .get(0)
-                    .map(|f| {
-                        f.opcodes
-                            .iter()
-                            .map(|op: &AcirOpcode<_>| match op {
-                                AcirOpcode::BlackBoxFuncCall(_) => "bb::call".to_string(),
-                                AcirOpcode::MemoryOp { .. } => "acir::memory".to_string(),
-                                AcirOpcode::Call { .. } => "acir::call".to_string(),
-                                _ => "acir::op".to_string(),
-                            })
-                            .collect()
+    let (noir_version, artifact_sha256, opcode_names, call_stacks): (
+        String,
+        Option<String>,
+        Vec<String>,
+        Vec<Vec<String>>,
+    ) = match crate::engine::load_program(&artifact) {
+        Ok(p) => {
+            let sha = crate::engine::artifact_sha256(&artifact);
+            let names: Vec<String> = p
+                .bytecode
+                .functions
+                .get(0)
+                .map(|f| {
+                    f.opcodes
+                        .iter()
+                        .map(|op: &AcirOpcode<_>| match op {
+                            AcirOpcode::BlackBoxFuncCall(_) => "bb::call".to_string(),
+                            AcirOpcode::MemoryOp { .. } => "acir::memory".to_string(),
+                            AcirOpcode::Call { .. } => "acir::call".to_string(),
+                            _ => "acir::op".to_string(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            // Call stacks of the main function's opcodes, from debug info
+            let debug_artifact: DebugArtifact = p.as_ref().clone().into();
+            let stacks = match debug_artifact.debug_symbols.first() {
+                Some(debug) => (0..per_opcode.len())
+                    .map(|i| {
+                        find_callsite_labels(debug, &OpcodeLocation::Acir(i), None, &debug_artifact)
                     })
-                    .unwrap_or_default();
-                (p.noir_version, sha, names)
-            }
-            Err(_) => (String::new(), None, Vec::new()),
-        };
+                    .collect(),
+                None => Vec::new(),
+            };
+            (p.noir_version.clone(), sha, names, stacks)
+        }
+        Err(_) => (String::new(), None, Vec::new(), Vec::new()),
+    };
+
+    let (call_sites, per_function) = if call_stacks.iter().any(|s| !s.is_empty()) {
+        let stacks: Vec<(Vec<String>, u64)> = call_stacks
+            .into_iter()
+            .zip(&per_opcode)
+            .map(|(stack, item)| (stack, item.gates as u64))
+            .collect();
+        let (sites, functions) = attribute_call_stacks(&stacks);
+        (Some(sites), Some(functions))
+    } else {
+        (None, None)
+    };
 
     // Replace placeholder opcode labels with names if lengths match
     if !opcode_names.is_empty() && opcode_names.len() == per_opcode.len() {
@@ -330,6 +564,8 @@ pub fn run(
         inputs_sha256: None,
     };
     let system: SystemInfo = collect_system_info();
+    let gates_time_ms =
+        (!times_ms.is_empty()).then(|| times_ms.iter().sum::<u128>() / times_ms.len() as u128);
     // Percentages per opcode
     let per_opcode_percent = if total_gates > 0 {
         let mut v = Vec::new();
@@ -351,15 +587,82 @@ pub fn run(
         per_opcode_percent,
         backend: backend_info,
         system: Some(system),
+        gates_time_ms,
+        iterations: (times_ms.len() > 1).then(|| compute_iteration_stats(times_ms, iterations, 0)),
+        call_sites,
+        per_function,
     };
 
-    if let Some(json_path) = json_out {
-        write_json(&json_path, &report)?;
+    if let Some(json_path) = &json_out {
+        write_json(json_path, &report)?;
     }
 
     println!(
-        "gates: backend={} total={} opcodes={} subgroup={:?}",
-        backend_name, total_gates, acir_opcodes, subgroup_size
+        "gates: backend={} total={} opcodes={} subgroup={:?} time_ms={:?}",
+        backend_name, total_gates, acir_opcodes, subgroup_size, gates_time_ms
     );
+    if let Some(n) = top {
+        match (&report.per_function, &report.call_sites) {
+            (Some(functions), Some(sites)) => print!("{}", format_top(functions, sites, n)),
+            _ => eprintln!("gates: no call-stack debug info in artifact, skipping --top"),
+        }
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(frames: &[&str], gates: u64) -> (Vec<String>, u64) {
+        (frames.iter().map(|f| f.to_string()).collect(), gates)
+    }
+
+    #[test]
+    fn test_attribute_call_stacks() {
+        let stacks = vec![
+            stack(&["main.nr:3:5::assert(x == 1)"], 4),
+            stack(&["main.nr:4:13::hash(x)", "lib.nr:10:5::x * x"], 30),
+            stack(
+                &[
+                    "main.nr:4:13::hash(x)",
+                    "lib.nr:11:5::std::hash::pedersen::<2>(y)",
+                    "p.nr:1:1::y",
+                ],
+                60,
+            ),
+            stack(&[], 6),
+        ];
+        let (sites, functions) = attribute_call_stacks(&stacks);
+
+        assert_eq!(sites[0].label, "main.nr:4:13::hash(x)");
+        assert_eq!(sites[0].gates, 90);
+        assert_eq!(sites[0].self_gates, 0);
+        assert_eq!(sites[0].opcodes, 2);
+        assert_eq!(sites[0].children.len(), 2);
+        assert_eq!(sites[0].children[0].gates, 60);
+        assert_eq!(sites[1].label, "<unknown>");
+        assert_eq!(sites[2].self_gates, 4);
+
+        let by_name: Vec<(&str, u64)> = functions
+            .iter()
+            .map(|f| (f.function.as_str(), f.gates))
+            .collect();
+        assert_eq!(by_name, vec![("pedersen", 60), ("hash", 30), ("main", 10)]);
+
+        let top = format_top(&functions, &sites, 1);
+        assert!(top.contains("60  60.0%  pedersen (1 opcodes)"));
+        assert!(top.contains("main.nr:4:13::hash(x) > lib.nr:11:5::"));
+        assert!(!top.contains("main.nr:3:5"));
+    }
+
+    #[test]
+    fn test_callee_name() {
+        assert_eq!(callee_name("main.nr:4:13::hash(x)"), "hash");
+        assert_eq!(
+            callee_name("a.nr:1:1::std::hash::pedersen::<2>(y)"),
+            "pedersen"
+        );
+        assert_eq!(callee_name("a.nr:1:1::self.update(y)"), "update");
+    }
+}
@@ -0,0 +1,519 @@
+//! Benchmarks as code: declare circuits next to the Rust code that uses them
+//! and run them with `cargo bench`.
+//!
+//! ```ignore
+//! // benches/circuits.rs, registered with
+//! // [[bench]]
+//! // name = "circuits"
+//! // harness = false
+//! use noir_bench::harness::BenchCircuit;
+//!
+//! fn merkle() -> BenchCircuit {
+//!     BenchCircuit::project("merkle", "circuits/merkle")
+//!         .iterations(5)
+//!         .max_gates(1 << 17)
+//!         .max_prove_ms(2_000.0)
+//! }
+//!
+//! noir_bench::bench_main!(merkle);
+//! ```
+//!
+//! Under `cargo bench` each circuit is compiled, proved and verified through
+//! the engine workflow and its expectations are checked; the process exits
+//! non-zero when one fails. Under `cargo test` (no `--bench` flag) each
+//! circuit runs once as a smoke test and timing expectations are skipped,
+//! since one unmeasured run says nothing about speed. Free arguments filter
+//! circuits by substring, as with libtest, and `--jsonl <file>` appends the
+//! BenchRecords for `compare` and `history`.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::schema::{BenchRecord, TimingStat};
+use crate::engine::{NargoToolchain, ProveInputs, Toolchain, full_benchmark};
+use crate::{BenchError, BenchResult, JsonlWriter};
+
+/// Where a circuit comes from.
+#[derive(Debug, Clone)]
+pub enum CircuitSource {
+    /// A Noir project directory (with Nargo.toml), compiled before the run
+    Project(PathBuf),
+    /// An already compiled artifact (program.json)
+    Artifact(PathBuf),
+}
+
+/// A bound a benchmark's record must stay within.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expectation {
+    MaxGates(u64),
+    MaxProofSizeBytes(u64),
+    /// Mean prove time
+    MaxProveMs(f64),
+}
+
+impl Expectation {
+    /// Timing bounds are only checked in measured (`--bench`) runs.
+    pub fn is_timing(&self) -> bool {
+        matches!(self, Expectation::MaxProveMs(_))
+    }
+
+    /// Check `record`, explaining the failure.
+    pub fn check(&self, record: &BenchRecord) -> Result<(), String> {
+        let (metric, limit, actual) = match self {
+            Expectation::MaxGates(max) => (
+                "total_gates",
+                *max as f64,
+                record.total_gates.map(|g| g as f64),
+            ),
+            Expectation::MaxProofSizeBytes(max) => (
+                "proof_size_bytes",
+                *max as f64,
+                record.proof_size_bytes.map(|b| b as f64),
+            ),
+            Expectation::MaxProveMs(max) => (
+                "prove_ms",
+                *max,
+                record.prove_stats.as_ref().map(|s| s.mean_ms),
+            ),
+        };
+        match actual {
+            None => Err(format!("{metric} was not measured")),
+            Some(actual) if actual > limit => Err(format!("{metric} {actual} exceeds {limit}")),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// One circuit to benchmark, with its expectations.
+#[derive(Debug, Clone)]
+pub struct BenchCircuit {
+    pub name: String,
+    pub source: CircuitSource,
+    /// Prover inputs; defaults to `Prover.toml` in a project directory
+    pub prover_toml: Option<PathBuf>,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub expectations: Vec<Expectation>,
+}
+
+impl BenchCircuit {
+    fn new(name: impl Into<String>, source: CircuitSource) -> Self {
+        BenchCircuit {
+            name: name.into(),
+            source,
+            prover_toml: None,
+            warmup: 1,
+            iterations: 3,
+            expectations: Vec::new(),
+        }
+    }
+
+    /// A Noir project directory, compiled with the harness's toolchain.
+    pub fn project(name: impl Into<String>, dir: impl Into<PathBuf>) -> Self {
+        Self::new(name, CircuitSource::Project(dir.into()))
+    }
+
+    /// An already compiled artifact.
+    pub fn artifact(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self::new(name, CircuitSource::Artifact(path.into()))
+    }
+
+    /// Set the Prover.toml path.
+    pub fn prover_toml(mut self, path: impl Into<PathBuf>) -> Self {
+        self.prover_toml = Some(path.into());
+        self
+    }
+
+    /// Set the number of warmup runs (measured runs only).
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Set the number of measured iterations (measured runs only).
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Fail when the backend reports more gates than `max`.
+    pub fn max_gates(self, max: u64) -> Self {
+        self.expect(Expectation::MaxGates(max))
+    }
+
+    /// Fail when the proof is larger than `max` bytes.
+    pub fn max_proof_size(self, max: u64) -> Self {
+        self.expect(Expectation::MaxProofSizeBytes(max))
+    }
+
+    /// Fail when the mean prove time exceeds `max` milliseconds.
+    pub fn max_prove_ms(self, max: f64) -> Self {
+        self.expect(Expectation::MaxProveMs(max))
+    }
+
+    /// Add an expectation.
+    pub fn expect(mut self, expectation: Expectation) -> Self {
+        self.expectations.push(expectation);
+        self
+    }
+}
+
+/// How circuits are run: measured under `cargo bench`, once otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    Bench,
+    Smoke,
+}
+
+/// Result of one circuit.
+#[derive(Debug)]
+pub struct CircuitOutcome {
+    pub name: String,
+    pub record: Option<BenchRecord>,
+    /// Failed expectations, or the error that stopped the run
+    pub failures: Vec<String>,
+}
+
+impl CircuitOutcome {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Runs [`BenchCircuit`]s through the engine workflow.
+pub struct Harness {
+    circuits: Vec<BenchCircuit>,
+    toolchain: Box<dyn Toolchain>,
+    backend: Box<dyn Backend>,
+    mode: RunMode,
+    filters: Vec<String>,
+    jsonl: Option<PathBuf>,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// libtest flags followed by a value, which isn't a circuit filter.
+const LIBTEST_VALUE_FLAGS: &[&str] = &[
+    "--color",
+    "--format",
+    "--logfile",
+    "--skip",
+    "--test-threads",
+    "-Z",
+    "--shuffle-seed",
+];
+
+impl Harness {
+    /// A harness using nargo and `bb` from PATH, in smoke mode.
+    pub fn new() -> Self {
+        let backend = BarretenbergBackend::new(
+            BarretenbergConfig::new("bb").with_timeout(Duration::from_secs(24 * 60 * 60)),
+        );
+        Harness {
+            circuits: Vec::new(),
+            toolchain: Box::new(NargoToolchain::new()),
+            backend: Box::new(backend),
+            mode: RunMode::Smoke,
+            filters: Vec::new(),
+            jsonl: None,
+        }
+    }
+
+    /// Add a circuit.
+    pub fn circuit(mut self, circuit: BenchCircuit) -> Self {
+        self.circuits.push(circuit);
+        self
+    }
+
+    /// Use another toolchain (e.g. a pinned nargo).
+    pub fn with_toolchain(mut self, toolchain: impl Toolchain + 'static) -> Self {
+        self.toolchain = Box::new(toolchain);
+        self
+    }
+
+    /// Use another backend.
+    pub fn with_backend(mut self, backend: impl Backend + 'static) -> Self {
+        self.backend = Box::new(backend);
+        self
+    }
+
+    /// Append the records to this JSONL file.
+    pub fn jsonl(mut self, path: impl Into<PathBuf>) -> Self {
+        self.jsonl = Some(path.into());
+        self
+    }
+
+    /// Apply the arguments cargo passes to a `harness = false` target:
+    /// `--bench` selects measured runs, `--jsonl <file>` (or `--jsonl=<file>`)
+    /// sets the output, other flags are ignored along with the value of the
+    /// libtest flags that take one (`--color always`, ...), and the remaining
+    /// free arguments filter circuits by name. Everything after `--` is a
+    /// filter.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            match flag {
+                "--" => {
+                    self.filters.extend(args.by_ref());
+                }
+                "--bench" => self.mode = RunMode::Bench,
+                "--jsonl" => {
+                    self.jsonl = inline_value.or_else(|| args.next()).map(PathBuf::from);
+                }
+                flag if LIBTEST_VALUE_FLAGS.contains(&flag) => {
+                    if inline_value.is_none() {
+                        args.next();
+                    }
+                }
+                flag if flag.starts_with('-') => {}
+                _ => self.filters.push(arg),
+            }
+        }
+        self
+    }
+
+    /// The selected run mode.
+    pub fn mode(&self) -> RunMode {
+        self.mode
+    }
+
+    fn selected(&self) -> impl Iterator<Item = &BenchCircuit> {
+        self.circuits.iter().filter(|c| {
+            self.filters.is_empty() || self.filters.iter().any(|f| c.name.contains(f.as_str()))
+        })
+    }
+
+    /// Run every selected circuit and check its expectations.
+    pub fn run(&self) -> BenchResult<Vec<CircuitOutcome>> {
+        let writer = self.jsonl.as_ref().map(JsonlWriter::new);
+        let mut outcomes = Vec::new();
+        for circuit in self.selected() {
+            let outcome = match self.run_circuit(circuit) {
+                Ok(record) => {
+                    if let Some(writer) = &writer {
+                        writer.append(&record)?;
+                    }
+                    let failures = circuit
+                        .expectations
+                        .iter()
+                        .filter(|e| self.mode == RunMode::Bench || !e.is_timing())
+                        .filter_map(|e| e.check(&record).err())
+                        .collect();
+                    CircuitOutcome {
+                        name: circuit.name.clone(),
+                        record: Some(record),
+                        failures,
+                    }
+                }
+                Err(e) => CircuitOutcome {
+                    name: circuit.name.clone(),
+                    record: None,
+                    failures: vec![e.to_string()],
+                },
+            };
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    fn run_circuit(&self, circuit: &BenchCircuit) -> BenchResult<BenchRecord> {
+        let (warmup, iterations) = match self.mode {
+            RunMode::Bench => (circuit.warmup, circuit.iterations),
+            RunMode::Smoke => (0, 1),
+        };
+        let (artifact, compile_ms, project_dir) = match &circuit.source {
+            CircuitSource::Artifact(path) => (path.clone(), None, None),
+            CircuitSource::Project(dir) => {
+                let compiled = self.toolchain.compile(dir)?;
+                (
+                    compiled.artifact_path,
+                    Some(compiled.compile_time_ms as f64),
+                    Some(dir.as_path()),
+                )
+            }
+        };
+        let mut inputs = ProveInputs::new(&artifact, &circuit.name)
+            .with_timeout(Duration::from_secs(24 * 60 * 60));
+        let prover_toml = circuit.prover_toml.clone().or_else(|| {
+            project_dir
+                .map(|d| d.join("Prover.toml"))
+                .filter(|p| p.exists())
+        });
+        if let Some(prover_toml) = prover_toml {
+            inputs = inputs.with_prover_toml(prover_toml);
+        }
+        let result = full_benchmark(
+            self.toolchain.as_ref(),
+            self.backend.as_ref(),
+            &inputs,
+            warmup,
+            iterations,
+        )?;
+        if !result.verify_success {
+            return Err(BenchError::Message(format!(
+                "{}: proof did not verify",
+                circuit.name
+            )));
+        }
+        let mut record = result.record;
+        if let Some(ms) = compile_ms {
+            record.compile_stats = Some(TimingStat::from_samples(&[ms]));
+        }
+        Ok(record)
+    }
+
+    /// Run, print one line per circuit and exit non-zero on any failure.
+    /// This is what [`bench_main!`](crate::bench_main) calls.
+    pub fn run_main(self) {
+        let outcomes = match self.run() {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                eprintln!("{e:#}");
+                std::process::exit(1);
+            }
+        };
+        println!("\nrunning {} noir benchmark(s)", outcomes.len());
+        for outcome in &outcomes {
+            println!("{}", format_outcome(outcome));
+        }
+        let failed = outcomes.iter().filter(|o| !o.passed()).count();
+        println!(
+            "\nnoir benchmark result: {}. {} passed; {failed} failed\n",
+            if failed == 0 { "ok" } else { "FAILED" },
+            outcomes.len() - failed
+        );
+        if let Some(path) = &self.jsonl {
+            println!("records appended to {}", path.display());
+        }
+        if failed > 0 {
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `bench <name> ... ok (prove 12.34ms, 65536 gates)` or the failures.
+fn format_outcome(outcome: &CircuitOutcome) -> String {
+    let mut details = Vec::new();
+    if let Some(record) = &outcome.record {
+        if let Some(stats) = &record.prove_stats {
+            details.push(format!(
+                "prove {}",
+                crate::report::format_value(stats.mean_ms, "prove_ms")
+            ));
+        }
+        if let Some(gates) = record.total_gates {
+            details.push(format!("{gates} gates"));
+        }
+    }
+    let mut line = format!(
+        "bench {} ... {}",
+        outcome.name,
+        if outcome.passed() { "ok" } else { "FAILED" }
+    );
+    if !details.is_empty() {
+        line.push_str(&format!(" ({})", details.join(", ")));
+    }
+    for failure in &outcome.failures {
+        line.push_str(&format!("\n    {failure}"));
+    }
+    line
+}
+
+/// Define `main` for a `harness = false` bench target from functions that
+/// return [`BenchCircuit`]s, criterion style.
+#[macro_export]
+macro_rules! bench_main {
+    ($($circuit:path),+ $(,)?) => {
+        fn main() {
+            $crate::harness::Harness::new()
+                $(.circuit($circuit()))+
+                .with_args(::std::env::args().skip(1))
+                .run_main();
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig};
+    use crate::engine::MockToolchain;
+
+    fn harness(args: &[&str]) -> Harness {
+        Harness::new()
+            .with_toolchain(MockToolchain::new())
+            .with_backend(MockBackend::new(MockConfig::new("mock")))
+            .with_args(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_args_select_mode_output_and_filters() {
+        let h = harness(&["--bench", "--nocapture", "merkle", "--jsonl", "out.jsonl"]);
+        assert_eq!(h.mode(), RunMode::Bench);
+        assert_eq!(h.filters, vec!["merkle"]);
+        assert_eq!(h.jsonl, Some(PathBuf::from("out.jsonl")));
+        assert_eq!(harness(&[]).mode(), RunMode::Smoke);
+
+        // Flag values are not filters
+        let h = harness(&[
+            "--color",
+            "always",
+            "--jsonl=runs.jsonl",
+            "--format=terse",
+            "hash",
+        ]);
+        assert_eq!(h.filters, vec!["hash"]);
+        assert_eq!(h.jsonl, Some(PathBuf::from("runs.jsonl")));
+        let h = harness(&["--skip", "merkle", "--", "--odd-name"]);
+        assert_eq!(h.filters, vec!["--odd-name"]);
+    }
+
+    #[test]
+    fn test_expectations_are_checked() {
+        // The mock backend reports 1000 gates, a 4096 byte proof and 100ms proves
+        let h = harness(&["--bench"])
+            .circuit(BenchCircuit::project("small", "circuits/small").max_gates(500))
+            .circuit(
+                BenchCircuit::artifact("fits", "fits.json")
+                    .iterations(2)
+                    .max_gates(1000)
+                    .max_proof_size(4096)
+                    .max_prove_ms(150.0),
+            );
+        let outcomes = h.run().unwrap();
+        assert_eq!(outcomes[0].failures, vec!["total_gates 1000 exceeds 500"]);
+        assert!(outcomes[1].passed());
+        let record = outcomes[1].record.as_ref().unwrap();
+        assert_eq!(record.prove_stats.as_ref().unwrap().iterations, 2);
+    }
+
+    #[test]
+    fn test_smoke_runs_skip_timing_and_honour_filters() {
+        let circuits = || {
+            [
+                BenchCircuit::artifact("hash", "hash.json").max_prove_ms(1.0),
+                BenchCircuit::artifact("merkle", "merkle.json"),
+            ]
+        };
+        let h = circuits()
+            .into_iter()
+            .fold(harness(&["hash"]), Harness::circuit);
+        let outcomes = h.run().unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes[0].passed());
+
+        let h = circuits()
+            .into_iter()
+            .fold(harness(&["--bench", "hash"]), Harness::circuit);
+        assert_eq!(h.run().unwrap()[0].failures, vec!["prove_ms 100 exceeds 1"]);
+    }
+}
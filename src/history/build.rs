@@ -3,29 +3,66 @@
 //! This module reads BenchRecord from JSONL and derives RunIndexRecordV1.
 
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 
 use crate::BenchError;
+use crate::core::annotations;
+use crate::core::env::normalize_arch;
+use crate::core::rounding::round_metric;
 use crate::core::schema::BenchRecord;
-use crate::storage::JsonlWriter;
+use crate::storage::{ParseLimits, read_untrusted};
 
 use super::schema::{
-    RUN_INDEX_SCHEMA_VERSION, RunIndexMetricsV1, RunIndexRecordV1, make_run_href, make_run_slug,
+    INDEX_METRICS, IndexChunkV1, IndexManifestV1, MetricSeriesV1, RUN_INDEX_SCHEMA_VERSION,
+    RunIndexMetricsV1, RunIndexRecordV1, make_circuit_href, make_circuit_slug, make_diff_href,
+    make_run_href, make_run_slug,
 };
 
-/// Round a floating point value to 3 decimal places for deterministic output.
-///
-/// Uses the formula: (x * 1000.0).round() / 1000.0
-/// This ensures consistent rounding across platforms.
-fn round_to_3dp(value: f64) -> f64 {
-    (value * 1000.0).round() / 1000.0
+/// Points kept per downsampled series.
+pub const DEFAULT_SERIES_POINTS: usize = 200;
+
+/// Budget metrics (as named in `[budgets]`) that map onto index metrics:
+/// (budget metric, index metric, scale). Earlier entries win, so a median
+/// budget is preferred over a mean one for the p50 series.
+const SLO_METRICS: &[(&str, &str, f64)] = &[
+    ("prove_ms_median", "prove_ms_p50", 1.0),
+    ("prove_ms", "prove_ms_p50", 1.0),
+    ("prove_ms_p95", "prove_ms_p95", 1.0),
+    ("verify_ms_median", "verify_ms_p50", 1.0),
+    ("verify_ms", "verify_ms_p50", 1.0),
+    ("total_gates", "gates", 1.0),
+    ("gates", "gates", 1.0),
+    ("subgroup_size", "subgroup_size", 1.0),
+    ("peak_rss_mb", "peak_rss_bytes", 1_000_000.0),
+];
+
+/// A circuit's budgets (see `check_cmd::circuit_budgets`) keyed by index
+/// metric, for the SLO lines of the history charts. Budgets on metrics the
+/// index doesn't carry are dropped.
+pub fn index_slos(budgets: &BTreeMap<String, f64>) -> BTreeMap<String, f64> {
+    let mut slos = BTreeMap::new();
+    for (budget_metric, index_metric, scale) in SLO_METRICS {
+        if let Some(budget) = budgets.get(*budget_metric) {
+            slos.entry(index_metric.to_string())
+                .or_insert(budget * scale);
+        }
+    }
+    slos
 }
 
+/// Index status of runs that were meant to fail and did.
+const EXPECTED_FAILURE: &str = "expected_failure";
+
 /// Derive status from BenchRecord.
 ///
-/// Returns "ok" if prove_stats exists and has iterations > 0, otherwise "error".
+/// Returns "expected_failure" for runs that were meant to fail, "ok" if
+/// prove_stats exists and has iterations > 0, otherwise "error".
 fn derive_status(record: &BenchRecord) -> String {
+    if record.is_expected_failure() {
+        return EXPECTED_FAILURE.to_string();
+    }
     // If we have prove_stats with at least one iteration, consider it successful
     if let Some(ref stats) = record.prove_stats {
         if stats.iterations > 0 {
@@ -45,31 +82,43 @@ fn derive_metrics(record: &BenchRecord) -> RunIndexMetricsV1 {
         .prove_stats
         .as_ref()
         .and_then(|s| s.median_ms)
-        .map(round_to_3dp);
+        .map(round_metric);
 
     let prove_ms_p95 = record
         .prove_stats
         .as_ref()
         .and_then(|s| s.p95_ms)
-        .map(round_to_3dp);
+        .map(round_metric);
+
+    let prove_ms_p95_low_confidence = record
+        .prove_stats
+        .as_ref()
+        .is_some_and(|s| s.p95_low_confidence());
 
     let verify_ms_p50 = record
         .verify_stats
         .as_ref()
         .and_then(|s| s.median_ms)
-        .map(round_to_3dp);
+        .map(round_metric);
 
     let gates = record.total_gates;
 
     // Convert peak_rss_mb to bytes (if present)
     let peak_rss_bytes = record.peak_rss_mb.map(|mb| (mb * 1_000_000.0) as u64);
 
+    // Bucket by subgroup size; derive it from gates when the backend didn't report it
+    let subgroup_size = record
+        .subgroup_size
+        .or_else(|| gates.filter(|&g| g > 0).map(u64::next_power_of_two));
+
     RunIndexMetricsV1 {
         prove_ms_p50,
         prove_ms_p95,
+        prove_ms_p95_low_confidence,
         verify_ms_p50,
         gates,
         peak_rss_bytes,
+        subgroup_size,
     }
 }
 
@@ -78,17 +127,26 @@ fn derive_metrics(record: &BenchRecord) -> RunIndexMetricsV1 {
 /// Note: detail_slug and detail_href are NOT set here - they are assigned
 /// after sorting in `assign_detail_slugs`.
 fn derive_record(record: &BenchRecord) -> RunIndexRecordV1 {
+    let annotations = annotations::from_metadata(&record.metadata);
+    let commit_href = annotations
+        .get("commit")
+        .and_then(|commit| annotations::href("commit", commit, &annotations));
     RunIndexRecordV1 {
         schema_version: RUN_INDEX_SCHEMA_VERSION,
         record_id: record.record_id.clone(),
         timestamp: record.timestamp.clone(),
         circuit_name: record.circuit_name.clone(),
         backend: record.backend.name.clone(),
+        arch: record.env.arch.as_deref().map(normalize_arch),
         suite: None, // Not currently in BenchRecord; reserved for future
         status: derive_status(record),
         metrics: derive_metrics(record),
+        annotations,
+        commit_href,
         detail_slug: None, // Assigned after sorting
         detail_href: None, // Assigned after sorting
+        circuit_href: None,
+        diff_href: None,
     }
 }
 
@@ -108,6 +166,61 @@ pub fn assign_detail_slugs(records: &mut [RunIndexRecordV1]) {
     }
 }
 
+/// Assign each distinct circuit a trend page href.
+///
+/// Slugs come from the circuit name (see `make_circuit_slug`); names that
+/// sanitize to the same slug get "_2", "_3", ... in name order.
+pub fn assign_circuit_slugs(records: &mut [RunIndexRecordV1]) {
+    let names: BTreeSet<String> = records.iter().map(|r| r.circuit_name.clone()).collect();
+    let mut used = BTreeSet::new();
+    let mut hrefs = BTreeMap::new();
+    for name in names {
+        let base = make_circuit_slug(&name);
+        let mut slug = base.clone();
+        let mut n = 1;
+        while !used.insert(slug.clone()) {
+            n += 1;
+            slug = format!("{base}_{n}");
+        }
+        hrefs.insert(name, make_circuit_href(&slug));
+    }
+    for record in records.iter_mut() {
+        record.circuit_href = hrefs.get(&record.circuit_name).cloned();
+    }
+}
+
+/// For each sorted record, the index of the previous run of the same circuit
+/// and backend on the same architecture, if any. Expected failures only
+/// follow other expected failures, since their timings are time to failure.
+pub fn previous_runs(records: &[RunIndexRecordV1]) -> Vec<Option<usize>> {
+    let mut last: BTreeMap<(&str, &str, Option<&str>, bool), usize> = BTreeMap::new();
+    records
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let key = (
+                r.circuit_name.as_str(),
+                r.backend.as_str(),
+                r.arch.as_deref(),
+                r.status == EXPECTED_FAILURE,
+            );
+            last.insert(key, i)
+        })
+        .collect()
+}
+
+/// Link each run to its diff against the previous run of the same circuit
+/// and backend. Must be called after `assign_detail_slugs`.
+pub fn assign_diff_hrefs(records: &mut [RunIndexRecordV1]) {
+    let previous = previous_runs(records);
+    for (i, prev) in previous.into_iter().enumerate() {
+        let Some(prev) = prev else { continue };
+        if let (Some(a), Some(b)) = (&records[prev].detail_slug, &records[i].detail_slug) {
+            records[i].diff_href = Some(make_diff_href(a, b));
+        }
+    }
+}
+
 /// Compare two timestamps for sorting.
 ///
 /// Attempts ISO 8601 comparison; falls back to string comparison if parsing fails.
@@ -129,7 +242,8 @@ fn sort_records(records: &mut [RunIndexRecordV1]) {
 
 /// Build a derived index from a JSONL file.
 ///
-/// Reads all BenchRecords from the JSONL file, derives RunIndexRecordV1 for each,
+/// Reads all BenchRecords from the JSONL file (leniently, skipping bad lines
+/// with a warning, see `storage::untrusted`), derives RunIndexRecordV1 for each,
 /// sorts them deterministically, assigns detail slugs, and returns the result.
 ///
 /// # Arguments
@@ -138,9 +252,13 @@ fn sort_records(records: &mut [RunIndexRecordV1]) {
 /// # Returns
 /// A vector of RunIndexRecordV1 sorted by (timestamp, record_id) with detail slugs assigned.
 pub fn build_index(jsonl_path: &Path) -> Result<Vec<RunIndexRecordV1>, BenchError> {
-    let reader = JsonlWriter::new(jsonl_path);
-    let bench_records = reader.read_all()?;
+    let read = read_untrusted(jsonl_path, &ParseLimits::default())?;
+    read.report_warnings(jsonl_path);
+    Ok(build_index_from_records(&read.records))
+}
 
+/// Same as `build_index`, for records already read (e.g. filtered by arch).
+pub fn build_index_from_records(bench_records: &[BenchRecord]) -> Vec<RunIndexRecordV1> {
     let mut index_records: Vec<RunIndexRecordV1> =
         bench_records.iter().map(derive_record).collect();
 
@@ -149,8 +267,177 @@ pub fn build_index(jsonl_path: &Path) -> Result<Vec<RunIndexRecordV1>, BenchErro
 
     // Assign deterministic slugs based on sorted order
     assign_detail_slugs(&mut index_records);
+    assign_circuit_slugs(&mut index_records);
+    assign_diff_hrefs(&mut index_records);
+
+    index_records
+}
+
+/// Month of a record's timestamp ("YYYY-MM"), or "unknown".
+fn chunk_month(timestamp: &str) -> String {
+    let month = timestamp.get(..7).unwrap_or("");
+    let bytes = month.as_bytes();
+    let valid = bytes.len() == 7
+        && bytes[4] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, b)| i == 4 || b.is_ascii_digit());
+    if valid {
+        month.to_string()
+    } else {
+        "unknown".to_string()
+    }
+}
+
+/// Split sorted index records into monthly chunks, oldest month first.
+pub fn chunk_by_month(records: &[RunIndexRecordV1]) -> BTreeMap<String, Vec<RunIndexRecordV1>> {
+    let mut chunks: BTreeMap<String, Vec<RunIndexRecordV1>> = BTreeMap::new();
+    for record in records {
+        chunks
+            .entry(chunk_month(&record.timestamp))
+            .or_default()
+            .push(record.clone());
+    }
+    chunks
+}
+
+/// Downsample a series to at most `max_points` with largest-triangle-three-buckets,
+/// which keeps the first and last points and the visually significant ones
+/// (spikes and steps) in between.
+pub fn downsample(points: &[(String, f64)], max_points: usize) -> Vec<(String, f64)> {
+    let n = points.len();
+    if max_points < 3 || n <= max_points {
+        return points.to_vec();
+    }
+
+    let bucket = (n - 2) as f64 / (max_points - 2) as f64;
+    let mut sampled = Vec::with_capacity(max_points);
+    sampled.push(points[0].clone());
+    let mut a = 0;
+    for i in 0..max_points - 2 {
+        // Average of the next bucket
+        let next_start = ((i + 1) as f64 * bucket) as usize + 1;
+        let next_end = (((i + 2) as f64 * bucket) as usize + 1).min(n);
+        let next = &points[next_start..next_end.max(next_start + 1).min(n)];
+        let avg_x = (next_start + next_end - 1) as f64 / 2.0;
+        let avg_y = next.iter().map(|(_, v)| v).sum::<f64>() / next.len() as f64;
+
+        // Point of this bucket with the largest triangle against a and the average
+        let start = (i as f64 * bucket) as usize + 1;
+        let end = (((i + 1) as f64 * bucket) as usize + 1).min(n - 1);
+        let (ax, ay) = (a as f64, points[a].1);
+        let mut best = start;
+        let mut best_area = -1.0;
+        for (j, (_, y)) in points.iter().enumerate().take(end).skip(start) {
+            let area = ((ax - avg_x) * (y - ay) - (ax - j as f64) * (avg_y - ay)).abs();
+            if area > best_area {
+                best_area = area;
+                best = j;
+            }
+        }
+        sampled.push(points[best].clone());
+        a = best;
+    }
+    sampled.push(points[n - 1].clone());
+    sampled
+}
+
+/// Build a downsampled series per (circuit, backend, metric) from sorted records.
+/// Expected failures are left out: their timings are time to failure.
+///
+/// `slos` maps circuit name to its SLOs by index metric (see `index_slos`).
+pub fn build_series(
+    records: &[RunIndexRecordV1],
+    max_points: usize,
+    slos: &BTreeMap<String, BTreeMap<String, f64>>,
+) -> Vec<MetricSeriesV1> {
+    let mut grouped: BTreeMap<(&str, &str, &str), Vec<(String, f64)>> = BTreeMap::new();
+    for record in records.iter().filter(|r| r.status != EXPECTED_FAILURE) {
+        for metric in INDEX_METRICS {
+            if let Some(value) = record.metrics.value(metric) {
+                grouped
+                    .entry((
+                        record.circuit_name.as_str(),
+                        record.backend.as_str(),
+                        *metric,
+                    ))
+                    .or_default()
+                    .push((record.timestamp.clone(), value));
+            }
+        }
+    }
+    grouped
+        .into_iter()
+        .map(|((circuit_name, backend, metric), points)| MetricSeriesV1 {
+            circuit_name: circuit_name.to_string(),
+            backend: backend.to_string(),
+            metric: metric.to_string(),
+            total_points: points.len(),
+            points: downsample(&points, max_points),
+            slo: slos.get(circuit_name).and_then(|m| m.get(metric)).copied(),
+        })
+        .collect()
+}
+
+/// Write a compact JSON file, creating its parent directory.
+fn write_json<T: serde::Serialize + ?Sized>(
+    value: &T,
+    output_path: &Path,
+) -> Result<(), BenchError> {
+    if let Some(parent) = output_path.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent)
+                .map_err(|e| BenchError::Message(format!("failed to create directory: {e}")))?;
+        }
+    }
+    let json = serde_json::to_string(value).map_err(|e| {
+        BenchError::Message(format!(
+            "failed to serialize {}: {e}",
+            output_path.display()
+        ))
+    })?;
+    fs::write(output_path, json)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", output_path.display())))
+}
+
+/// Write the chunked index to `out_dir`:
+/// - index-YYYY-MM.json - index records of one month
+/// - series.json - downsampled series per circuit/backend/metric
+/// - index-manifest.json - the chunk list (see `IndexManifestV1`)
+///
+/// Like index.json, the output is deterministic for the same records.
+pub fn write_chunked_index(
+    records: &[RunIndexRecordV1],
+    out_dir: &Path,
+    max_points: usize,
+    slos: &BTreeMap<String, BTreeMap<String, f64>>,
+) -> Result<IndexManifestV1, BenchError> {
+    let mut chunks = Vec::new();
+    for (month, chunk) in chunk_by_month(records) {
+        let href = format!("index-{month}.json");
+        write_json(&chunk, &out_dir.join(&href))?;
+        chunks.push(IndexChunkV1 {
+            month,
+            href,
+            records: chunk.len(),
+        });
+    }
+
+    let series_href = "series.json".to_string();
+    write_json(
+        &build_series(records, max_points, slos),
+        &out_dir.join(&series_href),
+    )?;
 
-    Ok(index_records)
+    let manifest = IndexManifestV1 {
+        schema_version: RUN_INDEX_SCHEMA_VERSION,
+        total_records: records.len(),
+        chunks,
+        series_href,
+    };
+    write_json(&manifest, &out_dir.join("index-manifest.json"))?;
+    Ok(manifest)
 }
 
 /// Write index records to a JSON file.
@@ -202,11 +489,11 @@ mod tests {
     }
 
     #[test]
-    fn test_round_to_3dp() {
-        assert_eq!(round_to_3dp(100.1234), 100.123);
-        assert_eq!(round_to_3dp(100.1235), 100.124); // rounds up
-        assert_eq!(round_to_3dp(100.1), 100.1);
-        assert_eq!(round_to_3dp(0.0), 0.0);
+    fn test_round_metric() {
+        assert_eq!(round_metric(100.1234), 100.123);
+        assert_eq!(round_metric(100.1235), 100.124); // rounds up
+        assert_eq!(round_metric(100.1), 100.1);
+        assert_eq!(round_metric(0.0), 0.0);
     }
 
     #[test]
@@ -231,6 +518,39 @@ mod tests {
         assert_eq!(derive_status(&record), "error");
     }
 
+    #[test]
+    fn test_derive_status_expected_failure() {
+        let mut record = make_test_record("test", "2024-01-15T12:00:00Z", "id1");
+        record.prove_stats = Some(TimingStat::from_samples(&[5.0]));
+        record.status = Some(crate::core::RunStatus::ExpectedFailure);
+        assert_eq!(derive_status(&record), "expected_failure");
+    }
+
+    #[test]
+    fn test_expected_failures_kept_out_of_series_and_diffs() {
+        let mut records: Vec<RunIndexRecordV1> = [("t1", false), ("t2", true), ("t3", false)]
+            .iter()
+            .map(|(ts, expected_failure)| {
+                let mut record = make_test_record("a", ts, ts);
+                record.prove_stats = Some(TimingStat::from_samples(&[100.0]));
+                if *expected_failure {
+                    record.status = Some(crate::core::RunStatus::ExpectedFailure);
+                }
+                derive_record(&record)
+            })
+            .collect();
+        assign_detail_slugs(&mut records);
+        assign_diff_hrefs(&mut records);
+        assert_eq!(records[1].diff_href, None);
+        assert_eq!(
+            records[2].diff_href.as_deref(),
+            Some("diffs/run_000001-vs-run_000003.html")
+        );
+
+        let series = build_series(&records, 100, &BTreeMap::new());
+        assert!(series.iter().all(|s| s.total_points == 2));
+    }
+
     #[test]
     fn test_derive_metrics_with_timing() {
         let mut record = make_test_record("test", "2024-01-15T12:00:00Z", "id1");
@@ -242,6 +562,9 @@ mod tests {
             min_ms: 100.0,
             max_ms: 120.0,
             p95_ms: Some(118.5678),
+            samples_ms: Vec::new(),
+            data_quality: None,
+            p95_method: None,
         });
         record.total_gates = Some(50000);
         record.peak_rss_mb = Some(256.5);
@@ -250,11 +573,23 @@ mod tests {
 
         assert_eq!(metrics.prove_ms_p50, Some(110.123)); // rounded
         assert_eq!(metrics.prove_ms_p95, Some(118.568)); // rounded
+        assert!(metrics.prove_ms_p95_low_confidence); // only 5 iterations
         assert_eq!(metrics.verify_ms_p50, None);
         assert_eq!(metrics.gates, Some(50000));
         assert_eq!(metrics.peak_rss_bytes, Some(256_500_000));
     }
 
+    #[test]
+    fn test_derive_metrics_subgroup_bucket() {
+        let mut record = make_test_record("test", "2024-01-15T12:00:00Z", "id1");
+        record.total_gates = Some(50000);
+        assert_eq!(derive_metrics(&record).subgroup_size, Some(65536));
+
+        // Backend-reported subgroup size wins
+        record.subgroup_size = Some(131072);
+        assert_eq!(derive_metrics(&record).subgroup_size, Some(131072));
+    }
+
     #[test]
     fn test_sort_records_by_timestamp_then_id() {
         let mut records = vec![
@@ -384,6 +719,159 @@ mod tests {
         assert_eq!(index_record.metrics.gates, Some(25000));
     }
 
+    #[test]
+    fn test_assign_circuit_slugs_dedupes_collisions() {
+        let mut records: Vec<RunIndexRecordV1> = ["a b", "a_b", "a b", "keccak"]
+            .iter()
+            .map(|name| derive_record(&make_test_record(name, "2024-01-15T12:00:00Z", name)))
+            .collect();
+        assign_circuit_slugs(&mut records);
+
+        let hrefs: Vec<_> = records
+            .iter()
+            .map(|r| r.circuit_href.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            hrefs,
+            vec![
+                "circuits/a_b.html",
+                "circuits/a_b_2.html",
+                "circuits/a_b.html",
+                "circuits/keccak.html"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assign_diff_hrefs_links_previous_run() {
+        let mut records: Vec<RunIndexRecordV1> = [("a", "t1"), ("b", "t2"), ("a", "t3")]
+            .iter()
+            .map(|(name, ts)| derive_record(&make_test_record(name, ts, ts)))
+            .collect();
+        assign_detail_slugs(&mut records);
+        assign_diff_hrefs(&mut records);
+
+        assert_eq!(records[0].diff_href, None);
+        assert_eq!(records[1].diff_href, None);
+        assert_eq!(
+            records[2].diff_href.as_deref(),
+            Some("diffs/run_000001-vs-run_000003.html")
+        );
+    }
+
+    #[test]
+    fn test_chunk_by_month() {
+        let records: Vec<RunIndexRecordV1> = [
+            "2024-01-15T12:00:00Z",
+            "2024-01-31T12:00:00Z",
+            "2024-02-01T00:00:00Z",
+            "garbage",
+        ]
+        .iter()
+        .map(|ts| derive_record(&make_test_record("c", ts, ts)))
+        .collect();
+
+        let chunks = chunk_by_month(&records);
+        let months: Vec<_> = chunks.keys().map(String::as_str).collect();
+        assert_eq!(months, vec!["2024-01", "2024-02", "unknown"]);
+        assert_eq!(chunks["2024-01"].len(), 2);
+    }
+
+    #[test]
+    fn test_downsample_keeps_endpoints_and_spikes() {
+        let mut points: Vec<(String, f64)> =
+            (0..1000).map(|i| (format!("t{i:04}"), 100.0)).collect();
+        points[500].1 = 900.0;
+
+        let sampled = downsample(&points, 50);
+        assert_eq!(sampled.len(), 50);
+        assert_eq!(sampled[0].0, "t0000");
+        assert_eq!(sampled[49].0, "t0999");
+        assert!(sampled.iter().any(|(_, v)| *v == 900.0), "spike kept");
+
+        // Short series are returned as-is
+        assert_eq!(downsample(&points[..10], 50).len(), 10);
+    }
+
+    #[test]
+    fn test_write_chunked_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let records: Vec<RunIndexRecordV1> = (1..=3)
+            .map(|month| {
+                let mut record =
+                    make_test_record("c", &format!("2024-0{month}-01T00:00:00Z"), "id");
+                record.prove_stats = Some(TimingStat::from_samples(&[100.0 * month as f64]));
+                derive_record(&record)
+            })
+            .collect();
+
+        let slos = BTreeMap::from([(
+            "c".to_string(),
+            BTreeMap::from([("prove_ms_p50".to_string(), 5000.0)]),
+        )]);
+        let manifest = write_chunked_index(&records, temp.path(), 2, &slos).unwrap();
+        assert_eq!(manifest.total_records, 3);
+        assert_eq!(manifest.chunks.len(), 3);
+        assert_eq!(manifest.chunks[0].href, "index-2024-01.json");
+        assert!(temp.path().join("index-2024-03.json").exists());
+
+        let manifest_json = fs::read_to_string(temp.path().join("index-manifest.json")).unwrap();
+        let parsed: IndexManifestV1 = serde_json::from_str(&manifest_json).unwrap();
+        assert_eq!(parsed, manifest);
+
+        let series_json = fs::read_to_string(temp.path().join("series.json")).unwrap();
+        let series: Vec<MetricSeriesV1> = serde_json::from_str(&series_json).unwrap();
+        let prove = series.iter().find(|s| s.metric == "prove_ms_p50").unwrap();
+        assert_eq!(prove.total_points, 3);
+        // Below 3 points requested, series are not downsampled
+        assert_eq!(prove.points.len(), 3);
+        assert_eq!(prove.slo, Some(5000.0));
+        let gates = series.iter().find(|s| s.metric == "gates");
+        assert!(gates.is_none_or(|s| s.slo.is_none()));
+    }
+
+    #[test]
+    fn test_index_slos_maps_budget_metrics() {
+        let budgets = BTreeMap::from([
+            ("prove_ms".to_string(), 6000.0),
+            ("prove_ms_median".to_string(), 5000.0),
+            ("gates".to_string(), 1_000_000.0),
+            ("peak_rss_mb".to_string(), 2048.0),
+            ("proof_size_bytes".to_string(), 16_000.0),
+        ]);
+        let slos = index_slos(&budgets);
+        // The median budget wins over the mean one for the p50 series
+        assert_eq!(slos["prove_ms_p50"], 5000.0);
+        assert_eq!(slos["gates"], 1_000_000.0);
+        assert_eq!(slos["peak_rss_bytes"], 2_048_000_000.0);
+        assert_eq!(slos.len(), 3);
+    }
+
+    #[test]
+    fn test_derive_record_annotations() {
+        let mut record = make_test_record("my_circuit", "2024-01-15T12:00:00Z", "unique-id");
+        record
+            .metadata
+            .insert("annotation.commit".to_string(), "abc123".to_string());
+        record.metadata.insert(
+            "annotation.repo".to_string(),
+            "https://github.com/o/r".to_string(),
+        );
+
+        let index_record = derive_record(&record);
+
+        assert_eq!(index_record.annotations["commit"], "abc123");
+        assert_eq!(
+            index_record.commit_href.as_deref(),
+            Some("https://github.com/o/r/commit/abc123")
+        );
+        assert!(
+            derive_record(&make_test_record("c", "t", "id"))
+                .commit_href
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_json_output_deterministic() {
         let records = vec![RunIndexRecordV1::new(
@@ -552,10 +1040,10 @@ mod tests {
     #[test]
     fn test_rounding_consistency() {
         // Test edge cases for rounding
-        assert_eq!(round_to_3dp(0.0005), 0.001); // rounds up from 0.5
-        assert_eq!(round_to_3dp(0.0004), 0.0); // rounds down
-        assert_eq!(round_to_3dp(123.4565), 123.457); // standard case
-        assert_eq!(round_to_3dp(123.4564), 123.456); // standard case
+        assert_eq!(round_metric(0.0005), 0.001); // rounds up from 0.5
+        assert_eq!(round_metric(0.0004), 0.0); // rounds down
+        assert_eq!(round_metric(123.4565), 123.457); // standard case
+        assert_eq!(round_metric(123.4564), 123.456); // standard case
 
         // Verify rounding is applied in derive_metrics
         let mut record = make_test_record("test", "2024-01-15T12:00:00Z", "id1");
@@ -567,6 +1055,9 @@ mod tests {
             min_ms: 100.0,
             max_ms: 100.0,
             p95_ms: Some(100.1231), // should round to 100.123
+            samples_ms: Vec::new(),
+            data_quality: None,
+            p95_method: None,
         });
 
         let metrics = derive_metrics(&record);
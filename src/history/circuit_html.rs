@@ -0,0 +1,424 @@
+//! Per-circuit trend page generator.
+//!
+//! Generates a static HTML page per circuit with the latest, best and worst
+//! value of each index metric and a trend chart over all of its runs.
+//! NO JavaScript - the charts are inline SVG.
+//! All user-controlled strings are HTML-escaped for XSS safety.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::BenchError;
+use crate::report::metrics::{self, Direction};
+use crate::report::{format_timestamp, format_value, headroom_pct};
+
+use super::run_html::html_escape;
+use super::schema::RunIndexRecordV1;
+
+/// Metrics summarized on circuit pages. Which run is best follows the
+/// metric's direction in `report::metrics`, and values are shown in its unit.
+const CIRCUIT_METRICS: &[&str] = &[
+    "prove_ms_p50",
+    "prove_ms_p95",
+    "verify_ms_p50",
+    "gates",
+    "subgroup_size",
+    "peak_rss_bytes",
+];
+
+/// Metrics with a trend chart, when they have at least two points.
+const CHART_METRICS: &[&str] = &["prove_ms_p50", "gates"];
+
+/// Series colors, assigned to backends in name order.
+const SERIES_COLORS: &[&str] = &["#4ecdc4", "#ffd166", "#a78bfa", "#ff6b6b"];
+
+/// A value linked to the run it came from (relative to circuits/).
+fn run_link(record: &RunIndexRecordV1, text: &str) -> String {
+    match &record.detail_href {
+        Some(href) => format!(
+            r#"<a href="../{}">{}</a>"#,
+            html_escape(href),
+            html_escape(text)
+        ),
+        None => html_escape(text),
+    }
+}
+
+fn render_summary_rows(records: &[&RunIndexRecordV1]) -> String {
+    let mut rows = String::new();
+    for key in CIRCUIT_METRICS {
+        let points: Vec<(&RunIndexRecordV1, f64)> = records
+            .iter()
+            .filter_map(|r| Some((*r, r.metrics.value(key)?)))
+            .collect();
+        let Some(&(latest, latest_value)) = points.last() else {
+            continue;
+        };
+        let lowest = points
+            .iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("points is non-empty");
+        let highest = points
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("points is non-empty");
+        let (best, worst) = match metrics::direction(key) {
+            Direction::HigherIsBetter => (highest, lowest),
+            _ => (lowest, highest),
+        };
+        rows.push_str(&format!(
+            "<tr><td>{key}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            run_link(latest, &format_value(latest_value, key)),
+            run_link(best.0, &format_value(best.1, key)),
+            run_link(worst.0, &format_value(worst.1, key)),
+            points.len(),
+        ));
+    }
+    rows
+}
+
+/// Trend chart of one metric over the circuit's runs (oldest to newest),
+/// one series per backend, with a dashed line at the metric's SLO when it
+/// has one. Empty with fewer than two points.
+fn render_trend_chart(records: &[&RunIndexRecordV1], key: &str, slo: Option<f64>) -> String {
+    let mut series: BTreeMap<&str, Vec<(usize, f64, &RunIndexRecordV1)>> = BTreeMap::new();
+    for (i, record) in records.iter().enumerate() {
+        if let Some(value) = record.metrics.value(key) {
+            series
+                .entry(record.backend.as_str())
+                .or_default()
+                .push((i, value, record));
+        }
+    }
+    let count: usize = series.values().map(Vec::len).sum();
+    if count < 2 {
+        return String::new();
+    }
+    // The SLO line stays in view even when every run is far below it
+    let values = series.values().flatten().map(|(_, v, _)| *v).chain(slo);
+    let min = values.clone().fold(f64::INFINITY, f64::min);
+    let max = values.fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+
+    let (width, height, pad_l, pad_r, pad_t, pad_b) = (800.0, 200.0, 70.0, 16.0, 16.0, 28.0);
+    let plot_w = width - pad_l - pad_r;
+    let plot_h = height - pad_t - pad_b;
+    let last = (records.len() - 1).max(1) as f64;
+    let scale_x = |i: usize| pad_l + i as f64 / last * plot_w;
+    let scale_y = |v: f64| pad_t + plot_h - (v - min) / range * plot_h;
+
+    let mut svg = format!(
+        r#"<svg class="chart" viewBox="0 0 {width} {height}" role="img" aria-label="{key} trend">"#
+    );
+    svg.push_str(&format!(
+        r#"<line x1="{pad_l}" y1="{pad_t}" x2="{pad_l}" y2="{:.1}" class="axis"/><line x1="{pad_l}" y1="{:.1}" x2="{:.1}" y2="{:.1}" class="axis"/>"#,
+        pad_t + plot_h,
+        pad_t + plot_h,
+        pad_l + plot_w,
+        pad_t + plot_h,
+    ));
+    for v in [min, max] {
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" class="label" text-anchor="end">{}</text>"#,
+            pad_l - 6.0,
+            scale_y(v) + 4.0,
+            format_value(v, key),
+        ));
+    }
+    svg.push_str(&format!(
+        r#"<text x="{:.1}" y="{:.1}" class="label" text-anchor="middle">run (oldest to newest)</text>"#,
+        pad_l + plot_w / 2.0,
+        height - 8.0,
+    ));
+    if let Some(slo) = slo {
+        let latest = records
+            .iter()
+            .rev()
+            .find_map(|r| r.metrics.value(key))
+            .expect("the chart has points");
+        svg.push_str(&format!(
+            r#"<line x1="{pad_l}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" class="slo"/><text x="{:.1}" y="{:.1}" class="label slo-label" text-anchor="end">SLO {} &middot; {}</text>"#,
+            pad_l + plot_w,
+            pad_l + plot_w,
+            scale_y(slo) - 4.0,
+            format_value(slo, key),
+            headroom(latest, slo),
+            y = scale_y(slo),
+        ));
+    }
+    for (s, (backend, points)) in series.iter().enumerate() {
+        let color = SERIES_COLORS[s % SERIES_COLORS.len()];
+        let polyline: Vec<String> = points
+            .iter()
+            .map(|(i, v, _)| format!("{:.1},{:.1}", scale_x(*i), scale_y(*v)))
+            .collect();
+        svg.push_str(&format!(
+            r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="2"/>"#,
+            polyline.join(" ")
+        ));
+        for (i, v, record) in points {
+            svg.push_str(&format!(
+                r#"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{color}"><title>{} {}: {}</title></circle>"#,
+                scale_x(*i),
+                scale_y(*v),
+                html_escape(&format_timestamp(&record.timestamp)),
+                html_escape(backend),
+                format_value(*v, key),
+            ));
+        }
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{pad_t}" class="label" fill="{color}">{}</text>"#,
+            pad_l + 8.0 + 120.0 * s as f64,
+            html_escape(backend),
+        ));
+    }
+    svg.push_str("</svg>");
+    format!("<h2>{key}</h2>\n{svg}\n")
+}
+
+/// Headroom of the latest value under its SLO, e.g. "38% headroom" or
+/// "12% over".
+fn headroom(latest: f64, slo: f64) -> String {
+    let pct = headroom_pct(latest, slo);
+    if pct >= 0.0 {
+        format!("{pct:.0}% headroom")
+    } else {
+        format!("{:.0}% over", -pct)
+    }
+}
+
+fn render_run_rows(records: &[&RunIndexRecordV1]) -> String {
+    let mut rows = String::new();
+    // Newest first
+    for record in records.iter().rev() {
+        let timestamp = format_timestamp(&record.timestamp);
+        rows.push_str(&format!(
+            "<tr><td class=\"mono\">{}</td><td>{}</td><td class=\"{}\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"mono\">{}</td></tr>\n",
+            run_link(record, &timestamp),
+            html_escape(&record.backend),
+            if record.status == "ok" { "ok" } else { "error" },
+            html_escape(&record.status),
+            record
+                .metrics
+                .prove_ms_p50
+                .map(|v| format_value(v, "prove_ms_p50"))
+                .unwrap_or_default(),
+            record
+                .metrics
+                .gates
+                .map(|v| format_value(v as f64, "gates"))
+                .unwrap_or_default(),
+            html_escape(
+                record
+                    .annotations
+                    .get("commit")
+                    .map(String::as_str)
+                    .unwrap_or("")
+            ),
+        ));
+    }
+    rows
+}
+
+/// Render a circuit trend page as static HTML.
+///
+/// `records` are the circuit's index records, sorted oldest to newest (as
+/// `build_index` returns them). The page has:
+/// - Latest, best and worst value of each metric, linked to the run
+/// - Trend charts (inline SVG, one series per backend), with the SLOs from
+///   `slos` (index metric -> budget, see `build::index_slos`) as reference lines
+/// - All runs, newest first, linked to their detail pages
+pub fn render_circuit_html(
+    circuit_name: &str,
+    records: &[&RunIndexRecordV1],
+    slos: &BTreeMap<String, f64>,
+) -> String {
+    let name = html_escape(circuit_name);
+    let summary_rows = render_summary_rows(records);
+    let charts: String = CHART_METRICS
+        .iter()
+        .map(|key| render_trend_chart(records, key, slos.get(*key).copied()))
+        .collect();
+    let run_rows = render_run_rows(records);
+    let range = match (records.first(), records.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} &ndash; {}",
+            html_escape(&format_timestamp(&first.timestamp)),
+            html_escape(&format_timestamp(&last.timestamp))
+        ),
+        _ => String::new(),
+    };
+
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>{name} - noir-bench History</title>
+<style>
+* {{ box-sizing: border-box; margin: 0; padding: 0; }}
+body {{
+  font-family: system-ui, -apple-system, sans-serif;
+  background: #1a1a2e;
+  color: #e8e8e8;
+  padding: 24px;
+  max-width: 900px;
+  margin: 0 auto;
+}}
+a {{ color: #4ecdc4; text-decoration: none; }}
+a:hover {{ text-decoration: underline; }}
+.back {{ margin-bottom: 16px; font-size: 0.875rem; }}
+h1 {{ font-size: 1.5rem; margin-bottom: 8px; }}
+.meta {{ color: #9a9a9a; font-size: 0.8125rem; margin-bottom: 24px; }}
+h2 {{ font-size: 1.125rem; margin: 24px 0 12px 0; color: #9a9a9a; }}
+table {{ width: 100%; border-collapse: collapse; font-size: 0.875rem; background: #16213e; margin-bottom: 16px; }}
+th, td {{ padding: 8px 12px; text-align: left; border-bottom: 1px solid #2d3a5c; }}
+th {{ background: #1a1a2e; color: #9a9a9a; font-weight: 600; font-size: 0.75rem; text-transform: uppercase; }}
+.num {{ text-align: right; font-family: monospace; }}
+.mono {{ font-family: monospace; }}
+.ok {{ color: #4ecdc4; }}
+.error {{ color: #ff6b6b; }}
+svg.chart {{ width: 100%; height: auto; background: #16213e; border-radius: 4px; margin-bottom: 8px; }}
+svg.chart .label {{ fill: #9a9a9a; font-size: 11px; font-family: monospace; }}
+svg.chart .axis {{ stroke: #2d3a5c; }}
+svg.chart .slo {{ stroke: #ff6b6b; stroke-dasharray: 6 4; }}
+svg.chart .slo-label {{ fill: #ff6b6b; }}
+</style>
+</head>
+<body>
+<div class="back"><a href="../index.html">&larr; Back to History</a></div>
+<h1>{name}</h1>
+<div class="meta">{runs} run(s) &middot; {range}</div>
+
+<h2>Summary</h2>
+<table>
+<tr><th>Metric</th><th class="num">Latest</th><th class="num">Best</th><th class="num">Worst</th><th class="num">Runs</th></tr>
+{summary_rows}</table>
+
+{charts}
+<h2>Runs</h2>
+<table>
+<tr><th>Timestamp</th><th>Backend</th><th>Status</th><th class="num">prove_p50_ms</th><th class="num">gates</th><th>Commit</th></tr>
+{run_rows}</table>
+
+</body>
+</html>"##,
+        runs = records.len(),
+    )
+}
+
+/// Write a circuit trend page to a file.
+pub fn write_circuit_html(
+    circuit_name: &str,
+    records: &[&RunIndexRecordV1],
+    slos: &BTreeMap<String, f64>,
+    output_path: &Path,
+) -> Result<(), BenchError> {
+    if let Some(parent) = output_path.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent)
+                .map_err(|e| BenchError::Message(format!("failed to create directory: {e}")))?;
+        }
+    }
+
+    let html = render_circuit_html(circuit_name, records, slos);
+    fs::write(output_path, html).map_err(|e| {
+        BenchError::Message(format!("failed to write {}: {e}", output_path.display()))
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: &str, backend: &str, prove_ms: f64, slug: &str) -> RunIndexRecordV1 {
+        let mut r = RunIndexRecordV1::new(
+            format!("id-{slug}"),
+            timestamp.to_string(),
+            "keccak".to_string(),
+            backend.to_string(),
+            "ok".to_string(),
+        );
+        r.metrics.prove_ms_p50 = Some(prove_ms);
+        r.metrics.gates = Some(1000);
+        r.detail_href = Some(format!("runs/{slug}.html"));
+        r
+    }
+
+    #[test]
+    fn test_render_circuit_html_summary() {
+        let records = [
+            record("2024-01-01T00:00:00Z", "bb", 120.0, "run_000001"),
+            record("2024-01-02T00:00:00Z", "bb", 90.0, "run_000002"),
+            record("2024-01-03T00:00:00Z", "bb", 100.0, "run_000003"),
+        ];
+        let refs: Vec<&RunIndexRecordV1> = records.iter().collect();
+        let html = render_circuit_html("keccak", &refs, &BTreeMap::new());
+
+        assert!(!html.contains("<script"));
+        assert!(html.contains("3 run(s)"));
+        // Latest, best and worst link to their runs
+        assert!(html.contains(
+            r#"<td>prove_ms_p50</td><td class="num"><a href="../runs/run_000003.html">100ms</a></td><td class="num"><a href="../runs/run_000002.html">90ms</a></td><td class="num"><a href="../runs/run_000001.html">120ms</a></td><td class="num">3</td>"#
+        ));
+        // prove_ms_p50 and gates trend charts
+        assert!(html.contains("<h2>prove_ms_p50</h2>"));
+        assert!(html.contains("<h2>gates</h2>"));
+        assert_eq!(html.matches("<polyline").count(), 2);
+        assert!(html.contains("href=\"../index.html\""));
+    }
+
+    #[test]
+    fn test_render_circuit_html_slo_line() {
+        let records = [
+            record("2024-01-01T00:00:00Z", "bb", 3000.0, "run_000001"),
+            record("2024-01-02T00:00:00Z", "bb", 3100.0, "run_000002"),
+        ];
+        let refs: Vec<&RunIndexRecordV1> = records.iter().collect();
+        let slos = BTreeMap::from([("prove_ms_p50".to_string(), 5000.0)]);
+        let html = render_circuit_html("keccak", &refs, &slos);
+        // Only the prove_ms_p50 chart has an SLO
+        assert_eq!(html.matches(r#"class="slo""#).count(), 1);
+        assert!(html.contains("SLO 5.00s &middot; 38% headroom"));
+
+        assert_eq!(headroom(5500.0, 5000.0), "10% over");
+        assert!(!render_circuit_html("keccak", &refs, &BTreeMap::new()).contains("SLO"));
+    }
+
+    #[test]
+    fn test_render_circuit_html_series_per_backend() {
+        let records = [
+            record("2024-01-01T00:00:00Z", "bb", 120.0, "run_000001"),
+            record("2024-01-02T00:00:00Z", "other", 90.0, "run_000002"),
+            record("2024-01-03T00:00:00Z", "bb", 100.0, "run_000003"),
+        ];
+        let refs: Vec<&RunIndexRecordV1> = records.iter().collect();
+        let html = render_circuit_html("keccak", &refs, &BTreeMap::new());
+        // Two backends, two charts
+        assert_eq!(html.matches("<polyline").count(), 4);
+    }
+
+    #[test]
+    fn test_render_circuit_html_single_run_has_no_chart() {
+        let records = [record("2024-01-01T00:00:00Z", "bb", 120.0, "run_000001")];
+        let refs: Vec<&RunIndexRecordV1> = records.iter().collect();
+        let html = render_circuit_html("keccak", &refs, &BTreeMap::new());
+        assert!(!html.contains("<svg"));
+        assert!(html.contains("run_000001.html"));
+    }
+
+    #[test]
+    fn test_render_circuit_html_escapes_xss() {
+        let mut r = record("2024-01-01T00:00:00Z", "<b>", 120.0, "run_000001");
+        r.annotations
+            .insert("commit".to_string(), "<script>x</script>".to_string());
+        let html = render_circuit_html("<img onerror=alert(1)>", &[&r], &BTreeMap::new());
+        assert!(!html.contains("<img onerror"));
+        assert!(!html.contains("<script"));
+        assert!(html.contains("&lt;b&gt;"));
+    }
+}
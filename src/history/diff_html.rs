@@ -0,0 +1,299 @@
+//! Run-to-run diff page generator.
+//!
+//! Generates a static HTML page comparing two runs side by side: environment
+//! and config differences plus the metric deltas of a RegressionReport
+//! (rendered with `report::render_metric_table`).
+//! NO JavaScript. All user-controlled strings are HTML-escaped for XSS safety.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::BenchError;
+use crate::core::schema::BenchRecord;
+use crate::report::{RegressionReport, format_timestamp, render_metric_table};
+
+use super::run_html::html_escape;
+
+/// A run being diffed: its record and its slug (e.g. "run_000123").
+pub struct DiffRun<'a> {
+    pub record: &'a BenchRecord,
+    pub slug: &'a str,
+}
+
+fn opt<T: ToString>(v: &Option<T>) -> String {
+    v.as_ref().map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Environment and backend properties of a record, in display order.
+fn env_rows(record: &BenchRecord) -> Vec<(&'static str, String)> {
+    let env = &record.env;
+    vec![
+        ("OS", env.os.clone()),
+        ("Hostname", opt(&env.hostname)),
+        ("CPU", opt(&env.cpu_model)),
+        ("Cores", opt(&env.cpu_cores)),
+        ("RAM bytes", opt(&env.total_ram_bytes)),
+        ("Nargo version", opt(&env.nargo_version)),
+        ("bb version", opt(&env.bb_version)),
+        ("Git SHA", opt(&env.git_sha)),
+        ("Git dirty", opt(&env.git_dirty)),
+        ("Backend", record.backend.name.clone()),
+        ("Backend version", opt(&record.backend.version)),
+        ("Backend variant", opt(&record.backend.variant)),
+    ]
+}
+
+/// Run config, parameters and metadata of a record.
+fn config_rows(record: &BenchRecord) -> Vec<(String, String)> {
+    let mut rows = vec![
+        ("Circuit".to_string(), record.circuit_name.clone()),
+        ("Params".to_string(), opt(&record.params)),
+        (
+            "Warmup iterations".to_string(),
+            record.config.warmup_iterations.to_string(),
+        ),
+        (
+            "Measured iterations".to_string(),
+            record.config.measured_iterations.to_string(),
+        ),
+        ("Timeout (s)".to_string(), opt(&record.config.timeout_secs)),
+        ("CLI args".to_string(), record.cli_args.join(" ")),
+    ];
+    rows.extend(
+        record
+            .metadata
+            .iter()
+            .map(|(k, v)| (format!("metadata.{k}"), v.clone())),
+    );
+    rows
+}
+
+/// Rows for both runs side by side, with changed rows marked. Keys present
+/// in only one run show an empty cell for the other. Returns the rows and
+/// the number of changed rows.
+fn render_side_by_side(a: &[(String, String)], b: &[(String, String)]) -> (String, usize) {
+    let lookup = |rows: &[(String, String)], key: &str| {
+        rows.iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_default()
+    };
+    let mut keys: Vec<&str> = a.iter().map(|(k, _)| k.as_str()).collect();
+    let seen: BTreeSet<&str> = keys.iter().copied().collect();
+    keys.extend(
+        b.iter()
+            .map(|(k, _)| k.as_str())
+            .filter(|k| !seen.contains(k)),
+    );
+
+    let mut html = String::new();
+    let mut changed = 0;
+    for key in keys {
+        let (va, vb) = (lookup(a, key), lookup(b, key));
+        let class = if va != vb {
+            changed += 1;
+            " class=\"changed\""
+        } else {
+            ""
+        };
+        html.push_str(&format!(
+            "<tr{class}><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(key),
+            html_escape(&va),
+            html_escape(&vb)
+        ));
+    }
+    (html, changed)
+}
+
+fn owned(rows: Vec<(&'static str, String)>) -> Vec<(String, String)> {
+    rows.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+}
+
+/// Render a static HTML diff of two runs.
+///
+/// `report` holds the metric deltas of `b` against `a` (see
+/// `history_cmd::diff_report`). Links are relative to the page's directory;
+/// `runs_prefix` is the path from there to the run detail pages (e.g.
+/// "../runs/").
+pub fn render_run_diff_html(
+    a: &DiffRun,
+    b: &DiffRun,
+    report: &RegressionReport,
+    runs_prefix: &str,
+) -> String {
+    let (env_html, env_changed) =
+        render_side_by_side(&owned(env_rows(a.record)), &owned(env_rows(b.record)));
+    let (config_html, config_changed) =
+        render_side_by_side(&config_rows(a.record), &config_rows(b.record));
+    let metric_table = render_metric_table(report);
+    let link = |run: &DiffRun| {
+        format!(
+            r#"<a href="{}{}.html">{}</a>"#,
+            html_escape(runs_prefix),
+            html_escape(run.slug),
+            html_escape(run.slug)
+        )
+    };
+    let heading = |run: &DiffRun| {
+        format!(
+            "{}<br><span class=\"sub\">{}</span>",
+            link(run),
+            html_escape(&format_timestamp(&run.record.timestamp))
+        )
+    };
+    let changed_note = |n: usize| match n {
+        0 => "no differences".to_string(),
+        n => format!("{n} difference(s)"),
+    };
+
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>{a_slug} vs {b_slug} - noir-bench</title>
+<style>
+* {{ box-sizing: border-box; margin: 0; padding: 0; }}
+body {{
+  font-family: system-ui, -apple-system, sans-serif;
+  background: #1a1a2e;
+  color: #e8e8e8;
+  padding: 24px;
+  max-width: 1100px;
+  margin: 0 auto;
+}}
+a {{ color: #4ecdc4; text-decoration: none; }}
+a:hover {{ text-decoration: underline; }}
+.back {{ margin-bottom: 16px; font-size: 0.875rem; }}
+h1 {{ font-size: 1.5rem; margin-bottom: 8px; }}
+h2 {{ font-size: 1.125rem; margin: 24px 0 12px 0; color: #9a9a9a; }}
+h2 .sub, .sub {{ font-size: 0.8125rem; color: #9a9a9a; font-weight: normal; }}
+table {{ width: 100%; border-collapse: collapse; font-size: 0.875rem; background: #16213e; margin-bottom: 16px; }}
+th, td {{ padding: 8px 12px; text-align: left; border-bottom: 1px solid #2d3a5c; vertical-align: top; }}
+th {{ background: #1a1a2e; color: #9a9a9a; font-weight: 600; font-size: 0.75rem; text-transform: uppercase; }}
+tr.changed td {{ background: #3a2f1e; }}
+tr.changed td:first-child {{ border-left: 3px solid #ffd166; }}
+.mono {{ font-family: monospace; }}
+.status-cell {{ font-weight: 600; }}
+.status-cell.exceeded, .delta-positive {{ color: #ff6b6b; }}
+.status-cell.improved, .delta-negative {{ color: #4ecdc4; }}
+.status-cell.ok {{ color: #9a9a9a; }}
+.status-cell.missing {{ color: #ffd166; }}
+</style>
+</head>
+<body>
+<div class="back"><a href="../index.html">&larr; Back to History</a></div>
+<h1>{a_link} vs {b_link}</h1>
+
+<h2>Metrics</h2>
+{metric_table}
+<h2>Environment <span class="sub">{env_note}</span></h2>
+<table>
+<tr><th>Property</th><th>{a_heading}</th><th>{b_heading}</th></tr>
+{env_html}</table>
+
+<h2>Config <span class="sub">{config_note}</span></h2>
+<table>
+<tr><th>Property</th><th>{a_heading}</th><th>{b_heading}</th></tr>
+{config_html}</table>
+
+</body>
+</html>"##,
+        a_slug = html_escape(a.slug),
+        b_slug = html_escape(b.slug),
+        a_link = link(a),
+        b_link = link(b),
+        a_heading = heading(a),
+        b_heading = heading(b),
+        env_note = changed_note(env_changed),
+        config_note = changed_note(config_changed),
+    )
+}
+
+/// Write a run diff page to a file.
+pub fn write_run_diff_html(
+    a: &DiffRun,
+    b: &DiffRun,
+    report: &RegressionReport,
+    runs_prefix: &str,
+    output_path: &Path,
+) -> Result<(), BenchError> {
+    if let Some(parent) = output_path.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent)
+                .map_err(|e| BenchError::Message(format!("failed to create directory: {e}")))?;
+        }
+    }
+
+    let html = render_run_diff_html(a, b, report, runs_prefix);
+    fs::write(output_path, html).map_err(|e| {
+        BenchError::Message(format!("failed to write {}: {e}", output_path.display()))
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig};
+
+    fn record(cpu: &str, iterations: u32) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            "keccak".to_string(),
+            EnvironmentInfo {
+                cpu_model: Some(cpu.to_string()),
+                ..EnvironmentInfo::default()
+            },
+            BackendInfo {
+                name: "bb".to_string(),
+                version: Some("0.62.0".to_string()),
+                variant: None,
+            },
+            RunConfig {
+                measured_iterations: iterations,
+                ..RunConfig::default()
+            },
+        );
+        r.timestamp = "2024-01-15T12:00:00Z".to_string();
+        r
+    }
+
+    #[test]
+    fn test_render_run_diff_marks_changes() {
+        let a = record("Ryzen", 3);
+        let mut b = record("<i>Xeon</i>", 5);
+        b.metadata.insert("corpus".to_string(), "v2".to_string());
+        let report = RegressionReport::new("run_000001", "run_000002", 10.0);
+
+        let html = render_run_diff_html(
+            &DiffRun {
+                record: &a,
+                slug: "run_000001",
+            },
+            &DiffRun {
+                record: &b,
+                slug: "run_000002",
+            },
+            &report,
+            "../runs/",
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(html.contains(r#"<a href="../runs/run_000001.html">run_000001</a>"#));
+        assert!(html.contains(
+            "<tr class=\"changed\"><td>CPU</td><td>Ryzen</td><td>&lt;i&gt;Xeon&lt;/i&gt;</td></tr>"
+        ));
+        assert!(html.contains("<tr><td>OS</td>"));
+        // Metadata only in b still shows up
+        assert!(html.contains("<td>metadata.corpus</td><td></td><td>v2</td>"));
+        // CPU; iterations and metadata
+        assert!(html.contains("1 difference(s)"));
+        assert!(html.contains("2 difference(s)"));
+        assert!(html.contains("class=\"metric-table\""));
+    }
+}
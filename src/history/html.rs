@@ -1,6 +1,7 @@
 //! HTML generator for history index.
 //!
-//! Generates a single-file HTML that fetches index.json at runtime.
+//! Generates a single-file HTML that fetches the chunked index (index-manifest.json,
+//! newest months first) at runtime, falling back to index.json.
 //! Uses textContent for all dynamic data insertion (XSS-safe).
 //! SVG chart built via DOM APIs (createElement, setAttribute) - no innerHTML.
 
@@ -8,16 +9,22 @@ use std::fs;
 use std::path::Path;
 
 use crate::BenchError;
+use crate::report::DisplaySettings;
 
 /// Render the history index HTML.
 ///
 /// The HTML is a single file with embedded CSS and JS that:
-/// - Fetches ./index.json at runtime
+/// - Fetches ./index-manifest.json and the newest monthly chunks at runtime,
+///   loading older months on demand (falls back to ./index.json)
+/// - Charts a single circuit from the pre-computed downsampled series, with a
+///   dashed line at the series' SLO (from `[budgets]`) and its headroom
 /// - Renders a table using textContent (not innerHTML) for safety
 /// - Renders an SVG trend chart using DOM APIs (createElement, setAttribute)
+/// - Shows timestamps and numbers with the process-wide display settings
+///   (`report::display`), like the pages rendered in Rust
 /// - Is deterministic: same output every time
 pub fn render_history_html() -> String {
-    // Static template - no dynamic data embedded
+    // Static template - only the display settings are filled in
     r##"<!DOCTYPE html>
 <html lang="en">
 <head>
@@ -48,6 +55,16 @@ h2 { font-size: 1.125rem; margin: 24px 0 12px 0; color: #9a9a9a; }
   font-size: 0.875rem;
 }
 #controls input[type="number"] { width: 80px; }
+#load-more { margin-bottom: 12px; }
+#load-more button {
+  background: #16213e;
+  border: 1px solid #2d3a5c;
+  color: #4ecdc4;
+  padding: 6px 10px;
+  border-radius: 4px;
+  font-size: 0.875rem;
+  cursor: pointer;
+}
 #controls select:focus, #controls input:focus { outline: 1px solid #4ecdc4; }
 #chart-container {
   background: #16213e;
@@ -80,14 +97,28 @@ a:hover { text-decoration: underline; }
 <div id="status">Loading...</div>
 <div id="error"></div>
 <div id="controls" style="display:none">
+<label for="view-select">View:</label>
+<select id="view-select">
+<option value="runs">All runs</option>
+<option value="circuits">By circuit</option>
+</select>
 <label for="metric-select">Metric:</label>
 <select id="metric-select"></select>
 <label for="circuit-filter">Circuit filter:</label>
 <input type="text" id="circuit-filter" placeholder="substring match">
+<label for="backend-filter">Backend:</label>
+<select id="backend-filter"><option value="">all</option></select>
+<label for="status-filter">Status:</label>
+<select id="status-filter">
+<option value="">all</option>
+<option value="ok">ok</option>
+<option value="error">not ok</option>
+</select>
 <label for="row-limit">Row limit:</label>
 <input type="number" id="row-limit" min="1" max="100000" value="500">
 </div>
 <div id="limit-info" style="display:none"></div>
+<div id="load-more" style="display:none"><button id="load-older" type="button">Load older months</button></div>
 <h2 id="chart-title" style="display:none">Trend Chart</h2>
 <div id="chart-container" style="display:none">
 <div id="chart-message"></div>
@@ -103,19 +134,40 @@ a:hover { text-decoration: underline; }
 <th class="num">prove_p50_ms</th>
 <th class="num">prove_p95_ms</th>
 <th class="num">gates</th>
+<th class="num">subgroup</th>
+<th>Commit</th>
 <th>Details</th>
 </tr>
 </thead>
 <tbody id="tbody"></tbody>
 </table>
+<table id="circuit-table" style="display:none">
+<thead>
+<tr>
+<th>Circuit</th>
+<th class="num">Runs</th>
+<th>Latest run</th>
+<th class="num" id="circuit-latest-th">latest</th>
+<th class="num">best</th>
+<th class="num">worst</th>
+</tr>
+</thead>
+<tbody id="circuit-tbody"></tbody>
+</table>
 <script>
+// --time-zone / --thousands-separator of the run that wrote this page
+var DISPLAY = __DISPLAY__;
 var allRecords = [];
+var manifest = null;
+var loadedChunks = 0;
+var allSeries = null;
 var DEFAULT_ROW_LIMIT = 500;
 var METRICS = [
   {key: 'prove_ms_p50', label: 'prove_ms_p50'},
   {key: 'prove_ms_p95', label: 'prove_ms_p95'},
   {key: 'verify_ms_p50', label: 'verify_ms_p50'},
   {key: 'gates', label: 'gates'},
+  {key: 'subgroup_size', label: 'subgroup_size'},
   {key: 'peak_rss_bytes', label: 'peak_rss_bytes'}
 ];
 
@@ -129,6 +181,7 @@ function hasMetric(records, key) {
 
 function populateMetricSelect(records) {
   var sel = document.getElementById('metric-select');
+  var current = sel.value;
   sel.innerHTML = '';
   for (var i = 0; i < METRICS.length; i++) {
     var metric = METRICS[i];
@@ -137,23 +190,107 @@ function populateMetricSelect(records) {
       opt.value = metric.key;
       opt.textContent = metric.label;
       sel.appendChild(opt);
+      if (metric.key === current) sel.value = current;
     }
   }
 }
 
+function populateBackendFilter(records) {
+  var sel = document.getElementById('backend-filter');
+  var current = sel.value;
+  var seen = {};
+  var backends = [];
+  for (var i = 0; i < records.length; i++) {
+    var b = records[i].backend || '';
+    if (b && !Object.prototype.hasOwnProperty.call(seen, b)) {
+      seen[b] = true;
+      backends.push(b);
+    }
+  }
+  backends.sort();
+  sel.innerHTML = '';
+  var all = document.createElement('option');
+  all.value = '';
+  all.textContent = 'all';
+  sel.appendChild(all);
+  for (var j = 0; j < backends.length; j++) {
+    var opt = document.createElement('option');
+    opt.value = backends[j];
+    opt.textContent = backends[j];
+    sel.appendChild(opt);
+  }
+  setSelectValue(sel, current);
+}
+
+function statusMatches(status, wanted) {
+  if (!wanted) return true;
+  return wanted === 'ok' ? status === 'ok' : status !== 'ok';
+}
+
 function getFilteredRecords() {
   var filter = document.getElementById('circuit-filter').value.toLowerCase();
-  if (!filter) return allRecords;
+  var backend = document.getElementById('backend-filter').value;
+  var status = document.getElementById('status-filter').value;
+  if (!filter && !backend && !status) return allRecords;
   var result = [];
   for (var i = 0; i < allRecords.length; i++) {
     var r = allRecords[i];
-    if (r.circuit_name && r.circuit_name.toLowerCase().indexOf(filter) !== -1) {
-      result.push(r);
-    }
+    if (filter && !(r.circuit_name && r.circuit_name.toLowerCase().indexOf(filter) !== -1)) continue;
+    if (backend && r.backend !== backend) continue;
+    if (!statusMatches(r.status, status)) continue;
+    result.push(r);
   }
   return result;
 }
 
+// Select a value only if the select offers it
+function setSelectValue(sel, value) {
+  for (var i = 0; i < sel.options.length; i++) {
+    if (sel.options[i].value === value) {
+      sel.value = value;
+      return;
+    }
+  }
+}
+
+// View state in the URL hash (#view=..&metric=..&circuit=..&backend=..&status=..&limit=..)
+// so a link reproduces the exact view
+var HASH_CONTROLS = [
+  {param: 'view', id: 'view-select'},
+  {param: 'metric', id: 'metric-select'},
+  {param: 'circuit', id: 'circuit-filter'},
+  {param: 'backend', id: 'backend-filter'},
+  {param: 'status', id: 'status-filter'},
+  {param: 'limit', id: 'row-limit'}
+];
+
+function writeHash() {
+  var params = new URLSearchParams();
+  for (var i = 0; i < HASH_CONTROLS.length; i++) {
+    var el = document.getElementById(HASH_CONTROLS[i].id);
+    // Inputs left at their default (row limit) stay out of the link
+    if (el.value && el.value !== el.defaultValue) params.set(HASH_CONTROLS[i].param, el.value);
+  }
+  var hash = params.toString();
+  if (hash !== location.hash.replace(/^#/, '')) {
+    history.replaceState(null, '', hash ? '#' + hash : location.pathname + location.search);
+  }
+}
+
+function applyHash() {
+  var params = new URLSearchParams(location.hash.replace(/^#/, ''));
+  for (var i = 0; i < HASH_CONTROLS.length; i++) {
+    var value = params.get(HASH_CONTROLS[i].param);
+    if (value === null) continue;
+    var el = document.getElementById(HASH_CONTROLS[i].id);
+    if (el.tagName === 'SELECT') {
+      setSelectValue(el, value);
+    } else {
+      el.value = value;
+    }
+  }
+}
+
 function getRowLimit() {
   var input = document.getElementById('row-limit');
   var val = parseInt(input.value, 10);
@@ -167,6 +304,36 @@ function getLimitedRecords(filtered) {
   return { records: filtered.slice(0, limit), total: filtered.length, limited: true };
 }
 
+// The downsampled series for the filtered circuit, or null unless the
+// filter matches exactly one circuit/backend series for this metric
+function findSeries(key) {
+  if (!allSeries) return null;
+  var filter = document.getElementById('circuit-filter').value.toLowerCase();
+  var backend = document.getElementById('backend-filter').value;
+  // The series cover every run, so they can't honor a status filter
+  if (!filter || document.getElementById('status-filter').value) return null;
+  var match = null;
+  for (var i = 0; i < allSeries.length; i++) {
+    var s = allSeries[i];
+    if (s.metric !== key || s.circuit_name.toLowerCase().indexOf(filter) === -1) continue;
+    if (backend && s.backend !== backend) continue;
+    if (match !== null) return null;
+    match = s;
+  }
+  return match;
+}
+
+// Points of the series from findSeries, or null
+function seriesPoints(key) {
+  var match = findSeries(key);
+  if (match === null) return null;
+  var points = [];
+  for (var j = 0; j < match.points.length; j++) {
+    points.push({idx: j, val: match.points[j][1], commit: ''});
+  }
+  return points;
+}
+
 function renderChart(records) {
   var svg = document.getElementById('chart-svg');
   var msg = document.getElementById('chart-message');
@@ -176,12 +343,16 @@ function renderChart(records) {
   // Clear SVG using DOM (safe)
   while (svg.firstChild) svg.removeChild(svg.firstChild);
 
-  // Extract data points
-  var points = [];
-  for (var i = 0; i < records.length; i++) {
-    var m = records[i].metrics || {};
-    if (m[key] != null) {
-      points.push({idx: i, val: m[key]});
+  // Extract data points: a single circuit's full history from the
+  // downsampled series, otherwise the loaded records
+  var points = seriesPoints(key);
+  if (points === null) {
+    points = [];
+    for (var i = 0; i < records.length; i++) {
+      var m = records[i].metrics || {};
+      if (m[key] != null) {
+        points.push({idx: i, val: m[key], commit: commitOf(records[i])});
+      }
     }
   }
 
@@ -201,12 +372,20 @@ function renderChart(records) {
   var chartW = W - padL - padR;
   var chartH = H - padT - padB;
 
-  // Find min/max
+  // SLO of a single circuit's series, from [budgets]
+  var series = findSeries(key);
+  var slo = series !== null && series.slo != null ? series.slo : null;
+
+  // Find min/max, keeping the SLO line in view
   var minVal = points[0].val, maxVal = points[0].val;
   for (var i = 1; i < points.length; i++) {
     if (points[i].val < minVal) minVal = points[i].val;
     if (points[i].val > maxVal) maxVal = points[i].val;
   }
+  if (slo !== null) {
+    minVal = Math.min(minVal, slo);
+    maxVal = Math.max(maxVal, slo);
+  }
   // Handle flat line
   if (minVal === maxVal) {
     minVal = minVal * 0.9;
@@ -269,6 +448,32 @@ function renderChart(records) {
   xLbl.textContent = 'Record index (oldest to newest)';
   svg.appendChild(xLbl);
 
+  // Dashed SLO line, labeled with the latest point's headroom
+  if (slo !== null) {
+    var sloLine = document.createElementNS(ns, 'line');
+    sloLine.setAttribute('x1', padL);
+    sloLine.setAttribute('y1', scaleY(slo));
+    sloLine.setAttribute('x2', W - padR);
+    sloLine.setAttribute('y2', scaleY(slo));
+    sloLine.setAttribute('stroke', '#ff6b6b');
+    sloLine.setAttribute('stroke-width', '1');
+    sloLine.setAttribute('stroke-dasharray', '6 4');
+    svg.appendChild(sloLine);
+
+    var latest = points[points.length - 1].val;
+    var headroom = slo === 0 ? 0 : (slo - latest) / slo * 100;
+    var sloLbl = document.createElementNS(ns, 'text');
+    sloLbl.setAttribute('x', W - padR);
+    sloLbl.setAttribute('y', scaleY(slo) - 4);
+    sloLbl.setAttribute('text-anchor', 'end');
+    sloLbl.setAttribute('fill', '#ff6b6b');
+    sloLbl.setAttribute('font-size', '10');
+    sloLbl.setAttribute('font-family', 'monospace');
+    sloLbl.textContent = 'SLO ' + formatNumber(slo) + ' \u00b7 ' +
+      (headroom >= 0 ? headroom.toFixed(0) + '% headroom' : (-headroom).toFixed(0) + '% over');
+    svg.appendChild(sloLbl);
+  }
+
   // Build polyline points string
   var polyPoints = '';
   for (var i = 0; i < points.length; i++) {
@@ -292,17 +497,54 @@ function renderChart(records) {
     circle.setAttribute('cy', scaleY(points[i].val));
     circle.setAttribute('r', '4');
     circle.setAttribute('fill', '#4ecdc4');
+    if (points[i].commit) {
+      var tip = document.createElementNS(ns, 'title');
+      tip.textContent = points[i].commit;
+      circle.appendChild(tip);
+    }
     svg.appendChild(circle);
   }
 }
 
 function formatNumber(n) {
+  if (DISPLAY.separator !== null && Math.abs(n) >= 1000) {
+    var digits = Math.abs(Math.round(n)).toString().replace(/\B(?=(\d{3})+(?!\d))/g, DISPLAY.separator);
+    return (Math.round(n) < 0 ? '-' : '') + digits;
+  }
   if (n >= 1000000) return (n / 1000000).toFixed(1) + 'M';
   if (n >= 1000) return (n / 1000).toFixed(1) + 'K';
   if (n === Math.floor(n)) return n.toString();
   return n.toFixed(1);
 }
 
+// RFC 3339 timestamp as YYYY-MM-DD HH:MM:SS in the configured zone
+function formatTimestamp(ts) {
+  if (!ts) return '';
+  var t = Date.parse(ts);
+  if (isNaN(t)) return ts.slice(0, 19).replace('T', ' ');
+  var shifted = new Date(t + DISPLAY.offset_seconds * 1000);
+  return shifted.toISOString().slice(0, 19).replace('T', ' ') + DISPLAY.offset_suffix;
+}
+
+// Subgroup size of the closest earlier run of the same circuit and backend
+function previousSubgroup(records, index) {
+  var r = records[index];
+  var best = null;
+  for (var j = 0; j < records.length; j++) {
+    var o = records[j];
+    if (j === index || o.circuit_name !== r.circuit_name || o.backend !== r.backend) continue;
+    if (!o.metrics || o.metrics.subgroup_size == null) continue;
+    if (!(o.timestamp < r.timestamp)) continue;
+    if (best === null || o.timestamp > best.timestamp) best = o;
+  }
+  return best ? best.metrics.subgroup_size : null;
+}
+
+// Annotated commit of a run (full value; shortened for display)
+function commitOf(r) {
+  return (r.annotations && r.annotations.commit) || '';
+}
+
 function renderTable(records) {
   var tbody = document.getElementById('tbody');
   var table = document.getElementById('table');
@@ -316,7 +558,7 @@ function renderTable(records) {
     // Timestamp
     var td0 = document.createElement('td');
     td0.className = 'mono';
-    td0.textContent = r.timestamp ? r.timestamp.replace('T', ' ').replace('Z', '').slice(0, 19) : '';
+    td0.textContent = formatTimestamp(r.timestamp);
     tr.appendChild(td0);
 
     // Circuit
@@ -345,6 +587,10 @@ function renderTable(records) {
     var td5 = document.createElement('td');
     td5.className = 'mono num';
     td5.textContent = m.prove_ms_p95 != null ? m.prove_ms_p95.toFixed(1) : '';
+    if (m.prove_ms_p95_low_confidence) {
+      td5.textContent += ' ~';
+      td5.title = 'low confidence: fewer than 20 samples';
+    }
     tr.appendChild(td5);
 
     // gates
@@ -353,6 +599,37 @@ function renderTable(records) {
     td6.textContent = m.gates != null ? m.gates : '';
     tr.appendChild(td6);
 
+    // subgroup (flag power-of-two crossings against this circuit's previous run)
+    var tdSub = document.createElement('td');
+    tdSub.className = 'mono num';
+    if (m.subgroup_size != null) {
+      tdSub.textContent = '2^' + Math.round(Math.log2(m.subgroup_size));
+      var prev = previousSubgroup(records, i);
+      if (prev != null && prev !== m.subgroup_size) {
+        tdSub.className += ' error';
+        tdSub.title = 'subgroup ' + (m.subgroup_size > prev ? 'grew' : 'shrank') + ' from 2^' + Math.round(Math.log2(prev)) +
+          ': expect ~' + (m.subgroup_size / prev) + 'x prove time';
+      }
+    }
+    tr.appendChild(tdSub);
+
+    // Commit (linked when the index has a link for it)
+    var tdCommit = document.createElement('td');
+    tdCommit.className = 'mono';
+    var commit = commitOf(r);
+    var shortCommit = /^[0-9a-fA-F]{12,}$/.test(commit) ? commit.slice(0, 10) : commit;
+    if (commit && r.commit_href && /^https?:\/\//.test(r.commit_href)) {
+      var commitLink = document.createElement('a');
+      commitLink.href = r.commit_href;
+      commitLink.rel = 'noopener';
+      commitLink.textContent = shortCommit;
+      tdCommit.appendChild(commitLink);
+    } else {
+      tdCommit.textContent = shortCommit;
+    }
+    tdCommit.title = commit;
+    tr.appendChild(tdCommit);
+
     // Details link
     var td7 = document.createElement('td');
     if (r.detail_href) {
@@ -361,6 +638,14 @@ function renderTable(records) {
       link.textContent = 'View';
       td7.appendChild(link);
     }
+    if (r.diff_href) {
+      var diffLink = document.createElement('a');
+      diffLink.href = r.diff_href;
+      diffLink.textContent = 'Diff';
+      diffLink.title = 'Compare with the previous run';
+      td7.appendChild(document.createTextNode(' · '));
+      td7.appendChild(diffLink);
+    }
     tr.appendChild(td7);
 
     tbody.appendChild(tr);
@@ -369,7 +654,70 @@ function renderTable(records) {
   table.style.display = '';
 }
 
+// One row per circuit: latest, best and worst of the selected metric, linked
+// to the circuit's trend page. Records are oldest first, so the last wins.
+function renderCircuitTable(records) {
+  var key = document.getElementById('metric-select').value;
+  var tbody = document.getElementById('circuit-tbody');
+  tbody.innerHTML = '';
+  document.getElementById('circuit-latest-th').textContent = key ? 'latest ' + key : 'latest';
+
+  var groups = {};
+  var names = [];
+  for (var i = 0; i < records.length; i++) {
+    var r = records[i];
+    var name = r.circuit_name || '';
+    if (!Object.prototype.hasOwnProperty.call(groups, name)) {
+      groups[name] = [];
+      names.push(name);
+    }
+    groups[name].push(r);
+  }
+  names.sort();
+
+  for (var n = 0; n < names.length; n++) {
+    var runs = groups[names[n]];
+    var latest = runs[runs.length - 1];
+    var latestVal = null, best = null, worst = null;
+    for (var j = 0; j < runs.length; j++) {
+      var v = (runs[j].metrics || {})[key];
+      if (v == null) continue;
+      latestVal = v;
+      if (best === null || v < best) best = v;
+      if (worst === null || v > worst) worst = v;
+    }
+
+    var tr = document.createElement('tr');
+    var tdName = document.createElement('td');
+    if (latest.circuit_href) {
+      var link = document.createElement('a');
+      link.href = latest.circuit_href;
+      link.textContent = names[n];
+      tdName.appendChild(link);
+    } else {
+      tdName.textContent = names[n];
+    }
+    tr.appendChild(tdName);
+
+    var cells = [
+      {text: runs.length, cls: 'mono num'},
+      {text: formatTimestamp(latest.timestamp), cls: 'mono'},
+      {text: latestVal != null ? formatNumber(latestVal) : '', cls: 'mono num'},
+      {text: best != null ? formatNumber(best) : '', cls: 'mono num'},
+      {text: worst != null ? formatNumber(worst) : '', cls: 'mono num'}
+    ];
+    for (var c = 0; c < cells.length; c++) {
+      var td = document.createElement('td');
+      td.className = cells[c].cls;
+      td.textContent = cells[c].text;
+      tr.appendChild(td);
+    }
+    tbody.appendChild(tr);
+  }
+}
+
 function update() {
+  writeHash();
   var filtered = getFilteredRecords();
   var result = getLimitedRecords(filtered);
   var limitInfo = document.getElementById('limit-info');
@@ -383,19 +731,92 @@ function update() {
   }
 
   renderChart(result.records);
-  renderTable(result.records);
+  if (document.getElementById('view-select').value === 'circuits') {
+    document.getElementById('table').style.display = 'none';
+    renderCircuitTable(filtered);
+    document.getElementById('circuit-table').style.display = '';
+  } else {
+    document.getElementById('circuit-table').style.display = 'none';
+    renderTable(result.records);
+  }
 }
 
+function fetchJson(href) {
+  return fetch(href).then(function(r) {
+    if (!r.ok) throw new Error(href + ': HTTP ' + r.status);
+    return r.json();
+  });
+}
+
+// Prepend the newest month not loaded yet
+function loadOlderChunk() {
+  var idx = manifest.chunks.length - 1 - loadedChunks;
+  if (idx < 0) return Promise.resolve();
+  return fetchJson('./' + manifest.chunks[idx].href).then(function(chunk) {
+    allRecords = chunk.concat(allRecords);
+    loadedChunks++;
+  });
+}
+
+// Load newest months until the row limit is filled
+function loadInitialChunks() {
+  if (loadedChunks >= manifest.chunks.length || allRecords.length >= getRowLimit()) {
+    return Promise.resolve();
+  }
+  return loadOlderChunk().then(loadInitialChunks);
+}
+
+function showLoaded() {
+  var status = document.getElementById('status');
+  var more = manifest !== null && loadedChunks < manifest.chunks.length;
+  if (manifest !== null) {
+    status.textContent = 'Loaded ' + allRecords.length + ' of ' + manifest.total_records + ' record(s)';
+  } else {
+    status.textContent = 'Loaded ' + allRecords.length + ' record(s)';
+  }
+  document.getElementById('load-more').style.display = more ? '' : 'none';
+}
+
+document.getElementById('load-older').addEventListener('click', function() {
+  loadOlderChunk().then(function() {
+    showLoaded();
+    populateMetricSelect(allRecords);
+    populateBackendFilter(allRecords);
+    update();
+  });
+});
+document.getElementById('view-select').addEventListener('change', update);
 document.getElementById('metric-select').addEventListener('change', update);
 document.getElementById('circuit-filter').addEventListener('input', update);
 document.getElementById('row-limit').addEventListener('input', update);
-
-fetch('./index.json')
-  .then(function(r) { return r.json(); })
-  .then(function(data) {
-    allRecords = data;
-    document.getElementById('status').textContent = 'Loaded ' + data.length + ' record(s)';
-    populateMetricSelect(data);
+document.getElementById('backend-filter').addEventListener('change', update);
+document.getElementById('status-filter').addEventListener('change', update);
+window.addEventListener('hashchange', function() {
+  applyHash();
+  update();
+});
+
+// Text inputs (row limit) apply before loading so the first chunks fill it
+applyHash();
+
+fetchJson('./index-manifest.json')
+  .then(function(m) {
+    manifest = m;
+    return Promise.all([
+      fetchJson('./' + m.series_href).then(function(s) { allSeries = s; }),
+      loadInitialChunks()
+    ]);
+  }, function() {
+    // Sites built before chunking only have index.json
+    return fetch('./index.json')
+      .then(function(r) { return r.json(); })
+      .then(function(data) { allRecords = data; });
+  })
+  .then(function() {
+    showLoaded();
+    populateMetricSelect(allRecords);
+    populateBackendFilter(allRecords);
+    applyHash();
     document.getElementById('controls').style.display = '';
     document.getElementById('chart-title').style.display = '';
     document.getElementById('chart-container').style.display = '';
@@ -407,7 +828,11 @@ fetch('./index.json')
   });
 </script>
 </body>
-</html>"##.to_string()
+</html>"##
+        .replace(
+            "__DISPLAY__",
+            &DisplaySettings::current().to_script_json(),
+        )
 }
 
 /// Write the history HTML to a file.
@@ -449,6 +874,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_html_embeds_display_settings() {
+        let html = render_history_html();
+        assert!(!html.contains("__DISPLAY__"));
+        assert!(html.contains("var DISPLAY = {\"offset_seconds\":"));
+        assert!(html.contains("td0.textContent = formatTimestamp(r.timestamp);"));
+    }
+
     #[test]
     fn test_html_structure() {
         let html = render_history_html();
@@ -458,6 +891,49 @@ mod tests {
         assert!(html.contains("</table>"));
     }
 
+    #[test]
+    fn test_html_commit_column_links_only_http() {
+        let html = render_history_html();
+        assert!(html.contains("<th>Commit</th>"));
+        assert!(html.contains(r"/^https?:\/\//.test(r.commit_href)"));
+        // Chart points carry the commit as an SVG tooltip
+        assert!(html.contains("createElementNS(ns, 'title')"));
+    }
+
+    #[test]
+    fn test_html_has_grouped_circuit_view() {
+        let html = render_history_html();
+        assert!(html.contains(r#"<option value="circuits">By circuit</option>"#));
+        assert!(html.contains(r#"<table id="circuit-table""#));
+        assert!(html.contains("link.href = latest.circuit_href;"));
+        assert!(html.contains("addEventListener('change', update)"));
+    }
+
+    #[test]
+    fn test_html_loads_chunks_lazily() {
+        let html = render_history_html();
+        assert!(html.contains("fetchJson('./index-manifest.json')"));
+        assert!(html.contains("fetchJson('./' + manifest.chunks[idx].href)"));
+        assert!(html.contains("fetchJson('./' + m.series_href)"));
+        assert!(html.contains(r#"<button id="load-older" type="button">"#));
+        // Falls back to the unchunked index
+        assert!(html.contains("fetch('./index.json')"));
+    }
+
+    #[test]
+    fn test_html_has_backend_status_filters_and_url_state() {
+        let html = render_history_html();
+        assert!(html.contains(r#"<select id="backend-filter">"#));
+        assert!(html.contains(r#"<select id="status-filter">"#));
+        assert!(html.contains("if (backend && r.backend !== backend) continue;"));
+        // Every control round-trips through the hash
+        for param in ["view", "metric", "circuit", "backend", "status", "limit"] {
+            assert!(html.contains(&format!("{{param: '{param}', id: ")));
+        }
+        assert!(html.contains("history.replaceState(null, '', "));
+        assert!(html.contains("addEventListener('hashchange'"));
+    }
+
     #[test]
     fn test_html_uses_textcontent_for_safety() {
         let html = render_history_html();
@@ -600,6 +1076,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_html_has_diff_link_support() {
+        let html = render_history_html();
+
+        assert!(html.contains("if (r.diff_href)"));
+        assert!(html.contains("diffLink.href = r.diff_href"));
+        assert!(html.contains("diffLink.textContent = 'Diff'"));
+    }
+
     #[test]
     fn test_html_has_chart_controls() {
         let html = render_history_html();
@@ -722,6 +1207,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_html_chart_draws_series_slo() {
+        let html = render_history_html();
+        // The SLO comes from the single matched series and widens the y range
+        assert!(html.contains("series.slo != null"));
+        assert!(html.contains("maxVal = Math.max(maxVal, slo)"));
+        assert!(html.contains("sloLine.setAttribute('stroke-dasharray', '6 4')"));
+        assert!(html.contains("'% headroom'"));
+    }
+
     #[test]
     fn test_html_chart_shows_not_enough_data_message() {
         let html = render_history_html();
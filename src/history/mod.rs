@@ -2,16 +2,27 @@
 //!
 //! This module provides functionality to build derived index artifacts from
 //! the canonical JSONL telemetry format. The derived artifacts (index.json, index.html,
-//! per-run detail pages) are for visualization and querying - the canonical source remains JSONL.
+//! per-run detail pages, per-circuit trend pages, run-to-run diff pages) are for visualization and querying - the
+//! canonical source remains JSONL.
 
 pub mod build;
+pub mod circuit_html;
+pub mod diff_html;
 pub mod html;
 pub mod run_html;
 pub mod schema;
 
-pub use build::{assign_detail_slugs, build_index, write_index_json};
+pub use build::{
+    DEFAULT_SERIES_POINTS, assign_circuit_slugs, assign_detail_slugs, assign_diff_hrefs,
+    build_index, build_index_from_records, build_series, chunk_by_month, downsample, index_slos,
+    previous_runs, write_chunked_index, write_index_json,
+};
+pub use circuit_html::{render_circuit_html, write_circuit_html};
+pub use diff_html::{DiffRun, render_run_diff_html, write_run_diff_html};
 pub use html::{render_history_html, write_history_html};
 pub use run_html::{html_escape, render_run_detail_html, write_run_detail_html};
 pub use schema::{
-    RUN_INDEX_SCHEMA_VERSION, RunIndexMetricsV1, RunIndexRecordV1, make_run_href, make_run_slug,
+    INDEX_METRICS, IndexChunkV1, IndexManifestV1, MetricSeriesV1, RUN_INDEX_SCHEMA_VERSION,
+    RunIndexMetricsV1, RunIndexRecordV1, make_circuit_href, make_circuit_slug, make_diff_href,
+    make_run_href, make_run_slug,
 };
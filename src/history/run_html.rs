@@ -8,26 +8,12 @@ use std::fs;
 use std::path::Path;
 
 use crate::BenchError;
-use crate::core::schema::BenchRecord;
+use crate::core::schema::{BenchRecord, MIN_P95_SAMPLES, TimingStat};
+pub use crate::report::html::html_escape;
+use crate::report::{format_timestamp, format_value};
 
-/// HTML-escape a string for safe insertion into HTML content.
-///
-/// Escapes: & < > " '
-/// This prevents XSS when inserting user-controlled strings into HTML.
-pub fn html_escape(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    for c in s.chars() {
-        match c {
-            '&' => result.push_str("&amp;"),
-            '<' => result.push_str("&lt;"),
-            '>' => result.push_str("&gt;"),
-            '"' => result.push_str("&quot;"),
-            '\'' => result.push_str("&#x27;"),
-            _ => result.push(c),
-        }
-    }
-    result
-}
+/// Chart width in SVG user units (scaled to the page width).
+const CHART_WIDTH: f64 = 600.0;
 
 /// Format an optional f64 for display.
 fn fmt_opt_f64(v: Option<f64>, suffix: &str) -> String {
@@ -45,8 +31,145 @@ fn fmt_opt_u64(v: Option<u64>, suffix: &str) -> String {
     }
 }
 
+/// Pipeline phases charted on the detail page, in execution order.
+fn chart_phases(record: &BenchRecord) -> Vec<(&'static str, &'static str, &TimingStat)> {
+    [
+        ("compile", "#ffd166", record.compile_stats.as_ref()),
+        ("witness", "#a78bfa", record.witness_stats.as_ref()),
+        ("prove", "#4ecdc4", record.prove_stats.as_ref()),
+        ("verify", "#ff6b6b", record.verify_stats.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(name, color, stat)| Some((name, color, stat?)))
+    .collect()
+}
+
+/// Waterfall of mean phase times: each bar starts where the previous phase
+/// ended, so bar widths show each phase's share of the run.
+fn render_phase_waterfall(record: &BenchRecord) -> String {
+    let phases = chart_phases(record);
+    let total: f64 = phases.iter().map(|(_, _, s)| s.mean_ms).sum();
+    if total <= 0.0 {
+        return String::new();
+    }
+
+    let (label_w, row_h) = (70.0, 24.0);
+    let bar_w = CHART_WIDTH - label_w - 130.0;
+    let height = row_h * phases.len() as f64 + 8.0;
+    let mut svg = format!(
+        r#"<svg class="chart" viewBox="0 0 {CHART_WIDTH} {height}" role="img" aria-label="Phase waterfall">"#
+    );
+    let mut start = 0.0;
+    for (i, (name, color, stat)) in phases.iter().enumerate() {
+        let y = 4.0 + row_h * i as f64;
+        let x = label_w + start / total * bar_w;
+        let w = (stat.mean_ms / total * bar_w).max(1.0);
+        svg.push_str(&format!(
+            r#"<text x="0" y="{:.1}" class="label">{name}</text><rect x="{x:.1}" y="{y:.1}" width="{w:.1}" height="{:.1}" fill="{color}"><title>{name}: {:.3} ms</title></rect><text x="{:.1}" y="{:.1}" class="label">{:.1} ms ({:.0}%)</text>"#,
+            y + row_h * 0.65,
+            row_h - 6.0,
+            stat.mean_ms,
+            x + w + 6.0,
+            y + row_h * 0.65,
+            stat.mean_ms,
+            stat.mean_ms / total * 100.0,
+        ));
+        start += stat.mean_ms;
+    }
+    svg.push_str("</svg>");
+    format!("<h3>Where time went</h3>\n{svg}")
+}
+
+/// Scatter of raw iteration times per phase (iteration on x, ms on y).
+/// Empty when the record has no per-iteration samples.
+fn render_iteration_scatter(record: &BenchRecord) -> String {
+    let phases: Vec<_> = chart_phases(record)
+        .into_iter()
+        .filter(|(_, _, s)| !s.samples_ms.is_empty())
+        .collect();
+    let max_ms = phases
+        .iter()
+        .flat_map(|(_, _, s)| s.samples_ms.iter().copied())
+        .fold(0.0, f64::max);
+    if phases.is_empty() || max_ms <= 0.0 {
+        return String::new();
+    }
+    let max_iters = phases
+        .iter()
+        .map(|(_, _, s)| s.samples_ms.len())
+        .max()
+        .unwrap_or(1);
+
+    let (pad_l, pad_r, pad_t, pad_b, height) = (60.0, 16.0, 12.0, 36.0, 220.0);
+    let plot_w = CHART_WIDTH - pad_l - pad_r;
+    let plot_h = height - pad_t - pad_b;
+    let scale_x = |i: usize| {
+        if max_iters == 1 {
+            pad_l + plot_w / 2.0
+        } else {
+            pad_l + i as f64 / (max_iters - 1) as f64 * plot_w
+        }
+    };
+    let scale_y = |ms: f64| pad_t + plot_h - ms / max_ms * plot_h;
+
+    let mut svg = format!(
+        r#"<svg class="chart" viewBox="0 0 {CHART_WIDTH} {height}" role="img" aria-label="Iteration times">"#
+    );
+    svg.push_str(&format!(
+        r#"<line x1="{pad_l}" y1="{pad_t}" x2="{pad_l}" y2="{:.1}" class="axis"/><line x1="{pad_l}" y1="{:.1}" x2="{:.1}" y2="{:.1}" class="axis"/>"#,
+        pad_t + plot_h,
+        pad_t + plot_h,
+        pad_l + plot_w,
+        pad_t + plot_h,
+    ));
+    for ms in [0.0, max_ms / 2.0, max_ms] {
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" class="label" text-anchor="end">{ms:.1}</text>"#,
+            pad_l - 6.0,
+            scale_y(ms) + 4.0,
+        ));
+    }
+    svg.push_str(&format!(
+        r#"<text x="{:.1}" y="{:.1}" class="label" text-anchor="middle">iteration (1..{max_iters}), ms</text>"#,
+        pad_l + plot_w / 2.0,
+        height - 8.0,
+    ));
+    for (p, (name, color, stat)) in phases.iter().enumerate() {
+        for (i, ms) in stat.samples_ms.iter().enumerate() {
+            svg.push_str(&format!(
+                r#"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{color}"><title>{name} #{}: {ms:.3} ms</title></circle>"#,
+                scale_x(i),
+                scale_y(*ms),
+                i + 1,
+            ));
+        }
+        // Legend
+        let lx = pad_l + 8.0 + 90.0 * p as f64;
+        svg.push_str(&format!(
+            r#"<circle cx="{lx:.1}" cy="{pad_t}" r="4" fill="{color}"/><text x="{:.1}" y="{:.1}" class="label">{name}</text>"#,
+            lx + 8.0,
+            pad_t + 4.0,
+        ));
+    }
+    svg.push_str("</svg>");
+    format!("<h3>Iteration times</h3>\n{svg}")
+}
+
+/// P95 cell, flagged when there are too few samples to trust it.
+fn fmt_p95(stat: &TimingStat) -> String {
+    let p95 = fmt_opt_f64(stat.p95_ms, " ms");
+    if stat.p95_low_confidence() {
+        format!(
+            r#"{p95} <span class="low-confidence" title="fewer than {MIN_P95_SAMPLES} samples">(low confidence, n={})</span>"#,
+            stat.iterations
+        )
+    } else {
+        p95
+    }
+}
+
 /// Render a timing stat section as HTML.
-fn render_timing_section(name: &str, stat: Option<&crate::core::schema::TimingStat>) -> String {
+fn render_timing_section(name: &str, stat: Option<&TimingStat>) -> String {
     match stat {
         Some(s) => {
             format!(
@@ -69,19 +192,78 @@ fn render_timing_section(name: &str, stat: Option<&crate::core::schema::TimingSt
                 fmt_opt_f64(s.stddev_ms, " ms"),
                 s.min_ms,
                 s.max_ms,
-                fmt_opt_f64(s.p95_ms, " ms"),
+                fmt_p95(s),
             )
         }
         None => String::new(),
     }
 }
 
+/// Per-phase backend proving times (from `--backend-verbose`), largest first.
+fn render_backend_phases(record: &BenchRecord) -> String {
+    let Some(phases) = record.backend_phases.as_ref().filter(|p| !p.is_empty()) else {
+        return String::new();
+    };
+    let total: f64 = phases.values().sum();
+    let mut rows: Vec<_> = phases.iter().collect();
+    rows.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let rows: String = rows
+        .into_iter()
+        .map(|(phase, ms)| {
+            let pct = if total > 0.0 { ms / total * 100.0 } else { 0.0 };
+            format!(
+                r#"<tr><td>{}</td><td class="num">{:.3} ms</td><td class="num">{:.0}%</td></tr>"#,
+                html_escape(phase),
+                ms,
+                pct
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        r#"<details open>
+<summary>Backend Phases</summary>
+<table class="stat-table">
+{rows}
+</table>
+</details>"#
+    )
+}
+
+/// Metrics scraped from a template backend's output (`--custom-metric`).
+fn render_custom_metrics(record: &BenchRecord) -> String {
+    if record.custom_metrics.is_empty() {
+        return String::new();
+    }
+    let rows: String = record
+        .custom_metrics
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                r#"<tr><td>{}</td><td class="num">{}</td></tr>"#,
+                html_escape(name),
+                html_escape(&format_value(*value, name))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        r#"<details open>
+<summary>Custom Metrics</summary>
+<table class="stat-table">
+{rows}
+</table>
+</details>"#
+    )
+}
+
 /// Render a per-run detail page as static HTML.
 ///
 /// The output is a complete HTML document with:
 /// - Header with circuit name and back link
 /// - Summary metrics table
 /// - Environment/toolchain info
+/// - Phase waterfall and iteration scatter (inline SVG)
 /// - Phase timing details (collapsible)
 /// - Raw JSON record (collapsible)
 ///
@@ -91,7 +273,7 @@ pub fn render_run_detail_html(record: &BenchRecord, slug: &str) -> String {
     // Escape all user-controlled strings
     let circuit_name = html_escape(&record.circuit_name);
     let record_id = html_escape(&record.record_id);
-    let timestamp = html_escape(&record.timestamp);
+    let timestamp = html_escape(&format_timestamp(&record.timestamp));
     let backend_name = html_escape(&record.backend.name);
     let backend_version = record
         .backend
@@ -143,6 +325,11 @@ pub fn render_run_detail_html(record: &BenchRecord, slug: &str) -> String {
     let subgroup_size = fmt_opt_u64(record.subgroup_size, "");
     let proof_size = fmt_opt_u64(record.proof_size_bytes, " bytes");
     let pk_size = fmt_opt_u64(record.proving_key_size_bytes, " bytes");
+    let vk_cached = match record.vk_cached {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "—",
+    };
     let vk_size = fmt_opt_u64(record.verification_key_size_bytes, " bytes");
     let peak_rss = record
         .peak_rss_mb
@@ -151,10 +338,21 @@ pub fn render_run_detail_html(record: &BenchRecord, slug: &str) -> String {
 
     // Timing sections
     let compile_section = render_timing_section("Compile/Load", record.compile_stats.as_ref());
+    let compile_warm_section =
+        render_timing_section("Warm Compile", record.compile_warm_stats.as_ref());
+    let compile_incremental_section = render_timing_section(
+        "Incremental Compile",
+        record.compile_incremental_stats.as_ref(),
+    );
     let witness_section =
         render_timing_section("Witness Generation", record.witness_stats.as_ref());
     let prove_section = render_timing_section("Proving", record.prove_stats.as_ref());
+    let vk_gen_section = render_timing_section("VK Generation", record.vk_gen_stats.as_ref());
     let verify_section = render_timing_section("Verification", record.verify_stats.as_ref());
+    let backend_phases_section = render_backend_phases(record);
+    let custom_metrics_section = render_custom_metrics(record);
+    let phase_waterfall = render_phase_waterfall(record);
+    let iteration_scatter = render_iteration_scatter(record);
 
     // Raw JSON (escaped for HTML)
     let raw_json = serde_json::to_string_pretty(record).unwrap_or_else(|_| "{}".to_string());
@@ -208,6 +406,11 @@ summary:hover {{ background: #1f2b47; }}
 pre {{ background: #16213e; padding: 16px; border-radius: 4px; overflow-x: auto; font-size: 0.75rem; line-height: 1.4; white-space: pre-wrap; word-break: break-all; }}
 .ok {{ color: #4ecdc4; }}
 .error {{ color: #ff6b6b; }}
+.low-confidence {{ color: #f0ad4e; font-family: system-ui, sans-serif; font-size: 0.75rem; }}
+h3 {{ font-size: 0.9375rem; margin: 16px 0 8px 0; color: #9a9a9a; }}
+svg.chart {{ width: 100%; height: auto; background: #16213e; border-radius: 4px; margin-bottom: 8px; }}
+svg.chart .label {{ fill: #9a9a9a; font-size: 11px; font-family: monospace; }}
+svg.chart .axis {{ stroke: #2d3a5c; }}
 </style>
 </head>
 <body>
@@ -225,6 +428,7 @@ pre {{ background: #16213e; padding: 16px; border-radius: 4px; overflow-x: auto;
 <tr><td>Subgroup Size</td><td class="num">{subgroup_size}</td></tr>
 <tr><td>Proof Size</td><td class="num">{proof_size}</td></tr>
 <tr><td>Proving Key Size</td><td class="num">{pk_size}</td></tr>
+<tr><td>VK Reused</td><td class="num">{vk_cached}</td></tr>
 <tr><td>Verification Key Size</td><td class="num">{vk_size}</td></tr>
 <tr><td>Peak RSS</td><td class="num">{peak_rss}</td></tr>
 </table>
@@ -257,9 +461,16 @@ pre {{ background: #16213e; padding: 16px; border-radius: 4px; overflow-x: auto;
 </table>
 
 <h2>Phases</h2>
+{phase_waterfall}
+{iteration_scatter}
 {compile_section}
+{compile_warm_section}
+{compile_incremental_section}
 {witness_section}
 {prove_section}
+{vk_gen_section}
+{backend_phases_section}
+{custom_metrics_section}
 {verify_section}
 
 <details>
@@ -283,6 +494,7 @@ pre {{ background: #16213e; padding: 16px; border-radius: 4px; overflow-x: auto;
         subgroup_size = subgroup_size,
         proof_size = proof_size,
         pk_size = pk_size,
+        vk_cached = vk_cached,
         vk_size = vk_size,
         peak_rss = peak_rss,
         os = os,
@@ -302,9 +514,16 @@ pre {{ background: #16213e; padding: 16px; border-radius: 4px; overflow-x: auto;
             .map(|t| format!("{} s", t))
             .unwrap_or_else(|| "—".to_string()),
         compile_section = compile_section,
+        compile_warm_section = compile_warm_section,
+        compile_incremental_section = compile_incremental_section,
         witness_section = witness_section,
         prove_section = prove_section,
+        vk_gen_section = vk_gen_section,
+        backend_phases_section = backend_phases_section,
+        custom_metrics_section = custom_metrics_section,
         verify_section = verify_section,
+        phase_waterfall = phase_waterfall,
+        iteration_scatter = iteration_scatter,
         cli_args = cli_args,
         raw_json_escaped = raw_json_escaped,
     )
@@ -422,6 +641,18 @@ mod tests {
         assert!(html.contains("<summary>"));
     }
 
+    #[test]
+    fn test_render_run_detail_html_flags_low_confidence_p95() {
+        let mut record = make_test_record();
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(html.contains("(low confidence, n=3)"));
+
+        let samples: Vec<f64> = (0..MIN_P95_SAMPLES).map(|i| 100.0 + f64::from(i)).collect();
+        record.prove_stats = Some(TimingStat::from_samples(&samples));
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(!html.contains("low confidence"));
+    }
+
     #[test]
     fn test_render_run_detail_html_deterministic() {
         let record = make_test_record();
@@ -466,4 +697,64 @@ mod tests {
         // Should not contain raw < or > from the circuit name in the JSON section
         // (The JSON will have the literal string, but it should be HTML-escaped)
     }
+
+    #[test]
+    fn test_render_run_detail_html_charts() {
+        let mut record = make_test_record();
+        record.witness_stats = Some(TimingStat::from_samples(&[20.0, 30.0]));
+        let html = render_run_detail_html(&record, "run_000001");
+
+        // Waterfall: witness (25 ms) then prove (110 ms), offset by witness
+        assert!(html.contains("Where time went"));
+        assert!(html.contains("<title>witness: 25.000 ms</title>"));
+        assert!(html.contains("110.0 ms (81%)"));
+
+        // Scatter: one point per raw iteration time
+        assert!(html.contains("Iteration times"));
+        assert_eq!(html.matches("<title>prove #").count(), 3);
+        assert!(html.contains("<title>witness #2: 30.000 ms</title>"));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn test_render_run_detail_html_backend_phases() {
+        let mut record = make_test_record();
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(!html.contains("Backend Phases"));
+
+        record.backend_phases = Some(
+            [("fft".to_string(), 25.0), ("msm".to_string(), 75.0)]
+                .into_iter()
+                .collect(),
+        );
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(html.contains("Backend Phases"));
+        assert!(
+            html.contains(r#"<td>msm</td><td class="num">75.000 ms</td><td class="num">75%</td>"#)
+        );
+        assert!(html.find("<td>msm</td>").unwrap() < html.find("<td>fft</td>").unwrap());
+    }
+
+    #[test]
+    fn test_render_run_detail_html_custom_metrics() {
+        let mut record = make_test_record();
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(!html.contains("Custom Metrics"));
+
+        record
+            .custom_metrics
+            .insert("folded_constraints".to_string(), 1200.0);
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(html.contains("Custom Metrics"));
+        assert!(html.contains(r#"<td>folded_constraints</td><td class="num">1200.00</td>"#));
+    }
+
+    #[test]
+    fn test_render_run_detail_html_no_samples_no_scatter() {
+        let mut record = make_test_record();
+        record.prove_stats.as_mut().unwrap().samples_ms.clear();
+        let html = render_run_detail_html(&record, "run_000001");
+        assert!(html.contains("Where time went"));
+        assert!(!html.contains("Iteration times"));
+    }
 }
@@ -3,6 +3,8 @@
 //! These schemas are DERIVED artifacts - they do NOT modify or replace BenchRecord v1.
 //! The canonical telemetry format remains JSONL with BenchRecord.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Schema version for RunIndexRecord (derived schema, independent of BenchRecord).
@@ -29,16 +31,28 @@ pub struct RunIndexRecordV1 {
     /// Backend name (from BenchRecord.backend.name)
     pub backend: String,
 
+    /// CPU architecture (from BenchRecord.env.arch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+
     /// Suite name if available (currently not in BenchRecord, reserved for future)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suite: Option<String>,
 
-    /// Status: "ok" or "error" (derived best-effort)
+    /// Status: "ok", "error" or "expected_failure" (derived best-effort)
     pub status: String,
 
     /// Summary metrics for display
     pub metrics: RunIndexMetricsV1,
 
+    /// Run annotations (from `annotation.*` BenchRecord metadata)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+
+    /// Link to the annotated commit, when it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_href: Option<String>,
+
     /// Deterministic slug for detail page (e.g., "run_000001")
     /// Assigned based on sorted index order (1-based).
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -47,6 +61,15 @@ pub struct RunIndexRecordV1 {
     /// Relative href to detail page (e.g., "runs/run_000001.html")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub detail_href: Option<String>,
+
+    /// Relative href to the circuit's trend page (e.g., "circuits/keccak.html")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_href: Option<String>,
+
+    /// Relative href to the diff against the previous run of the same circuit
+    /// and backend (e.g., "diffs/run_000001-vs-run_000003.html")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_href: Option<String>,
 }
 
 /// Summary metrics for the run index.
@@ -63,6 +86,10 @@ pub struct RunIndexMetricsV1 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prove_ms_p95: Option<f64>,
 
+    /// Set when `prove_ms_p95` comes from fewer than `MIN_P95_SAMPLES` samples
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub prove_ms_p95_low_confidence: bool,
+
     /// Verify time p50 (median) in milliseconds, rounded to 3 decimal places
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verify_ms_p50: Option<f64>,
@@ -74,6 +101,85 @@ pub struct RunIndexMetricsV1 {
     /// Peak RSS in bytes (from peak_rss_mb * 1_000_000, if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub peak_rss_bytes: Option<u64>,
+
+    /// Subgroup size bucket (next power of two of the gate count)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgroup_size: Option<u64>,
+}
+
+/// Metric keys of `RunIndexMetricsV1`, in display order.
+pub const INDEX_METRICS: &[&str] = &[
+    "prove_ms_p50",
+    "prove_ms_p95",
+    "verify_ms_p50",
+    "gates",
+    "subgroup_size",
+    "peak_rss_bytes",
+];
+
+impl RunIndexMetricsV1 {
+    /// Value of a metric by key (see `INDEX_METRICS`).
+    pub fn value(&self, key: &str) -> Option<f64> {
+        match key {
+            "prove_ms_p50" => self.prove_ms_p50,
+            "prove_ms_p95" => self.prove_ms_p95,
+            "verify_ms_p50" => self.verify_ms_p50,
+            "gates" => self.gates.map(|v| v as f64),
+            "subgroup_size" => self.subgroup_size.map(|v| v as f64),
+            "peak_rss_bytes" => self.peak_rss_bytes.map(|v| v as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Manifest of a chunked index (index-manifest.json).
+///
+/// Index records are split into one file per month (index-YYYY-MM.json) so the
+/// HTML viewer can load recent months first and older ones on demand.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexManifestV1 {
+    /// Schema version (always 1 for this version)
+    pub schema_version: u32,
+
+    /// Records across all chunks
+    pub total_records: usize,
+
+    /// Chunks, oldest month first
+    pub chunks: Vec<IndexChunkV1>,
+
+    /// Relative href to the downsampled series file
+    pub series_href: String,
+}
+
+/// One month of index records.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexChunkV1 {
+    /// Month of the records ("YYYY-MM", or "unknown" for unparseable timestamps)
+    pub month: String,
+
+    /// Relative href to the chunk file (e.g., "index-2024-01.json")
+    pub href: String,
+
+    /// Records in the chunk
+    pub records: usize,
+}
+
+/// Downsampled metric series for one circuit and backend (series.json).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricSeriesV1 {
+    pub circuit_name: String,
+    pub backend: String,
+    pub metric: String,
+
+    /// Points before downsampling
+    pub total_points: usize,
+
+    /// (timestamp, value) pairs, oldest first
+    pub points: Vec<(String, f64)>,
+
+    /// Budget (SLO) for the metric from `[budgets]`, drawn as a reference line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo: Option<f64>,
 }
 
 impl RunIndexRecordV1 {
@@ -91,11 +197,16 @@ impl RunIndexRecordV1 {
             timestamp,
             circuit_name,
             backend,
+            arch: None,
             suite: None,
             status,
             metrics: RunIndexMetricsV1::default(),
+            annotations: BTreeMap::new(),
+            commit_href: None,
             detail_slug: None,
             detail_href: None,
+            circuit_href: None,
+            diff_href: None,
         }
     }
 }
@@ -114,6 +225,41 @@ pub fn make_run_href(slug: &str) -> String {
     format!("runs/{}.html", slug)
 }
 
+/// Generate a relative href for the diff page of two runs.
+///
+/// Format: "diffs/{a}-vs-{b}.html"
+pub fn make_diff_href(a_slug: &str, b_slug: &str) -> String {
+    format!("diffs/{}-vs-{}.html", a_slug, b_slug)
+}
+
+/// Generate a file-name-safe slug from a circuit name.
+///
+/// ASCII letters, digits, '-' and '_' are kept; anything else becomes '_'.
+pub fn make_circuit_slug(circuit_name: &str) -> String {
+    let slug: String = circuit_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if slug.is_empty() {
+        "circuit".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Generate a relative href for a circuit trend page.
+///
+/// Format: "circuits/{slug}.html"
+pub fn make_circuit_href(slug: &str) -> String {
+    format!("circuits/{}.html", slug)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,17 +272,24 @@ mod tests {
             timestamp: "2024-01-15T12:00:00Z".to_string(),
             circuit_name: "test_circuit".to_string(),
             backend: "bb".to_string(),
+            arch: Some("x86_64".to_string()),
             suite: None,
             status: "ok".to_string(),
             metrics: RunIndexMetricsV1 {
                 prove_ms_p50: Some(100.123),
                 prove_ms_p95: Some(150.456),
+                prove_ms_p95_low_confidence: false,
                 verify_ms_p50: None,
                 gates: Some(10000),
                 peak_rss_bytes: None,
+                subgroup_size: Some(16384),
             },
+            annotations: BTreeMap::from([("commit".to_string(), "abc123".to_string())]),
+            commit_href: None,
             detail_slug: Some("run_000001".to_string()),
             detail_href: Some("runs/run_000001.html".to_string()),
+            circuit_href: Some("circuits/test_circuit.html".to_string()),
+            diff_href: None,
         };
 
         let json = serde_json::to_string(&record).unwrap();
@@ -162,6 +315,8 @@ mod tests {
         // detail_slug and detail_href should not appear when None
         assert!(!json.contains("detail_slug"));
         assert!(!json.contains("detail_href"));
+        assert!(!json.contains("annotations"));
+        assert!(!json.contains("commit_href"));
     }
 
     #[test]
@@ -172,6 +327,14 @@ mod tests {
         assert_eq!(make_run_slug(1000000), "run_1000000"); // exceeds 6 digits, still works
     }
 
+    #[test]
+    fn test_make_circuit_slug() {
+        assert_eq!(make_circuit_slug("keccak_256"), "keccak_256");
+        assert_eq!(make_circuit_slug("../a b"), "___a_b");
+        assert_eq!(make_circuit_slug(""), "circuit");
+        assert_eq!(make_circuit_href("keccak"), "circuits/keccak.html");
+    }
+
     #[test]
     fn test_make_run_href() {
         assert_eq!(make_run_href("run_000001"), "runs/run_000001.html");
@@ -1,25 +1,53 @@
-//! CLI command handler for `history build`.
+//! CLI command handlers for `history build` and `history diff`.
 //!
-//! Builds derived artifacts (index.json, index.html, per-run detail pages) from canonical JSONL.
+//! Builds derived artifacts (index.json, index.html, per-run detail pages, per-circuit
+//! trend pages, run-to-run diff pages) from canonical JSONL.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
-use crate::history::{build_index, write_history_html, write_index_json, write_run_detail_html};
-use crate::storage::JsonlWriter;
+use crate::check_cmd::{Budgets, circuit_budgets};
+use crate::compare_cmd::{self, DEFAULT_THRESHOLD};
+use crate::core::SignaturePolicy;
+use crate::core::env::arch_matches;
+use crate::core::schema::BenchRecord;
+use crate::history::{
+    DEFAULT_SERIES_POINTS, DiffRun, RunIndexRecordV1, build_index_from_records, index_slos,
+    make_diff_href, previous_runs, write_chunked_index, write_circuit_html, write_history_html,
+    write_index_json, write_run_detail_html, write_run_diff_html,
+};
+use crate::report::RegressionReport;
+use crate::storage::{ParseLimits, read_untrusted};
 use crate::{BenchError, BenchResult};
 
 /// Run the `history build` command.
 ///
 /// Reads BenchRecord from JSONL, derives RunIndexRecordV1, and writes:
-/// - <out>/index.json - derived index data
+/// - <out>/index.json - derived index data (all records)
+/// - <out>/index-YYYY-MM.json, series.json, index-manifest.json - the same
+///   index chunked by month plus downsampled series, loaded lazily by index.html
 /// - <out>/index.html - single-file HTML dashboard
 /// - <out>/runs/*.html - per-run detail pages (static, no JS)
+/// - <out>/circuits/*.html - per-circuit trend pages (static, no JS)
+/// - <out>/diffs/*.html - diff of each run against the previous run of the same
+///   circuit and backend (static, no JS)
+///
+/// Every record must pass `policy` (signature checks) before anything is written.
 ///
 /// # Arguments
 /// * `jsonl_path` - Path to input JSONL file
 /// * `out_dir` - Output directory for derived artifacts
-pub fn build(jsonl_path: PathBuf, out_dir: PathBuf) -> BenchResult<()> {
+/// * `policy` - Which signed/unsigned records to accept
+/// * `arch` - Only include records measured on this CPU architecture
+/// * `budgets` - `[budgets]` of bench-config.toml, drawn as SLO lines on the
+///   trend charts
+pub fn build(
+    jsonl_path: PathBuf,
+    out_dir: PathBuf,
+    policy: &SignaturePolicy,
+    arch: Option<&str>,
+    budgets: &Budgets,
+) -> BenchResult<()> {
     // Validate input exists
     if !jsonl_path.exists() {
         return Err(BenchError::Message(format!(
@@ -28,9 +56,21 @@ pub fn build(jsonl_path: PathBuf, out_dir: PathBuf) -> BenchResult<()> {
         )));
     }
 
-    // Build the index from JSONL (this also assigns detail slugs)
+    // Read the original BenchRecords (full data for detail pages) and
+    // authenticate them before deriving anything
     eprintln!("Reading JSONL from: {}", jsonl_path.display());
-    let records = build_index(&jsonl_path)?;
+    // Archives may come from many contributors: skip bad lines with a warning
+    let read = read_untrusted(&jsonl_path, &ParseLimits::default())?;
+    read.report_warnings(&jsonl_path);
+    let mut bench_records = read.records;
+    policy.check_all(&bench_records)?;
+    if let Some(arch) = arch {
+        bench_records.retain(|r| arch_matches(&r.env, arch));
+        eprintln!("Kept {} record(s) for arch {arch}", bench_records.len());
+    }
+
+    // Build the index (this also assigns detail slugs)
+    let records = build_index_from_records(&bench_records);
     eprintln!("Derived {} index record(s)", records.len());
 
     // Ensure output directory exists
@@ -44,6 +84,29 @@ pub fn build(jsonl_path: PathBuf, out_dir: PathBuf) -> BenchResult<()> {
     write_index_json(&records, &json_path)?;
     eprintln!("Wrote index.json to: {}", json_path.display());
 
+    // SLOs per circuit, keyed by index metric
+    let slos: BTreeMap<String, BTreeMap<String, f64>> = records
+        .iter()
+        .map(|r| r.circuit_name.as_str())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|name| {
+            (
+                name.to_string(),
+                index_slos(&circuit_budgets(budgets, name)),
+            )
+        })
+        .filter(|(_, slos)| !slos.is_empty())
+        .collect();
+
+    // Write monthly chunks and downsampled series for the HTML viewer
+    let manifest = write_chunked_index(&records, &out_dir, DEFAULT_SERIES_POINTS, &slos)?;
+    eprintln!(
+        "Wrote {} index chunk(s) and series.json to: {}",
+        manifest.chunks.len(),
+        out_dir.display()
+    );
+
     // Write index.html
     let html_path = out_dir.join("index.html");
     write_history_html(&html_path)?;
@@ -56,13 +119,10 @@ pub fn build(jsonl_path: PathBuf, out_dir: PathBuf) -> BenchResult<()> {
             .map_err(|e| BenchError::Message(format!("failed to create runs directory: {e}")))?;
     }
 
-    // Read the original BenchRecords to get full data for detail pages
-    let reader = JsonlWriter::new(&jsonl_path);
-    let mut bench_records = reader.read_all()?;
     bench_records.sort_by(|a, b| a.record_id.cmp(&b.record_id));
 
     // Build a deterministically ordered map from record_id to BenchRecord for lookup
-    let record_map: BTreeMap<&str, &crate::core::schema::BenchRecord> = bench_records
+    let record_map: BTreeMap<&str, &BenchRecord> = bench_records
         .iter()
         .map(|r| (r.record_id.as_str(), r))
         .collect();
@@ -93,10 +153,166 @@ pub fn build(jsonl_path: PathBuf, out_dir: PathBuf) -> BenchResult<()> {
         runs_dir.display()
     );
 
+    // Generate per-circuit trend pages (index records are already oldest first)
+    let mut by_circuit: BTreeMap<&str, (&str, Vec<&RunIndexRecordV1>)> = BTreeMap::new();
+    for record in &records {
+        if let Some(href) = record.circuit_href.as_deref() {
+            by_circuit
+                .entry(href)
+                .or_insert_with(|| (record.circuit_name.as_str(), Vec::new()))
+                .1
+                .push(record);
+        }
+    }
+    let no_slos = BTreeMap::new();
+    for (href, (circuit_name, circuit_records)) in &by_circuit {
+        write_circuit_html(
+            circuit_name,
+            circuit_records,
+            slos.get(*circuit_name).unwrap_or(&no_slos),
+            &out_dir.join(href),
+        )?;
+    }
+    eprintln!(
+        "Wrote {} circuit page(s) to: {}",
+        by_circuit.len(),
+        out_dir.join("circuits").display()
+    );
+
+    // Generate diff pages against the previous run of the same circuit and backend
+    let mut diff_count = 0;
+    for (i, prev) in previous_runs(&records).into_iter().enumerate() {
+        let Some(prev) = prev else { continue };
+        let (a_index, b_index) = (&records[prev], &records[i]);
+        if let (Some(href), Some(a_slug), Some(b_slug), Some(a), Some(b)) = (
+            b_index.diff_href.as_deref(),
+            a_index.detail_slug.as_deref(),
+            b_index.detail_slug.as_deref(),
+            record_map.get(a_index.record_id.as_str()),
+            record_map.get(b_index.record_id.as_str()),
+        ) {
+            let report = diff_report(a, a_slug, b, b_slug, DEFAULT_THRESHOLD)?;
+            write_run_diff_html(
+                &DiffRun {
+                    record: a,
+                    slug: a_slug,
+                },
+                &DiffRun {
+                    record: b,
+                    slug: b_slug,
+                },
+                &report,
+                "../runs/",
+                &out_dir.join(href),
+            )?;
+            diff_count += 1;
+        }
+    }
+    eprintln!(
+        "Wrote {} diff page(s) to: {}",
+        diff_count,
+        out_dir.join("diffs").display()
+    );
+
     eprintln!("History build complete.");
     Ok(())
 }
 
+/// Compare run `b` against run `a` as a single-circuit RegressionReport.
+///
+/// The runs need not share a circuit: `b` is compared under `a`'s circuit name.
+pub fn diff_report(
+    a: &BenchRecord,
+    a_ref: &str,
+    b: &BenchRecord,
+    b_ref: &str,
+    threshold: f64,
+) -> BenchResult<RegressionReport> {
+    let mut b = b.clone();
+    b.circuit_name = a.circuit_name.clone();
+    let result = compare_cmd::compare_records(
+        vec![a.clone()],
+        vec![b],
+        a_ref,
+        b_ref,
+        threshold,
+        &BTreeMap::new(),
+        &BTreeMap::new(),
+        None,
+    )?;
+    Ok(compare_cmd::to_regression_report(&result))
+}
+
+/// Run the `history diff` command.
+///
+/// Renders a static diff page of two runs to `<out>/diffs/<a>-vs-<b>.html`,
+/// alongside the pages written by `history build` so its run links resolve.
+/// Runs are named by detail slug (e.g. "run_000123") or record_id.
+///
+/// # Arguments
+/// * `jsonl_path` - Path to input JSONL file
+/// * `run_a` - Baseline run
+/// * `run_b` - Run compared against `run_a`
+/// * `out_dir` - History output directory
+/// * `threshold` - Regression threshold percentage for the metric table
+/// * `policy` - Which signed/unsigned records to accept
+pub fn diff(
+    jsonl_path: PathBuf,
+    run_a: &str,
+    run_b: &str,
+    out_dir: PathBuf,
+    threshold: f64,
+    policy: &SignaturePolicy,
+) -> BenchResult<()> {
+    if !jsonl_path.exists() {
+        return Err(BenchError::Message(format!(
+            "JSONL file not found: {}",
+            jsonl_path.display()
+        )));
+    }
+
+    // Same lenient reading as `history build`, so every run it rendered can
+    // be diffed
+    let read = read_untrusted(&jsonl_path, &ParseLimits::default())?;
+    read.report_warnings(&jsonl_path);
+    let bench_records = read.records;
+    policy.check_all(&bench_records)?;
+    let records = build_index_from_records(&bench_records);
+
+    let resolve = |run: &str| -> BenchResult<(&BenchRecord, &str)> {
+        let index_record = records
+            .iter()
+            .find(|r| r.detail_slug.as_deref() == Some(run))
+            .or_else(|| records.iter().find(|r| r.record_id == run))
+            .ok_or_else(|| BenchError::Message(format!("run not found: {run}")))?;
+        let record = bench_records
+            .iter()
+            .find(|r| r.record_id == index_record.record_id)
+            .ok_or_else(|| BenchError::Message(format!("run not found: {run}")))?;
+        Ok((record, index_record.detail_slug.as_deref().unwrap_or(run)))
+    };
+    let (a, a_slug) = resolve(run_a)?;
+    let (b, b_slug) = resolve(run_b)?;
+
+    let report = diff_report(a, a_slug, b, b_slug, threshold)?;
+    let path = out_dir.join(make_diff_href(a_slug, b_slug));
+    write_run_diff_html(
+        &DiffRun {
+            record: a,
+            slug: a_slug,
+        },
+        &DiffRun {
+            record: b,
+            slug: b_slug,
+        },
+        &report,
+        "../runs/",
+        &path,
+    )?;
+    eprintln!("Wrote diff page to: {}", path.display());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,7 +354,13 @@ mod tests {
             .unwrap();
 
         // Run build
-        let result = build(jsonl_path, out_dir.clone());
+        let result = build(
+            jsonl_path,
+            out_dir.clone(),
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        );
         assert!(result.is_ok(), "Build should succeed: {:?}", result.err());
 
         // Verify outputs exist
@@ -195,6 +417,15 @@ mod tests {
             "Detail page should have no JavaScript"
         );
 
+        // Verify the chunked index covers every record
+        let manifest_json = std::fs::read_to_string(out_dir.join("index-manifest.json")).unwrap();
+        let manifest: crate::history::IndexManifestV1 =
+            serde_json::from_str(&manifest_json).expect("manifest should be valid JSON");
+        assert_eq!(manifest.total_records, 2);
+        assert_eq!(manifest.chunks.len(), 1);
+        assert!(out_dir.join(&manifest.chunks[0].href).exists());
+        assert!(out_dir.join(&manifest.series_href).exists());
+
         // Verify index.html has expected content
         let html_content = std::fs::read_to_string(out_dir.join("index.html")).unwrap();
         assert!(html_content.contains("noir-bench History"));
@@ -207,11 +438,58 @@ mod tests {
         let jsonl_path = temp.path().join("nonexistent.jsonl");
         let out_dir = temp.path().join("out");
 
-        let result = build(jsonl_path, out_dir);
+        let result = build(
+            jsonl_path,
+            out_dir,
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    #[test]
+    fn test_build_rejects_unsigned_when_required() {
+        let temp = TempDir::new().unwrap();
+        let jsonl_path = temp.path().join("input.jsonl");
+        let out_dir = temp.path().join("out");
+        let key = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+
+        JsonlWriter::new(&jsonl_path)
+            .with_signing_key(key.clone())
+            .append(&make_test_record("signed", "2024-01-15T12:00:00Z"))
+            .unwrap();
+        let policy = SignaturePolicy {
+            trusted_keys: [crate::core::signing::public_key_hex(&key)].into(),
+            require_signed: true,
+        };
+        assert!(
+            build(
+                jsonl_path.clone(),
+                out_dir.clone(),
+                &policy,
+                None,
+                &Budgets::new()
+            )
+            .is_ok()
+        );
+
+        JsonlWriter::new(&jsonl_path)
+            .append(&make_test_record("unsigned", "2024-01-15T13:00:00Z"))
+            .unwrap();
+        let err = build(
+            jsonl_path,
+            temp.path().join("out2"),
+            &policy,
+            None,
+            &Budgets::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not signed"), "{err}");
+        assert!(!temp.path().join("out2").exists());
+    }
+
     #[test]
     fn test_build_deterministic_output() {
         let temp = TempDir::new().unwrap();
@@ -227,8 +505,22 @@ mod tests {
         let out1 = temp.path().join("out1");
         let out2 = temp.path().join("out2");
 
-        build(jsonl_path.clone(), out1.clone()).unwrap();
-        build(jsonl_path, out2.clone()).unwrap();
+        build(
+            jsonl_path.clone(),
+            out1.clone(),
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        )
+        .unwrap();
+        build(
+            jsonl_path,
+            out2.clone(),
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        )
+        .unwrap();
 
         // Compare outputs - all must be byte-for-byte identical
         let json1 = std::fs::read_to_string(out1.join("index.json")).unwrap();
@@ -259,7 +551,14 @@ mod tests {
         writer.append(&record).unwrap();
 
         // Build
-        build(jsonl_path, out_dir.clone()).unwrap();
+        build(
+            jsonl_path,
+            out_dir.clone(),
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        )
+        .unwrap();
 
         // Verify detail page escapes dangerous strings
         let detail = std::fs::read_to_string(out_dir.join("runs/run_000001.html")).unwrap();
@@ -298,7 +597,14 @@ mod tests {
             .unwrap();
 
         // Build
-        build(jsonl_path, out_dir.clone()).unwrap();
+        build(
+            jsonl_path,
+            out_dir.clone(),
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        )
+        .unwrap();
 
         // Read index.json to get detail_href values
         let json_content = std::fs::read_to_string(out_dir.join("index.json")).unwrap();
@@ -317,6 +623,21 @@ mod tests {
             }
         }
 
+        // Verify each circuit_href points to an existing file
+        for record in &records {
+            let href = record.circuit_href.as_ref().expect("circuit_href assigned");
+            assert!(
+                out_dir.join(href).exists(),
+                "circuit_href '{}' should point to existing file",
+                href
+            );
+        }
+        // Distinct circuits have no previous run to diff against
+        assert!(records.iter().all(|r| r.diff_href.is_none()));
+        let circuit1 = std::fs::read_to_string(out_dir.join("circuits/circuit1.html")).unwrap();
+        assert!(circuit1.contains("runs/run_000001.html"));
+        assert!(!circuit1.contains("<script"));
+
         // Verify detail pages link back to index
         let detail1 = std::fs::read_to_string(out_dir.join("runs/run_000001.html")).unwrap();
         assert!(
@@ -324,4 +645,85 @@ mod tests {
             "Detail page should link back to ../index.html"
         );
     }
+
+    #[test]
+    fn test_build_writes_diff_pages() {
+        let temp = TempDir::new().unwrap();
+        let jsonl_path = temp.path().join("input.jsonl");
+        let out_dir = temp.path().join("out");
+
+        let writer = JsonlWriter::new(&jsonl_path);
+        writer
+            .append(&make_test_record("circuit", "2024-01-15T12:00:00Z"))
+            .unwrap();
+        let mut slower = make_test_record("circuit", "2024-01-16T12:00:00Z");
+        slower.prove_stats = Some(TimingStat::from_samples(&[200.0, 210.0, 220.0]));
+        writer.append(&slower).unwrap();
+
+        build(
+            jsonl_path,
+            out_dir.clone(),
+            &SignaturePolicy::default(),
+            None,
+            &Budgets::new(),
+        )
+        .unwrap();
+
+        let json_content = std::fs::read_to_string(out_dir.join("index.json")).unwrap();
+        let records: Vec<crate::history::RunIndexRecordV1> =
+            serde_json::from_str(&json_content).unwrap();
+        let href = records[1].diff_href.as_ref().expect("diff_href assigned");
+        assert_eq!(href, "diffs/run_000001-vs-run_000002.html");
+
+        let diff = std::fs::read_to_string(out_dir.join(href)).unwrap();
+        assert!(diff.contains("href=\"../runs/run_000001.html\""));
+        assert!(diff.contains("<td class=\"mono\">prove_ms</td>"));
+        assert!(diff.contains("class=\"status-cell exceeded\""));
+        assert!(!diff.contains("<script"));
+    }
+
+    #[test]
+    fn test_diff_resolves_slugs_and_record_ids() {
+        let temp = TempDir::new().unwrap();
+        let jsonl_path = temp.path().join("input.jsonl");
+        let out_dir = temp.path().join("out");
+
+        let writer = JsonlWriter::new(&jsonl_path);
+        writer
+            .append(&make_test_record("circuit1", "2024-01-15T12:00:00Z"))
+            .unwrap();
+        // A bad line is skipped, as by `history build`
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&jsonl_path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"{not json\n").unwrap();
+        let other = make_test_record("circuit2", "2024-01-15T13:00:00Z");
+        writer.append(&other).unwrap();
+
+        let policy = SignaturePolicy::default();
+        diff(
+            jsonl_path.clone(),
+            "run_000001",
+            &other.record_id,
+            out_dir.clone(),
+            DEFAULT_THRESHOLD,
+            &policy,
+        )
+        .unwrap();
+        let html =
+            std::fs::read_to_string(out_dir.join("diffs/run_000001-vs-run_000002.html")).unwrap();
+        assert!(html.contains("<td>Circuit</td><td>circuit1</td><td>circuit2</td>"));
+
+        let err = diff(
+            jsonl_path,
+            "run_000001",
+            "run_999999",
+            out_dir,
+            DEFAULT_THRESHOLD,
+            &policy,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("run not found"), "{err}");
+    }
 }
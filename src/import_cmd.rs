@@ -0,0 +1,114 @@
+//! CLI command handler for `import`.
+//!
+//! Converts legacy per-command JSON reports (`exec`/`prove`/`gates`/`verify`
+//! `--json` output) into BenchRecords and appends them to a JSONL archive.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::core::schema::{BenchRecord, upgrade_value};
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Convert one report file into a BenchRecord.
+pub fn convert_report_file(path: &Path) -> BenchResult<BenchRecord> {
+    let s = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+    let value: Value = serde_json::from_str(&s)
+        .map_err(|e| BenchError::Message(format!("invalid JSON in {}: {e}", path.display())))?;
+    let upgraded = upgrade_value(value)
+        .map_err(|e| BenchError::Message(format!("{}: {e}", path.display())))?;
+    serde_json::from_value(upgraded)
+        .map_err(|e| BenchError::Message(format!("invalid record in {}: {e}", path.display())))
+}
+
+/// Import report files into `jsonl_path`.
+///
+/// Records whose id is already present in the archive are skipped, so
+/// importing the same reports twice does not create duplicates.
+/// Returns `(imported, skipped)`.
+pub fn import_reports(reports: &[PathBuf], jsonl_path: &Path) -> BenchResult<(usize, usize)> {
+    let writer = JsonlWriter::new(jsonl_path);
+    let mut known: BTreeSet<String> = if writer.exists() {
+        writer
+            .read_all()?
+            .into_iter()
+            .map(|r| r.record_id)
+            .collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    // Convert everything first so a bad file doesn't leave a partial import
+    let records = reports
+        .iter()
+        .map(|p| convert_report_file(p))
+        .collect::<BenchResult<Vec<_>>>()?;
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    for record in records {
+        if !known.insert(record.record_id.clone()) {
+            skipped += 1;
+            continue;
+        }
+        writer.append(&record)?;
+        imported += 1;
+    }
+    Ok((imported, skipped))
+}
+
+/// Run the `import` command.
+pub fn run(reports: Vec<PathBuf>, jsonl: PathBuf) -> BenchResult<()> {
+    if reports.is_empty() {
+        return Err(BenchError::Message(
+            "import requires at least one report file".into(),
+        ));
+    }
+    let (imported, skipped) = import_reports(&reports, &jsonl)?;
+    eprintln!(
+        "Imported {} record(s) into {} ({} already present)",
+        imported,
+        jsonl.display(),
+        skipped
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROVE_REPORT: &str = r#"{"name":"merkle","timestamp":"2025-06-01T00:00:00Z","noir_version":"1.0.0","artifact_path":"target/merkle.json","cli_args":[],"artifact_sha256":null,"inputs_sha256":null,"prove_time_ms":120,"witness_gen_time_ms":null,"backend_prove_time_ms":null,"peak_memory_bytes":null,"proof_size_bytes":2048,"proving_key_size_bytes":null,"verification_key_size_bytes":null,"gate_count":5000,"backend":{"name":"bb","version":null},"system":null,"iterations":null}"#;
+
+    #[test]
+    fn test_import_reports_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("prove.json");
+        std::fs::write(&report, PROVE_REPORT).unwrap();
+        let jsonl = dir.path().join("history.jsonl");
+
+        let (imported, skipped) = import_reports(&[report.clone()], &jsonl).unwrap();
+        assert_eq!((imported, skipped), (1, 0));
+        let (imported, skipped) = import_reports(&[report], &jsonl).unwrap();
+        assert_eq!((imported, skipped), (0, 1));
+
+        let records = JsonlWriter::new(&jsonl).read_all().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].circuit_name, "merkle");
+        assert_eq!(records[0].total_gates, Some(5000));
+    }
+
+    #[test]
+    fn test_import_reports_rejects_unknown_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let bad = dir.path().join("bad.json");
+        std::fs::write(&bad, r#"{"hello":"world"}"#).unwrap();
+        let jsonl = dir.path().join("history.jsonl");
+
+        assert!(import_reports(&[bad], &jsonl).is_err());
+        assert!(!jsonl.exists());
+    }
+}
@@ -1,19 +1,48 @@
+pub mod abtest_cmd;
+pub mod attest_cmd;
 pub mod backend;
+pub mod baseline_cmd;
 pub mod bench;
+pub mod calldata_cmd;
+pub mod changelog_cmd;
+pub mod check_cmd;
 pub mod ci_cmd;
+pub mod circuit_cmd;
 pub mod compare_cmd;
+pub mod compile_cmd;
 pub mod core;
+pub mod corpus_cmd;
+pub mod daemon_cmd;
+pub mod e2e_cmd;
 pub mod engine;
+pub mod estimate_cmd;
+#[cfg(feature = "revm")]
+pub mod evm_revm;
 pub mod evm_verify_cmd;
 pub mod exec_cmd;
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
 pub mod gates_cmd;
+pub mod harness;
 pub mod history;
 pub mod history_cmd;
+pub mod import_cmd;
 pub mod logging;
+pub mod model_cmd;
+pub mod pipeline_cmd;
+pub mod primitives_cmd;
+pub mod proof_cmd;
 pub mod prove_cmd;
 pub mod report;
+pub mod report_cmd;
+pub mod reproduce_cmd;
+pub mod rotate_cmd;
+pub mod starknet_verify_cmd;
 pub mod storage;
 pub mod suite_cmd;
+pub mod svm_verify_cmd;
+pub mod upgrade_cmd;
+pub mod verifier_template;
 pub mod verify_cmd;
 
 // Re-export core types for convenience
@@ -48,10 +77,31 @@ use thiserror::Error;
 pub enum BenchError {
     #[error("{0}")]
     Message(String),
+    /// An external command failed; its full output was kept in `log`
+    #[error("{message}")]
+    CommandFailed {
+        message: String,
+        log: Option<PathBuf>,
+    },
+    /// The circuit rejected its inputs: an assertion or constraint failed
+    /// while generating the witness. `--expect-failure` runs expect this
+    /// and nothing else.
+    #[error("{0}")]
+    Unsatisfied(String),
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
 }
 
+impl BenchError {
+    /// Log file holding the full output of the failed command, if kept.
+    pub fn log_path(&self) -> Option<&std::path::Path> {
+        match self {
+            BenchError::CommandFailed { log, .. } => log.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 pub type BenchResult<T> = Result<T, BenchError>;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -61,6 +111,8 @@ pub struct SystemInfo {
     pub cpu_cores_physical: Option<usize>,
     pub total_ram_bytes: Option<u64>,
     pub os: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -95,6 +147,21 @@ pub struct ExecReport {
     pub flamegraph_svg: Option<PathBuf>,
     pub system: Option<SystemInfo>,
     pub iterations: Option<IterationStats>,
+    /// Split of the last iteration between ACIR, Brillig and foreign calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec_split: Option<core::ExecSplit>,
+    /// Return value of the last iteration, when the circuit returns one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_value: Option<String>,
+    /// Whether the return value matched `--expect-return` / `--expect-file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_ok: Option<bool>,
+    /// `expected_failure` when the run was meant to fail and did (`--expect-failure`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<core::RunStatus>,
+    /// Error the expected failure was detected by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -118,6 +185,38 @@ pub struct ProveReport {
     pub backend: BackendInfo,
     pub system: Option<SystemInfo>,
     pub iterations: Option<IterationStats>,
+    /// Oracle resolver round-trip time during witness generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oracle_resolver_ms: Option<f64>,
+    /// One-time backend setup kept out of `prove_time_ms` (`--amortize-setup`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_setup_ms: Option<u128>,
+    /// How `prove_time_ms` was measured; absent for raw timings
+    /// (`amortized_setup` with `--amortize-setup`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prove_mode: Option<String>,
+    /// Per-phase backend proving times in ms (`--backend-verbose`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_phases: Option<std::collections::BTreeMap<String, f64>>,
+    /// vk generation kept out of `prove_time_ms`, summed over runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_gen_time_ms: Option<u128>,
+    /// Whether runs reused a vk generated by an earlier run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_cached: Option<bool>,
+    /// Key cache lookup of the first run that needed keys (`--key-cache`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_cache: Option<core::KeyCacheStatus>,
+    /// Metrics scraped from a template backend's output (`--custom-metric`),
+    /// averaged over measured runs
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub custom_metrics: std::collections::BTreeMap<String, f64>,
+    /// `expected_failure` when the run was meant to fail and did (`--expect-failure`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<core::RunStatus>,
+    /// Error the expected failure was detected by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,6 +239,39 @@ pub struct GatesReport {
     pub per_opcode_percent: Option<Vec<(String, f64)>>,
     pub backend: BackendInfo,
     pub system: Option<SystemInfo>,
+    /// Mean gate analysis time; absent when the result came from the gate cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gates_time_ms: Option<u128>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<IterationStats>,
+    /// Gates nested by ACIR call stack (when the artifact has debug info)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_sites: Option<Vec<GatesCallSite>>,
+    /// Gates aggregated per function (when the artifact has debug info)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_function: Option<Vec<GatesFunctionTotal>>,
+}
+
+/// Gates attributed to one frame of the ACIR call stack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatesCallSite {
+    /// Source label, "file:line:col::code"
+    pub label: String,
+    /// Gates of all opcodes at or below this frame
+    pub gates: u64,
+    /// Gates of opcodes whose innermost frame is this one
+    pub self_gates: u64,
+    pub opcodes: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<GatesCallSite>,
+}
+
+/// Gates of the opcodes generated inside one function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatesFunctionTotal {
+    pub function: String,
+    pub gates: u64,
+    pub opcodes: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,6 +296,61 @@ pub struct EvmVerifyReport {
     pub system: Option<SystemInfo>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarknetVerifyReport {
+    #[serde(flatten)]
+    pub meta: CommonMeta,
+    /// Cairo VM steps
+    pub steps: Option<u64>,
+    pub l1_gas: Option<u64>,
+    pub l1_data_gas: Option<u64>,
+    pub l2_gas: Option<u64>,
+    /// Estimated overall fee, in the fee token's smallest unit
+    pub estimated_fee: Option<u128>,
+    pub backend: BackendInfo,
+    pub system: Option<SystemInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SvmVerifyReport {
+    #[serde(flatten)]
+    pub meta: CommonMeta,
+    /// Compute units consumed by the verifier invocation
+    pub compute_units: u64,
+    /// Compute unit limit of that invocation
+    pub compute_unit_limit: Option<u64>,
+    pub program_id: Option<String>,
+    pub backend: BackendInfo,
+    pub system: Option<SystemInfo>,
+}
+
+/// Gas for verifying `proofs` proofs in one transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvmBatchPoint {
+    pub proofs: u64,
+    pub gas_used: u128,
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
+    pub gas_per_proof: f64,
+    /// Extra gas per proof over the previous (smaller) batch
+    #[serde(serialize_with = "crate::core::rounding::serialize_opt")]
+    pub marginal_gas: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvmBatchReport {
+    #[serde(flatten)]
+    pub meta: CommonMeta,
+    pub points: Vec<EvmBatchPoint>,
+    /// Fixed gas per transaction, from a linear fit over the points
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
+    pub base_gas: f64,
+    /// Gas per extra proof, from the same fit
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
+    pub marginal_gas_per_proof: f64,
+    pub backend: BackendInfo,
+    pub system: Option<SystemInfo>,
+}
+
 // Shared helpers
 pub fn collect_system_info() -> SystemInfo {
     use sysinfo::System;
@@ -180,6 +367,7 @@ pub fn collect_system_info() -> SystemInfo {
         cpu_cores_physical,
         total_ram_bytes,
         os,
+        arch: Some(std::env::consts::ARCH.to_string()),
     }
 }
 
@@ -213,24 +401,51 @@ pub fn compute_iteration_stats(
         .sum::<f64>()
         / len;
     let stddev = var.sqrt();
+    // Absurd timings can overflow the sums; leave the stats out rather than
+    // report NaN or infinity
     IterationStats {
         iterations,
         warmup,
         times_ms,
-        avg_ms: Some(avg),
+        avg_ms: Some(avg).filter(|v| v.is_finite()),
         min_ms: Some(min),
         max_ms: Some(max),
-        stddev_ms: Some(stddev),
+        stddev_ms: Some(stddev).filter(|v| v.is_finite()),
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Content hashes (sha256 hex) tying a record to the exact files it measured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Fingerprints {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub acir_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub inputs_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness_hash: Option<String>,
 }
 
+impl Fingerprints {
+    pub fn is_empty(&self) -> bool {
+        self.acir_hash.is_none()
+            && self.inputs_hash.is_none()
+            && self.proof_hash.is_none()
+            && self.vk_hash.is_none()
+            && self.witness_hash.is_none()
+    }
+}
+
+/// sha256 (lowercase hex) of `bytes`.
 pub fn sha256_hex(bytes: &[u8]) -> String {
-    use sha256::digest;
-    digest(bytes)
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// sha256 of a file's contents, streamed, or `None` if it can't be read.
+pub fn file_sha256(path: &std::path::Path) -> Option<String> {
+    core::sha256_file(path).ok()
 }
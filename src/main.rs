@@ -1,14 +1,19 @@
 #![forbid(unsafe_code)]
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
 
-use noir_bench::{CsvExporter, JsonlWriter};
+use noir_bench::core::{PercentileMethod, SignaturePolicy};
+use noir_bench::report::{DisplaySettings, reporter};
+use noir_bench::storage::{OutDir, Retention, out_dir};
+use noir_bench::{BenchError, CsvExporter, JsonlWriter};
 use noir_bench::{
-    bench, ci_cmd, compare_cmd, evm_verify_cmd, exec_cmd, gates_cmd, history_cmd, prove_cmd,
-    suite_cmd, verify_cmd,
+    abtest_cmd, attest_cmd, baseline_cmd, bench, calldata_cmd, changelog_cmd, check_cmd, ci_cmd,
+    circuit_cmd, compare_cmd, compile_cmd, corpus_cmd, daemon_cmd, e2e_cmd, estimate_cmd,
+    evm_verify_cmd, exec_cmd, gates_cmd, history_cmd, import_cmd, model_cmd, pipeline_cmd,
+    primitives_cmd, proof_cmd, prove_cmd, report_cmd, reproduce_cmd, rotate_cmd,
+    starknet_verify_cmd, suite_cmd, svm_verify_cmd, upgrade_cmd, verify_cmd,
 };
-use serde_json::Value as JsonValue;
 
 #[derive(Parser, Debug)]
 #[command(name = "noir-bench")]
@@ -20,12 +25,39 @@ struct Cli {
 
     #[command(subcommand)]
     command: Commands,
-    /// Export results to CSV (where applicable)
+    /// Export results to CSV (where applicable; same as --reporter csv=PATH)
     #[arg(long)]
     csv: Option<std::path::PathBuf>,
-    /// Export results to Markdown (where applicable)
+    /// Export results to Markdown (where applicable; same as --reporter md=PATH)
     #[arg(long)]
     md: Option<std::path::PathBuf>,
+    /// Write this invocation's outputs (reports, JSONL, logs, HTML) under this
+    /// directory by default, with a manifest.json listing them; path flags
+    /// still override
+    #[arg(long, global = true, value_name = "DIR")]
+    out_dir: Option<std::path::PathBuf>,
+    /// Report results to these outputs, as NAME[=TARGET]: csv, md, html, junit,
+    /// tap, prometheus, webhook, slack (comma-separated or repeated)
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        value_name = "NAME[=TARGET]"
+    )]
+    reporter: Vec<String>,
+    /// Sign written BenchRecords with this Ed25519 key (hex seed file)
+    #[arg(long, global = true)]
+    sign_key: Option<std::path::PathBuf>,
+    /// How p95 is computed from samples: linear (interpolated, default) or nearest-rank
+    #[arg(long, global = true)]
+    percentile_method: Option<String>,
+    /// Time zone of timestamps in markdown and HTML reports: utc (default) or local
+    #[arg(long, global = true, default_value = "utc")]
+    time_zone: String,
+    /// Show gate and opcode counts in full, grouped with this separator (e.g. ","),
+    /// instead of abbreviated as 18.33M
+    #[arg(long, global = true, value_name = "SEP")]
+    thousands_separator: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -58,6 +90,25 @@ enum Commands {
         /// Number of warmup iterations to run before measuring
         #[arg(long, default_value_t = 0)]
         warmup: usize,
+        /// Answer oracle calls from this mock file (TOML or JSON)
+        #[arg(long)]
+        oracle_mocks: Option<std::path::PathBuf>,
+        /// Delay every foreign call by this many milliseconds
+        #[arg(long)]
+        oracle_latency_ms: Option<f64>,
+        /// Resolve oracle calls with this JSON-RPC resolver URL
+        #[arg(long)]
+        oracle_resolver: Option<String>,
+        /// Fail unless the circuit returns this value (Prover.toml syntax, e.g. 42 or "[1, 2]")
+        #[arg(long, value_name = "VALUE", conflicts_with = "expect_file")]
+        expect_return: Option<String>,
+        /// Fail unless the circuit returns the `return` entry of this TOML/JSON file
+        #[arg(long, value_name = "FILE")]
+        expect_file: Option<std::path::PathBuf>,
+        /// The inputs are meant to fail: measure the time until execution fails,
+        /// and fail if it succeeds
+        #[arg(long, conflicts_with_all = ["expect_return", "expect_file", "flamegraph"])]
+        expect_failure: bool,
     },
 
     /// Report gates via backend provider
@@ -71,6 +122,9 @@ enum Commands {
         /// Path to backend binary (e.g., bb)
         #[arg(long)]
         backend_path: Option<std::path::PathBuf>,
+        /// Extra arg passed to the backend (repeatable; checked against known bb flags)
+        #[arg(long = "backend-arg", value_name = "ARG", allow_hyphen_values = true)]
+        backend_arg: Vec<String>,
         /// Additional args passed to backend after its gates command
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         backend_args: Vec<String>,
@@ -80,6 +134,55 @@ enum Commands {
         /// Write machine-readable JSON report to this file
         #[arg(long)]
         json: Option<std::path::PathBuf>,
+        /// Number of timed gate analysis runs
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Cache gate results by artifact hash and backend in this directory
+        /// (default .noir-bench/gates)
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = noir_bench::storage::DEFAULT_GATE_CACHE)]
+        cache: Option<std::path::PathBuf>,
+        /// Print the N functions and call sites with the most gates
+        #[arg(long)]
+        top: Option<usize>,
+    },
+
+    /// Describe a compiled circuit for reviews
+    Circuit {
+        #[command(subcommand)]
+        sub: CircuitCommands,
+    },
+
+    /// Benchmark `nargo compile` on a project, cold and warm
+    ///
+    /// Cold runs clear the project's target/ before each compile; warm runs
+    /// leave it in place; with --incremental, each run first edits one source
+    /// file. Each phase is recorded as separate stats in a BenchRecord.
+    Compile {
+        /// Noir project directory (containing Nargo.toml)
+        #[arg(long)]
+        project: std::path::PathBuf,
+        /// Circuit name for the record (default: package name from Nargo.toml)
+        #[arg(long)]
+        name: Option<String>,
+        /// Path to nargo binary (default: nargo from PATH)
+        #[arg(long)]
+        nargo_path: Option<std::path::PathBuf>,
+        /// Also measure incremental recompiles after editing this source file
+        /// (relative to --project; default src/main.nr). The file is restored afterwards
+        #[arg(long, num_args = 0..=1, default_missing_value = "src/main.nr")]
+        incremental: Option<std::path::PathBuf>,
+        /// Number of measured compiles per phase
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Number of warmup compiles per phase before measuring
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+        /// Write the BenchRecord as JSON to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+        /// Append the BenchRecord to this JSONL file
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
     },
 
     /// Benchmark proving via backend provider
@@ -96,12 +199,19 @@ enum Commands {
         /// Path to backend binary
         #[arg(long)]
         backend_path: Option<std::path::PathBuf>,
+        /// Extra arg passed to the backend (repeatable; checked against known bb flags)
+        #[arg(long = "backend-arg", value_name = "ARG", allow_hyphen_values = true)]
+        backend_arg: Vec<String>,
         /// Additional args passed to backend
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         backend_args: Vec<String>,
         /// Generic backend command template (placeholders: {artifact},{witness},{proof},{outdir})
         #[arg(long)]
         template: Option<String>,
+        /// Extra metric to scrape from the template backend's stdout (repeatable):
+        /// NAME for a `NAME=<number>` line, NAME=regex:PATTERN or NAME=json:/POINTER
+        #[arg(long = "custom-metric", value_name = "SPEC", requires = "template")]
+        custom_metric: Vec<String>,
         /// Timeout seconds
         #[arg(long, default_value_t = 0)]
         timeout: u64,
@@ -114,6 +224,139 @@ enum Commands {
         /// Write machine-readable JSON report to this file
         #[arg(long)]
         json: Option<std::path::PathBuf>,
+        /// Resolve oracle calls during witness generation with this JSON-RPC resolver URL
+        #[arg(long)]
+        oracle_resolver: Option<String>,
+        /// Reuse the vk across iterations and subtract an estimate of bb's
+        /// process startup from each prove time (SRS loading stays in it)
+        #[arg(long, alias = "persistent-backend")]
+        amortize_setup: bool,
+        /// Run bb verbosely and record its per-phase proving times
+        #[arg(long)]
+        backend_verbose: bool,
+        /// Cache generated vks in this directory (default .noir-bench/keys) and
+        /// reuse them in later runs instead of generating them again
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = noir_bench::storage::DEFAULT_KEY_CACHE)]
+        key_cache: Option<std::path::PathBuf>,
+        /// Regenerate vks instead of loading them from --key-cache
+        #[arg(long, requires = "key_cache")]
+        cold_keys: bool,
+        /// The inputs are meant to fail: measure the time until proving fails,
+        /// and fail if it succeeds
+        #[arg(long)]
+        expect_failure: bool,
+    },
+
+    /// Build a cost table of black-box functions (sha256, keccak, ecdsa, ...)
+    ///
+    /// Generates one tiny circuit per primitive and input size, compiles it with
+    /// nargo, and measures gates (and proving with --prove) with bb. Writes
+    /// primitive_costs.json and primitive_costs.md to --out-dir (default:
+    /// out/primitives), next to the generated projects.
+    Primitives {
+        /// Only these primitives (comma-separated, e.g. sha256,keccak)
+        #[arg(long, value_delimiter = ',')]
+        only: Option<Vec<String>>,
+        /// Input sizes to use instead of each primitive's defaults (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        sizes: Option<Vec<u32>>,
+        /// Also prove each circuit (default: gate counts only)
+        #[arg(long)]
+        prove: bool,
+        /// Number of measured prove iterations (with --prove)
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Number of warmup prove iterations (with --prove)
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+        /// Path to nargo binary (default: nargo from PATH)
+        #[arg(long)]
+        nargo_path: Option<std::path::PathBuf>,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+        /// Append a BenchRecord per proved circuit to this JSONL file
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
+    },
+
+    /// Predict prove time and memory for a circuit without proving it
+    ///
+    /// Gets the gate count from bb and applies the backend's cost model (fitted
+    /// per backend version, or a built-in default), scaled to this machine's
+    /// calibration score. Prints the estimate with 95% error bars.
+    Estimate {
+        /// Path to program artifact (program.json)
+        #[arg(long)]
+        artifact: std::path::PathBuf,
+        /// Circuit name (default: artifact file stem)
+        #[arg(long)]
+        name: Option<String>,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+        /// Use this cost model file instead of looking one up in --models-dir
+        #[arg(long)]
+        model: Option<std::path::PathBuf>,
+        /// Directory of fitted cost models
+        #[arg(long, default_value = noir_bench::core::cost_model::DEFAULT_MODELS_DIR)]
+        models_dir: std::path::PathBuf,
+        /// Write the estimate as JSON to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+    },
+
+    /// Convert proofs between encodings
+    Proof {
+        #[command(subcommand)]
+        sub: ProofCommands,
+    },
+
+    /// Freeze and list named baseline snapshots, e.g. one per release
+    Baseline {
+        #[command(subcommand)]
+        sub: BaselineCommands,
+    },
+
+    /// Render a markdown performance changelog between two releases
+    ///
+    /// Each release is a named baseline from `baseline freeze` or, with
+    /// --jsonl, a git ref whose commit has runs in the history.
+    Changelog {
+        /// Older release: baseline name or git ref
+        #[arg(long)]
+        from: String,
+        /// Newer release: baseline name or git ref
+        #[arg(long)]
+        to: String,
+        /// History BenchRecords JSONL to look up git refs in
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
+        /// Directory of named baselines
+        #[arg(long, default_value = noir_bench::baseline_cmd::DEFAULT_BASELINES_DIR)]
+        baselines_dir: std::path::PathBuf,
+        /// Change percentage a metric must exceed to be listed (default: 10.0)
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Write the changelog to this file instead of stdout
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+
+    /// Fit and manage the cost models used by `estimate`
+    Model {
+        #[command(subcommand)]
+        sub: ModelCommands,
+    },
+
+    /// Run the bundled, versioned benchmark corpus
+    ///
+    /// The corpus is a fixed set of representative circuits (merkle proof,
+    /// ECDSA verification, keccak sponge, rollup state transition) shipped
+    /// inside the binary, so results are comparable across machines.
+    Corpus {
+        #[command(subcommand)]
+        sub: CorpusCommands,
     },
 
     /// Verify a proof via backend provider
@@ -124,16 +367,25 @@ enum Commands {
         /// Path to proof file
         #[arg(long)]
         proof: std::path::PathBuf,
+        /// Path to verification key (passed to bb as -k; hashed into the record)
+        #[arg(long)]
+        vk: Option<std::path::PathBuf>,
+        /// Public inputs file (passed to bb as -i; hashed into the record)
+        #[arg(long)]
+        public_inputs: Option<std::path::PathBuf>,
         /// Backend name (e.g., barretenberg)
         #[arg(long)]
         backend: Option<String>,
         /// Path to backend binary
         #[arg(long)]
         backend_path: Option<std::path::PathBuf>,
+        /// Extra arg passed to the backend (repeatable; checked against known bb flags)
+        #[arg(long = "backend-arg", value_name = "ARG", allow_hyphen_values = true)]
+        backend_arg: Vec<String>,
         /// Additional args passed to backend
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         backend_args: Vec<String>,
-        /// Generic backend command template (placeholders: {artifact},{proof})
+        /// Generic backend command template (placeholders: {artifact},{proof},{vk},{public_inputs})
         #[arg(long)]
         template: Option<String>,
         /// Number of measured iterations to run
@@ -142,15 +394,140 @@ enum Commands {
         /// Number of warmup iterations to run before measuring
         #[arg(long, default_value_t = 0)]
         warmup: usize,
-        /// Write machine-readable JSON report to this file
+        /// Flip a byte of the proof and time its rejection; fails if any run
+        /// accepts it
+        #[arg(long, conflicts_with = "store")]
+        expect_invalid: bool,
+        /// Verify this already tampered proof instead of flipping a byte of --proof
+        #[arg(long, requires = "expect_invalid")]
+        tampered_proof: Option<std::path::PathBuf>,
+        /// Write the BenchRecord as JSON to this file
         #[arg(long)]
         json: Option<std::path::PathBuf>,
+        /// Append the BenchRecord to this JSONL file
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
+        /// Keep the proof and vk in this content-addressed store (for `attest`)
+        #[arg(long)]
+        store: Option<std::path::PathBuf>,
+    },
+
+    /// Re-verify a record's stored proof against its stored verification key
+    ///
+    /// Looks the record up by id, fetches the proof and vk from the artifact
+    /// store by their fingerprint hashes, checks the hashes, and runs bb verify.
+    Attest {
+        /// Record id to attest
+        record_id: String,
+        /// JSONL archive containing the record
+        #[arg(long)]
+        jsonl: std::path::PathBuf,
+        /// Artifact store holding the proof and vk
+        #[arg(long, default_value = noir_bench::storage::DEFAULT_ARTIFACT_STORE)]
+        store: std::path::PathBuf,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+        /// Extra arg passed to the backend (repeatable; checked against known bb flags)
+        #[arg(long = "backend-arg", value_name = "ARG", allow_hyphen_values = true)]
+        backend_arg: Vec<String>,
+        /// Additional args passed to bb verify
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        backend_args: Vec<String>,
+    },
+
+    /// Re-run the benchmark behind a history record and compare against it
+    ///
+    /// Takes the artifact, inputs, params, iterations, timeout and bb options
+    /// from the record, checks the artifact and inputs against its hashes and
+    /// the installed nargo and bb against its versions, then prints the new
+    /// run side by side with the original.
+    Reproduce {
+        /// Record id to reproduce
+        record_id: String,
+        /// JSONL history containing the record
+        #[arg(long)]
+        jsonl: std::path::PathBuf,
+        /// Artifact to run instead of the record's circuit_path
+        #[arg(long)]
+        artifact: Option<std::path::PathBuf>,
+        /// Inputs to use instead of the Prover.toml found next to the artifact
+        #[arg(long, value_name = "Prover.toml")]
+        prover_toml: Option<std::path::PathBuf>,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+        /// Run even if hashes or tool versions differ from the record
+        #[arg(long)]
+        force: bool,
+        /// Append the reproduced record to this JSONL file
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+
+    /// A/B test two bb binaries on circuits from a bench config
+    ///
+    /// Runs a paired comparison (as compare --paired) per circuit: both binaries
+    /// prove the same witnesses interleaved, and a paired t-test decides which is
+    /// faster. Prints prove times, the difference with its 95% confidence interval
+    /// and a verdict per circuit; exits 1 if B is slower on any circuit.
+    Abtest {
+        /// Baseline bb binary (A)
+        #[arg(long)]
+        backend_a: std::path::PathBuf,
+        /// Candidate bb binary (B)
+        #[arg(long)]
+        backend_b: std::path::PathBuf,
+        /// Circuit from the bench config, by name or name-p<params> (repeatable;
+        /// default: all circuits)
+        #[arg(long)]
+        circuit: Vec<String>,
+        /// Bench config with the circuits (default bench-config.toml)
+        #[arg(long)]
+        config: Option<std::path::PathBuf>,
+        /// Extra arg passed to both binaries (repeatable)
+        #[arg(long = "backend-arg", value_name = "ARG", allow_hyphen_values = true)]
+        backend_arg: Vec<String>,
+        /// Measured pairs per circuit
+        #[arg(long, default_value_t = 10)]
+        pairs: usize,
+        /// Unmeasured warmup pairs per circuit
+        #[arg(long, default_value_t = 1)]
+        warmup: usize,
+        /// Significance level of the paired test
+        #[arg(long, default_value_t = noir_bench::compare_cmd::DEFAULT_PAIRED_ALPHA)]
+        alpha: f64,
+        /// Smallest difference in percent that counts as faster or slower
+        #[arg(long, default_value_t = 0.0)]
+        threshold: f64,
+        /// Output format: markdown or json
+        #[arg(long, default_value = "markdown")]
+        format: String,
+        /// Write the report as JSON to this file
+        #[arg(long)]
+        json_out: Option<std::path::PathBuf>,
+    },
+
+    /// Export a report to PDF for audit snapshots
+    ///
+    /// Prints an HTML report (compare/ci --html-out, suite --html) to PDF with
+    /// headless Chromium; a regression report JSON is rendered to HTML first.
+    Report {
+        /// HTML report, or regression report JSON
+        input: std::path::PathBuf,
+        /// PDF file to write
+        #[arg(long)]
+        pdf: std::path::PathBuf,
+        /// Chromium/Chrome binary (default: $NOIR_BENCH_CHROMIUM, then PATH)
+        #[arg(long)]
+        chromium: Option<std::path::PathBuf>,
     },
 
     /// Compare benchmark results and detect regressions
     ///
     /// Supports comparing single JSON reports or JSONL files containing multiple records.
     /// For CI usage, use --baseline-file and --target-file with JSONL files.
+    #[command(group = clap::ArgGroup::new("history_baseline").args(["baseline_file", "against"]))]
     Compare {
         /// Baseline JSON report (single file comparison)
         #[arg(long, conflicts_with = "baseline_file")]
@@ -164,10 +541,59 @@ enum Commands {
         /// Target JSONL file (multi-record comparison for CI)
         #[arg(long, conflicts_with = "contender")]
         target_file: Option<std::path::PathBuf>,
-        /// Regression threshold percentage (default: 10.0)
-        #[arg(long, default_value = "10.0")]
-        threshold: f64,
-        /// Output format: text, json, markdown
+        /// Compare --target-file against a named baseline from `baseline freeze`
+        #[arg(
+            long,
+            value_name = "NAME",
+            requires = "target_file",
+            conflicts_with_all = ["baseline", "baseline_file", "reference"]
+        )]
+        against: Option<String>,
+        /// Directory of named baselines
+        #[arg(long, default_value = noir_bench::baseline_cmd::DEFAULT_BASELINES_DIR)]
+        baselines_dir: std::path::PathBuf,
+        /// Compare --target-file corpus records against a reference dataset:
+        /// a URL or a JSON file
+        #[arg(long, requires = "target_file", conflicts_with_all = ["baseline", "baseline_file"])]
+        reference: Option<String>,
+        /// Reference machine id (default: closest calibration score)
+        #[arg(long, requires = "reference")]
+        reference_machine: Option<String>,
+        /// Regression threshold percentage (default: 10.0, or threshold_percent from --config)
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Use K standard deviations of the baseline samples as the threshold for
+        /// timing metrics (falls back to --threshold when the baseline has no spread)
+        #[arg(long, value_name = "K")]
+        noise_sigma: Option<f64>,
+        /// Treat --baseline-file as a history and compare against the median of
+        /// the last N runs of each circuit/backend
+        #[arg(long, value_name = "N", requires = "baseline_file")]
+        baseline_window: Option<usize>,
+        /// Only compare records measured on this CPU architecture (e.g. x86_64, aarch64)
+        #[arg(long, requires = "baseline_file")]
+        arch: Option<String>,
+        /// Compare records measured on different CPU architectures instead of refusing
+        #[arg(long)]
+        allow_cross_arch: bool,
+        /// Keep baseline records flagged as anomalies at history ingestion
+        /// (a named baseline leaves them out when frozen, unless frozen with
+        /// `baseline freeze --include-anomalies`)
+        #[arg(long, requires = "history_baseline")]
+        include_anomalies: bool,
+        /// Only compare these metrics, e.g. --metrics prove_ms,gates,proof_size,peak_rss
+        #[arg(long, value_delimiter = ',')]
+        metrics: Vec<String>,
+        /// Report these metrics without ever failing on them
+        #[arg(long, value_delimiter = ',')]
+        informational_metrics: Vec<String>,
+        /// bench-config.toml to read per-metric and per-circuit thresholds from ([ci])
+        #[arg(long)]
+        config: Option<std::path::PathBuf>,
+        /// Annotate the report, e.g. --annotate commit=<sha> pr=<url>
+        #[arg(long, num_args = 1.., value_name = "KEY=VALUE")]
+        annotate: Vec<String>,
+        /// Output format: text, json, markdown, junit, tap
         #[arg(long, default_value = "text")]
         format: String,
         /// Write RegressionReport JSON to this file
@@ -176,6 +602,42 @@ enum Commands {
         /// Write standalone HTML report to this file
         #[arg(long)]
         html_out: Option<std::path::PathBuf>,
+        /// Instead of comparing reports, prove --artifact with the baseline and
+        /// contender bb interleaved (A,B,A,B,...) in this session and compare
+        /// prove times with a paired t-test; --threshold defaults to 0 here
+        #[arg(
+            long,
+            requires_all = ["artifact", "contender_bb"],
+            conflicts_with_all = ["baseline", "contender", "baseline_file", "target_file", "reference"]
+        )]
+        paired: bool,
+        /// Compiled circuit to prove in --paired mode
+        #[arg(long, requires = "paired")]
+        artifact: Option<std::path::PathBuf>,
+        /// Prover.toml for --artifact (default: Prover.toml)
+        #[arg(long, requires = "paired")]
+        prover_toml: Option<std::path::PathBuf>,
+        /// Baseline bb binary in --paired mode
+        #[arg(long, default_value = "bb")]
+        baseline_bb: std::path::PathBuf,
+        /// Contender bb binary in --paired mode (may be the baseline's, with other args)
+        #[arg(long, requires = "paired")]
+        contender_bb: Option<std::path::PathBuf>,
+        /// Extra arg for the baseline bb (repeatable)
+        #[arg(long = "baseline-arg", value_name = "ARG", allow_hyphen_values = true)]
+        baseline_args: Vec<String>,
+        /// Extra arg for the contender bb (repeatable)
+        #[arg(long = "contender-arg", value_name = "ARG", allow_hyphen_values = true)]
+        contender_args: Vec<String>,
+        /// Measured pairs in --paired mode
+        #[arg(long, default_value_t = 10)]
+        pairs: usize,
+        /// Unmeasured warmup pairs in --paired mode
+        #[arg(long, default_value_t = 1)]
+        warmup: usize,
+        /// Significance level of the paired test
+        #[arg(long, default_value_t = noir_bench::compare_cmd::DEFAULT_PAIRED_ALPHA)]
+        alpha: f64,
     },
 
     /// Run a suite from YAML config
@@ -183,19 +645,127 @@ enum Commands {
         /// Path to suite YAML config
         #[arg(long)]
         config: std::path::PathBuf,
-        /// Write JSONL stream of results
-        #[arg(long)]
-        jsonl: Option<std::path::PathBuf>,
+        /// Stream results as JSONL to a file, tcp://host:port or an http(s):// endpoint
+        /// (repeatable)
+        #[arg(long, value_name = "PATH|URL")]
+        jsonl: Vec<String>,
         /// Write a summary JSON file
         #[arg(long)]
         summary: Option<std::path::PathBuf>,
+        /// Write a standalone HTML report (summary, per-circuit sections, failures)
+        #[arg(long)]
+        html: Option<std::path::PathBuf>,
+        /// BenchRecord JSONL of earlier runs to estimate the ETA from
+        #[arg(long)]
+        history: Option<std::path::PathBuf>,
+        /// Append progress events (circuit done, ETA from --history) as JSON
+        /// lines to this file
+        #[arg(long)]
+        progress: Option<std::path::PathBuf>,
+    },
+
+    /// Run the stages of a pipeline file (compile, gates, prove, evm-verify)
+    /// with each stage using what the earlier ones produced, and write one
+    /// consolidated record
+    Pipeline {
+        /// Pipeline YAML file
+        #[arg(long, default_value = pipeline_cmd::DEFAULT_CONFIG)]
+        config: std::path::PathBuf,
+        /// Write the record as JSON
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+        /// Append the record to a JSONL file
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
+    },
+
+    /// Time a dapp pipeline: witness with noir_js in node, then native bb prove
+    E2e {
+        /// Compiled circuit artifact (program.json)
+        #[arg(long)]
+        artifact: std::path::PathBuf,
+        /// Inputs for noir_js
+        #[arg(long, default_value = "Prover.toml")]
+        prover_toml: std::path::PathBuf,
+        /// Node project whose node_modules has @noir-lang/noir_js
+        #[arg(long, default_value = ".")]
+        node_project: std::path::PathBuf,
+        /// Path to node binary (default: node from PATH)
+        #[arg(long)]
+        node_bin: Option<std::path::PathBuf>,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+        /// Number of measured pipeline runs
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Number of warmup pipeline runs
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+        /// Write machine-readable JSON report to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+    },
+
+    /// Run a Cairo verifier and capture Starknet steps, gas and fee
+    StarknetVerify {
+        /// Scarb project directory containing the verifier + tests
+        #[arg(long, value_name = "DIR")]
+        project_dir: std::path::PathBuf,
+        /// Test name filter (passed to snforge, or {match} in --command)
+        #[arg(long, value_name = "pattern")]
+        r#match: Option<String>,
+        /// Command template to run instead of snforge, e.g. a devnet script;
+        /// placeholders: {project}, {match}
+        #[arg(long)]
+        command: Option<String>,
+        /// Write machine-readable JSON report to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+    },
+
+    /// Run a Solana verifier program and capture compute units consumed
+    SvmVerify {
+        /// Verifier program project with solana-program-test tests
+        #[arg(long, value_name = "DIR")]
+        project_dir: std::path::PathBuf,
+        /// Test name filter (passed to cargo test-sbf, or {match} in --command)
+        #[arg(long, value_name = "pattern")]
+        r#match: Option<String>,
+        /// Verifier program id (default: the largest invocation in the logs)
+        #[arg(long)]
+        program_id: Option<String>,
+        /// Command template to run instead of cargo test-sbf;
+        /// placeholders: {project}, {match}
+        #[arg(long)]
+        command: Option<String>,
+        /// Write machine-readable JSON report to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
     },
 
-    /// Run a Foundry/Anvil EVM verifier and capture gas usage
+    /// Run an EVM verifier (Foundry, Hardhat or in-process revm) and capture gas usage
     EvmVerify {
-        /// Path to Foundry project directory containing verifier + tests
-        #[arg(long, value_name = "foundry_dir")]
-        foundry_dir: std::path::PathBuf,
+        /// EVM toolchain
+        #[arg(long, value_enum, default_value_t)]
+        toolchain: evm_verify_cmd::EvmToolchainKind,
+        /// Foundry or Hardhat project directory containing verifier + tests
+        #[arg(long, alias = "foundry-dir", value_name = "DIR")]
+        project_dir: Option<std::path::PathBuf>,
+        /// Verifier creation bytecode for revm: hex file or forge/Hardhat artifact JSON
+        #[arg(long)]
+        bytecode: Option<std::path::PathBuf>,
+        /// Hex calldata of the verification call: sent by revm, passed to
+        /// Foundry and Hardhat tests in NOIR_BENCH_CALLDATA
+        #[arg(long, conflicts_with = "proof")]
+        calldata: Option<std::path::PathBuf>,
+        /// Proof file (raw, hex or fields), encoded as verify(bytes,bytes32[])
+        /// calldata like --calldata
+        #[arg(long)]
+        proof: Option<std::path::PathBuf>,
+        /// Public inputs file (raw, hex or fields; 32 bytes per input)
+        #[arg(long, requires = "proof")]
+        public_inputs: Option<std::path::PathBuf>,
         /// Optional Noir program artifact (program.json) to tag meta
         #[arg(long)]
         artifact: Option<std::path::PathBuf>,
@@ -214,6 +784,10 @@ enum Commands {
         /// Write machine-readable JSON report to this file
         #[arg(long)]
         json: Option<std::path::PathBuf>,
+        /// Measure batch verification for these proof counts, e.g. 1,2,4,8; the
+        /// count is passed in NOIR_BENCH_BATCH_SIZE and replaces {n} in --match
+        #[arg(long, value_delimiter = ',', value_name = "N,...", conflicts_with_all = ["calldata_bytes", "gas_per_second"])]
+        batch: Option<Vec<u64>>,
     },
 
     /// Export JSONL benchmark records to CSV format
@@ -231,7 +805,10 @@ enum Commands {
     /// Runs a subset of benchmarks, compares against a baseline, and outputs
     /// results suitable for CI environments. Returns exit code 1 if regressions
     /// are detected above the threshold.
+    #[command(args_conflicts_with_subcommands = true)]
     Ci {
+        #[command(subcommand)]
+        sub: Option<CiCommands>,
         /// Path to bench-config.toml
         #[arg(long)]
         config: Option<std::path::PathBuf>,
@@ -241,6 +818,10 @@ enum Commands {
         /// Baseline JSONL file for comparison
         #[arg(long)]
         baseline_file: Option<std::path::PathBuf>,
+        /// Treat --baseline-file as a history and compare against the median of
+        /// the last N runs of each circuit/backend
+        #[arg(long, value_name = "N")]
+        baseline_window: Option<usize>,
         /// Regression threshold percentage (default: 10.0)
         #[arg(long, default_value = "10.0")]
         threshold: f64,
@@ -250,41 +831,390 @@ enum Commands {
         /// Number of warmup iterations (default: 1)
         #[arg(long)]
         warmup: Option<usize>,
+        /// Run only shard i of n (e.g. 2/5); combine shards with `ci collect`
+        #[arg(long)]
+        shard: Option<String>,
         /// Output file for benchmark results JSONL
         #[arg(long)]
         output: Option<std::path::PathBuf>,
-        /// Output format: text, json, markdown (default: markdown)
+        /// Output format: text, json, markdown, junit, tap (default: markdown)
+        #[arg(long, default_value = "markdown")]
+        format: String,
+        /// Write RegressionReport JSON to this file
+        #[arg(long)]
+        json_out: Option<std::path::PathBuf>,
+        /// Write standalone HTML report to this file
+        #[arg(long)]
+        html_out: Option<std::path::PathBuf>,
+        /// Annotate records and reports, e.g. --annotate commit=<sha> pr=<url>
+        #[arg(long, num_args = 1.., value_name = "KEY=VALUE")]
+        annotate: Vec<String>,
+        /// Only compare these metrics, e.g. --metrics prove_ms,gates,proof_size,peak_rss
+        #[arg(long, value_delimiter = ',', global = true)]
+        metrics: Vec<String>,
+        /// Report these metrics without ever failing on them
+        #[arg(long, value_delimiter = ',', global = true)]
+        informational_metrics: Vec<String>,
+        /// Create a GitHub check run with per-circuit annotations (uses GITHUB_TOKEN,
+        /// GITHUB_REPOSITORY and GITHUB_SHA)
+        #[arg(long, global = true)]
+        github_check: bool,
+    },
+
+    /// Run the CI circuits on a cron schedule (UTC)
+    ///
+    /// Each run appends records to the history JSONL, rebuilds the history site,
+    /// and runs --notify with a markdown report when the run regressed against
+    /// the previous one.
+    Daemon {
+        /// Cron schedule, e.g. "0 3 * * *" for 03:00 UTC daily
+        #[arg(long)]
+        cron: String,
+        /// Path to bench-config.toml (circuits, iterations and thresholds from [ci])
+        #[arg(long, default_value = "bench-config.toml")]
+        config: std::path::PathBuf,
+        /// History JSONL to append records to
+        #[arg(long, default_value = "out/history.jsonl")]
+        history: std::path::PathBuf,
+        /// Output directory for the history site
+        #[arg(long, default_value = "out/site")]
+        site: std::path::PathBuf,
+        /// Directory for the previous run, used as the next run's baseline
+        #[arg(long, default_value = noir_bench::daemon_cmd::DEFAULT_STATE_DIR)]
+        state_dir: std::path::PathBuf,
+        /// Directory of fitted cost models, re-fitted as history grows
+        #[arg(long, default_value = noir_bench::core::cost_model::DEFAULT_MODELS_DIR)]
+        models_dir: std::path::PathBuf,
+        /// Command run on regressions; gets the markdown report on stdin and
+        /// NOIR_BENCH_REGRESSIONS in its environment
+        #[arg(long)]
+        notify: Option<String>,
+        /// Run once immediately instead of waiting for the schedule
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Analyze the on-chain cost of a proof's calldata
+    ///
+    /// Zero/nonzero bytes, calldata gas (EIP-2028 and the EIP-7623 floor), EIP-4844
+    /// blob gas and gzip compressibility.
+    Calldata {
+        /// Binary proof file
+        #[arg(long, required_unless_present = "calldata")]
+        proof: Option<std::path::PathBuf>,
+        /// Public inputs file, passed with the proof in the verify call
+        #[arg(long, requires = "proof")]
+        public_inputs: Option<std::path::PathBuf>,
+        /// Hex calldata of the verification call, instead of --proof
+        #[arg(long, conflicts_with = "proof")]
+        calldata: Option<std::path::PathBuf>,
+        /// Write the analysis as JSON to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+    },
+
+    /// Check the latest results against absolute budgets from [budgets]
+    ///
+    /// Fails with a table of violations when a circuit exceeds a budget.
+    Check {
+        /// Path to bench-config.toml with [budgets.<circuit>] tables
+        #[arg(long, default_value = "bench-config.toml")]
+        config: std::path::PathBuf,
+        /// JSONL file with BenchRecords to check (not needed for EVM budgets only)
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
+        /// Output format: text, markdown
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// List every budget, not just the violations
+        #[arg(long)]
+        all: bool,
+        /// Saved `evm-verify --json` report for a circuit's EVM budgets, instead
+        /// of running forge in its [[circuit]] path
+        #[arg(long, num_args = 1.., value_name = "CIRCUIT=FILE")]
+        evm_report: Vec<String>,
+        /// Path to forge binary for EVM budgets (defaults to forge on PATH)
+        #[arg(long)]
+        forge_bin: Option<std::path::PathBuf>,
+    },
+
+    /// Upgrade a JSONL archive to the current BenchRecord schema
+    ///
+    /// Accepts older schema versions and legacy `bench` JSONL lines.
+    Upgrade {
+        /// Input JSONL file
+        #[arg(long = "in")]
+        input: std::path::PathBuf,
+        /// Output JSONL file (may equal --in to upgrade in place)
+        #[arg(long)]
+        out: std::path::PathBuf,
+    },
+
+    /// Split a JSONL archive into one (compressed) file per month, written to
+    /// --out-dir as <stem>-YYYY-MM.jsonl[.gz|.zst]
+    Rotate {
+        /// Input JSONL file (.jsonl, .jsonl.gz or .jsonl.zst)
+        #[arg(long = "in")]
+        input: std::path::PathBuf,
+        /// Compression of the monthly files: gzip, zstd or none
+        #[arg(long, default_value = "gzip")]
+        compress: String,
+    },
+
+    /// Import legacy exec/prove/gates/verify JSON reports into a JSONL archive
+    ///
+    /// Converts each report into a BenchRecord so older data can be used by
+    /// history, compare, and trend analysis. Already-imported reports are skipped.
+    Import {
+        /// Legacy report JSON files (as written by --json)
+        #[arg(required = true)]
+        reports: Vec<std::path::PathBuf>,
+        /// JSONL archive to append the converted records to
+        #[arg(long)]
+        jsonl: std::path::PathBuf,
+    },
+
+    /// Build derived history artifacts from JSONL
+    ///
+    /// Reads canonical JSONL telemetry and produces:
+    /// - index.json: derived run index for querying
+    /// - index.html: single-file HTML viewer (fetches index.json at runtime)
+    /// - runs/*.html: per-run detail pages
+    /// - circuits/*.html: per-circuit trend pages (latest, best/worst, trend chart)
+    History {
+        #[command(subcommand)]
+        sub: HistoryCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CiCommands {
+    /// Merge shard result JSONLs and produce a single regression report
+    Collect {
+        /// Shard result JSONL files (the --output of each `ci --shard` run)
+        #[arg(required = true)]
+        shards: Vec<std::path::PathBuf>,
+        /// Path to bench-config.toml
+        #[arg(long)]
+        config: Option<std::path::PathBuf>,
+        /// Baseline JSONL file for comparison
+        #[arg(long)]
+        baseline_file: Option<std::path::PathBuf>,
+        /// Regression threshold percentage (default: 10.0)
+        #[arg(long, default_value = "10.0")]
+        threshold: f64,
+        /// Output file for the merged results JSONL
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+        /// Output format: text, json, markdown, junit, tap (default: markdown)
         #[arg(long, default_value = "markdown")]
         format: String,
         /// Write RegressionReport JSON to this file
         #[arg(long)]
-        json_out: Option<std::path::PathBuf>,
-        /// Write standalone HTML report to this file
+        json_out: Option<std::path::PathBuf>,
+        /// Write standalone HTML report to this file
+        #[arg(long)]
+        html_out: Option<std::path::PathBuf>,
+        /// Only accept signed records from these public keys (hex, one per line)
+        #[arg(long)]
+        trusted_keys: Option<std::path::PathBuf>,
+        /// Reject unsigned records (requires --trusted-keys)
+        #[arg(long)]
+        require_signed: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommands {
+    /// Build index.json and index.html from JSONL
+    Build {
+        /// Path to input JSONL file containing BenchRecords
+        #[arg(long)]
+        jsonl: std::path::PathBuf,
+        /// Output directory for index.json and index.html
+        #[arg(long)]
+        out: std::path::PathBuf,
+        /// Only accept signed records from these public keys (hex, one per line)
+        #[arg(long)]
+        trusted_keys: Option<std::path::PathBuf>,
+        /// Reject unsigned records (requires --trusted-keys)
+        #[arg(long)]
+        require_signed: bool,
+        /// Only include records measured on this CPU architecture (e.g. x86_64, aarch64)
+        #[arg(long)]
+        arch: Option<String>,
+        /// bench-config.toml whose [budgets] are drawn as SLO lines on the trend charts
+        #[arg(long)]
+        config: Option<std::path::PathBuf>,
+    },
+    /// Render a static HTML diff of two runs (env, config and metric deltas)
+    Diff {
+        /// Path to input JSONL file containing BenchRecords
+        #[arg(long)]
+        jsonl: std::path::PathBuf,
+        /// Baseline run (detail slug such as run_000123, or record_id)
+        run_a: String,
+        /// Run compared against the baseline
+        run_b: String,
+        /// History output directory; the page is written to <out>/diffs/
+        #[arg(long)]
+        out: std::path::PathBuf,
+        /// Regression threshold percentage for the metric table
+        #[arg(long, default_value_t = noir_bench::compare_cmd::DEFAULT_THRESHOLD)]
+        threshold: f64,
+        /// Only accept signed records from these public keys (hex, one per line)
+        #[arg(long)]
+        trusted_keys: Option<std::path::PathBuf>,
+        /// Reject unsigned records (requires --trusted-keys)
+        #[arg(long)]
+        require_signed: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CorpusCommands {
+    /// List the circuits in the embedded corpus
+    List,
+    /// Extract the corpus to --out-dir (default: out/corpus), then compile,
+    /// prove and verify every circuit
+    Run {
+        /// Only these circuits (comma-separated, e.g. merkle_proof,ecdsa_verify)
+        #[arg(long, value_delimiter = ',')]
+        only: Option<Vec<String>>,
+        /// Append a BenchRecord per circuit to this JSONL file
+        #[arg(long, default_value = noir_bench::corpus_cmd::DEFAULT_JSONL)]
+        jsonl: std::path::PathBuf,
+        /// Number of measured prove iterations
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Number of warmup prove iterations
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+        /// Path to nargo binary (default: nargo from PATH)
+        #[arg(long)]
+        nargo_path: Option<std::path::PathBuf>,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CircuitCommands {
+    /// Write a circuit card: gates, opcode breakdown, ABI and Brillig sizes
+    ///
+    /// Prints the card as markdown unless --json, --markdown or --html is
+    /// given.
+    Report {
+        /// Path to program artifact (program.json)
+        #[arg(long)]
+        artifact: std::path::PathBuf,
+        /// Circuit name (default: artifact file stem)
+        #[arg(long)]
+        name: Option<String>,
+        /// Path to bb binary
+        #[arg(long, default_value = "bb")]
+        backend_path: std::path::PathBuf,
+        /// Skip the backend gate count
+        #[arg(long)]
+        no_gates: bool,
+        /// Write the card as JSON to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
+        /// Write the card as markdown to this file
+        #[arg(long)]
+        markdown: Option<std::path::PathBuf>,
+        /// Write the card as HTML to this file
+        #[arg(long)]
+        html: Option<std::path::PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BaselineCommands {
+    /// Snapshot per-circuit history aggregates as a named baseline
+    ///
+    /// Each circuit/backend gets the median of its last --window runs (runs
+    /// flagged as anomalies are skipped), stored as <baselines-dir>/<name>.jsonl
+    /// for `compare --against <name>`.
+    Freeze {
+        /// Baseline name, e.g. a release tag
+        #[arg(long)]
+        name: String,
+        /// History BenchRecords JSONL
+        #[arg(long)]
+        jsonl: std::path::PathBuf,
+        /// Runs per circuit to take the median over
+        #[arg(long, default_value_t = noir_bench::baseline_cmd::DEFAULT_FREEZE_WINDOW)]
+        window: usize,
+        /// Directory of named baselines
+        #[arg(long, default_value = noir_bench::baseline_cmd::DEFAULT_BASELINES_DIR)]
+        baselines_dir: std::path::PathBuf,
+        /// Replace an existing baseline of the same name
+        #[arg(long)]
+        force: bool,
+        /// Keep runs flagged as anomalies at history ingestion
         #[arg(long)]
-        html_out: Option<std::path::PathBuf>,
+        include_anomalies: bool,
     },
+    /// List named baselines
+    List {
+        /// Directory of named baselines
+        #[arg(long, default_value = noir_bench::baseline_cmd::DEFAULT_BASELINES_DIR)]
+        baselines_dir: std::path::PathBuf,
+    },
+}
 
-    /// Build derived history artifacts from JSONL
+#[derive(Subcommand, Debug)]
+enum ProofCommands {
+    /// Re-encode a bb proof as raw bytes, hex, fields JSON or EVM calldata
     ///
-    /// Reads canonical JSONL telemetry and produces:
-    /// - index.json: derived run index for querying
-    /// - index.html: single-file HTML viewer (fetches index.json at runtime)
-    History {
-        #[command(subcommand)]
-        sub: HistoryCommands,
+    /// Prints the size of the proof in every encoding, and the calldata gas
+    /// of the verify call.
+    Convert {
+        /// Proof file
+        #[arg(long)]
+        input: std::path::PathBuf,
+        /// Public inputs file (raw, hex or fields), for calldata
+        #[arg(long)]
+        public_inputs: Option<std::path::PathBuf>,
+        /// Encoding of --input: raw, hex, fields, calldata (detected by default)
+        #[arg(long)]
+        from: Option<String>,
+        /// Encoding to write: raw, hex, fields, calldata
+        #[arg(long)]
+        to: String,
+        /// Write the converted proof here instead of stdout (required for raw)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+        /// Write the sizes as JSON to this file
+        #[arg(long)]
+        json: Option<std::path::PathBuf>,
     },
 }
 
 #[derive(Subcommand, Debug)]
-enum HistoryCommands {
-    /// Build index.json and index.html from JSONL
-    Build {
-        /// Path to input JSONL file containing BenchRecords
+enum ModelCommands {
+    /// Fit prove_ms and peak_rss against subgroup size from historical records
+    ///
+    /// Stores the model in --models-dir as <backend>@<version>.json (or
+    /// <backend>.json without --backend-version), where `estimate` finds it.
+    Fit {
+        /// Historical BenchRecords JSONL
         #[arg(long)]
         jsonl: std::path::PathBuf,
-        /// Output directory for index.json and index.html
+        /// Backend name as recorded (bb is accepted for barretenberg)
+        #[arg(long, default_value = "bb")]
+        backend: String,
+        /// Only fit on records of this backend version
         #[arg(long)]
-        out: std::path::PathBuf,
+        backend_version: Option<String>,
+        /// Directory to store the fitted model in
+        #[arg(long, default_value = noir_bench::core::cost_model::DEFAULT_MODELS_DIR)]
+        models_dir: std::path::PathBuf,
+        /// Only re-fit if the model is missing or this many new records accumulated
+        #[arg(long)]
+        min_new: Option<usize>,
     },
 }
 
@@ -322,6 +1252,94 @@ enum BenchCommands {
         /// JSONL output (default: out/bench.jsonl)
         #[arg(long)]
         jsonl: Option<std::path::PathBuf>,
+        /// Benchmark under each of these nargo versions (e.g. 0.38.0,0.39.0,nightly).
+        /// Uses the bb backend and appends toolchain-tagged BenchRecords to --jsonl
+        /// and rows to --csv
+        #[arg(long)]
+        nargo_versions: Option<String>,
+        /// Directory of per-version nargo installs (<dir>/<version>/bin/nargo);
+        /// without it each version is installed with noirup into a temporary
+        /// directory, leaving ~/.nargo untouched
+        #[arg(long, requires = "nargo_versions")]
+        nargo_dir: Option<std::path::PathBuf>,
+        /// Benchmark under each of these nargo compile configs and print a
+        /// config-vs-config section: NAME=FLAGS (e.g. brillig=--force-brillig,
+        /// default=) or the name of a [[compile_config]] in the config (repeatable)
+        #[arg(long, value_name = "NAME[=FLAGS]", conflicts_with_all = ["nargo_versions", "bb_versions", "compile_configs"])]
+        compile_config: Vec<String>,
+        /// Benchmark under every [[compile_config]] in the config
+        #[arg(long, conflicts_with_all = ["nargo_versions", "bb_versions"])]
+        compile_configs: bool,
+        /// Benchmark under each of these bb versions (e.g. 0.66.0,0.67.0) and print a
+        /// version-vs-version regression report
+        #[arg(long, conflicts_with = "nargo_versions")]
+        bb_versions: Option<String>,
+        /// Directory of per-version bb installs (<dir>/<version>/bin/bb);
+        /// without it each version is installed with bbup into a temporary
+        /// directory, leaving ~/.bb untouched
+        #[arg(long, requires = "bb_versions")]
+        bb_dir: Option<std::path::PathBuf>,
+        /// Regression threshold percent for --bb-versions reports (default: 10)
+        #[arg(long, requires = "bb_versions")]
+        threshold: Option<f64>,
+        /// Cache generated vks in this directory (default .noir-bench/keys) and
+        /// reuse them in later runs instead of generating them again
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = noir_bench::storage::DEFAULT_KEY_CACHE)]
+        key_cache: Option<std::path::PathBuf>,
+        /// Regenerate vks instead of loading them from --key-cache
+        #[arg(long, requires = "key_cache")]
+        cold_keys: bool,
+        /// JSONL line shape: bench-record, or legacy for the flat pre-BenchRecord lines
+        #[arg(long, default_value = "bench-record")]
+        output: String,
+        /// Directory per-run work directories (witnesses, bb outputs) are created in
+        /// (default: work/ next to the JSONL output)
+        #[arg(long)]
+        work_dir: Option<std::path::PathBuf>,
+        /// Which circuit work directories to keep: keep-on-failure, always, never
+        #[arg(long, default_value = "keep-on-failure")]
+        retention: String,
+        /// Health-check bb (gate analysis) and warm its SRS before measuring; the
+        /// warm-up time is recorded as probe_ms and kept out of the stats
+        #[arg(long)]
+        probe: bool,
+        /// Evict the artifact, witness and bb's SRS from the page cache before
+        /// every prove to measure cold-start proving (Linux only)
+        #[arg(long)]
+        cold_cache: bool,
+    },
+    /// Sweep a circuit across expression widths and bb options and print a
+    /// gates / proof size / prove time tradeoff table
+    Sweep {
+        /// Circuit name from config
+        #[arg(long)]
+        circuit: String,
+        /// Params value to select (optional)
+        #[arg(long)]
+        params: Option<u64>,
+        /// Expression widths to compile for, 0 for unbounded (default: 3,4,5,0
+        /// when no --bb-option is given)
+        #[arg(long, value_delimiter = ',')]
+        expression_widths: Vec<u32>,
+        /// bb option set to prove with, NAME=FLAGS (e.g. no-zk=--disable_zk,
+        /// keccak="--oracle_hash keccak", default=) (repeatable)
+        #[arg(long, value_name = "NAME=FLAGS")]
+        bb_option: Vec<String>,
+        /// Number of measured iterations to run
+        #[arg(long, default_value_t = 1)]
+        iterations: usize,
+        /// Number of warmup iterations to run before measuring
+        #[arg(long, default_value_t = 0)]
+        warmup: usize,
+        /// Path to bench-config.toml
+        #[arg(long)]
+        config: Option<std::path::PathBuf>,
+        /// JSONL output (default: out/bench.jsonl)
+        #[arg(long)]
+        jsonl: Option<std::path::PathBuf>,
+        /// Write the tradeoff table rows as JSON to this file
+        #[arg(long)]
+        json_out: Option<std::path::PathBuf>,
     },
     /// Run across all circuits and params in config
     RunAll {
@@ -343,6 +1361,47 @@ enum BenchCommands {
         /// JSONL output (default: out/bench.jsonl)
         #[arg(long)]
         jsonl: Option<std::path::PathBuf>,
+        /// Benchmark every circuit under each of these bb versions and print a
+        /// version-vs-version regression report
+        #[arg(long)]
+        bb_versions: Option<String>,
+        /// Directory of per-version bb installs (<dir>/<version>/bin/bb);
+        /// without it each version is installed with bbup into a temporary
+        /// directory, leaving ~/.bb untouched
+        #[arg(long, requires = "bb_versions")]
+        bb_dir: Option<std::path::PathBuf>,
+        /// Regression threshold percent for --bb-versions reports (default: 10)
+        #[arg(long, requires = "bb_versions")]
+        threshold: Option<f64>,
+        /// Cache generated vks in this directory (default .noir-bench/keys) and
+        /// reuse them in later runs instead of generating them again
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = noir_bench::storage::DEFAULT_KEY_CACHE)]
+        key_cache: Option<std::path::PathBuf>,
+        /// Regenerate vks instead of loading them from --key-cache
+        #[arg(long, requires = "key_cache")]
+        cold_keys: bool,
+        /// JSONL line shape: bench-record, or legacy for the flat pre-BenchRecord lines
+        #[arg(long, default_value = "bench-record")]
+        output: String,
+        /// Directory per-run work directories (witnesses, bb outputs) are created in
+        /// (default: work/ next to the JSONL output)
+        #[arg(long)]
+        work_dir: Option<std::path::PathBuf>,
+        /// Which circuit work directories to keep: keep-on-failure, always, never
+        #[arg(long, default_value = "keep-on-failure")]
+        retention: String,
+        /// Health-check bb (gate analysis) and warm its SRS before measuring; the
+        /// warm-up time is recorded as probe_ms and kept out of the stats
+        #[arg(long)]
+        probe: bool,
+        /// Evict the artifact, witness and bb's SRS from the page cache before
+        /// every prove to measure cold-start proving (Linux only)
+        #[arg(long)]
+        cold_cache: bool,
+        /// Append progress events (circuit done, ETA from earlier runs in --jsonl) as
+        /// JSON lines to this file
+        #[arg(long)]
+        progress: Option<std::path::PathBuf>,
     },
     /// Export CSV from JSONL records
     ExportCsv {
@@ -383,104 +1442,146 @@ fn init_tracing(verbose: bool) {
         .try_init();
 }
 
+/// Subcommand path of the invocation, e.g. `bench run-all`.
+fn command_path(matches: &clap::ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut m = matches;
+    while let Some((name, sub)) = m.subcommand() {
+        names.push(name);
+        m = sub;
+    }
+    names.join(" ")
+}
+
+/// Point the output path flags of `command` that weren't given at their
+/// default names in the `--out-dir` directory. Only results the command
+/// produces anyway are filled in; extra renderings (`--csv`, `--md`,
+/// `--html-out`, `suite --html`) still have to be asked for.
+fn apply_out_dir(cli: &mut Cli, dir: &OutDir) {
+    use out_dir::{RECORDS_JSONL, REGRESSION_JSON, REPORT_CSV, REPORT_JSON, SUMMARY_JSON};
+    match &mut cli.command {
+        Commands::Exec { output, json, .. } => {
+            if output.is_none() {
+                *output = Some(dir.root().to_path_buf());
+            }
+            dir.fill(json, REPORT_JSON);
+        }
+        Commands::Gates { json, .. }
+        | Commands::Prove { json, .. }
+        | Commands::Estimate { json, .. }
+        | Commands::E2e { json, .. }
+        | Commands::StarknetVerify { json, .. }
+        | Commands::SvmVerify { json, .. }
+        | Commands::EvmVerify { json, .. }
+        | Commands::Calldata { json, .. }
+        | Commands::Proof {
+            sub: ProofCommands::Convert { json, .. },
+        } => dir.fill(json, REPORT_JSON),
+        Commands::Compile { json, jsonl, .. }
+        | Commands::Verify { json, jsonl, .. }
+        | Commands::Pipeline { json, jsonl, .. } => {
+            dir.fill(json, REPORT_JSON);
+            dir.fill(jsonl, RECORDS_JSONL);
+        }
+        Commands::Primitives { jsonl, .. } => dir.fill(jsonl, RECORDS_JSONL),
+        Commands::Abtest { json_out, .. } => dir.fill(json_out, REGRESSION_JSON),
+        Commands::Compare { json_out, .. } => dir.fill(json_out, REGRESSION_JSON),
+        Commands::Suite { jsonl, summary, .. } => {
+            if jsonl.is_empty() {
+                jsonl.push(dir.path(RECORDS_JSONL).display().to_string());
+            }
+            dir.fill(summary, SUMMARY_JSON);
+        }
+        Commands::Ci {
+            sub: Some(CiCommands::Collect {
+                output, json_out, ..
+            }),
+            ..
+        }
+        | Commands::Ci {
+            output, json_out, ..
+        } => {
+            dir.fill(output, RECORDS_JSONL);
+            dir.fill(json_out, REGRESSION_JSON);
+        }
+        Commands::Bench { sub } => match sub {
+            BenchCommands::Run { csv, jsonl, .. } | BenchCommands::RunAll { csv, jsonl, .. } => {
+                dir.fill(csv, REPORT_CSV);
+                dir.fill(jsonl, RECORDS_JSONL);
+            }
+            BenchCommands::Sweep {
+                jsonl, json_out, ..
+            } => {
+                dir.fill(jsonl, RECORDS_JSONL);
+                dir.fill(json_out, REPORT_JSON);
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Write the `--out-dir` manifest, then exit with `code`.
+fn exit_with(out_dir: Option<&OutDir>, code: i32) -> ! {
+    if let Some(dir) = out_dir {
+        dir.finish(code);
+    }
+    std::process::exit(code)
+}
+
 fn main() {
     color_eyre::install().ok();
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    match DisplaySettings::parse(&cli.time_zone, cli.thousands_separator.clone()) {
+        Some(settings) => DisplaySettings::set_default(settings),
+        None => {
+            eprintln!(
+                "invalid --time-zone '{}' (expected utc or local)",
+                cli.time_zone
+            );
+            std::process::exit(2);
+        }
+    }
     init_tracing(cli.verbose);
-
-    fn write_exports(
-        json_path: &std::path::Path,
-        csv: &Option<std::path::PathBuf>,
-        md: &Option<std::path::PathBuf>,
-    ) {
-        let Ok(bytes) = std::fs::read(json_path) else {
-            return;
-        };
-        let Ok(v): Result<JsonValue, _> = serde_json::from_slice(&bytes) else {
-            return;
-        };
-        if let Some(csv_path) = csv {
-            let mut line = String::new();
-            if v.get("execution_time_ms").is_some() {
-                line = format!(
-                    "kind,time_ms,samples\nexec,{},{}\n",
-                    v["execution_time_ms"], v["samples_count"]
-                );
-            } else if v.get("total_gates").is_some() {
-                line = format!(
-                    "kind,total_gates,acir_opcodes\ngates,{},{}\n",
-                    v["total_gates"], v["acir_opcodes"]
-                );
-            } else if v.get("prove_time_ms").is_some() {
-                line = format!(
-                    "kind,prove_time_ms,witness_gen_ms,backend_ms,proof_size,peak_mem\nprove,{},{},{},{},{}\n",
-                    v["prove_time_ms"],
-                    v.get("witness_gen_time_ms").unwrap_or(&JsonValue::Null),
-                    v.get("backend_prove_time_ms").unwrap_or(&JsonValue::Null),
-                    v.get("proof_size_bytes").unwrap_or(&JsonValue::Null),
-                    v.get("peak_memory_bytes").unwrap_or(&JsonValue::Null)
-                );
-            } else if v.get("verify_time_ms").is_some() {
-                line = format!(
-                    "kind,verify_time_ms,ok\nverify,{},{}\n",
-                    v["verify_time_ms"], v["ok"]
-                );
-            } else if v.get("gas_used").is_some() {
-                line = format!(
-                    "kind,gas_used,calldata_bytes,est_latency_ms\nevm-verify,{},{},{}\n",
-                    v["gas_used"],
-                    v.get("calldata_bytes").unwrap_or(&JsonValue::Null),
-                    v.get("est_latency_ms").unwrap_or(&JsonValue::Null)
+    if let Some(method) = &cli.percentile_method {
+        match PercentileMethod::parse(method) {
+            Some(method) => PercentileMethod::set_default(method),
+            None => {
+                eprintln!(
+                    "invalid --percentile-method '{method}' (expected linear or nearest-rank)"
                 );
-            }
-            if !line.is_empty() {
-                let _ = std::fs::write(csv_path, line.as_bytes());
+                std::process::exit(2);
             }
         }
-        if let Some(md_path) = md {
-            let mut md_s = String::new();
-            if v.get("execution_time_ms").is_some() {
-                md_s.push_str("| kind | time_ms | samples |\n|---|---:|---:|\n");
-                md_s.push_str(&format!(
-                    "| exec | {} | {} |\n",
-                    v["execution_time_ms"], v["samples_count"]
-                ));
-            } else if v.get("total_gates").is_some() {
-                md_s.push_str("| kind | total_gates | acir_opcodes |\n|---|---:|---:|\n");
-                md_s.push_str(&format!(
-                    "| gates | {} | {} |\n",
-                    v["total_gates"], v["acir_opcodes"]
-                ));
-            } else if v.get("prove_time_ms").is_some() {
-                md_s.push_str("| kind | prove_ms | witness_ms | backend_ms | proof_size | peak_mem |\n|---|---:|---:|---:|---:|---:|\n");
-                md_s.push_str(&format!(
-                    "| prove | {} | {} | {} | {} | {} |\n",
-                    v["prove_time_ms"],
-                    v.get("witness_gen_time_ms").unwrap_or(&JsonValue::Null),
-                    v.get("backend_prove_time_ms").unwrap_or(&JsonValue::Null),
-                    v.get("proof_size_bytes").unwrap_or(&JsonValue::Null),
-                    v.get("peak_memory_bytes").unwrap_or(&JsonValue::Null)
-                ));
-            } else if v.get("verify_time_ms").is_some() {
-                md_s.push_str("| kind | verify_ms | ok |\n|---|---:|:--:|\n");
-                md_s.push_str(&format!(
-                    "| verify | {} | {} |\n",
-                    v["verify_time_ms"], v["ok"]
-                ));
-            } else if v.get("gas_used").is_some() {
-                md_s.push_str(
-                    "| kind | gas_used | calldata_bytes | est_latency_ms |\n|---|---:|---:|---:|\n",
-                );
-                md_s.push_str(&format!(
-                    "| evm-verify | {} | {} | {} |\n",
-                    v["gas_used"],
-                    v.get("calldata_bytes").unwrap_or(&JsonValue::Null),
-                    v.get("est_latency_ms").unwrap_or(&JsonValue::Null)
-                ));
+    }
+
+    let out_dir = match cli.out_dir.clone() {
+        Some(root) => match OutDir::create(root, &command_path(&matches)) {
+            Ok(dir) => {
+                apply_out_dir(&mut cli, &dir);
+                Some(dir)
             }
-            if !md_s.is_empty() {
-                let _ = std::fs::write(md_path, md_s.as_bytes());
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(2);
             }
+        },
+        None => None,
+    };
+
+    let mut reporter_specs = cli.reporter.clone();
+    if let Some(path) = &cli.csv {
+        reporter_specs.push(format!("csv={}", path.display()));
+    }
+    if let Some(path) = &cli.md {
+        reporter_specs.push(format!("md={}", path.display()));
+    }
+    match reporter::open_reporters(&reporter_specs) {
+        Ok(reporters) => reporter::set_reporters(reporters),
+        Err(e) => {
+            eprintln!("invalid --reporter: {e}");
+            std::process::exit(2);
         }
     }
 
@@ -496,15 +1597,98 @@ fn main() {
                 config,
                 csv,
                 jsonl,
-            } => bench::bench_cmd::run(
+                nargo_versions,
+                nargo_dir,
+                compile_config,
+                compile_configs,
+                bb_versions,
+                bb_dir,
+                threshold,
+                key_cache,
+                cold_keys,
+                output,
+                work_dir,
+                retention,
+                probe,
+                cold_cache,
+            } => match (nargo_versions, bb_versions) {
+                (None, None) if compile_configs || !compile_config.is_empty() => {
+                    bench::bench_cmd::run_compile_config_matrix(
+                        circuit,
+                        backend,
+                        params,
+                        config,
+                        jsonl,
+                        Some(iterations),
+                        Some(warmup),
+                        compile_config,
+                    )
+                }
+                (Some(versions), _) => bench::bench_cmd::run_matrix(
+                    circuit,
+                    backend,
+                    params,
+                    config,
+                    csv,
+                    jsonl,
+                    Some(iterations),
+                    Some(warmup),
+                    versions,
+                    nargo_dir,
+                ),
+                (None, Some(versions)) => bench::bench_cmd::run_bb_matrix(
+                    Some(circuit),
+                    params,
+                    config,
+                    jsonl,
+                    Some(iterations),
+                    Some(warmup),
+                    versions,
+                    bb_dir,
+                    threshold,
+                ),
+                (None, None) => bench::bench_cmd::OutputFormat::parse(&output)
+                    .and_then(|output| Ok((output, Retention::parse(&retention)?)))
+                    .and_then(|(output, retention)| {
+                        bench::bench_cmd::run(
+                            circuit,
+                            backend,
+                            params,
+                            config,
+                            csv,
+                            jsonl,
+                            Some(iterations),
+                            Some(warmup),
+                            key_cache,
+                            cold_keys,
+                            output,
+                            work_dir,
+                            retention,
+                            probe,
+                            cold_cache,
+                        )
+                    }),
+            },
+            BenchCommands::Sweep {
+                circuit,
+                params,
+                expression_widths,
+                bb_option,
+                iterations,
+                warmup,
+                config,
+                jsonl,
+                json_out,
+            } => bench::bench_cmd::run_sweep_cmd(
                 circuit,
-                backend,
                 params,
                 config,
-                csv,
                 jsonl,
                 Some(iterations),
                 Some(warmup),
+                expression_widths,
+                bb_option,
+                json_out,
             ),
             BenchCommands::RunAll {
                 backend,
@@ -513,14 +1697,50 @@ fn main() {
                 config,
                 csv,
                 jsonl,
-            } => bench::bench_cmd::run_all(
-                backend,
-                config,
-                csv,
-                jsonl,
-                Some(iterations),
-                Some(warmup),
-            ),
+                bb_versions,
+                bb_dir,
+                threshold,
+                key_cache,
+                cold_keys,
+                output,
+                work_dir,
+                retention,
+                probe,
+                cold_cache,
+                progress,
+            } => match bb_versions {
+                Some(versions) => bench::bench_cmd::run_bb_matrix(
+                    None,
+                    None,
+                    config,
+                    jsonl,
+                    Some(iterations),
+                    Some(warmup),
+                    versions,
+                    bb_dir,
+                    threshold,
+                ),
+                None => bench::bench_cmd::OutputFormat::parse(&output)
+                    .and_then(|output| Ok((output, Retention::parse(&retention)?)))
+                    .and_then(|(output, retention)| {
+                        bench::bench_cmd::run_all(
+                            backend,
+                            config,
+                            csv,
+                            jsonl,
+                            Some(iterations),
+                            Some(warmup),
+                            key_cache,
+                            cold_keys,
+                            output,
+                            work_dir,
+                            retention,
+                            probe,
+                            cold_cache,
+                            progress,
+                        )
+                    }),
+            },
             BenchCommands::ExportCsv { jsonl, csv } => bench::bench_cmd::export_csv(jsonl, csv),
             BenchCommands::EvmVerify {
                 circuit,
@@ -536,7 +1756,16 @@ fn main() {
             flamegraph,
             iterations,
             warmup,
+            oracle_mocks,
+            oracle_latency_ms,
+            oracle_resolver,
+            expect_return,
+            expect_file,
+            expect_failure,
         } => {
+            let expected = expect_return
+                .map(exec_cmd::ExpectedReturn::Value)
+                .or(expect_file.map(exec_cmd::ExpectedReturn::File));
             let r = exec_cmd::run(
                 artifact.clone(),
                 prover_toml.clone(),
@@ -545,9 +1774,14 @@ fn main() {
                 flamegraph,
                 Some(iterations),
                 Some(warmup),
+                oracle_mocks,
+                oracle_latency_ms,
+                oracle_resolver,
+                expected,
+                expect_failure,
             );
             if let (Ok(_), Some(j)) = (&r, &json) {
-                write_exports(j, &cli.csv, &cli.md);
+                reporter::publish_json_file(j);
             }
             r
         }
@@ -555,102 +1789,370 @@ fn main() {
             artifact,
             backend,
             backend_path,
+            backend_arg,
             backend_args,
             template,
             json,
+            iterations,
+            cache,
+            top,
         } => {
             let r = gates_cmd::run(
                 artifact.clone(),
                 backend,
                 backend_path,
-                backend_args,
+                [backend_arg, backend_args].concat(),
                 template,
                 json.clone(),
+                iterations,
+                cache,
+                top,
             );
             if let (Ok(_), Some(j)) = (&r, &json) {
-                write_exports(j, &cli.csv, &cli.md);
+                reporter::publish_json_file(j);
             }
             r
         }
+        Commands::Circuit { sub } => match sub {
+            CircuitCommands::Report {
+                artifact,
+                name,
+                backend_path,
+                no_gates,
+                json,
+                markdown,
+                html,
+            } => circuit_cmd::report(artifact, name, backend_path, no_gates, json, markdown, html),
+        },
+        Commands::Compile {
+            project,
+            name,
+            nargo_path,
+            incremental,
+            iterations,
+            warmup,
+            json,
+            jsonl,
+        } => compile_cmd::run(
+            project,
+            name,
+            nargo_path,
+            incremental,
+            Some(iterations),
+            Some(warmup),
+            json,
+            jsonl,
+            cli.sign_key.clone(),
+        ),
         Commands::Prove {
             artifact,
             prover_toml,
             backend,
             backend_path,
+            backend_arg,
             backend_args,
             template,
+            custom_metric,
             timeout,
             iterations,
             warmup,
             json,
+            oracle_resolver,
+            amortize_setup,
+            backend_verbose,
+            key_cache,
+            cold_keys,
+            expect_failure,
         } => {
             let r = prove_cmd::run(
                 artifact,
                 prover_toml,
                 backend,
                 backend_path,
-                backend_args,
+                [backend_arg, backend_args].concat(),
                 template,
+                custom_metric,
                 timeout,
                 Some(iterations),
                 Some(warmup),
                 json.clone(),
+                oracle_resolver,
+                amortize_setup,
+                backend_verbose,
+                key_cache,
+                cold_keys,
+                expect_failure,
             );
             if let (Ok(_), Some(j)) = (&r, &json) {
-                write_exports(j, &cli.csv, &cli.md);
+                reporter::publish_json_file(j);
             }
             r
         }
+        Commands::Primitives {
+            only,
+            sizes,
+            prove,
+            iterations,
+            warmup,
+            nargo_path,
+            backend_path,
+            jsonl,
+        } => primitives_cmd::run(
+            cli.out_dir
+                .clone()
+                .unwrap_or_else(|| primitives_cmd::DEFAULT_OUT_DIR.into()),
+            only,
+            sizes,
+            prove,
+            Some(iterations),
+            Some(warmup),
+            nargo_path,
+            backend_path,
+            jsonl,
+            cli.sign_key.clone(),
+        ),
+        Commands::Estimate {
+            artifact,
+            name,
+            backend_path,
+            model,
+            models_dir,
+            json,
+        } => estimate_cmd::run(artifact, name, backend_path, model, models_dir, json),
+        Commands::Baseline { sub } => match sub {
+            BaselineCommands::Freeze {
+                name,
+                jsonl,
+                window,
+                baselines_dir,
+                force,
+                include_anomalies,
+            } => baseline_cmd::run_freeze(
+                jsonl,
+                name,
+                baselines_dir,
+                window,
+                force,
+                include_anomalies,
+            ),
+            BaselineCommands::List { baselines_dir } => baseline_cmd::run_list(baselines_dir),
+        },
+        Commands::Changelog {
+            from,
+            to,
+            jsonl,
+            baselines_dir,
+            threshold,
+            out,
+        } => changelog_cmd::run(from, to, jsonl, baselines_dir, threshold, out),
+        Commands::Model { sub } => match sub {
+            ModelCommands::Fit {
+                jsonl,
+                backend,
+                backend_version,
+                models_dir,
+                min_new,
+            } => model_cmd::run_fit(jsonl, backend, backend_version, models_dir, min_new),
+        },
+        Commands::Corpus { sub } => match sub {
+            CorpusCommands::List => corpus_cmd::list(),
+            CorpusCommands::Run {
+                only,
+                jsonl,
+                iterations,
+                warmup,
+                nargo_path,
+                backend_path,
+            } => corpus_cmd::run(
+                only,
+                cli.out_dir
+                    .clone()
+                    .unwrap_or_else(|| corpus_cmd::DEFAULT_OUT_DIR.into()),
+                jsonl,
+                Some(iterations),
+                Some(warmup),
+                nargo_path,
+                backend_path,
+                cli.sign_key.clone(),
+            ),
+        },
         Commands::Verify {
             artifact,
             proof,
+            vk,
+            public_inputs,
             backend,
             backend_path,
+            backend_arg,
             backend_args,
             template,
             iterations,
             warmup,
+            expect_invalid,
+            tampered_proof,
             json,
+            jsonl,
+            store,
         } => {
             let r = verify_cmd::run(
                 artifact,
                 proof,
+                vk,
+                public_inputs,
                 backend,
                 backend_path,
-                backend_args,
+                [backend_arg, backend_args].concat(),
                 template,
                 Some(iterations),
                 Some(warmup),
+                expect_invalid,
+                tampered_proof,
                 json.clone(),
+                jsonl,
+                store,
+                cli.sign_key.clone(),
             );
             if let (Ok(_), Some(j)) = (&r, &json) {
-                write_exports(j, &cli.csv, &cli.md);
+                reporter::publish_json_file(j);
             }
             r
         }
+        Commands::Abtest {
+            backend_a,
+            backend_b,
+            circuit,
+            config,
+            backend_arg,
+            pairs,
+            warmup,
+            alpha,
+            threshold,
+            format,
+            json_out,
+        } => abtest_cmd::run(
+            backend_a,
+            backend_b,
+            circuit,
+            config,
+            backend_arg,
+            warmup,
+            pairs,
+            alpha,
+            threshold,
+            format,
+            json_out,
+        )
+        .map(|report| {
+            if report.b_slower() > 0 {
+                exit_with(out_dir.as_ref(), 1);
+            }
+        }),
+        Commands::Report {
+            input,
+            pdf,
+            chromium,
+        } => report_cmd::run(input, pdf, chromium),
+        Commands::Compare {
+            threshold,
+            format,
+            json_out,
+            paired: true,
+            artifact,
+            prover_toml,
+            baseline_bb,
+            contender_bb,
+            baseline_args,
+            contender_args,
+            pairs,
+            warmup,
+            alpha,
+            ..
+        } => compare_cmd::run_paired(
+            artifact.expect("clap requires --artifact with --paired"),
+            prover_toml,
+            baseline_bb,
+            contender_bb.expect("clap requires --contender-bb with --paired"),
+            baseline_args,
+            contender_args,
+            warmup,
+            pairs,
+            alpha,
+            threshold.unwrap_or(0.0),
+            format,
+            json_out,
+        )
+        .map(|result| {
+            if result.status == compare_cmd::CompareStatus::Regression {
+                exit_with(out_dir.as_ref(), 1);
+            }
+        }),
         Commands::Compare {
             baseline,
             contender,
             baseline_file,
             target_file,
+            against,
+            baselines_dir,
+            reference,
+            reference_machine,
             threshold,
+            noise_sigma,
+            baseline_window,
+            arch,
+            allow_cross_arch,
+            include_anomalies,
+            metrics,
+            informational_metrics,
+            config,
+            annotate,
             format,
             json_out,
             html_out,
+            ..
         } => {
-            match compare_cmd::run(
-                baseline,
-                contender,
-                baseline_file,
-                target_file,
-                threshold,
-                format,
-                json_out,
-                html_out,
-            ) {
+            let baseline_file = match against {
+                Some(name) if include_anomalies => {
+                    baseline_cmd::resolve_with_anomalies(&baselines_dir, &name).map(Some)
+                }
+                Some(name) => baseline_cmd::resolve(&baselines_dir, &name).map(Some),
+                None => Ok(baseline_file),
+            };
+            let result = match (reference, target_file) {
+                (Some(reference), Some(target_file)) => compare_cmd::run_reference(
+                    reference,
+                    reference_machine,
+                    target_file,
+                    threshold.unwrap_or(compare_cmd::DEFAULT_THRESHOLD),
+                    format,
+                    json_out,
+                    html_out,
+                ),
+                (_, target_file) => baseline_file.and_then(|baseline_file| {
+                    compare_cmd::run(
+                        baseline,
+                        contender,
+                        baseline_file,
+                        target_file,
+                        threshold,
+                        noise_sigma,
+                        baseline_window,
+                        arch,
+                        allow_cross_arch,
+                        include_anomalies,
+                        metrics,
+                        informational_metrics,
+                        config,
+                        annotate,
+                        format,
+                        json_out,
+                        html_out,
+                    )
+                }),
+            };
+            match result {
                 Ok(result) => {
                     if result.ci_exit_code != 0 {
-                        std::process::exit(result.ci_exit_code);
+                        exit_with(out_dir.as_ref(), result.ci_exit_code);
                     }
                     Ok(())
                 }
@@ -661,27 +2163,111 @@ fn main() {
             config,
             jsonl,
             summary,
-        } => suite_cmd::run(config, jsonl, summary),
+            html,
+            history,
+            progress,
+        } => suite_cmd::run(config, jsonl, summary, html, history, progress),
+        Commands::Pipeline {
+            config,
+            json,
+            jsonl,
+        } => pipeline_cmd::run(config, json, jsonl, cli.sign_key.clone()),
+        Commands::E2e {
+            artifact,
+            prover_toml,
+            node_project,
+            node_bin,
+            backend_path,
+            iterations,
+            warmup,
+            json,
+        } => e2e_cmd::run(
+            artifact,
+            prover_toml,
+            node_project,
+            node_bin,
+            backend_path,
+            iterations,
+            warmup,
+            json,
+        ),
+        Commands::StarknetVerify {
+            project_dir,
+            r#match,
+            command,
+            json,
+        } => starknet_verify_cmd::run(project_dir, r#match, command, json),
+        Commands::SvmVerify {
+            project_dir,
+            r#match,
+            program_id,
+            command,
+            json,
+        } => svm_verify_cmd::run(project_dir, r#match, program_id, command, json),
+        Commands::EvmVerify {
+            toolchain,
+            project_dir,
+            artifact,
+            r#match,
+            forge_bin,
+            json,
+            batch: Some(batch),
+            ..
+        } => evm_verify_cmd::run_batch(
+            toolchain,
+            project_dir,
+            artifact,
+            r#match,
+            batch,
+            forge_bin,
+            json,
+        ),
         Commands::EvmVerify {
-            foundry_dir,
+            toolchain,
+            project_dir,
+            bytecode,
+            calldata,
+            proof,
+            public_inputs,
             artifact,
             r#match,
             calldata_bytes,
             gas_per_second,
             forge_bin,
             json,
+            batch: None,
         } => {
-            let r = evm_verify_cmd::run(
-                foundry_dir,
-                artifact,
-                r#match,
-                calldata_bytes,
-                gas_per_second,
-                forge_bin,
-                json.clone(),
-            );
+            // revm encodes the call itself
+            let verify_call = match toolchain {
+                evm_verify_cmd::EvmToolchainKind::Revm => Ok(None),
+                _ => proof_cmd::load_verify_call(
+                    calldata.as_deref(),
+                    proof.as_deref(),
+                    public_inputs.as_deref(),
+                ),
+            };
+            let r = verify_call.and_then(|verify_call| {
+                let toolchain = evm_verify_cmd::EvmToolchain::from_args(
+                    toolchain,
+                    forge_bin,
+                    bytecode,
+                    calldata,
+                    proof,
+                    public_inputs,
+                )?;
+                evm_verify_cmd::run(
+                    toolchain,
+                    project_dir,
+                    artifact,
+                    r#match,
+                    verify_call,
+                    calldata_bytes,
+                    gas_per_second,
+                    json.clone(),
+                )
+            });
             if let (Ok(_), Some(j)) = (&r, &json) {
-                write_exports(j, &cli.csv, &cli.md);
+                reporter::publish_json_file(j);
             }
             r
         }
@@ -710,45 +2296,210 @@ fn main() {
             }
         }
         Commands::Ci {
+            sub,
             config,
             circuits,
             baseline_file,
+            baseline_window,
             threshold,
             iterations,
             warmup,
+            shard,
             output,
             format,
             json_out,
             html_out,
+            annotate,
+            metrics,
+            informational_metrics,
+            github_check,
         } => {
-            match ci_cmd::run(
-                config,
-                circuits,
-                baseline_file,
-                Some(threshold),
-                iterations,
-                warmup,
-                output,
-                format,
-                json_out,
-                html_out,
-            ) {
+            let outcome = match sub {
+                Some(CiCommands::Collect {
+                    shards,
+                    config,
+                    baseline_file,
+                    threshold,
+                    output,
+                    format,
+                    json_out,
+                    html_out,
+                    trusted_keys,
+                    require_signed,
+                }) => SignaturePolicy::from_args(trusted_keys.as_deref(), require_signed).and_then(
+                    |policy| {
+                        ci_cmd::collect(
+                            shards,
+                            config,
+                            baseline_file,
+                            Some(threshold),
+                            output,
+                            format,
+                            json_out,
+                            html_out,
+                            policy,
+                            metrics,
+                            informational_metrics,
+                            github_check,
+                        )
+                    },
+                ),
+                None => ci_cmd::run(
+                    config,
+                    circuits,
+                    baseline_file,
+                    baseline_window,
+                    Some(threshold),
+                    iterations,
+                    warmup,
+                    shard,
+                    output,
+                    format,
+                    json_out,
+                    html_out,
+                    cli.sign_key.clone(),
+                    annotate,
+                    metrics,
+                    informational_metrics,
+                    github_check,
+                ),
+            };
+            match outcome {
                 Ok(exit_code) => {
                     if exit_code != 0 {
-                        std::process::exit(exit_code);
+                        exit_with(out_dir.as_ref(), exit_code);
                     }
                     Ok(())
                 }
                 Err(e) => Err(e),
             }
         }
+        Commands::Attest {
+            record_id,
+            jsonl,
+            store,
+            backend_path,
+            backend_arg,
+            backend_args,
+        } => attest_cmd::run(
+            record_id,
+            jsonl,
+            store,
+            backend_path,
+            [backend_arg, backend_args].concat(),
+        ),
+        Commands::Reproduce {
+            record_id,
+            jsonl,
+            artifact,
+            prover_toml,
+            backend_path,
+            force,
+            out,
+        } => reproduce_cmd::run(
+            record_id,
+            jsonl,
+            artifact,
+            prover_toml,
+            backend_path,
+            force,
+            out,
+        ),
+        Commands::Daemon {
+            cron,
+            config,
+            history,
+            site,
+            state_dir,
+            models_dir,
+            notify,
+            once,
+        } => daemon_cmd::run(
+            cron,
+            config,
+            history,
+            site,
+            state_dir,
+            models_dir,
+            notify,
+            once,
+            cli.sign_key.clone(),
+        ),
+        Commands::Calldata {
+            proof,
+            public_inputs,
+            calldata,
+            json,
+        } => calldata_cmd::run(proof, public_inputs, calldata, json),
+        Commands::Proof {
+            sub:
+                ProofCommands::Convert {
+                    input,
+                    public_inputs,
+                    from,
+                    to,
+                    out,
+                    json,
+                },
+        } => proof_cmd::run_convert(input, public_inputs, from, to, out, json),
+        Commands::Check {
+            config,
+            jsonl,
+            format,
+            all,
+            evm_report,
+            forge_bin,
+        } => check_cmd::run(config, jsonl, format, all, evm_report, forge_bin),
+        Commands::Upgrade { input, out } => upgrade_cmd::run(input, out),
+        Commands::Rotate { input, compress } => match cli.out_dir.clone() {
+            Some(out_dir) => rotate_cmd::run(input, out_dir, compress),
+            None => Err(BenchError::Message(
+                "rotate needs --out-dir for the monthly files".to_string(),
+            )),
+        },
+        Commands::Import { reports, jsonl } => import_cmd::run(reports, jsonl),
         Commands::History { sub } => match sub {
-            HistoryCommands::Build { jsonl, out } => history_cmd::build(jsonl, out),
+            HistoryCommands::Build {
+                jsonl,
+                out,
+                trusted_keys,
+                require_signed,
+                arch,
+                config,
+            } => config
+                .as_deref()
+                .map(check_cmd::load_budgets)
+                .transpose()
+                .and_then(|budgets| {
+                    let policy =
+                        SignaturePolicy::from_args(trusted_keys.as_deref(), require_signed)?;
+                    history_cmd::build(
+                        jsonl,
+                        out,
+                        &policy,
+                        arch.as_deref(),
+                        &budgets.unwrap_or_default(),
+                    )
+                }),
+            HistoryCommands::Diff {
+                jsonl,
+                run_a,
+                run_b,
+                out,
+                threshold,
+                trusted_keys,
+                require_signed,
+            } => SignaturePolicy::from_args(trusted_keys.as_deref(), require_signed).and_then(
+                |policy| history_cmd::diff(jsonl, &run_a, &run_b, out, threshold, &policy),
+            ),
         },
     };
 
     if let Err(e) = result {
         eprintln!("{:#}", e);
-        std::process::exit(1);
+        exit_with(out_dir.as_ref(), 1);
+    }
+    if let Some(dir) = &out_dir {
+        dir.finish(0);
     }
 }
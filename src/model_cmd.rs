@@ -0,0 +1,230 @@
+//! CLI command handler for `model`.
+//!
+//! Fits the backend cost models used by `estimate` from historical
+//! BenchRecords (see `core::cost_model`), and re-fits them once enough new
+//! records have accumulated.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::core::BenchRecord;
+use crate::core::calibration;
+use crate::core::cost_model::{self, CostModel};
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// New records needed before an existing model is re-fitted.
+pub const DEFAULT_REFIT_MIN_NEW: usize = 20;
+
+/// Backend name as recorded in BenchRecords ("bb" is accepted for barretenberg).
+fn backend_name(backend: &str) -> &str {
+    match backend {
+        "bb" => "barretenberg",
+        other => other,
+    }
+}
+
+/// Records usable for fitting: they have both a prove time and a size, and
+/// aren't flagged as anomalies.
+fn fit_candidates(records: &[BenchRecord], backend: &str, version: Option<&str>) -> usize {
+    records
+        .iter()
+        .filter(|r| r.backend.name == backend && !r.anomaly)
+        .filter(|r| version.is_none() || r.backend.version.as_deref() == version)
+        .filter(|r| r.prove_stats.is_some())
+        .filter(|r| r.subgroup_size.is_some() || r.total_gates.is_some())
+        .count()
+}
+
+/// Whether the stored model (if any) is missing or `min_new` records behind.
+fn is_stale(path: &Path, candidates: usize, min_new: usize) -> bool {
+    match CostModel::load(path) {
+        Ok(model) => candidates >= model.samples + min_new,
+        Err(_) => candidates >= cost_model::MIN_FIT_SAMPLES,
+    }
+}
+
+/// Fit a model for a backend (optionally one version) and store it in
+/// `models_dir`, where `estimate` looks it up.
+pub fn fit(
+    records: &[BenchRecord],
+    backend: &str,
+    version: Option<&str>,
+    models_dir: &Path,
+    default_score: f64,
+) -> BenchResult<(CostModel, PathBuf)> {
+    let backend = backend_name(backend);
+    let model = CostModel::fit(records, backend, version, default_score)?;
+    let path = cost_model::model_path(models_dir, backend, version);
+    model.save(&path)?;
+    Ok((model, path))
+}
+
+/// Re-fit every per-version model that is missing or has fallen
+/// `min_new` records behind. Versions without enough data are skipped.
+/// Returns the paths of the models written.
+pub fn refit_stale(
+    records: &[BenchRecord],
+    models_dir: &Path,
+    min_new: usize,
+    default_score: f64,
+) -> Vec<PathBuf> {
+    let groups: BTreeSet<(String, Option<String>)> = records
+        .iter()
+        .map(|r| (r.backend.name.clone(), r.backend.version.clone()))
+        .collect();
+
+    let mut written = Vec::new();
+    for (backend, version) in &groups {
+        let version = version.as_deref();
+        let path = cost_model::model_path(models_dir, backend, version);
+        let candidates = fit_candidates(records, backend, version);
+        if !is_stale(&path, candidates, min_new) {
+            continue;
+        }
+        match fit(records, backend, version, models_dir, default_score) {
+            Ok((_, path)) => written.push(path),
+            Err(e) => eprintln!("model: skipping {backend}: {e}"),
+        }
+    }
+    written
+}
+
+fn describe(model: &CostModel, path: &Path) -> String {
+    let mut out = format!(
+        "model: {} -> {}\n  prove_ms = {:.3e} * size^{:.3} (log sigma {:.3}, {} samples, calibration {:.1})\n",
+        model.backend,
+        path.display(),
+        model.prove_ms.coefficient,
+        model.prove_ms.exponent,
+        model.prove_ms.log_sigma,
+        model.samples,
+        model.calibration_score
+    );
+    if let Some(rss) = &model.peak_rss_mb {
+        out.push_str(&format!(
+            "  peak_rss_mb = {:.3e} * size^{:.3} (log sigma {:.3})\n",
+            rss.coefficient, rss.exponent, rss.log_sigma
+        ));
+    }
+    out
+}
+
+/// Entry point for `model fit`.
+///
+/// With `min_new`, the model is only re-fitted when it is missing or at least
+/// that many new records have accumulated since it was last fitted.
+pub fn run_fit(
+    jsonl: PathBuf,
+    backend: String,
+    backend_version: Option<String>,
+    models_dir: PathBuf,
+    min_new: Option<usize>,
+) -> BenchResult<()> {
+    if !jsonl.exists() {
+        return Err(BenchError::Message(format!(
+            "history not found: {}",
+            jsonl.display()
+        )));
+    }
+    let records = JsonlWriter::new(&jsonl).read_all()?;
+    let name = backend_name(&backend);
+    let version = backend_version.as_deref();
+
+    if let Some(min_new) = min_new {
+        let path = cost_model::model_path(&models_dir, name, version);
+        let candidates = fit_candidates(&records, name, version);
+        if !is_stale(&path, candidates, min_new) {
+            println!(
+                "model: {} is up to date ({candidates} usable record(s))",
+                path.display()
+            );
+            return Ok(());
+        }
+    }
+
+    // Records without a calibration score are assumed to come from this machine
+    let (model, path) = fit(&records, name, version, &models_dir, calibration::measure())?;
+    print!("{}", describe(&model, &path));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+    fn record(version: &str, subgroup: u64, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            "c".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "barretenberg".to_string(),
+                version: Some(version.to_string()),
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.subgroup_size = Some(subgroup);
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r
+    }
+
+    #[test]
+    fn test_fit_stores_model_where_estimate_finds_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let records = vec![
+            record("1.0.0", 1 << 10, 100.0),
+            record("1.0.0", 1 << 12, 420.0),
+            record("1.0.0", 1 << 14, 1700.0),
+        ];
+
+        let (model, path) = fit(&records, "bb", Some("1.0.0"), dir.path(), 100.0).unwrap();
+        assert_eq!(model.backend, "barretenberg");
+        assert!(path.ends_with("barretenberg@1.0.0.json"));
+
+        let (found, source) =
+            cost_model::resolve(dir.path(), "barretenberg", Some("1.0.0")).unwrap();
+        assert_eq!(found.samples, 3);
+        assert_eq!(source, path.display().to_string());
+    }
+
+    #[test]
+    fn test_fit_skips_anomalous_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut outlier = record("1.0.0", 1 << 12, 9000.0);
+        outlier.anomaly = true;
+        let records = vec![
+            record("1.0.0", 1 << 10, 100.0),
+            record("1.0.0", 1 << 12, 420.0),
+            record("1.0.0", 1 << 14, 1700.0),
+            outlier,
+        ];
+
+        let (model, _) = fit(&records, "bb", Some("1.0.0"), dir.path(), 100.0).unwrap();
+        assert_eq!(model.samples, 3);
+    }
+
+    #[test]
+    fn test_refit_stale_waits_for_new_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut records = vec![
+            record("1.0.0", 1 << 10, 100.0),
+            record("1.0.0", 1 << 12, 420.0),
+            record("1.0.0", 1 << 14, 1700.0),
+            // Not enough data for this version
+            record("2.0.0", 1 << 14, 1500.0),
+        ];
+
+        let written = refit_stale(&records, dir.path(), 2, 100.0);
+        assert_eq!(written.len(), 1);
+
+        // One new record is below min_new, two trigger a re-fit
+        records.push(record("1.0.0", 1 << 16, 7000.0));
+        assert!(refit_stale(&records, dir.path(), 2, 100.0).is_empty());
+        records.push(record("1.0.0", 1 << 16, 7100.0));
+        let written = refit_stale(&records, dir.path(), 2, 100.0);
+        assert_eq!(written.len(), 1);
+        assert_eq!(CostModel::load(&written[0]).unwrap().samples, 5);
+    }
+}
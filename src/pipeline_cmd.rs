@@ -0,0 +1,679 @@
+//! CLI command handler for `pipeline`.
+//!
+//! A pipeline file lists the stages of one run (compile, gates, prove,
+//! evm-verify) in order. Each stage picks up what the earlier ones produced:
+//! the compiled artifact, its gate analysis, the proof and its public
+//! inputs. A stage can be made conditional on a metric measured so far
+//! (`when: "gates < 100000"`) or `optional`, so its failure is recorded
+//! without stopping the run. The run produces one consolidated record.
+//!
+//! ```yaml
+//! project: circuits/rollup
+//! stages:
+//!   - stage: compile
+//!   - stage: gates
+//!   - stage: prove
+//!     iterations: 3
+//!   - stage: evm-verify
+//!     when: "gates < 2000000"
+//!     toolchain: foundry
+//!     project_dir: contracts
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig, GateInfo};
+use crate::core::signing::load_signing_key;
+use crate::core::{BackendInfo, BenchRecord, EnvironmentInfo, RunConfig, RunError, TimingStat};
+use crate::engine::workflow::VerifyStatus;
+use crate::engine::{NargoToolchain, ProveInputs, Toolchain, full_benchmark};
+use crate::evm_verify_cmd::{self, EvmToolchain, EvmToolchainKind};
+use crate::report::alerts::{CompareOp, known_metrics, metric_value};
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult, EvmVerifyReport, proof_cmd};
+
+/// Default pipeline file.
+pub const DEFAULT_CONFIG: &str = "pipeline.yaml";
+
+/// Metadata key listing each stage's outcome, e.g.
+/// `compile=ok,gates=ok,prove=ok,evm-verify=skipped`.
+pub const STAGES_METADATA_KEY: &str = "pipeline_stages";
+
+/// Metrics a `when` condition can use besides the alert rule metrics.
+const PIPELINE_METRICS: [&str; 2] = ["evm_gas", "verify_ok"];
+
+#[derive(Debug, Deserialize)]
+pub struct PipelineConfig {
+    /// Circuit name on the record (default: the project directory or
+    /// artifact file name)
+    pub name: Option<String>,
+    /// Noir project built by the compile stage
+    pub project: Option<PathBuf>,
+    /// Compiled artifact, for pipelines without a compile stage
+    pub artifact: Option<PathBuf>,
+    /// Proof (with `public_inputs` next to it), for pipelines without a
+    /// prove stage
+    pub proof: Option<PathBuf>,
+    /// Inputs (default: `Prover.toml` in the project)
+    pub prover_toml: Option<PathBuf>,
+    pub nargo_path: Option<PathBuf>,
+    pub backend_path: Option<PathBuf>,
+    pub backend_args: Option<Vec<String>>,
+    /// Proving timeout
+    pub timeout_secs: Option<u64>,
+    pub stages: Vec<StageConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StageKind {
+    Compile,
+    Gates,
+    Prove,
+    EvmVerify,
+}
+
+impl StageKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            StageKind::Compile => "compile",
+            StageKind::Gates => "gates",
+            StageKind::Prove => "prove",
+            StageKind::EvmVerify => "evm-verify",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StageConfig {
+    pub stage: StageKind,
+    /// Run only if `<metric> <op> <value>` holds for what earlier stages
+    /// measured
+    pub when: Option<String>,
+    /// Record a failure and go on with the next stage
+    #[serde(default)]
+    pub optional: bool,
+    /// prove: measured and warmup iterations
+    pub iterations: Option<usize>,
+    pub warmup: Option<usize>,
+    /// evm-verify: foundry (default), hardhat or revm
+    #[serde(default)]
+    pub toolchain: EvmToolchainKind,
+    /// evm-verify: Foundry or Hardhat project
+    pub project_dir: Option<PathBuf>,
+    /// evm-verify: test name pattern
+    #[serde(rename = "match")]
+    pub test_pattern: Option<String>,
+    /// evm-verify: verifier bytecode for revm
+    pub bytecode: Option<PathBuf>,
+    pub forge_bin: Option<PathBuf>,
+    pub gas_per_second: Option<u64>,
+}
+
+/// A parsed `when` condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub metric: String,
+    op: CompareOp,
+    pub threshold: f64,
+}
+
+impl Condition {
+    /// Parse `<metric> <op> <value>`, e.g. `gates < 100000`.
+    pub fn parse(expr: &str) -> BenchResult<Self> {
+        let invalid = |why: &str| BenchError::Message(format!("invalid condition '{expr}': {why}"));
+        let (at, tok, op) = expr
+            .char_indices()
+            .find_map(|(i, _)| {
+                CompareOp::ALL
+                    .iter()
+                    .find(|(tok, _)| expr[i..].starts_with(tok))
+                    .map(|(tok, op)| (i, *tok, *op))
+            })
+            .ok_or_else(|| invalid("expected one of >=, <=, ==, !=, >, <"))?;
+        let metric = expr[..at].trim();
+        if !known_metrics().iter().any(|m| m == metric) && !PIPELINE_METRICS.contains(&metric) {
+            return Err(invalid(&format!("unknown metric '{metric}'")));
+        }
+        let value = expr[at + tok.len()..].trim();
+        let threshold = value
+            .parse()
+            .map_err(|_| invalid(&format!("'{value}' is not a number")))?;
+        Ok(Condition {
+            metric: metric.to_string(),
+            op,
+            threshold,
+        })
+    }
+
+    /// Whether the condition holds for `record`; `None` when the metric
+    /// hasn't been measured.
+    pub fn holds(&self, record: &BenchRecord) -> Option<bool> {
+        let value = match self.metric.as_str() {
+            "evm_gas" => record.metadata.get("evm_gas")?.parse().ok()?,
+            "verify_ok" => match record.metadata.get("verify_ok")?.as_str() {
+                "true" => 1.0,
+                _ => 0.0,
+            },
+            metric => metric_value(record, metric)?,
+        };
+        Some(self.op.holds(value, self.threshold))
+    }
+}
+
+impl PipelineConfig {
+    /// Read and check a pipeline file.
+    pub fn load(path: &Path) -> BenchResult<Self> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?;
+        let config: PipelineConfig = serde_yaml::from_slice(&bytes)
+            .map_err(|e| BenchError::Message(format!("failed to parse {}: {e}", path.display())))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that every stage's conditions parse and its inputs come from
+    /// the file or an earlier stage.
+    pub fn validate(&self) -> BenchResult<()> {
+        if self.stages.is_empty() {
+            return Err(BenchError::Message("pipeline has no stages".into()));
+        }
+        let mut has_artifact = self.artifact.is_some();
+        let mut has_proof = self.proof.is_some();
+        for (i, stage) in self.stages.iter().enumerate() {
+            let name = stage.stage.name();
+            let missing =
+                |what: &str| BenchError::Message(format!("stage {} ({name}) needs {what}", i + 1));
+            if let Some(when) = &stage.when {
+                Condition::parse(when)?;
+            }
+            match stage.stage {
+                StageKind::Compile => {
+                    if self.project.is_none() {
+                        return Err(missing("`project`"));
+                    }
+                    has_artifact = true;
+                }
+                StageKind::Gates | StageKind::Prove => {
+                    if !has_artifact {
+                        return Err(missing("`artifact` or an earlier compile stage"));
+                    }
+                    has_proof |= stage.stage == StageKind::Prove;
+                }
+                StageKind::EvmVerify => {
+                    if !has_proof {
+                        return Err(missing("`proof` or an earlier prove stage"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Circuit name on the record.
+    fn circuit_name(&self) -> String {
+        let from_path = |p: &Path| p.file_stem().map(|s| s.to_string_lossy().into_owned());
+        self.name
+            .clone()
+            .or_else(|| self.project.as_deref().and_then(from_path))
+            .or_else(|| self.artifact.as_deref().and_then(from_path))
+            .unwrap_or_else(|| "pipeline".to_string())
+    }
+
+    fn prover_toml(&self) -> PathBuf {
+        self.prover_toml
+            .clone()
+            .unwrap_or_else(|| match &self.project {
+                Some(project) => project.join("Prover.toml"),
+                None => PathBuf::from("Prover.toml"),
+            })
+    }
+}
+
+/// What the stages hand to each other.
+#[derive(Debug, Default)]
+struct Handoff {
+    artifact: Option<PathBuf>,
+    gate_info: Option<GateInfo>,
+    proof: Option<PathBuf>,
+}
+
+impl Handoff {
+    fn artifact(&self) -> BenchResult<&Path> {
+        self.artifact.as_deref().ok_or_else(|| {
+            BenchError::Message("no artifact: the compile stage was skipped or failed".into())
+        })
+    }
+
+    fn proof(&self) -> BenchResult<&Path> {
+        self.proof.as_deref().ok_or_else(|| {
+            BenchError::Message("no proof: the prove stage was skipped or failed".into())
+        })
+    }
+}
+
+fn run_stage(
+    stage: &StageConfig,
+    config: &PipelineConfig,
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+    handoff: &mut Handoff,
+    record: &mut BenchRecord,
+) -> BenchResult<()> {
+    match stage.stage {
+        StageKind::Compile => {
+            let project = config.project.as_deref().unwrap_or(Path::new("."));
+            let compiled = toolchain.compile(project)?;
+            record.compile_stats =
+                Some(TimingStat::from_samples(&[compiled.compile_time_ms as f64]));
+            record.circuit_path = Some(compiled.artifact_path.to_string_lossy().to_string());
+            handoff.artifact = Some(compiled.artifact_path);
+        }
+        StageKind::Gates => {
+            let start = Instant::now();
+            let info = backend.gate_info(handoff.artifact()?)?;
+            record.gates_stats = Some(TimingStat::from_samples(&[
+                start.elapsed().as_secs_f64() * 1000.0
+            ]));
+            record.total_gates = Some(info.backend_gates);
+            record.acir_opcodes = info.acir_opcodes;
+            record.subgroup_size = info.subgroup_size;
+            handoff.gate_info = Some(info);
+        }
+        StageKind::Prove => {
+            let mut inputs = ProveInputs::new(handoff.artifact()?, &record.circuit_name)
+                .with_prover_toml(config.prover_toml());
+            if let Some(secs) = config.timeout_secs {
+                inputs = inputs.with_timeout(Duration::from_secs(secs));
+            }
+            if let Some(info) = &handoff.gate_info {
+                inputs = inputs.with_gate_info(info.clone());
+            }
+            let result = full_benchmark(
+                toolchain,
+                backend,
+                &inputs,
+                stage.warmup.unwrap_or(0),
+                stage.iterations.unwrap_or(1),
+            )?;
+            // The prove record has everything but what earlier stages measured
+            let mut proved = result.record;
+            proved.compile_stats = record.compile_stats.take();
+            proved.gates_stats = proved.gates_stats.or(record.gates_stats.take());
+            proved.metadata.append(&mut record.metadata);
+            // Only set when verification ran, so a skipped one reads as not
+            // measured rather than as a failure
+            let verified = match &result.verify_status {
+                VerifyStatus::Ok => Some(true),
+                VerifyStatus::Failed(_) => Some(false),
+                VerifyStatus::SkippedUnsupported | VerifyStatus::SkippedMissingArtifacts => None,
+            };
+            if let Some(ok) = verified {
+                proved
+                    .metadata
+                    .insert("verify_ok".to_string(), ok.to_string());
+            }
+            *record = proved;
+            // The record keeps `verify_ok=false`; the stage still fails
+            if let VerifyStatus::Failed(why) = &result.verify_status {
+                return Err(BenchError::Message(format!("proof did not verify: {why}")));
+            }
+            handoff.proof = result.proof_path;
+        }
+        StageKind::EvmVerify => {
+            let proof = handoff.proof()?.to_path_buf();
+            let public_inputs = Some(proof.with_file_name("public_inputs")).filter(|p| p.exists());
+            // revm encodes the call itself
+            let verify_call = match stage.toolchain {
+                EvmToolchainKind::Revm => None,
+                _ => proof_cmd::load_verify_call(None, Some(&proof), public_inputs.as_deref())?,
+            };
+            let evm = EvmToolchain::from_args(
+                stage.toolchain,
+                stage.forge_bin.clone(),
+                stage.bytecode.clone(),
+                None,
+                Some(proof),
+                public_inputs,
+            )?;
+            let dir = tempfile::tempdir()
+                .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+            let json = dir.path().join("evm-verify.json");
+            evm_verify_cmd::run(
+                evm,
+                stage.project_dir.clone(),
+                handoff.artifact.clone(),
+                stage.test_pattern.clone(),
+                verify_call,
+                None,
+                stage.gas_per_second,
+                Some(json.clone()),
+            )?;
+            let report: EvmVerifyReport = std::fs::read(&json)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                .ok_or_else(|| BenchError::Message("evm-verify wrote no report".into()))?;
+            record
+                .metadata
+                .insert("evm_gas".to_string(), report.gas_used.to_string());
+            if let Some(bytes) = report.calldata_bytes {
+                record
+                    .metadata
+                    .insert("evm_calldata_bytes".to_string(), bytes.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the stages of `config` in order and return the consolidated record.
+/// A required stage that fails stops the pipeline and sets `record.error`.
+pub fn execute(
+    config: &PipelineConfig,
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+) -> BenchRecord {
+    let backend_info = BackendInfo {
+        name: backend.name().to_string(),
+        version: backend.version(),
+        variant: None,
+    };
+    let mut record = BenchRecord::new(
+        config.circuit_name(),
+        EnvironmentInfo::detect_with_bb_path(config.backend_path.as_deref()),
+        backend_info,
+        RunConfig::default(),
+    );
+    record.circuit_path = config
+        .artifact
+        .as_ref()
+        .map(|p| p.to_string_lossy().to_string());
+    let mut handoff = Handoff {
+        artifact: config.artifact.clone(),
+        proof: config.proof.clone(),
+        ..Handoff::default()
+    };
+
+    let mut outcomes: Vec<(&str, &str)> = Vec::new();
+    for stage in &config.stages {
+        let name = stage.stage.name();
+        let condition = stage.when.as_deref().map(Condition::parse).transpose();
+        let skip = match &condition {
+            Ok(Some(cond)) => match cond.holds(&record) {
+                Some(holds) => !holds,
+                None => {
+                    eprintln!("pipeline: {name}: {} not measured, skipping", cond.metric);
+                    true
+                }
+            },
+            _ => false,
+        };
+        if skip {
+            eprintln!("pipeline: {name}: skipped");
+            outcomes.push((name, "skipped"));
+            continue;
+        }
+
+        eprintln!("pipeline: {name}");
+        let result = condition
+            .and_then(|_| run_stage(stage, config, toolchain, backend, &mut handoff, &mut record));
+        match result {
+            Ok(()) => outcomes.push((name, "ok")),
+            Err(e) if stage.optional => {
+                eprintln!("pipeline: {name} failed (optional): {e}");
+                outcomes.push((name, "failed"));
+            }
+            Err(e) => {
+                eprintln!("pipeline: {name} failed: {e}");
+                outcomes.push((name, "failed"));
+                record.error = Some(RunError::from_error(&e));
+                break;
+            }
+        }
+    }
+
+    let stages: Vec<String> = outcomes
+        .iter()
+        .map(|(name, outcome)| format!("{name}={outcome}"))
+        .collect();
+    record
+        .metadata
+        .insert(STAGES_METADATA_KEY.to_string(), stages.join(","));
+    record
+}
+
+/// Main entry point for `pipeline`.
+pub fn run(
+    config_path: PathBuf,
+    json_out: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    sign_key: Option<PathBuf>,
+) -> BenchResult<()> {
+    let config = PipelineConfig::load(&config_path)?;
+    let toolchain = match &config.nargo_path {
+        Some(path) => NargoToolchain::with_path(path),
+        None => NargoToolchain::new(),
+    };
+    let mut bb = BarretenbergConfig::new(
+        config
+            .backend_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("bb")),
+    )
+    .with_args(config.backend_args.clone().unwrap_or_default());
+    if let Some(secs) = config.timeout_secs {
+        bb = bb.with_timeout(Duration::from_secs(secs));
+    }
+    let backend = BarretenbergBackend::new(bb);
+
+    let mut record = execute(&config, &toolchain, &backend);
+    record
+        .metadata
+        .insert("pipeline".to_string(), config_path.display().to_string());
+
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        let bytes = serde_json::to_vec_pretty(&record)
+            .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+        std::fs::write(&json, bytes)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", json.display())))?;
+    }
+    if let Some(jsonl) = jsonl_out {
+        let mut writer = JsonlWriter::new(&jsonl);
+        if let Some(key) = &sign_key {
+            writer = writer.with_signing_key(load_signing_key(key)?);
+        }
+        writer.append(&record)?;
+    }
+
+    let mut summary = format!(
+        "pipeline: {} {}",
+        record.circuit_name, record.metadata[STAGES_METADATA_KEY]
+    );
+    if let Some(gates) = record.total_gates {
+        summary.push_str(&format!(" gates={gates}"));
+    }
+    if let Some(stats) = &record.prove_stats {
+        summary.push_str(&format!(" prove_ms_avg={:.2}", stats.mean_ms));
+    }
+    if let Some(gas) = record.metadata.get("evm_gas") {
+        summary.push_str(&format!(" evm_gas={gas}"));
+    }
+    println!("{summary}");
+
+    match &record.error {
+        Some(error) => Err(BenchError::Message(format!(
+            "pipeline failed: {}",
+            error.message
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig, VerifyOutput};
+    use crate::engine::MockToolchain;
+
+    fn config(yaml: &str) -> PipelineConfig {
+        let config: PipelineConfig = serde_yaml::from_str(yaml).unwrap();
+        config.validate().unwrap();
+        config
+    }
+
+    #[test]
+    fn test_condition_parse_and_holds() {
+        let cond = Condition::parse("gates >= 1000").unwrap();
+        assert_eq!(cond.metric, "gates");
+        assert_eq!(cond.threshold, 1000.0);
+
+        let mut record = BenchRecord::new(
+            "rollup".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "barretenberg".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        record.total_gates = Some(1000);
+        assert_eq!(cond.holds(&record), Some(true));
+        assert_eq!(
+            Condition::parse("gates<1000").unwrap().holds(&record),
+            Some(false)
+        );
+        record.total_gates = None;
+        assert_eq!(cond.holds(&record), None);
+
+        record.metadata.insert("verify_ok".into(), "true".into());
+        assert_eq!(
+            Condition::parse("verify_ok == 1").unwrap().holds(&record),
+            Some(true)
+        );
+        assert!(Condition::parse("gates 1000").is_err());
+        assert!(Condition::parse("widgets > 1").is_err());
+        assert!(Condition::parse("gates > lots").is_err());
+    }
+
+    #[test]
+    fn test_validate_checks_handoff() {
+        let yaml = "project: circuits/rollup\nstages:\n  - stage: prove\n";
+        let config: PipelineConfig = serde_yaml::from_str(yaml).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("stage 1 (prove) needs"), "{err}");
+
+        let yaml = "artifact: program.json\nstages:\n  - stage: gates\n  - stage: evm-verify\n";
+        let config: PipelineConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.validate().is_err());
+
+        let yaml = "proof: proof\nstages:\n  - stage: evm-verify\n    toolchain: anvil\n";
+        assert!(serde_yaml::from_str::<PipelineConfig>(yaml).is_err());
+
+        let yaml = "project: circuits/rollup\nstages:\n  - stage: bench\n";
+        assert!(serde_yaml::from_str::<PipelineConfig>(yaml).is_err());
+    }
+
+    #[test]
+    fn test_pipeline_hands_off_and_skips() {
+        let config = config(
+            "project: circuits/rollup
+stages:
+  - stage: compile
+  - stage: gates
+  - stage: prove
+    when: \"gates < 5000\"
+    iterations: 2
+  - stage: evm-verify
+    when: \"gates > 5000\"
+    project_dir: contracts
+",
+        );
+        let record = execute(
+            &config,
+            &MockToolchain::new(),
+            &MockBackend::new(MockConfig::new("mock-bb")),
+        );
+
+        assert!(record.error.is_none());
+        assert_eq!(record.circuit_name, "rollup");
+        assert_eq!(
+            record.circuit_path.as_deref(),
+            Some("/tmp/mock-artifact.json")
+        );
+        assert_eq!(record.compile_stats.as_ref().unwrap().mean_ms, 50.0);
+        assert_eq!(record.total_gates, Some(1000));
+        assert_eq!(record.prove_stats.as_ref().unwrap().iterations, 2);
+        assert_eq!(
+            record.metadata[STAGES_METADATA_KEY],
+            "compile=ok,gates=ok,prove=ok,evm-verify=skipped"
+        );
+    }
+
+    #[test]
+    fn test_required_stage_failure_stops_pipeline() {
+        let config = config(
+            "artifact: program.json
+stages:
+  - stage: gates
+    optional: true
+  - stage: prove
+  - stage: evm-verify
+    project_dir: contracts
+",
+        );
+        let mut mock = MockConfig::new("mock-bb").prove_fails();
+        mock.gate_info_fails = true;
+        let record = execute(&config, &MockToolchain::new(), &MockBackend::new(mock));
+
+        assert_eq!(
+            record.metadata[STAGES_METADATA_KEY],
+            "gates=failed,prove=failed"
+        );
+        assert!(record.error.unwrap().message.contains("mock prove failed"));
+        assert_eq!(record.circuit_name, "program");
+    }
+
+    #[test]
+    fn test_failed_verification_is_recorded() {
+        let config = config(
+            "artifact: program.json
+stages:
+  - stage: prove
+    optional: true
+  - stage: evm-verify
+    when: \"verify_ok == 1\"
+    project_dir: contracts
+",
+        );
+        let mut mock = MockConfig::new("mock-bb").with_verify_output(VerifyOutput {
+            verify_time_ms: 50,
+            success: false,
+        });
+        let prove_output = mock.prove_output.as_mut().unwrap();
+        prove_output.proof_path = Some(PathBuf::from("/tmp/mock-proof"));
+        prove_output.vk_path = Some(PathBuf::from("/tmp/mock-vk"));
+        let record = execute(&config, &MockToolchain::new(), &MockBackend::new(mock));
+
+        assert!(record.error.is_none());
+        assert_eq!(record.metadata["verify_ok"], "false");
+        assert_eq!(
+            record.metadata[STAGES_METADATA_KEY],
+            "prove=failed,evm-verify=skipped"
+        );
+
+        // Without a proof to verify, verify_ok isn't measured
+        let record = execute(
+            &config,
+            &MockToolchain::new(),
+            &MockBackend::new(MockConfig::new("mock-bb")),
+        );
+        assert!(!record.metadata.contains_key("verify_ok"));
+    }
+}
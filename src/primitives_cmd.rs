@@ -0,0 +1,536 @@
+//! CLI command handler for `primitives`.
+//!
+//! Generates tiny Noir projects that each exercise one black-box function at a
+//! given input size, compiles them with the toolchain, and measures gates (and
+//! optionally proving) with the backend. The result is a primitive cost table
+//! written as JSON and markdown.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::BenchRecord;
+use crate::core::signing::load_signing_key;
+use crate::engine::{NargoToolchain, ProveInputs, Toolchain, full_benchmark};
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult};
+
+/// Default output directory for generated projects and the cost table.
+pub const DEFAULT_OUT_DIR: &str = "out/primitives";
+
+/// Version of the cost table JSON format.
+pub const COST_TABLE_VERSION: u32 = 1;
+
+/// A black-box function with the input sizes it is benchmarked at.
+#[derive(Debug, Clone, Copy)]
+pub struct Primitive {
+    pub name: &'static str,
+    /// What `size` counts (e.g. "byte", "permutation")
+    pub unit: &'static str,
+    pub default_sizes: &'static [u32],
+}
+
+/// Built-in primitives.
+pub const PRIMITIVES: &[Primitive] = &[
+    Primitive {
+        name: "sha256",
+        unit: "compression",
+        default_sizes: &[1, 4, 16],
+    },
+    Primitive {
+        name: "keccak",
+        unit: "permutation",
+        default_sizes: &[1, 4, 16],
+    },
+    Primitive {
+        name: "blake2s",
+        unit: "byte",
+        default_sizes: &[32, 128, 512],
+    },
+    Primitive {
+        name: "blake3",
+        unit: "byte",
+        default_sizes: &[32, 128, 512],
+    },
+    Primitive {
+        name: "pedersen",
+        unit: "field",
+        default_sizes: &[2, 8, 32],
+    },
+    Primitive {
+        name: "poseidon2",
+        unit: "permutation",
+        default_sizes: &[1, 4, 16],
+    },
+    Primitive {
+        name: "ecdsa_secp256k1",
+        unit: "verification",
+        default_sizes: &[1],
+    },
+];
+
+// Valid secp256k1 signature over sha256("noir-bench") so the ecdsa circuit executes.
+const ECDSA_PUB_KEY_X: &str = "0c726b1a58a38ddc2d3902d524015eeb79a00d3d3883b98ddc1de30c4479f16a";
+const ECDSA_PUB_KEY_Y: &str = "5d0210a72eda2c7f10a7753680dfea93d9f12c3bbf951f9bea74629a51fba78f";
+const ECDSA_SIGNATURE: &str = "d24fb2bcb8f636cd9d5dbe1413d97f7e56907a03b51fea6eb38ee1c576a3fc852590e2cc8ece088cfb22d71316d2de7ac207c3d1ae7febf023ec69a2ae8bc46f";
+const ECDSA_MESSAGE_HASH: &str = "1d875bc56510e0817724f04a2fa829f7d865d866ba4a6291e4a2dec16331a4a2";
+
+// SHA-256 initial hash values
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Look up a primitive by name.
+pub fn find_primitive(name: &str) -> BenchResult<Primitive> {
+    PRIMITIVES
+        .iter()
+        .find(|p| p.name == name)
+        .copied()
+        .ok_or_else(|| {
+            let known: Vec<_> = PRIMITIVES.iter().map(|p| p.name).collect();
+            BenchError::Message(format!(
+                "unknown primitive '{name}' (known: {})",
+                known.join(", ")
+            ))
+        })
+}
+
+/// Noir source for `primitive` at `size`.
+///
+/// Repeated calls chain their outputs so the compiler can't deduplicate them.
+pub fn circuit_source(primitive: &str, size: u32) -> BenchResult<String> {
+    let src = match primitive {
+        "sha256" => format!(
+            "fn main(input: [u32; 16], state: [u32; 8]) -> pub [u32; 8] {{\n    let mut s = state;\n    for _ in 0..{size} {{\n        s = std::hash::sha256_compression(input, s);\n    }}\n    s\n}}\n"
+        ),
+        "keccak" => format!(
+            "fn main(input: [u64; 25]) -> pub [u64; 25] {{\n    let mut s = input;\n    for _ in 0..{size} {{\n        s = std::hash::keccakf1600(s);\n    }}\n    s\n}}\n"
+        ),
+        "blake2s" | "blake3" => format!(
+            "fn main(input: [u8; {size}]) -> pub [u8; 32] {{\n    std::hash::{primitive}(input)\n}}\n"
+        ),
+        "pedersen" => format!(
+            "fn main(input: [Field; {size}]) -> pub Field {{\n    std::hash::pedersen_hash(input)\n}}\n"
+        ),
+        "poseidon2" => format!(
+            "fn main(input: [Field; 4]) -> pub [Field; 4] {{\n    let mut s = input;\n    for _ in 0..{size} {{\n        s = std::hash::poseidon2_permutation(s, 4);\n    }}\n    s\n}}\n"
+        ),
+        "ecdsa_secp256k1" => format!(
+            "fn main(\n    pub_key_x: [u8; 32],\n    pub_key_y: [u8; 32],\n    signature: [u8; 64],\n    message_hash: [u8; 32],\n) -> pub bool {{\n    let mut ok = true;\n    for _ in 0..{size} {{\n        ok &= std::ecdsa_secp256k1::verify_signature(pub_key_x, pub_key_y, signature, message_hash);\n    }}\n    ok\n}}\n"
+        ),
+        other => {
+            return Err(BenchError::Message(format!(
+                "no circuit template for primitive '{other}'"
+            )));
+        }
+    };
+    Ok(src)
+}
+
+fn toml_array<T: std::fmt::Display>(values: impl IntoIterator<Item = T>) -> String {
+    let items: Vec<String> = values.into_iter().map(|v| v.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn hex_bytes(s: &str) -> String {
+    toml_array(hex::decode(s).expect("valid hex constant"))
+}
+
+/// Prover.toml inputs for `primitive` at `size`.
+pub fn prover_toml(primitive: &str, size: u32) -> BenchResult<String> {
+    let toml = match primitive {
+        "sha256" => format!(
+            "input = {}\nstate = {}\n",
+            toml_array(0..16u32),
+            toml_array(SHA256_IV)
+        ),
+        "keccak" => format!("input = {}\n", toml_array(0..25u64)),
+        "blake2s" | "blake3" => format!("input = {}\n", toml_array((0..size).map(|i| i % 256))),
+        "pedersen" => format!("input = {}\n", toml_array(1..=size)),
+        "poseidon2" => format!("input = {}\n", toml_array(1..=4u32)),
+        "ecdsa_secp256k1" => format!(
+            "pub_key_x = {}\npub_key_y = {}\nsignature = {}\nmessage_hash = {}\n",
+            hex_bytes(ECDSA_PUB_KEY_X),
+            hex_bytes(ECDSA_PUB_KEY_Y),
+            hex_bytes(ECDSA_SIGNATURE),
+            hex_bytes(ECDSA_MESSAGE_HASH)
+        ),
+        other => {
+            return Err(BenchError::Message(format!(
+                "no inputs for primitive '{other}'"
+            )));
+        }
+    };
+    Ok(toml)
+}
+
+fn write_file(path: &Path, contents: &str) -> BenchResult<()> {
+    std::fs::write(path, contents)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+}
+
+/// Write a Nargo project for `primitive` at `size` under `out_dir`.
+///
+/// Returns the project directory (`<out_dir>/<primitive>_<size>`).
+pub fn generate_project(out_dir: &Path, primitive: &str, size: u32) -> BenchResult<PathBuf> {
+    let name = format!("{primitive}_{size}");
+    let dir = out_dir.join(&name);
+    std::fs::create_dir_all(dir.join("src"))
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", dir.display())))?;
+    write_file(
+        &dir.join("Nargo.toml"),
+        &format!(
+            "[package]\nname = \"{name}\"\ntype = \"bin\"\nauthors = [\"\"]\n\n[dependencies]\n"
+        ),
+    )?;
+    write_file(&dir.join("src/main.nr"), &circuit_source(primitive, size)?)?;
+    write_file(&dir.join("Prover.toml"), &prover_toml(primitive, size)?)?;
+    Ok(dir)
+}
+
+/// One row of the cost table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimitiveCost {
+    pub primitive: String,
+    pub size: u32,
+    pub unit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acir_opcodes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gates: Option<u64>,
+    /// Gates divided by `size`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gates_per_unit: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prove_ms: Option<f64>,
+    /// "ok" or "failed: <reason>"
+    pub status: String,
+}
+
+/// Gate/prove cost of each primitive at each size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrimitiveCostTable {
+    pub version: u32,
+    pub generated_at: String,
+    pub backend: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nargo_version: Option<String>,
+    pub rows: Vec<PrimitiveCost>,
+}
+
+impl PrimitiveCostTable {
+    /// Render the table as markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("## Primitive cost table\n\n");
+        out.push_str(&format!(
+            "Backend: {} {} | nargo: {}\n\n",
+            self.backend,
+            self.backend_version.as_deref().unwrap_or(""),
+            self.nargo_version.as_deref().unwrap_or("unknown")
+        ));
+        out.push_str(
+            "| Primitive | Size | ACIR opcodes | Gates | Gates/unit | Prove (ms) | Status |\n",
+        );
+        out.push_str("|---|---:|---:|---:|---:|---:|---|\n");
+        let opt = |v: Option<u64>| v.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+        for r in &self.rows {
+            out.push_str(&format!(
+                "| {} | {} {}{} | {} | {} | {} | {} | {} |\n",
+                r.primitive,
+                r.size,
+                r.unit,
+                if r.size == 1 { "" } else { "s" },
+                opt(r.acir_opcodes),
+                opt(r.gates),
+                r.gates_per_unit
+                    .map(|v| format!("{v:.1}"))
+                    .unwrap_or_else(|| "-".to_string()),
+                r.prove_ms
+                    .map(|v| format!("{v:.1}"))
+                    .unwrap_or_else(|| "-".to_string()),
+                r.status
+            ));
+        }
+        out
+    }
+}
+
+/// Parse `--only` into primitives; `None` selects all of them.
+pub fn select_primitives(only: Option<&[String]>) -> BenchResult<Vec<Primitive>> {
+    match only {
+        None => Ok(PRIMITIVES.to_vec()),
+        Some(names) => names.iter().map(|n| find_primitive(n.trim())).collect(),
+    }
+}
+
+fn now_string() -> String {
+    time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_else(|_| "".to_string())
+}
+
+/// Generate, compile and measure every (primitive, size) pair.
+///
+/// Without `prove` only gate counts are collected. Failures are recorded in
+/// the row's status and don't stop the remaining pairs. Returns the table and
+/// a BenchRecord per successful pair (tagged with `primitive`/`size` metadata).
+pub fn build_cost_table(
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+    primitives: &[Primitive],
+    sizes: Option<&[u32]>,
+    out_dir: &Path,
+    prove: bool,
+    warmup: usize,
+    iterations: usize,
+) -> (PrimitiveCostTable, Vec<BenchRecord>) {
+    let mut rows = Vec::new();
+    let mut records = Vec::new();
+
+    for primitive in primitives {
+        for &size in sizes.unwrap_or(primitive.default_sizes) {
+            eprintln!("primitives: {} size={}", primitive.name, size);
+            let mut row = PrimitiveCost {
+                primitive: primitive.name.to_string(),
+                size,
+                unit: primitive.unit.to_string(),
+                acir_opcodes: None,
+                gates: None,
+                gates_per_unit: None,
+                prove_ms: None,
+                status: "ok".to_string(),
+            };
+            let measured = measure_one(
+                toolchain, backend, out_dir, primitive, size, prove, warmup, iterations,
+            );
+            match measured {
+                Ok((gates, acir_opcodes, record)) => {
+                    row.gates = gates;
+                    row.acir_opcodes = acir_opcodes;
+                    row.gates_per_unit = gates.map(|g| g as f64 / size.max(1) as f64);
+                    if let Some(record) = record {
+                        row.prove_ms = record.prove_stats.as_ref().map(|s| s.mean_ms);
+                        records.push(record);
+                    }
+                }
+                Err(e) => row.status = format!("failed: {e}"),
+            }
+            rows.push(row);
+        }
+    }
+
+    let table = PrimitiveCostTable {
+        version: COST_TABLE_VERSION,
+        generated_at: now_string(),
+        backend: backend.name().to_string(),
+        backend_version: backend.version(),
+        nargo_version: toolchain.version().ok(),
+        rows,
+    };
+    (table, records)
+}
+
+fn measure_one(
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+    out_dir: &Path,
+    primitive: &Primitive,
+    size: u32,
+    prove: bool,
+    warmup: usize,
+    iterations: usize,
+) -> BenchResult<(Option<u64>, Option<u64>, Option<BenchRecord>)> {
+    let project = generate_project(out_dir, primitive.name, size)?;
+    let artifact = toolchain.compile(&project)?.artifact_path;
+
+    if !prove {
+        let info = backend.gate_info(&artifact)?;
+        return Ok((Some(info.backend_gates), info.acir_opcodes, None));
+    }
+
+    let inputs = ProveInputs::new(&artifact, format!("{}_{}", primitive.name, size))
+        .with_prover_toml(project.join("Prover.toml"))
+        .with_timeout(Duration::from_secs(24 * 60 * 60));
+    let mut result = full_benchmark(toolchain, backend, &inputs, warmup, iterations)?;
+    result
+        .record
+        .metadata
+        .insert("primitive".to_string(), primitive.name.to_string());
+    result
+        .record
+        .metadata
+        .insert("primitive_size".to_string(), size.to_string());
+    result.record.params = Some(size as u64);
+    Ok((result.constraints, result.acir_opcodes, Some(result.record)))
+}
+
+/// Main entry point for the primitives command.
+///
+/// Records appended to `jsonl_out` are signed with `sign_key` when given.
+pub fn run(
+    out_dir: PathBuf,
+    only: Option<Vec<String>>,
+    sizes: Option<Vec<u32>>,
+    prove: bool,
+    iterations: Option<usize>,
+    warmup: Option<usize>,
+    nargo_path: Option<PathBuf>,
+    backend_path: PathBuf,
+    jsonl_out: Option<PathBuf>,
+    sign_key: Option<PathBuf>,
+) -> BenchResult<()> {
+    let primitives = select_primitives(only.as_deref())?;
+    std::fs::create_dir_all(&out_dir)
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", out_dir.display())))?;
+    let toolchain = match nargo_path {
+        Some(path) => NargoToolchain::with_path(path),
+        None => NargoToolchain::new(),
+    };
+    let backend = BarretenbergBackend::new(
+        BarretenbergConfig::new(backend_path).with_timeout(Duration::from_secs(24 * 60 * 60)),
+    );
+
+    let (table, records) = build_cost_table(
+        &toolchain,
+        &backend,
+        &primitives,
+        sizes.as_deref(),
+        &out_dir,
+        prove,
+        warmup.unwrap_or(0),
+        iterations.unwrap_or(1),
+    );
+
+    let json_path = out_dir.join("primitive_costs.json");
+    let json = serde_json::to_string_pretty(&table)
+        .map_err(|e| BenchError::Message(format!("failed to serialize cost table: {e}")))?;
+    write_file(&json_path, &json)?;
+    let md_path = out_dir.join("primitive_costs.md");
+    let markdown = table.to_markdown();
+    write_file(&md_path, &markdown)?;
+    eprintln!(
+        "Wrote cost table to {} and {}",
+        json_path.display(),
+        md_path.display()
+    );
+
+    if let Some(jsonl) = jsonl_out {
+        let mut writer = JsonlWriter::new(&jsonl);
+        if let Some(key) = &sign_key {
+            writer = writer.with_signing_key(load_signing_key(key)?);
+        }
+        for record in &records {
+            writer.append(record)?;
+        }
+    }
+
+    println!("{markdown}");
+
+    if table.rows.iter().all(|r| r.status != "ok") {
+        return Err(BenchError::Message(
+            "every primitive benchmark failed".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{MockBackend, MockConfig};
+    use crate::engine::MockToolchain;
+
+    #[test]
+    fn test_every_primitive_has_source_and_inputs() {
+        for p in PRIMITIVES {
+            for &size in p.default_sizes {
+                let src = circuit_source(p.name, size).unwrap();
+                assert!(src.contains("fn main("), "{}", p.name);
+                let inputs = prover_toml(p.name, size).unwrap();
+                assert!(inputs.parse::<toml::Table>().is_ok(), "{}", p.name);
+            }
+        }
+        assert!(circuit_source("md5", 1).is_err());
+    }
+
+    #[test]
+    fn test_byte_inputs_match_size() {
+        let inputs = prover_toml("blake2s", 128).unwrap();
+        let table = inputs.parse::<toml::Table>().unwrap();
+        assert_eq!(table["input"].as_array().unwrap().len(), 128);
+        assert!(
+            circuit_source("blake2s", 128)
+                .unwrap()
+                .contains("[u8; 128]")
+        );
+
+        let ecdsa = prover_toml("ecdsa_secp256k1", 1).unwrap();
+        let table = ecdsa.parse::<toml::Table>().unwrap();
+        assert_eq!(table["signature"].as_array().unwrap().len(), 64);
+    }
+
+    #[test]
+    fn test_select_primitives() {
+        assert_eq!(select_primitives(None).unwrap().len(), PRIMITIVES.len());
+        let only = vec!["keccak".to_string(), " sha256".to_string()];
+        let selected = select_primitives(Some(&only)).unwrap();
+        assert_eq!(selected[0].name, "keccak");
+        assert_eq!(selected[1].name, "sha256");
+        let err = select_primitives(Some(&["md5".to_string()])).unwrap_err();
+        assert!(err.to_string().contains("known: sha256"), "{err}");
+    }
+
+    #[test]
+    fn test_build_cost_table_with_mocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let primitives = vec![find_primitive("pedersen").unwrap()];
+
+        let (table, records) = build_cost_table(
+            &MockToolchain::new(),
+            &MockBackend::new(MockConfig::new("mock-bb")),
+            &primitives,
+            Some(&[2, 8]),
+            dir.path(),
+            false,
+            0,
+            1,
+        );
+        assert!(records.is_empty());
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1].gates, Some(1000));
+        assert_eq!(table.rows[1].gates_per_unit, Some(125.0));
+        assert!(dir.path().join("pedersen_8/src/main.nr").exists());
+        assert!(table.to_markdown().contains("| pedersen | 8 fields |"));
+
+        let (table, records) = build_cost_table(
+            &MockToolchain::new(),
+            &MockBackend::new(MockConfig::new("mock-bb")),
+            &primitives,
+            Some(&[2]),
+            dir.path(),
+            true,
+            0,
+            1,
+        );
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].metadata["primitive"], "pedersen");
+        assert_eq!(table.rows[0].prove_ms, Some(100.0));
+
+        let (table, _) = build_cost_table(
+            &MockToolchain::new().failing(),
+            &MockBackend::new(MockConfig::new("mock-bb")),
+            &primitives,
+            Some(&[2]),
+            dir.path(),
+            false,
+            0,
+            1,
+        );
+        assert!(table.rows[0].status.starts_with("failed"));
+    }
+}
@@ -0,0 +1,435 @@
+//! CLI command handler for `proof convert`: re-encode a bb proof.
+//!
+//! bb writes proofs as raw bytes (`--output_format bytes`) or as a JSON array
+//! of 32-byte field elements (`--output_format fields`); verifier contracts
+//! and scripts want them as hex, or as the ABI-encoded calldata of
+//! `verify(bytes,bytes32[])` with the public inputs. `convert` reads any of
+//! these and writes another, reporting the size of every encoding. The
+//! calldata encoding is also what `evm-verify --proof` hands to Foundry and
+//! Hardhat tests.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{BenchError, BenchResult};
+
+/// Solidity signature of the verifier entry point generated by bb.
+pub const VERIFY_SIGNATURE: &str = "verify(bytes,bytes32[])";
+
+/// First four bytes of the keccak256 of [`VERIFY_SIGNATURE`].
+pub const VERIFY_SELECTOR: [u8; 4] = [0xea, 0x50, 0xd0, 0xe4];
+
+/// Bytes per field element in the `fields` encoding and per public input.
+const FIELD_BYTES: usize = 32;
+
+/// How a proof is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofEncoding {
+    /// Binary, as `bb prove` writes it
+    Raw,
+    /// `0x`-prefixed hex text
+    Hex,
+    /// JSON array of 32-byte field elements as hex strings
+    Fields,
+    /// Hex of the ABI-encoded `verify(bytes,bytes32[])` call, public inputs
+    /// included
+    Calldata,
+}
+
+impl ProofEncoding {
+    /// Names accepted by `--from` and `--to`.
+    pub const NAMES: [&'static str; 4] = ["raw", "hex", "fields", "calldata"];
+
+    pub fn parse(name: &str) -> BenchResult<Self> {
+        match name {
+            "raw" => Ok(ProofEncoding::Raw),
+            "hex" => Ok(ProofEncoding::Hex),
+            "fields" => Ok(ProofEncoding::Fields),
+            "calldata" => Ok(ProofEncoding::Calldata),
+            other => Err(BenchError::Message(format!(
+                "unknown proof encoding '{other}' (expected {})",
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+
+    /// Guess the encoding of `bytes`: a JSON array is `fields`, hex text is
+    /// `calldata` when it decodes as a verify call and `hex` otherwise, and
+    /// anything else is `raw`.
+    pub fn detect(bytes: &[u8]) -> Self {
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            return ProofEncoding::Raw;
+        };
+        let text = text.trim();
+        if text.starts_with('[') {
+            return ProofEncoding::Fields;
+        }
+        match decode_hex(text) {
+            Some(decoded) if decode_verify_call(&decoded).is_ok() => ProofEncoding::Calldata,
+            Some(_) if !text.is_empty() => ProofEncoding::Hex,
+            _ => ProofEncoding::Raw,
+        }
+    }
+}
+
+/// Bytes of a hex string, with or without a `0x` prefix and surrounding
+/// whitespace.
+pub(crate) fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    hex::decode(text.strip_prefix("0x").unwrap_or(text)).ok()
+}
+
+fn word(v: usize) -> [u8; 32] {
+    let mut w = [0u8; 32];
+    w[24..].copy_from_slice(&(v as u64).to_be_bytes());
+    w
+}
+
+/// ABI-encode `verify(bytes proof, bytes32[] publicInputs)`.
+pub fn encode_verify_call(proof: &[u8], public_inputs: &[u8]) -> BenchResult<Vec<u8>> {
+    if public_inputs.len() % FIELD_BYTES != 0 {
+        return Err(BenchError::Message(format!(
+            "public inputs are {} bytes, not a multiple of 32",
+            public_inputs.len()
+        )));
+    }
+    let padded_proof = proof.len().div_ceil(32) * 32;
+    let mut out = VERIFY_SELECTOR.to_vec();
+    out.extend(word(0x40));
+    out.extend(word(0x40 + 32 + padded_proof));
+    out.extend(word(proof.len()));
+    out.extend(proof);
+    out.resize(out.len() + padded_proof - proof.len(), 0);
+    out.extend(word(public_inputs.len() / FIELD_BYTES));
+    out.extend(public_inputs);
+    Ok(out)
+}
+
+/// Split a `verify(bytes,bytes32[])` call into the proof and the public
+/// inputs.
+pub fn decode_verify_call(call: &[u8]) -> BenchResult<(Vec<u8>, Vec<u8>)> {
+    let invalid = |why: &str| BenchError::Message(format!("not a verify call: {why}"));
+    let Some(args) = call.strip_prefix(&VERIFY_SELECTOR[..]) else {
+        return Err(invalid("wrong selector"));
+    };
+    let read_word = |offset: usize| -> BenchResult<usize> {
+        let w = args
+            .get(offset..offset + 32)
+            .ok_or_else(|| invalid("truncated"))?;
+        if w[..28].iter().any(|&b| b != 0) {
+            return Err(invalid("offset or length out of range"));
+        }
+        Ok(u32::from_be_bytes(w[28..].try_into().unwrap()) as usize)
+    };
+    let proof_at = read_word(0)?;
+    let inputs_at = read_word(32)?;
+    let proof_len = read_word(proof_at)?;
+    let proof = args
+        .get(proof_at + 32..proof_at + 32 + proof_len)
+        .ok_or_else(|| invalid("truncated proof"))?;
+    let count = read_word(inputs_at)?;
+    let inputs = args
+        .get(inputs_at + 32..inputs_at + 32 + count * FIELD_BYTES)
+        .ok_or_else(|| invalid("truncated public inputs"))?;
+    Ok((proof.to_vec(), inputs.to_vec()))
+}
+
+/// Field elements of a `fields` JSON array, concatenated.
+fn decode_fields(text: &str) -> BenchResult<Vec<u8>> {
+    let fields: Vec<String> = serde_json::from_str(text)
+        .map_err(|e| BenchError::Message(format!("invalid fields JSON: {e}")))?;
+    let mut bytes = Vec::with_capacity(fields.len() * FIELD_BYTES);
+    for field in fields {
+        let value = decode_hex(&field)
+            .filter(|v| v.len() <= FIELD_BYTES)
+            .ok_or_else(|| BenchError::Message(format!("invalid field element '{field}'")))?;
+        bytes.resize(bytes.len() + FIELD_BYTES - value.len(), 0);
+        bytes.extend(value);
+    }
+    Ok(bytes)
+}
+
+fn encode_fields(bytes: &[u8]) -> BenchResult<String> {
+    if bytes.len() % FIELD_BYTES != 0 {
+        return Err(BenchError::Message(format!(
+            "proof is {} bytes, not a whole number of field elements",
+            bytes.len()
+        )));
+    }
+    let fields: Vec<String> = bytes
+        .chunks(FIELD_BYTES)
+        .map(|f| format!("0x{}", hex::encode(f)))
+        .collect();
+    Ok(serde_json::to_string(&fields).unwrap())
+}
+
+/// A proof and its public inputs, decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    pub proof: Vec<u8>,
+    /// 32 bytes per input; empty when not known
+    pub public_inputs: Vec<u8>,
+}
+
+impl Proof {
+    /// Decode `bytes` stored as `encoding` (detected when `None`). Only
+    /// calldata carries public inputs.
+    pub fn decode(bytes: &[u8], encoding: Option<ProofEncoding>) -> BenchResult<Self> {
+        let encoding = encoding.unwrap_or_else(|| ProofEncoding::detect(bytes));
+        let text = || {
+            std::str::from_utf8(bytes)
+                .map(str::trim)
+                .map_err(|_| BenchError::Message("proof is not text".into()))
+        };
+        let proof = match encoding {
+            ProofEncoding::Raw => bytes.to_vec(),
+            ProofEncoding::Hex => decode_hex(text()?)
+                .ok_or_else(|| BenchError::Message("proof is not valid hex".into()))?,
+            ProofEncoding::Fields => decode_fields(text()?)?,
+            ProofEncoding::Calldata => {
+                let call = decode_hex(text()?)
+                    .ok_or_else(|| BenchError::Message("calldata is not valid hex".into()))?;
+                let (proof, public_inputs) = decode_verify_call(&call)?;
+                return Ok(Proof {
+                    proof,
+                    public_inputs,
+                });
+            }
+        };
+        Ok(Proof {
+            proof,
+            public_inputs: Vec::new(),
+        })
+    }
+
+    /// Read the proof at `path`, with public inputs from `public_inputs`
+    /// (raw, hex or fields) replacing any carried by the proof.
+    pub fn read(
+        path: &Path,
+        encoding: Option<ProofEncoding>,
+        public_inputs: Option<&Path>,
+    ) -> BenchResult<Self> {
+        let mut proof = Proof::decode(&read(path)?, encoding)
+            .map_err(|e| BenchError::Message(format!("{}: {e}", path.display())))?;
+        if let Some(path) = public_inputs {
+            proof.public_inputs = Proof::decode(&read(path)?, None)
+                .map_err(|e| BenchError::Message(format!("{}: {e}", path.display())))?
+                .proof;
+        }
+        Ok(proof)
+    }
+
+    /// The `verify(bytes,bytes32[])` call for this proof.
+    pub fn verify_call(&self) -> BenchResult<Vec<u8>> {
+        encode_verify_call(&self.proof, &self.public_inputs)
+    }
+
+    /// The proof stored as `encoding`; text encodings get no trailing newline.
+    pub fn encode(&self, encoding: ProofEncoding) -> BenchResult<Vec<u8>> {
+        Ok(match encoding {
+            ProofEncoding::Raw => self.proof.clone(),
+            ProofEncoding::Hex => format!("0x{}", hex::encode(&self.proof)).into_bytes(),
+            ProofEncoding::Fields => encode_fields(&self.proof)?.into_bytes(),
+            ProofEncoding::Calldata => {
+                format!("0x{}", hex::encode(self.verify_call()?)).into_bytes()
+            }
+        })
+    }
+
+    /// Size of the proof in every encoding.
+    pub fn sizes(&self) -> BenchResult<ProofSizes> {
+        let call = self.verify_call()?;
+        Ok(ProofSizes {
+            raw_bytes: self.proof.len() as u64,
+            hex_bytes: 2 + 2 * self.proof.len() as u64,
+            fields_bytes: encode_fields(&self.proof).ok().map(|f| f.len() as u64),
+            calldata_bytes: call.len() as u64,
+            calldata_hex_bytes: 2 + 2 * call.len() as u64,
+            calldata_gas: crate::calldata_cmd::analyze(&call)?.calldata_gas,
+            public_inputs: (self.public_inputs.len() / FIELD_BYTES) as u64,
+        })
+    }
+}
+
+/// Sizes of one proof in each encoding.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofSizes {
+    pub raw_bytes: u64,
+    pub hex_bytes: u64,
+    /// `None` when the proof isn't a whole number of field elements
+    pub fields_bytes: Option<u64>,
+    /// ABI-encoded verify call, as sent on chain
+    pub calldata_bytes: u64,
+    /// The call as hex text, as written by `--to calldata`
+    pub calldata_hex_bytes: u64,
+    /// EIP-2028 gas of the call's calldata
+    pub calldata_gas: u64,
+    pub public_inputs: u64,
+}
+
+/// `proof convert --json` report.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofConvertReport {
+    pub input: PathBuf,
+    pub from: ProofEncoding,
+    pub to: ProofEncoding,
+    pub output: Option<PathBuf>,
+    pub sizes: ProofSizes,
+}
+
+fn read(path: &Path) -> BenchResult<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))
+}
+
+/// The verify call from hex `calldata`, or encoded from `proof` and
+/// `public_inputs`; `None` when neither is given.
+pub fn load_verify_call(
+    calldata: Option<&Path>,
+    proof: Option<&Path>,
+    public_inputs: Option<&Path>,
+) -> BenchResult<Option<Vec<u8>>> {
+    if let Some(path) = calldata {
+        let text = String::from_utf8(read(path)?)
+            .map_err(|_| BenchError::Message(format!("{} is not text", path.display())))?;
+        return decode_hex(text.trim())
+            .map(Some)
+            .ok_or_else(|| BenchError::Message(format!("invalid hex in {}", path.display())));
+    }
+    match proof {
+        Some(path) => Ok(Some(Proof::read(path, None, public_inputs)?.verify_call()?)),
+        None => Ok(None),
+    }
+}
+
+/// Run `proof convert`: re-encode `input` as `to`, written to `out` (or
+/// stdout for text encodings), and report the size of every encoding.
+pub fn run_convert(
+    input: PathBuf,
+    public_inputs: Option<PathBuf>,
+    from: Option<String>,
+    to: String,
+    out: Option<PathBuf>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let from = match from {
+        Some(name) => ProofEncoding::parse(&name)?,
+        None => ProofEncoding::detect(&read(&input)?),
+    };
+    let to = ProofEncoding::parse(&to)?;
+    let proof = Proof::read(&input, Some(from), public_inputs.as_deref())?;
+    let encoded = proof.encode(to)?;
+    match &out {
+        Some(path) => {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).ok();
+            }
+            std::fs::write(path, &encoded).map_err(|e| {
+                BenchError::Message(format!("failed to write {}: {e}", path.display()))
+            })?;
+        }
+        None if to == ProofEncoding::Raw => {
+            return Err(BenchError::Message(
+                "raw proofs are binary; pass --out to write one".into(),
+            ));
+        }
+        None => println!("{}", String::from_utf8_lossy(&encoded)),
+    }
+
+    let sizes = proof.sizes()?;
+    let report = ProofConvertReport {
+        input,
+        from,
+        to,
+        output: out,
+        sizes,
+    };
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        std::fs::write(&json, serde_json::to_vec_pretty(&report).unwrap())
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", json.display())))?;
+    }
+    let s = &report.sizes;
+    let fields = s
+        .fields_bytes
+        .map(|b| b.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    eprintln!(
+        "proof: raw={} hex={} fields={} calldata={} (hex {}, {} gas, {} public inputs)",
+        s.raw_bytes,
+        s.hex_bytes,
+        fields,
+        s.calldata_bytes,
+        s.calldata_hex_bytes,
+        s.calldata_gas,
+        s.public_inputs
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Proof {
+        Proof {
+            proof: (0..64u8).collect(),
+            public_inputs: [[0u8; 31].as_slice(), &[7]].concat(),
+        }
+    }
+
+    #[test]
+    fn test_encodings_round_trip() {
+        let proof = sample();
+        for encoding in [
+            ProofEncoding::Raw,
+            ProofEncoding::Hex,
+            ProofEncoding::Fields,
+        ] {
+            let bytes = proof.encode(encoding).unwrap();
+            assert_eq!(ProofEncoding::detect(&bytes), encoding);
+            assert_eq!(Proof::decode(&bytes, None).unwrap().proof, proof.proof);
+        }
+        let call = proof.encode(ProofEncoding::Calldata).unwrap();
+        assert_eq!(ProofEncoding::detect(&call), ProofEncoding::Calldata);
+        assert_eq!(Proof::decode(&call, None).unwrap(), proof);
+
+        let fields = String::from_utf8(proof.encode(ProofEncoding::Fields).unwrap()).unwrap();
+        assert!(fields.starts_with("[\"0x000102"));
+        // Short field elements are left-padded to 32 bytes
+        let short = Proof::decode(b"[\"0x07\"]", None).unwrap();
+        assert_eq!(short.proof, sample().public_inputs);
+    }
+
+    #[test]
+    fn test_verify_call_layout() {
+        let call = encode_verify_call(&[0xaa; 33], &[0x11; 64]).unwrap();
+        assert_eq!(call[..4], VERIFY_SELECTOR);
+        assert_eq!(call.len(), 4 + 32 * 8);
+        let (proof, inputs) = decode_verify_call(&call).unwrap();
+        assert_eq!((proof.len(), inputs.len()), (33, 64));
+        assert!(decode_verify_call(&call[..100]).is_err());
+        assert!(decode_verify_call(&[0; 68]).is_err());
+    }
+
+    #[test]
+    fn test_sizes() {
+        let sizes = sample().sizes().unwrap();
+        assert_eq!(sizes.raw_bytes, 64);
+        assert_eq!(sizes.hex_bytes, 130);
+        assert_eq!(sizes.fields_bytes, Some(2 * 68 + 3));
+        assert_eq!(sizes.calldata_bytes, 4 + 32 * 3 + 64 + 32);
+        assert_eq!(sizes.public_inputs, 1);
+
+        let odd = Proof {
+            proof: vec![1; 33],
+            public_inputs: Vec::new(),
+        };
+        assert_eq!(odd.sizes().unwrap().fields_bytes, None);
+        assert!(odd.encode(ProofEncoding::Fields).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
@@ -5,7 +6,6 @@ use std::time::{Duration, Instant};
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use nargo::foreign_calls::DefaultForeignCallBuilder;
 use noir_artifact_cli::execution::execute as execute_program_artifact;
-use noir_artifact_cli::fs::artifact::read_program_from_file;
 use noir_artifact_cli::fs::witness::save_witness_to_dir;
 
 use crate::{
@@ -13,9 +13,15 @@ use crate::{
     collect_system_info, compute_iteration_stats,
 };
 // New unified backend abstraction
-use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::backend::{
+    Backend, BarretenbergBackend, BarretenbergConfig, MetricScraper, mean_metrics,
+    parse_metric_spec, scrape_metrics,
+};
+use crate::core::process::Supervised;
+use crate::core::{AMORTIZED_SETUP_MODE, KeyCacheStatus, RunStatus};
 // New engine workflow
 use crate::engine::{self, NargoToolchain, ProveInputs, Toolchain};
+use crate::storage::run_logs::command_failure;
 use shlex::Shlex;
 
 /// Provider trait for proving operations.
@@ -67,62 +73,13 @@ impl BarretenbergProverProvider {
         mut cmd: Command,
         timeout: Duration,
     ) -> BenchResult<(std::process::ExitStatus, Option<u64>)> {
-        #[cfg(feature = "mem")]
-        use sysinfo::{ProcessRefreshKind, RefreshKind, System};
-
-        let start = Instant::now();
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| BenchError::Message(e.to_string()))?;
-
-        #[cfg(feature = "mem")]
-        let mut sys = System::new_with_specifics(
-            RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
-        );
-        #[cfg(feature = "mem")]
-        let mut peak_rss: u64 = 0;
-
-        loop {
-            if let Some(status) = child
-                .try_wait()
-                .map_err(|e| BenchError::Message(e.to_string()))?
-            {
-                #[cfg(feature = "mem")]
-                {
-                    // final sample
-                    if let Some(pid) = child.id().try_into().ok().map(sysinfo::Pid::from_u32) {
-                        sys.refresh_process(pid);
-                        if let Some(p) = sys.process(pid) {
-                            peak_rss = peak_rss.max(p.memory() * 1024);
-                        }
-                    }
-                }
-                return Ok((status, {
-                    #[cfg(feature = "mem")]
-                    {
-                        Some(peak_rss)
-                    }
-                    #[cfg(not(feature = "mem"))]
-                    {
-                        None
-                    }
-                }));
-            }
-            if timeout.as_secs() > 0 && start.elapsed() >= timeout {
-                let _ = child.kill();
-                let _ = child.wait();
-                return Err(BenchError::Message("prove timed out".into()));
-            }
-            #[cfg(feature = "mem")]
-            {
-                if let Some(pid) = child.id().try_into().ok().map(sysinfo::Pid::from_u32) {
-                    sys.refresh_process(pid);
-                    if let Some(p) = sys.process(pid) {
-                        peak_rss = peak_rss.max(p.memory() * 1024);
-                    }
-                }
-            }
-            std::thread::sleep(Duration::from_millis(50));
+        let child = Supervised::spawn(&mut cmd).map_err(|e| BenchError::Message(e.to_string()))?;
+        match child
+            .wait_timeout(timeout)
+            .map_err(|e| BenchError::Message(e.to_string()))?
+        {
+            Some(done) => Ok((done.status, done.peak_memory_bytes)),
+            None => Err(BenchError::Message("prove timed out".into())),
         }
     }
 }
@@ -134,12 +91,11 @@ impl ProverProvider for BarretenbergProverProvider {
         inputs: Option<&Path>,
         timeout: Duration,
     ) -> BenchResult<ProveReport> {
-        // Read artifact
-        let program =
-            read_program_from_file(artifact).map_err(|e| BenchError::Message(e.to_string()))?;
+        // Parsed artifact, shared across iterations
+        let program = engine::load_program(artifact)?;
 
         // Generate witness from inputs using in-process execution
-        let compiled: noirc_artifacts::program::CompiledProgram = program.clone().into();
+        let compiled = engine::load_compiled(artifact)?;
         let prover_file = inputs.map(|p| p.with_extension("toml"));
         let prover_file = prover_file
             .as_ref()
@@ -152,7 +108,7 @@ impl ProverProvider for BarretenbergProverProvider {
             &mut DefaultForeignCallBuilder::default().build(),
             prover_file,
         )
-        .map_err(|e| BenchError::Message(format!("execution for witness failed: {e}")))?;
+        .map_err(|e| engine::witness_error("execution for witness failed", e))?;
         let witness_ms = witness_start.elapsed().as_millis();
 
         let tempdir = tempfile::tempdir().map_err(|e| BenchError::Message(e.to_string()))?;
@@ -196,8 +152,6 @@ impl ProverProvider for BarretenbergProverProvider {
         let verification_key_size_bytes = std::fs::metadata(&vk_file).ok().map(|m| m.len());
         let proving_key_size_bytes = std::fs::metadata(&pk_file).ok().map(|m| m.len());
 
-        let artifact_bytes = std::fs::read(artifact).ok();
-        let inputs_bytes = inputs.and_then(|p| std::fs::read(p).ok());
         let meta = CommonMeta {
             name: "prove".into(),
             timestamp: time::OffsetDateTime::now_utc()
@@ -206,8 +160,8 @@ impl ProverProvider for BarretenbergProverProvider {
             noir_version: program.noir_version.clone(),
             artifact_path: artifact.to_path_buf(),
             cli_args: std::env::args().collect(),
-            artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-            inputs_sha256: inputs_bytes.as_ref().map(|b| crate::sha256_hex(b)),
+            artifact_sha256: engine::artifact_sha256(artifact),
+            inputs_sha256: inputs.and_then(crate::file_sha256),
         };
         let report = ProveReport {
             meta,
@@ -222,6 +176,16 @@ impl ProverProvider for BarretenbergProverProvider {
             backend: self.backend_info(),
             system: Some(collect_system_info()),
             iterations: None,
+            oracle_resolver_ms: None,
+            backend_setup_ms: None,
+            prove_mode: None,
+            backend_phases: None,
+            vk_gen_time_ms: None,
+            vk_cached: None,
+            key_cache: None,
+            custom_metrics: BTreeMap::new(),
+            status: None,
+            failure_message: None,
         };
         Ok(report)
     }
@@ -245,6 +209,8 @@ impl ProverProvider for BarretenbergProverProvider {
 pub struct GenericProverProvider {
     pub command_template: String,
     pub extra_args: Vec<String>,
+    /// Extra metrics scraped from the command's stdout
+    pub custom_metrics: Vec<MetricScraper>,
 }
 
 impl GenericProverProvider {
@@ -283,12 +249,11 @@ impl ProverProvider for GenericProverProvider {
         &self,
         artifact: &Path,
         inputs: Option<&Path>,
-        _timeout: Duration,
+        timeout: Duration,
     ) -> BenchResult<ProveReport> {
         // Load artifact to get version and build witness using in-process, like Barretenberg flow
-        let program =
-            read_program_from_file(artifact).map_err(|e| BenchError::Message(e.to_string()))?;
-        let compiled: noirc_artifacts::program::CompiledProgram = program.clone().into();
+        let program = engine::load_program(artifact)?;
+        let compiled = engine::load_compiled(artifact)?;
         let prover_file = inputs.map(|p| p.with_extension("toml"));
         let prover_file = prover_file
             .as_ref()
@@ -300,32 +265,51 @@ impl ProverProvider for GenericProverProvider {
             &mut DefaultForeignCallBuilder::default().build(),
             prover_file,
         )
-        .map_err(|e| BenchError::Message(format!("execution for witness failed: {e}")))?;
+        .map_err(|e| engine::witness_error("execution for witness failed", e))?;
 
         let tempdir = tempfile::tempdir().map_err(|e| BenchError::Message(e.to_string()))?;
         let witness_path = save_witness_to_dir(&exec_res.witness_stack, "witness", tempdir.path())
             .map_err(|e| BenchError::Message(e.to_string()))?;
         let proof_path = tempdir.path().join("proof.bin");
 
+        // Output goes to files rather than pipes, which would fill up and
+        // stall the prover while it's only being waited on
+        let stdout_path = tempdir.path().join("prove.stdout");
+        let stderr_path = tempdir.path().join("prove.stderr");
+        let create = |path: &Path| {
+            std::fs::File::create(path).map_err(|e| {
+                BenchError::Message(format!("failed to create {}: {e}", path.display()))
+            })
+        };
         let mut cmd = self.build_command(artifact, &witness_path, &proof_path)?;
         cmd.stdin(Stdio::null())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        // crude timeout handling
-        let start = Instant::now();
-        let status = cmd
-            .status()
-            .map_err(|e| BenchError::Message(e.to_string()))?;
-        let prove_time_ms = start.elapsed().as_millis();
-        if !status.success() {
-            return Err(BenchError::Message(format!(
-                "generic prove failed: status={status}"
-            )));
+            .stdout(create(&stdout_path)?)
+            .stderr(create(&stderr_path)?);
+
+        let child = Supervised::spawn(&mut cmd).map_err(|e| {
+            BenchError::Message(format!(
+                "failed to run {}: {e}",
+                cmd.get_program().to_string_lossy()
+            ))
+        })?;
+        let finished = child
+            .wait_timeout(timeout)
+            .map_err(|e| BenchError::Message(e.to_string()))?
+            .ok_or_else(|| BenchError::Message("generic prove timed out".into()))?;
+        let stdout = std::fs::read(&stdout_path).unwrap_or_default();
+        let stderr = std::fs::read(&stderr_path).unwrap_or_default();
+        if !finished.status.success() {
+            return Err(command_failure(
+                "generic prove",
+                finished.status,
+                &stdout,
+                &stderr,
+                None,
+            ));
         }
+        let custom_metrics =
+            scrape_metrics(&self.custom_metrics, &String::from_utf8_lossy(&stdout));
         let proof_size_bytes = std::fs::metadata(&proof_path).ok().map(|m| m.len() as u64);
-        let artifact_bytes = std::fs::read(artifact).ok();
-        let inputs_bytes = inputs.and_then(|p| std::fs::read(p).ok());
         let meta = CommonMeta {
             name: "prove".into(),
             timestamp: time::OffsetDateTime::now_utc()
@@ -334,15 +318,15 @@ impl ProverProvider for GenericProverProvider {
             noir_version: program.noir_version.clone(),
             artifact_path: artifact.to_path_buf(),
             cli_args: std::env::args().collect(),
-            artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-            inputs_sha256: inputs_bytes.as_ref().map(|b| crate::sha256_hex(b)),
+            artifact_sha256: engine::artifact_sha256(artifact),
+            inputs_sha256: inputs.and_then(crate::file_sha256),
         };
         Ok(ProveReport {
             meta,
-            prove_time_ms,
+            prove_time_ms: finished.elapsed_ms,
             witness_gen_time_ms: None,
             backend_prove_time_ms: None,
-            peak_memory_bytes: None,
+            peak_memory_bytes: finished.peak_memory_bytes,
             proof_size_bytes,
             proving_key_size_bytes: None,
             verification_key_size_bytes: None,
@@ -353,6 +337,16 @@ impl ProverProvider for GenericProverProvider {
             },
             system: Some(collect_system_info()),
             iterations: None,
+            oracle_resolver_ms: None,
+            backend_setup_ms: None,
+            prove_mode: None,
+            backend_phases: None,
+            vk_gen_time_ms: None,
+            vk_cached: None,
+            key_cache: None,
+            custom_metrics,
+            status: None,
+            failure_message: None,
         })
     }
 
@@ -384,9 +378,8 @@ pub fn prove_with_backend<B: Backend>(
     timeout: Duration,
 ) -> BenchResult<ProveReport> {
     // Read artifact and generate witness
-    let program =
-        read_program_from_file(artifact).map_err(|e| BenchError::Message(e.to_string()))?;
-    let compiled: noirc_artifacts::program::CompiledProgram = program.clone().into();
+    let program = engine::load_program(artifact)?;
+    let compiled = engine::load_compiled(artifact)?;
     let prover_file = inputs.map(|p| p.with_extension("toml"));
     let prover_file = prover_file
         .as_ref()
@@ -400,7 +393,7 @@ pub fn prove_with_backend<B: Backend>(
         &mut DefaultForeignCallBuilder::default().build(),
         prover_file,
     )
-    .map_err(|e| BenchError::Message(format!("execution for witness failed: {e}")))?;
+    .map_err(|e| engine::witness_error("execution for witness failed", e))?;
     let witness_ms = witness_start.elapsed().as_millis();
 
     let tempdir = tempfile::tempdir().map_err(|e| BenchError::Message(e.to_string()))?;
@@ -410,8 +403,6 @@ pub fn prove_with_backend<B: Backend>(
     // Use the unified Backend trait
     let output = backend.prove(artifact, Some(&witness_path), timeout)?;
 
-    let artifact_bytes = std::fs::read(artifact).ok();
-    let inputs_bytes = inputs.and_then(|p| std::fs::read(p).ok());
     let meta = CommonMeta {
         name: "prove".into(),
         timestamp: time::OffsetDateTime::now_utc()
@@ -420,8 +411,8 @@ pub fn prove_with_backend<B: Backend>(
         noir_version: program.noir_version.clone(),
         artifact_path: artifact.to_path_buf(),
         cli_args: std::env::args().collect(),
-        artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-        inputs_sha256: inputs_bytes.as_ref().map(|b| crate::sha256_hex(b)),
+        artifact_sha256: engine::artifact_sha256(artifact),
+        inputs_sha256: inputs.and_then(crate::file_sha256),
     };
 
     let backend_info = BackendInfo {
@@ -442,6 +433,17 @@ pub fn prove_with_backend<B: Backend>(
         backend: backend_info,
         system: Some(collect_system_info()),
         iterations: None,
+        oracle_resolver_ms: None,
+        backend_setup_ms: output.setup_time_ms,
+        prove_mode: None,
+        backend_phases: output.phases,
+        vk_gen_time_ms: output.vk_gen_time_ms,
+        vk_cached: Some(output.vk_cached)
+            .filter(|_| output.vk_cached || output.vk_gen_time_ms.is_some()),
+        key_cache: output.key_cache,
+        custom_metrics: BTreeMap::new(),
+        status: None,
+        failure_message: None,
     })
 }
 
@@ -460,8 +462,7 @@ pub fn prove_with_engine<T: Toolchain, B: Backend>(
     timeout: Duration,
 ) -> BenchResult<ProveReport> {
     // Read artifact to get noir version for CommonMeta
-    let program =
-        read_program_from_file(artifact).map_err(|e| BenchError::Message(e.to_string()))?;
+    let program = engine::load_program(artifact)?;
 
     // Prepare workflow inputs
     let circuit_name = artifact
@@ -479,8 +480,6 @@ pub fn prove_with_engine<T: Toolchain, B: Backend>(
     let bench_record = engine::prove_only(toolchain, backend, &prove_inputs)?;
 
     // Convert BenchRecord to ProveReport for CLI compatibility
-    let artifact_bytes = std::fs::read(artifact).ok();
-    let inputs_bytes = inputs.and_then(|p| std::fs::read(p).ok());
 
     let meta = CommonMeta {
         name: "prove".into(),
@@ -488,8 +487,8 @@ pub fn prove_with_engine<T: Toolchain, B: Backend>(
         noir_version: program.noir_version.clone(),
         artifact_path: artifact.to_path_buf(),
         cli_args: std::env::args().collect(),
-        artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-        inputs_sha256: inputs_bytes.as_ref().map(|b| crate::sha256_hex(b)),
+        artifact_sha256: engine::artifact_sha256(artifact),
+        inputs_sha256: inputs.and_then(crate::file_sha256),
     };
 
     // Extract timing from BenchRecord's TimingStat
@@ -529,9 +528,84 @@ pub fn prove_with_engine<T: Toolchain, B: Backend>(
         backend: backend_info,
         system: Some(collect_system_info()),
         iterations: None,
+        oracle_resolver_ms: bench_record.oracle_stats.as_ref().map(|s| s.mean_ms),
+        backend_setup_ms: bench_record.backend_setup_ms.map(|ms| ms as u128),
+        prove_mode: None,
+        backend_phases: bench_record.backend_phases,
+        vk_gen_time_ms: bench_record
+            .vk_gen_stats
+            .as_ref()
+            .map(|s| (s.mean_ms * s.iterations as f64) as u128),
+        vk_cached: bench_record.vk_cached,
+        key_cache: bench_record.key_cache,
+        custom_metrics: bench_record.custom_metrics,
+        status: None,
+        failure_message: None,
     })
 }
 
+/// Report of an `--expect-failure` run: `prove_time_ms` is the mean time it
+/// took to detect the failure over the measured runs.
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> BenchResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| BenchError::Message(e.to_string()))?;
+    }
+    let json = serde_json::to_vec_pretty(value).map_err(|e| BenchError::Message(e.to_string()))?;
+    std::fs::write(path, json)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+}
+
+fn expected_failure_report(
+    artifact: &Path,
+    inputs: Option<&Path>,
+    backend: BackendInfo,
+    times: Vec<u128>,
+    iter_n: usize,
+    warmup_n: usize,
+    message: String,
+) -> ProveReport {
+    let meta = CommonMeta {
+        name: "prove".into(),
+        timestamp: time::OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default(),
+        noir_version: engine::load_program(artifact)
+            .map(|p| p.noir_version.clone())
+            .unwrap_or_default(),
+        artifact_path: artifact.to_path_buf(),
+        cli_args: std::env::args().collect(),
+        artifact_sha256: engine::artifact_sha256(artifact),
+        inputs_sha256: inputs.and_then(crate::file_sha256),
+    };
+    let time_ms = times.iter().sum::<u128>() / times.len().max(1) as u128;
+    let iterations =
+        (iter_n > 1 || warmup_n > 0).then(|| compute_iteration_stats(times, iter_n, warmup_n));
+    ProveReport {
+        meta,
+        prove_time_ms: time_ms,
+        witness_gen_time_ms: None,
+        backend_prove_time_ms: None,
+        peak_memory_bytes: None,
+        proof_size_bytes: None,
+        proving_key_size_bytes: None,
+        verification_key_size_bytes: None,
+        gate_count: None,
+        backend,
+        system: Some(collect_system_info()),
+        iterations,
+        oracle_resolver_ms: None,
+        backend_setup_ms: None,
+        prove_mode: None,
+        backend_phases: None,
+        vk_gen_time_ms: None,
+        vk_cached: None,
+        key_cache: None,
+        custom_metrics: BTreeMap::new(),
+        status: Some(RunStatus::ExpectedFailure),
+        failure_message: Some(message),
+    }
+}
+
 pub fn run(
     artifact: PathBuf,
     prover_toml: Option<PathBuf>,
@@ -539,10 +613,17 @@ pub fn run(
     backend_path: Option<PathBuf>,
     backend_args: Vec<String>,
     command_template: Option<String>,
+    custom_metrics: Vec<String>,
     timeout_secs: u64,
     iterations: Option<usize>,
     warmup: Option<usize>,
     json_out: Option<PathBuf>,
+    oracle_resolver: Option<String>,
+    amortize_setup: bool,
+    backend_verbose: bool,
+    key_cache: Option<PathBuf>,
+    cold_keys: bool,
+    expect_failure: bool,
 ) -> BenchResult<()> {
     let backend_name = backend.unwrap_or_else(|| "barretenberg".to_string());
     // Default to `bb` from PATH for the barretenberg backend when no path is provided.
@@ -563,24 +644,78 @@ pub fn run(
     let warmup_n = warmup.unwrap_or(0);
     let mut last_report: Option<ProveReport> = None;
     let mut times: Vec<u128> = Vec::new();
+    let mut setup_ms: Option<u128> = None;
+    let mut vk_gen_ms: Option<u128> = None;
+    let mut vk_cached: Option<bool> = None;
+    let mut key_cache_status = None;
+    let mut custom_runs: Vec<BTreeMap<String, f64>> = Vec::new();
+    let mut failure: Option<String> = None;
 
     // Create the unified backend for barretenberg (used for the new code path)
     let unified_backend: Option<BarretenbergBackend> =
         if backend_name == "barretenberg" && command_template.is_none() {
             backend_path.as_ref().map(|path| {
-                let config = BarretenbergConfig::new(path)
+                let mut config = BarretenbergConfig::new(path)
                     .with_args(backend_args.clone())
-                    .with_timeout(timeout);
+                    .with_timeout(timeout)
+                    .with_amortize_setup(amortize_setup)
+                    .with_verbose(backend_verbose)
+                    .with_cold_keys(cold_keys);
+                if let Some(dir) = &key_cache {
+                    config = config.with_key_cache(dir);
+                }
+                // Keep the output of failed bb runs next to the report
+                if let Some(dir) = json_out.as_ref().and_then(|j| j.parent()) {
+                    config = config.with_log_dir(dir.join("logs"));
+                }
                 BarretenbergBackend::new(config)
             })
         } else {
             None
         };
+    if let Some(bb) = &unified_backend {
+        bb.warn_on_args();
+    }
+
+    if amortize_setup && unified_backend.is_none() {
+        return Err(BenchError::Message(
+            "--amortize-setup is only supported with the barretenberg backend".into(),
+        ));
+    }
+    if backend_verbose && unified_backend.is_none() {
+        return Err(BenchError::Message(
+            "--backend-verbose is only supported with the barretenberg backend".into(),
+        ));
+    }
+    if key_cache.is_some() && unified_backend.is_none() {
+        return Err(BenchError::Message(
+            "--key-cache is only supported with the barretenberg backend".into(),
+        ));
+    }
+
+    if !custom_metrics.is_empty() && command_template.is_none() {
+        return Err(BenchError::Message(
+            "--custom-metric is only supported with --template".into(),
+        ));
+    }
+    let scrapers = custom_metrics
+        .iter()
+        .map(|spec| parse_metric_spec(spec))
+        .collect::<BenchResult<Vec<_>>>()?;
 
     // Create toolchain for engine workflow (uses nargo from PATH)
-    let toolchain = NargoToolchain::new();
+    let mut toolchain = NargoToolchain::new();
+    if let Some(url) = &oracle_resolver {
+        if unified_backend.is_none() {
+            return Err(BenchError::Message(
+                "--oracle-resolver is only supported with the barretenberg backend".into(),
+            ));
+        }
+        toolchain = toolchain.with_oracle_resolver(url);
+    }
 
     for i in 0..(warmup_n + iter_n) {
+        let start = Instant::now();
         let res = match (
             backend_name.as_str(),
             command_template.as_ref(),
@@ -608,6 +743,7 @@ pub fn run(
                 let provider = GenericProverProvider {
                     command_template: tpl.clone(),
                     extra_args: backend_args.clone(),
+                    custom_metrics: scrapers.clone(),
                 };
                 provider.prove(&artifact, prover_toml.as_deref(), timeout)
             }
@@ -617,28 +753,139 @@ pub fn run(
                 };
                 provider.prove(&artifact, prover_toml.as_deref(), timeout)
             }
-        }?;
+        };
+        if expect_failure {
+            let elapsed = start.elapsed().as_millis();
+            let e = match res {
+                Ok(_) => {
+                    return Err(BenchError::Message(
+                        "expected proving to fail (--expect-failure), but it succeeded".into(),
+                    ));
+                }
+                // Only the circuit rejecting its inputs counts; a missing
+                // backend, bad template or timeout is still an error.
+                Err(e @ BenchError::Unsatisfied(_)) => e,
+                Err(e) => return Err(e),
+            };
+            if i >= warmup_n {
+                times.push(elapsed);
+            }
+            failure = e.to_string().lines().next().map(str::to_string);
+            continue;
+        }
+        let res = res?;
         if i >= warmup_n {
             times.push(res.prove_time_ms);
+            custom_runs.push(res.custom_metrics.clone());
+        }
+        if let Some(ms) = res.backend_setup_ms {
+            *setup_ms.get_or_insert(0) += ms;
+        }
+        if let Some(ms) = res.vk_gen_time_ms {
+            *vk_gen_ms.get_or_insert(0) += ms;
+        }
+        if let Some(cached) = res.vk_cached {
+            *vk_cached.get_or_insert(false) |= cached;
         }
+        key_cache_status = key_cache_status.or(res.key_cache);
         last_report = Some(res);
     }
 
+    if expect_failure {
+        let backend = BackendInfo {
+            name: if command_template.is_some() {
+                "generic".to_string()
+            } else {
+                backend_name
+            },
+            version: None,
+        };
+        let report = expected_failure_report(
+            &artifact,
+            prover_toml.as_deref(),
+            backend,
+            times,
+            iter_n,
+            warmup_n,
+            failure.unwrap_or_default(),
+        );
+        if let Some(json) = json_out {
+            write_json(&json, &report)?;
+        }
+        println!(
+            "prove: backend={} failed as expected after {}ms: {}",
+            report.backend.name,
+            report.prove_time_ms,
+            report.failure_message.as_deref().unwrap_or_default()
+        );
+        return Ok(());
+    }
+
     let mut result = last_report.expect("at least one iteration");
+    result.backend_setup_ms = setup_ms;
+    result.vk_gen_time_ms = vk_gen_ms;
+    result.vk_cached = vk_cached;
+    result.key_cache = key_cache_status;
+    result.custom_metrics = mean_metrics(&custom_runs);
+    if amortize_setup {
+        result.prove_mode = Some(AMORTIZED_SETUP_MODE.to_string());
+    }
     if iter_n > 1 || warmup_n > 0 {
         let stats: IterationStats = compute_iteration_stats(times, iter_n, warmup_n);
         result.iterations = Some(stats);
     }
 
     if let Some(json) = json_out {
-        if let Some(dir) = json.parent() {
-            std::fs::create_dir_all(dir).ok();
-        }
-        std::fs::write(&json, serde_json::to_vec_pretty(&result).unwrap()).ok();
+        write_json(&json, &result)?;
     }
     println!(
         "prove: backend={} time={}ms size={:?}",
         result.backend.name, result.prove_time_ms, result.proof_size_bytes
     );
+    if let Some(ms) = result.oracle_resolver_ms {
+        println!("prove: oracle resolver {ms:.1}ms");
+    }
+    if let Some(ms) = result.backend_setup_ms {
+        println!("prove: backend setup {ms}ms (excluded from prove time)");
+    }
+    if let Some(ms) = result.vk_gen_time_ms {
+        let reuse = if result.vk_cached == Some(true) {
+            ", reused by later runs"
+        } else {
+            ""
+        };
+        println!("prove: vk generation {ms}ms (excluded from prove time{reuse})");
+    }
+    if let Some(status) = result.key_cache {
+        let status = match status {
+            KeyCacheStatus::Hit => "hit, keys loaded",
+            KeyCacheStatus::Miss => "miss, keys generated and stored",
+            KeyCacheStatus::Cold => "bypassed, keys regenerated and stored",
+        };
+        println!("prove: key cache {status}");
+    }
+    if let Some(phases) = &result.backend_phases {
+        let parts: Vec<String> = phases
+            .iter()
+            .map(|(phase, ms)| format!("{phase}={ms:.1}ms"))
+            .collect();
+        println!("prove: phases {}", parts.join(" "));
+    }
+    if !result.custom_metrics.is_empty() {
+        let parts: Vec<String> = result
+            .custom_metrics
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        println!("prove: custom metrics {}", parts.join(" "));
+    }
+    for scraper in &scrapers {
+        if !result.custom_metrics.contains_key(&scraper.name) {
+            eprintln!(
+                "warning: custom metric '{}' not found in backend output",
+                scraper.name
+            );
+        }
+    }
     Ok(())
 }
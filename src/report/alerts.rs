@@ -0,0 +1,633 @@
+//! Alerting rules evaluated over benchmark results.
+//!
+//! Rules come from the `[alerts]` section of bench-config.toml and are
+//! checked after each CI or daemon run:
+//!
+//! ```toml
+//! [alerts]
+//! webhook = "https://hooks.example.com/noir-bench"
+//!
+//! [[alerts.rule]]
+//! expr = 'prove_ms_p95{circuit="rollup"} > 30000'
+//! action = "fail"
+//!
+//! [[alerts.rule]]
+//! expr = "delta_pct(gates) > 5"
+//! action = "webhook"
+//! ```
+//!
+//! A rule is `<selector> <op> <number>`. The selector is a metric name with
+//! optional label matchers (`circuit`, `backend`, `params`; `=` or `!=`), or
+//! `delta_pct(<metric>{...})` for the percent change against the baseline
+//! record of the same circuit and params. The rule fires once per matching
+//! record whose value satisfies the comparison.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{BenchRecord, TimingStat};
+use crate::{BenchError, BenchResult};
+
+/// Timing metrics, each available as `<name>` (mean) and with a
+/// `_median`, `_p95`, `_min` or `_max` suffix.
+const TIMING_METRICS: &[&str] = &[
+    "prove_ms",
+    "witness_ms",
+    "verify_ms",
+    "compile_ms",
+    "compile_warm_ms",
+    "compile_incremental_ms",
+    "vk_gen_ms",
+];
+
+/// Scalar metrics (`gates` is an alias for `total_gates`).
+const SCALAR_METRICS: &[&str] = &[
+    "total_gates",
+    "gates",
+    "acir_opcodes",
+    "subgroup_size",
+    "proof_size_bytes",
+    "pk_size",
+    "vk_size",
+    "artifact_size_bytes",
+    "verify_cold_ms",
+    "peak_rss_mb",
+];
+
+const TIMING_SUFFIXES: &[&str] = &["median", "p95", "min", "max"];
+
+/// `[alerts]` section of bench-config.toml.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AlertsConfig {
+    /// Default URL for rules with the `webhook` action
+    #[serde(default)]
+    pub webhook: Option<String>,
+    #[serde(rename = "rule", default)]
+    pub rules: Vec<AlertRuleConfig>,
+}
+
+/// One `[[alerts.rule]]` entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRuleConfig {
+    pub expr: String,
+    #[serde(default)]
+    pub action: AlertAction,
+    /// Webhook URL for this rule, overriding `alerts.webhook`
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+/// What happens when a rule fires.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertAction {
+    /// Print a warning
+    #[default]
+    Warn,
+    /// Print an error and fail the run (exit code 1)
+    Fail,
+    /// POST the alert as JSON to the configured URL
+    Webhook,
+}
+
+impl fmt::Display for AlertAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertAction::Warn => write!(f, "warn"),
+            AlertAction::Fail => write!(f, "fail"),
+            AlertAction::Webhook => write!(f, "webhook"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    /// Operators, longest first so `>=` is not read as `>`.
+    pub(crate) const ALL: &[(&str, CompareOp)] = &[
+        (">=", CompareOp::Ge),
+        ("<=", CompareOp::Le),
+        ("==", CompareOp::Eq),
+        ("!=", CompareOp::Ne),
+        (">", CompareOp::Gt),
+        ("<", CompareOp::Lt),
+    ];
+
+    pub(crate) fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            CompareOp::Gt => value > threshold,
+            CompareOp::Ge => value >= threshold,
+            CompareOp::Lt => value < threshold,
+            CompareOp::Le => value <= threshold,
+            CompareOp::Eq => value == threshold,
+            CompareOp::Ne => value != threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LabelMatcher {
+    label: String,
+    value: String,
+    negate: bool,
+}
+
+impl LabelMatcher {
+    fn matches(&self, record: &BenchRecord) -> bool {
+        let actual = match self.label.as_str() {
+            "circuit" => Some(record.circuit_name.clone()),
+            "backend" => Some(record.backend.name.clone()),
+            "params" => record.params.map(|p| p.to_string()),
+            _ => None,
+        };
+        (actual.as_deref() == Some(self.value.as_str())) != self.negate
+    }
+}
+
+/// A parsed alert rule.
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub expr: String,
+    pub action: AlertAction,
+    pub webhook: Option<String>,
+    metric: String,
+    delta: bool,
+    labels: Vec<LabelMatcher>,
+    op: CompareOp,
+    threshold: f64,
+}
+
+/// A rule that fired for one record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FiredAlert {
+    pub rule: String,
+    pub action: AlertAction,
+    pub circuit_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<u64>,
+    pub value: f64,
+}
+
+impl FiredAlert {
+    pub fn describe(&self) -> String {
+        let circuit = match self.params {
+            Some(p) => format!("{}[{p}]", self.circuit_name),
+            None => self.circuit_name.clone(),
+        };
+        format!(
+            "{circuit}: {} (value {})",
+            self.rule,
+            format_number(self.value)
+        )
+    }
+}
+
+fn format_number(v: f64) -> String {
+    if v.fract() == 0.0 && v.abs() < 1e15 {
+        format!("{v:.0}")
+    } else {
+        format!("{v:.2}")
+    }
+}
+
+/// Metric names accepted in rules and budgets.
+pub fn known_metrics() -> Vec<String> {
+    let mut names: Vec<String> = TIMING_METRICS
+        .iter()
+        .flat_map(|m| {
+            std::iter::once(m.to_string())
+                .chain(TIMING_SUFFIXES.iter().map(move |s| format!("{m}_{s}")))
+        })
+        .collect();
+    names.extend(SCALAR_METRICS.iter().map(|m| m.to_string()));
+    names
+}
+
+fn timing_value(stat: Option<&TimingStat>, suffix: Option<&str>) -> Option<f64> {
+    let stat = stat?;
+    match suffix {
+        None => Some(stat.mean_ms),
+        Some("median") => stat.median_ms,
+        Some("p95") => stat.p95_ms,
+        Some("min") => Some(stat.min_ms),
+        Some("max") => Some(stat.max_ms),
+        Some(_) => None,
+    }
+}
+
+/// Value of a metric (as named in alert rules) for a record.
+pub fn metric_value(record: &BenchRecord, metric: &str) -> Option<f64> {
+    let (base, suffix) = match metric.rsplit_once('_') {
+        Some((base, suffix)) if TIMING_SUFFIXES.contains(&suffix) => (base, Some(suffix)),
+        _ => (metric, None),
+    };
+    let stat = match base {
+        "prove_ms" => Some(record.prove_stats.as_ref()),
+        "witness_ms" => Some(record.witness_stats.as_ref()),
+        "verify_ms" => Some(record.verify_stats.as_ref()),
+        "compile_ms" => Some(record.compile_stats.as_ref()),
+        "compile_warm_ms" => Some(record.compile_warm_stats.as_ref()),
+        "compile_incremental_ms" => Some(record.compile_incremental_stats.as_ref()),
+        "vk_gen_ms" => Some(record.vk_gen_stats.as_ref()),
+        _ => None,
+    };
+    if let Some(stat) = stat {
+        return timing_value(stat, suffix);
+    }
+    match metric {
+        "total_gates" | "gates" => record.total_gates.map(|v| v as f64),
+        "acir_opcodes" => record.acir_opcodes.map(|v| v as f64),
+        "subgroup_size" => record.subgroup_size.map(|v| v as f64),
+        "proof_size_bytes" => record.proof_size_bytes.map(|v| v as f64),
+        "pk_size" => record.proving_key_size_bytes.map(|v| v as f64),
+        "vk_size" => record.verification_key_size_bytes.map(|v| v as f64),
+        "artifact_size_bytes" => record.artifact_size_bytes.map(|v| v as f64),
+        "verify_cold_ms" => record.verify_cold_ms,
+        "peak_rss_mb" => record.peak_rss_mb,
+        _ => None,
+    }
+}
+
+/// Parse `name{label="value", ...}` into the metric name and matchers.
+fn parse_selector(s: &str, expr: &str) -> BenchResult<(String, Vec<LabelMatcher>)> {
+    let invalid = |why: &str| BenchError::Message(format!("invalid alert rule '{expr}': {why}"));
+    let (name, labels) = match s.split_once('{') {
+        Some((name, rest)) => {
+            let body = rest
+                .strip_suffix('}')
+                .ok_or_else(|| invalid("unclosed '{'"))?;
+            (name.trim(), body)
+        }
+        None => (s.trim(), ""),
+    };
+    if !known_metrics().iter().any(|m| m == name) {
+        return Err(invalid(&format!(
+            "unknown metric '{name}' (known: {})",
+            known_metrics().join(", ")
+        )));
+    }
+
+    let mut matchers = Vec::new();
+    for part in labels.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (label, value, negate) = if let Some((l, v)) = part.split_once("!=") {
+            (l, v, true)
+        } else if let Some((l, v)) = part.split_once('=') {
+            (l, v, false)
+        } else {
+            return Err(invalid(&format!("expected label=\"value\", got '{part}'")));
+        };
+        let label = label.trim();
+        if !matches!(label, "circuit" | "backend" | "params") {
+            return Err(invalid(&format!(
+                "unknown label '{label}' (known: circuit, backend, params)"
+            )));
+        }
+        let value = value
+            .trim()
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .ok_or_else(|| invalid(&format!("label value for '{label}' must be quoted")))?;
+        matchers.push(LabelMatcher {
+            label: label.to_string(),
+            value: value.to_string(),
+            negate,
+        });
+    }
+    Ok((name.to_string(), matchers))
+}
+
+impl AlertRule {
+    /// Parse a rule expression such as `delta_pct(gates{circuit="rollup"}) > 5`.
+    pub fn parse(expr: &str, action: AlertAction, webhook: Option<String>) -> BenchResult<Self> {
+        let invalid =
+            |why: &str| BenchError::Message(format!("invalid alert rule '{expr}': {why}"));
+
+        // The operator is the first one outside the selector's quotes and braces
+        let mut depth = 0;
+        let mut in_quotes = false;
+        let mut split = None;
+        for (i, c) in expr.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                '{' | '(' if !in_quotes => depth += 1,
+                '}' | ')' if !in_quotes => depth -= 1,
+                _ if !in_quotes && depth == 0 => {
+                    if let Some((tok, op)) = CompareOp::ALL
+                        .iter()
+                        .find(|(tok, _)| expr[i..].starts_with(tok))
+                    {
+                        split = Some((i, tok.len(), *op));
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let Some((at, len, op)) = split else {
+            return Err(invalid("expected a comparison (>, >=, <, <=, ==, !=)"));
+        };
+        let selector = expr[..at].trim();
+        let threshold: f64 = expr[at + len..]
+            .trim()
+            .parse()
+            .map_err(|_| invalid("threshold must be a number"))?;
+
+        let (inner, delta) = match selector.strip_prefix("delta_pct(") {
+            Some(rest) => (
+                rest.strip_suffix(')')
+                    .ok_or_else(|| invalid("unclosed 'delta_pct('"))?,
+                true,
+            ),
+            None => (selector, false),
+        };
+        let (metric, labels) = parse_selector(inner, expr)?;
+
+        if action == AlertAction::Webhook && webhook.is_none() {
+            return Err(invalid("webhook action needs a URL (alerts.webhook)"));
+        }
+
+        Ok(AlertRule {
+            expr: expr.trim().to_string(),
+            action,
+            webhook,
+            metric,
+            delta,
+            labels,
+            op,
+            threshold,
+        })
+    }
+
+    /// Value this rule compares for `record`, if the metric is available.
+    fn value(&self, record: &BenchRecord, baseline: Option<&BenchRecord>) -> Option<f64> {
+        let target = metric_value(record, &self.metric)?;
+        if !self.delta {
+            return Some(target);
+        }
+        let base = metric_value(baseline?, &self.metric)?;
+        if base == 0.0 {
+            return None;
+        }
+        Some((target - base) / base * 100.0)
+    }
+
+    /// Evaluate against `records`, with `baseline` records used for
+    /// `delta_pct` rules (paired by circuit name and params). Failed runs
+    /// (with an `error`) and expected failures are skipped on both sides.
+    pub fn evaluate(&self, records: &[BenchRecord], baseline: &[BenchRecord]) -> Vec<FiredAlert> {
+        let baseline: HashMap<(&str, Option<u64>), &BenchRecord> = baseline
+            .iter()
+            .filter(|r| r.error.is_none() && !r.is_expected_failure())
+            .map(|r| ((r.circuit_name.as_str(), r.params), r))
+            .collect();
+
+        records
+            .iter()
+            .filter(|r| r.error.is_none() && !r.is_expected_failure())
+            .filter(|r| self.labels.iter().all(|m| m.matches(r)))
+            .filter_map(|r| {
+                let base = baseline.get(&(r.circuit_name.as_str(), r.params)).copied();
+                let value = self.value(r, base)?;
+                self.op.holds(value, self.threshold).then(|| FiredAlert {
+                    rule: self.expr.clone(),
+                    action: self.action,
+                    circuit_name: r.circuit_name.clone(),
+                    params: r.params,
+                    value,
+                })
+            })
+            .collect()
+    }
+}
+
+impl AlertsConfig {
+    /// Parse every configured rule; the first invalid one is an error.
+    pub fn rules(&self) -> BenchResult<Vec<AlertRule>> {
+        self.rules
+            .iter()
+            .map(|r| {
+                let webhook = r.webhook.clone().or_else(|| self.webhook.clone());
+                AlertRule::parse(&r.expr, r.action, webhook)
+            })
+            .collect()
+    }
+}
+
+/// Evaluate all rules and carry out their actions.
+///
+/// Warnings and failures are printed to stderr; webhook alerts are POSTed
+/// (a failed delivery is logged, not fatal). Returns every fired alert.
+pub fn evaluate_and_dispatch(
+    rules: &[AlertRule],
+    records: &[BenchRecord],
+    baseline: &[BenchRecord],
+) -> Vec<FiredAlert> {
+    let mut fired = Vec::new();
+    for rule in rules {
+        let alerts = rule.evaluate(records, baseline);
+        for alert in &alerts {
+            match alert.action {
+                AlertAction::Warn => eprintln!("Alert (warn): {}", alert.describe()),
+                AlertAction::Fail => eprintln!("Alert (fail): {}", alert.describe()),
+                AlertAction::Webhook => {
+                    eprintln!("Alert (webhook): {}", alert.describe());
+                    if let Some(url) = &rule.webhook {
+                        if let Err(e) = post_webhook(url, alert) {
+                            eprintln!("Warning: alert webhook failed: {e}");
+                        }
+                    }
+                }
+            }
+        }
+        fired.extend(alerts);
+    }
+    fired
+}
+
+/// Whether any fired alert fails the run.
+pub fn any_failed(alerts: &[FiredAlert]) -> bool {
+    alerts.iter().any(|a| a.action == AlertAction::Fail)
+}
+
+/// POST an alert as JSON with curl.
+fn post_webhook(url: &str, alert: &FiredAlert) -> BenchResult<()> {
+    let body = serde_json::to_vec(alert)
+        .map_err(|e| BenchError::Message(format!("failed to serialize alert: {e}")))?;
+    let mut child = Command::new("curl")
+        .args([
+            "-fsS",
+            "-X",
+            "POST",
+            "-H",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| BenchError::Message(format!("failed to run curl: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(&body)
+            .map_err(|e| BenchError::Message(format!("failed to send alert: {e}")))?;
+    }
+    let status = child
+        .wait()
+        .map_err(|e| BenchError::Message(format!("failed to wait for curl: {e}")))?;
+    if !status.success() {
+        return Err(BenchError::Message(format!(
+            "POST {url} exited with status: {status}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig};
+
+    fn record(circuit: &str, prove_ms: &[f64], gates: u64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            circuit.to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "barretenberg".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.prove_stats = Some(TimingStat::from_samples(prove_ms));
+        r.total_gates = Some(gates);
+        r
+    }
+
+    fn rule(expr: &str) -> AlertRule {
+        AlertRule::parse(expr, AlertAction::Fail, None).unwrap()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let r = rule(r#"prove_ms_p95{circuit="rollup", backend!="mock"} > 30000"#);
+        assert_eq!(r.metric, "prove_ms_p95");
+        assert!(!r.delta);
+        assert_eq!(r.labels.len(), 2);
+        assert!(r.labels[1].negate);
+        assert_eq!(r.op, CompareOp::Gt);
+        assert_eq!(r.threshold, 30000.0);
+
+        let r = rule("delta_pct(gates) >= 5");
+        assert_eq!(r.metric, "gates");
+        assert!(r.delta);
+        assert_eq!(r.op, CompareOp::Ge);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rules() {
+        for expr in [
+            "prove_ms",
+            "nope > 1",
+            "prove_ms > fast",
+            r#"prove_ms{host="a"} > 1"#,
+            "prove_ms{circuit=rollup} > 1",
+            "delta_pct(gates > 1",
+        ] {
+            assert!(
+                AlertRule::parse(expr, AlertAction::Warn, None).is_err(),
+                "{expr}"
+            );
+        }
+        assert!(AlertRule::parse("gates > 1", AlertAction::Webhook, None).is_err());
+    }
+
+    #[test]
+    fn test_absolute_rule_respects_labels() {
+        let records = vec![
+            record("rollup", &[31_000.0, 35_000.0], 10),
+            record("merkle", &[40_000.0], 10),
+        ];
+        let fired = rule(r#"prove_ms_max{circuit="rollup"} > 30000"#).evaluate(&records, &[]);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].circuit_name, "rollup");
+        assert_eq!(fired[0].value, 35_000.0);
+        assert!(any_failed(&fired));
+    }
+
+    #[test]
+    fn test_failed_runs_are_skipped() {
+        let failed = |mut r: BenchRecord| {
+            r.error = Some(crate::core::RunError {
+                message: "bb crashed".to_string(),
+                log_path: None,
+            });
+            r
+        };
+        let records = vec![failed(record("rollup", &[40_000.0], 10))];
+        assert!(rule("prove_ms > 30000").evaluate(&records, &[]).is_empty());
+
+        let baseline = vec![failed(record("rollup", &[100.0], 10))];
+        let records = vec![record("rollup", &[100.0], 1000)];
+        assert!(
+            rule("delta_pct(gates) > 5")
+                .evaluate(&records, &baseline)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_delta_rule_needs_baseline() {
+        let baseline = vec![record("rollup", &[100.0], 1000)];
+        let records = vec![
+            record("rollup", &[100.0], 1060),
+            record("new_circuit", &[100.0], 5000),
+        ];
+        let fired = rule("delta_pct(gates) > 5").evaluate(&records, &baseline);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].circuit_name, "rollup");
+        assert!((fired[0].value - 6.0).abs() < 1e-9);
+
+        assert!(
+            rule("delta_pct(gates) > 10")
+                .evaluate(&records, &baseline)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_config_webhook_default() {
+        let cfg: AlertsConfig = toml::from_str(
+            r#"
+webhook = "https://example.com/hook"
+
+[[rule]]
+expr = "delta_pct(gates) > 5"
+action = "webhook"
+
+[[rule]]
+expr = "prove_ms > 1000"
+"#,
+        )
+        .unwrap();
+        let rules = cfg.rules().unwrap();
+        assert_eq!(
+            rules[0].webhook.as_deref(),
+            Some("https://example.com/hook")
+        );
+        assert_eq!(rules[1].action, AlertAction::Warn);
+    }
+}
@@ -0,0 +1,135 @@
+//! Compile-config comparison section: the same circuit built with different
+//! `nargo compile` flags, side by side.
+//!
+//! Records are grouped by circuit (without the `@<config>` suffix the matrix
+//! adds) and params; within a group the first config is the reference the
+//! others are measured against.
+
+use crate::core::BenchRecord;
+use crate::engine::matrix::COMPILE_CONFIG_METADATA_KEY;
+
+use super::{format_value, percent_change};
+
+/// Config name of a record: its compile-config tag, or "default".
+fn config_name(record: &BenchRecord) -> &str {
+    record
+        .metadata
+        .get(COMPILE_CONFIG_METADATA_KEY)
+        .map(String::as_str)
+        .unwrap_or("default")
+}
+
+/// Circuit name of a record without its `@<config>` suffix.
+fn base_circuit(record: &BenchRecord) -> &str {
+    let name = record.circuit_name.as_str();
+    name.strip_suffix(config_name(record))
+        .and_then(|n| n.strip_suffix('@'))
+        .unwrap_or(name)
+}
+
+fn cell(value: Option<f64>, metric: &str) -> String {
+    value
+        .map(|v| format_value(v, metric))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn delta(reference: Option<f64>, value: Option<f64>) -> String {
+    match (reference, value) {
+        (Some(r), Some(v)) => format!("{:+.1}%", percent_change(r, v)),
+        _ => "-".to_string(),
+    }
+}
+
+/// Render the "Compile Configurations" markdown section for `records`.
+pub fn render_compile_configs(records: &[BenchRecord]) -> String {
+    let mut groups: Vec<((&str, Option<u64>), Vec<&BenchRecord>)> = Vec::new();
+    for record in records {
+        let key = (base_circuit(record), record.params);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(record),
+            None => groups.push((key, vec![record])),
+        }
+    }
+
+    let mut out = String::from("### Compile Configurations\n\n");
+    for ((circuit, params), group) in groups {
+        let reference = group[0];
+        match params {
+            Some(p) => out.push_str(&format!("#### {circuit} (params={p})\n\n")),
+            None => out.push_str(&format!("#### {circuit}\n\n")),
+        }
+        out.push_str(&format!(
+            "| Config | Flags | Gates | ACIR Opcodes | Compile | Prove | Gates vs {0} | Prove vs {0} |\n\
+             |--------|-------|-------|--------------|---------|-------|---------|---------|\n",
+            config_name(reference)
+        ));
+        let gates = |r: &BenchRecord| r.total_gates.map(|g| g as f64);
+        let prove = |r: &BenchRecord| r.prove_stats.as_ref().map(|s| s.mean_ms);
+        for record in group {
+            let flags = if record.config.compile_flags.is_empty() {
+                "-".to_string()
+            } else {
+                format!("`{}`", record.config.compile_flags.join(" "))
+            };
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                config_name(record),
+                flags,
+                cell(gates(record), "gates"),
+                cell(record.acir_opcodes.map(|o| o as f64), "acir_opcodes"),
+                cell(
+                    record.compile_stats.as_ref().map(|s| s.mean_ms),
+                    "compile_ms"
+                ),
+                cell(prove(record), "prove_ms"),
+                delta(gates(reference), gates(record)),
+                delta(prove(reference), prove(record)),
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+    fn record(config: Option<&str>, flags: &[&str], gates: u64, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            "merkle".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        if let Some(config) = config {
+            r.metadata
+                .insert(COMPILE_CONFIG_METADATA_KEY.to_string(), config.to_string());
+            r.circuit_name = format!("merkle@{config}");
+        }
+        r.config.compile_flags = flags.iter().map(|f| f.to_string()).collect();
+        r.total_gates = Some(gates);
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r
+    }
+
+    #[test]
+    fn test_render_compile_configs_against_first_config() {
+        let md = render_compile_configs(&[
+            record(None, &[], 1000, 200.0),
+            record(Some("brillig"), &["--force-brillig"], 800, 150.0),
+        ]);
+        assert!(md.starts_with("### Compile Configurations\n\n#### merkle\n\n"));
+        assert!(md.contains("| Gates vs default | Prove vs default |"));
+        let rows: Vec<&str> = md.lines().filter(|l| l.starts_with("| ")).collect();
+        assert!(rows[1].starts_with("| default | - |"));
+        assert!(rows[1].ends_with("| +0.0% | +0.0% |"));
+        assert!(rows[2].starts_with("| brillig | `--force-brillig` |"));
+        assert!(rows[2].ends_with("| -20.0% | -25.0% |"));
+    }
+}
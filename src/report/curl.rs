@@ -0,0 +1,97 @@
+//! POSTing JSON with curl.
+//!
+//! The URL, headers and body reach curl as a config file on its stdin
+//! (`-K -`) instead of its command line, where a bearer token or a webhook
+//! URL (which usually embeds its secret) would be visible to every local
+//! user through `ps`.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::{BenchError, BenchResult};
+
+/// Quote `value` as a curl config string.
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The curl config for POSTing `body` to `url` with `headers`.
+fn post_config(url: &str, headers: &[&str], body: &str) -> String {
+    let mut config = format!("url = {}\nrequest = \"POST\"\n", quote(url));
+    for header in headers {
+        config.push_str(&format!("header = {}\n", quote(header)));
+    }
+    config.push_str(&format!("data-binary = {}\n", quote(body)));
+    config
+}
+
+/// POST `body` to `url` with `headers`, giving up after `max_time_secs` if
+/// set. Errors don't mention the URL, so callers can decide how much of it
+/// to show.
+pub(crate) fn post(
+    url: &str,
+    headers: &[&str],
+    body: &[u8],
+    max_time_secs: Option<u32>,
+) -> BenchResult<()> {
+    let body = std::str::from_utf8(body)
+        .map_err(|e| BenchError::Message(format!("request body is not UTF-8: {e}")))?;
+    let mut cmd = Command::new("curl");
+    cmd.args(["-fsS", "-K", "-"]);
+    if let Some(secs) = max_time_secs {
+        cmd.args(["--max-time", &secs.to_string()]);
+    }
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| BenchError::Message(format!("failed to run curl: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(post_config(url, headers, body).as_bytes())
+            .map_err(|e| BenchError::Message(format!("failed to send request to curl: {e}")))?;
+    }
+    let status = child
+        .wait()
+        .map_err(|e| BenchError::Message(format!("failed to wait for curl: {e}")))?;
+    if !status.success() {
+        return Err(BenchError::Message(format!(
+            "curl exited with status: {status}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_config_quotes_values() {
+        let config = post_config(
+            "https://api.github.com/repos/o/r/check-runs",
+            &["Authorization: Bearer t0k\"en"],
+            "{\"summary\":\"a\\\\b\nc\"}",
+        );
+        assert_eq!(
+            config,
+            "url = \"https://api.github.com/repos/o/r/check-runs\"\n\
+             request = \"POST\"\n\
+             header = \"Authorization: Bearer t0k\\\"en\"\n\
+             data-binary = \"{\\\"summary\\\":\\\"a\\\\\\\\b\\nc\\\"}\"\n"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Process-wide display settings for reports.
+//!
+//! How timestamps and large counts are shown is a reader preference rather
+//! than part of a report, so it's set once from the global `--time-zone` and
+//! `--thousands-separator` flags and applied by every renderer: `Unit::format`
+//! for counts, `format_timestamp` for dates. Markdown and the HTML reports
+//! therefore always agree.
+
+use std::sync::OnceLock;
+
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+
+/// Time zone report timestamps are shown in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeZone {
+    #[default]
+    Utc,
+    /// The machine's local offset, resolved when the settings are made
+    Local(UtcOffset),
+}
+
+/// How reports show timestamps and counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisplaySettings {
+    pub time_zone: TimeZone,
+    /// Show counts (gates, opcodes, ...) in full with this between groups of
+    /// three digits, instead of abbreviated as `18.33M`
+    pub thousands_separator: Option<String>,
+}
+
+static DISPLAY: OnceLock<DisplaySettings> = OnceLock::new();
+
+impl DisplaySettings {
+    /// Settings for a `--time-zone` value (`utc` or `local`) and
+    /// `--thousands-separator`. The local offset is looked up here, so call
+    /// this early, before other threads are started.
+    pub fn parse(time_zone: &str, thousands_separator: Option<String>) -> Option<Self> {
+        let time_zone = match time_zone {
+            "utc" => TimeZone::Utc,
+            "local" => TimeZone::Local(UtcOffset::current_local_offset().unwrap_or_else(|e| {
+                eprintln!("warning: could not determine the local time zone ({e}), showing UTC");
+                UtcOffset::UTC
+            })),
+            _ => return None,
+        };
+        Some(DisplaySettings {
+            time_zone,
+            thousands_separator: thousands_separator.filter(|s| !s.is_empty()),
+        })
+    }
+
+    /// Use `settings` for every report rendered by this process. Only the
+    /// first call has an effect.
+    pub fn set_default(settings: DisplaySettings) {
+        let _ = DISPLAY.set(settings);
+    }
+
+    /// The settings set with [`DisplaySettings::set_default`], else UTC and
+    /// abbreviated counts.
+    pub fn current() -> &'static DisplaySettings {
+        static DEFAULT: DisplaySettings = DisplaySettings {
+            time_zone: TimeZone::Utc,
+            thousands_separator: None,
+        };
+        DISPLAY.get().unwrap_or(&DEFAULT)
+    }
+
+    /// `value` rounded, with its digits grouped by the separator.
+    pub fn group_digits(&self, value: f64) -> Option<String> {
+        let sep = self.thousands_separator.as_deref()?;
+        let digits = format!("{:.0}", value.abs());
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                out.push_str(sep);
+            }
+            out.push(c);
+        }
+        Some(if value.round() < 0.0 {
+            format!("-{out}")
+        } else {
+            out
+        })
+    }
+
+    /// The offset timestamps are shown at and the suffix appended to them
+    /// (none for UTC, e.g. ` -05:30` for local time).
+    fn offset(&self) -> (UtcOffset, String) {
+        match self.time_zone {
+            TimeZone::Utc => (UtcOffset::UTC, String::new()),
+            TimeZone::Local(offset) => {
+                let (h, m, _) = offset.as_hms();
+                let sign = if offset.is_negative() { '-' } else { '+' };
+                (offset, format!(" {sign}{:02}:{:02}", h.abs(), m.abs()))
+            }
+        }
+    }
+
+    /// The settings for pages that format in the browser (the history
+    /// index), as a JSON object safe to embed in a `<script>`.
+    pub fn to_script_json(&self) -> String {
+        let (offset, suffix) = self.offset();
+        serde_json::json!({
+            "offset_seconds": offset.whole_seconds(),
+            "offset_suffix": suffix,
+            "separator": self.thousands_separator,
+        })
+        .to_string()
+        .replace('<', "\\u003c")
+    }
+
+    /// An RFC 3339 timestamp as `YYYY-MM-DD HH:MM:SS`, in UTC as is, in
+    /// local time with the offset appended. Unparseable input is cut to the
+    /// same shape.
+    pub fn format_timestamp(&self, timestamp: &str) -> String {
+        let Ok(parsed) = OffsetDateTime::parse(timestamp, &Rfc3339) else {
+            return timestamp.get(..19).unwrap_or(timestamp).replace('T', " ");
+        };
+        let (offset, suffix) = self.offset();
+        let at = parsed.to_offset(offset);
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}{suffix}",
+            at.year(),
+            u8::from(at.month()),
+            at.day(),
+            at.hour(),
+            at.minute(),
+            at.second()
+        )
+    }
+}
+
+/// A report timestamp formatted with the process-wide settings.
+pub fn format_timestamp(timestamp: &str) -> String {
+    DisplaySettings::current().format_timestamp(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_digits() {
+        let settings = DisplaySettings::parse("utc", Some(",".to_string())).unwrap();
+        assert_eq!(settings.group_digits(18327461.0).unwrap(), "18,327,461");
+        assert_eq!(settings.group_digits(999.0).unwrap(), "999");
+        assert_eq!(settings.group_digits(-1000.4).unwrap(), "-1,000");
+        let narrow = DisplaySettings::parse("utc", Some("\u{202f}".to_string())).unwrap();
+        assert_eq!(narrow.group_digits(1234.0).unwrap(), "1\u{202f}234");
+        assert!(DisplaySettings::default().group_digits(1234.0).is_none());
+        assert!(DisplaySettings::parse("cet", None).is_none());
+    }
+
+    #[test]
+    fn test_script_json() {
+        let settings = DisplaySettings {
+            time_zone: TimeZone::Local(UtcOffset::from_hms(-5, -30, 0).unwrap()),
+            thousands_separator: Some("</script>".to_string()),
+        };
+        let json = settings.to_script_json();
+        assert!(json.contains("\"offset_seconds\":-19800"));
+        assert!(json.contains("\"offset_suffix\":\" -05:30\""));
+        assert!(!json.contains("</script>"));
+        assert_eq!(
+            DisplaySettings::default().to_script_json(),
+            r#"{"offset_seconds":0,"offset_suffix":"","separator":null}"#
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp_in_zone() {
+        let utc = DisplaySettings::default();
+        assert_eq!(
+            utc.format_timestamp("2026-03-01T23:30:05+01:00"),
+            "2026-03-01 22:30:05"
+        );
+        assert_eq!(
+            utc.format_timestamp("2026-03-01T10:00:00.5Z"),
+            "2026-03-01 10:00:00"
+        );
+
+        let local = DisplaySettings {
+            time_zone: TimeZone::Local(UtcOffset::from_hms(-5, -30, 0).unwrap()),
+            thousands_separator: None,
+        };
+        assert_eq!(
+            local.format_timestamp("2026-03-01T02:00:00Z"),
+            "2026-02-28 20:30:00 -05:30"
+        );
+        assert_eq!(utc.format_timestamp("not a date"), "not a date");
+    }
+}
@@ -0,0 +1,233 @@
+//! GitHub check runs for CI results.
+//!
+//! A check run shows up in a pull request's Checks tab with a pass/fail
+//! conclusion, the markdown report as its summary, and one annotation per
+//! circuit that regressed, improved or failed. Annotations need a file and
+//! line, so they point at the circuit's entry in the bench config.
+//!
+//! Posting uses curl against the Checks API with the token, repository and
+//! commit GitHub Actions provides (`GITHUB_TOKEN`, `GITHUB_REPOSITORY`,
+//! `GITHUB_SHA`); the token needs `checks: write` and is passed to curl on
+//! stdin, so it never shows up in the process list.
+
+use serde::Serialize;
+
+use super::curl;
+use super::regression::{RegressionReport, RegressionStatus};
+use crate::{BenchError, BenchResult};
+
+/// Name of the check run in the Checks tab.
+pub const CHECK_NAME: &str = "noir-bench";
+
+/// The Checks API accepts at most 50 annotations per request.
+const MAX_ANNOTATIONS: usize = 50;
+
+/// The Checks API rejects summaries longer than this.
+const MAX_SUMMARY_BYTES: usize = 65_535;
+
+/// Body of a `POST /repos/{repo}/check-runs` request.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckRun {
+    pub name: String,
+    pub head_sha: String,
+    pub status: String,
+    /// `success`, `failure` or `neutral`
+    pub conclusion: String,
+    pub output: CheckOutput,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckOutput {
+    pub title: String,
+    pub summary: String,
+    pub annotations: Vec<CheckAnnotation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// `notice`, `warning` or `failure`
+    pub annotation_level: String,
+    pub title: String,
+    pub message: String,
+}
+
+impl CheckAnnotation {
+    /// Annotation on the line of `config_text` that names `circuit` (line 1
+    /// when it isn't found).
+    pub fn for_circuit(
+        config_path: &str,
+        config_text: &str,
+        circuit: &str,
+        level: &str,
+        message: String,
+    ) -> Self {
+        let quoted = format!("\"{circuit}\"");
+        let line = config_text
+            .lines()
+            .position(|l| l.trim_start().starts_with("name") && l.contains(&quoted))
+            .map(|i| i + 1)
+            .unwrap_or(1);
+        CheckAnnotation {
+            path: config_path.to_string(),
+            start_line: line,
+            end_line: line,
+            annotation_level: level.to_string(),
+            title: circuit.to_string(),
+            message,
+        }
+    }
+}
+
+/// Per-circuit annotations for a comparison: a failure for each regressed
+/// circuit and a notice for each improved one.
+pub fn report_annotations(
+    report: &RegressionReport,
+    config_path: &str,
+    config_text: &str,
+) -> Vec<CheckAnnotation> {
+    let mut circuits: Vec<_> = report.circuits.iter().collect();
+    circuits.sort_by(|a, b| a.circuit_name.cmp(&b.circuit_name));
+    circuits
+        .into_iter()
+        .filter_map(|circuit| {
+            let (level, status) = match circuit.status {
+                RegressionStatus::ExceededThreshold => ("failure", "regressed"),
+                RegressionStatus::Improved => ("notice", "improved"),
+                _ => return None,
+            };
+            let changes: Vec<String> = circuit
+                .metrics
+                .iter()
+                .filter(|m| m.status == circuit.status)
+                .map(|m| format!("{} {:+.1}%", m.metric, m.delta_pct))
+                .collect();
+            Some(CheckAnnotation::for_circuit(
+                config_path,
+                config_text,
+                &circuit.circuit_name,
+                level,
+                format!("{status}: {}", changes.join(", ")),
+            ))
+        })
+        .collect()
+}
+
+impl CheckRun {
+    /// A completed check run; `failed` decides the conclusion. Annotations
+    /// beyond the API limit are dropped and the summary is truncated.
+    pub fn completed(
+        head_sha: &str,
+        failed: bool,
+        title: String,
+        summary: String,
+        mut annotations: Vec<CheckAnnotation>,
+    ) -> Self {
+        let mut summary = summary;
+        if annotations.len() > MAX_ANNOTATIONS {
+            summary.push_str(&format!(
+                "\n\n_{} more annotation(s) not shown._\n",
+                annotations.len() - MAX_ANNOTATIONS
+            ));
+            annotations.truncate(MAX_ANNOTATIONS);
+        }
+        if summary.len() > MAX_SUMMARY_BYTES {
+            let mut end = MAX_SUMMARY_BYTES - 32;
+            while !summary.is_char_boundary(end) {
+                end -= 1;
+            }
+            summary.truncate(end);
+            summary.push_str("\n\n_(truncated)_\n");
+        }
+        CheckRun {
+            name: CHECK_NAME.to_string(),
+            head_sha: head_sha.to_string(),
+            status: "completed".to_string(),
+            conclusion: if failed { "failure" } else { "success" }.to_string(),
+            output: CheckOutput {
+                title,
+                summary,
+                annotations,
+            },
+        }
+    }
+
+    /// Create the check run on `repo` (`owner/name`). `GITHUB_API_URL` is
+    /// honoured for GitHub Enterprise.
+    pub fn post(&self, repo: &str, token: &str) -> BenchResult<()> {
+        let api =
+            std::env::var("GITHUB_API_URL").unwrap_or_else(|_| "https://api.github.com".into());
+        let url = format!("{}/repos/{repo}/check-runs", api.trim_end_matches('/'));
+        let body = serde_json::to_vec(self)
+            .map_err(|e| BenchError::Message(format!("failed to serialize check run: {e}")))?;
+        // The token goes to curl on stdin, never on its command line
+        curl::post(
+            &url,
+            &[
+                "Accept: application/vnd.github+json",
+                &format!("Authorization: Bearer {token}"),
+            ],
+            &body,
+            None,
+        )
+        .map_err(|e| BenchError::Message(format!("POST {url} failed: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{CircuitRegression, MetricDelta, ThresholdRule};
+
+    const CONFIG: &str = "[[circuit]]\nname = \"alpha\"\npath = \"a.json\"\n\n[[circuit]]\nname = \"zeta\"\npath = \"z.json\"\n";
+
+    fn circuit(name: &str, pct: f64, status: RegressionStatus) -> CircuitRegression {
+        CircuitRegression {
+            circuit_name: name.to_string(),
+            params: None,
+            metrics: vec![MetricDelta {
+                metric: "prove_ms".to_string(),
+                baseline: 100.0,
+                target: 100.0 + pct,
+                delta_abs: pct,
+                delta_pct: pct,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status,
+            }],
+            status,
+        }
+    }
+
+    #[test]
+    fn test_report_annotations_point_at_config_entries() {
+        let mut report = RegressionReport::new("base", "target", 10.0);
+        report.add_circuit(circuit("zeta", 25.0, RegressionStatus::ExceededThreshold));
+        report.add_circuit(circuit("alpha", -20.0, RegressionStatus::Improved));
+        report.add_circuit(circuit("other", 1.0, RegressionStatus::Ok));
+
+        let annotations = report_annotations(&report, "bench-config.toml", CONFIG);
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].title, "alpha");
+        assert_eq!(annotations[0].annotation_level, "notice");
+        assert_eq!(annotations[0].start_line, 2);
+        assert_eq!(annotations[1].annotation_level, "failure");
+        assert_eq!(annotations[1].start_line, 6);
+        assert_eq!(annotations[1].message, "regressed: prove_ms +25.0%");
+    }
+
+    #[test]
+    fn test_completed_check_run_caps_annotations() {
+        let annotations = (0..60)
+            .map(|i| {
+                CheckAnnotation::for_circuit("c.toml", "", &format!("c{i}"), "notice", "x".into())
+            })
+            .collect();
+        let run = CheckRun::completed("abc", true, "t".into(), "s".into(), annotations);
+        assert_eq!(run.conclusion, "failure");
+        assert_eq!(run.output.annotations.len(), MAX_ANNOTATIONS);
+        assert!(run.output.summary.contains("10 more annotation(s)"));
+    }
+}
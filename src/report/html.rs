@@ -10,7 +10,10 @@
 
 use std::path::Path;
 
-use crate::report::RegressionReport;
+use super::display::format_timestamp;
+use super::methodology::render_methodology_html;
+use super::metrics::{self, format_value};
+use crate::report::{MetricDelta, RegressionReport, RegressionStatus, headroom_pct};
 
 /// Escape JSON for safe embedding inside an HTML `<script type="application/json">` tag.
 ///
@@ -27,39 +30,8 @@ fn escape_json_for_html_script(json: &str) -> String {
     json.replace('<', "\\u003c")
 }
 
-/// Render a RegressionReport as a standalone HTML string.
-///
-/// The HTML includes embedded CSS and JS, with the report JSON embedded as a
-/// JavaScript constant. Circuits and warnings are sorted deterministically.
-pub fn render_html(report: &RegressionReport) -> String {
-    // Clone and sort for deterministic output
-    let mut sorted_report = report.clone();
-    sorted_report.circuits.sort_by(|a, b| {
-        a.circuit_name
-            .cmp(&b.circuit_name)
-            .then_with(|| a.params.cmp(&b.params))
-    });
-    sorted_report
-        .version_mismatches
-        .sort_by(|a, b| a.tool.cmp(&b.tool));
-
-    // Serialize report to JSON with stable formatting
-    let report_json =
-        serde_json::to_string_pretty(&sorted_report).unwrap_or_else(|_| "{}".to_string());
-    // Escape for safe embedding in HTML <script type="application/json"> tag
-    let escaped_json = escape_json_for_html_script(&report_json);
-
-    // Build HTML
-    let mut html = String::with_capacity(32 * 1024);
-
-    html.push_str(r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-<meta charset="UTF-8">
-<meta name="viewport" content="width=device-width, initial-scale=1.0">
-<title>noir-bench Regression Report</title>
-<style>
-:root {
+/// Styles of the standalone reports (regression and suite).
+pub(crate) const REPORT_CSS: &str = r#":root {
   --bg: #1a1a2e;
   --surface: #16213e;
   --surface-hover: #1f2b47;
@@ -188,6 +160,10 @@ tr:last-child td { border-bottom: none; }
 .delta-positive { color: var(--red); }
 .delta-negative { color: var(--green); }
 .mono { font-family: monospace; }
+.budget-cell.over { color: var(--red); }
+.budget-bar { display: inline-block; width: 60px; height: 6px; margin-right: 8px; vertical-align: middle; background: var(--border); border-right: 2px solid var(--red); }
+.budget-fill { display: block; height: 100%; background: var(--green); }
+.budget-cell.over .budget-fill { background: var(--red); }
 
 /* Expandable rows */
 .expand-btn {
@@ -221,6 +197,17 @@ tr:last-child td { border-bottom: none; }
 .prov-label { color: var(--text-muted); }
 .prov-value { font-family: monospace; }
 
+/* Methodology */
+.methodology-section {
+  background: var(--surface);
+  border-radius: 8px;
+  border: 1px solid var(--border);
+  padding: 16px;
+  margin-top: 24px;
+}
+.methodology-section summary { cursor: pointer; font-weight: 600; }
+.methodology-section .meta-table { margin: 12px 0; }
+
 /* Footer */
 .footer {
   margin-top: 32px;
@@ -237,40 +224,65 @@ tr:last-child td { border-bottom: none; }
   .provenance-grid { grid-template-columns: 1fr; }
   th, td { padding: 8px 12px; }
 }
-</style>
+"#;
+
+/// Render a RegressionReport as a standalone HTML string.
+///
+/// The HTML includes embedded CSS and JS, with the report JSON embedded as a
+/// JavaScript constant. Circuits and warnings are sorted deterministically.
+pub fn render_html(report: &RegressionReport) -> String {
+    // Clone and sort for deterministic output
+    let mut sorted_report = report.clone();
+    sorted_report.circuits.sort_by(|a, b| {
+        a.circuit_name
+            .cmp(&b.circuit_name)
+            .then_with(|| a.params.cmp(&b.params))
+    });
+    sorted_report
+        .version_mismatches
+        .sort_by(|a, b| a.tool.cmp(&b.tool));
+    sorted_report
+        .subgroup_crossings
+        .sort_by(|a, b| a.circuit_name.cmp(&b.circuit_name));
+
+    // Serialize report to JSON with stable formatting
+    let report_json = serde_json::to_string_pretty(&with_display_values(&sorted_report))
+        .unwrap_or_else(|_| "{}".to_string());
+    // Escape for safe embedding in HTML <script type="application/json"> tag
+    let escaped_json = escape_json_for_html_script(&report_json);
+
+    // Build HTML
+    let mut html = String::with_capacity(32 * 1024);
+
+    html.push_str(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>noir-bench Regression Report</title>
+<style>
+"#,
+    );
+    html.push_str(REPORT_CSS);
+    html.push_str(
+        r#"</style>
 </head>
 <body>
 <div class="container" id="app"></div>
-<script type="application/json" id="report-data">"#);
+<script type="application/json" id="report-data">"#,
+    );
 
     html.push_str(&escaped_json);
 
     html.push_str(r#"</script>
 <script>
-// Parse report data from non-executing JSON container
+// Parse report data from non-executing JSON container. Metric values and
+// the timestamp come pre-formatted (baseline_display, target_display,
+// delta_class, generated_display) by report::units and report::display, so
+// the page shows exactly what the markdown report does.
 const REPORT = JSON.parse(document.getElementById('report-data').textContent);
 
-// Format numeric value based on metric type
-function formatValue(value, metric) {
-  if (metric.includes('size') || metric.includes('mem') || metric.includes('rss')) {
-    if (metric.includes('rss_mb')) return value.toFixed(1) + ' MB';
-    if (value >= 1e9) return (value / 1e9).toFixed(1) + ' GB';
-    if (value >= 1e6) return (value / 1e6).toFixed(1) + ' MB';
-    if (value >= 1e3) return (value / 1e3).toFixed(1) + ' KB';
-    return Math.round(value) + ' B';
-  }
-  if (metric.includes('ms')) {
-    if (value >= 1000) return (value / 1000).toFixed(2) + 's';
-    return Math.round(value) + 'ms';
-  }
-  if (metric.includes('gates')) {
-    if (value >= 1e6) return (value / 1e6).toFixed(2) + 'M';
-    if (value >= 1e3) return (value / 1e3).toFixed(1) + 'K';
-    return Math.round(value).toString();
-  }
-  return value.toFixed(2);
-}
-
 // Status to CSS class
 function statusClass(status) {
   const map = {
@@ -339,6 +351,30 @@ function render() {
     return false;
   });
 
+  // Annotations (commit, PR, ...) as links where they have a target
+  function annotationHref(key, value, annotations) {
+    const isUrl = v => /^https?:\/\//.test(v);
+    if (isUrl(value)) return value;
+    const repo = annotations.repo;
+    if (!repo || !isUrl(repo)) return null;
+    const base = repo.replace(/\/+$/, '');
+    if (key === 'commit' && /^[0-9a-fA-F]+$/.test(value)) return base + '/commit/' + value;
+    if (key === 'pr' && /^#?[0-9]+$/.test(value)) return base + '/pull/' + value.replace('#', '');
+    return null;
+  }
+  function annotationRows(annotations) {
+    let rows = '';
+    for (const key of Object.keys(annotations).sort()) {
+      const value = annotations[key];
+      const href = annotationHref(key, value, annotations);
+      const cell = href
+        ? '<a href="' + esc(href) + '" rel="noopener">' + esc(value) + '</a>'
+        : esc(value);
+      rows += '<span class="meta-label">' + esc(key) + '</span><span class="meta-value">' + cell + '</span>';
+    }
+    return rows;
+  }
+
   let html = `
     <div class="header">
       <div class="header-status">
@@ -349,7 +385,8 @@ function render() {
         <span class="meta-label">Baseline</span><span class="meta-value">${esc(r.metadata.baseline_id)}</span>
         <span class="meta-label">Target</span><span class="meta-value">${esc(r.metadata.target_id)}</span>
         <span class="meta-label">Threshold</span><span class="meta-value">${r.metadata.threshold_percent.toFixed(1)}%</span>
-        <span class="meta-label">Generated</span><span class="meta-value">${esc(r.metadata.generated_at.slice(0,19).replace('T',' '))}</span>
+        <span class="meta-label">Generated</span><span class="meta-value">${esc(r.metadata.generated_display)}</span>
+        ${annotationRows(r.metadata.annotations || {})}
       </div>
     </div>
 
@@ -358,7 +395,7 @@ function render() {
       <div class="card improvements"><div class="card-value">${s.improvements}</div><div class="card-label">Improvements</div></div>
       <div class="card"><div class="card-value">${s.unchanged}</div><div class="card-label">Unchanged</div></div>
       <div class="card"><div class="card-value">${s.missing_baselines}</div><div class="card-label">Missing</div></div>
-      <div class="card warnings"><div class="card-value">${r.version_mismatches.length}</div><div class="card-label">Warnings</div></div>
+      <div class="card warnings"><div class="card-value">${(r.version_mismatches || []).length + (r.environment_mismatches || []).length + (r.thermal_warnings || []).length + (r.subgroup_crossings || []).length}</div><div class="card-label">Warnings</div></div>
     </div>`;
 
   // Version mismatch warnings
@@ -370,6 +407,35 @@ function render() {
     html += `</div>`;
   }
 
+  // Environment mismatch warnings (different machine)
+  if (r.environment_mismatches && r.environment_mismatches.length > 0) {
+    html += `<div class="warnings-section"><h3>Environment Changes</h3>`;
+    for (const m of r.environment_mismatches) {
+      html += `<div class="warning-item">${esc(m.field)}: ${esc(m.baseline)} → ${esc(m.target)}</div>`;
+    }
+    html += `</div>`;
+  }
+
+  // Records measured while the machine was throttling
+  if (r.thermal_warnings && r.thermal_warnings.length > 0) {
+    html += `<div class="warnings-section"><h3>Thermal Throttling</h3>`;
+    for (const w of r.thermal_warnings) {
+      html += `<div class="warning-item">${esc(w.circuit_name)}: ${esc(w.run)} measured under ${esc(w.level)} thermal pressure</div>`;
+    }
+    html += `</div>`;
+  }
+
+  // Subgroup boundary crossings (prove time follows the subgroup size)
+  if (r.subgroup_crossings && r.subgroup_crossings.length > 0) {
+    html += `<div class="warnings-section"><h3>Subgroup Size Changes</h3>`;
+    for (const c of r.subgroup_crossings) {
+      const factor = c.target_subgroup / c.baseline_subgroup;
+      const gates = c.gates_delta_pct != null ? 'gates ' + (c.gates_delta_pct > 0 ? '+' : '') + c.gates_delta_pct.toFixed(1) + '% but ' : '';
+      html += `<div class="warning-item">${esc(c.circuit_name)}: ${gates}subgroup 2^${Math.round(Math.log2(c.baseline_subgroup))} → 2^${Math.round(Math.log2(c.target_subgroup))}: expect ~${factor}x prove time</div>`;
+    }
+    html += `</div>`;
+  }
+
   // Filters
   html += `
     <div class="filters">
@@ -383,9 +449,18 @@ function render() {
       <button class="filter-btn ${state.onlyThreshold ? 'active' : ''}" onclick="toggle('onlyThreshold')">Only Threshold Breaches</button>
     </div>`;
 
-  // Circuit table
+  // Circuit table, with a Budget column (bar ending at the budget line)
+  // when [budgets] were configured
+  const hasBudgets = Object.keys(r.metadata.budgets || {}).length > 0;
+  function budgetCell(m) {
+    if (!hasBudgets) return '';
+    if (m.budget_display == null) return '<td></td>';
+    return '<td class="mono budget-cell' + (m.budget_over ? ' over' : '') + '">'
+      + '<span class="budget-bar"><span class="budget-fill" style="width:' + Number(m.budget_fill) + '%"></span></span>'
+      + esc(m.budget_display) + '</td>';
+  }
   html += `<div class="table-container"><table>
-    <thead><tr><th>Circuit</th><th>Metric</th><th>Baseline</th><th>Target</th><th>Delta</th><th>Status</th><th></th></tr></thead>
+    <thead><tr><th>Circuit</th><th>Metric</th><th>Baseline</th><th>Target</th><th>Delta</th>${hasBudgets ? '<th>Budget</th>' : ''}<th>Status</th><th></th></tr></thead>
     <tbody>`;
 
   for (const c of circuits) {
@@ -393,15 +468,15 @@ function render() {
     const isExp = state.expanded[cid];
     for (let i = 0; i < c.metrics.length; i++) {
       const m = c.metrics[i];
-      const deltaClass = m.delta_pct > 0 ? 'delta-positive' : m.delta_pct < 0 ? 'delta-negative' : '';
       const deltaStr = m.delta_abs === 0 ? '0' : (m.delta_pct > 0 ? '+' : '') + m.delta_pct.toFixed(1) + '%';
 
       html += `<tr>
         <td>${i === 0 ? esc(c.circuit_name) + (c.params ? ' [' + esc(String(c.params)) + ']' : '') : ''}</td>
         <td class="mono">${esc(m.metric)}</td>
-        <td class="mono">${formatValue(m.baseline, m.metric)}</td>
-        <td class="mono">${formatValue(m.target, m.metric)}</td>
-        <td class="mono ${deltaClass}">${deltaStr}</td>
+        <td class="mono">${esc(m.baseline_display)}</td>
+        <td class="mono">${esc(m.target_display)}</td>
+        <td class="mono ${m.delta_class}">${deltaStr}</td>
+        ${budgetCell(m)}
         <td class="status-cell ${statusClass(m.status)}">${statusText(m.status)}</td>
         <td>${i === 0 ? '<button class="expand-btn" data-cid="' + esc(cid) + '" onclick="toggleExpand(this.dataset.cid)">' + (isExp ? 'Hide' : 'Details') + '</button>' : ''}</td>
       </tr>`;
@@ -409,7 +484,7 @@ function render() {
 
     // Details row - use data-cid attribute instead of id with user content
     html += `<tr class="details-row ${isExp ? 'visible' : ''}" data-details-cid="${esc(cid)}">
-      <td colspan="7" class="details-cell">
+      <td colspan="${hasBudgets ? 8 : 7}" class="details-cell">
         <div class="details-grid">
           <div class="detail-item"><span class="detail-label">Circuit</span><span class="detail-value">${esc(c.circuit_name)}</span></div>
           ${c.params ? '<div class="detail-item"><span class="detail-label">Params</span><span class="detail-value">' + esc(String(c.params)) + '</span></div>' : ''}
@@ -421,7 +496,7 @@ function render() {
     for (const m of c.metrics) {
       html += `<div class="detail-item">
         <span class="detail-label">${esc(m.metric)}</span>
-        <span class="detail-value">${formatValue(m.baseline, m.metric)} → ${formatValue(m.target, m.metric)}</span>
+        <span class="detail-value">${esc(m.baseline_display)} → ${esc(m.target_display)}</span>
       </div>`;
     }
     html += `</div></td></tr>`;
@@ -466,6 +541,9 @@ function render() {
     html += `</div></div>`;
   }
 
+  // Methodology, pre-rendered by report::methodology
+  html += REPORT.methodology_html || '';
+
   // Footer
   html += `<div class="footer">Generated by noir-bench v${esc(REPORT.version ? REPORT.version.toString() : '1')} | Report schema v${REPORT.version || 1}</div>`;
 
@@ -485,6 +563,161 @@ render();
     html
 }
 
+/// HTML-escape a string for safe insertion into HTML content.
+///
+/// Escapes: & < > " '
+/// This prevents XSS when inserting user-controlled strings into HTML.
+pub fn html_escape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#x27;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// CSS class of a status cell (mirrors `statusClass` in the report JS).
+fn status_class(status: RegressionStatus) -> &'static str {
+    match status {
+        RegressionStatus::ExceededThreshold | RegressionStatus::Error => "exceeded",
+        RegressionStatus::Improved => "improved",
+        RegressionStatus::MissingBaseline => "missing",
+        RegressionStatus::Ok | RegressionStatus::Skipped => "ok",
+    }
+}
+
+/// CSS class of a metric's delta: red when it moved the wrong way for the
+/// metric's direction, green when it moved the right way.
+fn delta_class(m: &MetricDelta) -> &'static str {
+    match metrics::direction(&m.metric).is_worse(m.delta_pct) {
+        Some(true) => "delta-positive",
+        Some(false) => "delta-negative",
+        None => "",
+    }
+}
+
+/// Budget of a metric with the headroom its target leaves, e.g.
+/// "5.00s (38% headroom)" or "5.00s (12% over)".
+fn budget_text(budget: f64, m: &MetricDelta) -> String {
+    let pct = headroom_pct(m.target, budget);
+    let headroom = if pct >= 0.0 {
+        format!("{pct:.0}% headroom")
+    } else {
+        format!("{:.0}% over", -pct)
+    };
+    format!("{} ({headroom})", format_value(budget, &m.metric))
+}
+
+/// The report as JSON with each metric's display strings, delta class and
+/// budget (when it has one) added, for the report JS.
+fn with_display_values(report: &RegressionReport) -> serde_json::Value {
+    let mut value = serde_json::to_value(report).unwrap_or_default();
+    if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        metadata.insert(
+            "generated_display".into(),
+            format_timestamp(&report.metadata.generated_at).into(),
+        );
+    }
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "methodology_html".into(),
+            render_methodology_html(report).into(),
+        );
+    }
+    let circuits = value
+        .get_mut("circuits")
+        .and_then(serde_json::Value::as_array_mut);
+    for (circuit, circuit_json) in report.circuits.iter().zip(circuits.into_iter().flatten()) {
+        let metrics_json = circuit_json
+            .get_mut("metrics")
+            .and_then(serde_json::Value::as_array_mut);
+        for (m, m_json) in circuit
+            .metrics
+            .iter()
+            .zip(metrics_json.into_iter().flatten())
+        {
+            if let Some(obj) = m_json.as_object_mut() {
+                obj.insert(
+                    "baseline_display".into(),
+                    format_value(m.baseline, &m.metric).into(),
+                );
+                obj.insert(
+                    "target_display".into(),
+                    format_value(m.target, &m.metric).into(),
+                );
+                obj.insert("delta_class".into(), delta_class(m).into());
+                if let Some(budget) = report.budget(&circuit.circuit_name, &m.metric) {
+                    let fill = if budget > 0.0 {
+                        (m.target / budget * 100.0).clamp(0.0, 100.0).round()
+                    } else {
+                        100.0
+                    };
+                    obj.insert("budget_display".into(), budget_text(budget, m).into());
+                    obj.insert("budget_fill".into(), fill.into());
+                    obj.insert("budget_over".into(), (m.target > budget).into());
+                }
+            }
+        }
+    }
+    value
+}
+
+/// Render a report's metric deltas as a static HTML table (no JS), with the
+/// same columns and classes as the standalone report's circuit table.
+///
+/// For embedding in other pages; the page supplies the CSS.
+pub fn render_metric_table(report: &RegressionReport) -> String {
+    let has_budgets = !report.metadata.budgets.is_empty();
+    let mut html = format!(
+        "<table class=\"metric-table\">\n<thead><tr><th>Circuit</th><th>Metric</th><th>Baseline</th><th>Target</th><th>Delta</th><th>Threshold</th>{}<th>Status</th></tr></thead>\n<tbody>\n",
+        if has_budgets { "<th>Budget</th>" } else { "" },
+    );
+    for circuit in &report.circuits {
+        for (i, m) in circuit.metrics.iter().enumerate() {
+            let name = if i == 0 {
+                html_escape(&circuit.circuit_name)
+            } else {
+                String::new()
+            };
+            let delta = if m.delta_abs == 0.0 {
+                "0".to_string()
+            } else {
+                format!("{:+.1}%", m.delta_pct)
+            };
+            let budget = match report.budget(&circuit.circuit_name, &m.metric) {
+                Some(budget) => format!(
+                    "<td class=\"mono budget-cell{}\">{}</td>",
+                    if m.target > budget { " over" } else { "" },
+                    html_escape(&budget_text(budget, m)),
+                ),
+                None if has_budgets => "<td></td>".to_string(),
+                None => String::new(),
+            };
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"mono {}\">{}</td><td class=\"mono\">{}</td>{}<td class=\"status-cell {}\">{}</td></tr>\n",
+                name,
+                html_escape(&m.metric),
+                format_value(m.baseline, &m.metric),
+                format_value(m.target, &m.metric),
+                delta_class(m),
+                delta,
+                html_escape(&m.threshold_rule.format_threshold(m.threshold)),
+                budget,
+                status_class(m.status),
+                m.status.label(),
+            ));
+        }
+    }
+    html.push_str("</tbody>\n</table>\n");
+    html
+}
+
 /// Write a RegressionReport as a standalone HTML file.
 pub fn write_html(path: &Path, report: &RegressionReport) -> anyhow::Result<()> {
     let html = render_html(report);
@@ -494,8 +727,12 @@ pub fn write_html(path: &Path, report: &RegressionReport) -> anyhow::Result<()>
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::*;
-    use crate::report::{CircuitRegression, MetricDelta, RegressionReport, RegressionStatus};
+    use crate::report::{
+        CircuitRegression, MetricDelta, RegressionReport, RegressionStatus, ThresholdRule,
+    };
 
     fn create_test_report() -> RegressionReport {
         let mut report = RegressionReport::new("baseline.jsonl", "target.jsonl", 10.0);
@@ -511,6 +748,7 @@ mod tests {
                     delta_abs: 20.0,
                     delta_pct: 20.0,
                     threshold: 10.0,
+                    threshold_rule: ThresholdRule::Percent,
                     status: RegressionStatus::ExceededThreshold,
                 },
                 MetricDelta {
@@ -520,6 +758,7 @@ mod tests {
                     delta_abs: 0.0,
                     delta_pct: 0.0,
                     threshold: 10.0,
+                    threshold_rule: ThresholdRule::Percent,
                     status: RegressionStatus::Ok,
                 },
             ],
@@ -536,6 +775,7 @@ mod tests {
                 delta_abs: -50.0,
                 delta_pct: -25.0,
                 threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::Improved,
             }],
             status: RegressionStatus::Improved,
@@ -545,6 +785,75 @@ mod tests {
         report
     }
 
+    #[test]
+    fn test_render_metric_table_static() {
+        let mut report = create_test_report();
+        report.circuits[0].circuit_name = "<b>x</b>".to_string();
+        let table = render_metric_table(&report);
+
+        assert!(!table.contains("<script"));
+        assert!(!table.contains("<b>x</b>"));
+        assert!(table.contains("&lt;b&gt;x&lt;/b&gt;"));
+        assert!(table.contains("status-cell exceeded\">REGRESS</td>"));
+        assert_eq!(
+            table.matches("<tr><td").count(),
+            report
+                .circuits
+                .iter()
+                .map(|c| c.metrics.len())
+                .sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_budget_column_shows_headroom() {
+        let mut report = create_test_report();
+        assert!(!render_metric_table(&report).contains("<th>Budget</th>"));
+
+        report.set_budgets(BTreeMap::from([(
+            "test-circuit".to_string(),
+            BTreeMap::from([("prove_ms".to_string(), 150.0)]),
+        )]));
+        let table = render_metric_table(&report);
+        assert!(table.contains("<th>Budget</th>"));
+        assert!(table.contains(r#"<td class="mono budget-cell">150ms (20% headroom)</td>"#));
+
+        report.set_budgets(BTreeMap::from([(
+            "test-circuit".to_string(),
+            BTreeMap::from([("prove_ms".to_string(), 100.0)]),
+        )]));
+        let html = render_html(&report);
+        assert!(html.contains(r#""budget_display": "100ms (20% over)""#));
+        assert!(html.contains(r#""budget_over": true"#));
+        assert!(render_metric_table(&report).contains("budget-cell over"));
+    }
+
+    #[test]
+    fn test_embedded_values_match_markdown() {
+        let report = create_test_report();
+        let html = render_html(&report);
+        let markdown = crate::report::render_markdown(&report);
+        assert!(html.contains(r#""baseline_display": "100ms""#));
+        assert!(html.contains(r#""target_display": "120ms""#));
+        assert!(html.contains(r#""delta_class": "delta-positive""#));
+        assert!(markdown.contains("100ms"));
+        assert!(markdown.contains("120ms"));
+        assert!(!html.contains("function formatValue"));
+
+        let generated = format_timestamp(&report.metadata.generated_at);
+        assert!(html.contains(&format!(r#""generated_display": "{generated}""#)));
+        assert!(markdown.contains(&format!("| **Generated** | {generated} |")));
+    }
+
+    #[test]
+    fn test_format_value_for_static_table() {
+        assert_eq!(format_value(1500.0, "prove_ms"), "1.50s");
+        assert_eq!(format_value(120.4, "prove_ms"), "120ms");
+        assert_eq!(format_value(25000.0, "gates"), "25.0K");
+        assert_eq!(format_value(2048.0, "proof_size"), "2.0 KB");
+        assert_eq!(format_value(65536.0, "subgroup_size"), "2^16");
+    }
+
     #[test]
     fn test_escape_json_for_html_script() {
         // Should escape < to prevent </script> breakout
@@ -654,6 +963,24 @@ mod tests {
         assert!(!html.contains("<img onerror"));
     }
 
+    #[test]
+    fn test_render_html_includes_environment_mismatches() {
+        let mut report = create_test_report();
+        report
+            .environment_mismatches
+            .push(crate::engine::provenance::EnvironmentMismatch {
+                field: "cpu_model".to_string(),
+                baseline: "<b>EPYC</b>".to_string(),
+                target: "Apple M2".to_string(),
+            });
+
+        let html = render_html(&report);
+
+        assert!(html.contains("Environment Changes"));
+        assert!(html.contains("\"environment_mismatches\""));
+        assert!(!html.contains("<b>EPYC</b>"));
+    }
+
     #[test]
     fn test_write_html() {
         let report = create_test_report();
@@ -815,6 +1142,7 @@ mod tests {
                 delta_abs: 100.0,
                 delta_pct: 10.0,
                 threshold: 5.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::ExceededThreshold,
             }],
             status: RegressionStatus::ExceededThreshold,
@@ -909,6 +1237,7 @@ mod tests {
                 delta_abs: 10.0,
                 delta_pct: 10.0,
                 threshold: 5.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::ExceededThreshold,
             }],
             status: RegressionStatus::ExceededThreshold,
@@ -0,0 +1,229 @@
+//! JUnit XML rendering for CI test summary UIs.
+//!
+//! Each circuit becomes a `<testsuite>` and each of its metrics a
+//! `<testcase>` that fails when the metric regressed, so GitLab, Jenkins and
+//! similar tools list benchmark regressions next to ordinary test failures.
+
+use super::metrics::format_value;
+use super::regression::{RegressionReport, RegressionStatus};
+
+/// Outcome of one test case.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JunitOutcome {
+    Passed,
+    /// A regression: `<failure>`
+    Failed {
+        message: String,
+        details: String,
+    },
+    /// Could not be measured or compared: `<error>`
+    Error {
+        message: String,
+    },
+    Skipped {
+        message: String,
+    },
+}
+
+/// One `<testcase>`.
+#[derive(Debug, Clone)]
+pub struct JunitCase {
+    pub name: String,
+    pub outcome: JunitOutcome,
+    /// Printed as `<system-out>`
+    pub output: Option<String>,
+}
+
+/// One `<testsuite>`.
+#[derive(Debug, Clone)]
+pub struct JunitSuite {
+    pub name: String,
+    pub cases: Vec<JunitCase>,
+}
+
+impl JunitSuite {
+    fn count(&self, f: impl Fn(&JunitOutcome) -> bool) -> usize {
+        self.cases.iter().filter(|c| f(&c.outcome)).count()
+    }
+}
+
+/// One suite per circuit, one case per metric.
+pub fn suites_from_report(report: &RegressionReport) -> Vec<JunitSuite> {
+    let mut circuits: Vec<_> = report.circuits.iter().collect();
+    circuits.sort_by(|a, b| {
+        a.circuit_name
+            .cmp(&b.circuit_name)
+            .then_with(|| a.params.cmp(&b.params))
+    });
+    circuits
+        .into_iter()
+        .map(|circuit| {
+            let name = match circuit.params {
+                Some(p) => format!("{}[{p}]", circuit.circuit_name),
+                None => circuit.circuit_name.clone(),
+            };
+            let cases = circuit
+                .metrics
+                .iter()
+                .map(|m| {
+                    let summary = format!(
+                        "{}: {} -> {} ({:+.1}%, threshold {})",
+                        m.metric,
+                        format_value(m.baseline, &m.metric),
+                        format_value(m.target, &m.metric),
+                        m.delta_pct,
+                        m.threshold_rule.format_threshold(m.threshold)
+                    );
+                    let outcome = match m.status {
+                        RegressionStatus::ExceededThreshold => JunitOutcome::Failed {
+                            message: format!(
+                                "{} regressed {:+.1}% (threshold {})",
+                                m.metric,
+                                m.delta_pct,
+                                m.threshold_rule.format_threshold(m.threshold)
+                            ),
+                            details: summary.clone(),
+                        },
+                        RegressionStatus::Error => JunitOutcome::Error {
+                            message: format!("{} could not be compared", m.metric),
+                        },
+                        RegressionStatus::MissingBaseline => JunitOutcome::Skipped {
+                            message: "no baseline".to_string(),
+                        },
+                        RegressionStatus::Skipped => JunitOutcome::Skipped {
+                            message: "skipped".to_string(),
+                        },
+                        RegressionStatus::Ok | RegressionStatus::Improved => JunitOutcome::Passed,
+                    };
+                    JunitCase {
+                        name: m.metric.clone(),
+                        outcome,
+                        output: Some(summary),
+                    }
+                })
+                .collect();
+            JunitSuite { name, cases }
+        })
+        .collect()
+}
+
+/// Render suites as a JUnit XML document.
+pub fn render_junit(suites: &[JunitSuite]) -> String {
+    let tests: usize = suites.iter().map(|s| s.cases.len()).sum();
+    let failures: usize = suites
+        .iter()
+        .map(|s| s.count(|o| matches!(o, JunitOutcome::Failed { .. })))
+        .sum();
+    let errors: usize = suites
+        .iter()
+        .map(|s| s.count(|o| matches!(o, JunitOutcome::Error { .. })))
+        .sum();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"noir-bench\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\">\n"
+    ));
+    for suite in suites {
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\">\n",
+            escape_xml(&suite.name),
+            suite.cases.len(),
+            suite.count(|o| matches!(o, JunitOutcome::Failed { .. })),
+            suite.count(|o| matches!(o, JunitOutcome::Error { .. })),
+            suite.count(|o| matches!(o, JunitOutcome::Skipped { .. })),
+        ));
+        for case in &suite.cases {
+            out.push_str(&format!(
+                "    <testcase classname=\"noir-bench.{}\" name=\"{}\">\n",
+                escape_xml(&suite.name),
+                escape_xml(&case.name)
+            ));
+            match &case.outcome {
+                JunitOutcome::Passed => {}
+                JunitOutcome::Failed { message, details } => out.push_str(&format!(
+                    "      <failure message=\"{}\" type=\"regression\">{}</failure>\n",
+                    escape_xml(message),
+                    escape_xml(details)
+                )),
+                JunitOutcome::Error { message } => out.push_str(&format!(
+                    "      <error message=\"{}\"/>\n",
+                    escape_xml(message)
+                )),
+                JunitOutcome::Skipped { message } => out.push_str(&format!(
+                    "      <skipped message=\"{}\"/>\n",
+                    escape_xml(message)
+                )),
+            }
+            if let Some(output) = &case.output {
+                out.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    escape_xml(output)
+                ));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{CircuitRegression, MetricDelta, ThresholdRule};
+
+    fn metric(name: &str, baseline: f64, target: f64, status: RegressionStatus) -> MetricDelta {
+        MetricDelta {
+            metric: name.to_string(),
+            baseline,
+            target,
+            delta_abs: target - baseline,
+            delta_pct: (target - baseline) / baseline * 100.0,
+            threshold: 10.0,
+            threshold_rule: ThresholdRule::Percent,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_render_junit_marks_regressions_as_failures() {
+        let mut report = RegressionReport::new("base.jsonl", "target.jsonl", 10.0);
+        report.add_circuit(CircuitRegression {
+            circuit_name: "hash<&>".to_string(),
+            params: None,
+            metrics: vec![
+                metric(
+                    "prove_ms",
+                    100.0,
+                    150.0,
+                    RegressionStatus::ExceededThreshold,
+                ),
+                metric("total_gates", 1000.0, 1000.0, RegressionStatus::Ok),
+            ],
+            status: RegressionStatus::ExceededThreshold,
+        });
+
+        let xml = render_junit(&suites_from_report(&report));
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(
+            xml.contains(
+                "<testsuites name=\"noir-bench\" tests=\"2\" failures=\"1\" errors=\"0\">"
+            )
+        );
+        assert!(xml.contains("<testsuite name=\"hash&lt;&amp;&gt;\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains(
+            "<failure message=\"prove_ms regressed +50.0% (threshold 10.0%)\" type=\"regression\">"
+        ));
+        assert_eq!(xml.matches("<testcase ").count(), 2);
+        assert_eq!(xml.matches("<failure ").count(), 1);
+    }
+}
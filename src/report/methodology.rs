@@ -0,0 +1,345 @@
+//! Methodology section of regression reports.
+//!
+//! A reader outside the team can't tell from a number alone what it covers:
+//! whether prove_ms includes witness generation, how many runs it is the
+//! mean of, how peak memory was taken. The section is built from the
+//! compared records themselves (run config, cache mode, percentile method,
+//! OS) and a glossary of the report's metrics from the registry, and is
+//! rendered as an expandable block in markdown and HTML.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::html::html_escape;
+use super::metrics;
+use super::regression::RegressionReport;
+use crate::core::process::peak_memory_method;
+use crate::core::{BenchRecord, CacheMode, PercentileMethod};
+
+/// Warmup and measured iteration counts shared by some of the records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IterationPlan {
+    pub warmup: u32,
+    pub measured: u32,
+    /// Records measured this way
+    pub records: usize,
+}
+
+/// How the records behind a report were measured; every list holds the
+/// distinct values found across the records.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Methodology {
+    pub records: usize,
+    pub iterations: Vec<IterationPlan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeouts_secs: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cache_modes: Vec<CacheMode>,
+    /// How p95 was computed from the prove samples
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentile_methods: Vec<PercentileMethod>,
+    /// How peak memory was sampled, per OS the records were measured on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_sampling: Vec<String>,
+    /// Extra `nargo compile` flags, one entry per distinct set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compile_flags: Vec<String>,
+    /// Environment variables set for nargo and bb, as `KEY=value` for
+    /// those in `ENV_VALUES_SHOWN` and `KEY` for the rest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+}
+
+/// Environment variables whose values reports show; others may hold
+/// tokens or paths and are listed by name only.
+const ENV_VALUES_SHOWN: &[&str] = &[
+    "RAYON_NUM_THREADS",
+    "OMP_NUM_THREADS",
+    "HARDWARE_CONCURRENCY",
+    "MALLOC_ARENA_MAX",
+    "RUST_MIN_STACK",
+];
+
+/// `KEY=value` for allowlisted variables, else just `KEY`.
+fn env_entry(key: &str, value: &str) -> String {
+    if ENV_VALUES_SHOWN.contains(&key) {
+        format!("{key}={value}")
+    } else {
+        key.to_string()
+    }
+}
+
+fn push_unique<T: PartialEq>(values: &mut Vec<T>, value: T) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+impl Methodology {
+    /// Methodology of `records`; `None` when there are none.
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a BenchRecord>) -> Option<Self> {
+        let mut m = Methodology::default();
+        let mut env = BTreeSet::new();
+        for record in records {
+            m.records += 1;
+            let config = &record.config;
+            match m.iterations.iter_mut().find(|p| {
+                p.warmup == config.warmup_iterations && p.measured == config.measured_iterations
+            }) {
+                Some(plan) => plan.records += 1,
+                None => m.iterations.push(IterationPlan {
+                    warmup: config.warmup_iterations,
+                    measured: config.measured_iterations,
+                    records: 1,
+                }),
+            }
+            if let Some(timeout) = config.timeout_secs {
+                push_unique(&mut m.timeouts_secs, timeout);
+            }
+            if let Some(mode) = record.cache_mode {
+                push_unique(&mut m.cache_modes, mode);
+            }
+            if let Some(stats) = &record.prove_stats {
+                // Records from before the method was recorded used nearest-rank
+                let method = stats.p95_method.unwrap_or(PercentileMethod::NearestRank);
+                push_unique(&mut m.percentile_methods, method);
+            }
+            if record.peak_rss_mb.is_some() {
+                push_unique(&mut m.memory_sampling, peak_memory_method(&record.env.os));
+            }
+            if !config.compile_flags.is_empty() {
+                push_unique(&mut m.compile_flags, config.compile_flags.join(" "));
+            }
+            env.extend(config.env.iter().map(|(k, v)| env_entry(k, v)));
+        }
+        m.iterations
+            .sort_by_key(|p| (std::cmp::Reverse(p.records), p.warmup, p.measured));
+        m.timeouts_secs.sort_unstable();
+        m.env = env.into_iter().collect();
+        (m.records > 0).then_some(m)
+    }
+
+    /// One `(label, value)` line per aspect, for both renderers.
+    fn lines(&self) -> Vec<(&'static str, String)> {
+        let mut lines = Vec::new();
+        let plans: Vec<String> = self
+            .iterations
+            .iter()
+            .map(|p| {
+                format!(
+                    "{} warmup + {} measured ({} record(s))",
+                    p.warmup, p.measured, p.records
+                )
+            })
+            .collect();
+        lines.push(("Iterations", plans.join("; ")));
+        if !self.timeouts_secs.is_empty() {
+            let timeouts: Vec<String> =
+                self.timeouts_secs.iter().map(|t| format!("{t}s")).collect();
+            lines.push(("Timeout", timeouts.join(", ")));
+        }
+        if !self.cache_modes.is_empty() {
+            let modes: Vec<&str> = self
+                .cache_modes
+                .iter()
+                .map(|m| match m {
+                    CacheMode::Warm => "warm (inputs and SRS left in the page cache)",
+                    CacheMode::Cold => "cold (inputs and SRS evicted before every prove)",
+                })
+                .collect();
+            lines.push(("Page cache", modes.join("; ")));
+        }
+        if !self.percentile_methods.is_empty() {
+            let methods: Vec<&str> = self
+                .percentile_methods
+                .iter()
+                .map(|m| match m {
+                    PercentileMethod::Linear => "linear interpolation between ranks",
+                    PercentileMethod::NearestRank => "nearest rank",
+                })
+                .collect();
+            lines.push(("p95", methods.join("; ")));
+        }
+        if !self.memory_sampling.is_empty() {
+            lines.push(("Peak memory", self.memory_sampling.join("; ")));
+        }
+        if !self.compile_flags.is_empty() {
+            lines.push(("Compile flags", self.compile_flags.join("; ")));
+        }
+        if !self.env.is_empty() {
+            lines.push(("Environment", self.env.join(" ")));
+        }
+        lines
+    }
+}
+
+/// Registered metrics of the report, in order of first appearance, with
+/// their definitions.
+fn glossary(report: &RegressionReport) -> Vec<(String, &'static str)> {
+    let mut entries: Vec<(String, &'static str)> = Vec::new();
+    for m in report.circuits.iter().flat_map(|c| &c.metrics) {
+        if entries.iter().any(|(name, _)| *name == m.metric) {
+            continue;
+        }
+        if let Some(description) = metrics::description(&m.metric) {
+            entries.push((m.metric.clone(), description));
+        }
+    }
+    entries
+}
+
+/// Collapsed markdown methodology section; empty when there's nothing to say.
+pub fn render_methodology_markdown(report: &RegressionReport) -> String {
+    let glossary = glossary(report);
+    if report.methodology.is_none() && glossary.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("<details>\n<summary>Methodology</summary>\n\n");
+    if let Some(m) = &report.methodology {
+        for (label, value) in m.lines() {
+            out.push_str(&format!("- **{label}:** {value}\n"));
+        }
+        out.push('\n');
+    }
+    if !glossary.is_empty() {
+        out.push_str("| Metric | Definition |\n|--------|------------|\n");
+        for (name, description) in &glossary {
+            out.push_str(&format!("| {name} | {description} |\n"));
+        }
+        out.push('\n');
+    }
+    out.push_str("</details>\n\n");
+    out
+}
+
+/// Collapsed HTML methodology section; empty when there's nothing to say.
+pub fn render_methodology_html(report: &RegressionReport) -> String {
+    let glossary = glossary(report);
+    if report.methodology.is_none() && glossary.is_empty() {
+        return String::new();
+    }
+    let mut html =
+        String::from("<details class=\"methodology-section\">\n<summary>Methodology</summary>\n");
+    if let Some(m) = &report.methodology {
+        html.push_str("<div class=\"meta-table\">");
+        for (label, value) in m.lines() {
+            html.push_str(&format!(
+                "<span class=\"meta-label\">{label}</span><span class=\"meta-value\">{}</span>",
+                html_escape(&value)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+    if !glossary.is_empty() {
+        html.push_str("<div class=\"table-container\"><table>\n<thead><tr><th>Metric</th><th>Definition</th></tr></thead>\n<tbody>\n");
+        for (name, description) in &glossary {
+            html.push_str(&format!(
+                "<tr><td class=\"mono\">{}</td><td>{}</td></tr>\n",
+                html_escape(name),
+                html_escape(description)
+            ));
+        }
+        html.push_str("</tbody>\n</table></div>\n");
+    }
+    html.push_str("</details>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+    use crate::report::{CircuitRegression, MetricDelta, RegressionStatus, ThresholdRule};
+
+    fn record(warmup: u32, measured: u32, os: &str) -> BenchRecord {
+        let mut record = BenchRecord::new(
+            "merkle".to_string(),
+            EnvironmentInfo {
+                os: os.to_string(),
+                ..EnvironmentInfo::default()
+            },
+            BackendInfo {
+                name: "barretenberg".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig {
+                warmup_iterations: warmup,
+                measured_iterations: measured,
+                timeout_secs: Some(600),
+                env: [
+                    ("RAYON_NUM_THREADS".to_string(), "4".to_string()),
+                    ("API_TOKEN".to_string(), "secret".to_string()),
+                ]
+                .into(),
+                ..RunConfig::default()
+            },
+        );
+        record.cache_mode = Some(CacheMode::Cold);
+        record.prove_stats = Some(TimingStat::from_samples(&[100.0, 110.0]));
+        record.peak_rss_mb = Some(512.0);
+        record
+    }
+
+    #[test]
+    fn test_methodology_from_records() {
+        let records = [
+            record(1, 5, "Linux"),
+            record(1, 5, "Linux"),
+            record(0, 3, "Darwin"),
+        ];
+        let m = Methodology::from_records(&records).unwrap();
+        assert_eq!(m.records, 3);
+        assert_eq!(
+            m.iterations[0],
+            IterationPlan {
+                warmup: 1,
+                measured: 5,
+                records: 2
+            }
+        );
+        assert_eq!(m.timeouts_secs, vec![600]);
+        assert_eq!(m.cache_modes, vec![CacheMode::Cold]);
+        assert_eq!(m.memory_sampling.len(), 2);
+        assert_eq!(m.env, vec!["API_TOKEN", "RAYON_NUM_THREADS=4"]);
+        assert!(Methodology::from_records(&records[..0]).is_none());
+    }
+
+    #[test]
+    fn test_methodology_rendered_with_glossary() {
+        let mut report = RegressionReport::new("base", "target", 10.0);
+        report.add_circuit(CircuitRegression {
+            circuit_name: "merkle".to_string(),
+            params: None,
+            metrics: vec![MetricDelta {
+                metric: "prove_ms".to_string(),
+                baseline: 100.0,
+                target: 100.0,
+                delta_abs: 0.0,
+                delta_pct: 0.0,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status: RegressionStatus::Ok,
+            }],
+            status: RegressionStatus::Ok,
+        });
+        report.methodology = Methodology::from_records(&[record(1, 5, "Linux")]);
+
+        let md = render_methodology_markdown(&report);
+        assert!(md.starts_with("<details>\n<summary>Methodology</summary>"));
+        assert!(md.contains("- **Iterations:** 1 warmup + 5 measured (1 record(s))"));
+        assert!(md.contains("evicted before every prove"));
+        assert!(md.contains("| prove_ms | Mean wall time of the backend prove command"));
+
+        let html = render_methodology_html(&report);
+        assert!(html.contains("<summary>Methodology</summary>"));
+        assert!(html.contains("API_TOKEN RAYON_NUM_THREADS=4"));
+        assert!(!html.contains("secret"));
+        assert!(html.contains("excludes witness generation"));
+
+        report.methodology = None;
+        report.circuits.clear();
+        assert!(render_methodology_markdown(&report).is_empty());
+    }
+}
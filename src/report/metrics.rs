@@ -0,0 +1,388 @@
+//! Metric registry.
+//!
+//! One entry per metric that comparisons and history pages show, with the
+//! direction a change is judged in and the unit its values are displayed in.
+//! `compare`, the regression report, the HTML report and the history site
+//! all look metrics up here, so a new metric (say a throughput, where higher
+//! is better) only needs an entry. Each entry also says what exactly is
+//! measured, for the glossary reports embed.
+
+use serde::Serialize;
+
+use super::units::Unit;
+
+/// Which way a metric improves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Times, sizes, gate counts
+    LowerIsBetter,
+    /// Throughputs such as proofs/sec
+    HigherIsBetter,
+    /// Reported but never a regression or improvement (e.g. key sizes)
+    Informational,
+}
+
+/// A change judged against its threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Worse,
+    Better,
+    Within,
+}
+
+impl Direction {
+    /// Judge a change of `delta_pct` percent against `threshold_pct`.
+    pub fn judge(self, delta_pct: f64, threshold_pct: f64) -> Verdict {
+        let worse_pct = match self {
+            Direction::LowerIsBetter => delta_pct,
+            Direction::HigherIsBetter => -delta_pct,
+            Direction::Informational => return Verdict::Within,
+        };
+        if worse_pct > threshold_pct {
+            Verdict::Worse
+        } else if worse_pct < -threshold_pct {
+            Verdict::Better
+        } else {
+            Verdict::Within
+        }
+    }
+
+    /// Whether a change of `delta_pct` goes the wrong way, ignoring
+    /// thresholds (for colouring deltas). `None` when it is neutral.
+    pub fn is_worse(self, delta_pct: f64) -> Option<bool> {
+        match self {
+            _ if delta_pct == 0.0 => None,
+            Direction::LowerIsBetter => Some(delta_pct > 0.0),
+            Direction::HigherIsBetter => Some(delta_pct < 0.0),
+            Direction::Informational => None,
+        }
+    }
+}
+
+/// A registered metric.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MetricInfo {
+    pub name: &'static str,
+    pub direction: Direction,
+    pub unit: Unit,
+    /// What exactly is measured, for the glossary in reports
+    pub description: &'static str,
+    /// Keys of the metric in command JSON reports (`prove --json`, ...),
+    /// first match wins
+    #[serde(skip)]
+    pub report_keys: &'static [&'static str],
+}
+
+impl MetricInfo {
+    const fn reported_as(self, keys: &'static [&'static str]) -> MetricInfo {
+        MetricInfo {
+            report_keys: keys,
+            ..self
+        }
+    }
+
+    /// Value of the metric in a command JSON report.
+    pub fn report_value(&self, report: &serde_json::Value) -> Option<f64> {
+        self.report_keys
+            .iter()
+            .find_map(|key| report.get(key).and_then(serde_json::Value::as_f64))
+    }
+}
+
+const fn metric(
+    name: &'static str,
+    direction: Direction,
+    unit: Unit,
+    description: &'static str,
+) -> MetricInfo {
+    MetricInfo {
+        name,
+        direction,
+        unit,
+        description,
+        report_keys: &[],
+    }
+}
+
+use Direction::{Informational, LowerIsBetter};
+
+/// Every known metric, by the name used in reports.
+pub const METRICS: &[MetricInfo] = &[
+    // compare / regression report
+    metric(
+        "prove_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of the backend prove command over the measured iterations; excludes witness generation and one-time backend setup, includes proving key construction",
+    )
+    .reported_as(&["prove_time_ms"]),
+    metric(
+        "witness_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of witness generation (ACVM execution of the circuit on its inputs)",
+    )
+    .reported_as(&["witness_gen_time_ms"]),
+    metric(
+        "verify_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of proof verification, steady state when verify_cold_ms is recorded",
+    ),
+    // A single unrepeated run, too noisy to gate on
+    metric(
+        "verify_cold_ms",
+        Informational,
+        Unit::Millis,
+        "First verification iteration, which may read the backend binary, vk and SRS from disk",
+    ),
+    metric(
+        "gates_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of the backend gate count analysis",
+    )
+    .reported_as(&["gates_time_ms"]),
+    metric(
+        "backend_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Backend prove time as reported by the backend itself",
+    )
+    .reported_as(&["backend_prove_time_ms"]),
+    metric(
+        "backend_setup_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "One-time backend setup kept out of prove_ms (--amortize-setup)",
+    )
+    .reported_as(&["backend_setup_ms"]),
+    metric(
+        "vk_gen_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean vk generation time (bb write_vk) over the runs that generated a vk, warmup included",
+    )
+    .reported_as(&["vk_gen_time_ms"]),
+    metric(
+        "prove_cpu_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean CPU time (user + sys) of the backend prove process",
+    ),
+    // More parallelism is only better if it also makes proving faster
+    metric(
+        "prove_parallelism",
+        Informational,
+        Unit::Plain,
+        "Prove CPU time over wall time times logical cores (at most 1.0, all hardware threads busy)",
+    ),
+    metric(
+        "exec_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of unconstrained (Brillig) execution",
+    ),
+    // Estimated from opcode counts, not measured, so never a regression
+    metric(
+        "brillig_ms",
+        Informational,
+        Unit::Millis,
+        "Estimated part of witness generation spent in Brillig (unconstrained) code",
+    ),
+    metric(
+        "foreign_call_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Part of witness generation spent in foreign (oracle) calls",
+    ),
+    metric(
+        "oracle_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Round-trip time to the oracle resolver during witness generation",
+    ),
+    metric(
+        "compile_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of a cold nargo compile (target/ removed first)",
+    ),
+    metric(
+        "compile_warm_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of nargo compile with target/ left in place",
+    ),
+    metric(
+        "compile_incremental_ms",
+        LowerIsBetter,
+        Unit::Millis,
+        "Mean wall time of a recompile after editing one source file",
+    ),
+    metric(
+        "total_gates",
+        LowerIsBetter,
+        Unit::Count,
+        "Backend gate count of the circuit",
+    )
+    .reported_as(&["total_gates", "gate_count"]),
+    // A change is reported as a subgroup crossing instead
+    metric(
+        "subgroup_size",
+        Informational,
+        Unit::PowerOfTwo,
+        "Evaluation domain size: gate count rounded up to the next power of two",
+    )
+    .reported_as(&["subgroup_size"]),
+    metric(
+        "proof_size_bytes",
+        LowerIsBetter,
+        Unit::Bytes,
+        "Size of the proof file",
+    )
+    .reported_as(&["proof_size_bytes"]),
+    metric(
+        "acir_opcodes",
+        LowerIsBetter,
+        Unit::Count,
+        "Number of ACIR opcodes in the compiled circuit",
+    )
+    .reported_as(&["acir_opcodes"]),
+    metric(
+        "peak_memory_bytes",
+        LowerIsBetter,
+        Unit::Bytes,
+        "Peak memory of the backend process, sampled as described under Methodology",
+    )
+    .reported_as(&["peak_memory_bytes"]),
+    metric(
+        "peak_rss_mb",
+        LowerIsBetter,
+        Unit::Megabytes,
+        "Peak memory of the backend process in MB, sampled as described under Methodology",
+    ),
+    metric(
+        "pk_size",
+        Informational,
+        Unit::Bytes,
+        "Size of the proving key",
+    )
+    .reported_as(&["proving_key_size_bytes"]),
+    metric(
+        "vk_size",
+        Informational,
+        Unit::Bytes,
+        "Size of the verification key",
+    )
+    .reported_as(&["verification_key_size_bytes"]),
+    // history index
+    metric(
+        "prove_ms_p50",
+        LowerIsBetter,
+        Unit::Millis,
+        "Median prove time over the measured iterations",
+    ),
+    metric(
+        "prove_ms_p95",
+        LowerIsBetter,
+        Unit::Millis,
+        "95th percentile prove time over the measured iterations",
+    ),
+    metric(
+        "verify_ms_p50",
+        LowerIsBetter,
+        Unit::Millis,
+        "Median verification time over the measured iterations",
+    ),
+    metric(
+        "gates",
+        LowerIsBetter,
+        Unit::Count,
+        "Backend gate count of the circuit",
+    ),
+    metric(
+        "peak_rss_bytes",
+        LowerIsBetter,
+        Unit::Bytes,
+        "Peak memory of the backend process",
+    ),
+    // EVM verification (budgets)
+    metric(
+        "evm_gas",
+        LowerIsBetter,
+        Unit::Count,
+        "Gas used by the on-chain verifier for one proof",
+    ),
+    metric(
+        "calldata_bytes",
+        LowerIsBetter,
+        Unit::Bytes,
+        "Size of the verifier transaction calldata",
+    ),
+];
+
+/// Registry entry for `name`.
+pub fn lookup(name: &str) -> Option<&'static MetricInfo> {
+    METRICS.iter().find(|m| m.name == name)
+}
+
+/// Direction of `metric`; unregistered metrics are lower-is-better.
+pub fn direction(metric: &str) -> Direction {
+    lookup(metric)
+        .map(|m| m.direction)
+        .unwrap_or(Direction::LowerIsBetter)
+}
+
+/// Unit of `metric`, guessed from its name when unregistered.
+pub fn unit(metric: &str) -> Unit {
+    lookup(metric)
+        .map(|m| m.unit)
+        .unwrap_or_else(|| Unit::guess(metric))
+}
+
+/// Glossary entry of `metric`, if registered.
+pub fn description(metric: &str) -> Option<&'static str> {
+    lookup(metric).map(|m| m.description)
+}
+
+/// Format a value of `metric` for display (see `report::units`).
+pub fn format_value(value: f64, metric: &str) -> String {
+    unit(metric).format(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_judges_both_ways() {
+        let lower = Direction::LowerIsBetter;
+        assert_eq!(lower.judge(20.0, 10.0), Verdict::Worse);
+        assert_eq!(lower.judge(-20.0, 10.0), Verdict::Better);
+        assert_eq!(lower.judge(5.0, 10.0), Verdict::Within);
+
+        let higher = Direction::HigherIsBetter;
+        assert_eq!(higher.judge(20.0, 10.0), Verdict::Better);
+        assert_eq!(higher.judge(-20.0, 10.0), Verdict::Worse);
+        assert_eq!(higher.is_worse(-1.0), Some(true));
+
+        assert_eq!(Direction::Informational.judge(500.0, 10.0), Verdict::Within);
+        assert_eq!(Direction::Informational.is_worse(500.0), None);
+    }
+
+    #[test]
+    fn test_registered_and_guessed_units() {
+        assert_eq!(format_value(1500.0, "prove_ms"), "1.50s");
+        assert_eq!(format_value(1536.0, "peak_rss_mb"), "1536.0 MB");
+        assert_eq!(format_value(65536.0, "subgroup_size"), "2^16");
+        assert_eq!(format_value(2500.0, "acir_opcodes"), "2.5K");
+        assert_eq!(format_value(12.5, "proofs_per_sec"), "12.50/s");
+        assert_eq!(format_value(2048.0, "proof_size"), "2.0 KB");
+        assert_eq!(direction("vk_size"), Direction::Informational);
+        assert_eq!(direction("brillig_ms"), Direction::Informational);
+        assert_eq!(direction("verify_cold_ms"), Direction::Informational);
+        assert_eq!(direction("something_new"), Direction::LowerIsBetter);
+    }
+}
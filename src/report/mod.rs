@@ -3,15 +3,53 @@
 //! This module provides:
 //! - `RegressionReport`: Stable machine-readable regression report structure
 //! - Markdown rendering for PR comments
-//! - HTML rendering for standalone reports
+//! - HTML rendering for standalone reports, and a standalone suite report
+//! - PDF export of the HTML reports via headless Chromium
 //! - JSON output for CI pipelines
+//! - JUnit XML and TAP for CI test summary UIs
+//! - GitHub check runs with per-circuit annotations
+//! - A methodology section (run config, sampling, metric glossary) for reports
+//! - A metric registry (direction, unit) and the value formatting shared by
+//!   all of them, with process-wide time zone and digit grouping settings
+//! - Reference datasets for comparing against known hardware
+//! - Alerting rules evaluated after CI and daemon runs
+//! - A side-by-side section for circuits built with different compile flags
+//! - A tradeoff table for expression-width and bb option sweeps
+//! - Pluggable reporters (CSV, markdown, Prometheus, webhooks, ...) picked
+//!   with the global `--reporter` flag
 
+pub mod alerts;
+pub mod compile_configs;
+mod curl;
+pub mod display;
+pub mod github_check;
 pub mod html;
+pub mod junit;
+pub mod methodology;
+pub mod metrics;
+pub mod pdf;
+pub mod reference;
 pub mod regression;
+pub mod reporter;
+pub mod suite_html;
+pub mod sweep;
+pub mod tap;
+pub mod units;
 
 // Re-export key types
-pub use html::{render_html, write_html};
+pub use compile_configs::render_compile_configs;
+pub use display::{DisplaySettings, format_timestamp};
+pub use html::{render_html, render_metric_table, write_html};
+pub use junit::{JunitCase, JunitOutcome, JunitSuite, render_junit, suites_from_report};
+pub use methodology::Methodology;
+pub use metrics::{Direction, MetricInfo, format_value};
 pub use regression::{
-    CircuitRegression, MetricDelta, RegressionReport, RegressionStatus, ReportMetadata,
-    ReportSummary, compute_delta_status, format_value, render_markdown,
+    CircuitRegression, CompareStatus, MetricDelta, MetricSelection, RegressionReport,
+    RegressionStatus, ReportMetadata, ReportSummary, SubgroupCrossing, ThresholdRule,
+    canonical_metric, compute_delta_status, headroom_pct, percent_change, render_markdown,
 };
+pub use reporter::{ReportData, Reporter, open_reporter, open_reporters};
+pub use suite_html::{SuiteReport, SuiteTask, render_suite_html, write_suite_html};
+pub use sweep::{SweepRow, render_sweep, sweep_rows};
+pub use tap::render_tap;
+pub use units::Unit;
@@ -0,0 +1,150 @@
+//! PDF export of HTML reports with headless Chromium.
+//!
+//! Audit and compliance snapshots have to be readable without a browser and
+//! must not change when styles or scripts do, so the standalone HTML reports
+//! (regression, suite) are printed to PDF by a headless Chromium. The binary
+//! is taken from `--chromium`, then `NOIR_BENCH_CHROMIUM`, then the first of
+//! the usual Chromium/Chrome names on PATH.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{BenchError, BenchResult};
+
+/// Environment variable naming the Chromium binary to print with.
+pub const CHROMIUM_ENV: &str = "NOIR_BENCH_CHROMIUM";
+
+/// Binaries tried on PATH, in order.
+const CHROMIUM_NAMES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+];
+
+/// Time given to the page's scripts to finish before printing.
+const VIRTUAL_TIME_BUDGET_MS: u64 = 5000;
+
+fn on_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Chromium binary to print with: `explicit`, else `NOIR_BENCH_CHROMIUM`,
+/// else the first known name found on PATH.
+pub fn find_chromium(explicit: Option<&Path>) -> BenchResult<PathBuf> {
+    if let Some(path) = explicit
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::var_os(CHROMIUM_ENV).map(PathBuf::from))
+    {
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            on_path(&path.to_string_lossy()).ok_or_else(|| {
+                BenchError::Message(format!("chromium binary not found: {}", path.display()))
+            })
+        };
+    }
+    CHROMIUM_NAMES
+        .iter()
+        .find_map(|name| on_path(name))
+        .ok_or_else(|| {
+            BenchError::Message(format!(
+                "no headless chromium found (tried {}); pass --chromium or set {CHROMIUM_ENV}",
+                CHROMIUM_NAMES.join(", ")
+            ))
+        })
+}
+
+/// Arguments printing the page at `html` (absolute) to `pdf`.
+fn chromium_args(html: &Path, pdf: &Path) -> BenchResult<Vec<String>> {
+    // Percent-encodes spaces, `#`, `?` and non-ASCII in the path
+    let url = url::Url::from_file_path(html).map_err(|()| {
+        BenchError::Message(format!("cannot make a file URL of {}", html.display()))
+    })?;
+    Ok(vec![
+        "--headless".to_string(),
+        "--disable-gpu".to_string(),
+        "--no-sandbox".to_string(),
+        "--no-pdf-header-footer".to_string(),
+        format!("--virtual-time-budget={VIRTUAL_TIME_BUDGET_MS}"),
+        format!("--print-to-pdf={}", pdf.display()),
+        url.to_string(),
+    ])
+}
+
+fn absolute(path: &Path) -> BenchResult<PathBuf> {
+    std::path::absolute(path)
+        .map_err(|e| BenchError::Message(format!("invalid path {}: {e}", path.display())))
+}
+
+/// Print the HTML file at `html` to `pdf`.
+pub fn html_file_to_pdf(html: &Path, pdf: &Path, chromium: Option<&Path>) -> BenchResult<()> {
+    let chromium = find_chromium(chromium)?;
+    let (html, pdf) = (absolute(html)?, absolute(pdf)?);
+    if let Some(dir) = pdf.parent() {
+        std::fs::create_dir_all(dir).ok();
+    }
+    let _ = std::fs::remove_file(&pdf);
+
+    let output = Command::new(&chromium)
+        .args(chromium_args(&html, &pdf)?)
+        .output()
+        .map_err(|e| BenchError::Message(format!("failed to run {}: {e}", chromium.display())))?;
+    if !output.status.success() {
+        return Err(BenchError::Message(format!(
+            "{} failed to print {}: {}",
+            chromium.display(),
+            html.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    // Chromium exits 0 on some print failures, so check what it wrote
+    let head = std::fs::read(&pdf).unwrap_or_default();
+    if !head.starts_with(b"%PDF") {
+        return Err(BenchError::Message(format!(
+            "{} wrote no PDF for {}",
+            chromium.display(),
+            html.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Print rendered report HTML to `pdf`.
+pub fn html_to_pdf(html: &str, pdf: &Path, chromium: Option<&Path>) -> BenchResult<()> {
+    let page = tempfile::Builder::new()
+        .suffix(".html")
+        .tempfile()
+        .map_err(|e| BenchError::Message(format!("failed to create temp file: {e}")))?;
+    std::fs::write(page.path(), html)
+        .map_err(|e| BenchError::Message(format!("failed to write temp file: {e}")))?;
+    html_file_to_pdf(page.path(), pdf, chromium)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chromium_args_print_local_file() {
+        let args =
+            chromium_args(Path::new("/tmp/r/report.html"), Path::new("/tmp/r/out.pdf")).unwrap();
+        assert_eq!(args[0], "--headless");
+        assert!(args.contains(&"--print-to-pdf=/tmp/r/out.pdf".to_string()));
+        assert_eq!(args.last().unwrap(), "file:///tmp/r/report.html");
+
+        let args =
+            chromium_args(Path::new("/tmp/my runs/#1.html"), Path::new("/tmp/out.pdf")).unwrap();
+        assert_eq!(args.last().unwrap(), "file:///tmp/my%20runs/%231.html");
+    }
+
+    #[test]
+    fn test_find_chromium_rejects_missing_binary() {
+        let err = find_chromium(Some(Path::new("/nonexistent/chromium"))).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/chromium"));
+    }
+}
@@ -0,0 +1,249 @@
+//! Published reference results for the benchmark corpus.
+//!
+//! A reference dataset holds corpus results measured on known hardware, each
+//! machine tagged with its calibration score (see `core::calibration`).
+//! Reference timings are scaled by the ratio of calibration scores before
+//! being compared with local results, so a slower machine is not reported as
+//! a regression just for being slower.
+//!
+//! No dataset is vendored: results must come from real runs, so datasets are
+//! loaded from a URL or a JSON file.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::calibration::{CALIBRATION_VERSION, normalize_ms};
+use crate::core::{BackendInfo, BenchRecord, EnvironmentInfo, RunConfig, TimingStat};
+use crate::{BenchError, BenchResult};
+
+/// A set of reference machines with their corpus results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceDataset {
+    pub name: String,
+    /// Corpus version the results were measured on (e.g. "v1")
+    pub corpus_version: String,
+    /// Calibration workload version the machine scores were measured with
+    pub calibration_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub machines: Vec<ReferenceMachine>,
+}
+
+/// One reference machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceMachine {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_cores: Option<u32>,
+    pub os: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bb_version: Option<String>,
+    pub calibration_score: f64,
+    /// Results keyed by corpus circuit name
+    pub results: BTreeMap<String, ReferenceResult>,
+}
+
+/// Reference metrics for one corpus circuit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReferenceResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prove_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_gates: Option<u64>,
+}
+
+/// Parse a reference dataset from JSON.
+pub fn parse(json: &str) -> BenchResult<ReferenceDataset> {
+    let dataset: ReferenceDataset = serde_json::from_str(json)
+        .map_err(|e| BenchError::Message(format!("invalid reference dataset: {e}")))?;
+    if dataset.calibration_version != CALIBRATION_VERSION {
+        return Err(BenchError::Message(format!(
+            "reference '{}' uses calibration v{}, this build measures v{}",
+            dataset.name, dataset.calibration_version, CALIBRATION_VERSION
+        )));
+    }
+    Ok(dataset)
+}
+
+/// Load a reference dataset: an http(s) URL (downloaded with curl) or a
+/// path to a JSON file.
+pub fn load(spec: &str) -> BenchResult<ReferenceDataset> {
+    if spec.starts_with("https://") || spec.starts_with("http://") {
+        let output = Command::new("curl")
+            .args(["-fsSL", spec])
+            .output()
+            .map_err(|e| BenchError::Message(format!("failed to run curl: {e}")))?;
+        if !output.status.success() {
+            return Err(BenchError::Message(format!(
+                "failed to download {spec}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        return parse(&String::from_utf8_lossy(&output.stdout));
+    }
+    let path = Path::new(spec);
+    if !path.exists() {
+        return Err(BenchError::Message(format!(
+            "reference '{spec}' not found (pass a URL or a JSON file)"
+        )));
+    }
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {spec}: {e}")))?;
+    parse(&json)
+}
+
+impl ReferenceDataset {
+    /// Pick a machine by id, or the one whose calibration score is closest
+    /// to `local_score` when no id is given.
+    pub fn select_machine(
+        &self,
+        id: Option<&str>,
+        local_score: f64,
+    ) -> BenchResult<&ReferenceMachine> {
+        match id {
+            Some(id) => self.machines.iter().find(|m| m.id == id).ok_or_else(|| {
+                let known: Vec<_> = self.machines.iter().map(|m| m.id.as_str()).collect();
+                BenchError::Message(format!(
+                    "no machine '{id}' in reference '{}' (known: {})",
+                    self.name,
+                    known.join(", ")
+                ))
+            }),
+            None => self
+                .machines
+                .iter()
+                .min_by(|a, b| {
+                    let da = (a.calibration_score - local_score).abs();
+                    let db = (b.calibration_score - local_score).abs();
+                    da.total_cmp(&db)
+                })
+                .ok_or_else(|| {
+                    BenchError::Message(format!("reference '{}' has no machines", self.name))
+                }),
+        }
+    }
+}
+
+impl ReferenceMachine {
+    /// Build baseline records from this machine's results, with timings
+    /// scaled to a machine with `local_score`. Gate counts are hardware
+    /// independent and kept as-is.
+    pub fn normalized_records(&self, local_score: f64) -> Vec<BenchRecord> {
+        let env = EnvironmentInfo {
+            cpu_model: self.cpu_model.clone(),
+            cpu_cores: self.cpu_cores,
+            os: self.os.clone(),
+            bb_version: self.bb_version.clone(),
+            ..EnvironmentInfo::default()
+        };
+        let backend = BackendInfo {
+            name: "barretenberg".to_string(),
+            version: self.bb_version.clone(),
+            variant: None,
+        };
+        let scale = |ms: f64| {
+            TimingStat::from_samples(&[normalize_ms(ms, self.calibration_score, local_score)])
+        };
+
+        self.results
+            .iter()
+            .map(|(circuit, result)| {
+                let mut record = BenchRecord::new(
+                    circuit.clone(),
+                    env.clone(),
+                    backend.clone(),
+                    RunConfig::default(),
+                );
+                record.prove_stats = result.prove_ms.map(scale);
+                record.verify_stats = result.verify_ms.map(scale);
+                record.total_gates = result.total_gates;
+                record
+                    .metadata
+                    .insert("reference_machine".to_string(), self.id.clone());
+                record
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATASET: &str = r#"{
+        "name": "test",
+        "corpus_version": "v1",
+        "calibration_version": 1,
+        "machines": [
+            {"id": "slow", "os": "linux", "calibration_score": 50.0,
+             "results": {"merkle_proof": {"prove_ms": 2000.0}}},
+            {"id": "fast", "os": "linux", "calibration_score": 150.0,
+             "results": {"merkle_proof": {"prove_ms": 700.0}}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_load_reference_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reference.json");
+        std::fs::write(&path, DATASET).unwrap();
+        let dataset = load(path.to_str().unwrap()).unwrap();
+        assert_eq!(dataset.name, "test");
+        assert_eq!(dataset.machines.len(), 2);
+
+        let err = load("community-v1").unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_rejects_other_calibration_version() {
+        let json = r#"{"name":"x","corpus_version":"v1","calibration_version":99,"machines":[]}"#;
+        assert!(parse(json).is_err());
+    }
+
+    #[test]
+    fn test_select_machine_closest_score() {
+        let dataset = parse(DATASET).unwrap();
+        assert_eq!(dataset.select_machine(None, 10_000.0).unwrap().id, "fast");
+        assert_eq!(dataset.select_machine(None, 60.0).unwrap().id, "slow");
+        assert_eq!(
+            dataset.select_machine(Some("slow"), 1.0).unwrap().id,
+            "slow"
+        );
+        assert!(dataset.select_machine(Some("nope"), 1.0).is_err());
+    }
+
+    #[test]
+    fn test_normalized_records_scale_timings() {
+        let machine = ReferenceMachine {
+            id: "ref".to_string(),
+            cpu_model: None,
+            cpu_cores: None,
+            os: "linux".to_string(),
+            bb_version: None,
+            calibration_score: 100.0,
+            results: BTreeMap::from([(
+                "merkle_proof".to_string(),
+                ReferenceResult {
+                    prove_ms: Some(1000.0),
+                    verify_ms: None,
+                    total_gates: Some(42),
+                },
+            )]),
+        };
+
+        // Local machine is half as fast: expect twice the time
+        let records = machine.normalized_records(50.0);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].prove_stats.as_ref().unwrap().mean_ms, 2000.0);
+        assert!(records[0].verify_stats.is_none());
+        assert_eq!(records[0].total_gates, Some(42));
+    }
+}
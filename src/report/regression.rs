@@ -5,15 +5,25 @@
 //! - Rendered to Markdown for PR comments
 //! - Used to determine CI exit codes
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
-use crate::engine::provenance::{Provenance, VersionMismatch};
+use super::display::format_timestamp;
+use super::methodology::{Methodology, render_methodology_markdown};
+use super::metrics::{Direction, Verdict, format_value};
+use crate::core::annotations;
+use crate::engine::provenance::{EnvironmentMismatch, Provenance, ThermalWarning, VersionMismatch};
 
 /// Schema version for RegressionReport
 pub const REGRESSION_REPORT_VERSION: u32 = 1;
 
+/// Per-circuit threshold overrides: circuit name -> metric -> percent.
+///
+/// A `default` entry replaces the default threshold for that circuit (and
+/// shadows the global per-metric thresholds).
+pub type CircuitThresholds = BTreeMap<String, BTreeMap<String, f64>>;
+
 /// A complete regression report.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegressionReport {
@@ -28,6 +38,18 @@ pub struct RegressionReport {
     /// Tool version mismatches between baseline and target
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub version_mismatches: Vec<VersionMismatch>,
+    /// Machine differences (CPU, cores, RAM, OS, governor) between baseline and target
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environment_mismatches: Vec<EnvironmentMismatch>,
+    /// Records measured under elevated thermal pressure
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thermal_warnings: Vec<ThermalWarning>,
+    /// Circuits whose subgroup size changed between baseline and target
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subgroup_crossings: Vec<SubgroupCrossing>,
+    /// How the compared records were measured (see `report::methodology`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub methodology: Option<Methodology>,
 }
 
 /// Metadata about the regression report.
@@ -44,6 +66,18 @@ pub struct ReportMetadata {
     /// Per-metric thresholds when configured
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub metric_thresholds: BTreeMap<String, f64>,
+    /// Per-circuit threshold overrides (circuit -> metric -> percent)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub circuit_thresholds: BTreeMap<String, BTreeMap<String, f64>>,
+    /// Run annotations such as `commit` and `pr` (see `core::annotations`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    /// Metrics reported without ever counting as a regression
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub informational_metrics: BTreeSet<String>,
+    /// Absolute budgets (SLOs) from `[budgets]`, per circuit (circuit -> metric -> limit)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub budgets: BTreeMap<String, BTreeMap<String, f64>>,
     /// Baseline provenance (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub baseline_provenance: Option<Provenance>,
@@ -72,19 +106,137 @@ pub struct MetricDelta {
     /// Metric name (e.g., "prove_ms", "gates", "proof_size")
     pub metric: String,
     /// Baseline value
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub baseline: f64,
     /// Target value
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub target: f64,
     /// Absolute delta (target - baseline)
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub delta_abs: f64,
     /// Percentage change ((target - baseline) / baseline * 100)
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub delta_pct: f64,
     /// Threshold that was applied
+    #[serde(serialize_with = "crate::core::rounding::serialize")]
     pub threshold: f64,
+    /// How the threshold was derived
+    #[serde(default, skip_serializing_if = "ThresholdRule::is_percent")]
+    pub threshold_rule: ThresholdRule,
     /// Status for this metric
     pub status: RegressionStatus,
 }
 
+/// How the threshold for a metric was derived.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ThresholdRule {
+    /// Flat percentage (default, per-metric or per-circuit threshold)
+    #[default]
+    Percent,
+    /// `k` standard deviations of the baseline samples, expressed as a
+    /// percentage of the baseline mean
+    Noise { k: f64, stddev_ms: f64 },
+}
+
+impl ThresholdRule {
+    pub fn is_percent(&self) -> bool {
+        matches!(self, ThresholdRule::Percent)
+    }
+
+    /// Threshold for display, e.g. `10.0%` or `4.2% (3σ)`.
+    pub fn format_threshold(&self, threshold: f64) -> String {
+        match self {
+            ThresholdRule::Percent => format!("{threshold:.1}%"),
+            ThresholdRule::Noise { k, .. } => format!("{threshold:.1}% ({k}σ)"),
+        }
+    }
+}
+
+/// A circuit that crossed a power-of-two subgroup boundary.
+///
+/// Backend prove time scales with the subgroup size (the next power of two
+/// of the gate count), not the gate count itself, so a few extra gates can
+/// double prove time. These are reported explicitly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubgroupCrossing {
+    pub circuit_name: String,
+    pub baseline_subgroup: u64,
+    pub target_subgroup: u64,
+    /// Gate count change, when both sides report gates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gates_delta_pct: Option<f64>,
+}
+
+impl SubgroupCrossing {
+    /// Find a subgroup change in a circuit's metrics.
+    pub fn detect(circuit: &CircuitRegression) -> Option<SubgroupCrossing> {
+        let metric = |name: &str| circuit.metrics.iter().find(|m| m.metric == name);
+        let subgroup = metric("subgroup_size")?;
+        if subgroup.baseline <= 0.0
+            || subgroup.target <= 0.0
+            || subgroup.baseline == subgroup.target
+        {
+            return None;
+        }
+        Some(SubgroupCrossing {
+            circuit_name: circuit.circuit_name.clone(),
+            baseline_subgroup: subgroup.baseline as u64,
+            target_subgroup: subgroup.target as u64,
+            gates_delta_pct: metric("total_gates").map(|m| m.delta_pct),
+        })
+    }
+
+    /// Expected prove time ratio (target / baseline).
+    pub fn prove_factor(&self) -> f64 {
+        self.target_subgroup as f64 / self.baseline_subgroup as f64
+    }
+
+    /// One-line explanation, e.g. "gates +3.0% but subgroup doubled
+    /// (2^16 → 2^17): expect ~2x prove time".
+    pub fn describe(&self) -> String {
+        // Subgroup sizes are powers of two, so the factor is exact
+        let factor = self.prove_factor();
+        let change = if factor == 2.0 {
+            "doubled".to_string()
+        } else if factor == 0.5 {
+            "halved".to_string()
+        } else if factor > 1.0 {
+            format!("grew {factor}x")
+        } else {
+            format!("shrank {}x", 1.0 / factor)
+        };
+        let gates = self
+            .gates_delta_pct
+            .map(|pct| format!("gates {pct:+.1}% but "))
+            .unwrap_or_default();
+        format!(
+            "{gates}subgroup {change} (2^{} → 2^{}): expect ~{factor}x prove time",
+            self.baseline_subgroup.trailing_zeros(),
+            self.target_subgroup.trailing_zeros()
+        )
+    }
+}
+
+/// Status of a metric comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompareStatus {
+    Regression,
+    Improvement,
+    Unchanged,
+}
+
+impl CompareStatus {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            CompareStatus::Regression => "🔴",
+            CompareStatus::Improvement => "🟢",
+            CompareStatus::Unchanged => "⚪",
+        }
+    }
+}
+
 /// Status of a regression check.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -181,6 +333,10 @@ impl RegressionReport {
                 generated_at,
                 threshold_percent,
                 metric_thresholds: BTreeMap::new(),
+                circuit_thresholds: BTreeMap::new(),
+                annotations: BTreeMap::new(),
+                informational_metrics: BTreeSet::new(),
+                budgets: BTreeMap::new(),
                 baseline_provenance: None,
                 target_provenance: None,
             },
@@ -198,6 +354,10 @@ impl RegressionReport {
                 ci_exit_code: 0,
             },
             version_mismatches: Vec::new(),
+            environment_mismatches: Vec::new(),
+            thermal_warnings: Vec::new(),
+            subgroup_crossings: Vec::new(),
+            methodology: None,
         }
     }
 
@@ -234,6 +394,10 @@ impl RegressionReport {
             }
         }
 
+        if let Some(crossing) = SubgroupCrossing::detect(&circuit) {
+            self.subgroup_crossings.push(crossing);
+        }
+
         self.circuits.push(circuit);
     }
 
@@ -250,6 +414,8 @@ impl RegressionReport {
     pub fn set_provenance(&mut self, baseline: Option<Provenance>, target: Option<Provenance>) {
         if let (Some(b), Some(t)) = (&baseline, &target) {
             self.version_mismatches = crate::engine::provenance::check_version_mismatches(b, t);
+            self.environment_mismatches =
+                crate::engine::provenance::check_environment_mismatches(&b.system, &t.system);
         }
         self.metadata.baseline_provenance = baseline;
         self.metadata.target_provenance = target;
@@ -259,74 +425,180 @@ impl RegressionReport {
     pub fn set_metric_thresholds(&mut self, thresholds: BTreeMap<String, f64>) {
         self.metadata.metric_thresholds = thresholds;
     }
+
+    /// Set run annotations (commit, PR, ...) rendered as links in reports.
+    pub fn set_annotations(&mut self, annotations: BTreeMap<String, String>) {
+        self.metadata.annotations = annotations;
+    }
+
+    /// Set per-circuit threshold overrides used for comparison.
+    pub fn set_circuit_thresholds(&mut self, thresholds: BTreeMap<String, BTreeMap<String, f64>>) {
+        self.metadata.circuit_thresholds = thresholds;
+    }
+
+    /// Set the metrics that were compared as informational only.
+    pub fn set_informational_metrics(&mut self, metrics: BTreeSet<String>) {
+        self.metadata.informational_metrics = metrics;
+    }
+
+    /// Set the per-circuit budgets shown next to the target values.
+    pub fn set_budgets(&mut self, budgets: BTreeMap<String, BTreeMap<String, f64>>) {
+        self.metadata.budgets = budgets;
+    }
+
+    /// Budget of a compared metric for a circuit; budget names may be aliases
+    /// (`gates` for `total_gates`).
+    pub fn budget(&self, circuit: &str, metric: &str) -> Option<f64> {
+        self.metadata
+            .budgets
+            .get(circuit)?
+            .iter()
+            .find(|(name, _)| canonical_metric(name) == metric)
+            .map(|(_, budget)| *budget)
+    }
+}
+
+/// Headroom of `value` under `budget` as a percentage of the budget; negative
+/// when over it.
+pub fn headroom_pct(value: f64, budget: f64) -> f64 {
+    if budget == 0.0 {
+        if value > 0.0 { -100.0 } else { 0.0 }
+    } else {
+        (budget - value) / budget * 100.0
+    }
+}
+
+/// Short names accepted wherever metrics are selected by name.
+const METRIC_ALIASES: &[(&str, &str)] = &[
+    ("gates", "total_gates"),
+    ("proof_size", "proof_size_bytes"),
+    ("peak_rss", "peak_rss_mb"),
+    ("peak_memory", "peak_memory_bytes"),
+    ("opcodes", "acir_opcodes"),
+];
+
+/// Resolve a metric alias (`gates`, `proof_size`, `peak_rss`, ...) to the
+/// name used in comparisons; other names are returned unchanged.
+pub fn canonical_metric(name: &str) -> &str {
+    METRIC_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, metric)| *metric)
+        .unwrap_or(name)
+}
+
+/// Which metrics regression analysis considers (`--metrics`, `[ci] metrics`).
+///
+/// Informational metrics are compared and reported like any other, but a
+/// change in them never fails a run or counts as an improvement.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricSelection {
+    /// Metrics to compare; empty compares every metric
+    pub metrics: BTreeSet<String>,
+    /// Metrics reported but never failing
+    pub informational: BTreeSet<String>,
+}
+
+impl MetricSelection {
+    /// Build a selection from metric names or aliases.
+    pub fn new(metrics: &[String], informational: &[String]) -> Self {
+        let canonical = |names: &[String]| {
+            names
+                .iter()
+                .map(|n| n.trim())
+                .filter(|n| !n.is_empty())
+                .map(|n| canonical_metric(n).to_string())
+                .collect()
+        };
+        MetricSelection {
+            metrics: canonical(metrics),
+            informational: canonical(informational),
+        }
+    }
+
+    /// Whether `metric` is compared at all. Informational metrics are
+    /// always compared, even when not listed in `metrics`.
+    pub fn includes(&self, metric: &str) -> bool {
+        self.metrics.is_empty()
+            || self.metrics.contains(metric)
+            || self.informational.contains(metric)
+    }
+
+    /// Whether `metric` is reported without counting toward the result.
+    pub fn is_informational(&self, metric: &str) -> bool {
+        self.informational.contains(metric)
+    }
+
+    /// Names in the selection that are not in `known`.
+    pub fn unknown<'a>(&'a self, known: &[&str]) -> Vec<&'a str> {
+        self.metrics
+            .iter()
+            .chain(&self.informational)
+            .map(String::as_str)
+            .filter(|m| !known.contains(m))
+            .collect()
+    }
 }
 
 /// Compute delta status based on threshold.
 ///
-/// For metrics where higher is worse (time, memory, gates), a positive delta
-/// exceeding threshold is a regression.
+/// `direction` (see `report::metrics`) decides whether a rise or a fall past
+/// the threshold is the regression.
 pub fn compute_delta_status(
     baseline: f64,
     target: f64,
     threshold_pct: f64,
-    higher_is_worse: bool,
+    direction: Direction,
 ) -> (f64, f64, RegressionStatus) {
     let delta_abs = target - baseline;
-    let delta_pct = if baseline != 0.0 {
-        delta_abs * 100.0 / baseline
-    } else {
-        0.0
-    };
+    let delta_pct = percent_change(baseline, target);
 
-    let status = if higher_is_worse {
-        if delta_pct > threshold_pct {
-            RegressionStatus::ExceededThreshold
-        } else if delta_pct < -threshold_pct {
-            RegressionStatus::Improved
-        } else {
-            RegressionStatus::Ok
-        }
-    } else {
-        // For metrics where lower is worse (informational only)
-        RegressionStatus::Ok
+    let status = match direction.judge(delta_pct, threshold_pct) {
+        Verdict::Worse => RegressionStatus::ExceededThreshold,
+        Verdict::Better => RegressionStatus::Improved,
+        Verdict::Within => RegressionStatus::Ok,
     };
 
     (delta_abs, delta_pct, status)
 }
 
-/// Format a numeric value for display.
-pub fn format_value(value: f64, metric: &str) -> String {
-    if metric.contains("size") || metric.contains("mem") || metric.contains("rss") {
-        if metric.contains("rss_mb") {
-            format!("{:.1} MB", value)
-        } else if value >= 1_000_000_000.0 {
-            format!("{:.1} GB", value / 1_000_000_000.0)
-        } else if value >= 1_000_000.0 {
-            format!("{:.1} MB", value / 1_000_000.0)
-        } else if value >= 1_000.0 {
-            format!("{:.1} KB", value / 1_000.0)
-        } else {
-            format!("{:.0} B", value)
-        }
-    } else if metric.contains("ms") {
-        if value >= 1000.0 {
-            format!("{:.2}s", value / 1000.0)
-        } else {
-            format!("{:.0}ms", value)
-        }
-    } else if metric.contains("gates") {
-        if value >= 1_000_000.0 {
-            format!("{:.2}M", value / 1_000_000.0)
-        } else if value >= 1_000.0 {
-            format!("{:.1}K", value / 1_000.0)
-        } else {
-            format!("{:.0}", value)
-        }
+/// Percentage change from `baseline` to `target`.
+///
+/// Zero when the baseline is zero or the result isn't finite (a denormal
+/// baseline, corrupt input), so reports never carry NaN or infinity.
+pub fn percent_change(baseline: f64, target: f64) -> f64 {
+    let pct = (target - baseline) * 100.0 / baseline;
+    if baseline != 0.0 && pct.is_finite() {
+        pct
     } else {
-        format!("{:.2}", value)
+        0.0
     }
 }
 
+/// Escape text for a markdown table cell: a `|` would end the cell (even in
+/// a code span) and a newline the row.
+pub(crate) fn escape_markdown(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Escape text for inline markdown outside a table (list items, headings):
+/// emphasis, code, link and HTML characters are backslash-escaped so a name
+/// renders as written.
+pub(crate) fn escape_markdown_inline(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '|' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\r' | '\n' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 /// Render regression report as Markdown for PR comments.
 pub fn render_markdown(report: &RegressionReport) -> String {
     let mut out = String::new();
@@ -351,14 +623,35 @@ pub fn render_markdown(report: &RegressionReport) -> String {
          | **Baseline** | `{}` |\n\
          | **Target** | `{}` |\n\
          | **Default Threshold** | {:.1}% |\n\
-         | **Generated** | {} |\n\n",
-        report.metadata.baseline_id,
-        report.metadata.target_id,
+         | **Generated** | {} |\n",
+        escape_markdown(&report.metadata.baseline_id),
+        escape_markdown(&report.metadata.target_id),
         report.metadata.threshold_percent,
-        &report.metadata.generated_at[..19].replace('T', " ")
+        format_timestamp(&report.metadata.generated_at)
     ));
+    let annotations = &report.metadata.annotations;
+    for (key, value) in annotations {
+        let shown = escape_markdown(value);
+        let cell = match annotations::href(key, value, annotations) {
+            Some(href) => format!("[`{shown}`]({})", escape_markdown(&href)),
+            None => format!("`{shown}`"),
+        };
+        out.push_str(&format!("| **{}** | {cell} |\n", escape_markdown(key)));
+    }
+    if !report.metadata.informational_metrics.is_empty() {
+        let metrics: Vec<&str> = report
+            .metadata
+            .informational_metrics
+            .iter()
+            .map(String::as_str)
+            .collect();
+        out.push_str(&format!("| **Informational** | {} |\n", metrics.join(", ")));
+    }
+    out.push('\n');
 
-    if !report.metadata.metric_thresholds.is_empty() {
+    if !report.metadata.metric_thresholds.is_empty()
+        || !report.metadata.circuit_thresholds.is_empty()
+    {
         out.push_str("### Thresholds\n\n");
         out.push_str("| Metric | Threshold |\n|--------|-----------|\n");
         out.push_str(&format!(
@@ -366,7 +659,21 @@ pub fn render_markdown(report: &RegressionReport) -> String {
             report.metadata.threshold_percent
         ));
         for (metric, threshold) in &report.metadata.metric_thresholds {
-            out.push_str(&format!("| {} | {:.1}% |\n", metric, threshold));
+            out.push_str(&format!(
+                "| {} | {:.1}% |\n",
+                escape_markdown(metric),
+                threshold
+            ));
+        }
+        for (circuit, thresholds) in &report.metadata.circuit_thresholds {
+            for (metric, threshold) in thresholds {
+                out.push_str(&format!(
+                    "| {} ({}) | {:.1}% |\n",
+                    escape_markdown(metric),
+                    escape_markdown(circuit),
+                    threshold
+                ));
+            }
         }
         out.push_str("\n");
     }
@@ -378,14 +685,48 @@ pub fn render_markdown(report: &RegressionReport) -> String {
         for m in &report.version_mismatches {
             out.push_str(&format!(
                 "| {} | {} | {} |\n",
-                m.tool,
-                m.baseline_version.as_deref().unwrap_or("-"),
-                m.target_version.as_deref().unwrap_or("-")
+                escape_markdown(&m.tool),
+                escape_markdown(m.baseline_version.as_deref().unwrap_or("-")),
+                escape_markdown(m.target_version.as_deref().unwrap_or("-"))
             ));
         }
         out.push_str("\n");
     }
 
+    // Environment mismatch warnings
+    if !report.environment_mismatches.is_empty() {
+        out.push_str("### ⚠️ Environment Changes\n\n");
+        out.push_str(
+            "Baseline and target ran on different machines; deltas may reflect the hardware rather than the code.\n\n",
+        );
+        out.push_str("| Property | Baseline | Target |\n|----------|----------|--------|\n");
+        for m in &report.environment_mismatches {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                escape_markdown(&m.field),
+                escape_markdown(&m.baseline),
+                escape_markdown(&m.target)
+            ));
+        }
+        out.push('\n');
+    }
+
+    if !report.thermal_warnings.is_empty() {
+        out.push_str("### ⚠️ Thermal Throttling\n\n");
+        for w in &report.thermal_warnings {
+            out.push_str(&format!("- `{}`: {}\n", w.circuit_name, w.describe()));
+        }
+        out.push('\n');
+    }
+
+    if !report.subgroup_crossings.is_empty() {
+        out.push_str("### ⚠️ Subgroup Size Changes\n\n");
+        for c in &report.subgroup_crossings {
+            out.push_str(&format!("- `{}`: {}\n", c.circuit_name, c.describe()));
+        }
+        out.push('\n');
+    }
+
     // Summary box
     out.push_str("### Summary\n\n");
     out.push_str(&format!(
@@ -410,13 +751,13 @@ pub fn render_markdown(report: &RegressionReport) -> String {
             for metric in &circuit.metrics {
                 if metric.status == RegressionStatus::ExceededThreshold {
                     out.push_str(&format!(
-                        "| {} | {} | {} | {} | {:+.1}% | > {:.1}% | {} |\n",
-                        circuit.circuit_name,
-                        metric.metric,
+                        "| {} | {} | {} | {} | {:+.1}% | > {} | {} |\n",
+                        escape_markdown(&circuit.circuit_name),
+                        escape_markdown(&metric.metric),
                         format_value(metric.baseline, &metric.metric),
                         format_value(metric.target, &metric.metric),
                         metric.delta_pct,
-                        metric.threshold,
+                        metric.threshold_rule.format_threshold(metric.threshold),
                         metric.status.emoji()
                     ));
                 }
@@ -438,13 +779,13 @@ pub fn render_markdown(report: &RegressionReport) -> String {
             for metric in &circuit.metrics {
                 if metric.status == RegressionStatus::Improved {
                     out.push_str(&format!(
-                        "| {} | {} | {} | {} | {:+.1}% | < -{:.1}% | {} |\n",
-                        circuit.circuit_name,
-                        metric.metric,
+                        "| {} | {} | {} | {} | {:+.1}% | < -{} | {} |\n",
+                        escape_markdown(&circuit.circuit_name),
+                        escape_markdown(&metric.metric),
                         format_value(metric.baseline, &metric.metric),
                         format_value(metric.target, &metric.metric),
                         metric.delta_pct,
-                        metric.threshold,
+                        metric.threshold_rule.format_threshold(metric.threshold),
                         metric.status.emoji()
                     ));
                 }
@@ -464,25 +805,30 @@ pub fn render_markdown(report: &RegressionReport) -> String {
 
     for circuit in &report.circuits {
         for (i, metric) in circuit.metrics.iter().enumerate() {
-            let circuit_col = if i == 0 { &circuit.circuit_name } else { "" };
+            let circuit_col = if i == 0 {
+                escape_markdown(&circuit.circuit_name)
+            } else {
+                String::new()
+            };
             let delta_str = if metric.delta_abs == 0.0 {
                 "0".to_string()
             } else {
                 format!("{:+.1}%", metric.delta_pct)
             };
             out.push_str(&format!(
-                "| {} | {} | {} | {} | {} | {:.1}% | {} |\n",
+                "| {} | {} | {} | {} | {} | {} | {} |\n",
                 circuit_col,
-                metric.metric,
+                escape_markdown(&metric.metric),
                 format_value(metric.baseline, &metric.metric),
                 format_value(metric.target, &metric.metric),
                 delta_str,
-                metric.threshold,
+                metric.threshold_rule.format_threshold(metric.threshold),
                 metric.status.emoji()
             ));
         }
     }
     out.push_str("\n</details>\n\n");
+    out.push_str(&render_methodology_markdown(report));
 
     // Legend
     out.push_str("---\n");
@@ -501,7 +847,8 @@ mod tests {
 
     #[test]
     fn test_compute_delta_status_regression() {
-        let (delta_abs, delta_pct, status) = compute_delta_status(100.0, 120.0, 10.0, true);
+        let (delta_abs, delta_pct, status) =
+            compute_delta_status(100.0, 120.0, 10.0, Direction::LowerIsBetter);
         assert_eq!(delta_abs, 20.0);
         assert!((delta_pct - 20.0).abs() < 0.01);
         assert_eq!(status, RegressionStatus::ExceededThreshold);
@@ -509,7 +856,8 @@ mod tests {
 
     #[test]
     fn test_compute_delta_status_improvement() {
-        let (delta_abs, delta_pct, status) = compute_delta_status(100.0, 80.0, 10.0, true);
+        let (delta_abs, delta_pct, status) =
+            compute_delta_status(100.0, 80.0, 10.0, Direction::LowerIsBetter);
         assert_eq!(delta_abs, -20.0);
         assert!((delta_pct - (-20.0)).abs() < 0.01);
         assert_eq!(status, RegressionStatus::Improved);
@@ -517,19 +865,59 @@ mod tests {
 
     #[test]
     fn test_compute_delta_status_ok() {
-        let (_, delta_pct, status) = compute_delta_status(100.0, 105.0, 10.0, true);
+        let (_, delta_pct, status) =
+            compute_delta_status(100.0, 105.0, 10.0, Direction::LowerIsBetter);
         assert!((delta_pct - 5.0).abs() < 0.01);
         assert_eq!(status, RegressionStatus::Ok);
     }
 
+    #[test]
+    fn test_metric_selection_resolves_aliases() {
+        let selection = MetricSelection::new(
+            &["prove_ms".into(), "gates".into(), " proof_size ".into()],
+            &["peak_rss".into()],
+        );
+        assert!(selection.includes("prove_ms"));
+        assert!(selection.includes("total_gates"));
+        assert!(selection.includes("proof_size_bytes"));
+        assert!(selection.includes("peak_rss_mb"));
+        assert!(!selection.includes("verify_ms"));
+        assert!(selection.is_informational("peak_rss_mb"));
+        assert!(!selection.is_informational("prove_ms"));
+        assert_eq!(
+            selection.unknown(&["prove_ms", "total_gates", "peak_rss_mb"]),
+            ["proof_size_bytes"]
+        );
+        assert!(MetricSelection::default().includes("verify_ms"));
+    }
+
+    #[test]
+    fn test_compute_delta_status_higher_is_better() {
+        let (_, _, status) = compute_delta_status(100.0, 80.0, 10.0, Direction::HigherIsBetter);
+        assert_eq!(status, RegressionStatus::ExceededThreshold);
+        let (_, _, status) = compute_delta_status(100.0, 120.0, 10.0, Direction::HigherIsBetter);
+        assert_eq!(status, RegressionStatus::Improved);
+        let (_, _, status) = compute_delta_status(100.0, 300.0, 10.0, Direction::Informational);
+        assert_eq!(status, RegressionStatus::Ok);
+    }
+
     #[test]
     fn test_compute_delta_status_zero_baseline() {
-        let (delta_abs, delta_pct, status) = compute_delta_status(0.0, 100.0, 10.0, true);
+        let (delta_abs, delta_pct, status) =
+            compute_delta_status(0.0, 100.0, 10.0, Direction::LowerIsBetter);
         assert_eq!(delta_abs, 100.0);
         assert_eq!(delta_pct, 0.0); // Avoid division by zero
         assert_eq!(status, RegressionStatus::Ok);
     }
 
+    #[test]
+    fn test_percent_change_is_always_finite() {
+        assert_eq!(percent_change(100.0, 150.0), 50.0);
+        assert_eq!(percent_change(0.0, 0.0), 0.0);
+        assert_eq!(percent_change(f64::MIN_POSITIVE / 4.0, 1e300), 0.0);
+        assert_eq!(percent_change(f64::NAN, 1.0), 0.0);
+    }
+
     #[test]
     fn test_regression_status_emoji() {
         assert_eq!(RegressionStatus::ExceededThreshold.emoji(), "🔴");
@@ -569,6 +957,7 @@ mod tests {
                 delta_abs: 20.0,
                 delta_pct: 20.0,
                 threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::ExceededThreshold,
             }],
             status: RegressionStatus::ExceededThreshold,
@@ -582,6 +971,25 @@ mod tests {
         assert_eq!(report.summary.ci_exit_code, 1);
     }
 
+    #[test]
+    fn test_regression_report_budget_resolves_aliases() {
+        let mut report = RegressionReport::new("a", "b", 10.0);
+        report.set_budgets(BTreeMap::from([(
+            "c".to_string(),
+            BTreeMap::from([
+                ("gates".to_string(), 1000.0),
+                ("prove_ms".to_string(), 5000.0),
+            ]),
+        )]));
+        assert_eq!(report.budget("c", "total_gates"), Some(1000.0));
+        assert_eq!(report.budget("c", "prove_ms"), Some(5000.0));
+        assert_eq!(report.budget("c", "verify_ms"), None);
+        assert_eq!(report.budget("other", "prove_ms"), None);
+
+        assert_eq!(headroom_pct(3000.0, 5000.0), 40.0);
+        assert_eq!(headroom_pct(6000.0, 5000.0), -20.0);
+    }
+
     #[test]
     fn test_regression_report_serialization() {
         let mut report = RegressionReport::new("base", "target", 10.0);
@@ -595,6 +1003,7 @@ mod tests {
                 delta_abs: 50.0,
                 delta_pct: 5.0,
                 threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::Ok,
             }],
             status: RegressionStatus::Ok,
@@ -615,6 +1024,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_regression_report_rounds_floats() {
+        let baseline = 0.1 + 0.2;
+        let target = 1.0 / 3.0;
+        let delta = MetricDelta {
+            metric: "prove_ms".to_string(),
+            baseline,
+            target,
+            delta_abs: target - baseline,
+            delta_pct: (target - baseline) / baseline * 100.0,
+            threshold: 10.0,
+            threshold_rule: ThresholdRule::Percent,
+            status: RegressionStatus::ExceededThreshold,
+        };
+        let json = serde_json::to_value(&delta).unwrap();
+        assert_eq!(json["baseline"], serde_json::json!(0.3));
+        assert_eq!(json["target"], serde_json::json!(0.333));
+        assert_eq!(json["delta_abs"], serde_json::json!(0.033));
+        assert_eq!(json["delta_pct"], serde_json::json!(11.111));
+    }
+
     #[test]
     fn test_format_value_time() {
         assert_eq!(format_value(100.0, "prove_ms"), "100ms");
@@ -668,6 +1098,7 @@ mod tests {
                 delta_abs: 50.0,
                 delta_pct: 50.0,
                 threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::ExceededThreshold,
             }],
             status: RegressionStatus::ExceededThreshold,
@@ -700,6 +1131,7 @@ mod tests {
                 delta_abs: 20.0,
                 delta_pct: 20.0,
                 threshold: 25.0,
+                threshold_rule: ThresholdRule::Percent,
                 status: RegressionStatus::Ok,
             }],
             status: RegressionStatus::Ok,
@@ -714,6 +1146,66 @@ mod tests {
         assert!(md.contains("| total_gates | 0.0% |"));
     }
 
+    #[test]
+    fn test_render_markdown_links_annotations() {
+        let mut report = RegressionReport::new("base", "target", 10.0);
+        report.set_annotations(BTreeMap::from([
+            ("commit".to_string(), "abc123".to_string()),
+            ("repo".to_string(), "https://github.com/o/r".to_string()),
+            ("branch".to_string(), "main".to_string()),
+        ]));
+
+        let md = render_markdown(&report);
+        assert!(md.contains("| **commit** | [`abc123`](https://github.com/o/r/commit/abc123) |"));
+        assert!(md.contains("| **branch** | `main` |"));
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_pipes() {
+        let mut report = RegressionReport::new("base|v1", "target", 10.0);
+        report.set_annotations(BTreeMap::from([(
+            "note".to_string(),
+            "a | b\nc".to_string(),
+        )]));
+
+        let md = render_markdown(&report);
+        assert!(md.contains("| **Baseline** | `base\\|v1` |"));
+        assert!(md.contains("| **note** | `a \\| b c` |"));
+    }
+
+    #[test]
+    fn test_render_markdown_shows_noise_threshold_rule() {
+        let mut report = RegressionReport::new("base", "target", 10.0);
+        report.add_circuit(CircuitRegression {
+            circuit_name: "fast".to_string(),
+            params: None,
+            metrics: vec![MetricDelta {
+                metric: "prove_ms".to_string(),
+                baseline: 10.0,
+                target: 14.0,
+                delta_abs: 4.0,
+                delta_pct: 40.0,
+                threshold: 30.0,
+                threshold_rule: ThresholdRule::Noise {
+                    k: 3.0,
+                    stddev_ms: 1.0,
+                },
+                status: RegressionStatus::ExceededThreshold,
+            }],
+            status: RegressionStatus::ExceededThreshold,
+        });
+        report.finalize();
+
+        let md = render_markdown(&report);
+        assert!(md.contains("| > 30.0% (3σ) |"), "{md}");
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json["circuits"][0]["metrics"][0]["threshold_rule"]["kind"],
+            "noise"
+        );
+    }
+
     #[test]
     fn test_render_markdown_shows_version_mismatches() {
         let mut report = RegressionReport::new("base", "target", 10.0);
@@ -731,4 +1223,79 @@ mod tests {
         assert!(md.contains("0.38.0"));
         assert!(md.contains("0.39.0"));
     }
+
+    #[test]
+    fn test_render_markdown_shows_environment_mismatches() {
+        let mut report = RegressionReport::new("base", "target", 10.0);
+        report.environment_mismatches.push(EnvironmentMismatch {
+            field: "cpu_governor".to_string(),
+            baseline: "performance".to_string(),
+            target: "powersave".to_string(),
+        });
+        report.environment_mismatches.push(EnvironmentMismatch {
+            field: "cpu_model".to_string(),
+            baseline: "EPYC | rev A".to_string(),
+            target: "EPYC".to_string(),
+        });
+        report.finalize();
+
+        let md = render_markdown(&report);
+
+        assert!(md.contains("Environment Changes"));
+        assert!(md.contains("| cpu_governor | performance | powersave |"));
+        assert!(md.contains("| cpu_model | EPYC \\| rev A | EPYC |"));
+    }
+
+    #[test]
+    fn test_subgroup_crossing_flagged_in_markdown() {
+        let delta = |metric: &str, baseline: f64, target: f64, status| {
+            let (delta_abs, delta_pct, _) =
+                compute_delta_status(baseline, target, 10.0, Direction::LowerIsBetter);
+            MetricDelta {
+                metric: metric.to_string(),
+                baseline,
+                target,
+                delta_abs,
+                delta_pct,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status,
+            }
+        };
+        let mut report = RegressionReport::new("base", "target", 10.0);
+        report.add_circuit(CircuitRegression {
+            circuit_name: "rollup".to_string(),
+            params: None,
+            metrics: vec![
+                delta("total_gates", 64_000.0, 65_920.0, RegressionStatus::Ok),
+                delta("subgroup_size", 65_536.0, 131_072.0, RegressionStatus::Ok),
+            ],
+            status: RegressionStatus::Ok,
+        });
+        report.add_circuit(CircuitRegression {
+            circuit_name: "steady".to_string(),
+            params: None,
+            metrics: vec![delta(
+                "subgroup_size",
+                65_536.0,
+                65_536.0,
+                RegressionStatus::Ok,
+            )],
+            status: RegressionStatus::Ok,
+        });
+        report.finalize();
+
+        assert_eq!(report.subgroup_crossings.len(), 1);
+        let crossing = &report.subgroup_crossings[0];
+        assert_eq!(crossing.prove_factor(), 2.0);
+        assert_eq!(
+            crossing.describe(),
+            "gates +3.0% but subgroup doubled (2^16 → 2^17): expect ~2x prove time"
+        );
+
+        let md = render_markdown(&report);
+        assert!(md.contains("Subgroup Size Changes"));
+        assert!(md.contains("`rollup`: gates +3.0% but subgroup doubled"));
+        assert!(md.contains("| 2^16 | 2^17 |"));
+    }
 }
@@ -0,0 +1,634 @@
+//! Pluggable output targets for command results.
+//!
+//! Every command that produces a result hands it to the reporters picked
+//! with the global `--reporter` flag, so a new output target is one more
+//! [`Reporter`] in the registry below rather than new plumbing in every
+//! command. `compare` and `ci` also write their `--html-out` through the
+//! `html` reporter. Outputs of other shapes keep their own renderers: `ci
+//! --format junit|tap` (which adds failed runs and alerts as cases) and
+//! `suite --html`. A reporter is named by a `NAME[=TARGET]` spec:
+//!
+//! - `csv`, `md`: one-row table of a run, or one row per compared metric
+//! - `html`, `junit`, `tap`: the regression report renderers
+//! - `prometheus`: gauges in the Prometheus text exposition format
+//! - `webhook`: the result as JSON, POSTed to `TARGET` or `$NOIR_BENCH_WEBHOOK_URL`
+//! - `slack`: a short summary, POSTed to `TARGET` or `$SLACK_WEBHOOK_URL`
+//!
+//! File reporters write to `TARGET`, or a default under `out/`. Webhook URLs
+//! usually embed their secret, so messages only show their host. Like
+//! [`DisplaySettings`](super::DisplaySettings), the reporters are set once
+//! per process from the flag; reporters that fail only warn.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::{Value as JsonValue, json};
+
+use super::junit::{render_junit, suites_from_report};
+use super::regression::{RegressionReport, RegressionStatus, render_markdown};
+use super::tap::render_tap;
+use super::{curl, format_value, render_html};
+use crate::{BenchError, BenchResult};
+
+/// A result handed to the reporters.
+#[derive(Debug, Clone, Copy)]
+pub enum ReportData<'a> {
+    /// JSON report of a single exec, gates, prove, verify or evm-verify run
+    Command(&'a JsonValue),
+    /// Result of a comparison (`compare`, `ci`)
+    Regression(&'a RegressionReport),
+}
+
+impl ReportData<'_> {
+    /// Short name of the result, as sent to webhooks.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ReportData::Command(v) => command_row(v).map_or("command", |(kind, _)| kind),
+            ReportData::Regression(_) => "regression",
+        }
+    }
+}
+
+/// An output target for command results.
+pub trait Reporter: Send + Sync {
+    /// Report `data`; `Ok(false)` when the reporter has nothing to say about
+    /// this kind of result.
+    fn report(&self, data: &ReportData) -> BenchResult<bool>;
+
+    /// Where the output goes, for messages.
+    fn describe(&self) -> String;
+}
+
+type Constructor = fn(Option<&str>) -> BenchResult<Box<dyn Reporter>>;
+
+/// Registered reporters, by name.
+const REPORTERS: &[(&str, Constructor)] = &[
+    ("csv", |t| file_reporter(t, "out/report.csv", render_csv)),
+    ("md", |t| file_reporter(t, "out/report.md", render_md)),
+    ("html", |t| {
+        file_reporter(t, "out/report.html", render_html_data)
+    }),
+    ("junit", |t| {
+        file_reporter(t, "out/junit.xml", render_junit_data)
+    }),
+    ("tap", |t| {
+        file_reporter(t, "out/report.tap", render_tap_data)
+    }),
+    ("prometheus", |t| {
+        file_reporter(t, "out/metrics.prom", render_prometheus)
+    }),
+    ("webhook", |t| {
+        post_reporter(t, "NOIR_BENCH_WEBHOOK_URL", webhook_body)
+    }),
+    ("slack", |t| {
+        post_reporter(t, "SLACK_WEBHOOK_URL", slack_body)
+    }),
+];
+
+/// Names of the registered reporters.
+pub fn reporter_names() -> Vec<&'static str> {
+    REPORTERS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Open the reporter named by `spec` (`NAME[=TARGET]`, see the module docs).
+pub fn open_reporter(spec: &str) -> BenchResult<Box<dyn Reporter>> {
+    let (name, target) = match spec.split_once('=') {
+        Some((name, target)) => (name.trim(), Some(target.trim())),
+        None => (spec.trim(), None),
+    };
+    let (_, open) = REPORTERS.iter().find(|(n, _)| *n == name).ok_or_else(|| {
+        BenchError::Message(format!(
+            "unknown reporter '{name}' (expected one of: {})",
+            reporter_names().join(", ")
+        ))
+    })?;
+    open(target.filter(|t| !t.is_empty()))
+}
+
+/// Report `data` with the registered reporter `name` writing to `path`, for
+/// commands' own output flags (`--html-out`).
+pub fn write_with(name: &str, path: &Path, data: &ReportData) -> BenchResult<()> {
+    let (_, open) = REPORTERS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .ok_or_else(|| BenchError::Message(format!("unknown reporter '{name}'")))?;
+    open(Some(&path.to_string_lossy()))?.report(data)?;
+    Ok(())
+}
+
+/// Open every reporter in `specs`.
+pub fn open_reporters(specs: &[String]) -> BenchResult<Vec<Box<dyn Reporter>>> {
+    specs.iter().map(|s| open_reporter(s)).collect()
+}
+
+/// Hand `data` to each reporter, warning about those that fail.
+pub fn run_reporters(reporters: &[Box<dyn Reporter>], data: &ReportData) {
+    for reporter in reporters {
+        match reporter.report(data) {
+            Ok(true) => eprintln!("Reported {} to {}", data.kind(), reporter.describe()),
+            Ok(false) => {}
+            Err(e) => eprintln!("Warning: reporter {}: {e}", reporter.describe()),
+        }
+    }
+}
+
+static REPORTERS_IN_USE: OnceLock<Vec<Box<dyn Reporter>>> = OnceLock::new();
+
+/// Use `reporters` for every result of this process. Only the first call
+/// has an effect.
+pub fn set_reporters(reporters: Vec<Box<dyn Reporter>>) {
+    let _ = REPORTERS_IN_USE.set(reporters);
+}
+
+/// Whether any reporter was set with [`set_reporters`].
+pub fn has_reporters() -> bool {
+    REPORTERS_IN_USE.get().is_some_and(|r| !r.is_empty())
+}
+
+/// Hand `data` to the reporters set with [`set_reporters`].
+pub fn publish(data: &ReportData) {
+    if let Some(reporters) = REPORTERS_IN_USE.get() {
+        run_reporters(reporters, data);
+    }
+}
+
+/// Publish the JSON report a command wrote to `json_path`.
+pub fn publish_json_file(json_path: &Path) {
+    if !has_reporters() {
+        return;
+    }
+    let Ok(bytes) = std::fs::read(json_path) else {
+        return;
+    };
+    let Ok(v) = serde_json::from_slice::<JsonValue>(&bytes) else {
+        return;
+    };
+    publish(&ReportData::Command(&v));
+}
+
+/// Kind and columns of a single-run JSON report; the columns are
+/// `(csv name, markdown name, value)`.
+fn command_row(
+    v: &JsonValue,
+) -> Option<(&'static str, Vec<(&'static str, &'static str, JsonValue)>)> {
+    let field = |key: &str| v.get(key).cloned().unwrap_or(JsonValue::Null);
+    if v.get("execution_time_ms").is_some() {
+        Some((
+            "exec",
+            vec![
+                ("time_ms", "time_ms", field("execution_time_ms")),
+                ("samples", "samples", field("samples_count")),
+            ],
+        ))
+    } else if v.get("total_gates").is_some() {
+        Some((
+            "gates",
+            vec![
+                ("total_gates", "total_gates", field("total_gates")),
+                ("acir_opcodes", "acir_opcodes", field("acir_opcodes")),
+            ],
+        ))
+    } else if v.get("prove_time_ms").is_some() {
+        Some((
+            "prove",
+            vec![
+                ("prove_time_ms", "prove_ms", field("prove_time_ms")),
+                ("witness_gen_ms", "witness_ms", field("witness_gen_time_ms")),
+                ("backend_ms", "backend_ms", field("backend_prove_time_ms")),
+                ("proof_size", "proof_size", field("proof_size_bytes")),
+                ("peak_mem", "peak_mem", field("peak_memory_bytes")),
+            ],
+        ))
+    } else if let Some(stats) = v.get("verify_stats") {
+        Some((
+            "verify",
+            vec![
+                ("verify_mean_ms", "verify_mean_ms", stats["mean_ms"].clone()),
+                ("iterations", "iterations", stats["iterations"].clone()),
+                ("ok", "ok", v["metadata"]["verify_ok"].clone()),
+            ],
+        ))
+    } else if v.get("gas_used").is_some() {
+        Some((
+            "evm-verify",
+            vec![
+                ("gas_used", "gas_used", field("gas_used")),
+                ("calldata_bytes", "calldata_bytes", field("calldata_bytes")),
+                ("est_latency_ms", "est_latency_ms", field("est_latency_ms")),
+            ],
+        ))
+    } else {
+        None
+    }
+}
+
+fn params_label(params: Option<u64>) -> String {
+    params.map(|p| p.to_string()).unwrap_or_default()
+}
+
+fn status_name(status: RegressionStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(data: &ReportData) -> Option<String> {
+    match data {
+        ReportData::Command(v) => {
+            let (kind, columns) = command_row(v)?;
+            let names: Vec<&str> = columns.iter().map(|(name, _, _)| *name).collect();
+            let values: Vec<String> = columns.iter().map(|(_, _, v)| v.to_string()).collect();
+            Some(format!(
+                "kind,{}\n{kind},{}\n",
+                names.join(","),
+                values.join(",")
+            ))
+        }
+        ReportData::Regression(report) => {
+            let mut out =
+                String::from("circuit,params,metric,baseline,target,delta_pct,threshold,status\n");
+            for c in &report.circuits {
+                for m in &c.metrics {
+                    out.push_str(&format!(
+                        "{},{},{},{},{},{:.2},{},{}\n",
+                        csv_field(&c.circuit_name),
+                        params_label(c.params),
+                        csv_field(&m.metric),
+                        m.baseline,
+                        m.target,
+                        m.delta_pct,
+                        m.threshold,
+                        status_name(m.status)
+                    ));
+                }
+            }
+            Some(out)
+        }
+    }
+}
+
+fn render_md(data: &ReportData) -> Option<String> {
+    match data {
+        ReportData::Command(v) => {
+            let (kind, columns) = command_row(v)?;
+            let mut out = String::from("| kind |");
+            let mut rule = String::from("|---|");
+            let mut row = format!("| {kind} |");
+            for (_, name, value) in &columns {
+                out.push_str(&format!(" {name} |"));
+                rule.push_str(if value.is_boolean() { ":--:|" } else { "---:|" });
+                row.push_str(&format!(" {value} |"));
+            }
+            Some(format!("{out}\n{rule}\n{row}\n"))
+        }
+        ReportData::Regression(report) => Some(render_markdown(report)),
+    }
+}
+
+fn render_html_data(data: &ReportData) -> Option<String> {
+    match data {
+        ReportData::Regression(report) => Some(render_html(report)),
+        ReportData::Command(_) => None,
+    }
+}
+
+fn render_junit_data(data: &ReportData) -> Option<String> {
+    match data {
+        ReportData::Regression(report) => Some(render_junit(&suites_from_report(report))),
+        ReportData::Command(_) => None,
+    }
+}
+
+fn render_tap_data(data: &ReportData) -> Option<String> {
+    match data {
+        ReportData::Regression(report) => Some(render_tap(&suites_from_report(report))),
+        ReportData::Command(_) => None,
+    }
+}
+
+/// Metric name as a Prometheus metric or label name.
+fn prometheus_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn prometheus_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn render_prometheus(data: &ReportData) -> Option<String> {
+    let mut out = String::new();
+    match data {
+        ReportData::Command(v) => {
+            let (kind, columns) = command_row(v)?;
+            for (name, _, value) in &columns {
+                let Some(value) = value.as_f64() else {
+                    continue;
+                };
+                let metric = format!("noir_bench_{}", prometheus_name(name));
+                out.push_str(&format!("# TYPE {metric} gauge\n"));
+                out.push_str(&format!("{metric}{{kind=\"{kind}\"}} {value}\n"));
+            }
+        }
+        ReportData::Regression(report) => {
+            let series: [(&str, &str, fn(&super::MetricDelta) -> f64); 3] = [
+                (
+                    "noir_bench_baseline",
+                    "Baseline value of a compared metric",
+                    |m| m.baseline,
+                ),
+                (
+                    "noir_bench_target",
+                    "Target value of a compared metric",
+                    |m| m.target,
+                ),
+                (
+                    "noir_bench_delta_pct",
+                    "Change from baseline to target, in percent",
+                    |m| m.delta_pct,
+                ),
+            ];
+            for (metric, help, value) in series {
+                out.push_str(&format!("# HELP {metric} {help}\n# TYPE {metric} gauge\n"));
+                for c in &report.circuits {
+                    for m in &c.metrics {
+                        out.push_str(&format!(
+                            "{metric}{{circuit=\"{}\",params=\"{}\",metric=\"{}\",status=\"{}\"}} {}\n",
+                            prometheus_label(&c.circuit_name),
+                            params_label(c.params),
+                            prometheus_label(&m.metric),
+                            status_name(m.status),
+                            value(m)
+                        ));
+                    }
+                }
+            }
+            out.push_str(&format!(
+                "# HELP noir_bench_regressions Metrics that exceeded their threshold\n# TYPE noir_bench_regressions gauge\nnoir_bench_regressions {}\n",
+                report.summary.regressions
+            ));
+        }
+    }
+    Some(out)
+}
+
+/// Writes a rendering of the results it can render to a file.
+pub struct FileReporter {
+    path: PathBuf,
+    render: fn(&ReportData) -> Option<String>,
+}
+
+fn file_reporter(
+    target: Option<&str>,
+    default: &str,
+    render: fn(&ReportData) -> Option<String>,
+) -> BenchResult<Box<dyn Reporter>> {
+    Ok(Box::new(FileReporter {
+        path: PathBuf::from(target.unwrap_or(default)),
+        render,
+    }))
+}
+
+impl Reporter for FileReporter {
+    fn report(&self, data: &ReportData) -> BenchResult<bool> {
+        let Some(text) = (self.render)(data) else {
+            return Ok(false);
+        };
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).ok();
+        }
+        std::fs::write(&self.path, text).map_err(|e| {
+            BenchError::Message(format!("failed to write {}: {e}", self.path.display()))
+        })?;
+        Ok(true)
+    }
+
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+fn webhook_body(data: &ReportData) -> Option<JsonValue> {
+    let report = match data {
+        ReportData::Command(v) => (*v).clone(),
+        ReportData::Regression(report) => serde_json::to_value(report).ok()?,
+    };
+    Some(json!({ "kind": data.kind(), "report": report }))
+}
+
+/// One-paragraph summary of a result, for chat messages.
+fn summary_text(data: &ReportData) -> Option<String> {
+    match data {
+        ReportData::Command(v) => {
+            let (kind, columns) = command_row(v)?;
+            let values: Vec<String> = columns
+                .iter()
+                .filter(|(_, _, v)| !v.is_null())
+                .map(|(name, _, v)| format!("{name}={v}"))
+                .collect();
+            Some(format!("noir-bench {kind}: {}", values.join(", ")))
+        }
+        ReportData::Regression(report) => {
+            let s = &report.summary;
+            let mut text = format!(
+                "noir-bench: {} regression(s), {} improvement(s) across {} circuit(s) ({} vs {})",
+                s.regressions,
+                s.improvements,
+                s.total_circuits,
+                report.metadata.baseline_id,
+                report.metadata.target_id
+            );
+            for c in &report.circuits {
+                for m in c.metrics.iter().filter(|m| m.status.is_failure()) {
+                    text.push_str(&format!(
+                        "\n• {}: {} {} → {} ({:+.1}%)",
+                        c.circuit_name,
+                        m.metric,
+                        format_value(m.baseline, &m.metric),
+                        format_value(m.target, &m.metric),
+                        m.delta_pct
+                    ));
+                }
+            }
+            Some(text)
+        }
+    }
+}
+
+fn slack_body(data: &ReportData) -> Option<JsonValue> {
+    Some(json!({ "text": summary_text(data)? }))
+}
+
+/// POSTs a JSON body built from each result to a URL with curl.
+pub struct PostReporter {
+    url: String,
+    body: fn(&ReportData) -> Option<JsonValue>,
+}
+
+fn post_reporter(
+    target: Option<&str>,
+    url_env: &str,
+    body: fn(&ReportData) -> Option<JsonValue>,
+) -> BenchResult<Box<dyn Reporter>> {
+    let url = match target {
+        Some(url) => url.to_string(),
+        None => std::env::var(url_env)
+            .ok()
+            .filter(|u| !u.is_empty())
+            .ok_or_else(|| {
+                BenchError::Message(format!("reporter needs a URL (NAME=URL or ${url_env})"))
+            })?,
+    };
+    Ok(Box::new(PostReporter { url, body }))
+}
+
+impl Reporter for PostReporter {
+    fn report(&self, data: &ReportData) -> BenchResult<bool> {
+        let Some(body) = (self.body)(data) else {
+            return Ok(false);
+        };
+        let body = serde_json::to_vec(&body)
+            .map_err(|e| BenchError::Message(format!("failed to serialize report: {e}")))?;
+        curl::post(
+            &self.url,
+            &["Content-Type: application/json"],
+            &body,
+            Some(30),
+        )?;
+        Ok(true)
+    }
+
+    fn describe(&self) -> String {
+        redact_url(&self.url)
+    }
+}
+
+/// `url` with only its scheme and host, e.g. `https://hooks.slack.com/…`.
+fn redact_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => match parsed.host_str() {
+            Some(host) => format!("{}://{host}/…", parsed.scheme()),
+            None => format!("{}:…", parsed.scheme()),
+        },
+        Err(_) => "webhook URL".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{CircuitRegression, MetricDelta, ThresholdRule};
+
+    fn report() -> RegressionReport {
+        let mut report = RegressionReport::new("main", "pr", 10.0);
+        report.add_circuit(CircuitRegression {
+            circuit_name: "merkle".to_string(),
+            params: Some(16),
+            metrics: vec![MetricDelta {
+                metric: "prove_ms".to_string(),
+                baseline: 100.0,
+                target: 125.0,
+                delta_abs: 25.0,
+                delta_pct: 25.0,
+                threshold: 10.0,
+                threshold_rule: ThresholdRule::Percent,
+                status: RegressionStatus::ExceededThreshold,
+            }],
+            status: RegressionStatus::ExceededThreshold,
+        });
+        report.finalize();
+        report
+    }
+
+    #[test]
+    fn test_open_reporter_specs() {
+        assert_eq!(
+            open_reporter("csv").unwrap().describe(),
+            "out/report.csv".to_string()
+        );
+        assert_eq!(
+            open_reporter("html=site/index.html").unwrap().describe(),
+            "site/index.html".to_string()
+        );
+        assert_eq!(
+            open_reporter("slack=https://hooks.example/services/T1/B2/s3cret")
+                .unwrap()
+                .describe(),
+            "https://hooks.example/…".to_string()
+        );
+        assert_eq!(redact_url("not a url"), "webhook URL");
+        let err = open_reporter("pager").err().unwrap();
+        assert!(err.to_string().contains("expected one of: csv, md"));
+    }
+
+    #[test]
+    fn test_command_tables() {
+        let v = json!({"total_gates": 1024, "acir_opcodes": 77});
+        let data = ReportData::Command(&v);
+        assert_eq!(data.kind(), "gates");
+        assert_eq!(
+            render_csv(&data).unwrap(),
+            "kind,total_gates,acir_opcodes\ngates,1024,77\n"
+        );
+
+        let v = json!({"verify_stats": {"mean_ms": 3.5, "iterations": 5}, "metadata": {"verify_ok": true}});
+        assert_eq!(
+            render_md(&ReportData::Command(&v)).unwrap(),
+            "| kind | verify_mean_ms | iterations | ok |\n|---|---:|---:|:--:|\n| verify | 3.5 | 5 | true |\n"
+        );
+        assert!(render_html_data(&ReportData::Command(&v)).is_none());
+        assert!(render_csv(&ReportData::Command(&json!({}))).is_none());
+    }
+
+    #[test]
+    fn test_regression_outputs() {
+        let report = report();
+        let data = ReportData::Regression(&report);
+        assert_eq!(
+            render_csv(&data).unwrap(),
+            "circuit,params,metric,baseline,target,delta_pct,threshold,status\nmerkle,16,prove_ms,100,125,25.00,10,exceeded_threshold\n"
+        );
+        let prom = render_prometheus(&data).unwrap();
+        assert!(prom.contains("# TYPE noir_bench_target gauge\n"));
+        assert!(prom.contains(
+            "noir_bench_target{circuit=\"merkle\",params=\"16\",metric=\"prove_ms\",status=\"exceeded_threshold\"} 125\n"
+        ));
+        assert!(prom.ends_with("noir_bench_regressions 1\n"));
+
+        let slack = slack_body(&data).unwrap();
+        let text = slack["text"].as_str().unwrap();
+        assert!(text.starts_with("noir-bench: 1 regression(s)"));
+        assert!(text.contains("merkle: prove_ms"));
+        assert_eq!(webhook_body(&data).unwrap()["kind"], "regression");
+    }
+
+    #[test]
+    fn test_file_reporter_writes_applicable_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/junit.xml");
+        let reporter = open_reporter(&format!("junit={}", path.display())).unwrap();
+        let v = json!({"execution_time_ms": 1.0, "samples_count": 3});
+        assert!(!reporter.report(&ReportData::Command(&v)).unwrap());
+        assert!(!path.exists());
+        let report = report();
+        assert!(reporter.report(&ReportData::Regression(&report)).unwrap());
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .contains("<testsuites")
+        );
+    }
+}
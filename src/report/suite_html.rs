@@ -0,0 +1,290 @@
+//! Standalone HTML report of a suite run.
+//!
+//! One self-contained page to attach to a CI run for humans: summary cards,
+//! then a section per circuit with the metrics and timing stats of each of
+//! its tasks. Failed tasks are highlighted with their error. The page uses
+//! the regression report's styles (`html::REPORT_CSS`) and needs no JS.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::display::format_timestamp;
+use super::html::{REPORT_CSS, html_escape};
+use super::metrics::{METRICS, format_value};
+
+/// Extra styles for circuit sections and errors.
+const SUITE_CSS: &str = r#"
+.circuit-section { margin-top: 24px; }
+.circuit-section h3 { display: flex; align-items: center; gap: 12px; }
+.circuit-section.failed .table-container { border-color: var(--red); }
+.error-box {
+  background: rgba(255, 107, 107, 0.1);
+  border: 1px solid var(--red);
+  border-radius: 8px;
+  padding: 12px 16px;
+  margin-bottom: 12px;
+  font-size: 0.875rem;
+}
+.error-box pre { white-space: pre-wrap; font-family: monospace; margin-top: 4px; }
+.table-container + .table-container { margin-top: 12px; }
+"#;
+
+/// One task of a suite run on one circuit.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteTask {
+    pub circuit: String,
+    pub task: String,
+    /// The task's JSON report, when it succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// First line of the error, when it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SuiteTask {
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+/// A suite run for the HTML report.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteReport {
+    /// Suite config the run came from
+    pub config: String,
+    pub generated_at: String,
+    pub tasks: Vec<SuiteTask>,
+}
+
+impl SuiteReport {
+    pub fn failures(&self) -> usize {
+        self.tasks.iter().filter(|t| t.failed()).count()
+    }
+}
+
+fn card(class: &str, value: usize, label: &str) -> String {
+    format!(
+        "<div class=\"card {class}\"><div class=\"card-value\">{value}</div><div class=\"card-label\">{label}</div></div>\n"
+    )
+}
+
+fn metric_rows(task: &SuiteTask, result: &Value) -> String {
+    let mut rows = String::new();
+    // Every registered metric the task's report carries
+    for info in METRICS {
+        if let Some(v) = info.report_value(result) {
+            rows.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td class=\"mono\">{}</td></tr>\n",
+                html_escape(&task.task),
+                info.name,
+                format_value(v, info.name)
+            ));
+        }
+    }
+    rows
+}
+
+fn timing_row(task: &SuiteTask, result: &Value) -> Option<String> {
+    let stats = result.get("iterations")?.as_object()?;
+    let ms = |key: &str| {
+        stats
+            .get(key)
+            .and_then(Value::as_f64)
+            .map(|v| format_value(v, "prove_ms"))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let count = |key: &str| stats.get(key).and_then(Value::as_u64).unwrap_or(0);
+    Some(format!(
+        "<tr><td>{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td></tr>\n",
+        html_escape(&task.task),
+        count("iterations"),
+        count("warmup"),
+        ms("avg_ms"),
+        ms("min_ms"),
+        ms("max_ms"),
+        ms("stddev_ms"),
+    ))
+}
+
+fn circuit_section(circuit: &str, tasks: &[&SuiteTask]) -> String {
+    let failed = tasks.iter().any(|t| t.failed());
+    let mut html = format!(
+        "<section class=\"circuit-section{}\">\n<h3>{} <span class=\"status-badge {}\">{}</span></h3>\n",
+        if failed { " failed" } else { "" },
+        html_escape(circuit),
+        if failed { "fail" } else { "pass" },
+        if failed { "FAILED" } else { "OK" },
+    );
+    for task in tasks.iter().filter(|t| t.failed()) {
+        html.push_str(&format!(
+            "<div class=\"error-box\"><strong>{}</strong> failed<pre>{}</pre></div>\n",
+            html_escape(&task.task),
+            html_escape(task.error.as_deref().unwrap_or_default()),
+        ));
+    }
+
+    let succeeded: Vec<(&SuiteTask, &Value)> = tasks
+        .iter()
+        .filter_map(|t| t.result.as_ref().map(|r| (*t, r)))
+        .collect();
+    let metrics: String = succeeded.iter().map(|(t, r)| metric_rows(t, r)).collect();
+    if !metrics.is_empty() {
+        html.push_str("<div class=\"table-container\"><table>\n<thead><tr><th>Task</th><th>Metric</th><th>Value</th></tr></thead>\n<tbody>\n");
+        html.push_str(&metrics);
+        html.push_str("</tbody>\n</table></div>\n");
+    }
+    let timings: String = succeeded
+        .iter()
+        .filter_map(|(t, r)| timing_row(t, r))
+        .collect();
+    if !timings.is_empty() {
+        html.push_str("<div class=\"table-container\"><table>\n<thead><tr><th>Task</th><th>Runs</th><th>Warmup</th><th>Mean</th><th>Min</th><th>Max</th><th>Stddev</th></tr></thead>\n<tbody>\n");
+        html.push_str(&timings);
+        html.push_str("</tbody>\n</table></div>\n");
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+/// Render a suite run as a standalone HTML page. Circuits are sorted by
+/// name, tasks keep the suite's order.
+pub fn render_suite_html(report: &SuiteReport) -> String {
+    let mut circuits: BTreeMap<&str, Vec<&SuiteTask>> = BTreeMap::new();
+    for task in &report.tasks {
+        circuits.entry(&task.circuit).or_default().push(task);
+    }
+    let failures = report.failures();
+    let failed_circuits = circuits
+        .values()
+        .filter(|tasks| tasks.iter().any(|t| t.failed()))
+        .count();
+
+    let mut html = String::with_capacity(16 * 1024);
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"UTF-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n<title>noir-bench Suite Report</title>\n<style>\n");
+    html.push_str(REPORT_CSS);
+    html.push_str(SUITE_CSS);
+    html.push_str("</style>\n</head>\n<body>\n<div class=\"container\">\n");
+
+    html.push_str(&format!(
+        "<div class=\"header\">\n<h1>noir-bench Suite Report</h1>\n<div class=\"header-status\"><span class=\"status-badge {}\">{}</span></div>\n<div class=\"meta-table\"><span class=\"meta-label\">Config</span><span class=\"meta-value\">{}</span><span class=\"meta-label\">Generated</span><span class=\"meta-value\">{}</span></div>\n</div>\n",
+        if failures > 0 { "fail" } else { "pass" },
+        if failures > 0 { "FAILED" } else { "PASSED" },
+        html_escape(&report.config),
+        html_escape(&format_timestamp(&report.generated_at)),
+    ));
+
+    html.push_str("<div class=\"summary-cards\">\n");
+    html.push_str(&card("", circuits.len(), "Circuits"));
+    html.push_str(&card("", report.tasks.len(), "Tasks"));
+    html.push_str(&card(
+        "improvements",
+        report.tasks.len() - failures,
+        "Passed",
+    ));
+    html.push_str(&card(
+        if failures > 0 { "regressions" } else { "" },
+        failures,
+        "Failed",
+    ));
+    html.push_str("</div>\n");
+
+    // Failed circuits first, so they're what a reader sees
+    if failed_circuits > 0 {
+        html.push_str(&format!(
+            "<div class=\"warnings-section\"><h3>{failed_circuits} circuit(s) with failed tasks</h3>\n"
+        ));
+        for (circuit, tasks) in &circuits {
+            for task in tasks.iter().filter(|t| t.failed()) {
+                html.push_str(&format!(
+                    "<div class=\"warning-item\">{} / {}: {}</div>\n",
+                    html_escape(circuit),
+                    html_escape(&task.task),
+                    html_escape(task.error.as_deref().unwrap_or_default()),
+                ));
+            }
+        }
+        html.push_str("</div>\n");
+    }
+
+    html.push_str("<h2>Circuits</h2>\n");
+    let (failed, passed): (Vec<_>, Vec<_>) = circuits
+        .iter()
+        .partition(|(_, tasks)| tasks.iter().any(|t| t.failed()));
+    for (circuit, tasks) in failed.into_iter().chain(passed) {
+        html.push_str(&circuit_section(circuit, tasks));
+    }
+
+    html.push_str(
+        "<div class=\"footer\">Generated by noir-bench</div>\n</div>\n</body>\n</html>\n",
+    );
+    html
+}
+
+/// Write a suite run as a standalone HTML file.
+pub fn write_suite_html(path: &Path, report: &SuiteReport) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, render_suite_html(report))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_suite_html_highlights_failures() {
+        let report = SuiteReport {
+            config: "suite.yaml".to_string(),
+            generated_at: "2026-01-01T00:00:00Z".to_string(),
+            tasks: vec![
+                SuiteTask {
+                    circuit: "merkle".to_string(),
+                    task: "prove".to_string(),
+                    result: Some(json!({
+                        "prove_time_ms": 1500,
+                        "proof_size_bytes": 16256,
+                        "total_gates": 4096,
+                        "gate_count": 4096,
+                        "iterations": {"iterations": 3, "warmup": 1, "times_ms": [1400, 1500, 1600],
+                            "avg_ms": 1500.0, "min_ms": 1400, "max_ms": 1600, "stddev_ms": 81.6}
+                    })),
+                    error: None,
+                },
+                SuiteTask {
+                    circuit: "ecdsa".to_string(),
+                    task: "gates".to_string(),
+                    result: None,
+                    error: Some("bb gates failed: <oom>".to_string()),
+                },
+            ],
+        };
+        let html = render_suite_html(&report);
+
+        assert!(html.contains("<title>noir-bench Suite Report</title>"));
+        assert!(html.contains("--surface: #16213e"));
+        assert!(html.contains("<span class=\"status-badge fail\">FAILED</span>"));
+        assert!(
+            html.contains(
+                "<div class=\"card-value\">1</div><div class=\"card-label\">Failed</div>"
+            )
+        );
+        // Errors are escaped and the failed circuit comes first
+        assert!(html.contains("bb gates failed: &lt;oom&gt;"));
+        assert!(html.find("<h3>ecdsa").unwrap() < html.find("<h3>merkle").unwrap());
+        assert!(html.contains("<td>prove_ms</td><td class=\"mono\">1.50s</td>"));
+        assert!(html.contains("<td>proof_size_bytes</td><td class=\"mono\">16.3 KB</td>"));
+        assert!(html.contains(
+            "<td class=\"mono\">3</td><td class=\"mono\">1</td><td class=\"mono\">1.50s</td><td class=\"mono\">1.40s</td>"
+        ));
+        // Gate count aliases show up once
+        assert_eq!(html.matches("<td>total_gates</td>").count(), 1);
+        assert!(!html.contains("<script"));
+    }
+}
@@ -0,0 +1,207 @@
+//! Tradeoff table for expression-width and bb option sweeps.
+//!
+//! One row per sweep point with its gate count, circuit size, proof and key
+//! sizes and prove time. The best value of each column is bold, and points
+//! no other point beats on gates, proof size and prove time at once are
+//! marked as Pareto-optimal: those are the settings worth choosing between.
+
+use serde::Serialize;
+
+use crate::core::BenchRecord;
+use crate::engine::sweep::{BACKEND_ARGS_METADATA_KEY, SWEEP_METADATA_KEY};
+
+use super::format_value;
+
+/// One sweep point's measurements.
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepRow {
+    pub point: String,
+    /// Expression width the circuit was compiled for ("unbounded" for 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression_width: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend_args: Option<String>,
+    pub gates: Option<u64>,
+    pub subgroup_size: Option<u64>,
+    pub proof_size_bytes: Option<u64>,
+    pub verification_key_size_bytes: Option<u64>,
+    pub prove_ms: Option<f64>,
+    /// No other point is at least as good on gates, proof size and prove
+    /// time and better on one of them
+    pub pareto: bool,
+}
+
+impl SweepRow {
+    fn objectives(&self) -> Option<[f64; 3]> {
+        Some([
+            self.gates? as f64,
+            self.proof_size_bytes? as f64,
+            self.prove_ms?,
+        ])
+    }
+}
+
+fn dominates(a: &[f64; 3], b: &[f64; 3]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y)
+}
+
+/// Rows of the tradeoff table, in record order, with Pareto flags set.
+pub fn sweep_rows(records: &[BenchRecord]) -> Vec<SweepRow> {
+    let mut rows: Vec<SweepRow> = records
+        .iter()
+        .map(|r| {
+            let flags = &r.config.compile_flags;
+            let expression_width = flags
+                .iter()
+                .position(|f| f == "--expression-width")
+                .and_then(|i| flags.get(i + 1))
+                .map(|w| match w.as_str() {
+                    "0" => "unbounded".to_string(),
+                    w => w.to_string(),
+                });
+            SweepRow {
+                point: r
+                    .metadata
+                    .get(SWEEP_METADATA_KEY)
+                    .cloned()
+                    .unwrap_or_else(|| r.circuit_name.clone()),
+                expression_width,
+                backend_args: r.metadata.get(BACKEND_ARGS_METADATA_KEY).cloned(),
+                gates: r.total_gates,
+                subgroup_size: r.subgroup_size,
+                proof_size_bytes: r.proof_size_bytes,
+                verification_key_size_bytes: r.verification_key_size_bytes,
+                prove_ms: r.prove_stats.as_ref().map(|s| s.mean_ms),
+                pareto: false,
+            }
+        })
+        .collect();
+
+    let objectives: Vec<Option<[f64; 3]>> = rows.iter().map(SweepRow::objectives).collect();
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.pareto = objectives[i].is_some_and(|own| {
+            !objectives
+                .iter()
+                .flatten()
+                .any(|other| dominates(other, &own))
+        });
+    }
+    rows
+}
+
+/// A column cell, bold when it holds the column's lowest value.
+fn cell(value: Option<f64>, best: Option<f64>, metric: &str) -> String {
+    match value {
+        Some(v) if Some(v) == best => format!("**{}**", format_value(v, metric)),
+        Some(v) => format_value(v, metric),
+        None => "-".to_string(),
+    }
+}
+
+/// Render the tradeoff table of a sweep of `circuit` as markdown.
+pub fn render_sweep(circuit: &str, rows: &[SweepRow]) -> String {
+    let best =
+        |value: fn(&SweepRow) -> Option<f64>| rows.iter().filter_map(value).min_by(f64::total_cmp);
+    let gates = |r: &SweepRow| r.gates.map(|v| v as f64);
+    let proof = |r: &SweepRow| r.proof_size_bytes.map(|v| v as f64);
+    let vk = |r: &SweepRow| r.verification_key_size_bytes.map(|v| v as f64);
+    let prove = |r: &SweepRow| r.prove_ms;
+    let (best_gates, best_proof, best_vk, best_prove) =
+        (best(gates), best(proof), best(vk), best(prove));
+
+    let mut out = format!("### Sweep: {circuit}\n\n");
+    out.push_str(
+        "| Setting | Expression Width | bb Options | Gates | Subgroup | Proof Size | VK Size | Prove | Pareto |\n\
+         |---------|------------------|------------|-------|----------|------------|---------|-------|--------|\n",
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            row.point,
+            row.expression_width.as_deref().unwrap_or("-"),
+            row.backend_args
+                .as_deref()
+                .map(|a| format!("`{a}`"))
+                .unwrap_or_else(|| "-".to_string()),
+            cell(gates(row), best_gates, "gates"),
+            row.subgroup_size
+                .map(|s| format!("2^{}", s.max(1).ilog2()))
+                .unwrap_or_else(|| "-".to_string()),
+            cell(proof(row), best_proof, "proof_size_bytes"),
+            cell(vk(row), best_vk, "verification_key_size_bytes"),
+            cell(prove(row), best_prove, "prove_ms"),
+            if row.pareto { "✓" } else { "" },
+        ));
+    }
+    out.push_str(
+        "\nBold is the best value of a column; ✓ marks settings no other setting beats on gates, proof size and prove time at once.\n",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BackendInfo, EnvironmentInfo, RunConfig, TimingStat};
+
+    fn record(point: &str, width: &str, gates: u64, proof: u64, prove_ms: f64) -> BenchRecord {
+        let mut r = BenchRecord::new(
+            "merkle".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "bb".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        r.metadata
+            .insert(SWEEP_METADATA_KEY.to_string(), point.to_string());
+        r.config.compile_flags = vec!["--expression-width".to_string(), width.to_string()];
+        r.total_gates = Some(gates);
+        r.subgroup_size = Some(1 << 14);
+        r.proof_size_bytes = Some(proof);
+        r.prove_stats = Some(TimingStat::from_samples(&[prove_ms]));
+        r
+    }
+
+    #[test]
+    fn test_sweep_rows_mark_pareto_front() {
+        let rows = sweep_rows(&[
+            record("w3", "3", 12_000, 14_000, 300.0),
+            // Beaten by w4 on everything
+            record("w5", "5", 11_000, 14_500, 320.0),
+            record("w4", "4", 10_000, 14_000, 280.0),
+            // Fastest, but the largest proof
+            record("wunbounded", "0", 10_500, 16_000, 250.0),
+        ]);
+        let pareto: Vec<(&str, bool)> = rows.iter().map(|r| (r.point.as_str(), r.pareto)).collect();
+        assert_eq!(
+            pareto,
+            [
+                ("w3", false),
+                ("w5", false),
+                ("w4", true),
+                ("wunbounded", true)
+            ]
+        );
+        assert_eq!(rows[3].expression_width.as_deref(), Some("unbounded"));
+    }
+
+    #[test]
+    fn test_render_sweep_bolds_best_values() {
+        let rows = sweep_rows(&[
+            record("w3", "3", 12_000, 14_000, 300.0),
+            record("w4", "4", 10_000, 14_000, 280.0),
+        ]);
+        let md = render_sweep("merkle", &rows);
+        assert!(md.starts_with("### Sweep: merkle\n\n| Setting |"));
+        let w4 = md.lines().find(|l| l.starts_with("| w4 ")).unwrap();
+        assert!(w4.contains(&format!("**{}**", format_value(10_000.0, "gates"))));
+        assert!(w4.contains("| 2^14 |"));
+        assert!(w4.ends_with("| ✓ |"));
+        let w3 = md.lines().find(|l| l.starts_with("| w3 ")).unwrap();
+        assert!(!w3.contains(&format!("**{}**", format_value(12_000.0, "gates"))));
+        assert!(w3.ends_with("|  |"));
+    }
+}
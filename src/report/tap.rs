@@ -0,0 +1,83 @@
+//! TAP (Test Anything Protocol) rendering.
+//!
+//! Uses the same suites as the JUnit output: every metric of every circuit
+//! is one numbered test point, `not ok` when it regressed or errored.
+
+use super::junit::{JunitOutcome, JunitSuite};
+
+/// Render suites as a TAP version 13 stream.
+pub fn render_tap(suites: &[JunitSuite]) -> String {
+    let total: usize = suites.iter().map(|s| s.cases.len()).sum();
+    let mut out = format!("TAP version 13\n1..{total}\n");
+    let mut n = 0;
+    for suite in suites {
+        for case in &suite.cases {
+            n += 1;
+            let desc = format!("{} {}", suite.name, case.name).replace('#', "\\#");
+            match &case.outcome {
+                JunitOutcome::Passed => out.push_str(&format!("ok {n} - {desc}\n")),
+                JunitOutcome::Skipped { message } => {
+                    out.push_str(&format!("ok {n} - {desc} # SKIP {message}\n"))
+                }
+                JunitOutcome::Failed { message, .. } | JunitOutcome::Error { message } => {
+                    out.push_str(&format!("not ok {n} - {desc}\n"));
+                    out.push_str("  ---\n");
+                    out.push_str(&format!("  message: {}\n", yaml_string(message)));
+                    if let Some(output) = &case.output {
+                        out.push_str(&format!("  output: {}\n", yaml_string(output)));
+                    }
+                    out.push_str("  ...\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Quote a value for the YAML diagnostic block.
+fn yaml_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::JunitCase;
+
+    #[test]
+    fn test_render_tap_numbers_points_and_marks_failures() {
+        let suites = vec![JunitSuite {
+            name: "hash".to_string(),
+            cases: vec![
+                JunitCase {
+                    name: "prove_ms".to_string(),
+                    outcome: JunitOutcome::Failed {
+                        message: "prove_ms regressed +50.0% (threshold 10.0%)".to_string(),
+                        details: String::new(),
+                    },
+                    output: None,
+                },
+                JunitCase {
+                    name: "total_gates".to_string(),
+                    outcome: JunitOutcome::Passed,
+                    output: None,
+                },
+                JunitCase {
+                    name: "verify_ms".to_string(),
+                    outcome: JunitOutcome::Skipped {
+                        message: "no baseline".to_string(),
+                    },
+                    output: None,
+                },
+            ],
+        }];
+
+        assert_eq!(
+            render_tap(&suites),
+            "TAP version 13\n1..3\n\
+             not ok 1 - hash prove_ms\n  ---\n  message: \"prove_ms regressed +50.0% (threshold 10.0%)\"\n  ...\n\
+             ok 2 - hash total_gates\n\
+             ok 3 - hash verify_ms # SKIP no baseline\n"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Units-aware value formatting.
+//!
+//! The only place metric values are turned into display strings. Markdown,
+//! text, JUnit, the static HTML tables and the history pages call
+//! `Unit::format` directly; the standalone HTML report embeds the same
+//! strings pre-formatted next to each metric instead of formatting in
+//! JavaScript, so every renderer shows the same value the same way. Counts are shown in full with a thousands
+//! separator when one is set (see `display`).
+
+use serde::Serialize;
+
+use super::display::DisplaySettings;
+
+/// How a metric's values are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unit {
+    /// Milliseconds, shown as `ms` or `s`
+    Millis,
+    /// Bytes, shown as `B`, `KB`, `MB` or `GB`
+    Bytes,
+    /// Already in megabytes
+    Megabytes,
+    /// Gates, opcodes, ..., shown with `K`/`M` suffixes
+    Count,
+    /// Sizes that are powers of two, shown as `2^n`
+    PowerOfTwo,
+    /// Rates, shown as `/s`
+    PerSecond,
+    Plain,
+}
+
+impl Unit {
+    /// Format `value` for display.
+    pub fn format(self, value: f64) -> String {
+        self.format_with(value, DisplaySettings::current())
+    }
+
+    /// Format `value` for display with `settings`.
+    pub fn format_with(self, value: f64, settings: &DisplaySettings) -> String {
+        match self {
+            Unit::Millis if value >= 1000.0 => format!("{:.2}s", value / 1000.0),
+            Unit::Millis => format!("{:.0}ms", value),
+            Unit::Bytes if value >= 1_000_000_000.0 => {
+                format!("{:.1} GB", value / 1_000_000_000.0)
+            }
+            Unit::Bytes if value >= 1_000_000.0 => format!("{:.1} MB", value / 1_000_000.0),
+            Unit::Bytes if value >= 1_000.0 => format!("{:.1} KB", value / 1_000.0),
+            Unit::Bytes => format!("{:.0} B", value),
+            Unit::Megabytes => format!("{:.1} MB", value),
+            Unit::Count if settings.thousands_separator.is_some() => {
+                settings.group_digits(value).unwrap_or_default()
+            }
+            Unit::Count if value >= 1_000_000.0 => format!("{:.2}M", value / 1_000_000.0),
+            Unit::Count if value >= 1_000.0 => format!("{:.1}K", value / 1_000.0),
+            Unit::Count => format!("{:.0}", value),
+            Unit::PowerOfTwo if value >= 1.0 => format!("2^{}", value.log2().round()),
+            Unit::PowerOfTwo => format!("{:.0}", value),
+            Unit::PerSecond => format!("{:.2}/s", value),
+            Unit::Plain => format!("{:.2}", value),
+        }
+    }
+
+    /// Best guess for a metric missing from the registry, from its name.
+    pub fn guess(metric: &str) -> Unit {
+        if metric.contains("subgroup") {
+            Unit::PowerOfTwo
+        } else if metric.contains("rss_mb") {
+            Unit::Megabytes
+        } else if metric.contains("size") || metric.contains("mem") || metric.contains("rss") {
+            Unit::Bytes
+        } else if metric.contains("per_sec") {
+            Unit::PerSecond
+        } else if metric.contains("ms") {
+            Unit::Millis
+        } else if metric.contains("gates") {
+            Unit::Count
+        } else {
+            Unit::Plain
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_format_scales() {
+        assert_eq!(Unit::Millis.format(120.4), "120ms");
+        assert_eq!(Unit::Millis.format(1500.0), "1.50s");
+        assert_eq!(Unit::Bytes.format(999.0), "999 B");
+        assert_eq!(Unit::Bytes.format(2_500_000_000.0), "2.5 GB");
+        assert_eq!(Unit::Count.format(1_500_000.0), "1.50M");
+        assert_eq!(Unit::PowerOfTwo.format(0.0), "0");
+        assert_eq!(Unit::Plain.format(1.0 / 3.0), "0.33");
+    }
+
+    #[test]
+    fn test_unit_format_groups_counts_with_separator() {
+        let settings = DisplaySettings::parse("utc", Some(",".to_string())).unwrap();
+        assert_eq!(
+            Unit::Count.format_with(18_327_461.0, &settings),
+            "18,327,461"
+        );
+        assert_eq!(Unit::Millis.format_with(1500.0, &settings), "1.50s");
+        assert_eq!(
+            Unit::Count.format_with(18_327_461.0, &DisplaySettings::default()),
+            "18.33M"
+        );
+    }
+
+    #[test]
+    fn test_unit_guess_from_name() {
+        assert_eq!(Unit::guess("subgroup_size"), Unit::PowerOfTwo);
+        assert_eq!(Unit::guess("peak_rss_mb"), Unit::Megabytes);
+        assert_eq!(Unit::guess("proof_size"), Unit::Bytes);
+        assert_eq!(Unit::guess("proofs_per_sec"), Unit::PerSecond);
+        assert_eq!(Unit::guess("prove_ms_p99"), Unit::Millis);
+        assert_eq!(Unit::guess("gates"), Unit::Count);
+        assert_eq!(Unit::guess("ratio"), Unit::Plain);
+    }
+}
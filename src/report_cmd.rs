@@ -0,0 +1,64 @@
+//! CLI command handler for `report`.
+//!
+//! Exports a report for audit and compliance snapshots: a regression report
+//! JSON (as written by `compare --json-out`/`ci`) is rendered to HTML first,
+//! and an HTML report (regression, suite) is printed to PDF as is.
+
+use std::path::{Path, PathBuf};
+
+use crate::report::RegressionReport;
+use crate::report::pdf::{html_file_to_pdf, html_to_pdf};
+use crate::{BenchError, BenchResult};
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+pub fn run(input: PathBuf, pdf: PathBuf, chromium: Option<PathBuf>) -> BenchResult<()> {
+    if is_json(&input) {
+        let bytes = std::fs::read(&input)
+            .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", input.display())))?;
+        let report: RegressionReport = serde_json::from_slice(&bytes).map_err(|e| {
+            BenchError::Message(format!(
+                "{} is not a regression report: {e}",
+                input.display()
+            ))
+        })?;
+        html_to_pdf(
+            &crate::report::render_html(&report),
+            &pdf,
+            chromium.as_deref(),
+        )?;
+    } else {
+        if !input.is_file() {
+            return Err(BenchError::Message(format!(
+                "report not found: {}",
+                input.display()
+            )));
+        }
+        html_file_to_pdf(&input, &pdf, chromium.as_deref())?;
+    }
+    eprintln!("Wrote PDF report to {}", pdf.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_rejects_bad_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("out.pdf");
+
+        let err = run(dir.path().join("missing.html"), pdf.clone(), None).unwrap_err();
+        assert!(err.to_string().contains("report not found"));
+
+        let json = dir.path().join("summary.json");
+        std::fs::write(&json, br#"{"results": []}"#).unwrap();
+        let err = run(json, pdf.clone(), None).unwrap_err();
+        assert!(err.to_string().contains("is not a regression report"));
+        assert!(!pdf.exists());
+    }
+}
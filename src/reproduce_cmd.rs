@@ -0,0 +1,418 @@
+//! CLI command handler for `reproduce`.
+//!
+//! Re-runs the benchmark behind a history record with the configuration the
+//! record stored: the same artifact and inputs (checked against its
+//! `fingerprints`), params, warmup and measured iterations, timeout, bb
+//! options and environment variables. Before anything runs, the installed
+//! nargo and bb must report the versions the record was measured with. The
+//! new run is printed side by side with the original.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::attest_cmd::find_record;
+use crate::backend::{Backend, BarretenbergBackend, BarretenbergConfig};
+use crate::core::schema::BenchRecord;
+use crate::engine::provenance::{self, VersionMismatch};
+use crate::engine::sweep::BACKEND_ARGS_METADATA_KEY;
+use crate::engine::toolchain::parse_nargo_version;
+use crate::engine::{NargoToolchain, ProveInputs, Toolchain, full_benchmark};
+use crate::report::alerts::metric_value;
+use crate::report::format_value;
+use crate::storage::JsonlWriter;
+use crate::{BenchError, BenchResult, file_sha256};
+
+/// Metadata key a reproduced record points to its original with.
+pub const REPRODUCES_METADATA_KEY: &str = "reproduces";
+
+/// Metrics compared between the original and the reproduced run.
+const COMPARED_METRICS: &[&str] = &[
+    "witness_ms",
+    "prove_ms",
+    "verify_ms",
+    "vk_gen_ms",
+    "gates",
+    "proof_size_bytes",
+    "vk_size",
+    "peak_rss_mb",
+];
+
+/// How to re-run a record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReproducePlan {
+    pub artifact: PathBuf,
+    pub prover_toml: Option<PathBuf>,
+    pub backend_args: Vec<String>,
+    /// Environment variables for the nargo and bb processes
+    pub env: BTreeMap<String, String>,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub timeout: Option<Duration>,
+}
+
+impl ReproducePlan {
+    /// Plan a re-run of `record`, from its `circuit_path` unless `artifact`
+    /// is given. Inputs are looked up next to the artifact like `ci` does.
+    pub fn for_record(
+        record: &BenchRecord,
+        artifact: Option<PathBuf>,
+        prover_toml: Option<PathBuf>,
+    ) -> BenchResult<Self> {
+        let artifact = artifact
+            .or_else(|| record.circuit_path.as_ref().map(PathBuf::from))
+            .ok_or_else(|| {
+                BenchError::Message(format!(
+                    "record '{}' has no circuit_path; pass --artifact",
+                    record.record_id
+                ))
+            })?;
+        let prover_toml =
+            prover_toml.or_else(|| crate::ci_cmd::find_prover_toml(&artifact, record.params));
+        Ok(ReproducePlan {
+            prover_toml,
+            backend_args: record
+                .metadata
+                .get(BACKEND_ARGS_METADATA_KEY)
+                .map(|args| args.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            env: record.config.env.clone(),
+            warmup: record.config.warmup_iterations as usize,
+            iterations: (record.config.measured_iterations as usize).max(1),
+            timeout: record.config.timeout_secs.map(Duration::from_secs),
+            artifact,
+        })
+    }
+
+    /// Ways the planned run differs from the recorded one: artifact or
+    /// inputs that don't hash to the record's fingerprints. Hashes the record
+    /// doesn't have aren't checked.
+    pub fn check_inputs(&self, record: &BenchRecord) -> Vec<String> {
+        let fingerprints = record.fingerprints.clone().unwrap_or_default();
+        let mut problems = Vec::new();
+        let mut check = |what: &str, path: Option<&Path>, expected: Option<String>| {
+            let Some(expected) = expected else { return };
+            match path.and_then(file_sha256) {
+                Some(actual) if actual == expected => {}
+                Some(actual) => problems.push(format!(
+                    "{what} {} has sha256 {actual}, the record was measured with {expected}",
+                    path.unwrap_or(Path::new("-")).display()
+                )),
+                None => problems.push(format!(
+                    "{what} with sha256 {expected} not found{}",
+                    path.map(|p| format!(" at {}", p.display()))
+                        .unwrap_or_default()
+                )),
+            }
+        };
+        check("artifact", Some(&self.artifact), fingerprints.acir_hash);
+        check(
+            "Prover.toml",
+            self.prover_toml.as_deref(),
+            fingerprints.inputs_hash,
+        );
+        problems
+    }
+}
+
+/// Tools whose installed version differs from the one `record` was
+/// measured with. Versions the record doesn't have aren't checked.
+pub fn check_versions(
+    record: &BenchRecord,
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+) -> Vec<VersionMismatch> {
+    let recorded_bb = record
+        .backend
+        .version
+        .clone()
+        .or_else(|| record.env.bb_version.clone());
+    [
+        // The environment keeps `nargo --version` as printed, the toolchain
+        // reports it parsed
+        (
+            "nargo",
+            record
+                .env
+                .nargo_version
+                .as_deref()
+                .and_then(parse_nargo_version),
+            toolchain
+                .version()
+                .ok()
+                .as_deref()
+                .and_then(parse_nargo_version),
+        ),
+        (backend.name(), recorded_bb, backend.version()),
+    ]
+    .into_iter()
+    .filter(|(_, recorded, _)| recorded.is_some())
+    .filter(|(_, recorded, installed)| recorded != installed)
+    .map(|(tool, recorded, installed)| VersionMismatch {
+        tool: tool.to_string(),
+        baseline_version: recorded,
+        target_version: installed,
+    })
+    .collect()
+}
+
+/// Re-run `record` as planned. The new record keeps the original's params
+/// and compile flags and points back to it in metadata.
+pub fn reproduce_record(
+    record: &BenchRecord,
+    plan: &ReproducePlan,
+    toolchain: &dyn Toolchain,
+    backend: &dyn Backend,
+) -> BenchResult<BenchRecord> {
+    let mut inputs = ProveInputs::new(&plan.artifact, &record.circuit_name);
+    if let Some(prover_toml) = &plan.prover_toml {
+        inputs = inputs.with_prover_toml(prover_toml);
+    }
+    if let Some(timeout) = plan.timeout {
+        inputs = inputs.with_timeout(timeout);
+    }
+    let result = full_benchmark(toolchain, backend, &inputs, plan.warmup, plan.iterations)?;
+
+    let mut reproduced = result.record;
+    reproduced.params = record.params;
+    reproduced.config.compile_flags = record.config.compile_flags.clone();
+    reproduced.config.env = plan.env.clone();
+    if !plan.backend_args.is_empty() {
+        reproduced.metadata.insert(
+            BACKEND_ARGS_METADATA_KEY.to_string(),
+            plan.backend_args.join(" "),
+        );
+    }
+    reproduced.metadata.insert(
+        REPRODUCES_METADATA_KEY.to_string(),
+        record.record_id.clone(),
+    );
+    Ok(reproduced)
+}
+
+/// Side-by-side table of `original` and `reproduced`, one row per metric
+/// either of them has.
+pub fn render_comparison(original: &BenchRecord, reproduced: &BenchRecord) -> String {
+    let mut out = format!(
+        "{:<18} {:>14} {:>14} {:>9}\n",
+        "metric", "original", "reproduced", "delta"
+    );
+    for metric in COMPARED_METRICS {
+        let (before, after) = (
+            metric_value(original, metric),
+            metric_value(reproduced, metric),
+        );
+        if before.is_none() && after.is_none() {
+            continue;
+        }
+        let show = |v: Option<f64>| {
+            v.map(|v| format_value(v, metric))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let delta = match (before, after) {
+            (Some(b), Some(a)) if b != 0.0 => format!("{:+.1}%", (a - b) / b * 100.0),
+            (Some(b), Some(a)) if a == b => "+0.0%".to_string(),
+            _ => "-".to_string(),
+        };
+        out.push_str(&format!(
+            "{metric:<18} {:>14} {:>14} {delta:>9}\n",
+            show(before),
+            show(after)
+        ));
+    }
+    let vk_hash = |r: &BenchRecord| r.fingerprints.as_ref().and_then(|f| f.vk_hash.clone());
+    if let (Some(before), Some(after)) = (vk_hash(original), vk_hash(reproduced)) {
+        let same = if before == after { "same" } else { "differs" };
+        out.push_str(&format!("{:<18} {same:>14}\n", "vk_hash"));
+    }
+    out
+}
+
+/// Run the `reproduce` command.
+pub fn run(
+    record_id: String,
+    jsonl: PathBuf,
+    artifact: Option<PathBuf>,
+    prover_toml: Option<PathBuf>,
+    backend_path: PathBuf,
+    force: bool,
+    out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let record = find_record(&jsonl, &record_id)?;
+    let plan = ReproducePlan::for_record(&record, artifact, prover_toml)?;
+
+    let toolchain = NargoToolchain::new().with_env(plan.env.clone());
+    let mut bb_config = BarretenbergConfig::new(backend_path)
+        .with_args(plan.backend_args.clone())
+        .with_env(plan.env.clone());
+    if let Some(timeout) = plan.timeout {
+        bb_config = bb_config.with_timeout(timeout);
+    }
+    let backend = BarretenbergBackend::new(bb_config);
+    backend.warn_on_args();
+
+    let mut problems = plan.check_inputs(&record);
+    problems.extend(
+        check_versions(&record, &toolchain, &backend)
+            .into_iter()
+            .map(|m| {
+                format!(
+                    "{} is {}, the record was measured with {}",
+                    m.tool,
+                    m.target_version.as_deref().unwrap_or("not installed"),
+                    m.baseline_version.as_deref().unwrap_or("unknown")
+                )
+            }),
+    );
+    if !problems.is_empty() {
+        if !force {
+            return Err(BenchError::Message(format!(
+                "cannot reproduce record '{record_id}' exactly (pass --force to run anyway):\n  {}",
+                problems.join("\n  ")
+            )));
+        }
+        for problem in &problems {
+            eprintln!("warning: {problem}");
+        }
+    }
+
+    eprintln!(
+        "reproduce: {} (params={:?}) warmup={} iterations={} artifact={}",
+        record.circuit_name,
+        record.params,
+        plan.warmup,
+        plan.iterations,
+        plan.artifact.display()
+    );
+    let reproduced = reproduce_record(&record, &plan, &toolchain, &backend)?;
+    for mismatch in provenance::check_record_environment_mismatches(&record.env, &reproduced.env) {
+        eprintln!(
+            "note: {} differs from the original run ({} vs {})",
+            mismatch.field, mismatch.baseline, mismatch.target
+        );
+    }
+
+    println!("{}", render_comparison(&record, &reproduced));
+    if let Some(out) = out {
+        JsonlWriter::new(&out).append(&reproduced)?;
+        eprintln!("appended reproduced record to {}", out.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fingerprints;
+    use crate::backend::{MockBackend, MockConfig, ProveOutput};
+    use crate::core::TimingStat;
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig};
+    use crate::engine::toolchain::MockToolchain;
+
+    fn recorded(dir: &Path) -> BenchRecord {
+        let artifact = dir.join("target/merkle.json");
+        std::fs::create_dir_all(artifact.parent().unwrap()).unwrap();
+        std::fs::write(&artifact, b"{\"bytecode\":\"\"}").unwrap();
+        std::fs::write(dir.join("Prover.toml"), b"x = 1\n").unwrap();
+
+        let mut record = BenchRecord::new(
+            "merkle".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "mock-bb".to_string(),
+                version: Some("mock-1.0.0".to_string()),
+                variant: None,
+            },
+            RunConfig {
+                warmup_iterations: 0,
+                measured_iterations: 2,
+                timeout_secs: Some(60),
+                compile_flags: vec!["--force-brillig".to_string()],
+                env: BTreeMap::from([("RAYON_NUM_THREADS".to_string(), "4".to_string())]),
+            },
+        );
+        record.circuit_path = Some(artifact.display().to_string());
+        record.params = Some(16);
+        record.env.nargo_version = Some("nargo 1.0.0".to_string());
+        record.prove_stats = Some(TimingStat::from_samples(&[200.0, 200.0]));
+        record.fingerprints = Some(Fingerprints {
+            acir_hash: file_sha256(&artifact),
+            inputs_hash: Some(crate::sha256_hex(b"x = 1\n")),
+            ..Default::default()
+        });
+        record.metadata.insert(
+            BACKEND_ARGS_METADATA_KEY.to_string(),
+            "--disable_zk".to_string(),
+        );
+        record
+    }
+
+    #[test]
+    fn test_plan_takes_config_from_record_and_checks_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = recorded(dir.path());
+
+        let plan = ReproducePlan::for_record(&record, None, None).unwrap();
+        assert_eq!(plan.prover_toml, Some(dir.path().join("Prover.toml")));
+        assert_eq!(plan.backend_args, ["--disable_zk"]);
+        assert_eq!((plan.warmup, plan.iterations), (0, 2));
+        assert_eq!(plan.timeout, Some(Duration::from_secs(60)));
+        assert!(plan.check_inputs(&record).is_empty());
+
+        std::fs::write(dir.path().join("Prover.toml"), b"x = 2\n").unwrap();
+        let problems = plan.check_inputs(&record);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("Prover.toml "));
+
+        let mut no_path = record.clone();
+        no_path.circuit_path = None;
+        assert!(ReproducePlan::for_record(&no_path, None, None).is_err());
+    }
+
+    #[test]
+    fn test_check_versions_reports_changed_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = recorded(dir.path());
+        let toolchain = MockToolchain::new();
+        let backend = MockBackend::new(MockConfig::new("mock-bb"));
+
+        let mismatches = check_versions(&record, &toolchain, &backend);
+        let tools: Vec<&str> = mismatches.iter().map(|m| m.tool.as_str()).collect();
+        assert_eq!(tools, ["nargo"]);
+        assert_eq!(mismatches[0].baseline_version.as_deref(), Some("1.0.0"));
+
+        // The full `nargo --version` output matches the same release
+        let mut record = record;
+        record.env.nargo_version =
+            Some("nargo version = 1.0.0-beta.3\nnoirc version = 1.0.0-beta.3+abc".to_string());
+        let toolchain = MockToolchain::new().with_version("1.0.0-beta.3");
+        assert!(check_versions(&record, &toolchain, &backend).is_empty());
+    }
+
+    #[test]
+    fn test_reproduce_record_links_to_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = recorded(dir.path());
+        let plan = ReproducePlan::for_record(&record, None, None).unwrap();
+        let backend = MockBackend::new(MockConfig::new("mock-bb").with_prove_output(ProveOutput {
+            prove_time_ms: 220,
+            ..ProveOutput::default()
+        }));
+
+        let reproduced = reproduce_record(&record, &plan, &MockToolchain::new(), &backend).unwrap();
+        assert_eq!(reproduced.params, Some(16));
+        assert_eq!(reproduced.config.measured_iterations, 2);
+        assert_eq!(reproduced.config.compile_flags, ["--force-brillig"]);
+        assert_eq!(reproduced.config.env, record.config.env);
+        assert_eq!(
+            reproduced.metadata[REPRODUCES_METADATA_KEY],
+            record.record_id
+        );
+
+        let table = render_comparison(&record, &reproduced);
+        let prove = table.lines().find(|l| l.starts_with("prove_ms")).unwrap();
+        assert!(prove.ends_with("+10.0%"));
+        assert!(!table.contains("vk_gen_ms"));
+    }
+}
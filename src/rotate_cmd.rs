@@ -0,0 +1,171 @@
+//! CLI command handler for `rotate`.
+//!
+//! Splits a JSONL archive into one file per month of record timestamps, so
+//! old months can be compressed and shipped separately. Lines are copied
+//! verbatim (no schema upgrade, signatures stay valid).
+
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::storage::Compression;
+use crate::storage::jsonl::{open_lines, write_lines};
+use crate::{BenchError, BenchResult};
+
+/// Bucket for records without a usable timestamp.
+const UNDATED: &str = "undated";
+
+/// `YYYY-MM` of a record's timestamp, if it has one.
+fn month_of(line: &str) -> Option<String> {
+    let v: Value = serde_json::from_str(line).ok()?;
+    let ts = v.get("timestamp")?.as_str()?;
+    let month = ts.get(..7)?;
+    let (year, mm) = month.split_once('-')?;
+    (year.len() == 4 && mm.len() == 2 && year.chars().chain(mm.chars()).all(|c| c.is_ascii_digit()))
+        .then(|| month.to_string())
+}
+
+/// `runs.jsonl.gz` -> `runs`.
+fn archive_stem(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("archive");
+    let name = name
+        .strip_suffix(Compression::from_path(path).extension())
+        .unwrap_or(name);
+    name.strip_suffix(".jsonl").unwrap_or(name).to_string()
+}
+
+/// Split `in_path` into `<out_dir>/<stem>-<YYYY-MM>.jsonl[.gz|.zst]`.
+///
+/// Returns the files written with their record counts. Refuses to overwrite
+/// an existing month file, so rotating the same archive twice doesn't
+/// duplicate records; the input is left untouched.
+pub fn rotate_file(
+    in_path: &Path,
+    out_dir: &Path,
+    compression: Compression,
+) -> BenchResult<Vec<(PathBuf, usize)>> {
+    let reader = open_lines(in_path)
+        .map_err(|e| BenchError::Message(format!("failed to open {}: {e}", in_path.display())))?;
+
+    let mut months: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (line_num, line_result) in reader.lines().enumerate() {
+        let line = line_result.map_err(|e| {
+            BenchError::Message(format!("failed to read line {}: {e}", line_num + 1))
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let month = month_of(&line).unwrap_or_else(|| UNDATED.to_string());
+        months.entry(month).or_default().push(line);
+    }
+
+    let stem = archive_stem(in_path);
+    let outputs: Vec<(PathBuf, Vec<String>)> = months
+        .into_iter()
+        .map(|(month, lines)| {
+            let name = format!("{stem}-{month}.jsonl{}", compression.extension());
+            (out_dir.join(name), lines)
+        })
+        .collect();
+    if let Some((existing, _)) = outputs.iter().find(|(path, _)| path.exists()) {
+        return Err(BenchError::Message(format!(
+            "{} already exists; rotate into an empty directory",
+            existing.display()
+        )));
+    }
+
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", out_dir.display())))?;
+    let mut written = Vec::with_capacity(outputs.len());
+    for (path, lines) in outputs {
+        write_lines(&path, &lines, true)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))?;
+        written.push((path, lines.len()));
+    }
+    Ok(written)
+}
+
+/// Parse a `--compress` value.
+pub fn parse_compression(s: &str) -> BenchResult<Compression> {
+    match s {
+        "gzip" | "gz" => Ok(Compression::Gzip),
+        "zstd" | "zst" => Ok(Compression::Zstd),
+        "none" => Ok(Compression::None),
+        other => Err(BenchError::Message(format!(
+            "unknown compression '{other}' (expected gzip, zstd or none)"
+        ))),
+    }
+}
+
+/// Run the `rotate` command.
+pub fn run(in_path: PathBuf, out_dir: PathBuf, compress: String) -> BenchResult<()> {
+    if !in_path.exists() {
+        return Err(BenchError::Message(format!(
+            "JSONL file not found: {}",
+            in_path.display()
+        )));
+    }
+
+    let compression = parse_compression(&compress)?;
+    let written = rotate_file(&in_path, &out_dir, compression)?;
+    for (path, count) in &written {
+        eprintln!("  {}: {count} record(s)", path.display());
+    }
+    eprintln!(
+        "Rotated {} record(s) into {} file(s)",
+        written.iter().map(|(_, n)| n).sum::<usize>(),
+        written.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::JsonlWriter;
+
+    const JAN: &str = r#"{"schema_version":1,"record_id":"r1","timestamp":"2026-01-05T00:00:00Z","circuit_name":"a","env":{"os":"linux"},"backend":{"name":"bb"},"config":{"warmup_iterations":1,"measured_iterations":3}}"#;
+    const FEB: &str = r#"{"schema_version":1,"record_id":"r2","timestamp":"2026-02-10T00:00:00Z","circuit_name":"a","env":{"os":"linux"},"backend":{"name":"bb"},"config":{"warmup_iterations":1,"measured_iterations":3}}"#;
+
+    #[test]
+    fn test_rotate_splits_by_month() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_path = dir.path().join("runs.jsonl");
+        std::fs::write(&in_path, format!("{JAN}\n{FEB}\n{JAN}\nnot json\n")).unwrap();
+        let out_dir = dir.path().join("archive");
+
+        let written = rotate_file(&in_path, &out_dir, Compression::Gzip).unwrap();
+        let names: Vec<_> = written
+            .iter()
+            .map(|(p, n)| (p.file_name().unwrap().to_str().unwrap().to_string(), *n))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("runs-2026-01.jsonl.gz".to_string(), 2),
+                ("runs-2026-02.jsonl.gz".to_string(), 1),
+                ("runs-undated.jsonl.gz".to_string(), 1),
+            ]
+        );
+
+        let jan = JsonlWriter::new(out_dir.join("runs-2026-01.jsonl.gz"))
+            .read_all()
+            .unwrap();
+        assert_eq!(jan.len(), 2);
+
+        // A second rotation would duplicate records
+        assert!(rotate_file(&in_path, &out_dir, Compression::Gzip).is_err());
+    }
+
+    #[test]
+    fn test_archive_stem_strips_extensions() {
+        assert_eq!(archive_stem(Path::new("a/runs.jsonl.zst")), "runs");
+        assert_eq!(archive_stem(Path::new("runs.jsonl")), "runs");
+        assert_eq!(archive_stem(Path::new("runs")), "runs");
+    }
+}
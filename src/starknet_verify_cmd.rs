@@ -0,0 +1,214 @@
+//! CLI command handler for `starknet-verify`, the Starknet counterpart of
+//! `evm-verify`.
+//!
+//! Runs the Cairo verifier's tests with Starknet Foundry
+//! (`snforge test --detailed-resources`) in a Scarb project, or any command
+//! given as a template, e.g. a script that declares the verifier on a local
+//! devnet and estimates the fee of a verify call. Resources are read from the
+//! command's output as `key: <n>` (a `~` before the number is allowed):
+//! `steps`, `l1_gas`, `l1_data_gas`, `l2_gas` and `overall_fee` (or `fee`).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::{
+    BackendInfo, BenchError, BenchResult, CommonMeta, StarknetVerifyReport, SystemInfo,
+    collect_system_info, verifier_template,
+};
+
+/// Resources reported by one verifier run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StarknetResources {
+    pub steps: Option<u64>,
+    pub l1_gas: Option<u64>,
+    pub l1_data_gas: Option<u64>,
+    pub l2_gas: Option<u64>,
+    pub estimated_fee: Option<u128>,
+}
+
+impl StarknetResources {
+    fn is_empty(&self) -> bool {
+        *self == StarknetResources::default()
+    }
+}
+
+/// First `key: <n>` in `output`, where `key` is a whole word.
+fn read_value(output: &str, key: &str) -> Option<u128> {
+    let needle = format!("{key}:");
+    let mut from = 0;
+    while let Some(idx) = output[from..].find(&needle) {
+        let start = from + idx;
+        from = start + needle.len();
+        let preceded_by_word = output[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if preceded_by_word {
+            continue;
+        }
+        let num: String = output[from..]
+            .trim_start()
+            .trim_start_matches('~')
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '_')
+            .filter(|c| *c != '_')
+            .collect();
+        if let Ok(v) = num.parse() {
+            return Some(v);
+        }
+    }
+    None
+}
+
+/// Parse the resources of a verifier run from its output.
+pub fn parse_resources(output: &str) -> StarknetResources {
+    let u64_value = |key| read_value(output, key).and_then(|v| u64::try_from(v).ok());
+    StarknetResources {
+        steps: u64_value("steps"),
+        l1_gas: u64_value("l1_gas"),
+        l1_data_gas: u64_value("l1_data_gas"),
+        l2_gas: u64_value("l2_gas"),
+        estimated_fee: read_value(output, "overall_fee").or_else(|| read_value(output, "fee")),
+    }
+}
+
+/// The command to run: the template with `{project}` and `{match}` filled
+/// in, or `snforge test --detailed-resources [match]`.
+fn build_command(
+    template: Option<&str>,
+    project_dir: &Path,
+    test_pattern: &Option<String>,
+) -> BenchResult<Command> {
+    let mut default = vec![
+        "snforge".to_string(),
+        "test".to_string(),
+        "--detailed-resources".to_string(),
+    ];
+    default.extend(test_pattern.clone());
+    verifier_template::build_command(template, project_dir, test_pattern.as_deref(), default)
+}
+
+fn backend_info(template: Option<&str>) -> BackendInfo {
+    let Some(template) = template else {
+        let version = Command::new("snforge")
+            .arg("--version")
+            .output()
+            .ok()
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        return BackendInfo {
+            name: "snforge".into(),
+            version,
+        };
+    };
+    BackendInfo {
+        name: verifier_template::template_program(template),
+        version: None,
+    }
+}
+
+pub fn run(
+    project_dir: PathBuf,
+    test_pattern: Option<String>,
+    command_template: Option<String>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let mut cmd = build_command(command_template.as_deref(), &project_dir, &test_pattern)?;
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let output = cmd
+        .output()
+        .map_err(|e| BenchError::Message(format!("failed to run starknet verifier: {e}")))?;
+    let stdout_s = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        return Err(BenchError::Message(format!(
+            "starknet verifier failed. stderr=\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let resources = parse_resources(&stdout_s);
+    if resources.is_empty() {
+        return Err(BenchError::Message(
+            "no steps, gas or fee found in the verifier output".into(),
+        ));
+    }
+
+    let meta = CommonMeta {
+        name: "starknet-verify".into(),
+        timestamp: time::OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default(),
+        noir_version: "n/a".into(),
+        artifact_path: project_dir.clone(),
+        cli_args: std::env::args().collect(),
+        artifact_sha256: None,
+        inputs_sha256: None,
+    };
+    let system: Option<SystemInfo> = Some(collect_system_info());
+    let report = StarknetVerifyReport {
+        meta,
+        steps: resources.steps,
+        l1_gas: resources.l1_gas,
+        l1_data_gas: resources.l1_data_gas,
+        l2_gas: resources.l2_gas,
+        estimated_fee: resources.estimated_fee,
+        backend: backend_info(command_template.as_deref()),
+        system,
+    };
+
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        std::fs::write(&json, serde_json::to_vec_pretty(&report).unwrap()).ok();
+    }
+    println!(
+        "starknet-verify: steps={:?} l1_gas={:?} l1_data_gas={:?} l2_gas={:?} fee={:?}",
+        report.steps, report.l1_gas, report.l1_data_gas, report.l2_gas, report.estimated_fee
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snforge_detailed_resources() {
+        let out = "\
+[PASS] verifier_tests::test_verify (l1_gas: ~0, l1_data_gas: ~96, l2_gas: ~14_560_000)
+        steps: 382731
+        memory holes: 5203
+        builtins: (range_check: 25112, bitwise: 120)
+";
+        let r = parse_resources(out);
+        assert_eq!(r.steps, Some(382_731));
+        assert_eq!(r.l1_gas, Some(0));
+        assert_eq!(r.l1_data_gas, Some(96));
+        assert_eq!(r.l2_gas, Some(14_560_000));
+        assert_eq!(r.estimated_fee, None);
+    }
+
+    #[test]
+    fn test_parse_fee_from_estimate() {
+        let r = parse_resources("overall_fee: 1234567890123\nunit: FRI\n");
+        assert_eq!(r.estimated_fee, Some(1_234_567_890_123));
+        assert!(parse_resources("nothing here").is_empty());
+        // `fee` alone, not as the tail of another key
+        assert_eq!(parse_resources("max_fee: 5\nfee: 7").estimated_fee, Some(7));
+    }
+
+    #[test]
+    fn test_default_command_is_snforge() {
+        let cmd = build_command(None, Path::new("src"), &Some("test_verify".to_string())).unwrap();
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(cmd.get_program(), "snforge");
+        assert_eq!(args, ["test", "--detailed-resources", "test_verify"]);
+    }
+}
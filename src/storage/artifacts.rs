@@ -0,0 +1,162 @@
+//! Content-addressed store for proofs and verification keys.
+//!
+//! Files are kept under their sha256 so a record's `fingerprints` are enough
+//! to find them again later (see `attest`):
+//!
+//! ```text
+//! <root>/proofs/<proof_sha256>/proof
+//! <root>/proofs/<proof_sha256>/public_inputs   (if the backend wrote one)
+//! <root>/vks/<vk_sha256>
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::{BenchError, BenchResult, file_sha256};
+
+/// Default store location, relative to the working directory.
+pub const DEFAULT_ARTIFACT_STORE: &str = ".noir-bench/artifacts";
+
+/// A directory of proofs and vks keyed by content hash.
+#[derive(Debug, Clone)]
+pub struct ArtifactStore {
+    root: PathBuf,
+}
+
+impl ArtifactStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        ArtifactStore {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Get the store root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path a proof with this hash is stored at.
+    pub fn proof_path(&self, hash: &str) -> PathBuf {
+        self.root.join("proofs").join(hash).join("proof")
+    }
+
+    /// Path a verification key with this hash is stored at.
+    pub fn vk_path(&self, hash: &str) -> PathBuf {
+        self.root.join("vks").join(hash)
+    }
+
+    /// Copy a proof into the store and return its hash.
+    ///
+    /// A `public_inputs` file next to the proof is copied along with it, since
+    /// backends look for it there when verifying.
+    pub fn put_proof(&self, proof: &Path) -> BenchResult<String> {
+        let hash = hash_file(proof)?;
+        let dest = self.proof_path(&hash);
+        copy_into(proof, &dest)?;
+        if let Some(dir) = proof.parent() {
+            let public_inputs = dir.join("public_inputs");
+            if public_inputs.exists() {
+                copy_into(&public_inputs, &dest.with_file_name("public_inputs"))?;
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Copy a verification key into the store and return its hash.
+    pub fn put_vk(&self, vk: &Path) -> BenchResult<String> {
+        let hash = hash_file(vk)?;
+        copy_into(vk, &self.vk_path(&hash))?;
+        Ok(hash)
+    }
+
+    /// Look up a stored proof, checking its contents still match `hash`.
+    pub fn get_proof(&self, hash: &str) -> BenchResult<PathBuf> {
+        checked(self.proof_path(hash), hash, "proof")
+    }
+
+    /// Look up a stored verification key, checking its contents still match `hash`.
+    pub fn get_vk(&self, hash: &str) -> BenchResult<PathBuf> {
+        checked(self.vk_path(hash), hash, "vk")
+    }
+}
+
+fn hash_file(path: &Path) -> BenchResult<String> {
+    file_sha256(path)
+        .ok_or_else(|| BenchError::Message(format!("failed to read {}", path.display())))
+}
+
+fn copy_into(src: &Path, dest: &Path) -> BenchResult<()> {
+    if dest.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", dir.display())))?;
+    }
+    std::fs::copy(src, dest).map_err(|e| {
+        BenchError::Message(format!(
+            "failed to copy {} to {}: {e}",
+            src.display(),
+            dest.display()
+        ))
+    })?;
+    Ok(())
+}
+
+fn checked(path: PathBuf, hash: &str, what: &str) -> BenchResult<PathBuf> {
+    let Some(actual) = file_sha256(&path) else {
+        return Err(BenchError::Message(format!(
+            "{what} {hash} not found in artifact store ({})",
+            path.display()
+        )));
+    };
+    if actual != hash {
+        return Err(BenchError::Message(format!(
+            "stored {what} {} is corrupt: expected sha256 {hash}, got {actual}",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_get_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("out");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("proof"), b"proof-bytes").unwrap();
+        std::fs::write(src.join("public_inputs"), b"pi").unwrap();
+        std::fs::write(src.join("vk"), b"vk-bytes").unwrap();
+
+        let store = ArtifactStore::new(dir.path().join("store"));
+        let proof_hash = store.put_proof(&src.join("proof")).unwrap();
+        let vk_hash = store.put_vk(&src.join("vk")).unwrap();
+        assert_eq!(proof_hash, crate::sha256_hex(b"proof-bytes"));
+
+        let proof = store.get_proof(&proof_hash).unwrap();
+        assert_eq!(std::fs::read(&proof).unwrap(), b"proof-bytes");
+        assert!(proof.with_file_name("public_inputs").exists());
+        assert_eq!(
+            std::fs::read(store.get_vk(&vk_hash).unwrap()).unwrap(),
+            b"vk-bytes"
+        );
+    }
+
+    #[test]
+    fn test_get_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let vk = dir.path().join("vk");
+        std::fs::write(&vk, b"vk-bytes").unwrap();
+
+        let store = ArtifactStore::new(dir.path().join("store"));
+        let hash = store.put_vk(&vk).unwrap();
+        std::fs::write(store.vk_path(&hash), b"tampered").unwrap();
+
+        let err = store.get_vk(&hash).unwrap_err().to_string();
+        assert!(err.contains("corrupt"), "{err}");
+        assert!(store.get_proof("missing").is_err());
+    }
+}
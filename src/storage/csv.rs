@@ -1,12 +1,15 @@
 //! CSV export for benchmark records.
 
+use std::collections::BTreeSet;
 use std::io::Write;
 use std::path::Path;
 
 use crate::BenchError;
+use crate::backend::CUSTOM_METRIC_PREFIX;
 use crate::core::schema::BenchRecord;
 
-/// CSV column headers in deterministic order.
+/// CSV column headers in deterministic order. Custom metrics of the exported
+/// records follow as `custom.NAME` columns, sorted by name.
 pub const CSV_HEADERS: &[&str] = &[
     "schema_version",
     "record_id",
@@ -97,15 +100,31 @@ impl CsvExporter {
         writer: W,
     ) -> Result<(), BenchError> {
         let mut csv_writer = csv::Writer::from_writer(writer);
+        let custom: BTreeSet<&String> = records
+            .iter()
+            .flat_map(|r| r.custom_metrics.keys())
+            .collect();
 
         // Write headers
+        let headers = CSV_HEADERS.iter().map(|h| h.to_string()).chain(
+            custom
+                .iter()
+                .map(|name| format!("{CUSTOM_METRIC_PREFIX}{name}")),
+        );
         csv_writer
-            .write_record(CSV_HEADERS)
+            .write_record(headers)
             .map_err(|e| BenchError::Message(format!("failed to write CSV headers: {e}")))?;
 
         // Write each record
         for record in records {
-            let row = self.record_to_row(record);
+            let mut row = self.record_to_row(record);
+            row.extend(custom.iter().map(|name| {
+                record
+                    .custom_metrics
+                    .get(*name)
+                    .map(|v| v.to_string())
+                    .unwrap_or_default()
+            }));
             csv_writer
                 .write_record(&row)
                 .map_err(|e| BenchError::Message(format!("failed to write CSV row: {e}")))?;
@@ -230,6 +249,8 @@ impl CsvExporter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
+
     use crate::core::env::EnvironmentInfo;
     use crate::core::schema::{BackendInfo, RunConfig, TimingStat};
 
@@ -246,6 +267,8 @@ mod tests {
                 warmup_iterations: 2,
                 measured_iterations: 5,
                 timeout_secs: None,
+                compile_flags: Vec::new(),
+                env: BTreeMap::new(),
             },
         )
     }
@@ -291,6 +314,25 @@ mod tests {
         assert!(lines[1].contains("2048")); // proof_size_bytes
     }
 
+    #[test]
+    fn test_export_custom_metric_columns() {
+        let exporter = CsvExporter::new();
+        let mut a = make_test_record("circuit_a");
+        a.custom_metrics.insert("rounds".to_string(), 18.0);
+        let mut b = make_test_record("circuit_b");
+        b.custom_metrics
+            .insert("folded_constraints".to_string(), 1200.0);
+
+        let mut buffer = Vec::new();
+        exporter.export_to_writer(&[a, b], &mut buffer).unwrap();
+
+        let csv_str = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = csv_str.lines().collect();
+        assert!(lines[0].ends_with(",peak_rss_mb,custom.folded_constraints,custom.rounds"));
+        assert!(lines[1].ends_with(",,18"));
+        assert!(lines[2].ends_with(",1200,"));
+    }
+
     #[test]
     fn test_export_multiple_records() {
         let exporter = CsvExporter::new();
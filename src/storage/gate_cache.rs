@@ -0,0 +1,110 @@
+//! Cache of gate analysis results keyed by artifact content.
+//!
+//! Gate counts only depend on the artifact and the backend that analysed it,
+//! so runs that prove the same artifact again (suites, CI, the daemon) can
+//! reuse them instead of rerunning `bb gates`:
+//!
+//! ```text
+//! <root>/<sha256(artifact sha256, backend id)>.json
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::{BenchError, BenchResult, file_sha256, sha256_hex};
+
+/// Default cache location, relative to the working directory.
+pub const DEFAULT_GATE_CACHE: &str = ".noir-bench/gates";
+
+/// A directory of gate analysis results keyed by artifact hash and backend.
+#[derive(Debug, Clone)]
+pub struct GateCache {
+    root: PathBuf,
+}
+
+impl GateCache {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        GateCache {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Get the cache root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Cache key for `artifact` analysed by `backend_id`.
+    ///
+    /// `backend_id` must identify everything that changes the result, e.g.
+    /// the backend name, version and extra args.
+    pub fn key(artifact: &Path, backend_id: &str) -> BenchResult<String> {
+        let artifact_hash = file_sha256(artifact)
+            .ok_or_else(|| BenchError::Message(format!("failed to read {}", artifact.display())))?;
+        Ok(sha256_hex(
+            format!("{artifact_hash}\n{backend_id}").as_bytes(),
+        ))
+    }
+
+    /// Path the result for `key` is stored at.
+    pub fn path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{key}.json"))
+    }
+
+    /// Look up a cached result. Missing or unreadable entries are a miss.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = std::fs::read(self.path(key)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Store a result under `key`.
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> BenchResult<()> {
+        std::fs::create_dir_all(&self.root).map_err(|e| {
+            BenchError::Message(format!("failed to create {}: {e}", self.root.display()))
+        })?;
+        let json = serde_json::to_vec_pretty(value)
+            .map_err(|e| BenchError::Message(format!("failed to serialize gate info: {e}")))?;
+        let path = self.path(key);
+        std::fs::write(&path, json)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::GateInfo;
+
+    #[test]
+    fn test_put_and_get_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("program.json");
+        std::fs::write(&artifact, b"{}").unwrap();
+
+        let cache = GateCache::new(dir.path().join("gates"));
+        let key = GateCache::key(&artifact, "barretenberg@1.0.0").unwrap();
+        assert!(cache.get::<GateInfo>(&key).is_none());
+
+        cache.put(&key, &GateInfo::from_gates(5000)).unwrap();
+        let cached: GateInfo = cache.get(&key).unwrap();
+        assert_eq!(cached.backend_gates, 5000);
+        assert_eq!(cached.subgroup_size, Some(8192));
+    }
+
+    #[test]
+    fn test_key_depends_on_artifact_and_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.json");
+        let b = dir.path().join("b.json");
+        std::fs::write(&a, b"{\"v\":1}").unwrap();
+        std::fs::write(&b, b"{\"v\":2}").unwrap();
+
+        let key = GateCache::key(&a, "bb@1").unwrap();
+        assert_eq!(key, GateCache::key(&a, "bb@1").unwrap());
+        assert_ne!(key, GateCache::key(&a, "bb@2").unwrap());
+        assert_ne!(key, GateCache::key(&b, "bb@1").unwrap());
+        assert!(GateCache::key(&dir.path().join("missing.json"), "bb@1").is_err());
+    }
+}
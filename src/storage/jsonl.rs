@@ -1,11 +1,93 @@
 //! JSONL (JSON Lines) storage for benchmark records.
+//!
+//! Archives whose path ends in `.gz` or `.zst` are compressed transparently.
+//! Each append writes a new gzip member / zstd frame, which both formats
+//! allow to be concatenated, so appending never rewrites the file.
 
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 
+use ed25519_dalek::SigningKey;
+
 use crate::BenchError;
-use crate::core::schema::{BenchRecord, SCHEMA_VERSION};
+use crate::core::schema::{BenchRecord, SCHEMA_VERSION, parse_record};
+use crate::core::signing::{sign_record, signed_line};
+
+/// Compression of a JSONL archive, chosen by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// `.gz` is gzip, `.zst` is zstd, anything else is plain text.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// File extension including the dot (`""` for plain text).
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+}
+
+/// Open a JSONL archive for reading line by line, decompressing it if its
+/// extension says so.
+pub fn open_lines(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = match Compression::from_path(path) {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(file)?),
+    };
+    Ok(Box::new(BufReader::new(reader)))
+}
+
+/// Append `lines` (each without its newline) to a JSONL archive, compressed
+/// as one member/frame when the extension asks for it. With `truncate`, the
+/// file is replaced instead. The data is synced to disk before returning, so
+/// streamed records survive a crash of the run that wrote them.
+pub fn write_lines(path: &Path, lines: &[String], truncate: bool) -> io::Result<()> {
+    let mut buf = Vec::new();
+    for line in lines {
+        buf.extend_from_slice(line.as_bytes());
+        buf.push(b'\n');
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(!truncate)
+        .truncate(truncate)
+        .open(path)?;
+    let file = match Compression::from_path(path) {
+        Compression::None => {
+            file.write_all(&buf)?;
+            file
+        }
+        Compression::Gzip => {
+            let mut enc = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            enc.write_all(&buf)?;
+            enc.finish()?
+        }
+        Compression::Zstd => {
+            let mut enc = zstd::stream::write::Encoder::new(file, 0)?;
+            enc.write_all(&buf)?;
+            enc.finish()?
+        }
+    };
+    file.sync_data()
+}
 
 /// JSONL writer/reader for benchmark records.
 ///
@@ -14,6 +96,7 @@ use crate::core::schema::{BenchRecord, SCHEMA_VERSION};
 #[derive(Debug, Clone)]
 pub struct JsonlWriter {
     path: PathBuf,
+    signing_key: Option<SigningKey>,
 }
 
 impl JsonlWriter {
@@ -23,9 +106,16 @@ impl JsonlWriter {
     pub fn new(path: impl AsRef<Path>) -> Self {
         JsonlWriter {
             path: path.as_ref().to_path_buf(),
+            signing_key: None,
         }
     }
 
+    /// Sign unsigned records with this key as they are appended.
+    pub fn with_signing_key(mut self, key: SigningKey) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+
     /// Get the path to the JSONL file.
     pub fn path(&self) -> &Path {
         &self.path
@@ -38,6 +128,10 @@ impl JsonlWriter {
     /// - The record's schema_version doesn't match SCHEMA_VERSION
     /// - File operations fail
     /// - JSON serialization fails
+    ///
+    /// Records without a signature have their metrics rounded (see
+    /// `core::rounding`) and, with a signing key set, are signed before
+    /// writing; records that already carry one are written unchanged.
     pub fn append(&self, record: &BenchRecord) -> Result<(), BenchError> {
         // Validate schema version
         if record.schema_version != SCHEMA_VERSION {
@@ -55,18 +149,27 @@ impl JsonlWriter {
             }
         }
 
-        // Open file in append mode
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)
-            .map_err(|e| BenchError::Message(format!("failed to open file: {e}")))?;
+        let prepared;
+        let record = if record.signature.is_none() {
+            let mut r = record.clone();
+            r.round_metrics();
+            if let Some(key) = &self.signing_key {
+                sign_record(&mut r, key)?;
+            }
+            prepared = r;
+            &prepared
+        } else {
+            record
+        };
 
-        // Serialize and write
-        let json = serde_json::to_string(record)
-            .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+        // Signed records are written as the bytes their signature covers
+        let json = match record.signature {
+            Some(_) => signed_line(record)?,
+            None => serde_json::to_string(record)
+                .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?,
+        };
 
-        writeln!(file, "{}", json)
+        write_lines(&self.path, &[json], false)
             .map_err(|e| BenchError::Message(format!("failed to write record: {e}")))?;
 
         Ok(())
@@ -78,7 +181,7 @@ impl JsonlWriter {
     /// Returns an error if:
     /// - The file doesn't exist
     /// - File operations fail
-    /// - JSON deserialization fails for any line
+    /// - Any line is not a supported record shape (see `core::schema::parse_record`)
     pub fn read_all(&self) -> Result<Vec<BenchRecord>, BenchError> {
         self.read_filtered(None)
     }
@@ -92,7 +195,7 @@ impl JsonlWriter {
     /// Returns an error if:
     /// - The file doesn't exist
     /// - File operations fail
-    /// - JSON deserialization fails for any line
+    /// - Any line is not a supported record shape (see `core::schema::parse_record`)
     pub fn read_filtered(
         &self,
         circuit_name: Option<&str>,
@@ -104,10 +207,8 @@ impl JsonlWriter {
             )));
         }
 
-        let file = File::open(&self.path)
+        let reader = open_lines(&self.path)
             .map_err(|e| BenchError::Message(format!("failed to open file: {e}")))?;
-
-        let reader = BufReader::new(file);
         let mut records = Vec::new();
 
         for (line_num, line_result) in reader.lines().enumerate() {
@@ -120,7 +221,8 @@ impl JsonlWriter {
                 continue;
             }
 
-            let record: BenchRecord = serde_json::from_str(&line).map_err(|e| {
+            // Older schema versions and legacy bench lines are upgraded on read
+            let record = parse_record(&line).map_err(|e| {
                 BenchError::Message(format!("failed to parse line {}: {e}", line_num + 1))
             })?;
 
@@ -150,10 +252,8 @@ impl JsonlWriter {
             return Ok(0);
         }
 
-        let file = File::open(&self.path)
+        let reader = open_lines(&self.path)
             .map_err(|e| BenchError::Message(format!("failed to open file: {e}")))?;
-
-        let reader = BufReader::new(file);
         let count = reader
             .lines()
             .filter_map(|l| l.ok())
@@ -201,4 +301,58 @@ mod tests {
                 .contains("schema version mismatch")
         );
     }
+
+    #[test]
+    fn test_read_all_upgrades_older_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mixed.jsonl");
+        let v1 = r#"{"schema_version":1,"record_id":"old","timestamp":"2026-01-01T00:00:00Z","circuit_name":"a","env":{"os":"linux"},"backend":{"name":"bb"},"config":{"warmup_iterations":1,"measured_iterations":3}}"#;
+        std::fs::write(&path, format!("{v1}\n")).unwrap();
+
+        let writer = JsonlWriter::new(&path);
+        writer.append(&make_test_record("b")).unwrap();
+
+        let records = writer.read_all().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.schema_version == SCHEMA_VERSION));
+        assert_eq!(records[0].record_id, "old");
+    }
+
+    #[test]
+    fn test_compressed_archives_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["runs.jsonl.gz", "runs.jsonl.zst"] {
+            let path = dir.path().join(name);
+            let writer = JsonlWriter::new(&path);
+            writer.append(&make_test_record("a")).unwrap();
+            writer.append(&make_test_record("b")).unwrap();
+
+            let raw = std::fs::read(&path).unwrap();
+            assert!(!raw.starts_with(b"{"), "{name} was written uncompressed");
+            let names: Vec<_> = writer
+                .read_all()
+                .unwrap()
+                .into_iter()
+                .map(|r| r.circuit_name)
+                .collect();
+            assert_eq!(names, ["a", "b"]);
+            assert_eq!(writer.count().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn test_append_signs_with_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signed.jsonl");
+        let writer = JsonlWriter::new(&path).with_signing_key(SigningKey::from_bytes(&[3; 32]));
+        writer.append(&make_test_record("a")).unwrap();
+
+        let records = writer.read_all().unwrap();
+        assert!(records[0].signature.is_some());
+        assert!(
+            crate::core::signing::verify_signature(&records[0])
+                .unwrap()
+                .is_some()
+        );
+    }
 }
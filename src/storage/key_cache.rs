@@ -0,0 +1,109 @@
+//! Cache of verification keys keyed by circuit content.
+//!
+//! A vk only depends on the artifact and the backend (version and flags) that
+//! generated it, so a circuit benchmarked again in a later session can skip
+//! vk generation. bb builds the proving key in memory on every prove, so
+//! there is no proving key to cache:
+//!
+//! ```text
+//! <root>/<sha256(artifact sha256, backend id)>/vk
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::{BenchError, BenchResult};
+
+use super::GateCache;
+
+/// Default cache location, relative to the working directory.
+pub const DEFAULT_KEY_CACHE: &str = ".noir-bench/keys";
+
+/// A directory of generated vks keyed by artifact hash and backend.
+#[derive(Debug, Clone)]
+pub struct KeyCache {
+    root: PathBuf,
+}
+
+impl KeyCache {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        KeyCache {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Get the cache root.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Cache key for the vk of `artifact` generated by `backend_id`, which
+    /// must identify everything that changes it (name, version, flags).
+    pub fn key(artifact: &Path, backend_id: &str) -> BenchResult<String> {
+        GateCache::key(artifact, backend_id)
+    }
+
+    /// Directory the vk for `key` is stored in.
+    pub fn dir(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+
+    /// Path of the cached vk for `key`, if there is one.
+    pub fn vk(&self, key: &str) -> Option<PathBuf> {
+        Some(self.dir(key).join("vk")).filter(|vk| vk.is_file())
+    }
+
+    /// Store the vk at `vk` under `key`, replacing what was cached before.
+    /// Returns the cached vk's path.
+    ///
+    /// The vk is copied to a temporary file next to the entry and renamed over
+    /// it, so a concurrent run sees either the old vk or the new one, never a
+    /// partly written file or a missing entry.
+    pub fn put(&self, key: &str, vk: &Path) -> BenchResult<PathBuf> {
+        let io = |what: &str, path: &Path, e: std::io::Error| {
+            BenchError::Message(format!("failed to {what} {}: {e}", path.display()))
+        };
+        let dir = self.dir(key);
+        std::fs::create_dir_all(&dir).map_err(|e| io("create", &dir, e))?;
+        let tmp = dir.join(format!(".vk.tmp-{}", std::process::id()));
+        let cached = dir.join("vk");
+        if let Err(e) = std::fs::copy(vk, &tmp) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(io("copy", vk, e));
+        }
+        if let Err(e) = std::fs::rename(&tmp, &cached) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(io("write", &cached, e));
+        }
+        Ok(cached)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_lookup_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("program.json");
+        std::fs::write(&artifact, b"{}").unwrap();
+        let generated = dir.path().join("out");
+        std::fs::create_dir_all(&generated).unwrap();
+        std::fs::write(generated.join("vk"), b"vk-1").unwrap();
+
+        let cache = KeyCache::new(dir.path().join("keys"));
+        let key = KeyCache::key(&artifact, "barretenberg@1.0.0").unwrap();
+        assert!(cache.vk(&key).is_none());
+
+        let vk = cache.put(&key, &generated.join("vk")).unwrap();
+        assert_eq!(cache.vk(&key).as_deref(), Some(vk.as_path()));
+        assert_eq!(std::fs::read(&vk).unwrap(), b"vk-1");
+
+        // A regenerated vk replaces the entry without leaving temp files
+        std::fs::write(generated.join("vk"), b"vk-2").unwrap();
+        let vk = cache.put(&key, &generated.join("vk")).unwrap();
+        assert_eq!(std::fs::read(&vk).unwrap(), b"vk-2");
+        let entries: Vec<_> = std::fs::read_dir(cache.dir(&key)).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+}
@@ -2,9 +2,27 @@
 //!
 //! This module provides persistence for `BenchRecord` data in various formats.
 
+pub mod artifacts;
 pub mod csv;
+pub mod gate_cache;
 pub mod jsonl;
+pub mod key_cache;
+pub mod out_dir;
+pub mod run_logs;
+pub mod sink;
+pub mod triage;
+pub mod untrusted;
+pub mod work_dir;
 
 // Re-export key types
+pub use artifacts::{ArtifactStore, DEFAULT_ARTIFACT_STORE};
 pub use csv::{CSV_HEADERS, CsvExporter};
-pub use jsonl::JsonlWriter;
+pub use gate_cache::{DEFAULT_GATE_CACHE, GateCache};
+pub use jsonl::{Compression, JsonlWriter};
+pub use key_cache::{DEFAULT_KEY_CACHE, KeyCache};
+pub use out_dir::{MANIFEST_FILE, Manifest, ManifestEntry, OutDir};
+pub use run_logs::FailureLogs;
+pub use sink::{RecordSink, open_sink};
+pub use triage::{TRIAGE_DIR_NAME, TriageBundle};
+pub use untrusted::{ParseLimits, UntrustedRecords, read_untrusted};
+pub use work_dir::{CircuitWorkDir, Retention, RunWorkDir, WORK_DIR_NAME};
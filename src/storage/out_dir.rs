@@ -0,0 +1,308 @@
+//! Per-invocation output directories.
+//!
+//! With the global `--out-dir DIR`, a command's results default to fixed
+//! names inside `DIR` instead of needing a path flag each; explicit flags
+//! still win. Only results the command produces anyway are placed there
+//! (its JSON report, records and summary); renderings such as `--html-out`
+//! or `--csv` still have to be asked for. Failure logs, triage bundles and
+//! work directories already go next to the report or JSONL, so they end up
+//! in `DIR` too:
+//!
+//! ```text
+//! DIR/report.json       --json
+//! DIR/records.jsonl     --jsonl, ci/bench --output
+//! DIR/regression.json   --json-out
+//! DIR/report.csv        bench --csv
+//! DIR/summary.json      suite --summary
+//! DIR/logs/, DIR/triage/, DIR/work/, flamegraphs
+//! DIR/manifest.json
+//! ```
+//!
+//! When the command exits, `manifest.json` lists the files this invocation
+//! wrote to `DIR` (new, or changed since it started) with their size and
+//! sha256, along with the command line and exit code, so a CI job can
+//! upload the directory and later check what it holds.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BenchError, BenchResult, file_sha256};
+
+/// Name of the manifest written to the output directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Default names of the outputs in the directory.
+pub const REPORT_JSON: &str = "report.json";
+pub const RECORDS_JSONL: &str = "records.jsonl";
+pub const REGRESSION_JSON: &str = "regression.json";
+pub const REPORT_CSV: &str = "report.csv";
+pub const SUMMARY_JSON: &str = "summary.json";
+
+/// A file listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the output directory, with `/` separators
+    pub path: String,
+    /// What the file is: report, records, html, csv, markdown, log,
+    /// flamegraph, triage, work or other
+    pub kind: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// `manifest.json` of an output directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    /// Subcommand path, e.g. `bench run-all`
+    pub command: String,
+    pub args: Vec<String>,
+    pub noir_bench_version: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub exit_code: i32,
+    pub artifacts: Vec<ManifestEntry>,
+}
+
+/// Size and modification time of a file, to tell whether it was rewritten.
+type FileStamp = (u64, Option<SystemTime>);
+
+fn file_stamp(path: &Path) -> FileStamp {
+    match std::fs::metadata(path) {
+        Ok(meta) => (meta.len(), meta.modified().ok()),
+        Err(_) => (0, None),
+    }
+}
+
+/// The output directory of one invocation.
+#[derive(Debug, Clone)]
+pub struct OutDir {
+    root: PathBuf,
+    command: String,
+    started_at: String,
+    /// Files already in the directory when the invocation started
+    existing: BTreeMap<PathBuf, FileStamp>,
+}
+
+fn now_string() -> String {
+    time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default()
+}
+
+impl OutDir {
+    /// Create `root` (and its parents) for a run of `command`.
+    pub fn create(root: impl Into<PathBuf>, command: &str) -> BenchResult<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(|e| {
+            BenchError::Message(format!("failed to create {}: {e}", root.display()))
+        })?;
+        let mut files = Vec::new();
+        collect_files(&root, &mut files)?;
+        let existing = files
+            .into_iter()
+            .map(|file| {
+                let stamp = file_stamp(&file);
+                (file, stamp)
+            })
+            .collect();
+        Ok(OutDir {
+            root,
+            command: command.to_string(),
+            started_at: now_string(),
+            existing,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of `name` in the directory.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    /// Point an unset path flag at `name` in the directory.
+    pub fn fill(&self, flag: &mut Option<PathBuf>, name: &str) {
+        if flag.is_none() {
+            *flag = Some(self.path(name));
+        }
+    }
+
+    /// Build the manifest of the files this invocation wrote so far.
+    pub fn manifest(&self, exit_code: i32) -> BenchResult<Manifest> {
+        let mut files = Vec::new();
+        collect_files(&self.root, &mut files)?;
+        // Outputs of earlier runs into the same directory aren't ours
+        files.retain(|file| self.existing.get(file) != Some(&file_stamp(file)));
+        let mut artifacts = Vec::new();
+        for file in files {
+            let Ok(rel) = file.strip_prefix(&self.root) else {
+                continue;
+            };
+            let path: Vec<String> = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let path = path.join("/");
+            if path == MANIFEST_FILE {
+                continue;
+            }
+            let bytes = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+            let sha256 = file_sha256(&file)
+                .ok_or_else(|| BenchError::Message(format!("failed to read {}", file.display())))?;
+            artifacts.push(ManifestEntry {
+                kind: artifact_kind(&path).to_string(),
+                path,
+                bytes,
+                sha256,
+            });
+        }
+        artifacts.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(Manifest {
+            version: 1,
+            command: self.command.clone(),
+            args: std::env::args().collect(),
+            noir_bench_version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: self.started_at.clone(),
+            finished_at: now_string(),
+            exit_code,
+            artifacts,
+        })
+    }
+
+    /// Write `manifest.json` for a run that exits with `exit_code`; failures
+    /// only warn, since the run itself is over.
+    pub fn finish(&self, exit_code: i32) {
+        let path = self.path(MANIFEST_FILE);
+        let written = self.manifest(exit_code).and_then(|manifest| {
+            let json = serde_json::to_string_pretty(&manifest)
+                .map_err(|e| BenchError::Message(format!("failed to serialize manifest: {e}")))?;
+            std::fs::write(&path, json).map_err(|e| {
+                BenchError::Message(format!("failed to write {}: {e}", path.display()))
+            })
+        });
+        match written {
+            Ok(()) => eprintln!("Wrote manifest to {}", path.display()),
+            Err(e) => eprintln!("Warning: {e}"),
+        }
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> BenchResult<()> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", dir.display())))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => collect_files(&path, files)?,
+            Ok(t) if t.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Kind of a file in the directory, from its location and extension.
+fn artifact_kind(path: &str) -> &'static str {
+    let top = path.split('/').next().unwrap_or_default();
+    match top {
+        "logs" => return "log",
+        "triage" => return "triage",
+        "work" => return "work",
+        _ => {}
+    }
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let name = name
+        .strip_suffix(".gz")
+        .or_else(|| name.strip_suffix(".zst"))
+        .unwrap_or(name);
+    match name.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("jsonl") => "records",
+        Some("json") => "report",
+        Some("html") => "html",
+        Some("csv") => "csv",
+        Some("md") => "markdown",
+        Some("svg") => "flamegraph",
+        Some("log") => "log",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_keeps_explicit_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = OutDir::create(dir.path().join("run"), "prove").unwrap();
+        assert!(out.root().is_dir());
+
+        let mut json = None;
+        out.fill(&mut json, REPORT_JSON);
+        assert_eq!(json, Some(dir.path().join("run").join("report.json")));
+        let mut jsonl = Some(PathBuf::from("elsewhere.jsonl"));
+        out.fill(&mut jsonl, RECORDS_JSONL);
+        assert_eq!(jsonl, Some(PathBuf::from("elsewhere.jsonl")));
+    }
+
+    #[test]
+    fn test_manifest_skips_files_of_earlier_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.json"), "{}").unwrap();
+        std::fs::write(dir.path().join(RECORDS_JSONL), "a\n").unwrap();
+
+        let out = OutDir::create(dir.path(), "bench run").unwrap();
+        std::fs::write(out.path(RECORDS_JSONL), "a\nb\n").unwrap();
+        std::fs::write(out.path(REPORT_CSV), "x").unwrap();
+        let listed: Vec<String> = out
+            .manifest(0)
+            .unwrap()
+            .artifacts
+            .into_iter()
+            .map(|a| a.path)
+            .collect();
+        assert_eq!(listed, vec!["records.jsonl", "report.csv"]);
+    }
+
+    #[test]
+    fn test_manifest_lists_files_with_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = OutDir::create(dir.path(), "bench run-all").unwrap();
+        std::fs::write(out.path(REPORT_JSON), "{}").unwrap();
+        std::fs::write(out.path("records.jsonl.gz"), "x").unwrap();
+        std::fs::create_dir_all(out.path("logs")).unwrap();
+        std::fs::write(out.path("logs/bb_prove-001.log"), "boom").unwrap();
+
+        out.finish(1);
+        let text = std::fs::read_to_string(out.path(MANIFEST_FILE)).unwrap();
+        let manifest: Manifest = serde_json::from_str(&text).unwrap();
+        assert_eq!(manifest.command, "bench run-all");
+        assert_eq!(manifest.exit_code, 1);
+        let listed: Vec<(&str, &str)> = manifest
+            .artifacts
+            .iter()
+            .map(|a| (a.path.as_str(), a.kind.as_str()))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("logs/bb_prove-001.log", "log"),
+                ("records.jsonl.gz", "records"),
+                ("report.json", "report"),
+            ]
+        );
+        assert_eq!(manifest.artifacts[2].bytes, 2);
+        assert_eq!(manifest.artifacts[2].sha256, crate::sha256_hex(b"{}"));
+
+        // A second manifest doesn't list the first
+        out.finish(0);
+        let manifest = out.manifest(0).unwrap();
+        assert_eq!(manifest.artifacts.len(), 3);
+    }
+}
@@ -0,0 +1,163 @@
+//! Retained output of failed backend and toolchain commands.
+//!
+//! When bb or nargo fails, its full stdout/stderr is written to a log file
+//! under the run's output directory so the diagnostics survive the temp dirs
+//! the command ran in. Logs are capped in size, keeping the end of each
+//! stream where the error usually is, and only the tail of stderr goes into
+//! the error message printed to the console.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+
+use crate::{BenchError, BenchResult};
+
+/// Default cap per stream in a failure log (1 MiB).
+pub const DEFAULT_MAX_LOG_BYTES: usize = 1024 * 1024;
+
+/// Lines of stderr shown in the error message.
+pub const CONSOLE_TAIL_LINES: usize = 20;
+
+/// Directory failed command output is kept in.
+#[derive(Debug, Clone)]
+pub struct FailureLogs {
+    dir: PathBuf,
+    max_bytes: usize,
+}
+
+impl FailureLogs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FailureLogs {
+            dir: dir.into(),
+            max_bytes: DEFAULT_MAX_LOG_BYTES,
+        }
+    }
+
+    /// Cap each stream at `max_bytes`, dropping its beginning.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write a command's output to `<dir>/<label>-<n>.log` (first free `n`)
+    /// and return the path.
+    pub fn save(&self, label: &str, stdout: &[u8], stderr: &[u8]) -> BenchResult<PathBuf> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| {
+            BenchError::Message(format!("failed to create {}: {e}", self.dir.display()))
+        })?;
+        let label: String = label
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = (1..)
+            .map(|n| self.dir.join(format!("{label}-{n:03}.log")))
+            .find(|p| !p.exists())
+            .expect("unbounded range");
+
+        let mut content = Vec::new();
+        content.extend_from_slice(b"== stdout ==\n");
+        content.extend_from_slice(&cap_tail(stdout, self.max_bytes));
+        content.extend_from_slice(b"\n== stderr ==\n");
+        content.extend_from_slice(&cap_tail(stderr, self.max_bytes));
+        std::fs::write(&path, content)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", path.display())))?;
+        Ok(path)
+    }
+}
+
+/// The last `max_bytes` of `bytes`, prefixed with a note of what was cut.
+fn cap_tail(bytes: &[u8], max_bytes: usize) -> Vec<u8> {
+    if bytes.len() <= max_bytes {
+        return bytes.to_vec();
+    }
+    let cut = bytes.len() - max_bytes;
+    let mut out = format!("[... {cut} bytes truncated ...]\n").into_bytes();
+    out.extend_from_slice(&bytes[cut..]);
+    out
+}
+
+/// The last `n` non-empty lines of `text`.
+pub fn tail_lines(text: &str, n: usize) -> String {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(n)..].join("\n")
+}
+
+/// Error for a failed command: `what` and its status, the stderr tail, and
+/// the log its full output was saved to when `logs` is set.
+pub fn command_failure(
+    what: &str,
+    status: ExitStatus,
+    stdout: &[u8],
+    stderr: &[u8],
+    logs: Option<&FailureLogs>,
+) -> BenchError {
+    let mut message = format!("{what} failed: status={status}");
+    let tail = tail_lines(&String::from_utf8_lossy(stderr), CONSOLE_TAIL_LINES);
+    if !tail.is_empty() {
+        message.push('\n');
+        message.push_str(&tail);
+    }
+    let log = logs.and_then(|logs| match logs.save(what, stdout, stderr) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            eprintln!("warning: {e}");
+            None
+        }
+    });
+    if let Some(path) = &log {
+        message.push_str(&format!("\n(full output: {})", path.display()));
+    }
+    BenchError::CommandFailed { message, log }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\n\nc\n", 2), "b\nc");
+        assert_eq!(tail_lines("a\n", 5), "a");
+        assert_eq!(tail_lines("", 5), "");
+    }
+
+    #[test]
+    fn test_save_caps_and_numbers_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = FailureLogs::new(dir.path().join("logs")).with_max_bytes(4);
+
+        let first = logs.save("bb prove", b"out", b"0123456789").unwrap();
+        assert_eq!(first.file_name().unwrap(), "bb_prove-001.log");
+        let content = std::fs::read_to_string(&first).unwrap();
+        assert!(content.contains("== stdout ==\nout\n"));
+        assert!(content.contains("[... 6 bytes truncated ...]\n6789"));
+
+        let second = logs.save("bb prove", b"", b"").unwrap();
+        assert_eq!(second.file_name().unwrap(), "bb_prove-002.log");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_failure_references_log() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let logs = FailureLogs::new(dir.path());
+        let err = command_failure(
+            "bb prove",
+            ExitStatus::from_raw(1 << 8),
+            b"",
+            b"loading srs\nassertion failed: x == 1\n",
+            Some(&logs),
+        );
+        let log = err.log_path().unwrap().to_path_buf();
+        assert!(log.exists());
+        let message = err.to_string();
+        assert!(message.starts_with("bb prove failed: status="));
+        assert!(message.contains("assertion failed: x == 1"));
+        assert!(message.ends_with(&format!("(full output: {})", log.display())));
+    }
+}
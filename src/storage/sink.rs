@@ -0,0 +1,232 @@
+//! Destinations for streamed JSONL records.
+//!
+//! Long-running suites write each record as soon as it is produced, so a
+//! runner that dies late in the suite keeps what it already measured. A sink
+//! is picked from the `--jsonl` spec:
+//!
+//! - `tcp://host:port`: one JSON line per record over a TCP connection
+//! - `http://...` / `https://...`: one POST per record (sent with curl)
+//! - anything else: a local file, truncated and flushed after every record
+//!   (compressed per record when it ends in `.gz` / `.zst`)
+
+use std::io::Write;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::Value as JsonValue;
+
+use crate::storage::jsonl::write_lines;
+use crate::{BenchError, BenchResult};
+
+/// Something records can be streamed to, one at a time.
+pub trait RecordSink {
+    /// Send one record; it should be durable (or delivered) when this returns.
+    fn send(&mut self, record: &JsonValue) -> BenchResult<()>;
+
+    /// Where records go, for messages.
+    fn describe(&self) -> String;
+}
+
+/// Open the sink named by `spec` (see the module docs).
+pub fn open_sink(spec: &str) -> BenchResult<Box<dyn RecordSink>> {
+    if let Some(addr) = spec.strip_prefix("tcp://") {
+        Ok(Box::new(TcpSink::connect(addr)?))
+    } else if spec.starts_with("https://") || spec.starts_with("http://") {
+        Ok(Box::new(HttpSink::new(spec)))
+    } else {
+        Ok(Box::new(FileSink::create(spec)?))
+    }
+}
+
+fn to_line(record: &JsonValue) -> BenchResult<Vec<u8>> {
+    let mut line = serde_json::to_vec(record)
+        .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// JSONL file, flushed to disk after every record.
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    /// Create (or truncate) `path`, creating its parent directory.
+    pub fn create(path: impl AsRef<Path>) -> BenchResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        write_lines(&path, &[], true).map_err(|e| {
+            BenchError::Message(format!("failed to create {}: {e}", path.display()))
+        })?;
+        Ok(FileSink { path })
+    }
+}
+
+impl RecordSink for FileSink {
+    fn send(&mut self, record: &JsonValue) -> BenchResult<()> {
+        let line = serde_json::to_string(record)
+            .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+        write_lines(&self.path, &[line], false)
+            .map_err(|e| BenchError::Message(format!("failed to write record: {e}")))
+    }
+
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+/// Newline-delimited JSON over TCP. A dropped connection is reopened once
+/// per record before giving up.
+pub struct TcpSink {
+    addr: String,
+    stream: Option<TcpStream>,
+}
+
+impl TcpSink {
+    /// Connect to `addr` (`host:port`).
+    pub fn connect(addr: &str) -> BenchResult<Self> {
+        let stream = TcpStream::connect(addr)
+            .map_err(|e| BenchError::Message(format!("failed to connect to tcp://{addr}: {e}")))?;
+        Ok(TcpSink {
+            addr: addr.to_string(),
+            stream: Some(stream),
+        })
+    }
+}
+
+impl RecordSink for TcpSink {
+    fn send(&mut self, record: &JsonValue) -> BenchResult<()> {
+        let line = to_line(record)?;
+        if let Some(stream) = self.stream.as_mut() {
+            if stream.write_all(&line).and_then(|_| stream.flush()).is_ok() {
+                return Ok(());
+            }
+        }
+        self.stream = None;
+        let mut stream = TcpStream::connect(&self.addr).map_err(|e| {
+            BenchError::Message(format!("failed to reconnect to tcp://{}: {e}", self.addr))
+        })?;
+        stream
+            .write_all(&line)
+            .and_then(|_| stream.flush())
+            .map_err(|e| {
+                BenchError::Message(format!("failed to send record to tcp://{}: {e}", self.addr))
+            })?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("tcp://{}", self.addr)
+    }
+}
+
+/// POSTs each record as JSON to an HTTP(S) endpoint with curl.
+pub struct HttpSink {
+    url: String,
+}
+
+impl HttpSink {
+    pub fn new(url: &str) -> Self {
+        HttpSink {
+            url: url.to_string(),
+        }
+    }
+}
+
+impl RecordSink for HttpSink {
+    fn send(&mut self, record: &JsonValue) -> BenchResult<()> {
+        let body = to_line(record)?;
+        let mut child = Command::new("curl")
+            .args([
+                "-fsS",
+                "--max-time",
+                "30",
+                "-X",
+                "POST",
+                "-H",
+                "Content-Type: application/x-ndjson",
+                "--data-binary",
+                "@-",
+                &self.url,
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|e| BenchError::Message(format!("failed to run curl: {e}")))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(&body)
+                .map_err(|e| BenchError::Message(format!("failed to send record: {e}")))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| BenchError::Message(format!("failed to wait for curl: {e}")))?;
+        if !status.success() {
+            return Err(BenchError::Message(format!(
+                "POST {} exited with status: {status}",
+                self.url
+            )));
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        self.url.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_file_sink_writes_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/out.jsonl");
+        let mut sink = open_sink(path.to_str().unwrap()).unwrap();
+        sink.send(&serde_json::json!({"name": "a"})).unwrap();
+        sink.send(&serde_json::json!({"name": "b"})).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"name\":\"a\"}\n{\"name\":\"b\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_tcp_sink_streams_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            BufReader::new(stream)
+                .lines()
+                .map(|l| l.unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let mut sink = open_sink(&format!("tcp://{addr}")).unwrap();
+        assert_eq!(sink.describe(), format!("tcp://{addr}"));
+        sink.send(&serde_json::json!({"name": "a"})).unwrap();
+        sink.send(&serde_json::json!({"name": "b"})).unwrap();
+        drop(sink);
+
+        assert_eq!(
+            server.join().unwrap(),
+            vec!["{\"name\":\"a\"}", "{\"name\":\"b\"}"]
+        );
+    }
+
+    #[test]
+    fn test_tcp_sink_reports_unreachable_collector() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        assert!(open_sink(&format!("tcp://{addr}")).is_err());
+    }
+}
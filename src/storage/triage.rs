@@ -0,0 +1,272 @@
+//! Triage bundles for failed runs.
+//!
+//! When a circuit fails in CI or a suite, everything needed to reproduce the
+//! failure locally is collected into one zip next to the run's output:
+//!
+//! ```text
+//! <output>/triage/<bundle>.zip, <bundle> = <circuit>[-p<params>]-<timestamp>
+//!   <bundle>/manifest.json   circuit, params, error, artifact sha256, command lines
+//!   <bundle>/env.json        the machine the run failed on
+//!   <bundle>/record.json     the partial record written for the failure
+//!   <bundle>/Prover.toml     the inputs, if any
+//!   <bundle>/artifact/<name> the compiled circuit, unless it is very large
+//!   <bundle>/logs/<name>     captured output of the command that failed
+//! ```
+//!
+//! Entries sit under a directory named like the zip, so unpacking several
+//! bundles in one place keeps them apart.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value, json};
+use zip::CompressionMethod;
+use zip::write::SimpleFileOptions;
+
+use crate::core::EnvironmentInfo;
+use crate::{BenchError, BenchResult, file_sha256};
+
+/// Directory under the output directory that bundles are written to.
+pub const TRIAGE_DIR_NAME: &str = "triage";
+
+/// Artifacts larger than this are referenced by hash only.
+pub const MAX_ARTIFACT_BYTES: u64 = 32 * 1024 * 1024;
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Files and facts about one failed run, written out as a zip.
+#[derive(Debug, Clone)]
+pub struct TriageBundle {
+    name: String,
+    manifest: Map<String, Value>,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl TriageBundle {
+    /// Start a bundle for `circuit` (with `params`, if any) that failed with
+    /// `error`. The command line of this process is recorded as well.
+    pub fn new(circuit: &str, params: Option<u64>, error: &str) -> Self {
+        let name = match params {
+            Some(p) => format!("{}-p{p}", sanitize(circuit)),
+            None => sanitize(circuit),
+        };
+        let mut manifest = Map::new();
+        manifest.insert("circuit".into(), json!(circuit));
+        manifest.insert("params".into(), json!(params));
+        manifest.insert("error".into(), json!(error));
+        manifest.insert(
+            "command".into(),
+            json!(std::env::args().collect::<Vec<_>>()),
+        );
+        manifest.insert(
+            "noir_bench_version".into(),
+            json!(env!("CARGO_PKG_VERSION")),
+        );
+        TriageBundle {
+            name,
+            manifest,
+            files: Vec::new(),
+        }
+    }
+
+    fn add_file(&mut self, key: &str, name: String, path: &Path) {
+        if self.files.iter().any(|(n, _)| *n == name) {
+            eprintln!(
+                "warning: triage bundle already has a {name}, skipping {}",
+                path.display()
+            );
+            return;
+        }
+        match std::fs::read(path) {
+            Ok(bytes) => {
+                self.manifest.insert(key.into(), json!(name));
+                self.files.push((name, bytes));
+            }
+            Err(e) => eprintln!(
+                "warning: failed to add {} to triage bundle: {e}",
+                path.display()
+            ),
+        }
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "file".to_string())
+    }
+
+    /// Record the artifact's path and hash, and include it unless it is
+    /// larger than [`MAX_ARTIFACT_BYTES`].
+    pub fn with_artifact(mut self, path: &Path) -> Self {
+        self.manifest
+            .insert("artifact_path".into(), json!(path.display().to_string()));
+        self.manifest
+            .insert("artifact_sha256".into(), json!(file_sha256(path)));
+        let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if size > MAX_ARTIFACT_BYTES {
+            self.manifest.insert("artifact".into(), Value::Null);
+        } else {
+            let name = format!("artifact/{}", Self::file_name(path));
+            self.add_file("artifact", name, path);
+        }
+        self
+    }
+
+    pub fn with_prover_toml(mut self, path: &Path) -> Self {
+        self.manifest
+            .insert("prover_toml_path".into(), json!(path.display().to_string()));
+        self.add_file("prover_toml", "Prover.toml".to_string(), path);
+        self
+    }
+
+    /// Include the captured output of a failed command.
+    pub fn with_log(mut self, path: &Path) -> Self {
+        let name = format!("logs/{}", Self::file_name(path));
+        self.add_file("log", name, path);
+        self
+    }
+
+    /// Extra arguments the backend was run with.
+    pub fn with_backend_args(mut self, args: &[String]) -> Self {
+        self.manifest.insert("backend_args".into(), json!(args));
+        self
+    }
+
+    /// The (partial) record written for the failed run.
+    pub fn with_record(mut self, record: &Value) -> Self {
+        let bytes = serde_json::to_vec_pretty(record).expect("record serializes");
+        self.files.push(("record.json".to_string(), bytes));
+        self
+    }
+
+    /// Write the bundle to `<dir>/<circuit>[-p<params>]-<timestamp>.zip`.
+    pub fn write(self, dir: &Path) -> BenchResult<PathBuf> {
+        let now = time::OffsetDateTime::now_utc();
+        let root = format!(
+            "{}-{:04}{:02}{:02}T{:02}{:02}{:02}",
+            self.name,
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        let path = dir.join(format!("{root}.zip"));
+        std::fs::create_dir_all(dir)
+            .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", dir.display())))?;
+
+        let mut entries = vec![
+            (
+                "manifest.json".to_string(),
+                serde_json::to_vec_pretty(&self.manifest).expect("manifest serializes"),
+            ),
+            (
+                "env.json".to_string(),
+                serde_json::to_vec_pretty(&EnvironmentInfo::detect()).expect("env serializes"),
+            ),
+        ];
+        entries.extend(self.files);
+        let options = SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .last_modified_time(zip::DateTime::try_from(now).unwrap_or_default());
+        let written = std::fs::File::create(&path)
+            .map_err(zip::result::ZipError::from)
+            .and_then(|file| {
+                let mut zip = zip::ZipWriter::new(file);
+                for (name, bytes) in &entries {
+                    zip.start_file(format!("{root}/{name}"), options)?;
+                    zip.write_all(bytes)?;
+                }
+                zip.finish()
+            });
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&path);
+            return Err(BenchError::Message(format!(
+                "failed to write {}: {e}",
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn read_zip(path: &Path) -> Vec<(String, Vec<u8>)> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut entry = archive.by_index(i).unwrap();
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data).unwrap();
+                (entry.name().to_string(), data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bundle_collects_reproduction_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("merkle.json");
+        std::fs::write(&artifact, b"{\"bytecode\":\"\"}").unwrap();
+        let prover = dir.path().join("Prover.toml");
+        std::fs::write(&prover, b"x = 1\n").unwrap();
+        let log = dir.path().join("bb_prove-001.log");
+        std::fs::write(&log, b"== stderr ==\nout of memory\n").unwrap();
+
+        let path = TriageBundle::new("merkle/deep", Some(16), "bb prove failed")
+            .with_artifact(&artifact)
+            .with_prover_toml(&prover)
+            .with_log(&log)
+            .with_log(&log)
+            .with_log(&dir.path().join("missing.log"))
+            .with_backend_args(&["--disable_zk".to_string()])
+            .with_record(&json!({ "circuit_name": "merkle/deep" }))
+            .write(&dir.path().join(TRIAGE_DIR_NAME))
+            .unwrap();
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(file_name.starts_with("merkle_deep-p16-") && file_name.ends_with(".zip"));
+
+        let root = file_name.strip_suffix(".zip").unwrap();
+
+        let entries = read_zip(&path);
+        let names: Vec<&str> = entries
+            .iter()
+            .map(|(n, _)| n.strip_prefix(&format!("{root}/")).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "manifest.json",
+                "env.json",
+                "artifact/merkle.json",
+                "Prover.toml",
+                "logs/bb_prove-001.log",
+                "record.json"
+            ]
+        );
+        let manifest: Value = serde_json::from_slice(&entries[0].1).unwrap();
+        assert_eq!(manifest["params"], 16);
+        assert_eq!(manifest["error"], "bb prove failed");
+        assert_eq!(
+            manifest["artifact_sha256"],
+            crate::sha256_hex(b"{\"bytecode\":\"\"}")
+        );
+        assert_eq!(manifest["backend_args"][0], "--disable_zk");
+        assert_eq!(entries[3].1, b"x = 1\n");
+    }
+}
@@ -0,0 +1,274 @@
+//! Hardened JSONL reading for archives from untrusted sources.
+//!
+//! `history build` and `ci collect` ingest records from many contributors.
+//! Reading never fails or panics on a bad line: lines that are too long or
+//! too deeply nested, invalid JSON, unsupported record shapes and records
+//! with absurd values (negative or non-finite timings and sizes, sample
+//! lists beyond any real run) are skipped with a warning naming the line.
+//! Only failing to read the archive itself is an error.
+
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use crate::BenchError;
+use crate::core::schema::{BenchRecord, TimingStat, parse_record};
+
+use super::jsonl::open_lines;
+
+/// Limits applied to each line before it is parsed.
+#[derive(Debug, Clone, Copy)]
+pub struct ParseLimits {
+    /// Longest accepted line, in bytes
+    pub max_line_bytes: usize,
+    /// Deepest accepted nesting of JSON arrays and objects
+    pub max_depth: usize,
+    /// Most samples accepted in one timing stat
+    pub max_samples: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_line_bytes: 4 * 1024 * 1024,
+            max_depth: 32,
+            max_samples: 100_000,
+        }
+    }
+}
+
+/// Records that passed, and one warning per skipped line.
+#[derive(Debug, Default)]
+pub struct UntrustedRecords {
+    pub records: Vec<BenchRecord>,
+    pub warnings: Vec<String>,
+}
+
+impl UntrustedRecords {
+    /// Print the warnings to stderr, prefixed with `source`.
+    pub fn report_warnings(&self, source: &Path) {
+        for warning in &self.warnings {
+            eprintln!("warning: {}: {warning}", source.display());
+        }
+    }
+}
+
+/// Read every acceptable record of a (possibly compressed) JSONL archive.
+pub fn read_untrusted(path: &Path, limits: &ParseLimits) -> Result<UntrustedRecords, BenchError> {
+    let reader = open_lines(path)
+        .map_err(|e| BenchError::Message(format!("failed to open {}: {e}", path.display())))?;
+    read_lines_untrusted(reader, limits)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))
+}
+
+/// Same as [`read_untrusted`], for any line reader.
+pub fn read_lines_untrusted<R: BufRead>(
+    mut reader: R,
+    limits: &ParseLimits,
+) -> io::Result<UntrustedRecords> {
+    let mut out = UntrustedRecords::default();
+    let mut buf = Vec::new();
+    let mut line_num = 0;
+    loop {
+        buf.clear();
+        // Never buffer more than one byte past the limit
+        let read = reader
+            .by_ref()
+            .take(limits.max_line_bytes as u64 + 1)
+            .read_until(b'\n', &mut buf)?;
+        if read == 0 {
+            break;
+        }
+        line_num += 1;
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+        } else if buf.len() > limits.max_line_bytes {
+            reader.skip_until(b'\n')?;
+            out.warnings.push(format!(
+                "line {line_num}: skipped, longer than {} bytes",
+                limits.max_line_bytes
+            ));
+            continue;
+        }
+        match parse_untrusted(&buf, limits) {
+            Ok(Some(record)) => out.records.push(record),
+            Ok(None) => {}
+            Err(reason) => out
+                .warnings
+                .push(format!("line {line_num}: skipped, {reason}")),
+        }
+    }
+    Ok(out)
+}
+
+/// Parse one line. `Ok(None)` for blank lines; `Err` explains why the line
+/// was rejected.
+pub fn parse_untrusted(line: &[u8], limits: &ParseLimits) -> Result<Option<BenchRecord>, String> {
+    if line.len() > limits.max_line_bytes {
+        return Err(format!("longer than {} bytes", limits.max_line_bytes));
+    }
+    let text = std::str::from_utf8(line).map_err(|_| "not valid UTF-8".to_string())?;
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    if nesting_depth(line) > limits.max_depth {
+        return Err(format!("nested deeper than {}", limits.max_depth));
+    }
+    let record = parse_record(text).map_err(|e| e.to_string())?;
+    check_values(&record, limits)?;
+    Ok(Some(record))
+}
+
+/// Deepest nesting of arrays and objects, ignoring brackets inside strings.
+fn nesting_depth(bytes: &[u8]) -> usize {
+    let (mut depth, mut max) = (0usize, 0usize);
+    let (mut in_string, mut escaped) = (false, false);
+    for &b in bytes {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max
+}
+
+/// Reject records whose measurements can't be real.
+pub fn check_values(record: &BenchRecord, limits: &ParseLimits) -> Result<(), String> {
+    let stats: [(&str, &Option<TimingStat>); 9] = [
+        ("compile_stats", &record.compile_stats),
+        ("compile_warm_stats", &record.compile_warm_stats),
+        (
+            "compile_incremental_stats",
+            &record.compile_incremental_stats,
+        ),
+        ("witness_stats", &record.witness_stats),
+        ("prove_stats", &record.prove_stats),
+        ("verify_stats", &record.verify_stats),
+        ("gates_stats", &record.gates_stats),
+        ("oracle_stats", &record.oracle_stats),
+        ("vk_gen_stats", &record.vk_gen_stats),
+    ];
+    for (name, stat) in stats {
+        let Some(stat) = stat else { continue };
+        if stat.samples_ms.len() > limits.max_samples {
+            return Err(format!(
+                "{name} has {} samples (limit {})",
+                stat.samples_ms.len(),
+                limits.max_samples
+            ));
+        }
+        let values = [stat.mean_ms, stat.min_ms, stat.max_ms]
+            .into_iter()
+            .chain(stat.median_ms)
+            .chain(stat.stddev_ms)
+            .chain(stat.p95_ms)
+            .chain(stat.samples_ms.iter().copied());
+        for value in values {
+            check_non_negative(name, value)?;
+        }
+    }
+    for (name, value) in [
+        ("backend_setup_ms", record.backend_setup_ms),
+        ("probe_ms", record.probe_ms),
+        ("verify_cold_ms", record.verify_cold_ms),
+        ("peak_rss_mb", record.peak_rss_mb),
+    ] {
+        if let Some(value) = value {
+            check_non_negative(name, value)?;
+        }
+    }
+    for (phase, &value) in record.backend_phases.iter().flatten() {
+        check_non_negative(&format!("backend_phases.{phase}"), value)?;
+    }
+    if let Some(split) = &record.exec_split {
+        for value in [split.acir_ms, split.brillig_ms, split.foreign_call_ms]
+            .into_iter()
+            .chain(split.resolver_ms)
+        {
+            check_non_negative("exec_split", value)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_non_negative(name: &str, value: f64) -> Result<(), String> {
+    if !value.is_finite() {
+        Err(format!("{name} is not a finite number"))
+    } else if value < 0.0 {
+        Err(format!("{name} is negative ({value})"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::env::EnvironmentInfo;
+    use crate::core::schema::{BackendInfo, RunConfig};
+
+    fn record_line(mean_ms: f64) -> String {
+        let mut record = BenchRecord::new(
+            "c".to_string(),
+            EnvironmentInfo::default(),
+            BackendInfo {
+                name: "mock".to_string(),
+                version: None,
+                variant: None,
+            },
+            RunConfig::default(),
+        );
+        record.prove_stats = Some(TimingStat::from_samples(&[mean_ms]));
+        serde_json::to_string(&record).unwrap()
+    }
+
+    #[test]
+    fn test_bad_lines_become_warnings() {
+        let input = format!(
+            "{}\n\nnot json\n{}\n{}\n{}\n",
+            record_line(10.0),
+            record_line(-5.0),
+            "[".repeat(100) + &"]".repeat(100),
+            record_line(20.0),
+        );
+        let out = read_lines_untrusted(input.as_bytes(), &ParseLimits::default()).unwrap();
+        assert_eq!(out.records.len(), 2);
+        assert_eq!(out.warnings.len(), 3);
+        assert!(out.warnings[0].starts_with("line 3: skipped, invalid JSON"));
+        assert!(out.warnings[1].contains("prove_stats is negative"));
+        assert!(out.warnings[2].contains("nested deeper than 32"));
+    }
+
+    #[test]
+    fn test_oversized_line_is_skipped_without_buffering_it() {
+        let limits = ParseLimits {
+            max_line_bytes: 64,
+            ..ParseLimits::default()
+        };
+        let input = format!("{}\n{}\n", "x".repeat(10_000), "{}");
+        let out = read_lines_untrusted(input.as_bytes(), &limits).unwrap();
+        assert_eq!(out.warnings[0], "line 1: skipped, longer than 64 bytes");
+        // The next line is still read
+        assert!(out.warnings[1].starts_with("line 2: "));
+    }
+
+    #[test]
+    fn test_nesting_depth_ignores_strings() {
+        assert_eq!(nesting_depth(br#"{"a":[1,{"b":"[[[{{{"}]}"#), 3);
+        assert_eq!(nesting_depth(br#"{"a":"\"[[["}"#), 1);
+        assert_eq!(nesting_depth(b"]]]"), 0);
+    }
+}
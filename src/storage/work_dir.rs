@@ -0,0 +1,206 @@
+//! Per-run working directories for intermediate files.
+//!
+//! Witnesses and bb outputs (keys, proofs) of a run are written under the
+//! run's output directory instead of the system temp dir, one directory per
+//! circuit:
+//!
+//! ```text
+//! <output>/work/<run id>/<circuit>[-p<params>]/
+//! ```
+//!
+//! The run id holds the start time and process id, so concurrent runs never
+//! share files. A circuit's directory is removed when its guard is dropped,
+//! unless the [`Retention`] policy keeps it; the run directory goes once it
+//! is empty.
+
+use std::path::{Path, PathBuf};
+
+use crate::{BenchError, BenchResult};
+
+/// Directory under the output directory that run directories are created in.
+pub const WORK_DIR_NAME: &str = "work";
+
+/// Which circuit work directories survive the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Retention {
+    /// Keep the files of circuits whose run failed
+    #[default]
+    KeepOnFailure,
+    Always,
+    Never,
+}
+
+impl Retention {
+    pub fn parse(s: &str) -> BenchResult<Self> {
+        match s {
+            "keep-on-failure" | "on-failure" => Ok(Retention::KeepOnFailure),
+            "always" => Ok(Retention::Always),
+            "never" => Ok(Retention::Never),
+            other => Err(BenchError::Message(format!(
+                "unknown retention '{other}' (expected keep-on-failure, always or never)"
+            ))),
+        }
+    }
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The working directory of one run.
+#[derive(Debug)]
+pub struct RunWorkDir {
+    path: PathBuf,
+    retention: Retention,
+}
+
+impl RunWorkDir {
+    /// Create `<root>/<run id>`.
+    pub fn create(root: impl AsRef<Path>, retention: Retention) -> BenchResult<Self> {
+        let started = time::OffsetDateTime::now_utc();
+        let id = format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}-{}",
+            started.year(),
+            u8::from(started.month()),
+            started.day(),
+            started.hour(),
+            started.minute(),
+            started.second(),
+            std::process::id()
+        );
+        let path = root.as_ref().join(id);
+        std::fs::create_dir_all(&path).map_err(|e| {
+            BenchError::Message(format!("failed to create {}: {e}", path.display()))
+        })?;
+        Ok(RunWorkDir { path, retention })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Create the directory of `circuit` (with `params`, if any).
+    pub fn circuit(&self, circuit: &str, params: Option<u64>) -> BenchResult<CircuitWorkDir> {
+        let name = match params {
+            Some(p) => format!("{}-p{p}", sanitize(circuit)),
+            None => sanitize(circuit),
+        };
+        let path = self.path.join(name);
+        // A stale directory of the same circuit in this run is replaced
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).map_err(|e| {
+            BenchError::Message(format!("failed to create {}: {e}", path.display()))
+        })?;
+        Ok(CircuitWorkDir {
+            path,
+            retention: self.retention,
+            succeeded: false,
+        })
+    }
+}
+
+impl Drop for RunWorkDir {
+    fn drop(&mut self) {
+        // Only succeeds when no circuit directory was kept
+        if std::fs::remove_dir(&self.path).is_ok() {
+            if let Some(root) = self.path.parent() {
+                let _ = std::fs::remove_dir(root);
+            }
+        }
+    }
+}
+
+/// The working directory of one circuit, cleaned up on drop.
+#[derive(Debug)]
+pub struct CircuitWorkDir {
+    path: PathBuf,
+    retention: Retention,
+    succeeded: bool,
+}
+
+impl CircuitWorkDir {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Mark the circuit's run as successful; without this (an error was
+    /// returned, or verification failed) the directory counts as failed.
+    pub fn succeed(&mut self) {
+        self.succeeded = true;
+    }
+
+    fn keep(&self) -> bool {
+        match self.retention {
+            Retention::Always => true,
+            Retention::Never => false,
+            Retention::KeepOnFailure => !self.succeeded,
+        }
+    }
+}
+
+impl Drop for CircuitWorkDir {
+    fn drop(&mut self) {
+        if !self.keep() {
+            let _ = std::fs::remove_dir_all(&self.path);
+        } else if !self.succeeded {
+            eprintln!(
+                "note: kept work files of failed run in {}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_circuits(root: &Path, retention: Retention) -> (PathBuf, PathBuf, PathBuf) {
+        let run = RunWorkDir::create(root, retention).unwrap();
+        let run_path = run.path().to_path_buf();
+        let mut ok = run.circuit("merkle", Some(16)).unwrap();
+        let failed = run.circuit("ecdsa/verify", None).unwrap();
+        std::fs::write(ok.path().join("witness.gz"), b"w").unwrap();
+        std::fs::write(failed.path().join("witness.gz"), b"w").unwrap();
+        let (ok_path, failed_path) = (ok.path().to_path_buf(), failed.path().to_path_buf());
+        ok.succeed();
+        (run_path, ok_path, failed_path)
+    }
+
+    #[test]
+    fn test_retention_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join(WORK_DIR_NAME);
+
+        let (run, ok, failed) = run_circuits(&root, Retention::KeepOnFailure);
+        assert_eq!(ok.file_name().unwrap(), "merkle-p16");
+        assert_eq!(failed.file_name().unwrap(), "ecdsa_verify");
+        assert!(!ok.exists());
+        assert!(failed.join("witness.gz").exists());
+        assert!(run.exists());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let (_, ok, failed) = run_circuits(&root, Retention::Always);
+        assert!(ok.exists() && failed.exists());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        // Nothing kept: the run and work directories go too
+        let (run, _, _) = run_circuits(&root, Retention::Never);
+        assert!(!run.exists());
+        assert!(!root.exists());
+
+        assert_eq!(
+            Retention::parse("keep-on-failure").unwrap(),
+            Retention::KeepOnFailure
+        );
+        assert!(Retention::parse("sometimes").is_err());
+    }
+}
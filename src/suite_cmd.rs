@@ -1,10 +1,14 @@
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
 
+use crate::engine::{EtaEstimator, Progress};
+use crate::report::{SuiteReport, SuiteTask, write_suite_html};
+use crate::storage::{
+    DEFAULT_GATE_CACHE, DEFAULT_KEY_CACHE, ParseLimits, RecordSink, TRIAGE_DIR_NAME, TriageBundle,
+    open_sink, read_untrusted,
+};
 use crate::{BenchError, BenchResult};
 
 #[derive(Debug, Deserialize)]
@@ -14,59 +18,173 @@ struct SuiteConfig {
     backend: Option<String>,
     backend_path: Option<PathBuf>,
     template: Option<String>,
+    /// Metrics scraped from the template backend's output (see `prove
+    /// --custom-metric`)
+    custom_metrics: Option<Vec<String>>,
     backend_args: Option<Vec<String>>,
     iterations: Option<usize>,
     warmup: Option<usize>,
+    /// Gate result cache directory (default `.noir-bench/gates`)
+    gate_cache: Option<PathBuf>,
+    /// vk cache directory for the barretenberg backend (default
+    /// `.noir-bench/keys`)
+    key_cache: Option<PathBuf>,
+    /// The circuits' inputs are meant to fail: prove tasks measure the time
+    /// to failure and fail if proving succeeds (see `prove --expect-failure`)
+    expect_failure: Option<bool>,
+}
+
+/// Send a result to every sink as soon as it is produced. A sink that fails
+/// is reported but doesn't stop the suite.
+fn stream(sinks: &mut [Box<dyn RecordSink>], record: &JsonValue) {
+    for sink in sinks.iter_mut() {
+        if let Err(e) = sink.send(record) {
+            eprintln!(
+                "warning: failed to stream record to {}: {e}",
+                sink.describe()
+            );
+        }
+    }
+}
+
+/// Collect a triage bundle for `task` failing on `artifact` into `dir`, and
+/// pass the error on.
+fn triage(
+    err: BenchError,
+    dir: &Path,
+    task: &str,
+    artifact: &Path,
+    prover_toml: Option<&Path>,
+    cfg: &SuiteConfig,
+) -> BenchError {
+    let circuit = circuit_name(artifact);
+    let record = serde_json::json!({
+        "circuit_name": circuit,
+        "task": task,
+        "backend": cfg.backend.as_deref().unwrap_or("barretenberg"),
+        "error": crate::core::RunError::from_error(&err),
+    });
+    let mut bundle = TriageBundle::new(&circuit, None, &err.to_string())
+        .with_artifact(artifact)
+        .with_backend_args(cfg.backend_args.as_deref().unwrap_or_default())
+        .with_record(&record);
+    if let Some(prover_toml) = prover_toml {
+        bundle = bundle.with_prover_toml(prover_toml);
+    }
+    if let Some(log) = err.log_path() {
+        bundle = bundle.with_log(log);
+    }
+    match bundle.write(dir) {
+        Ok(path) => eprintln!("triage bundle for failed {task}: {}", path.display()),
+        Err(e) => eprintln!("warning: failed to write triage bundle: {e}"),
+    }
+    err
+}
+
+/// Name a suite circuit is reported under: its artifact's file stem.
+fn circuit_name(artifact: &Path) -> String {
+    artifact
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "circuit".to_string())
+}
+
+/// Read the JSON report a task wrote to `path`.
+/// A task's JSON report; one that can't be read counts as a failed task.
+fn read_task_output(path: &Path) -> BenchResult<JsonValue> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| BenchError::Message(format!("failed to read task output: {e}")))?;
+    serde_json::from_slice::<JsonValue>(&bytes)
+        .map_err(|e| BenchError::Message(format!("task output is not valid JSON: {e}")))
+}
+
+/// Earlier runs to estimate the suite's ETA from; unreadable history only
+/// costs the ETA.
+fn read_history(path: &Path) -> EtaEstimator {
+    match read_untrusted(path, &ParseLimits::default()) {
+        Ok(read) => {
+            read.report_warnings(path);
+            EtaEstimator::from_history(&read.records)
+        }
+        Err(e) => {
+            eprintln!("warning: no ETA, failed to read history: {e}");
+            EtaEstimator::default()
+        }
+    }
 }
 
 pub fn run(
     config_path: PathBuf,
-    jsonl_out: Option<PathBuf>,
+    jsonl_out: Vec<String>,
     summary_out: Option<PathBuf>,
+    html_out: Option<PathBuf>,
+    history: Option<PathBuf>,
+    progress_out: Option<PathBuf>,
 ) -> BenchResult<()> {
     let bytes = std::fs::read(&config_path).map_err(|e| BenchError::Message(e.to_string()))?;
     let cfg: SuiteConfig =
         serde_yaml::from_slice(&bytes).map_err(|e| BenchError::Message(e.to_string()))?;
 
-    let mut jsonl: Option<File> = match jsonl_out {
-        Some(p) => {
-            if let Some(dir) = p.parent() {
-                std::fs::create_dir_all(dir).ok();
-            }
-            Some(File::create(&p).map_err(|e| BenchError::Message(e.to_string()))?)
-        }
-        None => None,
-    };
+    let mut sinks = jsonl_out
+        .iter()
+        .map(|spec| open_sink(spec))
+        .collect::<BenchResult<Vec<_>>>()?;
 
     let mut results: Vec<JsonValue> = Vec::new();
+    let mut tasks: Vec<SuiteTask> = Vec::new();
+    // Triage bundles of failed tasks go next to the summary
+    let triage_dir = summary_out
+        .as_deref()
+        .and_then(Path::parent)
+        .unwrap_or_else(|| Path::new("."))
+        .join(TRIAGE_DIR_NAME);
+    let key_cache = (cfg.backend.as_deref().unwrap_or("barretenberg") == "barretenberg"
+        && cfg.template.is_none())
+    .then(|| {
+        cfg.key_cache
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_KEY_CACHE))
+    });
+
+    // Suite circuits are named by artifact and have no params
+    let mut progress = Progress::new(
+        "suite",
+        cfg.circuits
+            .iter()
+            .map(|a| (circuit_name(a), None))
+            .collect(),
+        history.as_deref().map(read_history).unwrap_or_default(),
+    );
+    if let Some(path) = progress_out {
+        progress = progress.with_events(path);
+    }
+    progress.start();
 
     for artifact in cfg.circuits.iter() {
         for task in cfg.tasks.iter() {
-            match task.as_str() {
-                "gates" => {
-                    let tmp = tempfile::NamedTempFile::new()
-                        .map_err(|e| BenchError::Message(e.to_string()))?;
-                    crate::gates_cmd::run(
-                        artifact.clone(),
-                        cfg.backend.clone(),
-                        cfg.backend_path.clone(),
-                        cfg.backend_args.clone().unwrap_or_default(),
-                        cfg.template.clone(),
-                        Some(tmp.path().to_path_buf()),
-                    )?;
-                    let bytes = std::fs::read(tmp.path()).unwrap_or_default();
-                    if let Ok(v) = serde_json::from_slice::<JsonValue>(&bytes) {
-                        results.push(v.clone());
-                        if let Some(f) = jsonl.as_mut() {
-                            let compact = serde_json::to_vec(&v).unwrap_or_default();
-                            let _ = f.write_all(&compact);
-                            let _ = f.write_all(b"\n");
-                        }
-                    }
-                }
+            let outcome = match task.as_str() {
+                "gates" => tempfile::NamedTempFile::new()
+                    .map_err(|e| BenchError::Message(e.to_string()))
+                    .and_then(|tmp| {
+                        crate::gates_cmd::run(
+                            artifact.clone(),
+                            cfg.backend.clone(),
+                            cfg.backend_path.clone(),
+                            cfg.backend_args.clone().unwrap_or_default(),
+                            cfg.template.clone(),
+                            Some(tmp.path().to_path_buf()),
+                            1,
+                            Some(
+                                cfg.gate_cache
+                                    .clone()
+                                    .unwrap_or_else(|| PathBuf::from(DEFAULT_GATE_CACHE)),
+                            ),
+                            None,
+                        )
+                        .map_err(|e| triage(e, &triage_dir, task, artifact, None, &cfg))?;
+                        read_task_output(tmp.path())
+                    }),
                 "prove" => {
-                    let tmp = tempfile::NamedTempFile::new()
-                        .map_err(|e| BenchError::Message(e.to_string()))?;
                     // try to locate Prover.toml either alongside the artifact or in the parent of target/
                     let mut prover_path: Option<PathBuf> = None;
                     if let Some(dir) = artifact.parent() {
@@ -83,46 +201,102 @@ pub fn run(
                             }
                         }
                     }
-                    crate::prove_cmd::run(
-                        artifact.clone(),
-                        prover_path,
-                        cfg.backend.clone(),
-                        cfg.backend_path.clone(),
-                        cfg.backend_args.clone().unwrap_or_default(),
-                        cfg.template.clone(),
-                        0,
-                        cfg.iterations,
-                        cfg.warmup,
-                        Some(tmp.path().to_path_buf()),
-                    )?;
-                    let bytes = std::fs::read(tmp.path()).unwrap_or_default();
-                    if let Ok(v) = serde_json::from_slice::<JsonValue>(&bytes) {
-                        results.push(v.clone());
-                        if let Some(f) = jsonl.as_mut() {
-                            let compact = serde_json::to_vec(&v).unwrap_or_default();
-                            let _ = f.write_all(&compact);
-                            let _ = f.write_all(b"\n");
-                        }
-                    }
+                    tempfile::NamedTempFile::new()
+                        .map_err(|e| BenchError::Message(e.to_string()))
+                        .and_then(|tmp| {
+                            crate::prove_cmd::run(
+                                artifact.clone(),
+                                prover_path.clone(),
+                                cfg.backend.clone(),
+                                cfg.backend_path.clone(),
+                                cfg.backend_args.clone().unwrap_or_default(),
+                                cfg.template.clone(),
+                                cfg.custom_metrics.clone().unwrap_or_default(),
+                                0,
+                                cfg.iterations,
+                                cfg.warmup,
+                                Some(tmp.path().to_path_buf()),
+                                None,
+                                false,
+                                false,
+                                key_cache.clone(),
+                                false,
+                                cfg.expect_failure.unwrap_or(false),
+                            )
+                            .map_err(|e| {
+                                triage(e, &triage_dir, task, artifact, prover_path.as_deref(), &cfg)
+                            })?;
+                            read_task_output(tmp.path())
+                        })
                 }
                 "verify" => {
                     // skip: needs proof path
+                    continue;
                 }
                 "exec" => {
                     // skip: needs Prover.toml
+                    continue;
+                }
+                _ => continue,
+            };
+
+            // A failed task is reported and the suite moves on
+            let circuit = circuit_name(artifact);
+            match outcome {
+                Ok(v) => {
+                    results.push(v.clone());
+                    stream(&mut sinks, &v);
+                    tasks.push(SuiteTask {
+                        circuit,
+                        task: task.clone(),
+                        result: Some(v),
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    eprintln!("suite: {task} failed for {circuit}: {e}");
+                    tasks.push(SuiteTask {
+                        circuit,
+                        task: task.clone(),
+                        result: None,
+                        error: Some(crate::core::RunError::from_error(&e).message),
+                    });
                 }
-                _ => {}
             }
         }
-        // done per artifact
+        progress.circuit_done(&circuit_name(artifact), None);
     }
+    progress.finish();
 
+    let report = SuiteReport {
+        config: config_path.display().to_string(),
+        generated_at: time::OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default(),
+        tasks,
+    };
     if let Some(p) = summary_out {
         if let Some(dir) = p.parent() {
             std::fs::create_dir_all(dir).ok();
         }
-        let summary = serde_json::json!({ "results": results });
+        let mut summary = serde_json::json!({ "results": results });
+        let failures: Vec<&SuiteTask> = report.tasks.iter().filter(|t| t.failed()).collect();
+        if !failures.is_empty() {
+            summary["failures"] = serde_json::json!(failures);
+        }
         std::fs::write(&p, serde_json::to_vec_pretty(&summary).unwrap_or_default()).ok();
     }
-    Ok(())
+    if let Some(p) = html_out {
+        write_suite_html(&p, &report)
+            .map_err(|e| BenchError::Message(format!("failed to write suite report: {e}")))?;
+        eprintln!("Wrote suite report to {}", p.display());
+    }
+
+    match report.failures() {
+        0 => Ok(()),
+        n => Err(BenchError::Message(format!(
+            "{n} of {} suite task(s) failed",
+            report.tasks.len()
+        ))),
+    }
 }
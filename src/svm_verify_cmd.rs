@@ -0,0 +1,220 @@
+//! CLI command handler for `svm-verify`, the Solana counterpart of
+//! `evm-verify`.
+//!
+//! Runs the verifier program's `solana-program-test` tests (`cargo test-sbf`)
+//! in its project, or any command given as a template, and reads the compute
+//! units from the runtime's log lines:
+//!
+//! ```text
+//! Program <id> consumed <n> of <limit> compute units
+//! ```
+//!
+//! The largest invocation (of `--program-id`, when given) is reported: the
+//! top-level call includes the compute units of its CPIs. Tests must run with
+//! logging enabled (`solana_logger::setup_with_default("solana_runtime=debug")`
+//! or `RUST_LOG`, which is set for the default command).
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::{
+    BackendInfo, BenchError, BenchResult, CommonMeta, SvmVerifyReport, SystemInfo,
+    collect_system_info, verifier_template,
+};
+
+/// One `Program <id> consumed <n> of <limit> compute units` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComputeUsage {
+    pub program_id: String,
+    pub consumed: u64,
+    pub limit: u64,
+}
+
+/// Every compute unit line in `output`.
+pub fn parse_compute_units(output: &str) -> Vec<ComputeUsage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = &line[line.find("Program ")? + "Program ".len()..];
+            let mut words = rest.split_whitespace();
+            let program_id = words.next()?.to_string();
+            if words.next()? != "consumed" {
+                return None;
+            }
+            let consumed = words.next()?.parse().ok()?;
+            if words.next()? != "of" {
+                return None;
+            }
+            let limit = words.next()?.parse().ok()?;
+            (words.next()? == "compute").then_some(ComputeUsage {
+                program_id,
+                consumed,
+                limit,
+            })
+        })
+        .collect()
+}
+
+/// The largest invocation, of `program_id` if given.
+pub fn verifier_usage<'a>(
+    usages: &'a [ComputeUsage],
+    program_id: Option<&str>,
+) -> Option<&'a ComputeUsage> {
+    usages
+        .iter()
+        .filter(|u| program_id.is_none_or(|id| u.program_id == id))
+        .max_by_key(|u| u.consumed)
+}
+
+/// The command to run: the template with `{project}` and `{match}` filled
+/// in, or `cargo test-sbf [match]` with runtime logging on.
+fn build_command(
+    template: Option<&str>,
+    project_dir: &Path,
+    test_pattern: &Option<String>,
+) -> BenchResult<Command> {
+    let mut default = vec!["cargo".to_string(), "test-sbf".to_string()];
+    default.extend(test_pattern.clone());
+    default.extend(["--".to_string(), "--nocapture".to_string()]);
+    let mut cmd =
+        verifier_template::build_command(template, project_dir, test_pattern.as_deref(), default)?;
+    if template.is_none() {
+        cmd.env("RUST_LOG", "solana_runtime::message_processor=debug");
+    }
+    Ok(cmd)
+}
+
+fn backend_info(template: Option<&str>) -> BackendInfo {
+    let Some(template) = template else {
+        let version = Command::new("cargo")
+            .args(["build-sbf", "--version"])
+            .output()
+            .ok()
+            .and_then(|o| String::from_utf8(o.stdout).ok())
+            .and_then(|s| s.lines().next().map(|l| l.trim().to_string()))
+            .filter(|s| !s.is_empty());
+        return BackendInfo {
+            name: "solana-program-test".into(),
+            version,
+        };
+    };
+    BackendInfo {
+        name: verifier_template::template_program(template),
+        version: None,
+    }
+}
+
+pub fn run(
+    project_dir: PathBuf,
+    test_pattern: Option<String>,
+    program_id: Option<String>,
+    command_template: Option<String>,
+    json_out: Option<PathBuf>,
+) -> BenchResult<()> {
+    let mut cmd = build_command(command_template.as_deref(), &project_dir, &test_pattern)?;
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let output = cmd
+        .output()
+        .map_err(|e| BenchError::Message(format!("failed to run svm verifier: {e}")))?;
+    let stderr_s = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        return Err(BenchError::Message(format!(
+            "svm verifier failed. stderr=\n{stderr_s}"
+        )));
+    }
+    // Runtime logs go to stderr, test prints to stdout
+    let combined = format!("{}\n{stderr_s}", String::from_utf8_lossy(&output.stdout));
+    let usages = parse_compute_units(&combined);
+    let usage = verifier_usage(&usages, program_id.as_deref()).ok_or_else(|| {
+        BenchError::Message(match &program_id {
+            Some(id) => format!("no compute unit log line for program {id}"),
+            None => "no compute unit log lines in the verifier output (is runtime logging on?)"
+                .to_string(),
+        })
+    })?;
+
+    let meta = CommonMeta {
+        name: "svm-verify".into(),
+        timestamp: time::OffsetDateTime::now_utc()
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default(),
+        noir_version: "n/a".into(),
+        artifact_path: project_dir.clone(),
+        cli_args: std::env::args().collect(),
+        artifact_sha256: None,
+        inputs_sha256: None,
+    };
+    let system: Option<SystemInfo> = Some(collect_system_info());
+    let report = SvmVerifyReport {
+        meta,
+        compute_units: usage.consumed,
+        compute_unit_limit: Some(usage.limit),
+        program_id: Some(usage.program_id.clone()),
+        backend: backend_info(command_template.as_deref()),
+        system,
+    };
+
+    if let Some(json) = json_out {
+        if let Some(dir) = json.parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        std::fs::write(&json, serde_json::to_vec_pretty(&report).unwrap()).ok();
+    }
+    println!(
+        "svm-verify: compute_units={} limit={:?} program={}",
+        report.compute_units, report.compute_unit_limit, usage.program_id
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+[2026-01-01T00:00:00Z DEBUG solana_runtime::message_processor::stable_log] Program Verif1er111 invoke [1]
+[2026-01-01T00:00:00Z DEBUG solana_runtime::message_processor::stable_log] Program ComputeBudget111111111111111111111111111111 consumed 150 of 1400000 compute units
+[2026-01-01T00:00:00Z DEBUG solana_runtime::message_processor::stable_log] Program Hash111 consumed 2000 of 1200000 compute units
+[2026-01-01T00:00:00Z DEBUG solana_runtime::message_processor::stable_log] Program Verif1er111 consumed 812345 of 1399850 compute units
+[2026-01-01T00:00:00Z DEBUG solana_runtime::message_processor::stable_log] Program Verif1er111 success
+";
+
+    #[test]
+    fn test_parse_compute_units() {
+        let usages = parse_compute_units(LOG);
+        assert_eq!(usages.len(), 3);
+        assert_eq!(
+            usages[2],
+            ComputeUsage {
+                program_id: "Verif1er111".to_string(),
+                consumed: 812_345,
+                limit: 1_399_850,
+            }
+        );
+    }
+
+    #[test]
+    fn test_verifier_usage_picks_largest_invocation() {
+        let usages = parse_compute_units(LOG);
+        assert_eq!(verifier_usage(&usages, None).unwrap().consumed, 812_345);
+        assert_eq!(
+            verifier_usage(&usages, Some("Hash111")).unwrap().consumed,
+            2000
+        );
+        assert!(verifier_usage(&usages, Some("Other")).is_none());
+    }
+
+    #[test]
+    fn test_default_command_enables_runtime_logs() {
+        let cmd = build_command(None, Path::new("src"), &Some("test_verify".to_string())).unwrap();
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(cmd.get_program(), "cargo");
+        assert_eq!(args, ["test-sbf", "test_verify", "--", "--nocapture"]);
+        assert!(cmd.get_envs().any(|(k, _)| k == "RUST_LOG"));
+    }
+}
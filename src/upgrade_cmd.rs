@@ -0,0 +1,169 @@
+//! CLI command handler for `upgrade`.
+//!
+//! Rewrites a JSONL archive so every record uses the current BenchRecord schema.
+
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::core::schema::{RecordShape, SCHEMA_VERSION, detect_shape, parse_record};
+use crate::storage::JsonlWriter;
+use crate::storage::jsonl::{Compression, open_lines};
+use crate::{BenchError, BenchResult};
+
+/// Per-shape counts from an upgrade run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpgradeSummary {
+    /// Number of records keyed by source shape label ("legacy", "v1", "v2", ...)
+    pub by_source: BTreeMap<String, usize>,
+    pub total: usize,
+}
+
+fn shape_label(shape: Option<RecordShape>) -> String {
+    match shape {
+        Some(RecordShape::LegacyBench) => "legacy".to_string(),
+        Some(RecordShape::LegacyReport(kind)) => format!("legacy-{}", kind.as_str()),
+        Some(RecordShape::Versioned(v)) => format!("v{v}"),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Upgrade every record in `in_path` and write them to `out_path`.
+///
+/// The whole input is parsed before anything is written, so a bad line
+/// leaves the output untouched and `in_path == out_path` is safe. Records
+/// go to a temporary file next to `out_path` that is then renamed over it,
+/// so an interrupted upgrade never leaves a half-written archive.
+pub fn upgrade_file(in_path: &PathBuf, out_path: &PathBuf) -> BenchResult<UpgradeSummary> {
+    let reader = open_lines(in_path)
+        .map_err(|e| BenchError::Message(format!("failed to open {}: {e}", in_path.display())))?;
+
+    let mut summary = UpgradeSummary::default();
+    let mut records = Vec::new();
+    for (line_num, line_result) in reader.lines().enumerate() {
+        let line = line_result.map_err(|e| {
+            BenchError::Message(format!("failed to read line {}: {e}", line_num + 1))
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let shape = serde_json::from_str::<Value>(&line)
+            .ok()
+            .and_then(|v| detect_shape(&v));
+        let record = parse_record(&line).map_err(|e| {
+            BenchError::Message(format!("failed to upgrade line {}: {e}", line_num + 1))
+        })?;
+
+        *summary.by_source.entry(shape_label(shape)).or_insert(0) += 1;
+        summary.total += 1;
+        records.push(record);
+    }
+
+    let dir = match out_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    std::fs::create_dir_all(dir)
+        .map_err(|e| BenchError::Message(format!("failed to create {}: {e}", dir.display())))?;
+    // Keep the extension so the temporary file gets the same compression
+    let tmp = tempfile::Builder::new()
+        .prefix(".upgrade-")
+        .suffix(Compression::from_path(out_path).extension())
+        .tempfile_in(dir)
+        .map_err(|e| BenchError::Message(format!("failed to create temp file: {e}")))?
+        .into_temp_path();
+    let writer = JsonlWriter::new(&tmp);
+    for record in &records {
+        writer.append(record)?;
+    }
+    tmp.persist(out_path).map_err(|e| {
+        BenchError::Message(format!("failed to replace {}: {e}", out_path.display()))
+    })?;
+
+    Ok(summary)
+}
+
+/// Run the `upgrade` command.
+pub fn run(in_path: PathBuf, out_path: PathBuf) -> BenchResult<()> {
+    if !in_path.exists() {
+        return Err(BenchError::Message(format!(
+            "JSONL file not found: {}",
+            in_path.display()
+        )));
+    }
+
+    let summary = upgrade_file(&in_path, &out_path)?;
+    for (source, count) in &summary.by_source {
+        eprintln!("  {source}: {count} record(s)");
+    }
+    eprintln!(
+        "Upgraded {} record(s) to schema v{} in {}",
+        summary.total,
+        SCHEMA_VERSION,
+        out_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_LINE: &str = r#"{"schema_version":1,"record_id":"r1","timestamp":"2026-01-01T00:00:00Z","circuit_name":"a","env":{"os":"linux"},"backend":{"name":"bb"},"config":{"warmup_iterations":1,"measured_iterations":3},"total_gates":10}"#;
+    const LEGACY_LINE: &str = r#"{"timestamp":"2026-01-01T00:00:00Z","circuit":"b","params":8,"backend":"barretenberg","prove_ms":12.5,"status":true}"#;
+
+    #[test]
+    fn test_upgrade_file_mixed_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_path = dir.path().join("old.jsonl");
+        let out_path = dir.path().join("new.jsonl");
+        std::fs::write(&in_path, format!("{V1_LINE}\n\n{LEGACY_LINE}\n")).unwrap();
+
+        let summary = upgrade_file(&in_path, &out_path).unwrap();
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.by_source.get("v1"), Some(&1));
+        assert_eq!(summary.by_source.get("legacy"), Some(&1));
+
+        // Output is plain current-version JSON, no migration needed on re-read
+        let contents = std::fs::read_to_string(&out_path).unwrap();
+        for line in contents.lines() {
+            let v: Value = serde_json::from_str(line).unwrap();
+            assert_eq!(v["schema_version"], SCHEMA_VERSION);
+        }
+        let records = JsonlWriter::new(&out_path).read_all().unwrap();
+        assert_eq!(records[1].params, Some(8));
+    }
+
+    #[test]
+    fn test_upgrade_file_is_idempotent_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.jsonl");
+        std::fs::write(&path, format!("{V1_LINE}\n{LEGACY_LINE}\n")).unwrap();
+
+        upgrade_file(&path, &path).unwrap();
+        let first = std::fs::read_to_string(&path).unwrap();
+        let summary = upgrade_file(&path, &path).unwrap();
+        let second = std::fs::read_to_string(&path).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(
+            summary.by_source.get(&format!("v{SCHEMA_VERSION}")),
+            Some(&2)
+        );
+    }
+
+    #[test]
+    fn test_upgrade_file_bad_line_leaves_output_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_path = dir.path().join("bad.jsonl");
+        let out_path = dir.path().join("out.jsonl");
+        std::fs::write(&in_path, format!("{V1_LINE}\nnot json\n")).unwrap();
+        std::fs::write(&out_path, "keep\n").unwrap();
+
+        assert!(upgrade_file(&in_path, &out_path).is_err());
+        assert_eq!(std::fs::read_to_string(&out_path).unwrap(), "keep\n");
+    }
+}
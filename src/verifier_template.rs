@@ -0,0 +1,107 @@
+//! Verifier commands shared by `starknet-verify` and `svm-verify`.
+//!
+//! Both run a chain's test tool in the verifier project, or a command
+//! template with `{project}` and `{match}` placeholders instead. The command
+//! runs in the project directory, and `{project}` is its absolute path, so
+//! a relative `--project-dir` isn't resolved a second time against itself.
+
+use std::path::Path;
+use std::process::Command;
+
+use shlex::Shlex;
+
+use crate::{BenchError, BenchResult};
+
+/// The command to run in `project_dir`: `template` with `{project}` and
+/// `{match}` filled in, or `default` (program and arguments) when there is
+/// no template.
+pub fn build_command(
+    template: Option<&str>,
+    project_dir: &Path,
+    test_pattern: Option<&str>,
+    default: Vec<String>,
+) -> BenchResult<Command> {
+    let project_dir = project_dir.canonicalize().map_err(|e| {
+        BenchError::Message(format!("project directory {}: {e}", project_dir.display()))
+    })?;
+    let mut parts: Vec<String> = match template {
+        Some(template) => {
+            let project_s = project_dir.to_string_lossy();
+            let pattern_s = test_pattern.unwrap_or("");
+            Shlex::new(template)
+                .map(|p| {
+                    p.replace("{project}", &project_s)
+                        .replace("{match}", pattern_s)
+                })
+                .collect()
+        }
+        None => default,
+    };
+    if parts.is_empty() {
+        return Err(BenchError::Message("empty command template".into()));
+    }
+    let mut cmd = Command::new(parts.remove(0));
+    cmd.args(parts);
+    cmd.current_dir(project_dir);
+    Ok(cmd)
+}
+
+/// Backend name of a command template: its program.
+pub fn template_program(template: &str) -> String {
+    Shlex::new(template)
+        .next()
+        .unwrap_or_else(|| "custom".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_build_command_fills_template() {
+        let temp = tempfile::tempdir().unwrap();
+        let project = temp.path().join("cairo");
+        std::fs::create_dir(&project).unwrap();
+        let cmd = build_command(
+            Some("./devnet-verify.sh --project {project} --test '{match}'"),
+            &project,
+            Some("test_verify"),
+            Vec::new(),
+        )
+        .unwrap();
+        let project = project.canonicalize().unwrap();
+        assert_eq!(cmd.get_program(), "./devnet-verify.sh");
+        assert_eq!(
+            args(&cmd),
+            [
+                "--project",
+                &project.to_string_lossy(),
+                "--test",
+                "test_verify"
+            ]
+        );
+        assert_eq!(cmd.get_current_dir(), Some(project.as_path()));
+        assert_eq!(
+            template_program("./devnet-verify.sh {project}"),
+            "./devnet-verify.sh"
+        );
+    }
+
+    #[test]
+    fn test_build_command_resolves_relative_project() {
+        // The command runs in the project, so {project} must not be relative
+        let cmd = build_command(Some("run {project}"), Path::new("src"), None, Vec::new()).unwrap();
+        let project = Path::new(&args(&cmd)[0]).to_path_buf();
+        assert!(project.is_absolute());
+        assert_eq!(project, Path::new("src").canonicalize().unwrap());
+
+        assert!(build_command(None, Path::new("missing-project"), None, vec!["x".into()]).is_err());
+        assert!(build_command(Some(""), Path::new("src"), None, Vec::new()).is_err());
+    }
+}
@@ -1,13 +1,21 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, ExitStatus, Stdio};
 use std::time::Instant;
 
-use noir_artifact_cli::fs::artifact::read_program_from_file;
 use shlex::Shlex;
 
+use crate::backend::{BarretenbergBackend, BarretenbergConfig};
+use crate::core::env::EnvironmentInfo;
+use crate::core::schema::{
+    BackendInfo as CoreBackendInfo, BenchRecord, RunConfig, RunStatus, TimingStat,
+};
+use crate::core::signing::load_signing_key;
+use crate::storage::run_logs::command_failure;
+use crate::storage::{ArtifactStore, JsonlWriter};
 use crate::{
-    BackendInfo, BenchError, BenchResult, CommonMeta, VerifyReport, collect_system_info,
-    compute_iteration_stats,
+    BackendInfo, BenchError, BenchResult, CommonMeta, Fingerprints, VerifyReport,
+    collect_system_info, file_sha256,
 };
 
 pub trait VerifyProvider {
@@ -17,16 +25,25 @@ pub trait VerifyProvider {
 
 pub struct BarretenbergVerifyProvider {
     pub backend_path: PathBuf,
+    /// Verification key passed as `-k` when set
+    pub vk_path: Option<PathBuf>,
+    /// Public inputs file passed as `-i` when set
+    pub public_inputs: Option<PathBuf>,
     pub extra_args: Vec<String>,
 }
 
 impl VerifyProvider for BarretenbergVerifyProvider {
     fn verify(&self, artifact: &Path, proof: &Path) -> BenchResult<VerifyReport> {
-        let program =
-            read_program_from_file(artifact).map_err(|e| BenchError::Message(e.to_string()))?;
+        let program = crate::engine::load_program(artifact)?;
         let mut cmd = Command::new(&self.backend_path);
         // Current bb verify does not accept -b; only -p (proof), -i (public inputs), -k (vk) optionally
         cmd.arg("verify").arg("-p").arg(proof);
+        if let Some(vk) = &self.vk_path {
+            cmd.arg("-k").arg(vk);
+        }
+        if let Some(inputs) = &self.public_inputs {
+            cmd.arg("-i").arg(inputs);
+        }
         for a in &self.extra_args {
             cmd.arg(a);
         }
@@ -34,22 +51,30 @@ impl VerifyProvider for BarretenbergVerifyProvider {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
         let start = Instant::now();
-        let status = cmd
-            .status()
-            .map_err(|e| BenchError::Message(e.to_string()))?;
+        let output = cmd
+            .output()
+            .map_err(|e| BenchError::Message(format!("failed to run bb verify: {e}")))?;
         let verify_time_ms = start.elapsed().as_millis();
-        let ok = status.success();
-        let artifact_bytes = std::fs::read(artifact).ok();
+        if verifier_crashed(output.status, Some(BB_REJECT_EXIT_CODE)) {
+            return Err(command_failure(
+                "bb verify",
+                output.status,
+                &output.stdout,
+                &output.stderr,
+                None,
+            ));
+        }
+        let ok = output.status.success();
         let meta = CommonMeta {
             name: "verify".into(),
             timestamp: time::OffsetDateTime::now_utc()
                 .format(&time::format_description::well_known::Rfc3339)
                 .unwrap_or_default(),
-            noir_version: program.noir_version,
+            noir_version: program.noir_version.clone(),
             artifact_path: artifact.to_path_buf(),
             cli_args: std::env::args().collect(),
-            artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-            inputs_sha256: None,
+            artifact_sha256: crate::engine::artifact_sha256(artifact),
+            inputs_sha256: self.public_inputs.as_deref().and_then(file_sha256),
         };
         let report = VerifyReport {
             meta,
@@ -79,6 +104,10 @@ impl VerifyProvider for BarretenbergVerifyProvider {
 
 pub struct GenericVerifyProvider {
     pub command_template: String,
+    /// Substituted for `{vk}` in the template when set
+    pub vk_path: Option<PathBuf>,
+    /// Substituted for `{public_inputs}` in the template when set
+    pub public_inputs: Option<PathBuf>,
     pub extra_args: Vec<String>,
 }
 
@@ -90,10 +119,22 @@ impl GenericVerifyProvider {
         }
         let artifact_s = artifact.to_string_lossy();
         let proof_s = proof.to_string_lossy();
+        let vk_s = self
+            .vk_path
+            .as_ref()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let inputs_s = self
+            .public_inputs
+            .as_ref()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
         for p in &mut parts {
             *p = p
                 .replace("{artifact}", &artifact_s)
-                .replace("{proof}", &proof_s);
+                .replace("{proof}", &proof_s)
+                .replace("{vk}", &vk_s)
+                .replace("{public_inputs}", &inputs_s);
         }
         let mut cmd = Command::new(&parts[0]);
         for p in &parts[1..] {
@@ -108,29 +149,41 @@ impl GenericVerifyProvider {
 
 impl VerifyProvider for GenericVerifyProvider {
     fn verify(&self, artifact: &Path, proof: &Path) -> BenchResult<VerifyReport> {
-        let program =
-            read_program_from_file(artifact).map_err(|e| BenchError::Message(e.to_string()))?;
+        let program = crate::engine::load_program(artifact)?;
         let mut cmd = self.build_command(artifact, proof)?;
         cmd.stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
         let start = Instant::now();
-        let status = cmd
-            .status()
-            .map_err(|e| BenchError::Message(e.to_string()))?;
+        let output = cmd.output().map_err(|e| {
+            BenchError::Message(format!(
+                "failed to run {}: {e}",
+                cmd.get_program().to_string_lossy()
+            ))
+        })?;
         let verify_time_ms = start.elapsed().as_millis();
-        let ok = status.success();
-        let artifact_bytes = std::fs::read(artifact).ok();
+        // A template's reject exit code is unknown; only a signal tells a
+        // crash from a rejection
+        if verifier_crashed(output.status, None) {
+            return Err(command_failure(
+                "generic verify",
+                output.status,
+                &output.stdout,
+                &output.stderr,
+                None,
+            ));
+        }
+        let ok = output.status.success();
         let meta = CommonMeta {
             name: "verify".into(),
             timestamp: time::OffsetDateTime::now_utc()
                 .format(&time::format_description::well_known::Rfc3339)
                 .unwrap_or_default(),
-            noir_version: program.noir_version,
+            noir_version: program.noir_version.clone(),
             artifact_path: artifact.to_path_buf(),
             cli_args: std::env::args().collect(),
-            artifact_sha256: artifact_bytes.as_ref().map(|b| crate::sha256_hex(b)),
-            inputs_sha256: None,
+            artifact_sha256: crate::engine::artifact_sha256(artifact),
+            inputs_sha256: self.public_inputs.as_deref().and_then(file_sha256),
         };
         let report = VerifyReport {
             meta,
@@ -160,22 +213,199 @@ impl VerifyProvider for GenericVerifyProvider {
     }
 }
 
+fn file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|m| m.len())
+}
+
+/// Bytes per field element in a proof.
+const FIELD_BYTES: usize = 32;
+
+/// Copy `proof` to `out` with the low bit of one field element flipped,
+/// returning the offset of the changed byte. Proofs are big-endian 32-byte
+/// field elements, counted here from the end so a length prefix doesn't
+/// shift them; the middle one is changed, past any leading public inputs.
+/// Only its least significant byte changes, so the element stays below the
+/// field modulus and the copy still parses: the verifier has to reject it
+/// on the proof itself.
+pub fn tamper_proof(proof: &Path, out: &Path) -> BenchResult<usize> {
+    let mut bytes = std::fs::read(proof)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", proof.display())))?;
+    if bytes.is_empty() {
+        return Err(BenchError::Message(format!(
+            "proof {} is empty",
+            proof.display()
+        )));
+    }
+    let fields = bytes.len() / FIELD_BYTES;
+    let offset = bytes.len() - 1 - fields / 2 * FIELD_BYTES;
+    bytes[offset] ^= 1;
+    std::fs::write(out, &bytes)
+        .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", out.display())))?;
+    Ok(offset)
+}
+
+/// Exit code of `bb verify` when it rejects a proof.
+const BB_REJECT_EXIT_CODE: i32 = 1;
+
+/// Whether a verifier that exited with `status` gave no verdict on the
+/// proof: it was killed by a signal (a crash, an abort on an uncaught
+/// exception, the OOM killer), or, when its reject exit code is known,
+/// exited with another non-zero code.
+fn verifier_crashed(status: ExitStatus, reject_code: Option<i32>) -> bool {
+    if status.success() {
+        return false;
+    }
+    match (status.code(), reject_code) {
+        (None, _) => true,
+        (Some(code), Some(reject)) => code != reject,
+        (Some(_), None) => false,
+    }
+}
+
+/// Fail early on a missing or empty input file, before any timing.
+fn check_input(path: &Path, what: &str) -> BenchResult<()> {
+    let len = std::fs::metadata(path)
+        .map_err(|e| BenchError::Message(format!("failed to read {}: {e}", path.display())))?
+        .len();
+    if len == 0 {
+        return Err(BenchError::Message(format!(
+            "{what} {} is empty",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Build a BenchRecord from the measured verify iterations.
+///
+/// `last` supplies the artifact/backend metadata; `samples_ms` are the
+/// steady-state verify times (see `steady_samples`) and `cold_ms` the first
+/// verify iteration. That one may read the binary, vk and SRS from disk, but
+/// the OS cache isn't guaranteed cold: an earlier run can leave them cached.
+/// Artifact, proof and vk hashes are recorded in `fingerprints` (the public
+/// inputs as `inputs_hash`) so the record can be tied to the exact inputs it
+/// verified.
+pub fn build_verify_record(
+    last: &VerifyReport,
+    samples_ms: &[f64],
+    cold_ms: Option<f64>,
+    warmup: usize,
+    all_ok: bool,
+    proof: &Path,
+    vk: Option<&Path>,
+    env: EnvironmentInfo,
+) -> BenchRecord {
+    let circuit_name = last
+        .meta
+        .artifact_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "verify".to_string());
+    let backend = CoreBackendInfo {
+        name: last.backend.name.clone(),
+        version: last.backend.version.clone(),
+        variant: None,
+    };
+    let config = RunConfig {
+        warmup_iterations: warmup as u32,
+        measured_iterations: samples_ms.len() as u32,
+        timeout_secs: None,
+        compile_flags: Vec::new(),
+        env: BTreeMap::new(),
+    };
+
+    let mut record = BenchRecord::new(circuit_name, env, backend, config);
+    record.circuit_path = Some(last.meta.artifact_path.to_string_lossy().to_string());
+    record.verify_stats = Some(TimingStat::from_samples(samples_ms));
+    record.verify_cold_ms = cold_ms;
+    record.proof_size_bytes = file_size(proof);
+    record.verification_key_size_bytes = vk.and_then(file_size);
+    record.cli_args = last.meta.cli_args.clone();
+
+    record.fingerprints = Some(Fingerprints {
+        acir_hash: last.meta.artifact_sha256.clone(),
+        inputs_hash: last.meta.inputs_sha256.clone(),
+        proof_hash: file_sha256(proof),
+        vk_hash: vk.and_then(file_sha256),
+        ..Default::default()
+    });
+    record
+        .metadata
+        .insert("verify_ok".to_string(), all_ok.to_string());
+    record
+}
+
+/// Measured verify times without the first iteration when no warmup ran
+/// before it, unless it is the only sample.
+fn steady_samples(samples: &[f64], warmup: usize) -> &[f64] {
+    if warmup == 0 && samples.len() > 1 {
+        &samples[1..]
+    } else {
+        samples
+    }
+}
+
+/// Run verify. With `expect_invalid` the proof is tampered with (or
+/// `tampered_proof` is verified instead) and every run must reject it; the
+/// record then times the rejection path.
 pub fn run(
     artifact: PathBuf,
     proof: PathBuf,
+    vk: Option<PathBuf>,
+    public_inputs: Option<PathBuf>,
     backend: Option<String>,
     backend_path: Option<PathBuf>,
     backend_args: Vec<String>,
     template: Option<String>,
     iterations: Option<usize>,
     warmup: Option<usize>,
+    expect_invalid: bool,
+    tampered_proof: Option<PathBuf>,
     json_out: Option<PathBuf>,
+    jsonl_out: Option<PathBuf>,
+    store: Option<PathBuf>,
+    sign_key: Option<PathBuf>,
 ) -> BenchResult<()> {
     let backend_name = backend.unwrap_or_else(|| "barretenberg".to_string());
-    let iter_n = iterations.unwrap_or(1);
+    let iter_n = iterations.unwrap_or(1).max(1);
     let warmup_n = warmup.unwrap_or(0);
+    // Check the vk up front so a bad path fails before any timing
+    if let Some(vk) = &vk {
+        check_input(vk, "verification key")?;
+    }
+    if let Some(inputs) = &public_inputs {
+        check_input(inputs, "public inputs")?;
+    }
+    // Keeps the tampered copy alive until the runs are over
+    let mut tamper_dir = None;
+    let (proof, tampered) = match (expect_invalid, tampered_proof) {
+        (false, _) => (proof, None),
+        (true, Some(path)) => {
+            check_input(&path, "tampered proof")?;
+            let note = path.display().to_string();
+            (path, Some(note))
+        }
+        (true, None) => {
+            let dir = tempfile::tempdir()
+                .map_err(|e| BenchError::Message(format!("failed to create temp dir: {e}")))?;
+            let path = dir.path().join("proof");
+            let offset = tamper_proof(&proof, &path)?;
+            tamper_dir = Some(dir);
+            (path, Some(format!("byte {offset} flipped")))
+        }
+    };
+    if let ("barretenberg", None, Some(path)) = (
+        backend_name.as_str(),
+        template.as_ref(),
+        backend_path.as_ref(),
+    ) {
+        BarretenbergBackend::new(BarretenbergConfig::new(path).with_args(backend_args.clone()))
+            .warn_on_args();
+    }
     let mut last: Option<VerifyReport> = None;
-    let mut times: Vec<u128> = Vec::new();
+    let mut cold_ms: Option<f64> = None;
+    let mut samples: Vec<f64> = Vec::new();
+    let mut all_ok = true;
     for i in 0..(warmup_n + iter_n) {
         let res = match (backend_name.as_str(), template.as_ref()) {
             ("barretenberg", None) => {
@@ -186,6 +416,8 @@ pub fn run(
                 };
                 let provider = BarretenbergVerifyProvider {
                     backend_path: path,
+                    vk_path: vk.clone(),
+                    public_inputs: public_inputs.clone(),
                     extra_args: backend_args.clone(),
                 };
                 provider.verify(&artifact, &proof)
@@ -193,6 +425,8 @@ pub fn run(
             (_, Some(tpl)) => {
                 let provider = GenericVerifyProvider {
                     command_template: tpl.clone(),
+                    vk_path: vk.clone(),
+                    public_inputs: public_inputs.clone(),
                     extra_args: backend_args.clone(),
                 };
                 provider.verify(&artifact, &proof)
@@ -203,25 +437,255 @@ pub fn run(
                 )));
             }
         }?;
+        if expect_invalid && res.ok {
+            return Err(BenchError::Message(
+                "expected verification to reject the tampered proof (--expect-invalid), but it was accepted"
+                    .into(),
+            ));
+        }
+        if i == 0 {
+            cold_ms = Some(res.verify_time_ms as f64);
+        }
         if i >= warmup_n {
-            times.push(res.verify_time_ms);
+            samples.push(res.verify_time_ms as f64);
+            all_ok &= res.ok;
         }
         last = Some(res);
     }
-    let mut report = last.expect("at least one verify iteration");
-    if iter_n > 1 || warmup_n > 0 {
-        report.iterations = Some(compute_iteration_stats(times, iter_n, warmup_n));
+    let last = last.expect("at least one verify iteration");
+
+    // Without warmup the first measured iteration is `verify_cold_ms`
+    let steady = steady_samples(&samples, warmup_n);
+
+    let env = EnvironmentInfo::detect_with_bb_path(backend_path.as_deref());
+    let mut record = build_verify_record(
+        &last,
+        steady,
+        cold_ms,
+        warmup_n,
+        all_ok,
+        &proof,
+        vk.as_deref(),
+        env,
+    );
+    if let Some(tampered) = tampered {
+        record.status = Some(RunStatus::ExpectedFailure);
+        record
+            .metadata
+            .insert("tampered_proof".to_string(), tampered);
+    }
+
+    if let Some(root) = store {
+        let store = ArtifactStore::new(&root);
+        store.put_proof(&proof)?;
+        if let Some(vk) = &vk {
+            store.put_vk(vk)?;
+        }
     }
 
     if let Some(json) = json_out {
         if let Some(dir) = json.parent() {
             std::fs::create_dir_all(dir).ok();
         }
-        std::fs::write(&json, serde_json::to_vec_pretty(&report).unwrap()).ok();
+        let bytes = serde_json::to_vec_pretty(&record)
+            .map_err(|e| BenchError::Message(format!("failed to serialize record: {e}")))?;
+        std::fs::write(&json, bytes)
+            .map_err(|e| BenchError::Message(format!("failed to write {}: {e}", json.display())))?;
     }
+    if let Some(jsonl) = jsonl_out {
+        let mut writer = JsonlWriter::new(&jsonl);
+        if let Some(key) = &sign_key {
+            writer = writer.with_signing_key(load_signing_key(key)?);
+        }
+        writer.append(&record)?;
+    }
+
+    let stats = record.verify_stats.as_ref().expect("verify_stats is set");
     println!(
-        "verify: backend={} time={}ms ok={}",
-        report.backend.name, report.verify_time_ms, report.ok
+        "verify: backend={} mean={:.1}ms min={:.1}ms max={:.1}ms iterations={} ok={}{}",
+        record.backend.name,
+        stats.mean_ms,
+        stats.min_ms,
+        stats.max_ms,
+        stats.iterations,
+        all_ok,
+        if expect_invalid {
+            " (tampered proof rejected)"
+        } else {
+            ""
+        }
     );
+    if let Some(cold) = record.verify_cold_ms {
+        println!("verify: cold={cold:.1}ms (first iteration)");
+    }
+    drop(tamper_dir);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_verify_record_populates_stats_and_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let proof = dir.path().join("proof");
+        let vk = dir.path().join("vk");
+        std::fs::write(&proof, b"proof-bytes").unwrap();
+        std::fs::write(&vk, b"vk").unwrap();
+
+        let last = VerifyReport {
+            meta: CommonMeta {
+                name: "verify".into(),
+                timestamp: "2026-01-01T00:00:00Z".into(),
+                noir_version: "1.0.0".into(),
+                artifact_path: PathBuf::from("target/merkle.json"),
+                cli_args: vec!["noir-bench".into(), "verify".into()],
+                artifact_sha256: Some("abc".into()),
+                inputs_sha256: None,
+            },
+            verify_time_ms: 12,
+            ok: true,
+            backend: BackendInfo {
+                name: "barretenberg".into(),
+                version: Some("0.82.0".into()),
+            },
+            system: None,
+            iterations: None,
+        };
+
+        let record = build_verify_record(
+            &last,
+            &[10.0, 12.0, 14.0],
+            Some(30.0),
+            1,
+            true,
+            &proof,
+            Some(&vk),
+            EnvironmentInfo::default(),
+        );
+
+        assert_eq!(record.circuit_name, "merkle");
+        assert_eq!(record.backend.version.as_deref(), Some("0.82.0"));
+        assert_eq!(record.config.warmup_iterations, 1);
+        assert_eq!(record.config.measured_iterations, 3);
+        let stats = record.verify_stats.as_ref().unwrap();
+        assert_eq!(stats.iterations, 3);
+        assert_eq!(stats.mean_ms, 12.0);
+        assert_eq!(stats.median_ms, Some(12.0));
+        assert_eq!(record.verify_cold_ms, Some(30.0));
+        assert_eq!(record.proof_size_bytes, Some(11));
+        assert_eq!(record.verification_key_size_bytes, Some(2));
+        let fp = record.fingerprints.as_ref().unwrap();
+        assert_eq!(fp.acir_hash.as_deref(), Some("abc"));
+        assert_eq!(fp.proof_hash, Some(crate::sha256_hex(b"proof-bytes")));
+        assert_eq!(fp.vk_hash, Some(crate::sha256_hex(b"vk")));
+        assert_eq!(
+            record.metadata.get("verify_ok").map(String::as_str),
+            Some("true")
+        );
+    }
+
+    #[test]
+    fn test_steady_samples_drop_first_iteration_without_warmup() {
+        // The first iteration is the cold one and stays out of the stats
+        assert_eq!(
+            steady_samples(&[30.0, 10.0, 12.0, 14.0], 0),
+            [10.0, 12.0, 14.0]
+        );
+        // A single sample is kept rather than leaving no stats
+        assert_eq!(steady_samples(&[30.0], 0), [30.0]);
+        // After warmup every measured iteration is steady state
+        assert_eq!(steady_samples(&[10.0, 12.0], 1), [10.0, 12.0]);
+    }
+
+    #[test]
+    fn test_generic_template_substitutes_vk() {
+        let provider = GenericVerifyProvider {
+            command_template: "verifier {artifact} {proof} {vk} {public_inputs}".into(),
+            vk_path: Some(PathBuf::from("/tmp/vk")),
+            public_inputs: Some(PathBuf::from("/tmp/public_inputs")),
+            extra_args: vec![],
+        };
+        let cmd = provider
+            .build_command(Path::new("/tmp/a.json"), Path::new("/tmp/p"))
+            .unwrap();
+        let args: Vec<_> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            args,
+            vec!["/tmp/a.json", "/tmp/p", "/tmp/vk", "/tmp/public_inputs"]
+        );
+    }
+
+    #[test]
+    fn test_tamper_proof_flips_low_bit_of_a_field_element() {
+        let dir = tempfile::tempdir().unwrap();
+        let proof = dir.path().join("proof");
+        let tampered = dir.path().join("tampered");
+
+        // A 4-byte length prefix and three field elements: the middle one's
+        // last byte changes
+        let mut bytes = vec![0u8, 0, 0, 3];
+        bytes.extend((0..3 * FIELD_BYTES).map(|i| i as u8));
+        std::fs::write(&proof, &bytes).unwrap();
+        let offset = tamper_proof(&proof, &tampered).unwrap();
+        assert_eq!(offset, 4 + 2 * FIELD_BYTES - 1);
+        let mut expected = bytes.clone();
+        expected[offset] ^= 1;
+        assert_eq!(std::fs::read(&tampered).unwrap(), expected);
+        assert_eq!(std::fs::read(&proof).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_tamper_proof_shorter_than_a_field_element() {
+        let dir = tempfile::tempdir().unwrap();
+        let proof = dir.path().join("proof");
+        let tampered = dir.path().join("tampered");
+
+        // The last byte is the only element's least significant one
+        std::fs::write(&proof, [1u8, 2, 3, 4, 5]).unwrap();
+        assert_eq!(tamper_proof(&proof, &tampered).unwrap(), 4);
+        assert_eq!(std::fs::read(&tampered).unwrap(), vec![1, 2, 3, 4, 5 ^ 1]);
+
+        std::fs::write(&proof, b"").unwrap();
+        assert!(tamper_proof(&proof, &tampered).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verifier_crash_is_not_a_rejection() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let exit = |code: i32| ExitStatus::from_raw(code << 8);
+        assert!(!verifier_crashed(exit(0), Some(BB_REJECT_EXIT_CODE)));
+        assert!(!verifier_crashed(exit(1), Some(BB_REJECT_EXIT_CODE)));
+        assert!(verifier_crashed(exit(2), Some(BB_REJECT_EXIT_CODE)));
+        // Without a known reject code any exit code is a verdict
+        assert!(!verifier_crashed(exit(2), None));
+        // Killed by SIGSEGV or aborted
+        assert!(verifier_crashed(ExitStatus::from_raw(11), None));
+        assert!(verifier_crashed(ExitStatus::from_raw(6), Some(1)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejection_logging_an_error_is_a_rejection() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("verify.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\necho 'verification error: proof rejected' >&2\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let output = Command::new(&script).output().unwrap();
+        assert!(String::from_utf8_lossy(&output.stderr).contains("error"));
+        assert!(!verifier_crashed(output.status, Some(BB_REJECT_EXIT_CODE)));
+        assert!(!verifier_crashed(output.status, None));
+    }
+}
@@ -0,0 +1,89 @@
+use nargo::parse_all;
+use noirc_driver::{CompileOptions, compile_main, file_manager_with_stdlib, prepare_crate};
+use noirc_frontend::hir::Context;
+use tempfile::tempdir;
+
+fn compile_program() -> noirc_artifacts::program::CompiledProgram {
+    let root = std::path::Path::new("");
+    let file_name = std::path::Path::new("main.nr");
+    let mut fm = file_manager_with_stdlib(root);
+    fm.add_file_with_source(
+        file_name,
+        r#"
+unconstrained fn double(x: Field) -> Field { x * 2 }
+
+fn main(x: Field, ys: pub [u8; 4]) -> pub Field {
+    // Safety: checked below
+    let d = unsafe { double(x) };
+    assert(d == x + x);
+    d + ys[0] as Field
+}
+"#
+        .to_string(),
+    )
+    .unwrap();
+    let parsed = parse_all(&fm);
+    let mut cx = Context::new(fm, parsed);
+    let crate_id = prepare_crate(&mut cx, file_name);
+    let opts = CompileOptions {
+        ..Default::default()
+    };
+    let (compiled, _warnings) = compile_main(&mut cx, crate_id, &opts, None).expect("compile");
+    compiled
+}
+
+#[test]
+fn circuit_report_with_mock_backend() {
+    let artifact: noirc_artifacts::program::ProgramArtifact = compile_program().into();
+
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    std::fs::write(&program_path, serde_json::to_vec(&artifact).unwrap()).unwrap();
+
+    // Mock bb: ignore args and print gates JSON
+    let backend = dir.path().join("bb-mock.sh");
+    let script = r#"#!/bin/sh
+cat <<'JSON'
+{"functions":[{"acir_opcodes":3,"circuit_size":100,"gates_per_opcode":[10,20,70]}]}
+JSON
+"#;
+    std::fs::write(&backend, script).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(&backend).unwrap().permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(&backend, perms).unwrap();
+    }
+
+    let json = dir.path().join("card.json");
+    let md = dir.path().join("card.md");
+    let html = dir.path().join("card.html");
+    noir_bench::circuit_cmd::report(
+        program_path,
+        Some("double".into()),
+        backend,
+        false,
+        Some(json.clone()),
+        Some(md.clone()),
+        Some(html.clone()),
+    )
+    .unwrap();
+
+    let card: serde_json::Value = serde_json::from_slice(&std::fs::read(&json).unwrap()).unwrap();
+    assert_eq!(card["circuit_name"], "double");
+    assert_eq!(card["total_gates"], 100);
+    assert_eq!(card["inputs"][0]["name"], "x");
+    assert_eq!(card["inputs"][1]["type"], "[u8; 4]");
+    assert_eq!(card["inputs"][1]["visibility"], "public");
+    assert_eq!(card["input_fields"], 5);
+    assert_eq!(card["return_value"]["type"], "Field");
+    assert!(card["acir_opcodes"].as_u64().unwrap() > 0);
+    assert!(!card["brillig_functions"].as_array().unwrap().is_empty());
+
+    let md = std::fs::read_to_string(&md).unwrap();
+    assert!(md.starts_with("# Circuit: double"));
+    assert!(md.contains("| Backend gates | 100 |"));
+    let html = std::fs::read_to_string(&html).unwrap();
+    assert!(html.contains("<h1>Circuit: double</h1>"));
+}
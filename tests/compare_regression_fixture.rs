@@ -37,7 +37,15 @@ fn test_compare_detects_synthetic_regression_and_includes_provenance() {
         Some(target_path),
         None,
         None,
-        10.0,
+        Some(10.0),
+        None,
+        None,
+        None,
+        false,
+        Vec::new(),
+        Vec::new(),
+        None,
+        vec!["commit=abc123".to_string()],
         "json".to_string(),
         Some(report_path.clone()),
         None,
@@ -72,4 +80,5 @@ fn test_compare_detects_synthetic_regression_and_includes_provenance() {
         report.metadata.target_provenance.is_some(),
         "target provenance should be populated"
     );
+    assert_eq!(report.metadata.annotations["commit"], "abc123");
 }
@@ -7,7 +7,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use noir_bench::backend::{MockBackend, MockConfig, ProveOutput};
-use noir_bench::core::{BenchRecord, TimingStat};
+use noir_bench::core::{BenchRecord, SCHEMA_VERSION, TimingStat};
 use noir_bench::engine::toolchain::{CompileArtifacts, MockToolchain, WitnessArtifact};
 use noir_bench::engine::workflow::{ProveInputs, prove_only, prove_with_iterations};
 
@@ -23,6 +23,8 @@ fn create_test_toolchain() -> MockToolchain {
         witness_output: Some(WitnessArtifact {
             witness_path: PathBuf::from("/mock/witness.gz"),
             witness_gen_time_ms: 50,
+            oracle_resolver_ms: None,
+            dir: None,
         }),
         should_fail: false,
     }
@@ -41,6 +43,13 @@ fn create_test_backend() -> MockBackend {
             verification_key_size_bytes: Some(1024),
             proof_path: None,
             vk_path: None,
+            setup_time_ms: None,
+            phases: None,
+            vk_gen_time_ms: None,
+            vk_cached: false,
+            key_cache: None,
+            prove_cpu: None,
+            vk_gen_cpu: None,
         }),
     )
 }
@@ -60,8 +69,11 @@ fn test_prove_only_returns_valid_bench_record() {
 
     let record = result.unwrap();
 
-    // Verify required BenchRecord v1 fields
-    assert_eq!(record.schema_version, 1, "Schema version should be 1");
+    // Verify required BenchRecord fields
+    assert_eq!(
+        record.schema_version, SCHEMA_VERSION,
+        "Schema version should be current"
+    );
     assert!(
         !record.record_id.is_empty(),
         "Record ID should not be empty"
@@ -274,13 +286,13 @@ fn test_bench_record_schema_version_is_constant() {
     let toolchain = create_test_toolchain();
     let backend = create_test_backend();
 
-    // Run multiple times to verify schema version is always 1
+    // Run multiple times to verify schema version is always current
     for i in 0..3 {
         let inputs = ProveInputs::new("/mock/circuit.json", format!("schema-test-{}", i));
         let record = prove_only(&toolchain, &backend, &inputs).unwrap();
         assert_eq!(
-            record.schema_version, 1,
-            "Schema version should always be 1"
+            record.schema_version, SCHEMA_VERSION,
+            "Schema version should always be current"
         );
     }
 }
@@ -40,14 +40,319 @@ fn exec_smoke() {
     // Run exec
     let out_dir = dir.path().join("out");
     std::fs::create_dir_all(&out_dir).unwrap();
+    let out_json = out_dir.join("exec.json");
     noir_bench::exec_cmd::run(
         program_path,
         prover_toml,
         Some(out_dir.clone()),
+        Some(out_json.clone()),
+        false,
+        Some(1),
+        Some(0),
+        None,
+        None,
+        None,
         None,
         false,
+    )
+    .unwrap();
+
+    // The unconstrained program runs Brillig opcodes, reported in the split
+    let v: serde_json::Value = serde_json::from_slice(&std::fs::read(&out_json).unwrap()).unwrap();
+    let split = &v["exec_split"];
+    assert_eq!(split["brillig_opcodes"], v["samples_count"]);
+    assert!(split["brillig_opcodes"].as_u64().unwrap() > 0);
+    assert!(split["acir_opcodes"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn exec_with_oracle_mocks() {
+    let root = std::path::Path::new("");
+    let file_name = std::path::Path::new("main.nr");
+    let mut fm = file_manager_with_stdlib(root);
+    fm.add_file_with_source(
+        file_name,
+        r#"
+#[oracle(get_price)]
+unconstrained fn get_price(x: Field) -> Field {}
+
+unconstrained fn main(x: Field) {
+    assert(get_price(x) == 42);
+}
+"#
+        .to_string(),
+    )
+    .unwrap();
+    let parsed = parse_all(&fm);
+    let mut cx = Context::new(fm, parsed);
+    let crate_id = prepare_crate(&mut cx, file_name);
+    let opts = CompileOptions {
+        force_brillig: true,
+        ..Default::default()
+    };
+    let (compiled, _warnings) = compile_main(&mut cx, crate_id, &opts, None).expect("compile");
+    let artifact: noirc_artifacts::program::ProgramArtifact = compiled.into();
+
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    let prover_toml = dir.path().join("Prover.toml");
+    let mocks = dir.path().join("mocks.toml");
+    std::fs::write(&program_path, serde_json::to_vec(&artifact).unwrap()).unwrap();
+    std::fs::write(&prover_toml, b"x = 1\n").unwrap();
+    std::fs::write(
+        &mocks,
+        "[[mock]]\nfunction = \"get_price\"\ninputs = [\"1\"]\noutputs = [\"42\"]\n",
+    )
+    .unwrap();
+
+    let out_json = dir.path().join("exec.json");
+    noir_bench::exec_cmd::run(
+        program_path.clone(),
+        prover_toml.clone(),
+        None,
+        Some(out_json.clone()),
+        false,
         Some(1),
         Some(0),
+        Some(mocks),
+        Some(20.0),
+        None,
+        None,
+        false,
+    )
+    .unwrap();
+
+    let v: serde_json::Value = serde_json::from_slice(&std::fs::read(&out_json).unwrap()).unwrap();
+    assert_eq!(v["exec_split"]["foreign_calls"], 1);
+    assert!(v["exec_split"]["foreign_call_ms"].as_f64().unwrap() >= 20.0);
+
+    // Without mocks the oracle has no resolver
+    assert!(
+        noir_bench::exec_cmd::run(
+            program_path.clone(),
+            prover_toml.clone(),
+            None,
+            None,
+            false,
+            Some(1),
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .is_err()
+    );
+
+    // An unreachable resolver fails the oracle call instead of hanging
+    assert!(
+        noir_bench::exec_cmd::run(
+            program_path,
+            prover_toml,
+            None,
+            None,
+            false,
+            Some(1),
+            Some(0),
+            None,
+            None,
+            Some("http://127.0.0.1:1".to_string()),
+            None,
+            false,
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn exec_checks_return_value() {
+    use noir_bench::exec_cmd::ExpectedReturn;
+
+    let root = std::path::Path::new("");
+    let file_name = std::path::Path::new("main.nr");
+    let mut fm = file_manager_with_stdlib(root);
+    fm.add_file_with_source(
+        file_name,
+        r#"unconstrained fn main(x: Field) -> pub [Field; 2] { [x + 1, x * 3] }"#.to_string(),
+    )
+    .unwrap();
+    let parsed = parse_all(&fm);
+    let mut cx = Context::new(fm, parsed);
+    let crate_id = prepare_crate(&mut cx, file_name);
+    let opts = CompileOptions {
+        force_brillig: true,
+        ..Default::default()
+    };
+    let (compiled, _warnings) = compile_main(&mut cx, crate_id, &opts, None).expect("compile");
+    let artifact: noirc_artifacts::program::ProgramArtifact = compiled.into();
+
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    let prover_toml = dir.path().join("Prover.toml");
+    std::fs::write(&program_path, serde_json::to_vec(&artifact).unwrap()).unwrap();
+    std::fs::write(&prover_toml, b"x = 2\nreturn = [3, 6]\n").unwrap();
+
+    let exec = |expected: ExpectedReturn, json: &std::path::Path| {
+        noir_bench::exec_cmd::run(
+            program_path.clone(),
+            prover_toml.clone(),
+            None,
+            Some(json.to_path_buf()),
+            false,
+            Some(1),
+            Some(0),
+            None,
+            None,
+            None,
+            Some(expected),
+            false,
+        )
+    };
+
+    let ok_json = dir.path().join("ok.json");
+    exec(ExpectedReturn::Value("[3, 6]".to_string()), &ok_json).unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&std::fs::read(&ok_json).unwrap()).unwrap();
+    assert_eq!(v["return_ok"], true);
+    assert_eq!(v["return_value"], "[3, 6]");
+
+    // The `return` entry of a Prover.toml works as an expectation file
+    exec(ExpectedReturn::File(prover_toml.clone()), &ok_json).unwrap();
+
+    // A mismatch still writes the report, flagged, and fails the run
+    let bad_json = dir.path().join("bad.json");
+    let err = exec(ExpectedReturn::Value("[3, 7]".to_string()), &bad_json).unwrap_err();
+    assert!(err.to_string().contains("return value mismatch"));
+    let v: serde_json::Value = serde_json::from_slice(&std::fs::read(&bad_json).unwrap()).unwrap();
+    assert_eq!(v["return_ok"], false);
+}
+
+#[test]
+fn exec_expect_failure_measures_time_to_failure() {
+    let root = std::path::Path::new("");
+    let file_name = std::path::Path::new("main.nr");
+    let mut fm = file_manager_with_stdlib(root);
+    fm.add_file_with_source(
+        file_name,
+        r#"unconstrained fn main(x: Field) { assert(x == 2); }"#.to_string(),
     )
     .unwrap();
+    let parsed = parse_all(&fm);
+    let mut cx = Context::new(fm, parsed);
+    let crate_id = prepare_crate(&mut cx, file_name);
+    let opts = CompileOptions {
+        force_brillig: true,
+        ..Default::default()
+    };
+    let (compiled, _warnings) = compile_main(&mut cx, crate_id, &opts, None).expect("compile");
+    let artifact: noirc_artifacts::program::ProgramArtifact = compiled.into();
+
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    let bad_toml = dir.path().join("Bad.toml");
+    let good_toml = dir.path().join("Good.toml");
+    std::fs::write(&program_path, serde_json::to_vec(&artifact).unwrap()).unwrap();
+    std::fs::write(&bad_toml, b"x = 1\n").unwrap();
+    std::fs::write(&good_toml, b"x = 2\n").unwrap();
+
+    let out_json = dir.path().join("exec.json");
+    noir_bench::exec_cmd::run(
+        program_path.clone(),
+        bad_toml,
+        None,
+        Some(out_json.clone()),
+        false,
+        Some(2),
+        Some(1),
+        None,
+        None,
+        None,
+        None,
+        true,
+    )
+    .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&std::fs::read(&out_json).unwrap()).unwrap();
+    assert_eq!(v["status"], "expected_failure");
+    assert!(v["failure_message"].as_str().is_some_and(|m| !m.is_empty()));
+    assert!(v["execution_time_ms"].as_f64().is_some());
+
+    // Inputs that pass fail the run
+    let err = noir_bench::exec_cmd::run(
+        program_path,
+        good_toml,
+        None,
+        None,
+        false,
+        Some(1),
+        Some(0),
+        None,
+        None,
+        None,
+        None,
+        true,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("expected"));
+}
+
+#[test]
+fn program_cache_shares_parse_until_artifact_changes() {
+    use noir_bench::engine::{artifact_sha256, load_compiled, load_program};
+    use std::sync::Arc;
+
+    let compiled = compile_unconstrained_program();
+    let artifact: noirc_artifacts::program::ProgramArtifact = compiled.into();
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    let bytes = serde_json::to_vec(&artifact).unwrap();
+    std::fs::write(&program_path, &bytes).unwrap();
+
+    let first = load_program(&program_path).unwrap();
+    let second = load_program(&program_path).unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(Arc::ptr_eq(
+        &load_compiled(&program_path).unwrap(),
+        &load_compiled(&program_path).unwrap()
+    ));
+    assert_eq!(
+        artifact_sha256(&program_path),
+        Some(noir_bench::sha256_hex(&bytes))
+    );
+
+    // A rewritten artifact is parsed again
+    std::fs::write(&program_path, serde_json::to_vec_pretty(&artifact).unwrap()).unwrap();
+    let third = load_program(&program_path).unwrap();
+    assert!(!Arc::ptr_eq(&first, &third));
+    assert_eq!(third.noir_version, first.noir_version);
+}
+
+#[test]
+fn witness_paths_are_unique_per_call() {
+    use noir_bench::engine::{NargoToolchain, Toolchain};
+
+    let compiled = compile_unconstrained_program();
+    let artifact: noirc_artifacts::program::ProgramArtifact = compiled.into();
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    let prover_toml = dir.path().join("Prover.toml");
+    std::fs::write(&program_path, serde_json::to_vec(&artifact).unwrap()).unwrap();
+    std::fs::write(&prover_toml, b"x = 1\n").unwrap();
+    let work_dir = dir.path().join("work");
+    std::fs::create_dir_all(&work_dir).unwrap();
+
+    let toolchain = NargoToolchain::new().with_work_dir(&work_dir);
+    let first = toolchain.gen_witness(&program_path, &prover_toml).unwrap();
+    let second = toolchain.gen_witness(&program_path, &prover_toml).unwrap();
+    assert_ne!(first.witness_path, second.witness_path);
+    assert!(first.witness_path.starts_with(&work_dir));
+    assert!(first.witness_path.exists() && second.witness_path.exists());
+
+    // The witness goes with the last clone of its artifact
+    let path = first.witness_path.clone();
+    let clone = first.clone();
+    drop(first);
+    assert!(path.exists());
+    drop(clone);
+    assert!(!path.exists());
+    assert!(second.witness_path.exists());
 }
@@ -45,13 +45,82 @@ JSON
     fs::set_permissions(&backend_path, perms).unwrap();
 
     // Run gates_cmd
+    let out_json = dir.path().join("gates.json");
     noir_bench::gates_cmd::run(
         program_path.clone(),
         Some("fake".to_string()),
         Some(backend_path.clone()),
         vec!["--include_gates_per_opcode".into()],
         None,
+        Some(out_json.clone()),
+        1,
         None,
+        Some(5),
     )
     .unwrap();
+
+    // The compiled artifact has debug info, so gates are attributed to call sites
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(&out_json).unwrap()).unwrap();
+    assert_eq!(v["per_function"][0]["function"], "main");
+    assert!(!v["call_sites"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn gates_iterations_and_cache() {
+    let dir = tempdir().unwrap();
+    let program_path = dir.path().join("program.json");
+    fs::write(&program_path, b"{}").unwrap();
+
+    // Fake backend that counts its invocations
+    let calls = dir.path().join("calls");
+    let backend_path = dir.path().join("fake_backend.sh");
+    let script = format!(
+        r#"#!/usr/bin/env bash
+set -euo pipefail
+echo x >> '{}'
+echo '{{"functions":[{{"acir_opcodes": 3, "circuit_size": 10, "gates_per_opcode": [4,3,3]}}]}}'
+"#,
+        calls.display()
+    );
+    fs::write(&backend_path, script).unwrap();
+    let mut perms = fs::metadata(&backend_path).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&backend_path, perms).unwrap();
+    let call_count = || {
+        fs::read_to_string(&calls)
+            .unwrap_or_default()
+            .lines()
+            .count()
+    };
+
+    let cache = dir.path().join("gates");
+    let run = |json: &std::path::Path| {
+        noir_bench::gates_cmd::run(
+            program_path.clone(),
+            Some("fake".to_string()),
+            Some(backend_path.clone()),
+            vec![],
+            None,
+            Some(json.to_path_buf()),
+            3,
+            Some(cache.clone()),
+            None,
+        )
+    };
+
+    let first = dir.path().join("first.json");
+    run(&first).unwrap();
+    assert_eq!(call_count(), 3);
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(&first).unwrap()).unwrap();
+    assert_eq!(v["total_gates"], 10);
+    assert!(v["gates_time_ms"].is_u64());
+    assert_eq!(v["iterations"]["iterations"], 3);
+
+    // Second run hits the cache: no backend calls and no timing
+    let second = dir.path().join("second.json");
+    run(&second).unwrap();
+    assert_eq!(call_count(), 3);
+    let v: serde_json::Value = serde_json::from_slice(&fs::read(&second).unwrap()).unwrap();
+    assert_eq!(v["total_gates"], 10);
+    assert!(v.get("gates_time_ms").is_none());
 }
@@ -59,6 +59,9 @@ JSON
         vec!["--include_gates_per_opcode".into()],
         None,
         Some(out_json.clone()),
+        1,
+        None,
+        None,
     )
     .unwrap();
 
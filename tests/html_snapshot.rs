@@ -5,191 +5,12 @@
 //! - Contains expected sections and structure
 //! - Properly escapes user-controlled content
 
-use noir_bench::engine::provenance::{Provenance, SystemInfo, ToolInfo, VersionMismatch};
-use noir_bench::report::{
-    CircuitRegression, MetricDelta, RegressionReport, RegressionStatus, render_html,
-};
-
-/// Create a fixed RegressionReport for snapshot testing.
-fn make_fixed_report() -> RegressionReport {
-    // Create report with fixed timestamp for determinism
-    let mut report = RegressionReport {
-        version: 1,
-        metadata: noir_bench::report::ReportMetadata {
-            baseline_id: "baseline-abc123".to_string(),
-            target_id: "target-def456".to_string(),
-            generated_at: "2026-01-15T12:00:00Z".to_string(),
-            threshold_percent: 10.0,
-            metric_thresholds: std::collections::BTreeMap::new(),
-            baseline_provenance: Some(Provenance {
-                noir_bench: ToolInfo {
-                    name: "noir-bench".to_string(),
-                    version: Some("0.1.0".to_string()),
-                    git_sha: Some("abc123".to_string()),
-                    git_dirty: Some(false),
-                    path: None,
-                },
-                nargo: Some(ToolInfo {
-                    name: "nargo".to_string(),
-                    version: Some("0.38.0".to_string()),
-                    git_sha: None,
-                    git_dirty: None,
-                    path: None,
-                }),
-                backend: Some(ToolInfo {
-                    name: "barretenberg".to_string(),
-                    version: Some("0.62.0".to_string()),
-                    git_sha: None,
-                    git_dirty: None,
-                    path: None,
-                }),
-                system: SystemInfo {
-                    os: "linux".to_string(),
-                    arch: "x86_64".to_string(),
-                    cpu_brand: Some("Test CPU".to_string()),
-                    cpu_cores: Some(8),
-                    ram_bytes: Some(16_000_000_000),
-                    hostname: Some("test-host".to_string()),
-                },
-                cli_args: vec!["noir-bench".to_string(), "ci".to_string()],
-                collected_at: "2026-01-15T12:00:00Z".to_string(),
-            }),
-            target_provenance: Some(Provenance {
-                noir_bench: ToolInfo {
-                    name: "noir-bench".to_string(),
-                    version: Some("0.1.0".to_string()),
-                    git_sha: Some("def456".to_string()),
-                    git_dirty: Some(false),
-                    path: None,
-                },
-                nargo: Some(ToolInfo {
-                    name: "nargo".to_string(),
-                    version: Some("0.39.0".to_string()),
-                    git_sha: None,
-                    git_dirty: None,
-                    path: None,
-                }),
-                backend: Some(ToolInfo {
-                    name: "barretenberg".to_string(),
-                    version: Some("0.63.0".to_string()),
-                    git_sha: None,
-                    git_dirty: None,
-                    path: None,
-                }),
-                system: SystemInfo {
-                    os: "linux".to_string(),
-                    arch: "x86_64".to_string(),
-                    cpu_brand: Some("Test CPU".to_string()),
-                    cpu_cores: Some(8),
-                    ram_bytes: Some(16_000_000_000),
-                    hostname: Some("test-host".to_string()),
-                },
-                cli_args: vec!["noir-bench".to_string(), "ci".to_string()],
-                collected_at: "2026-01-15T12:00:00Z".to_string(),
-            }),
-        },
-        circuits: Vec::new(),
-        summary: noir_bench::report::ReportSummary {
-            total_circuits: 0,
-            circuits_with_regressions: 0,
-            circuits_with_improvements: 0,
-            total_metrics: 0,
-            regressions: 0,
-            improvements: 0,
-            unchanged: 0,
-            missing_baselines: 0,
-            errors: 0,
-            ci_exit_code: 0,
-        },
-        version_mismatches: vec![
-            VersionMismatch {
-                tool: "nargo".to_string(),
-                baseline_version: Some("0.38.0".to_string()),
-                target_version: Some("0.39.0".to_string()),
-            },
-            VersionMismatch {
-                tool: "barretenberg".to_string(),
-                baseline_version: Some("0.62.0".to_string()),
-                target_version: Some("0.63.0".to_string()),
-            },
-        ],
-    };
-
-    // Add circuits with various statuses
-    report.add_circuit(CircuitRegression {
-        circuit_name: "circuit-alpha".to_string(),
-        params: Some(100),
-        metrics: vec![
-            MetricDelta {
-                metric: "prove_ms".to_string(),
-                baseline: 100.0,
-                target: 125.0,
-                delta_abs: 25.0,
-                delta_pct: 25.0,
-                threshold: 10.0,
-                status: RegressionStatus::ExceededThreshold,
-            },
-            MetricDelta {
-                metric: "gates".to_string(),
-                baseline: 10000.0,
-                target: 10000.0,
-                delta_abs: 0.0,
-                delta_pct: 0.0,
-                threshold: 10.0,
-                status: RegressionStatus::Ok,
-            },
-        ],
-        status: RegressionStatus::ExceededThreshold,
-    });
-
-    report.add_circuit(CircuitRegression {
-        circuit_name: "circuit-beta".to_string(),
-        params: None,
-        metrics: vec![
-            MetricDelta {
-                metric: "prove_ms".to_string(),
-                baseline: 200.0,
-                target: 150.0,
-                delta_abs: -50.0,
-                delta_pct: -25.0,
-                threshold: 10.0,
-                status: RegressionStatus::Improved,
-            },
-            MetricDelta {
-                metric: "proof_size".to_string(),
-                baseline: 2048.0,
-                target: 2048.0,
-                delta_abs: 0.0,
-                delta_pct: 0.0,
-                threshold: 10.0,
-                status: RegressionStatus::Ok,
-            },
-        ],
-        status: RegressionStatus::Improved,
-    });
-
-    report.add_circuit(CircuitRegression {
-        circuit_name: "circuit-gamma".to_string(),
-        params: Some(50),
-        metrics: vec![MetricDelta {
-            metric: "prove_ms".to_string(),
-            baseline: 50.0,
-            target: 52.0,
-            delta_abs: 2.0,
-            delta_pct: 4.0,
-            threshold: 10.0,
-            status: RegressionStatus::Ok,
-        }],
-        status: RegressionStatus::Ok,
-    });
-
-    report.finalize();
-    report
-}
+use noir_bench::fixtures::fixed_report;
+use noir_bench::report::{CircuitRegression, RegressionReport, RegressionStatus, render_html};
 
 #[test]
 fn test_html_output_determinism() {
-    let report = make_fixed_report();
+    let report = fixed_report();
 
     // Render twice
     let html1 = render_html(&report);
@@ -201,7 +22,7 @@ fn test_html_output_determinism() {
 
 #[test]
 fn test_html_contains_doctype_and_structure() {
-    let report = make_fixed_report();
+    let report = fixed_report();
     let html = render_html(&report);
 
     // Basic HTML structure
@@ -217,7 +38,7 @@ fn test_html_contains_doctype_and_structure() {
 
 #[test]
 fn test_html_contains_inline_css_and_js() {
-    let report = make_fixed_report();
+    let report = fixed_report();
     let html = render_html(&report);
 
     // CSS is inline
@@ -236,7 +57,7 @@ fn test_html_contains_inline_css_and_js() {
 
 #[test]
 fn test_html_contains_report_data() {
-    let report = make_fixed_report();
+    let report = fixed_report();
     let html = render_html(&report);
 
     // Report identifiers
@@ -264,7 +85,7 @@ fn test_html_contains_report_data() {
 
 #[test]
 fn test_html_contains_version_mismatches() {
-    let report = make_fixed_report();
+    let report = fixed_report();
     let html = render_html(&report);
 
     // Version mismatches should be present
@@ -281,7 +102,7 @@ fn test_html_contains_version_mismatches() {
 
 #[test]
 fn test_html_contains_provenance() {
-    let report = make_fixed_report();
+    let report = fixed_report();
     let html = render_html(&report);
 
     // Provenance info
@@ -306,6 +127,10 @@ fn test_html_sorted_circuits() {
             generated_at: "2026-01-15T12:00:00Z".to_string(),
             threshold_percent: 10.0,
             metric_thresholds: std::collections::BTreeMap::new(),
+            circuit_thresholds: std::collections::BTreeMap::new(),
+            annotations: std::collections::BTreeMap::new(),
+            informational_metrics: std::collections::BTreeSet::new(),
+            budgets: std::collections::BTreeMap::new(),
             baseline_provenance: None,
             target_provenance: None,
         },
@@ -323,6 +148,10 @@ fn test_html_sorted_circuits() {
             ci_exit_code: 0,
         },
         version_mismatches: Vec::new(),
+        environment_mismatches: Vec::new(),
+        thermal_warnings: Vec::new(),
+        subgroup_crossings: Vec::new(),
+        methodology: None,
     };
 
     // Add in reverse alphabetical order
@@ -361,6 +190,10 @@ fn test_html_escapes_dangerous_content() {
             generated_at: "2026-01-15T12:00:00Z".to_string(),
             threshold_percent: 10.0,
             metric_thresholds: std::collections::BTreeMap::new(),
+            circuit_thresholds: std::collections::BTreeMap::new(),
+            annotations: std::collections::BTreeMap::new(),
+            informational_metrics: std::collections::BTreeSet::new(),
+            budgets: std::collections::BTreeMap::new(),
             baseline_provenance: None,
             target_provenance: None,
         },
@@ -378,6 +211,10 @@ fn test_html_escapes_dangerous_content() {
             ci_exit_code: 0,
         },
         version_mismatches: Vec::new(),
+        environment_mismatches: Vec::new(),
+        thermal_warnings: Vec::new(),
+        subgroup_crossings: Vec::new(),
+        methodology: None,
     };
 
     report.add_circuit(CircuitRegression {
@@ -404,7 +241,7 @@ fn test_html_escapes_dangerous_content() {
 
 #[test]
 fn test_html_snapshot_hash_stability() {
-    let report = make_fixed_report();
+    let report = fixed_report();
     let html = render_html(&report);
 
     // Compute a simple hash for stability checking
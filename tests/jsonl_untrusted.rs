@@ -0,0 +1,81 @@
+//! Property tests for the hardened JSONL parser (`storage::untrusted`).
+//!
+//! Whatever the input, reading must not panic, every non-blank line must end
+//! up as either a record or a warning, and accepted records must hold only
+//! finite, non-negative measurements.
+
+use noir_bench::fixtures::fixed_record;
+use noir_bench::storage::untrusted::{ParseLimits, parse_untrusted, read_lines_untrusted};
+use proptest::prelude::*;
+
+fn limits() -> ParseLimits {
+    ParseLimits {
+        max_line_bytes: 4096,
+        max_depth: 16,
+        max_samples: 64,
+    }
+}
+
+/// The fixed record as a JSON value, to mutate into hostile variants.
+fn fixed_value() -> serde_json::Value {
+    serde_json::to_value(fixed_record()).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes_never_panic(lines in prop::collection::vec(prop::collection::vec(any::<u8>(), 0..256), 0..16)) {
+        let mut input = Vec::new();
+        for line in &lines {
+            input.extend(line.iter().filter(|b| **b != b'\n'));
+            input.push(b'\n');
+        }
+        let out = read_lines_untrusted(input.as_slice(), &limits()).unwrap();
+        prop_assert!(out.records.len() + out.warnings.len() <= lines.len());
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected(depth in 17usize..5000) {
+        let line = "[".repeat(depth) + &"]".repeat(depth);
+        let err = parse_untrusted(line.as_bytes(), &limits()).unwrap_err();
+        prop_assert!(err.contains("nested deeper"));
+    }
+
+    #[test]
+    fn timings_are_accepted_only_when_plausible(mean in any::<f64>(), sample in any::<f64>()) {
+        let mut value = fixed_value();
+        // serde_json can't represent non-finite numbers; they arrive as null
+        value["prove_stats"]["mean_ms"] = serde_json::json!(mean);
+        value["prove_stats"]["samples_ms"] = serde_json::json!([sample]);
+        let line = value.to_string();
+        let plausible = mean.is_finite() && mean >= 0.0 && sample.is_finite() && sample >= 0.0;
+        match parse_untrusted(line.as_bytes(), &limits()) {
+            Ok(Some(record)) => {
+                let stats = record.prove_stats.unwrap();
+                prop_assert!(stats.mean_ms.is_finite() && stats.mean_ms >= 0.0);
+                prop_assert!(stats.samples_ms.iter().all(|s| s.is_finite() && *s >= 0.0));
+            }
+            Ok(None) => prop_assert!(false, "non-blank line read as blank"),
+            Err(_) => prop_assert!(!plausible),
+        }
+    }
+
+    #[test]
+    fn too_many_samples_are_rejected(n in 65usize..200) {
+        let mut value = fixed_value();
+        value["prove_stats"]["samples_ms"] = serde_json::json!(vec![1.0; n]);
+        let err = parse_untrusted(value.to_string().as_bytes(), &limits()).unwrap_err();
+        prop_assert!(err.contains("samples"));
+    }
+}
+
+#[test]
+fn oversized_lines_are_skipped_and_reading_continues() {
+    let good = serde_json::to_string(&fixed_record()).unwrap();
+    let input = format!("{}\n{good}\n", "9".repeat(100_000));
+    let out = read_lines_untrusted(input.as_bytes(), &limits()).unwrap();
+    assert_eq!(out.records.len(), 1);
+    assert_eq!(
+        out.warnings,
+        vec!["line 1: skipped, longer than 4096 bytes"]
+    );
+}
@@ -1,11 +1,12 @@
+use std::path::{Path, PathBuf};
+
 use nargo::parse_all;
 use noirc_driver::{CompileOptions, compile_main, file_manager_with_stdlib, prepare_crate};
 use noirc_frontend::hir::Context;
 use tempfile::tempdir;
 
-#[test]
-fn prove_with_generic_backend() {
-    // Compile a tiny program to get a valid artifact
+/// Compile a tiny program into `dir`, returning the artifact and Prover.toml.
+fn compile_tiny_program(dir: &Path) -> (PathBuf, PathBuf) {
     let root = std::path::Path::new("");
     let file_name = std::path::Path::new("main.nr");
     let mut fm = file_manager_with_stdlib(root);
@@ -23,28 +24,17 @@ fn prove_with_generic_backend() {
     let (compiled, _warnings) = compile_main(&mut cx, crate_id, &opts, None).expect("compile");
     let artifact: noirc_artifacts::program::ProgramArtifact = compiled.into();
 
-    let dir = tempdir().unwrap();
-    let program_path = dir.path().join("program.json");
+    let program_path = dir.join("program.json");
     std::fs::write(&program_path, serde_json::to_vec(&artifact).unwrap()).unwrap();
     // Minimal inputs
-    let prover_toml = dir.path().join("Prover.toml");
+    let prover_toml = dir.join("Prover.toml");
     std::fs::write(&prover_toml, b"x = 1\n").unwrap();
+    (program_path, prover_toml)
+}
 
-    // Create a generic prover script that writes proof to output path
-    let backend_path = dir.path().join("fake_prove.sh");
-    let script = r#"#!/usr/bin/env bash
-set -euo pipefail
-# parse args to find -o/--output or last arg path
-out=""
-for i in "$@"; do
-  if [[ "$i" == "-o" || "$i" == "--output" ]]; then
-    shift
-    out="$1"
-  fi
-done
-if [[ -z "${out}" ]]; then out="proof.bin"; fi
-echo -n 0001 > "${out}"
-"#;
+/// Write an executable script and return a template running it.
+fn prover_template(dir: &Path, script: &str) -> String {
+    let backend_path = dir.join("fake_prove.sh");
     std::fs::write(&backend_path, script).unwrap();
     #[cfg(unix)]
     {
@@ -53,24 +43,100 @@ echo -n 0001 > "${out}"
         perms.set_mode(0o755);
         std::fs::set_permissions(&backend_path, perms).unwrap();
     }
-
-    // Template using placeholders
-    let template = format!(
+    format!(
         "{} prove -b {{artifact}} -w {{witness}} -o {{proof}}",
         backend_path.to_string_lossy()
-    );
+    )
+}
 
+fn prove_generic(
+    program_path: &Path,
+    prover_toml: &Path,
+    template: String,
+    custom_metrics: Vec<String>,
+    report_path: Option<PathBuf>,
+) -> noir_bench::BenchResult<()> {
     noir_bench::prove_cmd::run(
-        program_path.clone(),
-        Some(prover_toml.clone()),
+        program_path.to_path_buf(),
+        Some(prover_toml.to_path_buf()),
         Some("generic".to_string()),
         None,
         vec![],
         Some(template),
+        custom_metrics,
         5,
         Some(1),
         Some(0),
+        report_path,
+        None,
+        false,
+        false,
         None,
+        false,
+        false,
+    )
+}
+
+#[test]
+fn prove_with_generic_backend() {
+    let dir = tempdir().unwrap();
+    let (program_path, prover_toml) = compile_tiny_program(dir.path());
+
+    // Create a generic prover script that writes proof to output path
+    let script = r#"#!/usr/bin/env bash
+set -euo pipefail
+# parse args to find -o/--output or last arg path
+out=""
+for i in "$@"; do
+  if [[ "$i" == "-o" || "$i" == "--output" ]]; then
+    shift
+    out="$1"
+  fi
+done
+if [[ -z "${out}" ]]; then out="proof.bin"; fi
+echo -n 0001 > "${out}"
+echo "folded_constraints=42"
+"#;
+    let template = prover_template(dir.path(), script);
+
+    let report_path = dir.path().join("prove.json");
+    prove_generic(
+        &program_path,
+        &prover_toml,
+        template,
+        vec!["folded_constraints".to_string()],
+        Some(report_path.clone()),
     )
     .unwrap();
+
+    // Metrics declared with --custom-metric are scraped from stdout
+    let report: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&report_path).unwrap()).unwrap();
+    assert_eq!(report["custom_metrics"]["folded_constraints"], 42.0);
+}
+
+#[test]
+fn generic_backend_failure_includes_stderr() {
+    let dir = tempdir().unwrap();
+    let (program_path, prover_toml) = compile_tiny_program(dir.path());
+    let script =
+        "#!/usr/bin/env bash\necho 'loading crs' >&2\necho 'error: out of memory' >&2\nexit 3\n";
+    let template = prover_template(dir.path(), script);
+
+    let err = prove_generic(&program_path, &prover_toml, template, vec![], None).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("generic prove failed"), "{message}");
+    assert!(message.contains("error: out of memory"), "{message}");
+}
+
+#[test]
+fn generic_backend_times_out() {
+    let dir = tempdir().unwrap();
+    let (program_path, prover_toml) = compile_tiny_program(dir.path());
+    let template = prover_template(dir.path(), "#!/usr/bin/env bash\nsleep 30\n");
+
+    let start = std::time::Instant::now();
+    let err = prove_generic(&program_path, &prover_toml, template, vec![], None).unwrap_err();
+    assert!(err.to_string().contains("timed out"), "{err}");
+    assert!(start.elapsed() < std::time::Duration::from_secs(20));
 }
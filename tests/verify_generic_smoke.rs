@@ -42,9 +42,10 @@ exit 0
     perms.set_mode(0o755);
     fs::set_permissions(&backend_path, perms).unwrap();
 
-    // Create a dummy proof file
+    // Create a dummy proof file of two field elements
     let proof_path = dir.path().join("proof.bin");
-    fs::write(&proof_path, b"deadbeef").unwrap();
+    let proof: Vec<u8> = (0..64u8).collect();
+    fs::write(&proof_path, &proof).unwrap();
 
     // Use template to call script with placeholders
     let template = format!(
@@ -55,13 +56,90 @@ exit 0
     noir_bench::verify_cmd::run(
         program_path.clone(),
         proof_path.clone(),
+        None,
+        None,
         Some("generic".to_string()),
         None,
         vec![],
-        Some(template),
+        Some(template.clone()),
         Some(1),
         Some(0),
+        false,
+        None,
+        None,
+        None,
+        None,
         None,
     )
     .unwrap();
+
+    // The script accepts any proof, so a tampered one must fail the run
+    let err = noir_bench::verify_cmd::run(
+        program_path.clone(),
+        proof_path.clone(),
+        None,
+        None,
+        Some("generic".to_string()),
+        None,
+        vec![],
+        Some(template),
+        Some(1),
+        Some(0),
+        true,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("--expect-invalid"));
+
+    // A verifier that only accepts the original proof and public inputs
+    let inputs_path = dir.path().join("public_inputs");
+    fs::write(&inputs_path, b"01").unwrap();
+    let strict_path = dir.path().join("strict_verify.sh");
+    let strict = format!(
+        "#!/usr/bin/env bash\ncmp -s \"$1\" {} && [ \"$(cat \"$2\")\" = 01 ]\n",
+        proof_path.display()
+    );
+    fs::write(&strict_path, strict).unwrap();
+    fs::set_permissions(&strict_path, fs::Permissions::from_mode(0o755)).unwrap();
+    let template = format!("{} {{proof}} {{public_inputs}}", strict_path.display());
+    let jsonl = dir.path().join("verify.jsonl");
+    for expect_invalid in [false, true] {
+        noir_bench::verify_cmd::run(
+            program_path.clone(),
+            proof_path.clone(),
+            None,
+            Some(inputs_path.clone()),
+            Some("generic".to_string()),
+            None,
+            vec![],
+            Some(template.clone()),
+            Some(2),
+            Some(0),
+            expect_invalid,
+            None,
+            None,
+            Some(jsonl.clone()),
+            None,
+            None,
+        )
+        .unwrap();
+    }
+    let records: Vec<serde_json::Value> = fs::read_to_string(&jsonl)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(records[0]["metadata"]["verify_ok"], "true");
+    assert_eq!(
+        records[0]["fingerprints"]["inputs_hash"],
+        noir_bench::sha256_hex(b"01")
+    );
+    assert!(records[0].get("status").is_none());
+    assert_eq!(records[1]["metadata"]["verify_ok"], "false");
+    assert_eq!(records[1]["metadata"]["tampered_proof"], "byte 31 flipped");
+    assert_eq!(records[1]["status"], "expected_failure");
 }